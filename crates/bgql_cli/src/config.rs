@@ -0,0 +1,294 @@
+//! Project configuration from `bgql.toml`.
+//!
+//! Values are resolved as: command-line flag, then `bgql.toml`, then the
+//! built-in default.
+
+use crate::lint::LintRule;
+use crate::CodegenLanguage;
+use bgql_syntax::FormatOptions;
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// File name of the project configuration.
+pub const CONFIG_FILE: &str = "bgql.toml";
+
+/// Project configuration. Every section and key is optional.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// `[fmt]` section.
+    pub fmt: FmtConfig,
+    /// `[check]` section.
+    pub check: CheckConfig,
+    /// `[codegen]` section.
+    pub codegen: CodegenConfig,
+    /// `[lint]` section.
+    pub lint: LintConfig,
+}
+
+/// `[fmt]` section.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FmtConfig {
+    /// Indentation size.
+    pub indent: usize,
+    /// Use tabs instead of spaces.
+    pub tabs: bool,
+    /// Sort fields by name.
+    pub sort_fields: bool,
+}
+
+impl Default for FmtConfig {
+    fn default() -> Self {
+        Self {
+            indent: 2,
+            tabs: false,
+            sort_fields: false,
+        }
+    }
+}
+
+/// `[check]` section.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CheckConfig {
+    /// Treat warnings as errors.
+    pub strict: bool,
+    /// Maximum allowed query depth.
+    pub max_depth: usize,
+}
+
+impl Default for CheckConfig {
+    fn default() -> Self {
+        Self {
+            strict: false,
+            max_depth: 10,
+        }
+    }
+}
+
+/// `[codegen]` section.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CodegenConfig {
+    /// Target language.
+    pub lang: CodegenLanguage,
+    /// Output file. Relative paths are resolved against the config file's directory.
+    pub output: Option<PathBuf>,
+    /// Package/module name for the generated code.
+    pub package_name: Option<String>,
+}
+
+/// `[lint]` section.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintConfig {
+    /// Rules that are not run.
+    pub disable: Vec<LintRule>,
+}
+
+impl Config {
+    /// Parses a configuration from TOML source.
+    pub fn from_toml(source: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(source)
+    }
+
+    /// Returns formatter options. `indent` and `tabs` are the command-line flags.
+    #[must_use]
+    pub fn format_options(&self, indent: Option<usize>, tabs: bool) -> FormatOptions {
+        FormatOptions {
+            indent_size: indent.unwrap_or(self.fmt.indent),
+            use_tabs: tabs || self.fmt.tabs,
+            sort_fields: self.fmt.sort_fields,
+            ..FormatOptions::default()
+        }
+    }
+
+    /// Returns whether strict checking is enabled by the flag or the config.
+    #[must_use]
+    pub fn strict(&self, flag: bool) -> bool {
+        flag || self.check.strict
+    }
+
+    /// Returns the maximum query depth.
+    #[must_use]
+    pub fn max_depth(&self, flag: Option<usize>) -> usize {
+        flag.unwrap_or(self.check.max_depth)
+    }
+
+    /// Returns the enabled lint rules: all but those disabled by the config,
+    /// then adjusted by the `--rule` and `--no-rule` flags.
+    #[must_use]
+    pub fn lint_rules(&self, enable: &[LintRule], disable: &[LintRule]) -> Vec<LintRule> {
+        LintRule::value_variants()
+            .iter()
+            .copied()
+            .filter(|rule| {
+                !disable.contains(rule)
+                    && (enable.contains(rule) || !self.lint.disable.contains(rule))
+            })
+            .collect()
+    }
+
+    /// Returns the codegen target language.
+    #[must_use]
+    pub fn codegen_language(&self, flag: Option<CodegenLanguage>) -> CodegenLanguage {
+        flag.unwrap_or(self.codegen.lang)
+    }
+
+    /// Returns the codegen output file, if any.
+    #[must_use]
+    pub fn codegen_output(&self, flag: Option<PathBuf>) -> Option<PathBuf> {
+        flag.or_else(|| self.codegen.output.clone())
+    }
+
+    /// Returns the codegen package name, if any.
+    #[must_use]
+    pub fn package_name(&self, flag: Option<String>) -> Option<String> {
+        flag.or_else(|| self.codegen.package_name.clone())
+    }
+}
+
+/// Finds `bgql.toml` in `start` or the closest ancestor directory.
+#[must_use]
+pub fn find_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+/// Loads the configuration for the current directory.
+///
+/// Returns the defaults when no `bgql.toml` is found.
+pub fn load_config() -> io::Result<Config> {
+    load_config_from(&std::env::current_dir()?)
+}
+
+/// Loads the configuration that applies to `dir`.
+pub fn load_config_from(dir: &Path) -> io::Result<Config> {
+    let Some(path) = find_config(dir) else {
+        return Ok(Config::default());
+    };
+
+    let source = std::fs::read_to_string(&path)?;
+    let mut config = Config::from_toml(&source).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {}", path.display(), e),
+        )
+    })?;
+
+    if let (Some(output), Some(root)) = (&config.codegen.output, path.parent()) {
+        config.codegen.output = Some(root.join(output));
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_precedence() {
+        let defaults = Config::default();
+        let file = Config::from_toml(
+            r#"
+            [fmt]
+            indent = 4
+            sort_fields = true
+
+            [check]
+            max_depth = 5
+
+            [codegen]
+            lang = "rust"
+            package_name = "api"
+            "#,
+        )
+        .unwrap();
+
+        // Default
+        assert_eq!(defaults.format_options(None, false).indent_size, 2);
+        assert_eq!(defaults.max_depth(None), 10);
+        assert_eq!(defaults.codegen_language(None), CodegenLanguage::Typescript);
+        assert_eq!(defaults.package_name(None), None);
+
+        // File beats default
+        let options = file.format_options(None, false);
+        assert_eq!(options.indent_size, 4);
+        assert!(options.sort_fields);
+        assert!(!options.use_tabs);
+        assert_eq!(file.max_depth(None), 5);
+        assert_eq!(file.codegen_language(None), CodegenLanguage::Rust);
+        assert_eq!(file.package_name(None).as_deref(), Some("api"));
+
+        // Flag beats file
+        let options = file.format_options(Some(8), true);
+        assert_eq!(options.indent_size, 8);
+        assert!(options.use_tabs);
+        assert!(file.strict(true));
+        assert_eq!(file.max_depth(Some(3)), 3);
+        assert_eq!(
+            file.codegen_language(Some(CodegenLanguage::Go)),
+            CodegenLanguage::Go
+        );
+        assert_eq!(
+            file.package_name(Some("cli".to_string())).as_deref(),
+            Some("cli")
+        );
+    }
+
+    #[test]
+    fn test_config_rejects_unknown_keys() {
+        assert!(Config::from_toml("[fmt]\nindnet = 4\n").is_err());
+        assert!(Config::from_toml("[codegen]\nlang = \"cobol\"\n").is_err());
+        assert!(Config::from_toml("[lint]\ndisable = [\"naming\"]\n").is_err());
+    }
+
+    #[test]
+    fn test_lint_rules() {
+        let all = Config::default().lint_rules(&[], &[]);
+        assert_eq!(all, LintRule::value_variants());
+
+        let file = Config::from_toml("[lint]\ndisable = [\"naming-convention\", \"empty-type\"]\n")
+            .unwrap();
+        let rules = file.lint_rules(&[], &[]);
+        assert!(!rules.contains(&LintRule::NamingConvention));
+        assert!(!rules.contains(&LintRule::EmptyType));
+
+        // Flags beat the file
+        let rules = file.lint_rules(&[LintRule::NamingConvention], &[LintRule::ReservedWord]);
+        assert!(rules.contains(&LintRule::NamingConvention));
+        assert!(!rules.contains(&LintRule::EmptyType));
+        assert!(!rules.contains(&LintRule::ReservedWord));
+    }
+
+    #[test]
+    fn test_load_config_walks_up() {
+        let root = std::env::temp_dir().join(format!("bgql-config-{}", std::process::id()));
+        let nested = root.join("src").join("schema");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(
+            root.join(CONFIG_FILE),
+            "[check]\nstrict = true\n\n[codegen]\noutput = \"generated/types.ts\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(find_config(&nested), Some(root.join(CONFIG_FILE)));
+        let config = load_config_from(&nested).unwrap();
+        assert!(config.strict(false));
+        assert_eq!(
+            config.codegen_output(None),
+            Some(root.join("generated/types.ts"))
+        );
+
+        std::fs::write(root.join(CONFIG_FILE), "[check]\nstrict = \"yes\"\n").unwrap();
+        let err = load_config_from(&nested).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,375 @@
+//! Project scaffolding for `bgql init`.
+
+use crate::ProjectTemplate;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Options for creating a new project.
+#[derive(Debug, Clone)]
+pub struct InitOptions {
+    /// Project name, used for the directory and package name.
+    pub name: String,
+    /// Project template.
+    pub template: ProjectTemplate,
+    /// Write TypeScript sources instead of JavaScript.
+    pub typescript: bool,
+    /// Initialize a git repository.
+    pub git: bool,
+}
+
+/// Creates a project directory at `dir` and writes the template files.
+///
+/// Returns the created file paths, relative to `dir`. Fails without writing
+/// anything if `dir` exists and is not empty.
+pub fn init_project(dir: &Path, options: &InitOptions) -> io::Result<Vec<PathBuf>> {
+    if dir.exists() && dir.read_dir()?.next().is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!(
+                "directory `{}` already exists and is not empty",
+                dir.display()
+            ),
+        ));
+    }
+
+    let files = template_files(options);
+    for (path, content) in &files {
+        let path = dir.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, content)?;
+    }
+
+    if options.git {
+        git_init(dir);
+    }
+
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+/// Runs `git init`, warning instead of failing if git is unavailable.
+fn git_init(dir: &Path) {
+    let status = std::process::Command::new("git")
+        .arg("init")
+        .arg("--quiet")
+        .current_dir(dir)
+        .status();
+    if !matches!(status, Ok(status) if status.success()) {
+        eprintln!("warning: failed to initialize a git repository");
+    }
+}
+
+/// Returns the files a template consists of.
+fn template_files(options: &InitOptions) -> Vec<(PathBuf, String)> {
+    let ext = if options.typescript { "ts" } else { "js" };
+    let mut files = vec![
+        (PathBuf::from("schema.bgql"), schema(options.template)),
+        (PathBuf::from("bgql.toml"), config(options)),
+    ];
+
+    if options.git {
+        files.push((PathBuf::from(".gitignore"), GITIGNORE.to_string()));
+    }
+
+    match options.template {
+        ProjectTemplate::Minimal => {}
+        ProjectTemplate::Server => {
+            files.extend(node_files(options));
+            files.push((server_path(ext), SERVER.to_string()));
+        }
+        ProjectTemplate::Api => {
+            files.extend(node_files(options));
+            files.push((server_path(ext), SERVER.to_string()));
+            files.push((PathBuf::from("queries.bgql"), QUERIES.to_string()));
+        }
+        ProjectTemplate::Fullstack => {
+            files.extend(node_files(options));
+            files.push((server_path(ext), SERVER.to_string()));
+            files.push((
+                PathBuf::from(format!("src/client.{ext}")),
+                CLIENT.to_string(),
+            ));
+            files.push((PathBuf::from("queries.bgql"), QUERIES.to_string()));
+        }
+    }
+
+    files
+}
+
+fn server_path(ext: &str) -> PathBuf {
+    PathBuf::from(format!("src/server.{ext}"))
+}
+
+fn schema(template: ProjectTemplate) -> String {
+    let mut schema = String::from(
+        r#""A user of the application"
+type User {
+  id: ID
+  name: String
+  email: Option<String>
+}
+
+type Query {
+  user(id: ID): Option<User>
+  users: List<User>
+}
+"#,
+    );
+
+    if matches!(template, ProjectTemplate::Api | ProjectTemplate::Fullstack) {
+        schema.push_str(
+            r#"
+input CreateUserInput {
+  name: String
+  email: Option<String>
+}
+
+type Mutation {
+  createUser(input: CreateUserInput): User
+}
+"#,
+        );
+    }
+
+    schema
+}
+
+fn config(options: &InitOptions) -> String {
+    let output = if options.typescript {
+        "src/generated/graphql.ts"
+    } else {
+        "src/generated/graphql.d.ts"
+    };
+    format!(
+        r#"[fmt]
+indent = 2
+tabs = false
+
+[check]
+strict = false
+
+[codegen]
+lang = "typescript"
+output = "{output}"
+package_name = "{}"
+"#,
+        options.name
+    )
+}
+
+fn node_files(options: &InitOptions) -> Vec<(PathBuf, String)> {
+    let (main, dev) = if options.typescript {
+        ("src/server.ts", "tsx watch src/server.ts")
+    } else {
+        ("src/server.js", "node --watch src/server.js")
+    };
+    let dev_dependencies = if options.typescript {
+        ",\n  \"devDependencies\": {\n    \"tsx\": \"^4.0.0\",\n    \"typescript\": \"^5.0.0\"\n  }"
+    } else {
+        ""
+    };
+
+    let mut files = vec![(
+        PathBuf::from("package.json"),
+        format!(
+            r#"{{
+  "name": "{}",
+  "private": true,
+  "type": "module",
+  "main": "{main}",
+  "scripts": {{
+    "dev": "{dev}",
+    "codegen": "bgql codegen schema.bgql"
+  }},
+  "dependencies": {{
+    "@bgql/server": "latest"
+  }}{dev_dependencies}
+}}
+"#,
+            options.name
+        ),
+    )];
+
+    if options.typescript {
+        files.push((PathBuf::from("tsconfig.json"), TSCONFIG.to_string()));
+    }
+
+    files
+}
+
+const GITIGNORE: &str = "node_modules/\ndist/\nsrc/generated/\n";
+
+const SERVER: &str = r#"import { createServer } from '@bgql/server';
+import { readFileSync } from 'node:fs';
+
+const schema = readFileSync(new URL('../schema.bgql', import.meta.url), 'utf8');
+
+const server = createServer({
+  schema,
+  resolvers: {
+    Query: {
+      user: () => ({ id: '1', name: 'Alice', email: null }),
+      users: () => [],
+    },
+  },
+});
+
+server.listen({ port: 4000 });
+"#;
+
+const QUERIES: &str = r#"query GetUser($id: ID) {
+  user(id: $id) {
+    id
+    name
+  }
+}
+"#;
+
+const CLIENT: &str = r#"import { createClient } from '@bgql/client';
+
+export const client = createClient({
+  url: 'http://localhost:4000/graphql',
+});
+"#;
+
+const TSCONFIG: &str = r#"{
+  "compilerOptions": {
+    "target": "ES2022",
+    "module": "ESNext",
+    "moduleResolution": "Bundler",
+    "strict": true,
+    "outDir": "dist"
+  },
+  "include": ["src"]
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_project(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bgql-init-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn init(name: &str, template: ProjectTemplate, typescript: bool) -> PathBuf {
+        let dir = temp_project(name);
+        let options = InitOptions {
+            name: name.to_string(),
+            template,
+            typescript,
+            git: false,
+        };
+        init_project(&dir, &options).unwrap();
+        dir
+    }
+
+    fn files(dir: &Path) -> Vec<String> {
+        fn walk(root: &Path, dir: &Path, out: &mut Vec<String>) {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    walk(root, &path, out);
+                } else {
+                    let relative = path.strip_prefix(root).unwrap();
+                    out.push(relative.to_string_lossy().replace('\\', "/"));
+                }
+            }
+        }
+        let mut out = Vec::new();
+        walk(dir, dir, &mut out);
+        out.sort();
+        out
+    }
+
+    #[test]
+    fn test_init_templates() {
+        let cases: [(ProjectTemplate, bool, &[&str]); 5] = [
+            (
+                ProjectTemplate::Minimal,
+                false,
+                &["bgql.toml", "schema.bgql"],
+            ),
+            (
+                ProjectTemplate::Server,
+                false,
+                &["bgql.toml", "package.json", "schema.bgql", "src/server.js"],
+            ),
+            (
+                ProjectTemplate::Server,
+                true,
+                &[
+                    "bgql.toml",
+                    "package.json",
+                    "schema.bgql",
+                    "src/server.ts",
+                    "tsconfig.json",
+                ],
+            ),
+            (
+                ProjectTemplate::Api,
+                false,
+                &[
+                    "bgql.toml",
+                    "package.json",
+                    "queries.bgql",
+                    "schema.bgql",
+                    "src/server.js",
+                ],
+            ),
+            (
+                ProjectTemplate::Fullstack,
+                true,
+                &[
+                    "bgql.toml",
+                    "package.json",
+                    "queries.bgql",
+                    "schema.bgql",
+                    "src/client.ts",
+                    "src/server.ts",
+                    "tsconfig.json",
+                ],
+            ),
+        ];
+
+        for (i, (template, typescript, expected)) in cases.into_iter().enumerate() {
+            let dir = init(&format!("app{i}"), template, typescript);
+            assert_eq!(files(&dir), expected, "{template:?}");
+            assert!(crate::config::load_config_from(&dir).is_ok());
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_init_schema_parses() {
+        for template in [ProjectTemplate::Minimal, ProjectTemplate::Fullstack] {
+            let interner = bgql_core::Interner::new();
+            let schema = schema(template);
+            let result = bgql_syntax::parse(&schema, &interner);
+            assert!(!result.diagnostics.has_errors(), "{template:?}");
+        }
+    }
+
+    #[test]
+    fn test_init_refuses_non_empty_dir() {
+        let dir = temp_project("existing");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("keep.txt"), "").unwrap();
+
+        let options = InitOptions {
+            name: "existing".to_string(),
+            template: ProjectTemplate::Minimal,
+            typescript: false,
+            git: false,
+        };
+        let err = init_project(&dir, &options).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert!(err.to_string().contains("not empty"));
+        assert_eq!(files(&dir), ["keep.txt"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
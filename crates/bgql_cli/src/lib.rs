@@ -9,6 +9,9 @@
 //! # Validate a schema
 //! bgql check schema.bgql
 //!
+//! # Run style lints
+//! bgql lint schema.bgql --no-rule empty-type
+//!
 //! # Format files
 //! bgql fmt schema.bgql
 //!
@@ -28,10 +31,20 @@
 //! bgql lsp
 //! ```
 
-use bgql_core::Interner;
-use bgql_syntax::{parse, FormatOptions};
+pub mod config;
+pub mod init;
+pub mod lint;
+pub mod watch;
+
+use bgql_codegen::{check_reserved_names, CodeGenerator, CodegenOptions, Language};
+use bgql_core::diagnostics::codes;
+use bgql_core::{Diagnostic, DiagnosticSeverity, Interner, SourceFile, Text};
+use bgql_semantic::{checker, hir::HirDatabase, types::TypeRegistry};
+use bgql_syntax::{parse, Definition, Document, FormatOptions, Selection, SelectionSet};
 use clap::{Parser, Subcommand, ValueEnum};
 use colored::Colorize;
+use serde::Deserialize;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 #[derive(Parser, Debug)]
@@ -60,13 +73,25 @@ pub enum ProjectTemplate {
     Api,
 }
 
-#[derive(Clone, Copy, Debug, ValueEnum)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum CodegenLanguage {
+    #[default]
     Typescript,
     Rust,
     Go,
 }
 
+impl From<CodegenLanguage> for Language {
+    fn from(lang: CodegenLanguage) -> Self {
+        match lang {
+            CodegenLanguage::Typescript => Language::TypeScript,
+            CodegenLanguage::Rust => Language::Rust,
+            CodegenLanguage::Go => Language::Go,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, ValueEnum)]
 pub enum IdeTarget {
     /// Zed editor
@@ -77,6 +102,16 @@ pub enum IdeTarget {
     Neovim,
 }
 
+/// Output format of `bgql fmt --check`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum FmtOutput {
+    /// One line per file that would change
+    #[default]
+    Text,
+    /// JSON array of the files that would change
+    Json,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Initialize a new Better GraphQL project
@@ -110,9 +145,23 @@ pub enum Commands {
         #[arg(long)]
         complexity: bool,
 
-        /// Maximum allowed query depth
-        #[arg(long, default_value = "10")]
-        max_depth: usize,
+        /// Maximum allowed query depth [default: 10]
+        #[arg(long)]
+        max_depth: Option<usize>,
+    },
+
+    /// Run style lints on GraphQL files
+    Lint {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Enable a rule disabled in bgql.toml
+        #[arg(long = "rule", value_enum)]
+        rules: Vec<lint::LintRule>,
+
+        /// Disable a rule
+        #[arg(long = "no-rule", value_enum)]
+        no_rules: Vec<lint::LintRule>,
     },
 
     /// Format GraphQL files
@@ -125,9 +174,13 @@ pub enum Commands {
         #[arg(long)]
         check: bool,
 
-        /// Indentation size
-        #[arg(long, default_value = "2")]
-        indent: usize,
+        /// Output format for --check
+        #[arg(long, value_enum, default_value = "text", requires = "check")]
+        format: FmtOutput,
+
+        /// Indentation size [default: 2]
+        #[arg(long)]
+        indent: Option<usize>,
 
         /// Use tabs instead of spaces
         #[arg(long)]
@@ -144,13 +197,33 @@ pub enum Commands {
         #[arg(short, long)]
         output: Option<PathBuf>,
 
-        /// Target language
-        #[arg(short, long, value_enum, default_value = "typescript")]
-        lang: CodegenLanguage,
+        /// Target language [default: typescript]
+        #[arg(short, long, value_enum)]
+        lang: Option<CodegenLanguage>,
 
         /// Watch for changes and regenerate
         #[arg(short, long)]
         watch: bool,
+
+        /// Generate client SDK code
+        #[arg(long)]
+        client: bool,
+
+        /// Generate server SDK code
+        #[arg(long)]
+        server: bool,
+
+        /// Generate types for operations in the document
+        #[arg(long)]
+        operations: bool,
+
+        /// Package/module name for the generated code
+        #[arg(long)]
+        package_name: Option<String>,
+
+        /// Rust type used for custom scalars
+        #[arg(long)]
+        scalar_type: Option<String>,
     },
 
     /// Start the development server
@@ -248,34 +321,65 @@ pub enum Commands {
 
 pub fn run(cli: Cli) -> Result<i32, Box<dyn std::error::Error>> {
     match cli.command {
-        Commands::Init { .. } => {
-            println!("Init command not yet implemented");
-            Ok(0)
-        }
+        Commands::Init {
+            name,
+            template,
+            typescript,
+            no_git,
+        } => init_command(name, template, typescript, !no_git, cli.quiet),
         Commands::Check {
             files,
             strict,
             complexity: _,
-            max_depth: _,
-        } => check_files(&files, strict, cli.verbose),
+            max_depth,
+        } => {
+            let config = config::load_config()?;
+            check_files(
+                &files,
+                config.strict(strict),
+                config.max_depth(max_depth),
+                cli.verbose,
+            )
+        }
+        Commands::Lint {
+            files,
+            rules,
+            no_rules,
+        } => {
+            let config = config::load_config()?;
+            let enabled = config.lint_rules(&rules, &no_rules);
+            let language = config.codegen_language(None).into();
+            lint::lint_files(&files, &enabled, language, &mut std::io::stderr())
+        }
         Commands::Fmt {
             files,
             check,
+            format,
             indent,
             tabs,
-        } => format_files(&files, check, indent, tabs, cli.verbose),
+        } => {
+            let config = config::load_config()?;
+            let options = config.format_options(indent, tabs);
+            let mode = check.then_some(format);
+            format_files(&files, mode, &options, cli.verbose, &mut std::io::stdout())
+        }
         Commands::Codegen {
             schema,
             output,
             lang,
             watch: _,
+            client,
+            server,
+            operations,
+            package_name,
+            scalar_type,
         } => {
-            let lang_str = match lang {
-                CodegenLanguage::Typescript => "typescript",
-                CodegenLanguage::Rust => "rust",
-                CodegenLanguage::Go => "go",
-            };
-            generate_code(&schema, output.as_ref(), lang_str)
+            let config = config::load_config()?;
+            let language = config.codegen_language(lang).into();
+            let output = config.codegen_output(output);
+            let package_name = config.package_name(package_name);
+            let options = codegen_options(client, server, operations, package_name, scalar_type);
+            generate_code(&schema, output.as_ref(), language, options)
         }
         Commands::Dev { .. } => {
             println!("Development server not yet implemented");
@@ -285,10 +389,11 @@ pub fn run(cli: Cli) -> Result<i32, Box<dyn std::error::Error>> {
             println!("Build command not yet implemented");
             Ok(0)
         }
-        Commands::Watch { .. } => {
-            println!("Watch command not yet implemented");
-            Ok(0)
-        }
+        Commands::Watch {
+            path,
+            command,
+            extensions,
+        } => watch::watch(&path, &command, &extensions, cli.verbose),
         Commands::Lsp => {
             // Handled in main.rs
             Ok(0)
@@ -307,9 +412,40 @@ pub fn run(cli: Cli) -> Result<i32, Box<dyn std::error::Error>> {
     }
 }
 
+fn init_command(
+    name: String,
+    template: ProjectTemplate,
+    typescript: bool,
+    git: bool,
+    quiet: bool,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    let dir = PathBuf::from(&name);
+    let options = init::InitOptions {
+        name,
+        template,
+        typescript,
+        git,
+    };
+    let files = init::init_project(&dir, &options)?;
+
+    if !quiet {
+        for file in &files {
+            println!("  {} {}", "Created".green(), dir.join(file).display());
+        }
+        println!();
+        println!(
+            "{} Project created in {}",
+            "Success:".green().bold(),
+            dir.display()
+        );
+    }
+    Ok(0)
+}
+
 fn check_files(
     files: &[PathBuf],
-    _strict: bool,
+    strict: bool,
+    max_depth: usize,
     verbose: bool,
 ) -> Result<i32, Box<dyn std::error::Error>> {
     let mut has_errors = false;
@@ -319,19 +455,13 @@ fn check_files(
             println!("{} {}", "Checking".blue(), file.display());
         }
 
-        let source = std::fs::read_to_string(file)?;
-        let interner = Interner::new();
-        let result = parse(&source, &interner);
+        let source = SourceFile::new(file.clone(), std::fs::read_to_string(file)?);
+        let reported = check_source(&source, strict, max_depth);
 
-        if result.diagnostics.has_errors() {
+        if !reported.is_empty() {
             has_errors = true;
-            eprintln!("{} {}", "Error".red().bold(), file.display());
-
-            for error in result.diagnostics.errors() {
-                eprintln!("  {} {}", "-->".blue(), error.title);
-                if let Some(msg) = &error.message {
-                    eprintln!("      {}", msg);
-                }
+            for diagnostic in reported {
+                eprintln!("{}", diagnostic.render(&source));
             }
         } else if verbose {
             println!("{} {}", "OK".green(), file.display());
@@ -352,20 +482,118 @@ fn check_files(
     }
 }
 
+/// Parses and type-checks `source`, returning the diagnostics that fail the
+/// check.
+fn check_source(source: &SourceFile, strict: bool, max_depth: usize) -> Vec<Diagnostic> {
+    let interner = Interner::new();
+    let result = parse(source.content(), &interner);
+    let mut diagnostics = result.diagnostics;
+    if !diagnostics.has_errors() {
+        let types = TypeRegistry::new();
+        let hir = HirDatabase::new();
+        let checked = checker::check(&result.document, &types, &hir, &interner);
+        diagnostics.extend(checked.diagnostics.iter().cloned());
+        diagnostics.extend(check_depth(&result.document, &interner, max_depth));
+    }
+    diagnostics.sort_by_span();
+    diagnostics.dedup();
+
+    // In strict mode, warnings fail the check too. Style warnings are
+    // left to `bgql lint`, so they never fail a check.
+    diagnostics
+        .iter()
+        .filter(|d| match d.severity {
+            DiagnosticSeverity::Error => true,
+            DiagnosticSeverity::Warning => strict && lint::LintRule::from_code(&d.code).is_none(),
+            _ => false,
+        })
+        .cloned()
+        .collect()
+}
+
+/// Reports the operations in `document` whose selections nest deeper than
+/// `max_depth`. Fragment spreads count as their fragment's selections.
+fn check_depth(document: &Document<'_>, interner: &Interner, max_depth: usize) -> Vec<Diagnostic> {
+    let fragments: HashMap<Text, &SelectionSet<'_>> = document
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Fragment(fragment) => Some((fragment.name.value, &fragment.selection_set)),
+            _ => None,
+        })
+        .collect();
+
+    document
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Operation(operation) => Some(operation),
+            _ => None,
+        })
+        .filter_map(|operation| {
+            let depth = selection_depth(&operation.selection_set, &fragments, &mut Vec::new());
+            if depth <= max_depth {
+                return None;
+            }
+            let (name, span) = match &operation.name {
+                Some(name) => (format!("`{}`", interner.get(name.value)), name.span),
+                None => ("Anonymous operation".to_string(), operation.span),
+            };
+            Some(
+                Diagnostic::error(
+                    codes::QUERY_TOO_DEEP,
+                    format!("Operation {name} has depth {depth}"),
+                )
+                .with_span(span, format!("exceeds the maximum depth of {max_depth}")),
+            )
+        })
+        .collect()
+}
+
+/// Returns how many selection sets nest below `selections`.
+fn selection_depth(
+    selections: &SelectionSet<'_>,
+    fragments: &HashMap<Text, &SelectionSet<'_>>,
+    spreading: &mut Vec<Text>,
+) -> usize {
+    selections
+        .selections
+        .iter()
+        .map(|selection| match selection {
+            Selection::Field(field) => field.selection_set.as_ref().map_or(0, |nested| {
+                1 + selection_depth(nested, fragments, spreading)
+            }),
+            Selection::InlineFragment(fragment) => {
+                selection_depth(&fragment.selection_set, fragments, spreading)
+            }
+            Selection::FragmentSpread(spread) => {
+                // Cyclic spreads are reported by the checker
+                if spreading.contains(&spread.name.value) {
+                    return 0;
+                }
+                let Some(fragment) = fragments.get(&spread.name.value) else {
+                    return 0;
+                };
+                spreading.push(spread.name.value);
+                let depth = selection_depth(fragment, fragments, spreading);
+                spreading.pop();
+                depth
+            }
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Formats `files` in place, or only reports the ones that would change when
+/// `check` is set. The `--format json` report is written to `out`.
 fn format_files(
     files: &[PathBuf],
-    check_only: bool,
-    indent: usize,
-    use_tabs: bool,
+    check: Option<FmtOutput>,
+    options: &FormatOptions,
     verbose: bool,
+    out: &mut dyn std::io::Write,
 ) -> Result<i32, Box<dyn std::error::Error>> {
-    let mut needs_formatting = false;
-
-    let options = FormatOptions {
-        indent_size: indent,
-        use_tabs,
-        ..Default::default()
-    };
+    let mut unformatted = Vec::new();
 
     for file in files {
         let source = std::fs::read_to_string(file)?;
@@ -380,11 +608,13 @@ fn format_files(
         let formatted =
             bgql_syntax::format_with_options(&result.document, &interner, options.clone());
 
-        if check_only {
+        if let Some(output) = check {
             if source != formatted {
-                needs_formatting = true;
-                println!("{} {}", "Would format".yellow(), file.display());
-            } else if verbose {
+                unformatted.push(file.display().to_string());
+                if output == FmtOutput::Text {
+                    println!("{} {}", "Would format".yellow(), file.display());
+                }
+            } else if verbose && output == FmtOutput::Text {
                 println!("{} {}", "OK".green(), file.display());
             }
         } else if source != formatted {
@@ -395,17 +625,50 @@ fn format_files(
         }
     }
 
-    if check_only && needs_formatting {
+    if check == Some(FmtOutput::Json) {
+        writeln!(out, "{}", serde_json::to_string(&unformatted)?)?;
+    }
+
+    if check.is_some() && !unformatted.is_empty() {
         Ok(1)
     } else {
         Ok(0)
     }
 }
 
+/// Builds codegen options from the CLI flags.
+///
+/// When none of `--client`, `--server` and `--operations` is given, the
+/// generator defaults are kept; otherwise only the selected parts are generated.
+fn codegen_options(
+    client: bool,
+    server: bool,
+    operations: bool,
+    package_name: Option<String>,
+    scalar_type: Option<String>,
+) -> CodegenOptions {
+    let defaults = CodegenOptions::default();
+    let selected = client || server || operations;
+
+    CodegenOptions {
+        client: if selected { client } else { defaults.client },
+        server: if selected { server } else { defaults.server },
+        operations: if selected {
+            operations
+        } else {
+            defaults.operations
+        },
+        package_name: package_name.unwrap_or(defaults.package_name),
+        scalar_type: scalar_type.or(defaults.scalar_type),
+        ..defaults
+    }
+}
+
 fn generate_code(
     schema_path: &Path,
     output: Option<&PathBuf>,
-    lang: &str,
+    language: Language,
+    options: CodegenOptions,
 ) -> Result<i32, Box<dyn std::error::Error>> {
     let source = std::fs::read_to_string(schema_path)?;
     let interner = Interner::new();
@@ -419,16 +682,16 @@ fn generate_code(
         return Ok(1);
     }
 
-    // Generate code based on language
-    let code = match lang.to_lowercase().as_str() {
-        "typescript" | "ts" => generate_typescript(&result.document, &interner),
-        "rust" | "rs" => generate_rust(&result.document, &interner),
-        "go" => generate_go(&result.document, &interner),
-        _ => {
-            eprintln!("{} Unknown language: {}", "Error:".red().bold(), lang);
-            return Ok(1);
+    // Names the target language reserves would make the output uncompilable
+    let reserved = check_reserved_names(&result.document, &interner, language);
+    if !reserved.is_empty() {
+        let file = SourceFile::new(schema_path.to_path_buf(), source.clone());
+        for diagnostic in reserved.iter() {
+            eprintln!("{}", diagnostic.render(&file));
         }
-    };
+    }
+
+    let code = CodeGenerator::with_options(&result.document, &interner, options).generate(language);
 
     match output {
         Some(path) => {
@@ -443,222 +706,6 @@ fn generate_code(
     Ok(0)
 }
 
-fn generate_typescript(document: &bgql_syntax::Document<'_>, interner: &Interner) -> String {
-    let mut output = String::from("// Generated by Better GraphQL\n\n");
-
-    for def in &document.definitions {
-        if let bgql_syntax::Definition::Type(type_def) = def {
-            match type_def {
-                bgql_syntax::TypeDefinition::Object(obj) => {
-                    output.push_str(&format!(
-                        "export interface {} {{\n",
-                        interner.get(obj.name.value)
-                    ));
-                    for field in &obj.fields {
-                        let ts_type = type_to_typescript(&field.ty, interner);
-                        output.push_str(&format!(
-                            "  {}: {};\n",
-                            interner.get(field.name.value),
-                            ts_type
-                        ));
-                    }
-                    output.push_str("}\n\n");
-                }
-                bgql_syntax::TypeDefinition::Enum(e) => {
-                    let values: Vec<_> = e
-                        .values
-                        .iter()
-                        .map(|v| format!("\"{}\"", interner.get(v.name.value)))
-                        .collect();
-                    output.push_str(&format!(
-                        "export type {} = {};\n\n",
-                        interner.get(e.name.value),
-                        values.join(" | ")
-                    ));
-                }
-                bgql_syntax::TypeDefinition::Input(inp) => {
-                    output.push_str(&format!(
-                        "export interface {} {{\n",
-                        interner.get(inp.name.value)
-                    ));
-                    for field in &inp.fields {
-                        let ts_type = type_to_typescript(&field.ty, interner);
-                        output.push_str(&format!(
-                            "  {}: {};\n",
-                            interner.get(field.name.value),
-                            ts_type
-                        ));
-                    }
-                    output.push_str("}\n\n");
-                }
-                _ => {}
-            }
-        }
-    }
-
-    output
-}
-
-fn type_to_typescript(ty: &bgql_syntax::Type<'_>, interner: &Interner) -> String {
-    match ty {
-        bgql_syntax::Type::Named(named) => {
-            let name = interner.get(named.name);
-            match name.as_str() {
-                "Int" | "Float" => "number".to_string(),
-                "String" | "ID" => "string".to_string(),
-                "Boolean" => "boolean".to_string(),
-                other => other.to_string(),
-            }
-        }
-        bgql_syntax::Type::Option(inner, _) => {
-            format!("{} | null", type_to_typescript(inner, interner))
-        }
-        bgql_syntax::Type::List(inner, _) => {
-            format!("Array<{}>", type_to_typescript(inner, interner))
-        }
-        bgql_syntax::Type::Generic(gen) => {
-            let name = interner.get(gen.name);
-            let args: Vec<_> = gen
-                .arguments
-                .iter()
-                .map(|a| type_to_typescript(a, interner))
-                .collect();
-            format!("{}<{}>", name, args.join(", "))
-        }
-        _ => "unknown".to_string(),
-    }
-}
-
-fn generate_rust(document: &bgql_syntax::Document<'_>, interner: &Interner) -> String {
-    let mut output =
-        String::from("// Generated by Better GraphQL\n\nuse serde::{Deserialize, Serialize};\n\n");
-
-    for def in &document.definitions {
-        if let bgql_syntax::Definition::Type(type_def) = def {
-            match type_def {
-                bgql_syntax::TypeDefinition::Object(obj) => {
-                    output.push_str("#[derive(Debug, Clone, Serialize, Deserialize)]\n");
-                    output.push_str(&format!("pub struct {} {{\n", interner.get(obj.name.value)));
-                    for field in &obj.fields {
-                        let rs_type = type_to_rust(&field.ty, interner);
-                        output.push_str(&format!(
-                            "    pub {}: {},\n",
-                            interner.get(field.name.value),
-                            rs_type
-                        ));
-                    }
-                    output.push_str("}\n\n");
-                }
-                bgql_syntax::TypeDefinition::Enum(e) => {
-                    output.push_str(
-                        "#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]\n",
-                    );
-                    output.push_str(&format!("pub enum {} {{\n", interner.get(e.name.value)));
-                    for value in &e.values {
-                        output.push_str(&format!("    {},\n", interner.get(value.name.value)));
-                    }
-                    output.push_str("}\n\n");
-                }
-                _ => {}
-            }
-        }
-    }
-
-    output
-}
-
-fn type_to_rust(ty: &bgql_syntax::Type<'_>, interner: &Interner) -> String {
-    match ty {
-        bgql_syntax::Type::Named(named) => {
-            let name = interner.get(named.name);
-            match name.as_str() {
-                "Int" => "i32".to_string(),
-                "Float" => "f64".to_string(),
-                "String" | "ID" => "String".to_string(),
-                "Boolean" => "bool".to_string(),
-                other => other.to_string(),
-            }
-        }
-        bgql_syntax::Type::Option(inner, _) => {
-            format!("Option<{}>", type_to_rust(inner, interner))
-        }
-        bgql_syntax::Type::List(inner, _) => {
-            format!("Vec<{}>", type_to_rust(inner, interner))
-        }
-        _ => "()".to_string(),
-    }
-}
-
-fn generate_go(document: &bgql_syntax::Document<'_>, interner: &Interner) -> String {
-    let mut output = String::from("// Generated by Better GraphQL\n\npackage bgql\n\n");
-
-    for def in &document.definitions {
-        if let bgql_syntax::Definition::Type(type_def) = def {
-            match type_def {
-                bgql_syntax::TypeDefinition::Object(obj) => {
-                    output.push_str(&format!(
-                        "type {} struct {{\n",
-                        interner.get(obj.name.value)
-                    ));
-                    for field in &obj.fields {
-                        let go_type = type_to_go(&field.ty, interner);
-                        let field_name = capitalize(&interner.get(field.name.value));
-                        output.push_str(&format!(
-                            "\t{} {} `json:\"{}\"`\n",
-                            field_name,
-                            go_type,
-                            interner.get(field.name.value)
-                        ));
-                    }
-                    output.push_str("}\n\n");
-                }
-                bgql_syntax::TypeDefinition::Enum(e) => {
-                    let name = interner.get(e.name.value);
-                    output.push_str(&format!("type {} string\n\nconst (\n", name));
-                    for value in &e.values {
-                        let val = interner.get(value.name.value);
-                        output.push_str(&format!("\t{}_{} {} = \"{}\"\n", name, val, name, val));
-                    }
-                    output.push_str(")\n\n");
-                }
-                _ => {}
-            }
-        }
-    }
-
-    output
-}
-
-fn type_to_go(ty: &bgql_syntax::Type<'_>, interner: &Interner) -> String {
-    match ty {
-        bgql_syntax::Type::Named(named) => {
-            let name = interner.get(named.name);
-            match name.as_str() {
-                "Int" => "int".to_string(),
-                "Float" => "float64".to_string(),
-                "String" | "ID" => "string".to_string(),
-                "Boolean" => "bool".to_string(),
-                other => other.to_string(),
-            }
-        }
-        bgql_syntax::Type::Option(inner, _) => {
-            format!("*{}", type_to_go(inner, interner))
-        }
-        bgql_syntax::Type::List(inner, _) => {
-            format!("[]{}", type_to_go(inner, interner))
-        }
-        _ => "interface{}".to_string(),
-    }
-}
-
-fn capitalize(s: &str) -> String {
-    let mut chars = s.chars();
-    match chars.next() {
-        None => String::new(),
-        Some(f) => f.to_uppercase().collect::<String>() + chars.as_str(),
-    }
-}
-
 fn handle_ide_command(
     target: IdeTarget,
     install: bool,
@@ -849,4 +896,204 @@ mod tests {
         use clap::CommandFactory;
         Cli::command().debug_assert();
     }
+
+    #[test]
+    fn test_codegen_options_from_flags() {
+        let options = codegen_options(false, false, false, None, None);
+        assert!(options.client && options.server && options.operations);
+        assert_eq!(options.package_name, "generated");
+
+        let options = codegen_options(true, false, false, Some("api".to_string()), None);
+        assert!(options.client);
+        assert!(!options.server);
+        assert!(!options.operations);
+        assert_eq!(options.package_name, "api");
+    }
+
+    #[test]
+    fn test_codegen_client_only_omits_server_types() {
+        let source = r#"
+            type User { id: ID }
+            type Query { user(id: ID): Option<User> }
+        "#;
+        let interner = Interner::new();
+        let result = parse(source, &interner);
+        assert!(!result.diagnostics.has_errors());
+
+        let generate = |options: CodegenOptions| {
+            CodeGenerator::with_options(&result.document, &interner, options)
+                .generate(Language::TypeScript)
+        };
+
+        let client = generate(codegen_options(true, false, false, None, None));
+        assert!(client.contains("export interface User {"));
+        assert!(!client.contains("Resolvers"));
+
+        let both = generate(codegen_options(false, false, false, None, None));
+        assert!(both.contains("export interface QueryResolvers<"));
+    }
+
+    fn codegen(source: &str, language: Language, scalar_type: Option<&str>) -> String {
+        let interner = Interner::new();
+        let result = parse(source, &interner);
+        assert!(!result.diagnostics.has_errors());
+        let options = codegen_options(false, false, false, None, scalar_type.map(str::to_string));
+        CodeGenerator::with_options(&result.document, &interner, options).generate(language)
+    }
+
+    #[test]
+    fn test_generate_rust_input_type() {
+        let source = r#"
+            scalar DateTime
+
+            input CreateUserInput {
+                name: String
+                tags: List<String>
+                birthday: Option<DateTime>
+            }
+        "#;
+        let output = codegen(source, Language::Rust, None);
+
+        assert!(output.contains("pub struct CreateUserInput {\n"));
+        assert!(output.contains("    pub name: String,\n"));
+        assert!(output.contains("    pub tags: Vec<String>,\n"));
+        assert!(output.contains("    pub birthday: Option<String>,\n"));
+        assert!(output.contains("pub type DateTime = String;\n"));
+
+        let output = codegen(
+            "scalar DateTime\ninput A { at: DateTime }",
+            Language::Rust,
+            Some("chrono::DateTime"),
+        );
+        assert!(output.contains("pub type DateTime = chrono::DateTime;\n"));
+    }
+
+    #[test]
+    fn test_generate_go_union() {
+        let output = codegen(
+            r#"
+            type User { id: ID }
+            type Post { title: String }
+            union SearchResult = User | Post
+        "#,
+            Language::Go,
+            None,
+        );
+
+        assert!(output.contains("type SearchResult interface {\n\tisSearchResult()\n}\n"));
+        assert!(output.contains("func (User) isSearchResult() {}\n"));
+        assert!(output.contains("func (Post) isSearchResult() {}\n"));
+    }
+
+    #[test]
+    fn test_generate_go_interface() {
+        let output = codegen(
+            r#"
+            interface Node {
+                id: ID
+                parent: Option<Node>
+            }
+            type User implements Node {
+                id: ID
+                parent: Option<Node>
+                tags: List<String>
+            }
+        "#,
+            Language::Go,
+            None,
+        );
+
+        assert!(
+            output.contains("type Node interface {\n\tGetId() string\n\tGetParent() *Node\n}\n")
+        );
+        assert!(output.contains("\tTags []string `json:\"tags\"`\n"));
+        assert!(output.contains("func (o User) GetId() string { return o.Id }\n"));
+        assert!(output.contains("func (o User) GetParent() *Node { return o.Parent }\n"));
+        assert!(!output.contains("GetTags"));
+    }
+
+    #[test]
+    fn test_generate_rust_enum_with_data() {
+        let output = codegen(
+            r#"
+            enum Shape {
+                Point
+                Circle(Float)
+                Rect { width: Float, height: Float }
+            }
+        "#,
+            Language::Rust,
+            None,
+        );
+
+        assert!(output.contains("pub enum Shape {\n"));
+        assert!(output.contains("    Point,\n"));
+        assert!(output.contains("    Circle(f64),\n"));
+        assert!(output.contains("    Rect {\n        width: f64,\n        height: f64,\n    },\n"));
+    }
+
+    #[test]
+    fn test_check_source_reports_type_errors() {
+        let source = SourceFile::new(
+            "schema.bgql".into(),
+            "type Query {\n  user: Missing\n}".into(),
+        );
+        let reported = check_source(&source, false, 10);
+        assert!(reported.iter().any(|d| d.code == codes::UNDEFINED_TYPE));
+    }
+
+    #[test]
+    fn test_check_source_leaves_lints_to_lint_in_strict_mode() {
+        let source = SourceFile::new(
+            "schema.bgql".into(),
+            "type Query {\n  user: user\n}\ntype user {\n  id: ID\n}".into(),
+        );
+        assert!(check_source(&source, true, 10).is_empty());
+    }
+
+    #[test]
+    fn test_check_source_limits_operation_depth() {
+        let source = SourceFile::new(
+            "schema.bgql".into(),
+            "type Query {\n  user: User\n}\ntype User {\n  id: ID\n  friend: User\n}\n\
+             query Deep {\n  user {\n    friend {\n      ...Friends\n    }\n  }\n}\n\
+             fragment Friends on User {\n  friend {\n    id\n  }\n}"
+                .into(),
+        );
+        assert!(check_source(&source, false, 3).is_empty());
+
+        let reported = check_source(&source, false, 2);
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].code, codes::QUERY_TOO_DEEP);
+        assert_eq!(reported[0].title, "Operation `Deep` has depth 3");
+    }
+
+    #[test]
+    fn test_fmt_check_json_lists_unformatted_files() {
+        let dir = std::env::temp_dir().join(format!("bgql-fmt-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let formatted = dir.join("formatted.bgql");
+        let messy = dir.join("messy.bgql");
+        std::fs::write(&formatted, "type User {\n  id: ID\n}").unwrap();
+        std::fs::write(&messy, "type User{id:ID}").unwrap();
+
+        let files = [formatted.clone(), messy.clone()];
+        let mut out = Vec::new();
+        let code = format_files(
+            &files,
+            Some(FmtOutput::Json),
+            &FormatOptions::default(),
+            false,
+            &mut out,
+        )
+        .unwrap();
+
+        assert_eq!(code, 1);
+        let listed: Vec<String> = serde_json::from_slice(&out).unwrap();
+        assert_eq!(listed, vec![messy.display().to_string()]);
+        // Checking never rewrites files
+        assert_eq!(std::fs::read_to_string(&messy).unwrap(), "type User{id:ID}");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
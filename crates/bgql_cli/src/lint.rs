@@ -0,0 +1,149 @@
+//! Style lints for `bgql lint`.
+//!
+//! Lints are the semantic checker's warnings that describe style rather than
+//! correctness, so `bgql check` keeps reporting hard errors only. Every rule
+//! is enabled unless `bgql.toml` or a `--no-rule` flag disables it.
+
+use bgql_codegen::{check_reserved_names, Language};
+use bgql_core::diagnostics::codes;
+use bgql_core::{Interner, SourceFile};
+use bgql_semantic::{checker, hir::HirDatabase, types::TypeRegistry};
+use bgql_syntax::parse;
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// A lint rule that can be enabled or disabled by name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintRule {
+    /// Type names should be PascalCase, fields camelCase
+    NamingConvention,
+    /// Type parameters should be used by their type
+    UnusedTypeParameter,
+    /// Types should declare at least one field
+    EmptyType,
+    /// Union members should be listed once
+    DuplicateUnionMember,
+    /// Required fields of output types should not form a cycle
+    RequiredFieldCycle,
+    /// Names should not be keywords of the codegen language
+    ReservedWord,
+    /// Types should be referenced by a field, argument, union or directive
+    UnusedType,
+    /// `@deprecated` should give a reason
+    DeprecatedWithoutReason,
+}
+
+impl LintRule {
+    /// Returns the diagnostic code reported by this rule.
+    #[must_use]
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::NamingConvention => codes::NAMING_CONVENTION,
+            Self::UnusedTypeParameter => codes::UNUSED_TYPE_PARAMETER,
+            Self::EmptyType => codes::EMPTY_TYPE,
+            Self::DuplicateUnionMember => codes::DUPLICATE_UNION_MEMBER,
+            Self::RequiredFieldCycle => codes::CYCLIC_TYPE_REFERENCE,
+            Self::ReservedWord => codes::RESERVED_WORD,
+            Self::UnusedType => codes::UNUSED_TYPE,
+            Self::DeprecatedWithoutReason => codes::DEPRECATED_WITHOUT_REASON,
+        }
+    }
+
+    /// Returns the rule reporting diagnostics with `code`, if any.
+    #[must_use]
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::value_variants()
+            .iter()
+            .copied()
+            .find(|rule| rule.code() == code)
+    }
+}
+
+/// Lints `files` with the `enabled` rules and writes the findings to `out`.
+///
+/// Returns 1 if any file has a finding or fails to parse.
+pub fn lint_files(
+    files: &[PathBuf],
+    enabled: &[LintRule],
+    language: Language,
+    out: &mut dyn Write,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    let mut findings = 0;
+
+    for file in files {
+        let source = SourceFile::new(file.clone(), std::fs::read_to_string(file)?);
+        let interner = Interner::new();
+        let result = parse(source.content(), &interner);
+
+        if result.diagnostics.has_errors() {
+            for diagnostic in result.diagnostics.errors() {
+                writeln!(out, "{}", diagnostic.render(&source))?;
+            }
+            findings += 1;
+            continue;
+        }
+
+        let types = TypeRegistry::new();
+        let hir = HirDatabase::new();
+        let mut diagnostics = checker::check(&result.document, &types, &hir, &interner).diagnostics;
+        diagnostics.extend(
+            check_reserved_names(&result.document, &interner, language)
+                .iter()
+                .cloned(),
+        );
+        diagnostics.sort_by_span();
+        diagnostics.dedup();
+
+        let lints = diagnostics.warnings().filter(|diagnostic| {
+            LintRule::from_code(&diagnostic.code).is_some_and(|rule| enabled.contains(&rule))
+        });
+        for diagnostic in lints {
+            writeln!(out, "{}", diagnostic.render(&source))?;
+            findings += 1;
+        }
+    }
+
+    Ok(if findings > 0 { 1 } else { 0 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_rules_enable_and_disable() {
+        let dir = std::env::temp_dir().join(format!("bgql-lint-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("schema.bgql");
+        std::fs::write(&file, "type user_profile {\n  id: ID\n}\n").unwrap();
+        let files = [file];
+
+        let mut out = Vec::new();
+        let code = lint_files(
+            &files,
+            &[LintRule::NamingConvention],
+            Language::TypeScript,
+            &mut out,
+        )
+        .unwrap();
+        assert_eq!(code, 1);
+        let report = String::from_utf8(out).unwrap();
+        assert!(report.contains("Type name `user_profile` should be PascalCase"));
+
+        let mut out = Vec::new();
+        let code = lint_files(
+            &files,
+            &[LintRule::EmptyType],
+            Language::TypeScript,
+            &mut out,
+        )
+        .unwrap();
+        assert_eq!(code, 0);
+        assert!(out.is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
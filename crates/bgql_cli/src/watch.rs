@@ -0,0 +1,204 @@
+//! File watching for `bgql watch`.
+
+use colored::Colorize;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long to wait for a burst of events to settle before running.
+pub const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// A command `bgql watch` can re-run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchCommand {
+    Check,
+    Fmt,
+}
+
+impl FromStr for WatchCommand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "check" => Ok(Self::Check),
+            "fmt" | "format" => Ok(Self::Fmt),
+            other => Err(format!(
+                "unknown watch command `{other}` (expected `check` or `fmt`)"
+            )),
+        }
+    }
+}
+
+/// Watches `path` and re-runs `command` on changed files until interrupted.
+pub fn watch(
+    path: &Path,
+    command: &str,
+    extensions: &str,
+    verbose: bool,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    let command = WatchCommand::from_str(command)?;
+    let extensions = parse_extensions(extensions);
+    let config = crate::config::load_config()?;
+    let format_options = config.format_options(None, false);
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                for path in event.paths {
+                    let _ = tx.send(path);
+                }
+            }
+        }
+    })?;
+    watcher.watch(path, RecursiveMode::Recursive)?;
+
+    println!(
+        "{} {} for changes (Ctrl-C to stop)",
+        "Watching".green().bold(),
+        path.display()
+    );
+
+    debounce_changes(&rx, &extensions, DEBOUNCE, |files| {
+        println!(
+            "[{}] {} {} file(s)",
+            timestamp(),
+            match command {
+                WatchCommand::Check => "check",
+                WatchCommand::Fmt => "fmt",
+            },
+            files.len()
+        );
+        let files: Vec<_> = files.into_iter().filter(|f| f.exists()).collect();
+        let result = match command {
+            WatchCommand::Check => crate::check_files(
+                &files,
+                config.strict(false),
+                config.max_depth(None),
+                verbose,
+            ),
+            WatchCommand::Fmt => crate::format_files(
+                &files,
+                None,
+                &format_options,
+                verbose,
+                &mut std::io::stdout(),
+            ),
+        };
+        if let Err(e) = result {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+        }
+    });
+
+    Ok(0)
+}
+
+/// Splits a comma-separated extension list, ignoring dots and blanks.
+pub fn parse_extensions(extensions: &str) -> Vec<String> {
+    extensions
+        .split(',')
+        .map(|ext| ext.trim().trim_start_matches('.').to_string())
+        .filter(|ext| !ext.is_empty())
+        .collect()
+}
+
+fn matches_extension(path: &Path, extensions: &[String]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.iter().any(|e| e == ext))
+}
+
+/// Calls `on_change` once per burst of matching paths received on `rx`.
+///
+/// A burst ends when no matching path arrives for `debounce`. Returns once
+/// the sender is dropped.
+pub fn debounce_changes(
+    rx: &Receiver<PathBuf>,
+    extensions: &[String],
+    debounce: Duration,
+    mut on_change: impl FnMut(Vec<PathBuf>),
+) {
+    while let Ok(path) = rx.recv() {
+        if !matches_extension(&path, extensions) {
+            continue;
+        }
+
+        let mut changed = vec![path];
+        let disconnected = loop {
+            match rx.recv_timeout(debounce) {
+                Ok(path) => {
+                    if matches_extension(&path, extensions) && !changed.contains(&path) {
+                        changed.push(path);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => break false,
+                Err(RecvTimeoutError::Disconnected) => break true,
+            }
+        };
+
+        on_change(changed);
+        if disconnected {
+            return;
+        }
+    }
+}
+
+/// Returns the current UTC time as `HH:MM:SS`.
+fn timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600 % 24,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watch_command_from_str() {
+        assert_eq!("check".parse(), Ok(WatchCommand::Check));
+        assert_eq!("fmt".parse(), Ok(WatchCommand::Fmt));
+        assert!("build".parse::<WatchCommand>().is_err());
+    }
+
+    #[test]
+    fn test_debounce_fires_once_per_burst() {
+        let (tx, rx) = mpsc::channel();
+        let extensions = parse_extensions("bgql, .graphql");
+
+        let writer = std::thread::spawn(move || {
+            // A single save often produces several events
+            tx.send(PathBuf::from("schema.bgql")).unwrap();
+            tx.send(PathBuf::from("notes.txt")).unwrap();
+            tx.send(PathBuf::from("schema.bgql")).unwrap();
+            tx.send(PathBuf::from("queries.graphql")).unwrap();
+            std::thread::sleep(Duration::from_millis(200));
+            tx.send(PathBuf::from("schema.bgql")).unwrap();
+        });
+
+        let mut runs = Vec::new();
+        debounce_changes(&rx, &extensions, Duration::from_millis(50), |files| {
+            runs.push(files);
+        });
+        writer.join().unwrap();
+
+        assert_eq!(
+            runs,
+            vec![
+                vec![
+                    PathBuf::from("schema.bgql"),
+                    PathBuf::from("queries.graphql")
+                ],
+                vec![PathBuf::from("schema.bgql")],
+            ]
+        );
+    }
+}
@@ -1,8 +1,9 @@
 //! Go code generator.
 
 use crate::{extract_types, CodegenOptions, TypeConverter};
-use bgql_core::Interner;
+use bgql_core::{Interner, Text};
 use bgql_syntax::{Document, FieldDefinition, InputValueDefinition, Type, TypeDefinition};
+use std::collections::HashSet;
 
 /// Go code generator.
 pub struct GoGenerator<'a> {
@@ -102,6 +103,54 @@ impl<'a> GoGenerator<'a> {
         }
 
         self.output.push_str("}\n\n");
+
+        self.write_interface_accessors(&name, obj);
+    }
+
+    /// Writes getters for the fields of the interfaces `obj` implements, so
+    /// the struct satisfies the generated Go interfaces.
+    fn write_interface_accessors(
+        &mut self,
+        name: &str,
+        obj: &bgql_syntax::ObjectTypeDefinition<'_>,
+    ) {
+        let mut implemented = HashSet::new();
+
+        for iface_name in &obj.implements {
+            let Some(iface) = self.find_interface(iface_name.value) else {
+                continue;
+            };
+            for field in &obj.fields {
+                let is_interface_field = iface
+                    .fields
+                    .iter()
+                    .any(|f| f.name.value == field.name.value);
+                if !is_interface_field || !implemented.insert(field.name.value) {
+                    continue;
+                }
+                let field_name = capitalize(&self.interner.get(field.name.value));
+                self.output.push_str(&format!(
+                    "func (o {}) Get{}() {} {{ return o.{} }}\n",
+                    name,
+                    field_name,
+                    self.convert_type(&field.ty, self.interner),
+                    field_name
+                ));
+            }
+        }
+
+        if !implemented.is_empty() {
+            self.output.push('\n');
+        }
+    }
+
+    fn find_interface(&self, name: Text) -> Option<&'a bgql_syntax::InterfaceTypeDefinition<'a>> {
+        extract_types(self.document)
+            .into_iter()
+            .find_map(|type_def| match type_def {
+                TypeDefinition::Interface(iface) if iface.name.value == name => Some(iface),
+                _ => None,
+            })
     }
 
     fn write_interface(&mut self, iface: &bgql_syntax::InterfaceTypeDefinition<'_>) {
@@ -122,7 +171,7 @@ impl<'a> GoGenerator<'a> {
             let field_name = self.interner.get(field.name.value);
             let go_type = self.convert_type(&field.ty, self.interner);
             self.output
-                .push_str(&format!("\t{}() {}\n", capitalize(&field_name), go_type));
+                .push_str(&format!("\tGet{}() {}\n", capitalize(&field_name), go_type));
         }
 
         self.output.push_str("}\n\n");
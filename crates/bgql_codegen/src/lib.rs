@@ -15,15 +15,20 @@
 //! ```
 
 mod go;
+mod lint;
 mod rust;
 mod typescript;
 
 pub use go::GoGenerator;
+pub use lint::check_reserved_names;
 pub use rust::RustGenerator;
 pub use typescript::TypeScriptGenerator;
 
 use bgql_core::Interner;
-use bgql_syntax::{Definition, Document, OperationDefinition, Type, TypeDefinition};
+use bgql_syntax::{
+    merge_extensions, Definition, Document, FieldDefinition, FieldSelection, OperationDefinition,
+    OperationType, Selection, Type, TypeDefinition,
+};
 
 /// Target language for code generation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -46,6 +51,8 @@ pub struct CodegenOptions {
     pub package_name: String,
     /// Add runtime imports.
     pub include_runtime: bool,
+    /// Rust type that custom scalars alias to. Defaults to `String`.
+    pub scalar_type: Option<String>,
 }
 
 impl Default for CodegenOptions {
@@ -56,6 +63,7 @@ impl Default for CodegenOptions {
             operations: true,
             package_name: "generated".to_string(),
             include_runtime: true,
+            scalar_type: None,
         }
     }
 }
@@ -92,16 +100,21 @@ impl<'a> CodeGenerator<'a> {
 
     /// Generates code for the specified language.
     pub fn generate(&self, language: Language) -> String {
+        // Extended types are generated with their extensions. Extensions
+        // without a target are left to the checker to report.
+        let (definitions, _) = merge_extensions(&self.document.definitions);
+        let document = Document {
+            definitions,
+            span: self.document.span,
+        };
         match language {
             Language::TypeScript => {
-                TypeScriptGenerator::new(self.document, self.interner, &self.options).generate()
+                TypeScriptGenerator::new(&document, self.interner, &self.options).generate()
             }
             Language::Rust => {
-                RustGenerator::new(self.document, self.interner, &self.options).generate()
-            }
-            Language::Go => {
-                GoGenerator::new(self.document, self.interner, &self.options).generate()
+                RustGenerator::new(&document, self.interner, &self.options).generate()
             }
+            Language::Go => GoGenerator::new(&document, self.interner, &self.options).generate(),
         }
     }
 
@@ -134,7 +147,6 @@ pub(crate) fn extract_types<'a>(document: &'a Document<'a>) -> Vec<&'a TypeDefin
 }
 
 /// Extract operation definitions from document.
-#[allow(dead_code)]
 pub(crate) fn extract_operations<'a>(
     document: &'a Document<'a>,
 ) -> Vec<&'a OperationDefinition<'a>> {
@@ -151,6 +163,146 @@ pub(crate) fn extract_operations<'a>(
         .collect()
 }
 
+/// Returns the root type name an operation selects from.
+pub(crate) fn root_type_name(operation: OperationType) -> &'static str {
+    match operation {
+        OperationType::Query => "Query",
+        OperationType::Mutation => "Mutation",
+        OperationType::Subscription => "Subscription",
+    }
+}
+
+/// Finds a field definition on an object or interface type.
+pub(crate) fn find_field<'a>(
+    document: &'a Document<'a>,
+    interner: &Interner,
+    type_name: &str,
+    field_name: &str,
+) -> Option<&'a FieldDefinition<'a>> {
+    let fields = extract_types(document)
+        .into_iter()
+        .find_map(|type_def| match type_def {
+            TypeDefinition::Object(obj) if interner.get(obj.name.value) == type_name => {
+                Some(&obj.fields)
+            }
+            TypeDefinition::Interface(iface) if interner.get(iface.name.value) == type_name => {
+                Some(&iface.fields)
+            }
+            _ => None,
+        })?;
+    fields
+        .iter()
+        .find(|field| interner.get(field.name.value) == field_name)
+}
+
+/// Returns the named type at the core of `Option`/`List` wrappers.
+pub(crate) fn named_type(ty: &Type<'_>, interner: &Interner) -> Option<String> {
+    match ty {
+        Type::Named(named) => Some(interner.get(named.name)),
+        Type::Option(inner, _) | Type::List(inner, _) => named_type(inner, interner),
+        Type::Generic(generic) => Some(interner.get(generic.name)),
+        _ => None,
+    }
+}
+
+/// A field selection with the type condition it was selected under.
+pub(crate) struct SelectedField<'a> {
+    pub field: &'a FieldSelection<'a>,
+    /// Type condition of the enclosing fragment, if any.
+    pub type_condition: Option<String>,
+}
+
+impl SelectedField<'_> {
+    /// Returns the type the field is resolved against.
+    pub fn parent_type<'t>(&'t self, parent: &'t str) -> &'t str {
+        self.type_condition.as_deref().unwrap_or(parent)
+    }
+}
+
+/// Flattens a selection set into its field selections.
+///
+/// Inline fragments and fragment spreads defined in the document are merged
+/// in place. Fields are deduplicated by response key, keeping the first.
+pub(crate) fn collect_fields<'a>(
+    document: &'a Document<'a>,
+    interner: &Interner,
+    selections: &'a [Selection<'a>],
+) -> Vec<SelectedField<'a>> {
+    fn walk<'a>(
+        document: &'a Document<'a>,
+        interner: &Interner,
+        selections: &'a [Selection<'a>],
+        type_condition: Option<&str>,
+        visited: &mut Vec<String>,
+        fields: &mut Vec<SelectedField<'a>>,
+    ) {
+        for selection in selections {
+            match selection {
+                Selection::Field(field) => {
+                    let key = field.alias.as_ref().unwrap_or(&field.name).value;
+                    if !fields
+                        .iter()
+                        .any(|f| f.field.alias.as_ref().unwrap_or(&f.field.name).value == key)
+                    {
+                        fields.push(SelectedField {
+                            field,
+                            type_condition: type_condition.map(str::to_string),
+                        });
+                    }
+                }
+                Selection::InlineFragment(inline) => {
+                    let condition = inline.type_condition.map(|name| interner.get(name.value));
+                    walk(
+                        document,
+                        interner,
+                        &inline.selection_set.selections,
+                        condition.as_deref().or(type_condition),
+                        visited,
+                        fields,
+                    );
+                }
+                Selection::FragmentSpread(spread) => {
+                    let name = interner.get(spread.name.value);
+                    if visited.contains(&name) {
+                        continue;
+                    }
+                    let fragment = document.definitions.iter().find_map(|def| match def {
+                        Definition::Fragment(fragment)
+                            if interner.get(fragment.name.value) == name =>
+                        {
+                            Some(fragment)
+                        }
+                        _ => None,
+                    });
+                    if let Some(fragment) = fragment {
+                        visited.push(name);
+                        let condition = interner.get(fragment.type_condition.value);
+                        walk(
+                            document,
+                            interner,
+                            &fragment.selection_set.selections,
+                            Some(&condition),
+                            visited,
+                            fields,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    let mut fields = Vec::new();
+    walk(
+        document,
+        interner,
+        selections,
+        None,
+        &mut Vec::new(),
+        &mut fields,
+    );
+    fields
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -162,4 +314,176 @@ mod tests {
         assert!(options.server);
         assert!(options.operations);
     }
+
+    const OPERATION_SOURCE: &str = r#"
+        type User {
+            id: ID
+            name: String
+            friends: List<User>
+        }
+
+        type Query {
+            user(id: ID): Option<User>
+        }
+
+        fragment UserName on User {
+            name
+        }
+
+        query GetUser($id: ID) {
+            user(id: $id) {
+                id
+                ...UserName
+                friends {
+                    id
+                }
+            }
+        }
+    "#;
+
+    #[test]
+    fn test_rust_operation_types() {
+        let interner = Interner::new();
+        let result = bgql_syntax::parse(OPERATION_SOURCE, &interner);
+        assert!(!result.diagnostics.has_errors());
+        let output = CodeGenerator::new(&result.document, &interner).generate(Language::Rust);
+
+        assert!(output.contains("pub struct GetUserVariables {\n    pub id: String,\n}\n"));
+        assert!(output.contains("pub struct GetUserDataUserFriends {\n    pub id: String,\n}\n"));
+        assert!(output.contains(
+            "pub struct GetUserDataUser {\n    pub id: String,\n    pub name: String,\n    pub friends: Vec<GetUserDataUserFriends>,\n}\n"
+        ));
+        assert!(output
+            .contains("pub struct GetUserData {\n    pub user: Option<GetUserDataUser>,\n}\n"));
+    }
+
+    #[test]
+    fn test_typescript_operation_types() {
+        let interner = Interner::new();
+        let result = bgql_syntax::parse(OPERATION_SOURCE, &interner);
+        assert!(!result.diagnostics.has_errors());
+        let output = CodeGenerator::new(&result.document, &interner).generate(Language::TypeScript);
+
+        assert!(
+            output.contains("export interface GetUserQueryVariables {\n  readonly id: string;\n}")
+        );
+        assert!(output.contains(
+            "export interface GetUserQuery {\n  readonly user: {\n    readonly id: string;\n    readonly name: string;\n    readonly friends: ReadonlyArray<{\n      readonly id: string;\n    }>;\n  } | null;\n}"
+        ));
+    }
+
+    fn generate(source: &str, language: Language, options: CodegenOptions) -> String {
+        let interner = Interner::new();
+        let result = bgql_syntax::parse(source, &interner);
+        assert!(!result.diagnostics.has_errors());
+        CodeGenerator::with_options(&result.document, &interner, options).generate(language)
+    }
+
+    #[test]
+    fn test_rust_input_type_and_custom_scalar() {
+        let source = r#"
+            scalar Url
+
+            input CreateUserInput {
+                name: String
+                tags: List<String>
+                website: Option<Url>
+            }
+        "#;
+
+        let output = generate(source, Language::Rust, CodegenOptions::default());
+        assert!(output.contains("pub struct CreateUserInput {\n"));
+        assert!(output.contains("    pub name: String,\n"));
+        assert!(output.contains("    pub tags: Vec<String>,\n"));
+        assert!(output.contains("    pub website: Option<Url>,\n"));
+        assert!(output.contains("pub type Url = String;\n"));
+
+        let output = generate(
+            source,
+            Language::Rust,
+            CodegenOptions {
+                scalar_type: Some("url::Url".to_string()),
+                ..CodegenOptions::default()
+            },
+        );
+        assert!(output.contains("pub type Url = url::Url;\n"));
+    }
+
+    #[test]
+    fn test_extended_types() {
+        let output = generate(
+            r#"
+            type User { id: ID }
+            extend type User { name: String }
+        "#,
+            Language::TypeScript,
+            CodegenOptions::default(),
+        );
+
+        assert!(output.contains("readonly name: string;"), "{output}");
+        assert_eq!(output.matches("export interface User ").count(), 1);
+    }
+
+    #[test]
+    fn test_rust_enum_with_data() {
+        let output = generate(
+            r#"
+            enum Shape {
+                Point
+                Circle(Float)
+                Rect { width: Float, height: Float }
+            }
+        "#,
+            Language::Rust,
+            CodegenOptions::default(),
+        );
+
+        assert!(output.contains("pub enum Shape {\n"));
+        assert!(output.contains("    Point,\n"));
+        assert!(output.contains("    Circle(f64),\n"));
+        assert!(output.contains("    Rect {\n        width: f64,\n        height: f64,\n    },\n"));
+    }
+
+    #[test]
+    fn test_go_union() {
+        let output = generate(
+            r#"
+            type User { id: ID }
+            type Post { title: String }
+            union SearchResult = User | Post
+        "#,
+            Language::Go,
+            CodegenOptions::default(),
+        );
+
+        assert!(output.contains("type SearchResult interface {\n\tisSearchResult()\n}\n"));
+        assert!(output.contains("func (User) isSearchResult() {}\n"));
+        assert!(output.contains("func (Post) isSearchResult() {}\n"));
+    }
+
+    #[test]
+    fn test_go_interface_accessors() {
+        let output = generate(
+            r#"
+            interface Node {
+                id: ID
+                parent: Option<Node>
+            }
+            type User implements Node {
+                id: ID
+                parent: Option<Node>
+                tags: List<String>
+            }
+        "#,
+            Language::Go,
+            CodegenOptions::default(),
+        );
+
+        assert!(
+            output.contains("type Node interface {\n\tGetId() string\n\tGetParent() *Node\n}\n")
+        );
+        assert!(output.contains("func (o User) GetId() string { return o.Id }\n"));
+        assert!(output.contains("func (o User) GetParent() *Node { return o.Parent }\n"));
+        assert!(!output.contains("GetTags"));
+    }
 }
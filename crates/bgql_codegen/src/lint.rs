@@ -0,0 +1,292 @@
+//! Lints for names that break generated code.
+//!
+//! Type names are emitted as bare identifiers, so a name that is a keyword in
+//! the target language produces code that does not compile. Field names and
+//! enum values are only bare identifiers in Rust: TypeScript emits them as
+//! property names and Go capitalizes or prefixes them.
+
+use crate::Language;
+use bgql_core::diagnostics::codes;
+use bgql_core::{DiagnosticBag, Interner};
+use bgql_syntax::{
+    Definition, Document, FieldDefinition, InputValueDefinition, Name, TypeDefinition,
+};
+
+const TYPESCRIPT_KEYWORDS: &[&str] = &[
+    "any",
+    "as",
+    "await",
+    "bigint",
+    "boolean",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "declare",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "never",
+    "new",
+    "null",
+    "number",
+    "object",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "string",
+    "super",
+    "switch",
+    "symbol",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "type",
+    "typeof",
+    "undefined",
+    "unknown",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+const GO_KEYWORDS: &[&str] = &[
+    "break",
+    "case",
+    "chan",
+    "const",
+    "continue",
+    "default",
+    "defer",
+    "else",
+    "fallthrough",
+    "for",
+    "func",
+    "go",
+    "goto",
+    "if",
+    "import",
+    "interface",
+    "map",
+    "package",
+    "range",
+    "return",
+    "select",
+    "struct",
+    "switch",
+    "type",
+    "var",
+];
+
+impl Language {
+    /// Returns the language's display name.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::TypeScript => "TypeScript",
+            Self::Rust => "Rust",
+            Self::Go => "Go",
+        }
+    }
+
+    /// Returns true if `name` is a reserved word in the language.
+    #[must_use]
+    pub fn is_reserved(self, name: &str) -> bool {
+        let keywords = match self {
+            Self::TypeScript => TYPESCRIPT_KEYWORDS,
+            Self::Rust => RUST_KEYWORDS,
+            Self::Go => GO_KEYWORDS,
+        };
+        keywords.contains(&name)
+    }
+
+    /// Returns true if field names and enum values are emitted as bare
+    /// identifiers, where a reserved word doesn't compile.
+    #[must_use]
+    pub const fn has_bare_members(self) -> bool {
+        matches!(self, Self::Rust)
+    }
+}
+
+/// Warns about type names, and in languages that emit them as bare
+/// identifiers field names and enum values, that are reserved words in
+/// `language`.
+#[must_use]
+pub fn check_reserved_names(
+    document: &Document<'_>,
+    interner: &Interner,
+    language: Language,
+) -> DiagnosticBag {
+    let mut lint = ReservedNameLint {
+        interner,
+        language,
+        diagnostics: DiagnosticBag::new(),
+    };
+
+    for def in &document.definitions {
+        if let Definition::Type(type_def) = def {
+            lint.check_type(type_def);
+        }
+    }
+
+    lint.diagnostics
+}
+
+struct ReservedNameLint<'a> {
+    interner: &'a Interner,
+    language: Language,
+    diagnostics: DiagnosticBag,
+}
+
+impl ReservedNameLint<'_> {
+    fn check_type(&mut self, type_def: &TypeDefinition<'_>) {
+        match type_def {
+            TypeDefinition::Object(obj) => {
+                self.check_name("Type", &obj.name);
+                self.check_fields(&obj.fields);
+            }
+            TypeDefinition::Interface(iface) => {
+                self.check_name("Type", &iface.name);
+                self.check_fields(&iface.fields);
+            }
+            TypeDefinition::Input(input) => {
+                self.check_name("Type", &input.name);
+                self.check_input_fields(&input.fields);
+            }
+            TypeDefinition::Enum(e) => {
+                self.check_name("Type", &e.name);
+                for value in &e.values {
+                    self.check_member("Enum value", &value.name);
+                }
+            }
+            TypeDefinition::InputEnum(ie) => {
+                self.check_name("Type", &ie.name);
+                for variant in &ie.variants {
+                    self.check_member("Enum value", &variant.name);
+                    if let Some(fields) = &variant.fields {
+                        self.check_input_fields(fields);
+                    }
+                }
+            }
+            TypeDefinition::Union(u) => self.check_name("Type", &u.name),
+            TypeDefinition::Scalar(s) => self.check_name("Type", &s.name),
+            TypeDefinition::Opaque(o) => self.check_name("Type", &o.name),
+            TypeDefinition::TypeAlias(a) => self.check_name("Type", &a.name),
+            TypeDefinition::InputUnion(iu) => self.check_name("Type", &iu.name),
+        }
+    }
+
+    fn check_fields(&mut self, fields: &[FieldDefinition<'_>]) {
+        for field in fields {
+            self.check_member("Field", &field.name);
+        }
+    }
+
+    fn check_input_fields(&mut self, fields: &[InputValueDefinition<'_>]) {
+        for field in fields {
+            self.check_member("Field", &field.name);
+        }
+    }
+
+    fn check_member(&mut self, kind: &str, name: &Name) {
+        if self.language.has_bare_members() {
+            self.check_name(kind, name);
+        }
+    }
+
+    fn check_name(&mut self, kind: &str, name: &Name) {
+        let value = self.interner.get(name.value);
+        if self.language.is_reserved(&value) {
+            let language = self.language.name();
+            self.diagnostics.warning(
+                codes::RESERVED_WORD,
+                format!("{kind} `{value}` is a reserved word in {language}"),
+                name.span,
+                format!("rename `{value}`; the generated {language} code will not compile"),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(source: &str, language: Language) -> Vec<String> {
+        let interner = Interner::new();
+        let result = bgql_syntax::parse(source, &interner);
+        assert!(!result.diagnostics.has_errors());
+        check_reserved_names(&result.document, &interner, language)
+            .iter()
+            .map(|d| d.title.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_reserved_type_name_typescript() {
+        // Enum values and fields are property names in TypeScript
+        let source = r#"
+            scalar symbol
+            enum Modifier { class public MATCH }
+            type Query {
+                default: String
+            }
+        "#;
+        assert_eq!(
+            lint(source, Language::TypeScript),
+            ["Type `symbol` is a reserved word in TypeScript"]
+        );
+        assert!(lint(source, Language::Go).is_empty());
+    }
+
+    #[test]
+    fn test_reserved_names_rust() {
+        let source = r#"
+            enum Mode { match strict }
+            type Query {
+                impl: String
+                name: String
+            }
+        "#;
+        assert_eq!(
+            lint(source, Language::Rust),
+            [
+                "Enum value `match` is a reserved word in Rust",
+                "Field `impl` is a reserved word in Rust",
+            ]
+        );
+        assert!(lint(source, Language::TypeScript).is_empty());
+    }
+}
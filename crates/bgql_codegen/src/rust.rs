@@ -1,8 +1,14 @@
 //! Rust code generator.
 
-use crate::{extract_types, CodegenOptions, TypeConverter};
+use crate::{
+    collect_fields, extract_operations, extract_types, find_field, named_type, root_type_name,
+    CodegenOptions, TypeConverter,
+};
 use bgql_core::Interner;
-use bgql_syntax::{Document, FieldDefinition, InputValueDefinition, Type, TypeDefinition};
+use bgql_syntax::{
+    Document, FieldDefinition, InputValueDefinition, OperationDefinition, Selection, Type,
+    TypeDefinition,
+};
 
 /// Rust code generator.
 pub struct RustGenerator<'a> {
@@ -31,6 +37,10 @@ impl<'a> RustGenerator<'a> {
         self.write_imports();
         self.write_types();
 
+        if self.options.operations {
+            self.write_operations();
+        }
+
         if self.options.client {
             self.write_client_sdk();
         }
@@ -241,9 +251,10 @@ impl<'a> RustGenerator<'a> {
                 .push_str(&format!("/// {}\n", desc.value.replace('\n', "\n/// ")));
         }
 
-        // Custom scalars are type aliases to serde_json::Value
+        // Custom scalars are type aliases to String unless configured
+        let target = self.options.scalar_type.as_deref().unwrap_or("String");
         self.output
-            .push_str(&format!("pub type {} = serde_json::Value;\n\n", name));
+            .push_str(&format!("pub type {} = {};\n\n", name, target));
     }
 
     fn write_newtype(&mut self, o: &bgql_syntax::OpaqueTypeDefinition<'_>) {
@@ -392,6 +403,128 @@ impl<'a> RustGenerator<'a> {
             .push_str(&format!("    pub {}: {},\n", snake_name, rust_type));
     }
 
+    fn write_operations(&mut self) {
+        let operations: Vec<_> = extract_operations(self.document)
+            .into_iter()
+            .filter(|op| op.name.is_some())
+            .collect();
+        if operations.is_empty() {
+            return;
+        }
+
+        self.output.push_str(
+            "// =============================================================================\n",
+        );
+        self.output.push_str("// Operations\n");
+        self.output.push_str(
+            "// =============================================================================\n\n",
+        );
+
+        for operation in operations {
+            self.write_operation(operation);
+        }
+    }
+
+    fn write_operation(&mut self, operation: &OperationDefinition<'_>) {
+        let Some(name) = &operation.name else {
+            return;
+        };
+        let name = to_pascal_case(&self.interner.get(name.value));
+
+        // Variables struct
+        self.output
+            .push_str("#[derive(Debug, Clone, Serialize, Deserialize)]\n");
+        self.output
+            .push_str(&format!("pub struct {}Variables {{\n", name));
+        for var in &operation.variables {
+            let var_name = self.interner.get(var.name.value);
+            let snake_name = to_snake_case(&var_name);
+            if snake_name != var_name {
+                self.output
+                    .push_str(&format!("    #[serde(rename = \"{}\")]\n", var_name));
+            }
+            if matches!(&var.ty, Type::Option(_, _)) {
+                self.output
+                    .push_str("    #[serde(skip_serializing_if = \"Option::is_none\")]\n");
+            }
+            self.output.push_str(&format!(
+                "    pub {}: {},\n",
+                snake_name,
+                self.convert_type(&var.ty, self.interner)
+            ));
+        }
+        self.output.push_str("}\n\n");
+
+        // Data struct, typed against the root type
+        self.write_selection_struct(
+            &format!("{}Data", name),
+            root_type_name(operation.operation),
+            &operation.selection_set.selections,
+        );
+    }
+
+    /// Writes a struct for a selection set. Nested selections get their own
+    /// struct named after the parent struct and field, written first.
+    fn write_selection_struct(
+        &mut self,
+        struct_name: &str,
+        parent_type: &str,
+        selections: &[Selection<'_>],
+    ) {
+        let mut fields = Vec::new();
+
+        for selected in collect_fields(self.document, self.interner, selections) {
+            let field = selected.field;
+            let field_name = self.interner.get(field.name.value);
+            let response_key = match &field.alias {
+                Some(alias) => self.interner.get(alias.value),
+                None => field_name.clone(),
+            };
+            let parent_type = selected.parent_type(parent_type);
+
+            let rust_type = if field_name == "__typename" {
+                "String".to_string()
+            } else {
+                match find_field(self.document, self.interner, parent_type, &field_name) {
+                    Some(field_def) => match &field.selection_set {
+                        Some(selection_set) => {
+                            let child_struct =
+                                format!("{}{}", struct_name, to_pascal_case(&response_key));
+                            let child_type =
+                                named_type(&field_def.ty, self.interner).unwrap_or_default();
+                            self.write_selection_struct(
+                                &child_struct,
+                                &child_type,
+                                &selection_set.selections,
+                            );
+                            wrap_rust_type(&field_def.ty, child_struct)
+                        }
+                        None => self.convert_type(&field_def.ty, self.interner),
+                    },
+                    // Field isn't in this document's schema
+                    None => "serde_json::Value".to_string(),
+                }
+            };
+
+            fields.push((response_key, rust_type));
+        }
+
+        self.output
+            .push_str("#[derive(Debug, Clone, Serialize, Deserialize)]\n");
+        self.output
+            .push_str(&format!("pub struct {} {{\n", struct_name));
+        for (response_key, rust_type) in fields {
+            let snake_name = to_snake_case(&response_key);
+            if snake_name != response_key {
+                self.output
+                    .push_str(&format!("    #[serde(rename = \"{}\")]\n", response_key));
+            }
+            self.output
+                .push_str(&format!("    pub {}: {},\n", snake_name, rust_type));
+        }
+        self.output.push_str("}\n\n");
+    }
+
     fn write_client_sdk(&mut self) {
         self.output.push_str(
             "// =============================================================================\n",
@@ -561,7 +694,6 @@ impl<'a> RustGenerator<'a> {
 
         for field in &obj.fields {
             let name = self.interner.get(field.name.value);
-            let return_type = self.convert_type(&field.ty, self.interner);
 
             let args_param = if field.arguments.is_empty() {
                 String::new()
@@ -737,6 +869,15 @@ impl<'a> TypeConverter for RustGenerator<'a> {
     }
 }
 
+/// Wraps `name` in the `Option`/`Vec` layers of `ty`.
+fn wrap_rust_type(ty: &Type<'_>, name: String) -> String {
+    match ty {
+        Type::Option(inner, _) => format!("Option<{}>", wrap_rust_type(inner, name)),
+        Type::List(inner, _) => format!("Vec<{}>", wrap_rust_type(inner, name)),
+        _ => name,
+    }
+}
+
 fn to_snake_case(s: &str) -> String {
     let mut result = String::new();
     for (i, c) in s.chars().enumerate() {
@@ -1,7 +1,11 @@
 //! TypeScript code generator.
 
-use crate::{extract_operations, extract_types, CodegenOptions, TypeConverter};
+use crate::{
+    collect_fields, extract_operations, extract_types, find_field, named_type, root_type_name,
+    CodegenOptions, TypeConverter,
+};
 use bgql_core::Interner;
+use bgql_semantic::Deprecation;
 use bgql_syntax::{
     Directive, Document, FieldDefinition, InputValueDefinition, OperationDefinition, OperationType,
     Selection, Type, TypeDefinition,
@@ -67,8 +71,9 @@ impl<'a> TypeScriptGenerator<'a> {
         if self.options.include_runtime {
             self.output
                 .push_str("import type { Result } from '@bgql/client';\n");
-            self.output
-                .push_str("import type { BgqlClient } from '@bgql/client';\n");
+            self.output.push_str(
+                "import type { BgqlClient, ClientError, RequestOptions } from '@bgql/client';\n",
+            );
             if self.options.server {
                 self.output.push_str(
                     "import type { BaseContext, ResolverFn, DataLoader } from '@bgql/server';\n",
@@ -397,7 +402,7 @@ impl<'a> TypeScriptGenerator<'a> {
         // Write description if present
         if let Some(desc) = &obj.description {
             self.write_jsdoc_with_deprecated(
-                desc.value,
+                &desc.value,
                 is_deprecated,
                 deprecation_reason.as_deref(),
             );
@@ -441,7 +446,7 @@ impl<'a> TypeScriptGenerator<'a> {
 
         if let Some(desc) = &iface.description {
             self.write_jsdoc_with_deprecated(
-                desc.value,
+                &desc.value,
                 is_deprecated,
                 deprecation_reason.as_deref(),
             );
@@ -467,7 +472,7 @@ impl<'a> TypeScriptGenerator<'a> {
 
         if let Some(desc) = &e.description {
             self.write_jsdoc_with_deprecated(
-                desc.value,
+                &desc.value,
                 is_deprecated,
                 deprecation_reason.as_deref(),
             );
@@ -614,7 +619,7 @@ impl<'a> TypeScriptGenerator<'a> {
 
         if let Some(desc) = &u.description {
             self.write_jsdoc_with_deprecated(
-                desc.value,
+                &desc.value,
                 is_deprecated,
                 deprecation_reason.as_deref(),
             );
@@ -655,7 +660,7 @@ impl<'a> TypeScriptGenerator<'a> {
 
         if let Some(desc) = &inp.description {
             self.write_jsdoc_with_deprecated(
-                desc.value,
+                &desc.value,
                 is_deprecated,
                 deprecation_reason.as_deref(),
             );
@@ -681,7 +686,7 @@ impl<'a> TypeScriptGenerator<'a> {
 
         if let Some(desc) = &s.description {
             self.write_jsdoc_with_deprecated(
-                desc.value,
+                &desc.value,
                 is_deprecated,
                 deprecation_reason.as_deref(),
             );
@@ -699,7 +704,7 @@ impl<'a> TypeScriptGenerator<'a> {
         let underlying = self.convert_type(&o.underlying, self.interner);
 
         if let Some(desc) = &o.description {
-            self.write_jsdoc(desc.value);
+            self.write_jsdoc(&desc.value);
         }
 
         // Use branded type pattern for nominal typing
@@ -714,7 +719,7 @@ impl<'a> TypeScriptGenerator<'a> {
         let aliased = self.convert_type(&a.aliased, self.interner);
 
         if let Some(desc) = &a.description {
-            self.write_jsdoc(desc.value);
+            self.write_jsdoc(&desc.value);
         }
 
         self.output
@@ -725,7 +730,7 @@ impl<'a> TypeScriptGenerator<'a> {
         let name = self.interner.get(iu.name.value);
 
         if let Some(desc) = &iu.description {
-            self.write_jsdoc(desc.value);
+            self.write_jsdoc(&desc.value);
         }
 
         let members: Vec<_> = iu
@@ -744,7 +749,7 @@ impl<'a> TypeScriptGenerator<'a> {
         let name = self.interner.get(ie.name.value);
 
         if let Some(desc) = &ie.description {
-            self.write_jsdoc(desc.value);
+            self.write_jsdoc(&desc.value);
         }
 
         // Generate individual variant types
@@ -832,6 +837,17 @@ impl<'a> TypeScriptGenerator<'a> {
             self.output.push_str("   */\n");
         }
 
+        // Server SDKs see argumented fields as resolver signatures
+        if self.options.server && !field.arguments.is_empty() {
+            self.output.push_str(&format!(
+                "  readonly {}: (args: {}) => {};\n",
+                name,
+                self.args_type(field),
+                ts_type
+            ));
+            return;
+        }
+
         self.output.push_str(&format!(
             "  readonly {}{}: {};\n",
             name,
@@ -840,6 +856,23 @@ impl<'a> TypeScriptGenerator<'a> {
         ));
     }
 
+    fn args_type(&self, field: &FieldDefinition<'_>) -> String {
+        if field.arguments.is_empty() {
+            return "Record<string, never>".to_string();
+        }
+
+        let args: Vec<_> = field
+            .arguments
+            .iter()
+            .map(|arg| {
+                let arg_name = self.interner.get(arg.name.value);
+                let arg_type = self.convert_type(&arg.ty, self.interner);
+                format!("{}: {}", arg_name, arg_type)
+            })
+            .collect();
+        format!("{{ {} }}", args.join("; "))
+    }
+
     fn write_input_field(&mut self, field: &InputValueDefinition<'_>) {
         let name = self.interner.get(field.name.value);
         let ts_type = self.convert_type(&field.ty, self.interner);
@@ -976,59 +1009,77 @@ impl<'a> TypeScriptGenerator<'a> {
         self.output
             .push_str(&format!("export interface {} {{\n", type_name));
 
-        // Generate fields from selection set
-        for selection in &operation.selection_set.selections {
-            self.write_selection_field(selection, 1);
-        }
+        // Generate fields from selection set, typed against the root type
+        self.write_selection_fields(
+            root_type_name(operation.operation),
+            &operation.selection_set.selections,
+            1,
+        );
 
         self.output.push_str("}\n\n");
     }
 
-    fn write_selection_field(&mut self, selection: &Selection<'_>, indent: usize) {
+    fn write_selection_fields(
+        &mut self,
+        parent_type: &str,
+        selections: &[Selection<'_>],
+        indent: usize,
+    ) {
         let indent_str = "  ".repeat(indent);
 
-        match selection {
-            Selection::Field(field) => {
-                let field_name = match &field.alias {
-                    Some(alias) => self.interner.get(alias.value),
-                    None => self.interner.get(field.name.value),
-                };
+        for selected in collect_fields(self.document, self.interner, selections) {
+            let field = selected.field;
+            let field_name = self.interner.get(field.name.value);
+            let response_key = match &field.alias {
+                Some(alias) => self.interner.get(alias.value),
+                None => field_name.clone(),
+            };
+            let parent_type = selected.parent_type(parent_type);
 
-                // For now, generate as unknown type - in a real implementation,
-                // you would resolve this against the schema
-                if let Some(ref selection_set) = field.selection_set {
+            if field_name == "__typename" {
+                self.output.push_str(&format!(
+                    "{}readonly {}: string;\n",
+                    indent_str, response_key
+                ));
+                continue;
+            }
+
+            let field_def = find_field(self.document, self.interner, parent_type, &field_name);
+
+            match (&field.selection_set, field_def) {
+                (Some(selection_set), Some(field_def)) => {
+                    // Nested object: keep the field's Option/List wrappers around the literal
+                    let (prefix, suffix) = ts_type_wrappers(&field_def.ty);
+                    let child_type = named_type(&field_def.ty, self.interner).unwrap_or_default();
+                    self.output.push_str(&format!(
+                        "{}readonly {}: {}{{\n",
+                        indent_str, response_key, prefix
+                    ));
+                    self.write_selection_fields(&child_type, &selection_set.selections, indent + 1);
                     self.output
-                        .push_str(&format!("{}readonly {}: {{\n", indent_str, field_name));
-                    for sub_selection in &selection_set.selections {
-                        self.write_selection_field(sub_selection, indent + 1);
-                    }
+                        .push_str(&format!("{}}}{};\n", indent_str, suffix));
+                }
+                (Some(selection_set), None) => {
+                    self.output
+                        .push_str(&format!("{}readonly {}: {{\n", indent_str, response_key));
+                    self.write_selection_fields("", &selection_set.selections, indent + 1);
                     self.output.push_str(&format!("{}}};\n", indent_str));
-                } else {
-                    // Scalar field - type would be resolved from schema in full implementation
+                }
+                (None, Some(field_def)) => {
                     self.output.push_str(&format!(
-                        "{}readonly {}: unknown;\n",
-                        indent_str, field_name
+                        "{}readonly {}: {};\n",
+                        indent_str,
+                        response_key,
+                        self.convert_type(&field_def.ty, self.interner)
                     ));
                 }
-            }
-            Selection::FragmentSpread(spread) => {
-                let fragment_name = self.interner.get(spread.name.value);
-                self.output.push_str(&format!(
-                    "{}// Fragment spread: {}\n",
-                    indent_str, fragment_name
-                ));
-            }
-            Selection::InlineFragment(inline) => {
-                if let Some(type_condition) = &inline.type_condition {
-                    let type_name = self.interner.get(type_condition.value);
+                (None, None) => {
+                    // Field isn't in this document's schema
                     self.output.push_str(&format!(
-                        "{}// Inline fragment on {}\n",
-                        indent_str, type_name
+                        "{}readonly {}: unknown;\n",
+                        indent_str, response_key
                     ));
                 }
-                for sub_selection in &inline.selection_set.selections {
-                    self.write_selection_field(sub_selection, indent);
-                }
             }
         }
     }
@@ -1403,6 +1454,67 @@ impl<'a> TypeScriptGenerator<'a> {
 
         self.output.push_str("  };\n");
         self.output.push_str("}\n\n");
+
+        if self.options.operations {
+            self.write_operation_sdk();
+        }
+    }
+
+    /// Writes a client class with a method per named operation, built on the
+    /// typed document constants.
+    fn write_operation_sdk(&mut self) {
+        let operations: Vec<_> = extract_operations(self.document)
+            .into_iter()
+            .filter(|op| op.name.is_some() && op.operation != OperationType::Subscription)
+            .collect();
+        if operations.is_empty() {
+            return;
+        }
+
+        self.output
+            .push_str("/** Typed client with a method per operation */\n");
+        self.output.push_str("export class BgqlSdk {\n");
+        self.output
+            .push_str("  constructor(private readonly client: BgqlClient) {}\n");
+
+        for operation in operations {
+            let Some(name) = &operation.name else {
+                continue;
+            };
+            let op_name = self.interner.get(name.value);
+            let (op_type_suffix, execute_fn) = match operation.operation {
+                OperationType::Query => ("Query", "queryTyped"),
+                OperationType::Mutation => ("Mutation", "mutateTyped"),
+                OperationType::Subscription => continue,
+            };
+            let data_type_name = format!("{}{}", op_name, op_type_suffix);
+            let variables_default = if operation.variables.is_empty() {
+                " = {}"
+            } else {
+                ""
+            };
+
+            self.output.push('\n');
+            self.output.push_str(&format!(
+                "  /** {} {} */\n",
+                op_type_suffix.to_lowercase(),
+                op_name
+            ));
+            self.output.push_str(&format!(
+                "  {}(variables: {}Variables{}, options?: RequestOptions): Promise<Result<{}, ClientError>> {{\n",
+                uncapitalize(&op_name),
+                data_type_name,
+                variables_default,
+                data_type_name
+            ));
+            self.output.push_str(&format!(
+                "    return this.client.{}({}Document, variables, options);\n",
+                execute_fn, op_name
+            ));
+            self.output.push_str("  }\n");
+        }
+
+        self.output.push_str("}\n\n");
     }
 
     fn get_object_fields(&self, type_name: &str) -> Vec<(String, String, String, String)> {
@@ -1538,21 +1650,7 @@ impl<'a> TypeScriptGenerator<'a> {
                         .map(|field| {
                             let field_name = self.interner.get(field.name.value).to_string();
                             let return_type = self.convert_type(&field.ty, self.interner);
-
-                            let args_type = if field.arguments.is_empty() {
-                                "Record<string, never>".to_string()
-                            } else {
-                                let args: Vec<_> = field
-                                    .arguments
-                                    .iter()
-                                    .map(|arg| {
-                                        let arg_name = self.interner.get(arg.name.value);
-                                        let arg_type = self.convert_type(&arg.ty, self.interner);
-                                        format!("{}: {}", arg_name, arg_type)
-                                    })
-                                    .collect();
-                                format!("{{ {} }}", args.join("; "))
-                            };
+                            let args_type = self.args_type(field);
 
                             (field_name, args_type, return_type)
                         })
@@ -1603,22 +1701,8 @@ impl<'a> TypeScriptGenerator<'a> {
 
     /// Check if directives contain @deprecated and extract the reason if present.
     fn get_deprecation_info(&self, directives: &[Directive<'_>]) -> (bool, Option<String>) {
-        for directive in directives {
-            let name = self.interner.get(directive.name.value);
-            if name == "deprecated" {
-                // Look for 'reason' argument
-                for arg in &directive.arguments {
-                    let arg_name = self.interner.get(arg.name.value);
-                    if arg_name == "reason" {
-                        if let bgql_syntax::Value::String(reason, _) = &arg.value {
-                            return (true, Some(reason.clone()));
-                        }
-                    }
-                }
-                return (true, None);
-            }
-        }
-        (false, None)
+        let deprecation = Deprecation::from_directives(directives, self.interner);
+        (deprecation.is_deprecated, deprecation.deprecation_reason)
     }
 
     fn is_optional(&self, ty: &Type<'_>) -> bool {
@@ -1681,6 +1765,22 @@ impl<'a> TypeConverter for TypeScriptGenerator<'a> {
     }
 }
 
+/// Returns the text to put before and after an object literal so it matches
+/// the `Option`/`List` wrapping of `ty`.
+fn ts_type_wrappers(ty: &Type<'_>) -> (String, String) {
+    match ty {
+        Type::Option(inner, _) => {
+            let (prefix, suffix) = ts_type_wrappers(inner);
+            (prefix, format!("{} | null", suffix))
+        }
+        Type::List(inner, _) => {
+            let (prefix, suffix) = ts_type_wrappers(inner);
+            (format!("ReadonlyArray<{}", prefix), format!("{}>", suffix))
+        }
+        _ => (String::new(), String::new()),
+    }
+}
+
 fn capitalize(s: &str) -> String {
     let mut chars = s.chars();
     match chars.next() {
@@ -1689,6 +1789,14 @@ fn capitalize(s: &str) -> String {
     }
 }
 
+fn uncapitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(f) => f.to_lowercase().collect::<String>() + chars.as_str(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1939,4 +2047,80 @@ mod tests {
         assert!(expected.contains("TypedDocumentString"));
         assert!(expected.contains("string &"));
     }
+
+    fn generate(source: &str, options: &CodegenOptions) -> String {
+        let interner = Interner::new();
+        let result = bgql_syntax::parse(source, &interner);
+        assert!(!result.diagnostics.has_errors());
+        TypeScriptGenerator::new(&result.document, &interner, options).generate()
+    }
+
+    #[test]
+    fn test_argument_field_resolver_signature() {
+        let source = r#"
+            type User {
+                id: ID
+                name: String
+            }
+
+            type Query {
+                user(id: ID): Option<User>
+                users: List<User>
+            }
+        "#;
+
+        let server = generate(source, &CodegenOptions::default());
+        assert!(server.contains("  readonly user: (args: { id: string }) => User | null;\n"));
+        assert!(server.contains("  readonly users: ReadonlyArray<User>;\n"));
+        assert!(
+            server.contains("  user?: ResolverFn<Query, { id: string }, TContext, User | null>;\n")
+        );
+
+        let client = generate(
+            source,
+            &CodegenOptions {
+                server: false,
+                ..CodegenOptions::default()
+            },
+        );
+        assert!(client.contains("  readonly user?: User | null;\n"));
+    }
+
+    #[test]
+    fn test_operation_client_sdk() {
+        let source = r#"
+            type User {
+                id: ID
+                name: String
+            }
+
+            type Query {
+                user(id: ID): Option<User>
+            }
+
+            query GetUser($id: ID) {
+                user(id: $id) {
+                    name
+                }
+            }
+        "#;
+
+        let output = generate(
+            source,
+            &CodegenOptions {
+                server: false,
+                ..CodegenOptions::default()
+            },
+        );
+        assert!(output.contains("export class BgqlSdk {\n"));
+        assert!(output.contains(
+            "  getUser(variables: GetUserQueryVariables, options?: RequestOptions): Promise<Result<GetUserQuery, ClientError>> {\n"
+        ));
+        assert!(output
+            .contains("    return this.client.queryTyped(GetUserDocument, variables, options);\n"));
+        assert!(
+            output.contains("export interface GetUserQueryVariables {\n  readonly id: string;\n}")
+        );
+        assert!(output.contains("export interface GetUserQuery {\n"));
+    }
 }
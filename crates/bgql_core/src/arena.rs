@@ -5,6 +5,11 @@ use bumpalo::Bump;
 /// An arena allocator for AST nodes.
 ///
 /// Uses bumpalo for fast bump allocation.
+///
+/// Allocations borrow the arena, so none can outlive it or a
+/// [`reset`](Self::reset), which takes `&mut self`. Destructors of allocated
+/// values never run: values owning heap memory, like `String` or `Vec`, leak
+/// it when the arena is reset or dropped.
 #[derive(Debug)]
 pub struct Arena {
     bump: Bump,
@@ -37,12 +42,19 @@ impl Arena {
         self.bump.alloc(value)
     }
 
-    /// Allocates a slice in the arena.
+    /// Copies a slice into the arena.
     #[inline]
-    pub fn alloc_slice<T: Copy>(&self, slice: &[T]) -> &mut [T] {
+    pub fn alloc_slice_copy<T: Copy>(&self, slice: &[T]) -> &mut [T] {
         self.bump.alloc_slice_copy(slice)
     }
 
+    /// Copies a slice into the arena.
+    #[deprecated(note = "use `alloc_slice_copy`")]
+    #[inline]
+    pub fn alloc_slice<T: Copy>(&self, slice: &[T]) -> &mut [T] {
+        self.alloc_slice_copy(slice)
+    }
+
     /// Allocates a string in the arena.
     #[inline]
     pub fn alloc_str(&self, s: &str) -> &str {
@@ -56,6 +68,16 @@ impl Arena {
     }
 
     /// Resets the arena, deallocating all allocations.
+    ///
+    /// The memory is kept for later allocations, e.g. when re-parsing a
+    /// document. Earlier allocations must no longer be borrowed:
+    ///
+    /// ```compile_fail
+    /// let mut arena = bgql_core::Arena::new();
+    /// let value = arena.alloc(1);
+    /// arena.reset();
+    /// assert_eq!(*value, 1);
+    /// ```
     pub fn reset(&mut self) {
         self.bump.reset();
     }
@@ -82,7 +104,30 @@ mod tests {
     #[test]
     fn test_alloc_slice() {
         let arena = Arena::new();
-        let slice = arena.alloc_slice(&[1, 2, 3]);
+        let slice = arena.alloc_slice_copy(&[1, 2, 3]);
         assert_eq!(slice, &[1, 2, 3]);
     }
+
+    #[test]
+    fn test_reset_reuses_memory() {
+        let mut arena = Arena::new();
+        let allocate = |arena: &Arena| {
+            let id = arena.alloc(7u64);
+            let name = arena.alloc_str("User");
+            let spans = arena.alloc_slice_copy(&[(0u32, 4u32), (5, 9)]);
+            let flag = arena.alloc(true);
+            assert_eq!(*id, 7);
+            assert_eq!(name, "User");
+            assert_eq!(spans, &[(0, 4), (5, 9)]);
+            assert!(*flag);
+        };
+
+        allocate(&arena);
+        let allocated = arena.allocated_bytes();
+        assert!(allocated > 0);
+
+        arena.reset();
+        allocate(&arena);
+        assert_eq!(arena.allocated_bytes(), allocated);
+    }
 }
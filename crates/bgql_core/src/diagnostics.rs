@@ -1,9 +1,12 @@
 //! Diagnostic reporting for Better GraphQL.
 
+use crate::source_map::{LineCol, SourceFile};
 use crate::span::Span;
+use std::collections::HashSet;
+use std::fmt::Write;
 
-/// Diagnostic severity level.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Diagnostic severity level, ordered from most to least severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum DiagnosticSeverity {
     /// An error that prevents compilation.
     Error,
@@ -15,6 +18,19 @@ pub enum DiagnosticSeverity {
     Hint,
 }
 
+impl DiagnosticSeverity {
+    /// Returns the lowercase name, e.g. `error`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Info => "info",
+            Self::Hint => "hint",
+        }
+    }
+}
+
 /// A label attached to a diagnostic.
 #[derive(Debug, Clone)]
 pub struct Label {
@@ -34,6 +50,15 @@ impl Label {
     }
 }
 
+/// A replacement of a span of source that would fix a diagnostic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    /// The span to replace.
+    pub span: Span,
+    /// The text to put in its place.
+    pub replacement: String,
+}
+
 /// A diagnostic message.
 #[derive(Debug, Clone)]
 pub struct Diagnostic {
@@ -45,8 +70,12 @@ pub struct Diagnostic {
     pub title: String,
     /// Detailed message.
     pub message: Option<String>,
-    /// Labels pointing to source locations.
+    /// Labels pointing to source locations. The first is the primary label.
     pub labels: Vec<Label>,
+    /// Additional notes shown after the source snippets.
+    pub notes: Vec<String>,
+    /// Replacements that would fix the diagnostic.
+    pub suggestions: Vec<Suggestion>,
 }
 
 impl Diagnostic {
@@ -58,6 +87,8 @@ impl Diagnostic {
             title: title.into(),
             message: None,
             labels: Vec::new(),
+            notes: Vec::new(),
+            suggestions: Vec::new(),
         }
     }
 
@@ -69,6 +100,8 @@ impl Diagnostic {
             title: title.into(),
             message: None,
             labels: Vec::new(),
+            notes: Vec::new(),
+            suggestions: Vec::new(),
         }
     }
 
@@ -90,10 +123,125 @@ impl Diagnostic {
         self
     }
 
+    /// Adds a note shown after the source snippets.
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// Suggests replacing `span` with `replacement`.
+    pub fn with_suggestion(mut self, span: Span, replacement: impl Into<String>) -> Self {
+        self.suggestions.push(Suggestion {
+            span,
+            replacement: replacement.into(),
+        });
+        self
+    }
+
     /// Returns the primary span, if any.
     pub fn primary_span(&self) -> Option<Span> {
         self.labels.first().map(|l| l.span)
     }
+
+    /// Returns the labels after the primary one.
+    pub fn secondary_labels(&self) -> &[Label] {
+        self.labels.get(1..).unwrap_or_default()
+    }
+
+    /// Renders the diagnostic with the source lines of each label. The
+    /// primary label is underlined with carets, secondary labels with dashes:
+    ///
+    /// ```text
+    /// error[E0010]: undefined type
+    ///  --> schema.bgql:2:11
+    ///   |
+    /// 2 |   author: Usre
+    ///   |           ^^^^ unknown type `Usre`
+    /// ```
+    #[must_use]
+    pub fn render(&self, file: &SourceFile) -> String {
+        let mut out = format!(
+            "{}[{}]: {}\n",
+            self.severity.as_str(),
+            self.code,
+            self.title
+        );
+
+        let ranges: Vec<(LineCol, LineCol)> = self
+            .labels
+            .iter()
+            .map(|label| line_range(file, label.span))
+            .collect();
+        let width = ranges
+            .iter()
+            .map(|(_, end)| end.line.to_string().len())
+            .max()
+            .unwrap_or(1);
+        let gutter = " ".repeat(width);
+
+        if let Some((start, _)) = ranges.first() {
+            let _ = writeln!(
+                out,
+                "{gutter}--> {}:{}:{}",
+                file.path().display(),
+                start.line,
+                start.column
+            );
+        }
+
+        for (i, (label, (start, end))) in self.labels.iter().zip(&ranges).enumerate() {
+            let marker = if i == 0 { "^" } else { "-" };
+            let _ = writeln!(out, "{gutter} |");
+
+            for line_number in start.line..=end.line {
+                let text = file.line(line_number).unwrap_or_default();
+                let from = if line_number == start.line {
+                    start.column
+                } else {
+                    1
+                };
+                let to = if line_number == end.line {
+                    end.column
+                } else {
+                    text.chars().count() as u32 + 1
+                };
+                let markers = (to.saturating_sub(from) as usize).max(1);
+
+                let _ = writeln!(out, "{line_number:>width$} | {text}");
+                let _ = write!(
+                    out,
+                    "{gutter} | {}{}",
+                    " ".repeat(from as usize - 1),
+                    marker.repeat(markers)
+                );
+                if line_number == end.line && !label.message.is_empty() {
+                    let _ = write!(out, " {}", label.message);
+                }
+                out.push('\n');
+            }
+        }
+
+        for note in self.message.iter().chain(&self.notes) {
+            let _ = writeln!(out, "{gutter} = note: {note}");
+        }
+
+        out
+    }
+}
+
+/// Returns the first and last line/column a span covers.
+fn line_range(file: &SourceFile, span: Span) -> (LineCol, LineCol) {
+    let start = file.line_col(span.start);
+    let mut end = file.line_col(span.end);
+    // A span ending just past a newline ends on the previous line
+    if end.line > start.line && end.column == 1 {
+        let line = file.line(end.line - 1).unwrap_or_default();
+        end = LineCol {
+            line: end.line - 1,
+            column: line.chars().count() as u32 + 1,
+        };
+    }
+    (start, end)
 }
 
 /// A collection of diagnostics.
@@ -125,6 +273,22 @@ impl DiagnosticBag {
         self.add(Diagnostic::error(code, title).with_span(span, message));
     }
 
+    /// Adds an error diagnostic with several labels and notes.
+    ///
+    /// The first label is the primary one.
+    pub fn error_with_labels(
+        &mut self,
+        code: impl Into<String>,
+        title: impl Into<String>,
+        labels: impl IntoIterator<Item = Label>,
+        notes: impl IntoIterator<Item = String>,
+    ) {
+        let mut diagnostic = Diagnostic::error(code, title);
+        diagnostic.labels.extend(labels);
+        diagnostic.notes.extend(notes);
+        self.add(diagnostic);
+    }
+
     /// Adds a warning diagnostic.
     pub fn warning(
         &mut self,
@@ -183,6 +347,30 @@ impl DiagnosticBag {
     pub fn len(&self) -> usize {
         self.diagnostics.len()
     }
+
+    /// Sorts the diagnostics by the start of their primary span, then by
+    /// severity. Diagnostics without a span come last; ties keep the order
+    /// they were added in.
+    pub fn sort_by_span(&mut self) {
+        self.diagnostics.sort_by_key(|d| {
+            let start = d.primary_span().map(|span| span.start);
+            (start.is_none(), start, d.severity)
+        });
+    }
+
+    /// Removes diagnostics with the same code, primary span and title as an
+    /// earlier one.
+    pub fn dedup(&mut self) {
+        let mut seen = HashSet::new();
+        self.diagnostics
+            .retain(|d| seen.insert((d.code.clone(), d.primary_span(), d.title.clone())));
+    }
+}
+
+impl Extend<Diagnostic> for DiagnosticBag {
+    fn extend<I: IntoIterator<Item = Diagnostic>>(&mut self, diagnostics: I) {
+        self.diagnostics.extend(diagnostics);
+    }
 }
 
 /// Common diagnostic codes.
@@ -191,6 +379,9 @@ pub mod codes {
     pub const UNEXPECTED_TOKEN: &str = "E0001";
     pub const UNEXPECTED_EOF: &str = "E0002";
     pub const INVALID_SYNTAX: &str = "E0003";
+    pub const UNTERMINATED_STRING: &str = "E0004";
+    pub const INVALID_ESCAPE_SEQUENCE: &str = "E0005";
+    pub const NUMBER_OUT_OF_RANGE: &str = "E0006";
 
     // === Type Resolution Errors (E0010-E0019) ===
     pub const UNDEFINED_TYPE: &str = "E0010";
@@ -201,6 +392,8 @@ pub mod codes {
     pub const INTERFACE_FIELD_TYPE_MISMATCH: &str = "E0015";
     pub const CYCLIC_TYPE_REFERENCE: &str = "E0016";
     pub const INVALID_EXTENSION_TARGET: &str = "E0017";
+    pub const INVALID_NAME: &str = "E0018";
+    pub const INTERFACE_FIELD_EXTRA_REQUIRED_ARG: &str = "E0019";
 
     // === Type System Errors (E0020-E0029) ===
     pub const TYPE_MISMATCH: &str = "E0020";
@@ -208,11 +401,17 @@ pub mod codes {
     pub const INVALID_ARGUMENT_TYPE: &str = "E0022";
     pub const MISSING_REQUIRED_ARGUMENT: &str = "E0023";
     pub const ARITY_MISMATCH: &str = "E0024";
+    pub const CYCLIC_TYPE_ALIAS: &str = "E0025";
+    pub const DUPLICATE_INTERFACE_IMPLEMENTATION: &str = "E0026";
+    pub const INTERFACE_FIELD_ARGUMENT_MISMATCH: &str = "E0027";
+    pub const INPUT_TYPE_IN_OUTPUT_POSITION: &str = "E0028";
 
     // === Directive Errors (E0030-E0039) ===
     pub const INVALID_DIRECTIVE: &str = "E0030";
     pub const DIRECTIVE_NOT_ALLOWED: &str = "E0031";
     pub const DUPLICATE_DIRECTIVE: &str = "E0032";
+    pub const UNKNOWN_DIRECTIVE: &str = "E0033";
+    pub const UNKNOWN_ARGUMENT: &str = "E0034";
 
     // === Module Errors (E0040-E0049) ===
     pub const MODULE_NOT_FOUND: &str = "E0040";
@@ -224,12 +423,22 @@ pub mod codes {
     pub const INVALID_ROOT_TYPE: &str = "E0051";
     pub const DUPLICATE_SCHEMA: &str = "E0052";
 
+    // === Operation Errors (E0060-E0069) ===
+    pub const CONFLICTING_SELECTION: &str = "E0060";
+    pub const QUERY_TOO_DEEP: &str = "E0061";
+
     // === Warnings (W0001-W0099) ===
     pub const UNUSED_TYPE: &str = "W0001";
     pub const UNUSED_FIELD: &str = "W0002";
     pub const DEPRECATED_USAGE: &str = "W0003";
     pub const NAMING_CONVENTION: &str = "W0004";
     pub const POSSIBLE_TYPO: &str = "W0005";
+    pub const UNUSED_TYPE_PARAMETER: &str = "W0006";
+    pub const RESERVED_WORD: &str = "W0007";
+    pub const UNUSED_IMPORT: &str = "W0008";
+    pub const EMPTY_TYPE: &str = "W0009";
+    pub const DUPLICATE_UNION_MEMBER: &str = "W0010";
+    pub const DEPRECATED_WITHOUT_REASON: &str = "W0011";
 }
 
 #[cfg(test)]
@@ -245,6 +454,50 @@ mod tests {
         assert_eq!(bag.error_count(), 1);
     }
 
+    #[test]
+    fn test_sort_by_span() {
+        let mut bag = DiagnosticBag::new();
+        bag.add(Diagnostic::error("E003", "no span"));
+        bag.warning("W001", "late warning", Span::new(30, 35), "");
+        bag.error("E002", "late error", Span::new(30, 32), "");
+        bag.error("E001", "early error", Span::new(4, 8), "");
+        bag.warning("W002", "earliest warning", Span::new(0, 2), "");
+
+        bag.sort_by_span();
+        let titles: Vec<_> = bag.iter().map(|d| d.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            [
+                "earliest warning",
+                "early error",
+                "late error",
+                "late warning",
+                "no span"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dedup() {
+        let mut bag = DiagnosticBag::new();
+        bag.error("E010", "undefined type", Span::new(4, 8), "from the parser");
+        bag.error(
+            "E010",
+            "undefined type",
+            Span::new(4, 8),
+            "from the checker",
+        );
+        bag.error("E010", "undefined type", Span::new(12, 16), "");
+        bag.error("E011", "undefined type", Span::new(4, 8), "");
+        bag.add(Diagnostic::error("E050", "missing root type"));
+        bag.add(Diagnostic::error("E050", "missing root type"));
+
+        bag.dedup();
+        assert_eq!(bag.len(), 4);
+        let first = bag.iter().next().unwrap();
+        assert_eq!(first.labels[0].message, "from the parser");
+    }
+
     #[test]
     fn test_diagnostic_creation() {
         let diag = Diagnostic::error("E001", "Test")
@@ -254,4 +507,75 @@ mod tests {
         assert_eq!(diag.severity, DiagnosticSeverity::Error);
         assert_eq!(diag.primary_span(), Some(Span::new(0, 5)));
     }
+
+    fn source(content: &str) -> SourceFile {
+        SourceFile::new("schema.bgql".into(), content.to_string())
+    }
+
+    #[test]
+    fn test_render_single_line() {
+        let file = source("type Post {\n  author: Usre\n}\n");
+        let diag = Diagnostic::error("E0010", "undefined type")
+            .with_span(Span::new(22, 26), "unknown type `Usre`");
+
+        assert_eq!(
+            diag.render(&file),
+            "error[E0010]: undefined type\n\
+             \x20--> schema.bgql:2:11\n\
+             \x20 |\n\
+             2 |   author: Usre\n\
+             \x20 |           ^^^^ unknown type `Usre`\n"
+        );
+    }
+
+    #[test]
+    fn test_render_multi_line() {
+        let file = source("type User {\n  id: ID\n}\n");
+        let diag = Diagnostic::warning("W0001", "unused type")
+            .with_message("remove it or reference it")
+            .with_span(Span::new(5, 23), "never used");
+
+        let rendered = diag.render(&file);
+        let lines: Vec<_> = rendered.lines().collect();
+        assert_eq!(lines[1], " --> schema.bgql:1:6");
+        assert_eq!(lines[3], "1 | type User {");
+        assert_eq!(lines[4], "  |      ^^^^^^");
+        assert_eq!(lines[5], "2 |   id: ID");
+        assert_eq!(lines[6], "  | ^^^^^^^^");
+        assert_eq!(lines[7], "3 | }");
+        assert_eq!(lines[8], "  | ^ never used");
+        assert_eq!(lines[9], "  = note: remove it or reference it");
+    }
+
+    #[test]
+    fn test_error_with_labels() {
+        let file =
+            source("interface Node {\n  id: ID\n}\ntype User implements Node {\n  id: String\n}\n");
+        let mut bag = DiagnosticBag::new();
+        bag.error_with_labels(
+            "E0015",
+            "Field `id` has incompatible type",
+            [
+                Label::new(Span::new(58, 68), "found `String`"),
+                Label::new(Span::new(19, 25), "expected type declared here"),
+            ],
+            ["`User` implements `Node`".to_string()],
+        );
+
+        let diag = bag.iter().next().unwrap();
+        assert_eq!(diag.primary_span(), Some(Span::new(58, 68)));
+        assert_eq!(diag.secondary_labels().len(), 1);
+        assert_eq!(
+            diag.render(&file),
+            "error[E0015]: Field `id` has incompatible type\n\
+             \x20--> schema.bgql:5:3\n\
+             \x20 |\n\
+             5 |   id: String\n\
+             \x20 |   ^^^^^^^^^^ found `String`\n\
+             \x20 |\n\
+             2 |   id: ID\n\
+             \x20 |   ------ expected type declared here\n\
+             \x20 = note: `User` implements `Node`\n"
+        );
+    }
 }
@@ -5,13 +5,16 @@
 //! - `text`: String interning
 //! - `arena`: Arena allocation
 //! - `diagnostics`: Error reporting
+//! - `source_map`: Multi-file source registry
 
 pub mod arena;
 pub mod diagnostics;
+pub mod source_map;
 pub mod span;
 pub mod text;
 
 pub use arena::Arena;
-pub use diagnostics::{Diagnostic, DiagnosticBag, DiagnosticSeverity, Label};
-pub use span::Span;
+pub use diagnostics::{Diagnostic, DiagnosticBag, DiagnosticSeverity, Label, Suggestion};
+pub use source_map::{FileId, FileSpan, LineCol, SourceFile, SourceMap};
+pub use span::{ColumnUnit, LineIndex, Span};
 pub use text::{Interner, Text};
@@ -0,0 +1,222 @@
+//! Source file registry for multi-file workspaces.
+
+use crate::span::{ColumnUnit, LineIndex, Span};
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Identifier of a file registered in a [`SourceMap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FileId(u32);
+
+impl FileId {
+    /// Returns the raw index.
+    #[must_use]
+    #[inline]
+    pub const fn index(self) -> u32 {
+        self.0
+    }
+}
+
+/// A span within a specific file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FileSpan {
+    /// The file containing the span.
+    pub file: FileId,
+    /// Byte offsets within the file.
+    pub span: Span,
+}
+
+impl FileSpan {
+    /// Creates a new file span.
+    #[must_use]
+    #[inline]
+    pub const fn new(file: FileId, span: Span) -> Self {
+        Self { file, span }
+    }
+}
+
+/// A 1-based line and column. Columns count characters, not bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LineCol {
+    pub line: u32,
+    pub column: u32,
+}
+
+/// A registered source file.
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    path: PathBuf,
+    content: String,
+    lines: LineIndex,
+}
+
+impl SourceFile {
+    /// Creates a source file outside of a [`SourceMap`].
+    #[must_use]
+    pub fn new(path: PathBuf, content: String) -> Self {
+        let lines = LineIndex::new(&content);
+        Self {
+            path,
+            content,
+            lines,
+        }
+    }
+
+    /// Returns the file path.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the file content.
+    #[must_use]
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// Returns the number of lines.
+    #[must_use]
+    pub fn line_count(&self) -> usize {
+        self.lines.line_count()
+    }
+
+    /// Converts a byte offset to a line and column.
+    ///
+    /// Offsets past the end are clamped; offsets inside a UTF-8 sequence
+    /// are rounded down to the start of the character.
+    #[must_use]
+    pub fn line_col(&self, offset: u32) -> LineCol {
+        let (line, column) = self.lines.offset_to_line_col(offset, ColumnUnit::Char);
+        LineCol {
+            line: line + 1,
+            column: column + 1,
+        }
+    }
+
+    /// Returns the text of a 1-based line, without its line terminator.
+    #[must_use]
+    pub fn line(&self, line: u32) -> Option<&str> {
+        let span = self.lines.line_span(line.checked_sub(1)?)?;
+        Some(&self.content[span.start as usize..span.end as usize])
+    }
+}
+
+/// Registry of source files, addressed by [`FileId`].
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    /// Creates an empty source map.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a source and returns its id.
+    ///
+    /// Registering a path again replaces its content and keeps the same id.
+    pub fn add(&mut self, path: impl Into<PathBuf>, content: impl Into<String>) -> FileId {
+        let file = SourceFile::new(path.into(), content.into());
+        if let Some(id) = self.file_id(&file.path) {
+            self.files[id.0 as usize] = file;
+            return id;
+        }
+        self.files.push(file);
+        FileId(self.files.len() as u32 - 1)
+    }
+
+    /// Returns the file with the given id.
+    #[must_use]
+    pub fn get(&self, id: FileId) -> Option<&SourceFile> {
+        self.files.get(id.0 as usize)
+    }
+
+    /// Looks up the id of a registered path.
+    #[must_use]
+    pub fn file_id(&self, path: &Path) -> Option<FileId> {
+        self.files
+            .iter()
+            .position(|file| file.path == path)
+            .map(|index| FileId(index as u32))
+    }
+
+    /// Returns an iterator over all files and their ids.
+    pub fn files(&self) -> impl Iterator<Item = (FileId, &SourceFile)> {
+        self.files
+            .iter()
+            .enumerate()
+            .map(|(index, file)| (FileId(index as u32), file))
+    }
+
+    /// Returns the number of registered files.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns true if no files are registered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Returns the line and column of the start of a span.
+    #[must_use]
+    pub fn line_col(&self, span: FileSpan) -> Option<LineCol> {
+        Some(self.get(span.file)?.line_col(span.span.start))
+    }
+
+    /// Returns the source text covered by a span.
+    #[must_use]
+    pub fn snippet(&self, span: FileSpan) -> Option<&str> {
+        self.get(span.file)?
+            .content
+            .get(span.span.start as usize..span.span.end as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_map_two_files() {
+        let mut map = SourceMap::new();
+        let users = map.add("users.bgql", "type User {\n  id: ID\n}\n");
+        let posts = map.add("posts.bgql", "type Post {\n  author: User\n}\n");
+        assert_ne!(users, posts);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.file_id(Path::new("posts.bgql")), Some(posts));
+
+        let author = FileSpan::new(posts, Span::new(14, 20));
+        assert_eq!(map.snippet(author), Some("author"));
+        assert_eq!(map.line_col(author), Some(LineCol { line: 2, column: 3 }));
+        assert_eq!(map.get(posts).unwrap().line(2), Some("  author: User"));
+
+        let user = FileSpan::new(users, Span::new(5, 9));
+        assert_eq!(map.snippet(user), Some("User"));
+        assert_eq!(map.line_col(user), Some(LineCol { line: 1, column: 6 }));
+        assert_eq!(map.get(users).unwrap().path(), Path::new("users.bgql"));
+    }
+
+    #[test]
+    fn test_source_map_replace_and_unicode() {
+        let mut map = SourceMap::new();
+        let id = map.add("a.bgql", "old");
+        assert_eq!(map.add("a.bgql", "type Café {\n  名前: String\n}"), id);
+        assert_eq!(map.len(), 1);
+
+        let file = map.get(id).unwrap();
+        assert_eq!(file.line_count(), 3);
+        let offset = file.content().find(':').unwrap() as u32;
+        assert_eq!(file.line_col(offset), LineCol { line: 2, column: 5 });
+        assert_eq!(file.line(4), None);
+        assert_eq!(map.snippet(FileSpan::new(id, Span::new(0, 100))), None);
+    }
+}
@@ -91,6 +91,157 @@ impl From<Span> for miette::SourceSpan {
     }
 }
 
+/// The unit a [`LineIndex`] counts columns in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ColumnUnit {
+    /// UTF-8 bytes.
+    #[default]
+    Byte,
+    /// UTF-16 code units, as used by the Language Server Protocol.
+    Utf16,
+    /// Characters, as shown to users in diagnostics.
+    Char,
+}
+
+impl ColumnUnit {
+    /// Returns the columns taken by a character of `len` UTF-8 bytes.
+    fn width(self, len: u8) -> u32 {
+        match self {
+            Self::Byte => u32::from(len),
+            Self::Utf16 => utf16_len(len),
+            Self::Char => 1,
+        }
+    }
+}
+
+/// Converts between byte offsets and 0-based line/column positions of a
+/// source.
+///
+/// Built once per source; each conversion is a binary search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    /// Byte offset of the start of each line.
+    line_starts: Vec<u32>,
+    /// Byte offset of the end of each line, before its `\n` or `\r\n`.
+    line_ends: Vec<u32>,
+    /// Byte offset and UTF-8 length of each non-ASCII character.
+    wide_chars: Vec<(u32, u8)>,
+}
+
+impl LineIndex {
+    /// Indexes the lines of `source`.
+    #[must_use]
+    pub fn new(source: &str) -> Self {
+        let mut line_starts = vec![0];
+        let mut line_ends = Vec::new();
+        let mut wide_chars = Vec::new();
+        for (offset, c) in source.char_indices() {
+            let offset = offset as u32;
+            if c == '\n' {
+                let crlf = offset > 0 && source.as_bytes()[offset as usize - 1] == b'\r';
+                line_ends.push(offset - u32::from(crlf));
+                line_starts.push(offset + 1);
+            } else if !c.is_ascii() {
+                wide_chars.push((offset, c.len_utf8() as u8));
+            }
+        }
+        line_ends.push(source.len() as u32);
+        Self {
+            line_starts,
+            line_ends,
+            wide_chars,
+        }
+    }
+
+    /// Returns the number of lines.
+    #[must_use]
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Converts a byte offset to a 0-based `(line, column)`.
+    ///
+    /// Offsets past the end are clamped to the end of the source; offsets
+    /// inside a character are rounded down to its start.
+    #[must_use]
+    pub fn offset_to_line_col(&self, offset: u32, unit: ColumnUnit) -> (u32, u32) {
+        let offset = offset.min(*self.line_ends.last().unwrap_or(&0));
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = self.line_starts[line];
+        let mut column = offset - line_start;
+        if unit != ColumnUnit::Byte {
+            for &(char_offset, len) in self.wide_chars_between(line_start, offset) {
+                let width = unit.width(len);
+                let len = u32::from(len);
+                column -= match offset - char_offset {
+                    // The offset is inside the character
+                    covered if covered < len => covered,
+                    _ => len - width,
+                };
+            }
+        }
+        (line as u32, column)
+    }
+
+    /// Converts a 0-based line and column to a byte offset.
+    ///
+    /// Lines past the end map to the end of the source, and columns past the
+    /// end of a line to the end of the line. A column inside a character maps
+    /// to the start of the character.
+    #[must_use]
+    pub fn line_col_to_offset(&self, line: u32, column: u32, unit: ColumnUnit) -> u32 {
+        let Some(&line_start) = self.line_starts.get(line as usize) else {
+            return *self.line_ends.last().unwrap_or(&0);
+        };
+        let line_end = self.line_ends[line as usize];
+        if unit == ColumnUnit::Byte {
+            return (line_start + column).min(line_end);
+        }
+
+        let mut offset = line_start;
+        let mut remaining = column;
+        for &(char_offset, len) in self.wide_chars_between(line_start, line_end) {
+            // Every byte before the character is an ASCII character
+            if remaining <= char_offset - offset {
+                return offset + remaining;
+            }
+            remaining -= char_offset - offset;
+            if remaining < unit.width(len) {
+                return char_offset;
+            }
+            remaining -= unit.width(len);
+            offset = char_offset + u32::from(len);
+        }
+        (offset + remaining).min(line_end)
+    }
+
+    /// Returns the span of a 0-based line, without its line terminator.
+    #[must_use]
+    pub fn line_span(&self, line: u32) -> Option<Span> {
+        let start = *self.line_starts.get(line as usize)?;
+        Some(Span::new(start, self.line_ends[line as usize]))
+    }
+
+    /// Returns the non-ASCII characters starting in `start..end`.
+    fn wide_chars_between(&self, start: u32, end: u32) -> &[(u32, u8)] {
+        let from = self
+            .wide_chars
+            .partition_point(|&(offset, _)| offset < start);
+        let to = self.wide_chars.partition_point(|&(offset, _)| offset < end);
+        &self.wide_chars[from..to]
+    }
+}
+
+/// Returns the number of UTF-16 code units of a character of `len` UTF-8
+/// bytes.
+fn utf16_len(len: impl Into<u32>) -> u32 {
+    if len.into() == 4 {
+        2
+    } else {
+        1
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -120,4 +271,70 @@ mod tests {
         assert!(!span.contains(20));
         assert!(!span.contains(5));
     }
+
+    #[test]
+    fn test_line_index_multi_byte() {
+        let source = "type Café {\n  名前: String\n}";
+        let index = LineIndex::new(source);
+        assert_eq!(index.line_count(), 3);
+
+        let colon = source.find(':').unwrap() as u32;
+        assert_eq!(index.offset_to_line_col(colon, ColumnUnit::Byte), (1, 8));
+        assert_eq!(index.line_col_to_offset(1, 8, ColumnUnit::Byte), colon);
+
+        let brace = source.find('{').unwrap() as u32;
+        assert_eq!(index.offset_to_line_col(brace, ColumnUnit::Byte), (0, 11));
+        assert_eq!(index.offset_to_line_col(brace, ColumnUnit::Utf16), (0, 10));
+
+        // Past the end of a line or the source
+        assert_eq!(index.line_col_to_offset(0, 99, ColumnUnit::Byte), brace + 1);
+        assert_eq!(
+            index.line_col_to_offset(9, 0, ColumnUnit::Byte),
+            source.len() as u32
+        );
+        assert_eq!(index.offset_to_line_col(999, ColumnUnit::Byte), (2, 1));
+    }
+
+    #[test]
+    fn test_line_index_utf16() {
+        // `名` is one UTF-16 unit and three bytes; `🦀` is two units and four bytes
+        let source = "a\r\n名🦀: Int\r\n";
+        let index = LineIndex::new(source);
+
+        let colon = source.find(':').unwrap() as u32;
+        assert_eq!(index.offset_to_line_col(colon, ColumnUnit::Utf16), (1, 3));
+        assert_eq!(index.line_col_to_offset(1, 3, ColumnUnit::Utf16), colon);
+
+        let crab = source.find('🦀').unwrap() as u32;
+        assert_eq!(index.offset_to_line_col(crab, ColumnUnit::Utf16), (1, 1));
+        assert_eq!(index.line_col_to_offset(1, 1, ColumnUnit::Utf16), crab);
+        // Between the surrogates of `🦀`
+        assert_eq!(index.line_col_to_offset(1, 2, ColumnUnit::Utf16), crab);
+        assert_eq!(
+            index.offset_to_line_col(crab + 1, ColumnUnit::Utf16),
+            (1, 1)
+        );
+
+        // Columns stop before `\r\n`
+        assert_eq!(index.line_col_to_offset(0, 5, ColumnUnit::Utf16), 1);
+        assert_eq!(index.offset_to_line_col(1, ColumnUnit::Utf16), (0, 1));
+        assert_eq!(
+            index.line_col_to_offset(2, 0, ColumnUnit::Utf16),
+            source.len() as u32
+        );
+    }
+
+    #[test]
+    fn test_line_index_chars() {
+        let source = "a\r\n名🦀: Int\r\n";
+        let index = LineIndex::new(source);
+
+        let colon = source.find(':').unwrap() as u32;
+        assert_eq!(index.offset_to_line_col(colon, ColumnUnit::Char), (1, 2));
+        assert_eq!(index.line_col_to_offset(1, 2, ColumnUnit::Char), colon);
+
+        assert_eq!(index.line_span(0), Some(Span::new(0, 1)));
+        assert_eq!(index.line_span(1), Some(Span::new(3, colon + 5)));
+        assert_eq!(index.line_span(3), None);
+    }
 }
@@ -1,9 +1,12 @@
 //! String interning for Better GraphQL.
 
 use rustc_hash::FxHashMap;
-use std::cell::RefCell;
+use std::cell::{Cell, OnceCell, RefCell};
 
 /// An interned text identifier.
+///
+/// An [`Interner`] gives equal strings equal identifiers, so texts from the
+/// same interner compare as their strings do.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Text(u32);
 
@@ -21,13 +24,28 @@ impl Text {
     }
 }
 
+/// Number of strings in the first chunk of an [`Interner`]. Each further
+/// chunk is twice as large as the one before.
+const FIRST_CHUNK_LEN: usize = 64;
+
+/// Number of chunks, enough for every [`Text`] index.
+const CHUNK_COUNT: usize = 27;
+
+/// A fixed-size run of string slots, each set once.
+type Chunk = Box<[OnceCell<Box<str>>]>;
+
 /// A string interner that deduplicates strings.
+///
+/// Strings are stored in chunks that are allocated once and never move, so
+/// [`Interner::resolve`] can lend them out while more strings are interned.
 #[derive(Debug)]
 pub struct Interner {
     /// Map from string to index.
     map: RefCell<FxHashMap<String, Text>>,
-    /// Stored strings.
-    strings: RefCell<Vec<String>>,
+    /// Stored strings, by index.
+    chunks: [OnceCell<Chunk>; CHUNK_COUNT],
+    /// Number of stored strings.
+    len: Cell<u32>,
 }
 
 impl Default for Interner {
@@ -42,7 +60,8 @@ impl Interner {
     pub fn new() -> Self {
         let interner = Self {
             map: RefCell::new(FxHashMap::default()),
-            strings: RefCell::new(Vec::new()),
+            chunks: std::array::from_fn(|_| OnceCell::new()),
+            len: Cell::new(0),
         };
 
         // Pre-register built-in scalars and keywords
@@ -82,36 +101,76 @@ impl Interner {
     }
 
     /// Interns a string, returning its identifier.
+    ///
+    /// Interning an identical string again returns the same identifier.
     pub fn intern(&self, s: &str) -> Text {
         let mut map = self.map.borrow_mut();
         if let Some(&id) = map.get(s) {
             return id;
         }
 
-        let mut strings = self.strings.borrow_mut();
-        let id = Text(strings.len() as u32);
-        strings.push(s.to_string());
+        let id = Text(self.len.get());
+        let (chunk, slot) = Self::locate(id);
+        let chunk = self.chunks[chunk].get_or_init(|| {
+            (0..FIRST_CHUNK_LEN << chunk)
+                .map(|_| OnceCell::new())
+                .collect()
+        });
+        let _ = chunk[slot].set(s.into());
+        self.len.set(id.0 + 1);
         map.insert(s.to_string(), id);
         id
     }
 
+    /// Returns the string for an identifier, borrowed from the interner.
+    ///
+    /// Identifiers from another interner resolve to an empty string.
+    #[must_use]
+    pub fn resolve(&self, id: Text) -> &str {
+        let (chunk, slot) = Self::locate(id);
+        self.chunks
+            .get(chunk)
+            .and_then(OnceCell::get)
+            .and_then(|chunk| chunk[slot].get())
+            .map_or("", |s| s)
+    }
+
     /// Gets the string for an identifier.
+    ///
+    /// Allocates a copy; prefer [`Interner::resolve`].
     #[must_use]
     pub fn get(&self, id: Text) -> String {
-        let strings = self.strings.borrow();
-        strings.get(id.0 as usize).cloned().unwrap_or_default()
+        self.resolve(id).to_string()
     }
 
     /// Returns the number of interned strings.
     #[must_use]
     pub fn len(&self) -> usize {
-        self.strings.borrow().len()
+        self.len.get() as usize
     }
 
     /// Returns true if no strings are interned.
     #[must_use]
     pub fn is_empty(&self) -> bool {
-        self.strings.borrow().is_empty()
+        self.len.get() == 0
+    }
+
+    /// Iterates over the interned strings with their identifiers, in the
+    /// order they were interned.
+    pub fn iter(&self) -> impl Iterator<Item = (Text, &str)> {
+        (0..self.len.get()).map(|index| {
+            let id = Text(index);
+            (id, self.resolve(id))
+        })
+    }
+
+    /// Returns the chunk holding `id` and its slot in the chunk.
+    fn locate(id: Text) -> (usize, usize) {
+        // Chunk `k` starts at index `FIRST_CHUNK_LEN * (2^k - 1)`
+        let n = id.0 as usize / FIRST_CHUNK_LEN + 1;
+        let chunk = n.ilog2() as usize;
+        let start = FIRST_CHUNK_LEN * ((1 << chunk) - 1);
+        (chunk, id.0 as usize - start)
     }
 }
 
@@ -137,6 +196,45 @@ mod tests {
         assert_eq!(interner.get(id), "test");
     }
 
+    #[test]
+    fn test_resolve() {
+        let interner = Interner::new();
+        let hello = interner.intern("hello");
+        let resolved = interner.resolve(hello);
+
+        // Borrowed strings stay valid while more strings are interned
+        let ids: Vec<_> = (0..1000)
+            .map(|i| interner.intern(&format!("s{i}")))
+            .collect();
+        assert_eq!(resolved, "hello");
+        assert_eq!(interner.intern("hello"), hello);
+        for (i, id) in ids.iter().enumerate() {
+            assert_eq!(interner.resolve(*id), format!("s{i}"));
+            assert_eq!(interner.intern(&format!("s{i}")), *id);
+        }
+        assert_eq!(interner.resolve(Text::from_raw(u32::MAX)), "");
+    }
+
+    #[test]
+    fn test_iter() {
+        let interner = Interner::new();
+        let keywords = interner.len();
+        let user = interner.intern("User");
+        assert_eq!(interner.intern("User"), user);
+        interner.intern("Post");
+        interner.intern("User");
+        assert_eq!(interner.len(), keywords + 2);
+
+        let symbols: Vec<_> = interner.iter().skip(keywords).collect();
+        assert_eq!(symbols, [(user, "User"), (Text(user.0 + 1), "Post")]);
+
+        let mut all: Vec<_> = interner.iter().map(|(_, s)| s).collect();
+        all.sort_unstable();
+        all.dedup();
+        assert_eq!(all.len(), interner.len());
+        assert!(interner.iter().all(|(id, s)| interner.intern(s) == id));
+    }
+
     #[test]
     fn test_builtin_keywords() {
         let interner = Interner::new();
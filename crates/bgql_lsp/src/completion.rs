@@ -1,11 +1,31 @@
 //! Completion provider.
 
-use bgql_core::Interner;
+use bgql_core::{Interner, LineIndex};
+use bgql_semantic::DeprecationIndex;
 use bgql_syntax::{Definition, Document, TypeDefinition};
-use tower_lsp::lsp_types::{CompletionItem, CompletionItemKind, InsertTextFormat, Position};
+use tower_lsp::lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionItemTag, CompletionTextEdit, Documentation,
+    InsertTextFormat, MarkupContent, MarkupKind, Position, Range, TextEdit, Url,
+};
 
+use crate::hover::{get_type_name, type_markdown, type_signature};
 use crate::symbols::position_to_offset;
 
+/// Kept in the `data` of type completions so that `completionItem/resolve`
+/// can find the type again.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct CompletionData {
+    pub uri: Url,
+    pub type_name: String,
+}
+
+impl CompletionData {
+    /// Reads the data of a completion item.
+    pub fn from_item(item: &CompletionItem) -> Option<Self> {
+        serde_json::from_value(item.data.clone()?).ok()
+    }
+}
+
 /// Completion context.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CompletionContext {
@@ -21,44 +41,56 @@ pub enum CompletionContext {
     Implements,
     /// Inside arguments
     Arguments,
+    /// After the `=` of a default value (expecting a value)
+    DefaultValue,
     /// Unknown
     Unknown,
 }
 
 /// Get completions at the given position.
+///
+/// Type completions leave out their documentation, which is filled in by
+/// [`resolve_completion`].
 pub fn get_completions(
+    uri: &Url,
     content: &str,
+    index: &LineIndex,
     position: Position,
     document: &Document<'_>,
     interner: &Interner,
 ) -> Vec<CompletionItem> {
-    let offset = position_to_offset(content, position);
+    let offset = position_to_offset(index, position);
     let context = determine_context(content, offset);
 
     let mut completions = Vec::new();
 
     match context {
         CompletionContext::TopLevel => {
+            completions.extend(snippet_completions(content, offset, position));
             completions.extend(keyword_completions());
         }
         CompletionContext::TypePosition => {
-            completions.extend(type_completions(document, interner));
+            completions.extend(type_completions(uri, document, interner));
             completions.extend(builtin_type_completions());
         }
         CompletionContext::Directive => {
             completions.extend(directive_completions(document, interner));
         }
         CompletionContext::Implements => {
-            completions.extend(interface_completions(document, interner));
+            completions.extend(interface_completions(uri, document, interner));
+        }
+        CompletionContext::DefaultValue => {
+            completions.extend(enum_value_completions(content, offset, document, interner));
         }
         CompletionContext::TypeBody | CompletionContext::Arguments => {
             // Field names suggestions could go here
         }
         CompletionContext::Unknown => {
             // Provide all possible completions
+            completions.extend(snippet_completions(content, offset, position));
             completions.extend(keyword_completions());
             completions.extend(builtin_type_completions());
-            completions.extend(type_completions(document, interner));
+            completions.extend(type_completions(uri, document, interner));
         }
     }
 
@@ -97,6 +129,10 @@ fn determine_context(content: &str, offset: usize) -> CompletionContext {
     let open_braces = before.matches('{').count();
     let close_braces = before.matches('}').count();
     if open_braces > close_braces {
+        if trimmed.ends_with('=') {
+            return CompletionContext::DefaultValue;
+        }
+
         // Check if we're in arguments
         let open_parens = before.matches('(').count();
         let close_parens = before.matches(')').count();
@@ -118,48 +154,70 @@ fn determine_context(content: &str, offset: usize) -> CompletionContext {
     CompletionContext::Unknown
 }
 
+/// Type definition skeletons, as `(keyword, detail, snippet)`.
+const TYPE_SNIPPETS: &[(&str, &str, &str)] = &[
+    (
+        "type",
+        "Object type skeleton",
+        "type ${1:Name} {\n  ${2:field}: ${3:Type}\n}",
+    ),
+    (
+        "interface",
+        "Interface skeleton",
+        "interface ${1:Name} {\n  ${2:field}: ${3:Type}\n}",
+    ),
+    ("enum", "Enum skeleton", "enum ${1:Name} {\n  ${2:VALUE}\n}"),
+    (
+        "input",
+        "Input type skeleton",
+        "input ${1:Name} {\n  ${2:field}: ${3:Type}\n}",
+    ),
+    (
+        "union",
+        "Union skeleton",
+        "union ${1:Name} = ${2:Member} | ${3:Member}",
+    ),
+];
+
+/// Snippets expanding to type definition skeletons.
+///
+/// When the keyword has already been typed, e.g. `type `, the snippet
+/// replaces it rather than repeating it.
+fn snippet_completions(content: &str, offset: usize, position: Position) -> Vec<CompletionItem> {
+    let before = &content[..offset.min(content.len())];
+    let line = before.rsplit('\n').next().unwrap_or_default();
+    let typed = line.trim_start();
+
+    TYPE_SNIPPETS
+        .iter()
+        .map(|(keyword, detail, snippet)| {
+            let mut item = CompletionItem {
+                label: keyword.to_string(),
+                kind: Some(CompletionItemKind::SNIPPET),
+                detail: Some(detail.to_string()),
+                insert_text_format: Some(InsertTextFormat::SNIPPET),
+                ..Default::default()
+            };
+            let after_keyword = typed
+                .strip_prefix(keyword)
+                .is_some_and(|rest| !rest.is_empty() && rest.trim().is_empty());
+            if after_keyword {
+                let start = Position::new(position.line, (line.len() - typed.len()) as u32);
+                item.filter_text = Some(typed.to_string());
+                item.text_edit = Some(CompletionTextEdit::Edit(TextEdit::new(
+                    Range::new(start, position),
+                    snippet.to_string(),
+                )));
+            } else {
+                item.insert_text = Some(snippet.to_string());
+            }
+            item
+        })
+        .collect()
+}
+
 fn keyword_completions() -> Vec<CompletionItem> {
     vec![
-        CompletionItem {
-            label: "type".to_string(),
-            kind: Some(CompletionItemKind::KEYWORD),
-            detail: Some("Define an object type".to_string()),
-            insert_text: Some("type ${1:Name} {\n  $0\n}".to_string()),
-            insert_text_format: Some(InsertTextFormat::SNIPPET),
-            ..Default::default()
-        },
-        CompletionItem {
-            label: "interface".to_string(),
-            kind: Some(CompletionItemKind::KEYWORD),
-            detail: Some("Define an interface".to_string()),
-            insert_text: Some("interface ${1:Name} {\n  $0\n}".to_string()),
-            insert_text_format: Some(InsertTextFormat::SNIPPET),
-            ..Default::default()
-        },
-        CompletionItem {
-            label: "enum".to_string(),
-            kind: Some(CompletionItemKind::KEYWORD),
-            detail: Some("Define an enum".to_string()),
-            insert_text: Some("enum ${1:Name} {\n  $0\n}".to_string()),
-            insert_text_format: Some(InsertTextFormat::SNIPPET),
-            ..Default::default()
-        },
-        CompletionItem {
-            label: "union".to_string(),
-            kind: Some(CompletionItemKind::KEYWORD),
-            detail: Some("Define a union type".to_string()),
-            insert_text: Some("union ${1:Name} = $0".to_string()),
-            insert_text_format: Some(InsertTextFormat::SNIPPET),
-            ..Default::default()
-        },
-        CompletionItem {
-            label: "input".to_string(),
-            kind: Some(CompletionItemKind::KEYWORD),
-            detail: Some("Define an input type".to_string()),
-            insert_text: Some("input ${1:Name} {\n  $0\n}".to_string()),
-            insert_text_format: Some(InsertTextFormat::SNIPPET),
-            ..Default::default()
-        },
         CompletionItem {
             label: "scalar".to_string(),
             kind: Some(CompletionItemKind::KEYWORD),
@@ -349,12 +407,103 @@ fn builtin_type_completions() -> Vec<CompletionItem> {
     ]
 }
 
-fn type_completions(document: &Document<'_>, interner: &Interner) -> Vec<CompletionItem> {
+/// Values of the enum whose default value is being typed, e.g. `Role` in
+/// `role: Role = `. Deprecated values are tagged and sorted last.
+fn enum_value_completions(
+    content: &str,
+    offset: usize,
+    document: &Document<'_>,
+    interner: &Interner,
+) -> Vec<CompletionItem> {
+    let before = content[..offset.min(content.len())].trim_end();
+    let ty = before.strip_suffix('=').unwrap_or(before).trim_end();
+    let ty = ty.trim_end_matches(['!', '>']);
+    let enum_name = &ty[ty
+        .rfind(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .map_or(0, |i| i + 1)..];
+
+    let Some(enum_def) = document.definitions.iter().find_map(|def| match def {
+        Definition::Type(TypeDefinition::Enum(e)) if interner.get(e.name.value) == enum_name => {
+            Some(e)
+        }
+        _ => None,
+    }) else {
+        return Vec::new();
+    };
+
+    let deprecations = DeprecationIndex::new(document, interner);
+    enum_def
+        .values
+        .iter()
+        .map(|value| {
+            let label = interner.get(value.name.value);
+            let deprecation = deprecations.enum_value(enum_name, &label);
+            CompletionItem {
+                kind: Some(CompletionItemKind::ENUM_MEMBER),
+                detail: Some(enum_name.to_string()),
+                documentation: deprecation
+                    .deprecation_reason
+                    .map(|reason| Documentation::String(format!("Deprecated: {reason}"))),
+                tags: deprecation
+                    .is_deprecated
+                    .then(|| vec![CompletionItemTag::DEPRECATED]),
+                sort_text: Some(format!("{}{label}", u8::from(deprecation.is_deprecated))),
+                label,
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// Fills in the detail and documentation of a type completion from the
+/// type's definition in `document`. Other items are returned unchanged.
+pub fn resolve_completion(
+    mut item: CompletionItem,
+    document: &Document<'_>,
+    interner: &Interner,
+) -> CompletionItem {
+    let Some(data) = CompletionData::from_item(&item) else {
+        return item;
+    };
+
+    let type_def = document.definitions.iter().find_map(|def| match def {
+        Definition::Type(type_def)
+            if get_type_name(type_def, interner).as_deref() == Some(data.type_name.as_str()) =>
+        {
+            Some(type_def)
+        }
+        _ => None,
+    });
+
+    if let Some(type_def) = type_def {
+        item.detail = Some(type_signature(type_def, interner));
+        item.documentation = Some(Documentation::MarkupContent(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: type_markdown(type_def, interner),
+        }));
+    }
+
+    item
+}
+
+fn type_completion_data(uri: &Url, name: &str) -> Option<serde_json::Value> {
+    serde_json::to_value(CompletionData {
+        uri: uri.clone(),
+        type_name: name.to_string(),
+    })
+    .ok()
+}
+
+fn type_completions(
+    uri: &Url,
+    document: &Document<'_>,
+    interner: &Interner,
+) -> Vec<CompletionItem> {
     let mut completions = Vec::new();
 
     for def in &document.definitions {
         if let Definition::Type(type_def) = def {
-            if let Some(item) = type_def_to_completion(type_def, interner) {
+            if let Some(item) = type_def_to_completion(uri, type_def, interner) {
                 completions.push(item);
             }
         }
@@ -364,6 +513,7 @@ fn type_completions(document: &Document<'_>, interner: &Interner) -> Vec<Complet
 }
 
 fn type_def_to_completion(
+    uri: &Url,
     type_def: &TypeDefinition<'_>,
     interner: &Interner,
 ) -> Option<CompletionItem> {
@@ -414,11 +564,16 @@ fn type_def_to_completion(
         label: name.to_string(),
         kind: Some(kind),
         detail: Some(detail.to_string()),
+        data: type_completion_data(uri, &name),
         ..Default::default()
     })
 }
 
-fn interface_completions(document: &Document<'_>, interner: &Interner) -> Vec<CompletionItem> {
+fn interface_completions(
+    uri: &Url,
+    document: &Document<'_>,
+    interner: &Interner,
+) -> Vec<CompletionItem> {
     let mut completions = Vec::new();
 
     for def in &document.definitions {
@@ -428,6 +583,7 @@ fn interface_completions(document: &Document<'_>, interner: &Interner) -> Vec<Co
                 label: name.to_string(),
                 kind: Some(CompletionItemKind::INTERFACE),
                 detail: Some("interface".to_string()),
+                data: type_completion_data(uri, &name),
                 ..Default::default()
             });
         }
@@ -554,6 +710,14 @@ fn directive_completions(document: &Document<'_>, interner: &Interner) -> Vec<Co
             insert_text_format: Some(InsertTextFormat::SNIPPET),
             ..Default::default()
         },
+        CompletionItem {
+            label: "authScope".to_string(),
+            kind: Some(CompletionItemKind::FUNCTION),
+            detail: Some("Require scopes".to_string()),
+            insert_text: Some("authScope(scopes: [\"${0:read:users}\"])".to_string()),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            ..Default::default()
+        },
         CompletionItem {
             label: "hasPermission".to_string(),
             kind: Some(CompletionItemKind::FUNCTION),
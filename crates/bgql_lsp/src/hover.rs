@@ -4,21 +4,15 @@ use bgql_core::Interner;
 use bgql_syntax::{Definition, Document, Type, TypeDefinition};
 use tower_lsp::lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind};
 
-use crate::symbols::{position_to_offset, SymbolTable, SymbolType};
+use crate::symbols::{SymbolTable, SymbolType};
 
-/// Get hover information at the given position.
+/// Get hover information at the byte `offset`.
 pub fn get_hover(
     content: &str,
-    line: u32,
-    character: u32,
+    offset: usize,
     document: &Document<'_>,
     interner: &Interner,
 ) -> Option<Hover> {
-    let offset = position_to_offset(
-        content,
-        tower_lsp::lsp_types::Position::new(line, character),
-    );
-
     // Build symbol table
     let symbols = SymbolTable::from_document(document, interner);
 
@@ -68,6 +62,9 @@ fn build_hover_markdown(
         SymbolType::Union => {
             markdown.push_str(&format!("union {}", symbol.name));
         }
+        SymbolType::UnionMember => {
+            markdown.push_str(&symbol.name);
+        }
         SymbolType::Input => {
             markdown.push_str(&format!("input {}", symbol.name));
         }
@@ -87,7 +84,8 @@ fn build_hover_markdown(
             markdown.push_str(&format!("input enum {}", symbol.name));
         }
         SymbolType::Field => {
-            markdown.push_str(&format!("{}: ...", symbol.name));
+            let ty = symbol.detail.as_deref().unwrap_or("...");
+            markdown.push_str(&format!("{}: {}", symbol.name, ty));
         }
         SymbolType::Argument => {
             markdown.push_str(&format!("(parameter) {}", symbol.name));
@@ -198,7 +196,10 @@ fn get_builtin_type_hover(name: &str) -> Option<Hover> {
     })
 }
 
-fn get_type_name<'a>(type_def: &'a TypeDefinition<'a>, interner: &'a Interner) -> Option<String> {
+pub(crate) fn get_type_name<'a>(
+    type_def: &'a TypeDefinition<'a>,
+    interner: &'a Interner,
+) -> Option<String> {
     let name = match type_def {
         TypeDefinition::Object(obj) => interner.get(obj.name.value),
         TypeDefinition::Interface(iface) => interner.get(iface.name.value),
@@ -215,26 +216,51 @@ fn get_type_name<'a>(type_def: &'a TypeDefinition<'a>, interner: &'a Interner) -
 }
 
 fn build_type_hover(type_def: &TypeDefinition<'_>, interner: &Interner) -> Hover {
+    Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: type_markdown(type_def, interner),
+        }),
+        range: None,
+    }
+}
+
+/// Renders a type's signature and description as Markdown.
+pub(crate) fn type_markdown(type_def: &TypeDefinition<'_>, interner: &Interner) -> String {
     let mut markdown = String::new();
 
     markdown.push_str("```bgql\n");
+    markdown.push_str(&type_signature(type_def, interner));
+    markdown.push_str("\n```");
+
+    if let Some(desc) = type_description(type_def) {
+        markdown.push_str("\n\n---\n\n");
+        markdown.push_str(desc.trim());
+    }
+
+    markdown
+}
+
+/// Renders a one-line signature of a type, eliding field lists.
+pub(crate) fn type_signature(type_def: &TypeDefinition<'_>, interner: &Interner) -> String {
+    let mut signature = String::new();
     match type_def {
         TypeDefinition::Object(obj) => {
             let name = interner.get(obj.name.value);
-            markdown.push_str(&format!("type {}", name));
+            signature.push_str(&format!("type {}", name));
             if !obj.implements.is_empty() {
                 let impls: Vec<_> = obj
                     .implements
                     .iter()
                     .map(|i| interner.get(i.value))
                     .collect();
-                markdown.push_str(&format!(" implements {}", impls.join(" & ")));
+                signature.push_str(&format!(" implements {}", impls.join(" & ")));
             }
-            markdown.push_str(" { ... }");
+            signature.push_str(" { ... }");
         }
         TypeDefinition::Interface(iface) => {
             let name = interner.get(iface.name.value);
-            markdown.push_str(&format!("interface {} {{ ... }}", name));
+            signature.push_str(&format!("interface {} {{ ... }}", name));
         }
         TypeDefinition::Enum(e) => {
             let name = interner.get(e.name.value);
@@ -245,7 +271,7 @@ fn build_type_hover(type_def: &TypeDefinition<'_>, interner: &Interner) -> Hover
                 .map(|v| interner.get(v.name.value))
                 .collect();
             let suffix = if e.values.len() > 5 { ", ..." } else { "" };
-            markdown.push_str(&format!(
+            signature.push_str(&format!(
                 "enum {} {{ {}{} }}",
                 name,
                 values.join(", "),
@@ -255,30 +281,30 @@ fn build_type_hover(type_def: &TypeDefinition<'_>, interner: &Interner) -> Hover
         TypeDefinition::Union(u) => {
             let name = interner.get(u.name.value);
             let members: Vec<_> = u.members.iter().map(|m| interner.get(m.value)).collect();
-            markdown.push_str(&format!("union {} = {}", name, members.join(" | ")));
+            signature.push_str(&format!("union {} = {}", name, members.join(" | ")));
         }
         TypeDefinition::Input(inp) => {
             let name = interner.get(inp.name.value);
-            markdown.push_str(&format!("input {} {{ ... }}", name));
+            signature.push_str(&format!("input {} {{ ... }}", name));
         }
         TypeDefinition::Scalar(s) => {
             let name = interner.get(s.name.value);
-            markdown.push_str(&format!("scalar {}", name));
+            signature.push_str(&format!("scalar {}", name));
         }
         TypeDefinition::Opaque(o) => {
             let name = interner.get(o.name.value);
             let underlying = format_type(&o.underlying, interner);
-            markdown.push_str(&format!("opaque {} = {}", name, underlying));
+            signature.push_str(&format!("opaque {} = {}", name, underlying));
         }
         TypeDefinition::TypeAlias(a) => {
             let name = interner.get(a.name.value);
             let aliased = format_type(&a.aliased, interner);
-            markdown.push_str(&format!("alias {} = {}", name, aliased));
+            signature.push_str(&format!("alias {} = {}", name, aliased));
         }
         TypeDefinition::InputUnion(iu) => {
             let name = interner.get(iu.name.value);
             let members: Vec<_> = iu.members.iter().map(|m| interner.get(m.value)).collect();
-            markdown.push_str(&format!("input union {} = {}", name, members.join(" | ")));
+            signature.push_str(&format!("input union {} = {}", name, members.join(" | ")));
         }
         TypeDefinition::InputEnum(ie) => {
             let name = interner.get(ie.name.value);
@@ -289,7 +315,7 @@ fn build_type_hover(type_def: &TypeDefinition<'_>, interner: &Interner) -> Hover
                 .map(|v| interner.get(v.name.value))
                 .collect();
             let suffix = if ie.variants.len() > 5 { ", ..." } else { "" };
-            markdown.push_str(&format!(
+            signature.push_str(&format!(
                 "input enum {} {{ {}{} }}",
                 name,
                 variants.join(", "),
@@ -297,37 +323,25 @@ fn build_type_hover(type_def: &TypeDefinition<'_>, interner: &Interner) -> Hover
             ));
         }
     }
-    markdown.push_str("\n```");
-
-    // Add description
-    let desc = match type_def {
-        TypeDefinition::Object(obj) => obj.description.as_ref().map(|d| d.value),
-        TypeDefinition::Interface(iface) => iface.description.as_ref().map(|d| d.value),
-        TypeDefinition::Enum(e) => e.description.as_ref().map(|d| d.value),
-        TypeDefinition::Union(u) => u.description.as_ref().map(|d| d.value),
-        TypeDefinition::Input(inp) => inp.description.as_ref().map(|d| d.value),
-        TypeDefinition::Scalar(s) => s.description.as_ref().map(|d| d.value),
-        TypeDefinition::Opaque(o) => o.description.as_ref().map(|d| d.value),
-        TypeDefinition::TypeAlias(a) => a.description.as_ref().map(|d| d.value),
-        TypeDefinition::InputUnion(iu) => iu.description.as_ref().map(|d| d.value),
-        TypeDefinition::InputEnum(ie) => ie.description.as_ref().map(|d| d.value),
-    };
-
-    if let Some(desc) = desc {
-        markdown.push_str("\n\n---\n\n");
-        markdown.push_str(desc);
-    }
+    signature
+}
 
-    Hover {
-        contents: HoverContents::Markup(MarkupContent {
-            kind: MarkupKind::Markdown,
-            value: markdown,
-        }),
-        range: None,
+fn type_description<'a>(type_def: &'a TypeDefinition<'_>) -> Option<&'a str> {
+    match type_def {
+        TypeDefinition::Object(obj) => obj.description.as_ref().map(|d| d.value.as_ref()),
+        TypeDefinition::Interface(iface) => iface.description.as_ref().map(|d| d.value.as_ref()),
+        TypeDefinition::Enum(e) => e.description.as_ref().map(|d| d.value.as_ref()),
+        TypeDefinition::Union(u) => u.description.as_ref().map(|d| d.value.as_ref()),
+        TypeDefinition::Input(inp) => inp.description.as_ref().map(|d| d.value.as_ref()),
+        TypeDefinition::Scalar(s) => s.description.as_ref().map(|d| d.value.as_ref()),
+        TypeDefinition::Opaque(o) => o.description.as_ref().map(|d| d.value.as_ref()),
+        TypeDefinition::TypeAlias(a) => a.description.as_ref().map(|d| d.value.as_ref()),
+        TypeDefinition::InputUnion(iu) => iu.description.as_ref().map(|d| d.value.as_ref()),
+        TypeDefinition::InputEnum(ie) => ie.description.as_ref().map(|d| d.value.as_ref()),
     }
 }
 
-fn format_type(ty: &Type<'_>, interner: &Interner) -> String {
+pub(crate) fn format_type(ty: &Type<'_>, interner: &Interner) -> String {
     match ty {
         Type::Named(named) => interner.get(named.name).to_string(),
         Type::Option(inner, _) => format!("Option<{}>", format_type(inner, interner)),
@@ -8,6 +8,8 @@
 //! - Go to definition
 //! - Find references
 //! - Document symbols
+//! - Folding ranges
+//! - Workspace symbols
 //! - Formatting
 //! - Rename
 
@@ -17,9 +19,12 @@ mod state;
 mod symbols;
 
 use async_trait::async_trait;
-use bgql_core::Interner;
-use bgql_semantic::{checker, hir::HirDatabase, types::TypeRegistry};
-use bgql_syntax::{format, parse, Definition, TypeDefinition};
+use bgql_core::diagnostics::codes;
+use bgql_core::{DiagnosticBag, Interner, LineIndex, Span};
+use bgql_semantic::DeprecationIndex;
+use bgql_syntax::{
+    format, parse, Definition, Lexer, Name, OperationType, TokenKind, TypeDefinition, TypeParameter,
+};
 use std::sync::Arc;
 use symbols::{
     offset_to_position, position_to_offset, span_to_range, symbol_to_document_symbol, SymbolTable,
@@ -31,7 +36,7 @@ use tower_lsp::{Client, LanguageServer, LspService, Server};
 #[allow(unused_imports)]
 use tracing::info;
 
-use crate::state::ServerState;
+use crate::state::{DocumentState, ParsedDocument, ServerState};
 
 /// The Better GraphQL language server.
 pub struct BgqlLanguageServer {
@@ -47,90 +52,59 @@ impl BgqlLanguageServer {
         }
     }
 
+    /// Runs `f` with the cached parse of an open document.
+    async fn with_document<R>(&self, uri: &Url, f: impl FnOnce(&ParsedDocument) -> R) -> Option<R> {
+        let state = self.state.read().await;
+        state.get_document(uri).map(|doc| doc.with_parsed(f))
+    }
+
     async fn validate(&self, uri: &Url) {
-        let content = {
+        let diagnostics = {
             let state = self.state.read().await;
-            state.get_document(uri).map(|d| d.content.clone())
-        };
-
-        let Some(content) = content else {
-            return;
+            let Some(doc) = state.get_document(uri) else {
+                return;
+            };
+            doc.with_parsed(|parsed| self.get_diagnostics(doc, parsed, uri))
         };
 
-        let diagnostics = self.get_diagnostics(&content);
-
         self.client
             .publish_diagnostics(uri.clone(), diagnostics, None)
             .await;
     }
 
-    fn get_diagnostics(&self, content: &str) -> Vec<Diagnostic> {
-        let interner = Interner::new();
-        let result = parse(content, &interner);
-
+    fn get_diagnostics(
+        &self,
+        doc: &DocumentState,
+        parsed: &ParsedDocument,
+        uri: &Url,
+    ) -> Vec<Diagnostic> {
         // Collect parser diagnostics
-        let mut diagnostics: Vec<Diagnostic> = result
-            .diagnostics
-            .iter()
-            .filter_map(|diag| {
-                let span = diag.primary_span()?;
-                let start = offset_to_position(content, span.start as usize);
-                let end = offset_to_position(content, span.end as usize);
-
-                Some(Diagnostic {
-                    range: Range { start, end },
-                    severity: Some(match diag.severity {
-                        bgql_core::DiagnosticSeverity::Error => DiagnosticSeverity::ERROR,
-                        bgql_core::DiagnosticSeverity::Warning => DiagnosticSeverity::WARNING,
-                        bgql_core::DiagnosticSeverity::Info => DiagnosticSeverity::INFORMATION,
-                        bgql_core::DiagnosticSeverity::Hint => DiagnosticSeverity::HINT,
-                    }),
-                    message: diag.title.clone(),
-                    source: Some("bgql".to_string()),
-                    ..Default::default()
-                })
-            })
-            .collect();
-
-        // Run type checker if no parser errors
-        if !result.diagnostics.has_errors() {
-            let types = TypeRegistry::new();
-            let hir = HirDatabase::new();
-            let check_result = checker::check(&result.document, &types, &hir, &interner);
-
-            for diag in check_result.diagnostics.iter() {
-                if let Some(span) = diag.primary_span() {
-                    let start = offset_to_position(content, span.start as usize);
-                    let end = offset_to_position(content, span.end as usize);
-
-                    diagnostics.push(Diagnostic {
-                        range: Range { start, end },
-                        severity: Some(match diag.severity {
-                            bgql_core::DiagnosticSeverity::Error => DiagnosticSeverity::ERROR,
-                            bgql_core::DiagnosticSeverity::Warning => DiagnosticSeverity::WARNING,
-                            bgql_core::DiagnosticSeverity::Info => DiagnosticSeverity::INFORMATION,
-                            bgql_core::DiagnosticSeverity::Hint => DiagnosticSeverity::HINT,
-                        }),
-                        message: diag.title.clone(),
-                        source: Some("bgql".to_string()),
-                        ..Default::default()
-                    });
-                }
-            }
+        let mut diagnostics = DiagnosticBag::new();
+        diagnostics.extend(parsed.diagnostics().iter().cloned());
+
+        // Run type checker if no parser errors, rechecking only what changed
+        if !parsed.diagnostics().has_errors() {
+            doc.type_check(parsed, |result| {
+                diagnostics.extend(result.diagnostics.iter().cloned());
+            });
         }
 
+        diagnostics.sort_by_span();
+        diagnostics.dedup();
         diagnostics
+            .iter()
+            .filter_map(|diag| to_lsp_diagnostic(diag, parsed.line_index(), uri))
+            .collect()
     }
 
     fn find_definition_location(
         &self,
-        content: &str,
+        parsed: &ParsedDocument,
         position: Position,
-        interner: &Interner,
-        document: &bgql_syntax::Document<'_>,
     ) -> Option<Location> {
-        let offset = position_to_offset(content, position);
-        let word = get_word_at_offset(content, offset)?;
+        let (document, interner) = (parsed.document(), parsed.interner());
+        let offset = position_to_offset(parsed.line_index(), position);
+        let word = get_word_at_offset(parsed.source(), offset)?;
 
         for def in &document.definitions {
             if let Definition::Type(type_def) = def {
@@ -152,7 +126,7 @@ impl BgqlLanguageServer {
                 if name == word {
                     return Some(Location {
                         uri: Url::parse("file:///").ok()?,
-                        range: span_to_range(span, content),
+                        range: span_to_range(span, parsed.line_index()),
                     });
                 }
             }
@@ -162,6 +136,67 @@ impl BgqlLanguageServer {
     }
 }
 
+/// A replacement carried in an LSP diagnostic's `data` for quick fixes.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct SuggestionData {
+    range: Range,
+    replacement: String,
+}
+
+/// Converts a diagnostic to LSP. Secondary labels become related
+/// information, notes are appended to the message and suggestions are kept
+/// in `data`.
+fn to_lsp_diagnostic(
+    diag: &bgql_core::Diagnostic,
+    index: &LineIndex,
+    uri: &Url,
+) -> Option<Diagnostic> {
+    let span_range = |span: Span| span_to_range(span, index);
+
+    let related: Vec<_> = diag
+        .secondary_labels()
+        .iter()
+        .map(|label| DiagnosticRelatedInformation {
+            location: Location {
+                uri: uri.clone(),
+                range: span_range(label.span),
+            },
+            message: label.message.clone(),
+        })
+        .collect();
+
+    let suggestions: Vec<_> = diag
+        .suggestions
+        .iter()
+        .map(|suggestion| SuggestionData {
+            range: span_range(suggestion.span),
+            replacement: suggestion.replacement.clone(),
+        })
+        .collect();
+
+    let mut message = diag.title.clone();
+    for note in &diag.notes {
+        message.push_str("\nnote: ");
+        message.push_str(note);
+    }
+
+    Some(Diagnostic {
+        range: span_range(diag.primary_span()?),
+        severity: Some(match diag.severity {
+            bgql_core::DiagnosticSeverity::Error => DiagnosticSeverity::ERROR,
+            bgql_core::DiagnosticSeverity::Warning => DiagnosticSeverity::WARNING,
+            bgql_core::DiagnosticSeverity::Info => DiagnosticSeverity::INFORMATION,
+            bgql_core::DiagnosticSeverity::Hint => DiagnosticSeverity::HINT,
+        }),
+        code: Some(NumberOrString::String(diag.code.clone())),
+        message,
+        source: Some("bgql".to_string()),
+        related_information: (!related.is_empty()).then_some(related),
+        data: (!suggestions.is_empty()).then(|| serde_json::json!({ "suggestions": suggestions })),
+        ..Default::default()
+    })
+}
+
 fn get_word_at_offset(content: &str, offset: usize) -> Option<String> {
     let bytes = content.as_bytes();
 
@@ -218,6 +253,8 @@ impl LanguageServer for BgqlLanguageServer {
                 definition_provider: Some(OneOf::Left(true)),
                 references_provider: Some(OneOf::Left(true)),
                 document_symbol_provider: Some(OneOf::Left(true)),
+                folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+                workspace_symbol_provider: Some(OneOf::Left(true)),
                 document_formatting_provider: Some(OneOf::Left(true)),
                 rename_provider: Some(OneOf::Left(true)),
                 semantic_tokens_provider: Some(
@@ -314,46 +351,49 @@ impl LanguageServer for BgqlLanguageServer {
         let uri = &params.text_document_position.text_document.uri;
         let position = params.text_document_position.position;
 
-        let content = {
-            let state = self.state.read().await;
-            state.get_document(uri).map(|d| d.content.clone())
-        };
+        let completions = self
+            .with_document(uri, |parsed| {
+                completion::get_completions(
+                    uri,
+                    parsed.source(),
+                    parsed.line_index(),
+                    position,
+                    parsed.document(),
+                    parsed.interner(),
+                )
+            })
+            .await;
+        Ok(completions.map(CompletionResponse::Array))
+    }
 
-        let Some(content) = content else {
-            return Ok(None);
+    async fn completion_resolve(&self, item: CompletionItem) -> Result<CompletionItem> {
+        let Some(data) = completion::CompletionData::from_item(&item) else {
+            return Ok(item);
         };
 
-        let interner = Interner::new();
-        let result = parse(&content, &interner);
-
-        let completions =
-            completion::get_completions(&content, position, &result.document, &interner);
-        Ok(Some(CompletionResponse::Array(completions)))
+        let resolved = self
+            .with_document(&data.uri, |parsed| {
+                completion::resolve_completion(item.clone(), parsed.document(), parsed.interner())
+            })
+            .await;
+        Ok(resolved.unwrap_or(item))
     }
 
     async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
         let uri = &params.text_document_position_params.text_document.uri;
         let position = params.text_document_position_params.position;
 
-        let content = {
-            let state = self.state.read().await;
-            state.get_document(uri).map(|d| d.content.clone())
-        };
-
-        let Some(content) = content else {
-            return Ok(None);
-        };
-
-        let interner = Interner::new();
-        let result = parse(&content, &interner);
-
-        Ok(hover::get_hover(
-            &content,
-            position.line,
-            position.character,
-            &result.document,
-            &interner,
-        ))
+        let result = self
+            .with_document(uri, |parsed| {
+                hover::get_hover(
+                    parsed.source(),
+                    position_to_offset(parsed.line_index(), position),
+                    parsed.document(),
+                    parsed.interner(),
+                )
+            })
+            .await;
+        Ok(result.flatten())
     }
 
     async fn goto_definition(
@@ -363,26 +403,17 @@ impl LanguageServer for BgqlLanguageServer {
         let uri = &params.text_document_position_params.text_document.uri;
         let position = params.text_document_position_params.position;
 
-        let content = {
-            let state = self.state.read().await;
-            state.get_document(uri).map(|d| d.content.clone())
-        };
-
-        let Some(content) = content else {
-            return Ok(None);
-        };
-
-        let interner = Interner::new();
-        let result = parse(&content, &interner);
+        let location = self
+            .with_document(uri, |parsed| {
+                self.find_definition_location(parsed, position)
+            })
+            .await
+            .flatten();
 
-        if let Some(mut location) =
-            self.find_definition_location(&content, position, &interner, &result.document)
-        {
+        Ok(location.map(|mut location| {
             location.uri = uri.clone();
-            return Ok(Some(GotoDefinitionResponse::Scalar(location)));
-        }
-
-        Ok(None)
+            GotoDefinitionResponse::Scalar(location)
+        }))
     }
 
     async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
@@ -398,7 +429,8 @@ impl LanguageServer for BgqlLanguageServer {
             return Ok(None);
         };
 
-        let offset = position_to_offset(&content, position);
+        let index = LineIndex::new(&content);
+        let offset = position_to_offset(&index, position);
         let word = match get_word_at_offset(&content, offset) {
             Some(w) => w,
             None => return Ok(None),
@@ -415,8 +447,8 @@ impl LanguageServer for BgqlLanguageServer {
                 || !is_identifier_char(content.as_bytes()[abs_pos + word.len()]);
 
             if before_ok && after_ok {
-                let start = offset_to_position(&content, abs_pos);
-                let end = offset_to_position(&content, abs_pos + word.len());
+                let start = offset_to_position(&index, abs_pos);
+                let end = offset_to_position(&index, abs_pos + word.len());
                 locations.push(Location {
                     uri: uri.clone(),
                     range: Range { start, end },
@@ -439,60 +471,61 @@ impl LanguageServer for BgqlLanguageServer {
     ) -> Result<Option<DocumentSymbolResponse>> {
         let uri = &params.text_document.uri;
 
-        let content = {
-            let state = self.state.read().await;
-            state.get_document(uri).map(|d| d.content.clone())
-        };
-
-        let Some(content) = content else {
-            return Ok(None);
-        };
+        let symbols = self
+            .with_document(uri, |parsed| {
+                let symbol_table = SymbolTable::from_document(parsed.document(), parsed.interner());
+                symbol_table
+                    .root_symbols
+                    .iter()
+                    .map(|s| symbol_to_document_symbol(s, parsed.source(), parsed.line_index()))
+                    .collect::<Vec<DocumentSymbol>>()
+            })
+            .await;
 
-        let interner = Interner::new();
-        let result = parse(&content, &interner);
+        Ok(symbols.map(DocumentSymbolResponse::Nested))
+    }
 
-        let symbol_table = SymbolTable::from_document(&result.document, &interner);
-        let symbols: Vec<DocumentSymbol> = symbol_table
-            .root_symbols
-            .iter()
-            .map(|s| symbol_to_document_symbol(s, &content))
-            .collect();
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<Vec<SymbolInformation>>> {
+        let state = self.state.read().await;
+        let symbols = state.workspace_symbols(&params.query);
 
-        Ok(Some(DocumentSymbolResponse::Nested(symbols)))
+        if symbols.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(symbols))
+        }
     }
 
     async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
         let uri = &params.text_document.uri;
 
-        let content = {
-            let state = self.state.read().await;
-            state.get_document(uri).map(|d| d.content.clone())
-        };
-
-        let Some(content) = content else {
-            return Ok(None);
-        };
-
-        let interner = Interner::new();
-        let result = parse(&content, &interner);
+        let edit = self
+            .with_document(uri, |parsed| {
+                if parsed.diagnostics().has_errors() {
+                    return None;
+                }
 
-        if result.diagnostics.has_errors() {
-            return Ok(None);
-        }
+                let formatted = format(parsed.document(), parsed.interner());
 
-        let formatted = format(&result.document, &interner);
+                let lines: Vec<_> = parsed.source().lines().collect();
+                let end_line = lines.len().saturating_sub(1) as u32;
+                let end_char = lines.last().map(|l| l.len() as u32).unwrap_or(0);
 
-        let lines: Vec<_> = content.lines().collect();
-        let end_line = lines.len().saturating_sub(1) as u32;
-        let end_char = lines.last().map(|l| l.len() as u32).unwrap_or(0);
+                Some(TextEdit {
+                    range: Range {
+                        start: Position::new(0, 0),
+                        end: Position::new(end_line, end_char),
+                    },
+                    new_text: formatted,
+                })
+            })
+            .await
+            .flatten();
 
-        Ok(Some(vec![TextEdit {
-            range: Range {
-                start: Position::new(0, 0),
-                end: Position::new(end_line, end_char),
-            },
-            new_text: formatted,
-        }]))
+        Ok(edit.map(|edit| vec![edit]))
     }
 
     async fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
@@ -509,7 +542,8 @@ impl LanguageServer for BgqlLanguageServer {
             return Ok(None);
         };
 
-        let offset = position_to_offset(&content, position);
+        let index = LineIndex::new(&content);
+        let offset = position_to_offset(&index, position);
         let word = match get_word_at_offset(&content, offset) {
             Some(w) => w,
             None => return Ok(None),
@@ -526,8 +560,8 @@ impl LanguageServer for BgqlLanguageServer {
                 || !is_identifier_char(content.as_bytes()[abs_pos + word.len()]);
 
             if before_ok && after_ok {
-                let start = offset_to_position(&content, abs_pos);
-                let end = offset_to_position(&content, abs_pos + word.len());
+                let start = offset_to_position(&index, abs_pos);
+                let end = offset_to_position(&index, abs_pos + word.len());
                 edits.push(TextEdit {
                     range: Range { start, end },
                     new_text: new_name.clone(),
@@ -554,26 +588,28 @@ impl LanguageServer for BgqlLanguageServer {
         let uri = &params.text_document_position_params.text_document.uri;
         let position = params.text_document_position_params.position;
 
-        let content = {
-            let state = self.state.read().await;
-            state.get_document(uri).map(|d| d.content.clone())
-        };
-
-        let Some(content) = content else {
-            return Ok(None);
-        };
-
-        let offset = position_to_offset(&content, position);
-        let signatures = get_signature_help(&content, offset);
+        let signatures = self
+            .with_document(uri, |parsed| {
+                let offset = position_to_offset(parsed.line_index(), position);
+                get_signature_help(
+                    parsed.source(),
+                    offset,
+                    parsed.document(),
+                    parsed.interner(),
+                )
+            })
+            .await
+            .unwrap_or_default();
 
         if signatures.is_empty() {
             return Ok(None);
         }
 
+        let active_parameter = signatures[0].active_parameter;
         Ok(Some(SignatureHelp {
             signatures,
             active_signature: Some(0),
-            active_parameter: None,
+            active_parameter,
         }))
     }
 
@@ -583,44 +619,26 @@ impl LanguageServer for BgqlLanguageServer {
     ) -> Result<Option<SemanticTokensResult>> {
         let uri = &params.text_document.uri;
 
-        let content = {
-            let state = self.state.read().await;
-            state.get_document(uri).map(|d| d.content.clone())
-        };
-
-        let Some(content) = content else {
-            return Ok(None);
-        };
+        let tokens = self.with_document(uri, compute_semantic_tokens).await;
 
-        let interner = Interner::new();
-        let result = parse(&content, &interner);
+        Ok(tokens.map(|data| {
+            SemanticTokensResult::Tokens(SemanticTokens {
+                result_id: None,
+                data,
+            })
+        }))
+    }
 
-        let tokens = compute_semantic_tokens(&result.document, &content, &interner);
+    async fn folding_range(&self, params: FoldingRangeParams) -> Result<Option<Vec<FoldingRange>>> {
+        let uri = &params.text_document.uri;
 
-        Ok(Some(SemanticTokensResult::Tokens(SemanticTokens {
-            result_id: None,
-            data: tokens,
-        })))
+        Ok(self.with_document(uri, compute_folding_ranges).await)
     }
 
     async fn inlay_hint(&self, params: InlayHintParams) -> Result<Option<Vec<InlayHint>>> {
         let uri = &params.text_document.uri;
 
-        let content = {
-            let state = self.state.read().await;
-            state.get_document(uri).map(|d| d.content.clone())
-        };
-
-        let Some(content) = content else {
-            return Ok(None);
-        };
-
-        let interner = Interner::new();
-        let result = parse(&content, &interner);
-
-        let hints = compute_inlay_hints(&result.document, &content, &interner);
-
-        Ok(Some(hints))
+        Ok(self.with_document(uri, compute_inlay_hints).await)
     }
 
     async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
@@ -639,9 +657,11 @@ impl LanguageServer for BgqlLanguageServer {
 
         // Generate quick fixes for diagnostics
         for diag in &params.context.diagnostics {
-            if let Some(action) = generate_quick_fix(&content, diag, uri) {
-                actions.push(CodeActionOrCommand::CodeAction(action));
-            }
+            actions.extend(
+                generate_quick_fixes(&content, diag, uri)
+                    .into_iter()
+                    .map(CodeActionOrCommand::CodeAction),
+            );
         }
 
         if actions.is_empty() {
@@ -656,10 +676,35 @@ impl LanguageServer for BgqlLanguageServer {
 // Signature Help
 // =============================================================================
 
-fn get_signature_help(content: &str, offset: usize) -> Vec<SignatureInformation> {
+fn get_signature_help(
+    content: &str,
+    offset: usize,
+    document: &bgql_syntax::Document<'_>,
+    interner: &Interner,
+) -> Vec<SignatureInformation> {
     let before = &content[..offset.min(content.len())];
 
-    // Find the directive or field call context
+    // Inside an argument list, use the arguments declared by the field or directive
+    if let Some(open) = find_unclosed(before, '(', ')') {
+        let name_start = identifier_start(&before[..open]);
+        let name = before[name_start..open].trim_end();
+        if !name.is_empty() {
+            if before[..name_start].ends_with('@') {
+                return directive_signatures(
+                    document,
+                    interner,
+                    content,
+                    name,
+                    Some(&before[open + 1..]),
+                );
+            }
+            return field_signature(document, interner, content, before, open, name)
+                .into_iter()
+                .collect();
+        }
+    }
+
+    // Find the directive call context
     if let Some(at_pos) = before.rfind('@') {
         let directive_text = &before[at_pos + 1..];
         // Find directive name
@@ -668,12 +713,329 @@ fn get_signature_help(content: &str, offset: usize) -> Vec<SignatureInformation>
             .unwrap_or(directive_text.len());
         let directive_name = &directive_text[..name_end];
 
-        return get_directive_signatures(directive_name);
+        return directive_signatures(document, interner, content, directive_name, None);
     }
 
     Vec::new()
 }
 
+/// Builds the signature of the field called at `open`, resolved against the
+/// types defined in `document`.
+fn field_signature(
+    document: &bgql_syntax::Document<'_>,
+    interner: &Interner,
+    content: &str,
+    before: &str,
+    open: usize,
+    name: &str,
+) -> Option<SignatureInformation> {
+    let name_start = identifier_start(&before[..open]);
+    let (mut type_name, owners) = enclosing_selections(document, interner, &before[..name_start])?;
+    for owner in owners.iter().rev() {
+        let field = find_field(document, interner, &type_name, owner)?;
+        type_name = named_type(&field.ty, interner);
+    }
+    let field = find_field(document, interner, &type_name, name)?;
+
+    let active_parameter = last_argument_name(&before[open + 1..]).and_then(|current| {
+        field
+            .arguments
+            .iter()
+            .position(|arg| interner.get(arg.name.value) == current)
+    });
+
+    let mut signature = arguments_signature(
+        name,
+        field.description.as_ref(),
+        &field.arguments,
+        content,
+        interner,
+    );
+    signature.active_parameter = Some(active_parameter.unwrap_or(0) as u32);
+    Some(signature)
+}
+
+/// Builds the signature of the directive `name`, from its definition in
+/// `document` or else from the built-in directives. `args` is the partial
+/// argument list before the cursor, if it's inside one.
+fn directive_signatures(
+    document: &bgql_syntax::Document<'_>,
+    interner: &Interner,
+    content: &str,
+    name: &str,
+    args: Option<&str>,
+) -> Vec<SignatureInformation> {
+    let definition = document.definitions.iter().find_map(|def| match def {
+        Definition::Directive(directive) if interner.get(directive.name.value) == name => {
+            Some(directive)
+        }
+        _ => None,
+    });
+
+    let mut signatures = match definition {
+        Some(directive) => vec![arguments_signature(
+            &format!("@{}", name),
+            directive.description.as_ref(),
+            &directive.arguments,
+            content,
+            interner,
+        )],
+        None => get_directive_signatures(name),
+    };
+    for signature in &mut signatures {
+        signature.active_parameter = args.map(|args| active_argument(args, signature));
+    }
+    signatures
+}
+
+/// Returns the index of the parameter of `signature` being written in a
+/// partial argument list: the one named last, or for positional arguments
+/// the comma count.
+fn active_argument(args: &str, signature: &SignatureInformation) -> u32 {
+    let Some(current) = last_argument_name(args) else {
+        return argument_index(args);
+    };
+    signature
+        .parameters
+        .iter()
+        .flatten()
+        .position(|param| match &param.label {
+            ParameterLabel::Simple(label) => label.split(':').next() == Some(current),
+            ParameterLabel::LabelOffsets(_) => false,
+        })
+        .unwrap_or(0) as u32
+}
+
+/// Builds a signature labeled `name(arg: Type = default, ...)` from declared
+/// arguments.
+fn arguments_signature(
+    name: &str,
+    description: Option<&bgql_syntax::Description<'_>>,
+    arguments: &[bgql_syntax::InputValueDefinition<'_>],
+    content: &str,
+    interner: &Interner,
+) -> SignatureInformation {
+    let parameters: Vec<_> = arguments
+        .iter()
+        .map(|arg| {
+            let mut label = format!(
+                "{}: {}",
+                interner.get(arg.name.value),
+                hover::format_type(&arg.ty, interner)
+            );
+            if let Some(default) = &arg.default_value {
+                let span = default.span();
+                label.push_str(" = ");
+                label.push_str(&content[span.start as usize..span.end as usize]);
+            }
+            ParameterInformation {
+                label: ParameterLabel::Simple(label),
+                documentation: arg
+                    .description
+                    .as_ref()
+                    .map(|d| Documentation::String(d.value.to_string())),
+            }
+        })
+        .collect();
+
+    let labels: Vec<_> = parameters
+        .iter()
+        .map(|p| match &p.label {
+            ParameterLabel::Simple(label) => label.as_str(),
+            ParameterLabel::LabelOffsets(_) => "",
+        })
+        .collect();
+
+    SignatureInformation {
+        label: format!("{}({})", name, labels.join(", ")),
+        documentation: description.map(|d| Documentation::String(d.value.to_string())),
+        parameters: Some(parameters),
+        active_parameter: None,
+    }
+}
+
+/// Walks out of the selection sets enclosing the end of `before`.
+///
+/// Returns the type the outermost selection set selects from, and the fields
+/// owning the nested selection sets, innermost first.
+fn enclosing_selections(
+    document: &bgql_syntax::Document<'_>,
+    interner: &Interner,
+    before: &str,
+) -> Option<(String, Vec<String>)> {
+    let mut owners = Vec::new();
+    let mut end = before.len();
+
+    loop {
+        let brace = find_unclosed(&before[..end], '{', '}')?;
+
+        // Skip the arguments and directives between the owner and its `{`
+        let mut head = before[..brace].trim_end();
+        loop {
+            if head.ends_with(')') {
+                head = head[..find_unclosed(&head[..head.len() - 1], '(', ')')?].trim_end();
+            }
+            let start = identifier_start(head);
+            if start > 0 && head[..start].ends_with('@') {
+                head = head[..start - 1].trim_end();
+            } else {
+                break;
+            }
+        }
+
+        let start = identifier_start(head);
+        let ident = &head[start..];
+        let prefix = head[..start].trim_end();
+        let keyword = &prefix[identifier_start(prefix)..];
+
+        let root = |operation| root_type_name(document, interner, operation);
+        return match (keyword, ident) {
+            (_, "") | (_, "query") => Some((root(OperationType::Query), owners)),
+            (_, "mutation") => Some((root(OperationType::Mutation), owners)),
+            (_, "subscription") => Some((root(OperationType::Subscription), owners)),
+            ("query", _) => Some((root(OperationType::Query), owners)),
+            ("mutation", _) => Some((root(OperationType::Mutation), owners)),
+            ("subscription", _) => Some((root(OperationType::Subscription), owners)),
+            ("on", _) => Some((ident.to_string(), owners)),
+            _ => {
+                owners.push(ident.to_string());
+                end = start;
+                continue;
+            }
+        };
+    }
+}
+
+/// Returns the root type of `operation`, honouring a schema definition.
+fn root_type_name(
+    document: &bgql_syntax::Document<'_>,
+    interner: &Interner,
+    operation: OperationType,
+) -> String {
+    for def in &document.definitions {
+        if let Definition::Schema(schema) = def {
+            if let Some(op) = schema
+                .operations
+                .iter()
+                .find(|op| op.operation == operation)
+            {
+                return interner.get(op.type_name);
+            }
+        }
+    }
+    match operation {
+        OperationType::Query => "Query",
+        OperationType::Mutation => "Mutation",
+        OperationType::Subscription => "Subscription",
+    }
+    .to_string()
+}
+
+/// Finds a field of an object or interface type defined in `document`.
+fn find_field<'d, 'a>(
+    document: &'d bgql_syntax::Document<'a>,
+    interner: &Interner,
+    type_name: &str,
+    field_name: &str,
+) -> Option<&'d bgql_syntax::FieldDefinition<'a>> {
+    document.definitions.iter().find_map(|def| {
+        let (name, fields) = match def {
+            Definition::Type(TypeDefinition::Object(obj)) => (obj.name, &obj.fields),
+            Definition::Type(TypeDefinition::Interface(iface)) => (iface.name, &iface.fields),
+            _ => return None,
+        };
+        if interner.get(name.value) != type_name {
+            return None;
+        }
+        fields
+            .iter()
+            .find(|f| interner.get(f.name.value) == field_name)
+    })
+}
+
+/// Returns the named type a field type selects into.
+fn named_type(ty: &bgql_syntax::Type<'_>, interner: &Interner) -> String {
+    match ty {
+        bgql_syntax::Type::Named(named) => interner.get(named.name),
+        bgql_syntax::Type::Option(inner, _) | bgql_syntax::Type::List(inner, _) => {
+            named_type(inner, interner)
+        }
+        bgql_syntax::Type::Generic(generic) => interner.get(generic.name),
+        bgql_syntax::Type::Tuple(_) | bgql_syntax::Type::_Phantom(_) => String::new(),
+    }
+}
+
+/// Returns the position of the innermost `open` that is not closed by the end of `text`.
+fn find_unclosed(text: &str, open: char, close: char) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in text.char_indices().rev() {
+        if c == close {
+            depth += 1;
+        } else if c == open {
+            if depth == 0 {
+                return Some(i);
+            }
+            depth -= 1;
+        }
+    }
+    None
+}
+
+/// Returns where the identifier ending `text` (ignoring trailing whitespace) starts.
+fn identifier_start(text: &str) -> usize {
+    let trimmed = text.trim_end();
+    trimmed
+        .bytes()
+        .rposition(|b| !is_identifier_char(b))
+        .map_or(0, |i| i + 1)
+}
+
+/// Returns the index of the argument being written in a partial argument
+/// list: the number of commas outside nested values and strings.
+fn argument_index(args: &str) -> u32 {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut commas = 0;
+
+    for b in args.bytes() {
+        match b {
+            b'"' => in_string = !in_string,
+            _ if in_string => {}
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+            b',' if depth == 0 => commas += 1,
+            _ => {}
+        }
+    }
+    commas
+}
+
+/// Returns the last argument name written in a partial argument list.
+fn last_argument_name(args: &str) -> Option<&str> {
+    let bytes = args.as_bytes();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut last = None;
+
+    for (i, &b) in bytes.iter().enumerate() {
+        match b {
+            b'"' => in_string = !in_string,
+            _ if in_string => {}
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+            b':' if depth == 0 => {
+                let name = args[..i].trim_end();
+                let start = identifier_start(name);
+                if start < name.len() {
+                    last = Some(&name[start..]);
+                }
+            }
+            _ => {}
+        }
+    }
+    last
+}
+
 fn get_directive_signatures(name: &str) -> Vec<SignatureInformation> {
     match name {
         "deprecated" => vec![SignatureInformation {
@@ -851,62 +1213,89 @@ fn get_directive_signatures(name: &str) -> Vec<SignatureInformation> {
 // Semantic Tokens
 // =============================================================================
 
-fn compute_semantic_tokens(
-    document: &bgql_syntax::Document<'_>,
-    content: &str,
-    interner: &Interner,
-) -> Vec<SemanticToken> {
+/// Token types and modifiers, as indices into the legend of `initialize`.
+const CLASS_TOKEN: u32 = 1;
+const ENUM_TOKEN: u32 = 2;
+const INTERFACE_TOKEN: u32 = 3;
+const STRUCT_TOKEN: u32 = 4;
+const PROPERTY_TOKEN: u32 = 8;
+const ENUM_MEMBER_TOKEN: u32 = 9;
+const DECLARATION_MODIFIER: u32 = 1 << 0;
+const DEPRECATED_MODIFIER: u32 = 1 << 2;
+
+/// Highlights type names and their fields and enum values, marking the
+/// members deprecated by `@deprecated`.
+fn compute_semantic_tokens(parsed: &ParsedDocument) -> Vec<SemanticToken> {
+    let (document, interner) = (parsed.document(), parsed.interner());
+    let deprecations = DeprecationIndex::new(document, interner);
     let mut tokens = Vec::new();
     let mut prev_line = 0u32;
     let mut prev_start = 0u32;
+    let mut push = |span: Span, token_type: u32, deprecated: bool| {
+        let pos = offset_to_position(parsed.line_index(), span.start as usize);
+        let delta_line = pos.line - prev_line;
+        let delta_start = if delta_line == 0 {
+            pos.character - prev_start
+        } else {
+            pos.character
+        };
+        tokens.push(SemanticToken {
+            delta_line,
+            delta_start,
+            length: span.end - span.start,
+            token_type,
+            token_modifiers_bitset: if deprecated {
+                DECLARATION_MODIFIER | DEPRECATED_MODIFIER
+            } else {
+                DECLARATION_MODIFIER
+            },
+        });
+        prev_line = pos.line;
+        prev_start = pos.character;
+    };
 
     for def in &document.definitions {
-        match def {
-            Definition::Type(type_def) => {
-                let (span, _name) = match type_def {
-                    TypeDefinition::Object(obj) => (obj.name.span, interner.get(obj.name.value)),
-                    TypeDefinition::Interface(iface) => {
-                        (iface.name.span, interner.get(iface.name.value))
-                    }
-                    TypeDefinition::Enum(e) => (e.name.span, interner.get(e.name.value)),
-                    TypeDefinition::Union(u) => (u.name.span, interner.get(u.name.value)),
-                    TypeDefinition::Input(i) => (i.name.span, interner.get(i.name.value)),
-                    TypeDefinition::Scalar(s) => (s.name.span, interner.get(s.name.value)),
-                    TypeDefinition::Opaque(o) => (o.name.span, interner.get(o.name.value)),
-                    TypeDefinition::TypeAlias(a) => (a.name.span, interner.get(a.name.value)),
-                    TypeDefinition::InputUnion(iu) => (iu.name.span, interner.get(iu.name.value)),
-                    TypeDefinition::InputEnum(ie) => (ie.name.span, interner.get(ie.name.value)),
-                };
-
-                let pos = offset_to_position(content, span.start as usize);
-                let length = (span.end - span.start) as u32;
-
-                let delta_line = pos.line - prev_line;
-                let delta_start = if delta_line == 0 {
-                    pos.character - prev_start
-                } else {
-                    pos.character
-                };
-
-                let token_type = match type_def {
-                    TypeDefinition::Interface(_) => 3, // INTERFACE
-                    TypeDefinition::Enum(_) => 2,      // ENUM
-                    TypeDefinition::Input(_) => 4,     // STRUCT
-                    _ => 1,                            // CLASS
-                };
-
-                tokens.push(SemanticToken {
-                    delta_line,
-                    delta_start,
-                    length,
-                    token_type,
-                    token_modifiers_bitset: 1, // DECLARATION
-                });
-
-                prev_line = pos.line;
-                prev_start = pos.character;
+        let Definition::Type(type_def) = def else {
+            continue;
+        };
+        let name = type_def.name();
+        let type_name = interner.get(name.value);
+        match type_def {
+            TypeDefinition::Object(obj) => {
+                push(name.span, CLASS_TOKEN, false);
+                for field in &obj.fields {
+                    let field_name = interner.get(field.name.value);
+                    let deprecated = deprecations.field(&type_name, &field_name).is_deprecated;
+                    push(field.name.span, PROPERTY_TOKEN, deprecated);
+                }
             }
-            _ => {}
+            TypeDefinition::Interface(iface) => {
+                push(name.span, INTERFACE_TOKEN, false);
+                for field in &iface.fields {
+                    let field_name = interner.get(field.name.value);
+                    let deprecated = deprecations.field(&type_name, &field_name).is_deprecated;
+                    push(field.name.span, PROPERTY_TOKEN, deprecated);
+                }
+            }
+            TypeDefinition::Input(input) => {
+                push(name.span, STRUCT_TOKEN, false);
+                for field in &input.fields {
+                    let field_name = interner.get(field.name.value);
+                    let deprecated = deprecations.field(&type_name, &field_name).is_deprecated;
+                    push(field.name.span, PROPERTY_TOKEN, deprecated);
+                }
+            }
+            TypeDefinition::Enum(e) => {
+                push(name.span, ENUM_TOKEN, false);
+                for value in &e.values {
+                    let value_name = interner.get(value.name.value);
+                    let deprecated = deprecations
+                        .enum_value(&type_name, &value_name)
+                        .is_deprecated;
+                    push(value.name.span, ENUM_MEMBER_TOKEN, deprecated);
+                }
+            }
+            _ => push(name.span, CLASS_TOKEN, false),
         }
     }
 
@@ -917,11 +1306,8 @@ fn compute_semantic_tokens(
 // Inlay Hints
 // =============================================================================
 
-fn compute_inlay_hints(
-    document: &bgql_syntax::Document<'_>,
-    content: &str,
-    interner: &Interner,
-) -> Vec<InlayHint> {
+fn compute_inlay_hints(parsed: &ParsedDocument) -> Vec<InlayHint> {
+    let (document, content, interner) = (parsed.document(), parsed.source(), parsed.interner());
     let mut hints = Vec::new();
 
     for def in &document.definitions {
@@ -934,7 +1320,7 @@ fn compute_inlay_hints(
                     .map(|i| interner.get(i.value))
                     .collect();
                 let hint_text = format!(" impl {}", iface_names.len());
-                let pos = offset_to_position(content, obj.name.span.end as usize);
+                let pos = offset_to_position(parsed.line_index(), obj.name.span.end as usize);
 
                 hints.push(InlayHint {
                     position: pos,
@@ -958,7 +1344,7 @@ fn compute_inlay_hints(
                 let obj_text = &content[obj.span.start as usize..obj.span.end as usize];
                 if let Some(brace_pos) = obj_text.find('{') {
                     let abs_pos = obj.span.start as usize + brace_pos + 1;
-                    let pos = offset_to_position(content, abs_pos);
+                    let pos = offset_to_position(parsed.line_index(), abs_pos);
                     hints.push(InlayHint {
                         position: pos,
                         label: InlayHintLabel::String(hint_text),
@@ -977,11 +1363,144 @@ fn compute_inlay_hints(
     hints
 }
 
+// =============================================================================
+// Folding Ranges
+// =============================================================================
+
+/// Folds the bodies of object, interface, input and enum types, multi-line
+/// block strings and runs of line comments.
+fn compute_folding_ranges(parsed: &ParsedDocument) -> Vec<FoldingRange> {
+    // The AST keeps neither comments nor braces, so lex the source for them
+    let mut lexer = Lexer::with_trivia(parsed.source(), parsed.interner());
+    let tokens: Vec<_> = std::iter::from_fn(|| Some(lexer.next_token()))
+        .take_while(|token| !token.is_eof())
+        .collect();
+    // Each `{ ... }` pair, ordered by the `{`
+    let mut braces = Vec::new();
+    let mut open = Vec::new();
+    for token in &tokens {
+        match token.kind {
+            TokenKind::LBrace => open.push(token.span),
+            TokenKind::RBrace => {
+                if let Some(start) = open.pop() {
+                    braces.push(start.merge(token.span));
+                }
+            }
+            _ => {}
+        }
+    }
+    braces.sort_by_key(|span| span.start);
+
+    let mut ranges = Vec::new();
+    let mut fold = |span: Span, kind: Option<FoldingRangeKind>| {
+        let start_line = offset_to_position(parsed.line_index(), span.start as usize).line;
+        let end_line = offset_to_position(parsed.line_index(), span.end as usize).line;
+        if end_line > start_line {
+            ranges.push(FoldingRange {
+                start_line,
+                end_line,
+                kind,
+                ..Default::default()
+            });
+        }
+    };
+
+    let mut bodies = Vec::new();
+    collect_body_spans(&parsed.document().definitions, &braces, &mut bodies);
+    for body in bodies {
+        fold(body, None);
+    }
+
+    // A blank line ends a run of comments
+    let mut comments: Option<Span> = None;
+    let mut newlines = 0;
+    for token in &tokens {
+        match token.kind {
+            TokenKind::Comment => {
+                if newlines > 1 {
+                    if let Some(run) = comments.take() {
+                        fold(run, Some(FoldingRangeKind::Comment));
+                    }
+                }
+                comments = Some(comments.map_or(token.span, |run| run.merge(token.span)));
+                newlines = 0;
+            }
+            TokenKind::Newline => newlines += 1,
+            TokenKind::Whitespace => {}
+            kind => {
+                if let Some(run) = comments.take() {
+                    fold(run, Some(FoldingRangeKind::Comment));
+                }
+                if kind == TokenKind::BlockStringLiteral {
+                    fold(token.span, Some(FoldingRangeKind::Comment));
+                }
+            }
+        }
+    }
+    if let Some(run) = comments {
+        fold(run, Some(FoldingRangeKind::Comment));
+    }
+
+    ranges.sort_by_key(|range| range.start_line);
+    ranges
+}
+
+/// Collects the spans of the braced bodies of `definitions`, including
+/// module blocks and the definitions in them.
+///
+/// `braces` are the spans of every `{ ... }` in the source, in order.
+fn collect_body_spans(definitions: &[Definition<'_>], braces: &[Span], out: &mut Vec<Span>) {
+    for definition in definitions {
+        let header_end = match definition {
+            Definition::Type(type_def) | Definition::TypeExtension(type_def) => {
+                let (implements, type_params): (&[Name], &[TypeParameter<'_>]) = match type_def {
+                    TypeDefinition::Object(obj) => (&obj.implements, &obj.type_params),
+                    TypeDefinition::Interface(iface) => (&iface.implements, &iface.type_params),
+                    TypeDefinition::Input(_) | TypeDefinition::Enum(_) => (&[], &[]),
+                    _ => continue,
+                };
+                // The body follows the last directive, interface or type
+                // parameter, or else the name
+                type_def
+                    .directives()
+                    .last()
+                    .map(|directive| directive.span)
+                    .or_else(|| implements.last().map(|name| name.span))
+                    .or_else(|| type_params.last().map(|param| param.span))
+                    .unwrap_or(type_def.name().span)
+                    .end
+            }
+            Definition::Module(module) => {
+                let Some(body) = &module.body else {
+                    continue;
+                };
+                collect_body_spans(body, braces, out);
+                module.name.span.end
+            }
+            _ => continue,
+        };
+
+        let next = braces.partition_point(|body| body.start < header_end);
+        if let Some(&body) = braces
+            .get(next)
+            .filter(|body| body.end <= definition.span().end)
+        {
+            out.push(body);
+        }
+    }
+}
+
 // =============================================================================
 // Code Actions (Quick Fixes)
 // =============================================================================
 
-fn generate_quick_fix(content: &str, diagnostic: &Diagnostic, uri: &Url) -> Option<CodeAction> {
+fn generate_quick_fixes(content: &str, diagnostic: &Diagnostic, uri: &Url) -> Vec<CodeAction> {
+    if diagnostic.code == Some(NumberOrString::String(codes::DUPLICATE_TYPE.to_string())) {
+        return duplicate_type_fixes(content, diagnostic, uri);
+    }
+
+    let mut actions = replacement_fixes(diagnostic, uri);
+
     // Check for "Undefined type" errors - suggest adding the type
     if diagnostic.message.contains("Undefined type") {
         // Extract the type name from the message
@@ -1008,7 +1527,8 @@ fn generate_quick_fix(content: &str, diagnostic: &Diagnostic, uri: &Url) -> Opti
                     }],
                 );
 
-                return Some(CodeAction {
+                // Prefer fixing the name when it looks like a typo
+                actions.push(CodeAction {
                     title: format!("Create type `{}`", type_name),
                     kind: Some(CodeActionKind::QUICKFIX),
                     diagnostics: Some(vec![diagnostic.clone()]),
@@ -1016,9 +1536,10 @@ fn generate_quick_fix(content: &str, diagnostic: &Diagnostic, uri: &Url) -> Opti
                         changes: Some(changes),
                         ..Default::default()
                     }),
-                    is_preferred: Some(true),
+                    is_preferred: Some(actions.is_empty()),
                     ..Default::default()
                 });
+                return actions;
             }
         }
     }
@@ -1029,20 +1550,161 @@ fn generate_quick_fix(content: &str, diagnostic: &Diagnostic, uri: &Url) -> Opti
             if let Some(end) = diagnostic.message[start + 1..].find('`') {
                 let field_name = &diagnostic.message[start + 1..start + 1 + end];
 
-                return Some(CodeAction {
+                return vec![CodeAction {
                     title: format!("Add field `{}`", field_name),
                     kind: Some(CodeActionKind::QUICKFIX),
                     diagnostics: Some(vec![diagnostic.clone()]),
                     // Note: actual edit would need more context about the interface
                     ..Default::default()
-                });
+                }];
             }
         }
     }
 
+    actions
+}
+
+/// Offers to apply the replacements carried in the diagnostic's `data`,
+/// preferring the only one when there is a single suggestion.
+fn replacement_fixes(diagnostic: &Diagnostic, uri: &Url) -> Vec<CodeAction> {
+    let suggestions: Vec<SuggestionData> = diagnostic
+        .data
+        .as_ref()
+        .and_then(|data| data.get("suggestions"))
+        .and_then(|suggestions| serde_json::from_value(suggestions.clone()).ok())
+        .unwrap_or_default();
+    let preferred = suggestions.len() == 1;
+
+    suggestions
+        .into_iter()
+        .map(|suggestion| {
+            let mut changes = std::collections::HashMap::new();
+            changes.insert(
+                uri.clone(),
+                vec![TextEdit {
+                    range: suggestion.range,
+                    new_text: suggestion.replacement.clone(),
+                }],
+            );
+            CodeAction {
+                title: format!("Change to `{}`", suggestion.replacement),
+                kind: Some(CodeActionKind::QUICKFIX),
+                diagnostics: Some(vec![diagnostic.clone()]),
+                edit: Some(WorkspaceEdit {
+                    changes: Some(changes),
+                    ..Default::default()
+                }),
+                is_preferred: Some(preferred),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// Offers to rename or remove a duplicate type definition.
+///
+/// The diagnostic range points at the duplicate's name, which is matched
+/// against the parsed document to recover the full definition span.
+fn duplicate_type_fixes(content: &str, diagnostic: &Diagnostic, uri: &Url) -> Vec<CodeAction> {
+    let interner = Interner::new();
+    let result = parse(content, &interner);
+    let index = LineIndex::new(content);
+    let offset = position_to_offset(&index, diagnostic.range.start) as u32;
+
+    let Some((name, definition_span)) = find_type_definition(&result.document.definitions, offset)
+    else {
+        return Vec::new();
+    };
+    let type_name = interner.get(name.value);
+
+    let edit = |range: Range, new_text: String| {
+        let mut changes = std::collections::HashMap::new();
+        changes.insert(uri.clone(), vec![TextEdit { range, new_text }]);
+        Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        })
+    };
+
+    let new_name = format!("{}2", type_name);
+    vec![
+        CodeAction {
+            title: format!("Rename duplicate to `{}`", new_name),
+            kind: Some(CodeActionKind::QUICKFIX),
+            diagnostics: Some(vec![diagnostic.clone()]),
+            edit: edit(span_to_range(name.span, &index), new_name),
+            ..Default::default()
+        },
+        CodeAction {
+            title: format!("Remove duplicate `{}`", type_name),
+            kind: Some(CodeActionKind::QUICKFIX),
+            diagnostics: Some(vec![diagnostic.clone()]),
+            edit: edit(
+                span_to_range(removal_span(content, definition_span), &index),
+                String::new(),
+            ),
+            ..Default::default()
+        },
+    ]
+}
+
+/// Finds the type definition whose name starts at `offset`, searching inline modules too.
+///
+/// Returns the name and the span of the whole definition, including its description.
+fn find_type_definition(definitions: &[Definition<'_>], offset: u32) -> Option<(Name, Span)> {
+    for def in definitions {
+        match def {
+            Definition::Type(type_def) => {
+                let (name, description, span) = match type_def {
+                    TypeDefinition::Object(obj) => (obj.name, &obj.description, obj.span),
+                    TypeDefinition::Interface(iface) => {
+                        (iface.name, &iface.description, iface.span)
+                    }
+                    TypeDefinition::Enum(e) => (e.name, &e.description, e.span),
+                    TypeDefinition::Union(u) => (u.name, &u.description, u.span),
+                    TypeDefinition::Input(inp) => (inp.name, &inp.description, inp.span),
+                    TypeDefinition::Scalar(s) => (s.name, &s.description, s.span),
+                    TypeDefinition::Opaque(o) => (o.name, &o.description, o.span),
+                    TypeDefinition::TypeAlias(a) => (a.name, &a.description, a.span),
+                    TypeDefinition::InputUnion(iu) => (iu.name, &iu.description, iu.span),
+                    TypeDefinition::InputEnum(ie) => (ie.name, &ie.description, ie.span),
+                };
+                if name.span.start == offset {
+                    let span = description
+                        .as_ref()
+                        .map_or(span, |desc| desc.span.merge(span));
+                    return Some((name, span));
+                }
+            }
+            Definition::Module(module) => {
+                if let Some(found) = module
+                    .body
+                    .as_deref()
+                    .and_then(|body| find_type_definition(body, offset))
+                {
+                    return Some(found);
+                }
+            }
+            _ => {}
+        }
+    }
+
     None
 }
 
+/// Widens a definition span to the start of its line when only indentation
+/// or a `pub` modifier precedes it, so removal leaves no stray text behind.
+fn removal_span(content: &str, span: Span) -> Span {
+    let start = span.start as usize;
+    let line_start = content[..start].rfind('\n').map_or(0, |pos| pos + 1);
+    let prefix = content[line_start..start].trim();
+    if prefix.is_empty() || prefix == "pub" {
+        Span::new(line_start as u32, span.end)
+    } else {
+        span
+    }
+}
+
 /// Runs the language server.
 pub async fn run_server() {
     let stdin = tokio::io::stdin();
@@ -1055,6 +1717,7 @@ pub async fn run_server() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bgql_semantic::{checker, hir::HirDatabase, types::TypeRegistry};
 
     #[test]
     fn test_get_word_at_offset() {
@@ -1073,4 +1736,480 @@ mod tests {
         assert!(!is_identifier_char(b' '));
         assert!(!is_identifier_char(b':'));
     }
+
+    #[test]
+    fn test_field_argument_signature_help() {
+        let content = r#"
+type User {
+  id: ID
+  posts(
+    first: Int = 10
+    after: Option<String>
+  ): List<Post>
+}
+
+type Post {
+  id: ID
+}
+
+type Query {
+  user(id: ID): Option<User>
+}
+
+query GetUser {
+  user(id: "1") {
+    posts(first: 5, after: "x") {
+      id
+    }
+  }
+}
+"#;
+        let parsed = ParsedDocument::parse(content);
+        let help = |needle: &str| {
+            let offset = content.rfind(needle).unwrap() + needle.len();
+            get_signature_help(content, offset, parsed.document(), parsed.interner())
+        };
+
+        let signatures = help("user(");
+        assert_eq!(signatures.len(), 1);
+        assert_eq!(signatures[0].label, "user(id: ID)");
+        let parameters = signatures[0].parameters.as_ref().unwrap();
+        assert_eq!(
+            parameters[0].label,
+            ParameterLabel::Simple("id: ID".to_string())
+        );
+        assert_eq!(signatures[0].active_parameter, Some(0));
+
+        let signatures = help("posts(first: 5, after: ");
+        assert_eq!(
+            signatures[0].label,
+            "posts(first: Int = 10, after: Option<String>)"
+        );
+        assert_eq!(signatures[0].active_parameter, Some(1));
+    }
+
+    #[test]
+    fn test_user_directive_signature_help() {
+        let content = r#"
+"""Caches the field."""
+directive @cached(ttl: Int = 60, scope: String, tags: List<String>) on FIELD_DEFINITION
+
+type Query {
+  user: String @cached(ttl: 30, tags: ["a", "b"], scope: "x")
+  feed: String @cached(10, "y", ["c", "d"])
+  post: String @deprecated(reason: "gone")
+}
+"#;
+        let parsed = ParsedDocument::parse(content);
+        let help = |needle: &str| {
+            let offset = content.rfind(needle).unwrap() + needle.len();
+            get_signature_help(content, offset, parsed.document(), parsed.interner())
+        };
+
+        let signatures = help("@cached(");
+        assert_eq!(signatures.len(), 1);
+        assert_eq!(
+            signatures[0].label,
+            "@cached(ttl: Int = 60, scope: String, tags: List<String>)"
+        );
+        let labels: Vec<_> = signatures[0]
+            .parameters
+            .iter()
+            .flatten()
+            .map(|p| p.label.clone())
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                ParameterLabel::Simple("ttl: Int = 60".to_string()),
+                ParameterLabel::Simple("scope: String".to_string()),
+                ParameterLabel::Simple("tags: List<String>".to_string()),
+            ]
+        );
+        assert_eq!(
+            signatures[0].documentation,
+            Some(Documentation::String("Caches the field.".to_string()))
+        );
+        assert_eq!(signatures[0].active_parameter, Some(0));
+
+        // Named arguments select their parameter, wherever they're written
+        assert_eq!(help("ttl: 3")[0].active_parameter, Some(0));
+        assert_eq!(help("tags: [\"a\", ")[0].active_parameter, Some(2));
+        assert_eq!(help("scope: ")[0].active_parameter, Some(1));
+
+        // Positional arguments are counted, skipping commas inside values
+        assert_eq!(help("@cached(10, ")[0].active_parameter, Some(1));
+        assert_eq!(help("[\"c\", ")[0].active_parameter, Some(2));
+
+        // Built-in directives are still known
+        let signatures = help("@deprecated(");
+        assert_eq!(signatures[0].label, "@deprecated(reason: String)");
+    }
+
+    #[test]
+    fn test_secondary_labels_become_related_information() {
+        let content =
+            "interface Node {\n  id: ID\n}\ntype User implements Node {\n  id: String\n}\n";
+        let uri = Url::parse("file:///schema.bgql").unwrap();
+        let diag = bgql_core::Diagnostic::error(codes::INTERFACE_FIELD_TYPE_MISMATCH, "mismatch")
+            .with_span(Span::new(58, 68), "found `String`")
+            .with_span(Span::new(19, 25), "expected type declared here")
+            .with_note("`User` implements interface `Node`");
+
+        let lsp = to_lsp_diagnostic(&diag, &LineIndex::new(content), &uri).unwrap();
+        assert_eq!(lsp.range.start, Position::new(4, 2));
+        assert_eq!(
+            lsp.message,
+            "mismatch\nnote: `User` implements interface `Node`"
+        );
+
+        let related = lsp.related_information.unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].location.range.start, Position::new(1, 2));
+        assert_eq!(related[0].message, "expected type declared here");
+    }
+
+    #[test]
+    fn test_duplicate_type_quick_fix() {
+        let content = "type User {\n  id: ID\n}\n\ntype User {\n  name: String\n}\n\ntype Post {\n  id: ID\n}\n";
+        let uri = Url::parse("file:///schema.bgql").unwrap();
+        let name_start = content.rfind("User").unwrap();
+        let index = LineIndex::new(content);
+        let diagnostic = Diagnostic {
+            range: Range {
+                start: offset_to_position(&index, name_start),
+                end: offset_to_position(&index, name_start + 4),
+            },
+            code: Some(NumberOrString::String(codes::DUPLICATE_TYPE.to_string())),
+            message: "Duplicate type definition `User`".to_string(),
+            ..Default::default()
+        };
+
+        let actions = generate_quick_fixes(content, &diagnostic, &uri);
+        let titles: Vec<_> = actions.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec!["Rename duplicate to `User2`", "Remove duplicate `User`"]
+        );
+
+        let remove = &actions[1];
+        let changes = remove.edit.as_ref().unwrap().changes.as_ref().unwrap();
+        let edit = &changes[&uri][0];
+        assert_eq!(edit.range.start, Position::new(4, 0));
+        assert_eq!(edit.range.end, Position::new(8, 0));
+        assert_eq!(edit.new_text, "");
+    }
+
+    #[test]
+    fn test_undefined_type_replacement_quick_fix() {
+        let content = "type Profile {\n  bio: String\n}\n\ntype User {\n  profile: Profiel\n}\n";
+        let uri = Url::parse("file:///schema.bgql").unwrap();
+        let parsed = ParsedDocument::parse(content);
+        let types = TypeRegistry::new();
+        let hir = HirDatabase::new();
+        let result = checker::check(parsed.document(), &types, &hir, parsed.interner());
+        let undefined = result
+            .diagnostics
+            .iter()
+            .find(|d| d.code == codes::UNDEFINED_TYPE)
+            .unwrap();
+        let diagnostic = to_lsp_diagnostic(undefined, parsed.line_index(), &uri).unwrap();
+
+        let actions = generate_quick_fixes(content, &diagnostic, &uri);
+        let titles: Vec<_> = actions
+            .iter()
+            .map(|a| (a.title.as_str(), a.is_preferred))
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                ("Change to `Profile`", Some(true)),
+                ("Create type `Profiel`", Some(false)),
+            ]
+        );
+
+        let changes = actions[0].edit.as_ref().unwrap().changes.as_ref().unwrap();
+        let edit = &changes[&uri][0];
+        assert_eq!(edit.range.start, Position::new(5, 11));
+        assert_eq!(edit.range.end, Position::new(5, 18));
+        assert_eq!(edit.new_text, "Profile");
+    }
+
+    #[test]
+    fn test_workspace_symbols() {
+        let users = Url::parse("file:///users.bgql").unwrap();
+        let posts = Url::parse("file:///posts.bgql").unwrap();
+        let mut state = ServerState::new();
+        state.open_document(
+            users.clone(),
+            "type User {\n  id: ID\n  userName: String\n}\n".to_string(),
+            1,
+        );
+        state.open_document(
+            posts.clone(),
+            "type Post {\n  author: User\n  authorName: String\n}\n".to_string(),
+            1,
+        );
+
+        let symbols = state.workspace_symbols("uname");
+        let found: Vec<_> = symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.location.uri.clone()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![("authorName", posts.clone()), ("userName", users.clone())]
+        );
+        assert_eq!(symbols[0].container_name.as_deref(), Some("Post"));
+        assert_eq!(symbols[1].location.range.start, Position::new(2, 2));
+    }
+
+    #[test]
+    fn test_document_symbol_children() {
+        let content = "type User {\n  id: ID\n  posts(first: Int): List<Post>\n}\n\nenum Role {\n  ADMIN\n}\n\nunion Result = User | Role\n";
+        let parsed = ParsedDocument::parse(content);
+        let table = SymbolTable::from_document(parsed.document(), parsed.interner());
+        let symbols: Vec<_> = table
+            .root_symbols
+            .iter()
+            .map(|s| symbol_to_document_symbol(s, content, parsed.line_index()))
+            .collect();
+
+        let fields = symbols[0].children.as_ref().unwrap();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].name, "id");
+        assert_eq!(fields[0].kind, SymbolKind::FIELD);
+        assert_eq!(fields[0].detail.as_deref(), Some("ID"));
+        assert_eq!(
+            fields[0].range,
+            Range::new(Position::new(1, 2), Position::new(1, 8))
+        );
+        assert_eq!(
+            fields[0].selection_range,
+            Range::new(Position::new(1, 2), Position::new(1, 4))
+        );
+        assert_eq!(fields[1].detail.as_deref(), Some("List<Post>"));
+        let arguments = fields[1].children.as_ref().unwrap();
+        assert_eq!(arguments[0].name, "first");
+        assert_eq!(arguments[0].kind, SymbolKind::VARIABLE);
+
+        let values = symbols[1].children.as_ref().unwrap();
+        assert_eq!(values[0].name, "ADMIN");
+        assert_eq!(values[0].kind, SymbolKind::ENUM_MEMBER);
+
+        let members = symbols[2].children.as_ref().unwrap();
+        let names: Vec<_> = members.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["User", "Role"]);
+        assert_eq!(members[0].kind, SymbolKind::ENUM_MEMBER);
+        assert_eq!(
+            members[1].selection_range,
+            Range::new(Position::new(9, 22), Position::new(9, 26))
+        );
+    }
+
+    #[test]
+    fn test_type_body_folding_range() {
+        let content = "\"\"\"\nA user.\n\"\"\"\ntype User {\n  id: ID\n  name: String\n  email: String\n}\n\nunion Result = User | Error\n";
+        let ranges = compute_folding_ranges(&ParsedDocument::parse(content));
+
+        let bodies: Vec<_> = ranges.iter().filter(|r| r.kind.is_none()).collect();
+        assert_eq!(bodies.len(), 1);
+        assert_eq!(bodies[0].start_line, 3);
+        assert_eq!(bodies[0].end_line, 7);
+
+        let description = ranges.iter().find(|r| r.kind.is_some()).unwrap();
+        assert_eq!((description.start_line, description.end_line), (0, 2));
+    }
+
+    #[test]
+    fn test_module_and_extension_folding_ranges() {
+        let content = "mod users {\n  type User @key(fields: { id: true }) {\n    id: ID\n  }\n}\n\nextend type User {\n  # {\n  name: String\n}\n";
+        let ranges = compute_folding_ranges(&ParsedDocument::parse(content));
+
+        let bodies: Vec<_> = ranges
+            .iter()
+            .filter(|r| r.kind.is_none())
+            .map(|r| (r.start_line, r.end_line))
+            .collect();
+        assert_eq!(bodies, [(0, 4), (1, 3), (6, 9)]);
+    }
+
+    #[test]
+    fn test_parse_cache_reused_until_change() {
+        let uri = Url::parse("file:///schema.bgql").unwrap();
+        let mut state = ServerState::new();
+        state.open_document(uri.clone(), "type User {\n  id: ID\n}\n".to_string(), 1);
+
+        let doc = state.get_document(&uri).unwrap();
+        assert!(!doc.is_parsed());
+
+        let hover_user = |doc: &crate::state::DocumentState| {
+            doc.with_parsed(|parsed| {
+                let ptr = parsed.document().definitions.as_ptr() as usize;
+                let hover =
+                    hover::get_hover(parsed.source(), 6, parsed.document(), parsed.interner());
+                (ptr, hover.is_some())
+            })
+        };
+        let (first, found) = hover_user(doc);
+        assert!(found);
+        assert!(doc.is_parsed());
+        let (second, _) = hover_user(doc);
+        assert_eq!(first, second);
+
+        state.update_document(&uri, "type Post {\n  id: ID\n}\n".to_string(), 2);
+        let doc = state.get_document(&uri).unwrap();
+        assert!(!doc.is_parsed());
+        doc.with_parsed(|parsed| {
+            assert!(parsed.source().starts_with("type Post"));
+        });
+    }
+
+    #[test]
+    fn test_type_check_reuses_unchanged_definitions() {
+        let uri = Url::parse("file:///schema.bgql").unwrap();
+        let source = "directive @log(level: Level) on FIELD_DEFINITION\nenum Level { INFO WARN }\ntype Query { users: String @log(level: WARN) post: Post }\ntype Post { title: Strng }\n";
+        let mut state = ServerState::new();
+        state.open_document(uri.clone(), source.to_string(), 1);
+
+        let check = |state: &ServerState| {
+            let doc = state.get_document(&uri).unwrap();
+            doc.with_parsed(|parsed| {
+                doc.type_check(parsed, |result| {
+                    let codes: Vec<_> = result.diagnostics.iter().map(|d| d.code.clone()).collect();
+                    (result.checked_types.clone(), codes)
+                })
+            })
+        };
+        let (checked, found) = check(&state);
+        assert_eq!(checked, ["Level", "Query", "Post"]);
+        assert_eq!(found, [codes::UNDEFINED_TYPE]);
+
+        // Only `Level` changed; `Query` passes it to `@log`, and the error
+        // in `Post` is reused
+        state.update_document(&uri, source.replace("INFO WARN", "INFO"), 2);
+        let (checked, found) = check(&state);
+        assert_eq!(checked, ["Level", "Query"]);
+        assert_eq!(found.len(), 2);
+        assert!(found.iter().any(|code| code == codes::UNDEFINED_TYPE));
+    }
+
+    #[test]
+    fn test_semantic_tokens_mark_deprecated_members() {
+        let parsed = ParsedDocument::parse(
+            "type User {\n  name: String\n  username: String @deprecated\n}\nenum Role {\n  ADMIN\n  ROOT @deprecated(reason: \"Use ADMIN\")\n}\n",
+        );
+        let tokens: Vec<_> = compute_semantic_tokens(&parsed)
+            .iter()
+            .map(|token| (token.token_type, token.token_modifiers_bitset))
+            .collect();
+        assert_eq!(
+            tokens,
+            [
+                (CLASS_TOKEN, DECLARATION_MODIFIER),
+                (PROPERTY_TOKEN, DECLARATION_MODIFIER),
+                (PROPERTY_TOKEN, DECLARATION_MODIFIER | DEPRECATED_MODIFIER),
+                (ENUM_TOKEN, DECLARATION_MODIFIER),
+                (ENUM_MEMBER_TOKEN, DECLARATION_MODIFIER),
+                (
+                    ENUM_MEMBER_TOKEN,
+                    DECLARATION_MODIFIER | DEPRECATED_MODIFIER
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_value_completions_sort_deprecated_last() {
+        let content = "enum Role {\n  ROOT @deprecated\n  ADMIN\n}\ntype Query {\n  users(role: Role = ): String\n}\n";
+        let parsed = ParsedDocument::parse(content);
+        let uri = Url::parse("file:///test.bgql").unwrap();
+        let mut items = completion::get_completions(
+            &uri,
+            content,
+            parsed.line_index(),
+            Position::new(5, 21),
+            parsed.document(),
+            parsed.interner(),
+        );
+        items.sort_by(|a, b| a.sort_text.cmp(&b.sort_text));
+
+        let labels: Vec<_> = items.iter().map(|item| item.label.as_str()).collect();
+        assert_eq!(labels, ["ADMIN", "ROOT"]);
+        assert_eq!(items[0].tags, None);
+        assert_eq!(items[1].tags, Some(vec![CompletionItemTag::DEPRECATED]));
+    }
+
+    #[test]
+    fn test_type_skeleton_snippet() {
+        let completions_at = |content: &str, position: Position| {
+            let parsed = ParsedDocument::parse(content);
+            let uri = Url::parse("file:///test.bgql").unwrap();
+            completion::get_completions(
+                &uri,
+                content,
+                parsed.line_index(),
+                position,
+                parsed.document(),
+                parsed.interner(),
+            )
+        };
+        let expected = "type ${1:Name} {\n  ${2:field}: ${3:Type}\n}";
+
+        let completions = completions_at("", Position::new(0, 0));
+        let snippet = completions
+            .iter()
+            .find(|c| c.label == "type" && c.kind == Some(CompletionItemKind::SNIPPET))
+            .unwrap();
+        assert_eq!(snippet.insert_text.as_deref(), Some(expected));
+        assert_eq!(snippet.insert_text_format, Some(InsertTextFormat::SNIPPET));
+
+        // After `type `, the snippet replaces the keyword
+        let completions = completions_at("type User { id: ID }\n\ntype ", Position::new(2, 5));
+        let snippet = completions
+            .iter()
+            .find(|c| c.label == "type" && c.kind == Some(CompletionItemKind::SNIPPET))
+            .unwrap();
+        let Some(CompletionTextEdit::Edit(edit)) = &snippet.text_edit else {
+            panic!("expected a text edit");
+        };
+        assert_eq!(
+            edit.range,
+            Range::new(Position::new(2, 0), Position::new(2, 5))
+        );
+        assert_eq!(edit.new_text, expected);
+    }
+
+    #[test]
+    fn test_completion_resolve_documentation() {
+        let content = "\"\"\"\nA registered user.\n\"\"\"\ntype User implements Node {\n  id: ID\n}\n\ninterface Node {\n  id: ID\n}\n\ntype Post {\n  author: \n}\n";
+        let interner = Interner::new();
+        let result = parse(content, &interner);
+        let uri = Url::parse("file:///test.bgql").unwrap();
+
+        let completions = completion::get_completions(
+            &uri,
+            content,
+            &LineIndex::new(content),
+            Position::new(12, 10),
+            &result.document,
+            &interner,
+        );
+        let item = completions.into_iter().find(|c| c.label == "User").unwrap();
+        assert!(item.documentation.is_none());
+
+        let resolved = completion::resolve_completion(item, &result.document, &interner);
+        assert_eq!(
+            resolved.detail.as_deref(),
+            Some("type User implements Node { ... }")
+        );
+        let Some(Documentation::MarkupContent(documentation)) = resolved.documentation else {
+            panic!("expected markdown documentation");
+        };
+        assert_eq!(documentation.kind, MarkupKind::Markdown);
+        assert!(documentation
+            .value
+            .starts_with("```bgql\ntype User implements Node { ... }\n```"));
+        assert!(documentation.value.ends_with("A registered user."));
+    }
 }
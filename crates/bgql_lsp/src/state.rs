@@ -1,23 +1,239 @@
 //! Document state management.
 
+use crate::symbols::SymbolTable;
+use bgql_core::{DiagnosticBag, Interner, LineIndex};
+use bgql_semantic::checker::{CheckResult, TypeChecker};
+use bgql_semantic::{HirDatabase, TypeRegistry};
+use bgql_syntax::{parse, Definition, Document};
 use std::collections::HashMap;
-use tower_lsp::lsp_types::Url;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use tower_lsp::lsp_types::{SymbolInformation, Url};
+use yoke::{Yoke, Yokeable};
+
+/// A parsed document that owns the source and interner its AST borrows from.
+pub struct ParsedDocument {
+    parsed: Yoke<Parsed<'static>, Box<Sources>>,
+}
+
+/// The data a [`ParsedDocument`]'s AST borrows from.
+struct Sources {
+    source: String,
+    interner: Interner,
+    line_index: LineIndex,
+}
+
+/// The parser output, borrowing from [`Sources`].
+#[derive(Yokeable)]
+struct Parsed<'a> {
+    document: Document<'a>,
+    diagnostics: DiagnosticBag,
+}
+
+impl ParsedDocument {
+    /// Parses `source` into an owned document.
+    pub fn parse(source: &str) -> Self {
+        let sources = Box::new(Sources {
+            source: source.to_string(),
+            interner: Interner::new(),
+            line_index: LineIndex::new(source),
+        });
+        let parsed = Yoke::attach_to_cart(sources, |sources| {
+            let result = parse(&sources.source, &sources.interner);
+            Parsed {
+                document: result.document,
+                diagnostics: result.diagnostics,
+            }
+        });
+        Self { parsed }
+    }
+
+    /// Returns the parsed AST.
+    pub fn document(&self) -> &Document<'_> {
+        &self.parsed.get().document
+    }
+
+    /// Returns the parser diagnostics.
+    pub fn diagnostics(&self) -> &DiagnosticBag {
+        &self.parsed.get().diagnostics
+    }
+
+    /// Returns the interner used while parsing.
+    pub fn interner(&self) -> &Interner {
+        &self.parsed.backing_cart().interner
+    }
+
+    /// Returns the source text.
+    pub fn source(&self) -> &str {
+        &self.parsed.backing_cart().source
+    }
+
+    /// Returns the line index of the source, for converting offsets to
+    /// LSP positions and back.
+    pub fn line_index(&self) -> &LineIndex {
+        &self.parsed.backing_cart().line_index
+    }
+
+    /// Returns the source text of each type and directive definition,
+    /// including those in module bodies, keyed by the name the type checker
+    /// knows them by. Extensions are appended to the types they extend.
+    fn definition_sources(&self) -> HashMap<String, String> {
+        fn collect(
+            definitions: &[Definition<'_>],
+            parsed: &ParsedDocument,
+            out: &mut HashMap<String, String>,
+        ) {
+            let interner = parsed.interner();
+            for definition in definitions {
+                let (name, span) = match definition {
+                    Definition::Type(type_def) | Definition::TypeExtension(type_def) => {
+                        (interner.get(type_def.name().value), type_def.span())
+                    }
+                    Definition::Directive(directive) => (
+                        format!("@{}", interner.get(directive.name.value)),
+                        directive.span,
+                    ),
+                    Definition::Module(module) => {
+                        if let Some(body) = &module.body {
+                            collect(body, parsed, out);
+                        }
+                        continue;
+                    }
+                    _ => continue,
+                };
+                let text = parsed
+                    .source()
+                    .get(span.start as usize..span.end as usize)
+                    .unwrap_or_default();
+                out.entry(name).or_default().push_str(text);
+            }
+        }
+
+        let mut sources = HashMap::new();
+        collect(&self.document().definitions, self, &mut sources);
+        sources
+    }
+}
+
+impl std::fmt::Debug for ParsedDocument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParsedDocument")
+            .field("definitions", &self.document().definitions.len())
+            .field("diagnostics", &self.diagnostics().len())
+            .finish()
+    }
+}
+
+/// The last type check of a document, which the next one starts from.
+struct LastCheck {
+    result: CheckResult,
+    /// Source text of the checked definitions, from
+    /// [`ParsedDocument::definition_sources`].
+    definitions: HashMap<String, String>,
+}
+
+impl std::fmt::Debug for LastCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LastCheck")
+            .field("diagnostics", &self.result.diagnostics.len())
+            .field("definitions", &self.definitions.len())
+            .finish()
+    }
+}
 
 /// State for a single document.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Default)]
 pub struct DocumentState {
     pub content: String,
     pub version: i32,
+    /// Parse of `content`, built lazily and dropped on every update.
+    parsed: Mutex<Option<ParsedDocument>>,
+    /// Type check of an earlier version, kept across updates.
+    checked: Mutex<Option<LastCheck>>,
 }
 
 impl DocumentState {
     pub fn new(content: String, version: i32) -> Self {
-        Self { content, version }
+        Self {
+            content,
+            version,
+            parsed: Mutex::new(None),
+            checked: Mutex::new(None),
+        }
     }
 
     pub fn update(&mut self, content: String, version: i32) {
         self.content = content;
         self.version = version;
+        *self
+            .parsed
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    /// Runs `f` with the parsed document, parsing only if this version hasn't been parsed yet.
+    ///
+    /// The parse is taken out of the cache while `f` runs, so the cache
+    /// isn't locked for the duration of e.g. a type check; concurrent
+    /// callers parse their own copy meanwhile.
+    pub fn with_parsed<R>(&self, f: impl FnOnce(&ParsedDocument) -> R) -> R {
+        let cached = self.lock_parsed().take();
+        let parsed = cached.unwrap_or_else(|| ParsedDocument::parse(&self.content));
+        let result = f(&parsed);
+        self.lock_parsed().get_or_insert(parsed);
+        result
+    }
+
+    fn lock_parsed(&self) -> MutexGuard<'_, Option<ParsedDocument>> {
+        self.parsed.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Type checks `parsed`, the parse of this document, and runs `f` with
+    /// the result.
+    ///
+    /// Only the definitions whose source changed since the last check, and
+    /// the ones depending on them, are checked again. Like the parse, the
+    /// last check is taken out while checking.
+    pub fn type_check<R>(&self, parsed: &ParsedDocument, f: impl FnOnce(&CheckResult) -> R) -> R {
+        let definitions = parsed.definition_sources();
+        let last = self.lock_checked().take();
+
+        let types = TypeRegistry::new();
+        let hir = HirDatabase::new();
+        let mut checker = TypeChecker::new(&types, &hir, parsed.interner());
+        let result = match &last {
+            Some(last) => {
+                let mut changed: Vec<&str> = definitions
+                    .iter()
+                    .filter(|(name, text)| last.definitions.get(*name) != Some(*text))
+                    .map(|(name, _)| name.as_str())
+                    .collect();
+                changed.extend(
+                    last.definitions
+                        .keys()
+                        .filter(|name| !definitions.contains_key(*name))
+                        .map(String::as_str),
+                );
+                checker.check_incremental(parsed.document(), &last.result, &changed)
+            }
+            None => checker.check(parsed.document()),
+        };
+
+        let output = f(&result);
+        *self.lock_checked() = Some(LastCheck {
+            result,
+            definitions,
+        });
+        output
+    }
+
+    fn lock_checked(&self) -> MutexGuard<'_, Option<LastCheck>> {
+        self.checked.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns true if the current version has a cached parse.
+    #[cfg(test)]
+    pub fn is_parsed(&self) -> bool {
+        self.lock_parsed().is_some()
     }
 }
 
@@ -52,4 +268,19 @@ impl ServerState {
     pub fn get_document(&self, uri: &Url) -> Option<&DocumentState> {
         self.documents.get(uri)
     }
+
+    /// Searches symbols across all open documents.
+    pub fn workspace_symbols(&self, query: &str) -> Vec<SymbolInformation> {
+        let mut results = Vec::new();
+        for (uri, doc) in &self.documents {
+            doc.with_parsed(|parsed| {
+                let table = SymbolTable::from_document(parsed.document(), parsed.interner());
+                results.extend(table.search(query, uri, parsed.line_index()));
+            });
+        }
+        results.sort_by(|a, b| {
+            (&a.name, a.location.uri.as_str()).cmp(&(&b.name, b.location.uri.as_str()))
+        });
+        results
+    }
 }
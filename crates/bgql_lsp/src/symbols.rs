@@ -1,14 +1,17 @@
 //! Symbol table and document indexing.
 
-use bgql_core::{Interner, Span};
+use crate::hover::format_type;
+use bgql_core::{ColumnUnit, Interner, LineIndex, Span};
 use bgql_syntax::{
     Definition, Document, EnumTypeDefinition, FieldDefinition, InputEnumTypeDefinition,
-    InputObjectTypeDefinition, InputUnionTypeDefinition, InterfaceTypeDefinition,
+    InputObjectTypeDefinition, InputUnionTypeDefinition, InterfaceTypeDefinition, Name,
     ObjectTypeDefinition, OpaqueTypeDefinition, ScalarTypeDefinition, TypeAliasDefinition,
     TypeDefinition, UnionTypeDefinition,
 };
 use std::collections::HashMap;
-use tower_lsp::lsp_types::{DocumentSymbol, Position, Range, SymbolKind};
+use tower_lsp::lsp_types::{
+    DocumentSymbol, Location, Position, Range, SymbolInformation, SymbolKind, Url,
+};
 
 /// A symbol in the document.
 #[derive(Debug, Clone)]
@@ -16,6 +19,10 @@ pub struct Symbol {
     pub name: String,
     pub kind: SymbolType,
     pub span: Span,
+    /// Span of the symbol's name.
+    pub selection_span: Span,
+    /// Short annotation such as a field's type.
+    pub detail: Option<String>,
     pub description: Option<String>,
     pub children: Vec<Symbol>,
 }
@@ -28,6 +35,7 @@ pub enum SymbolType {
     Enum,
     EnumValue,
     Union,
+    UnionMember,
     Input,
     Scalar,
     Opaque,
@@ -50,7 +58,7 @@ impl SymbolType {
             SymbolType::Type | SymbolType::Opaque | SymbolType::Alias => SymbolKind::CLASS,
             SymbolType::Interface => SymbolKind::INTERFACE,
             SymbolType::Enum => SymbolKind::ENUM,
-            SymbolType::EnumValue => SymbolKind::ENUM_MEMBER,
+            SymbolType::EnumValue | SymbolType::UnionMember => SymbolKind::ENUM_MEMBER,
             SymbolType::Union | SymbolType::InputUnion | SymbolType::InputEnum => SymbolKind::ENUM,
             SymbolType::Input => SymbolKind::STRUCT,
             SymbolType::Scalar => SymbolKind::TYPE_PARAMETER,
@@ -99,6 +107,8 @@ impl SymbolTable {
                     name,
                     kind: SymbolType::Directive,
                     span: dir.span,
+                    selection_span: dir.name.span,
+                    detail: None,
                     description: dir.description.as_ref().map(|d| d.value.to_string()),
                     children: Vec::new(),
                 })
@@ -118,6 +128,11 @@ impl SymbolTable {
                     name,
                     kind,
                     span: op.span,
+                    selection_span: op
+                        .name
+                        .as_ref()
+                        .map_or(Span::new(op.span.start, op.span.start), |n| n.span),
+                    detail: None,
                     description: None,
                     children: Vec::new(),
                 })
@@ -128,6 +143,8 @@ impl SymbolTable {
                     name,
                     kind: SymbolType::Fragment,
                     span: frag.span,
+                    selection_span: frag.name.span,
+                    detail: None,
                     description: None,
                     children: Vec::new(),
                 })
@@ -148,11 +165,15 @@ impl SymbolTable {
                     name,
                     kind: SymbolType::Module,
                     span: m.span,
+                    selection_span: m.name.span,
+                    detail: None,
                     description: None,
                     children,
                 })
             }
             Definition::Use(_) => None, // Use statements don't create symbols
+            // Extensions add to the symbols of the types they extend
+            Definition::SchemaExtension(_) | Definition::TypeExtension(_) => None,
         }
     }
 
@@ -187,6 +208,8 @@ impl SymbolTable {
             name,
             kind: SymbolType::Type,
             span: obj.span,
+            selection_span: obj.name.span,
+            detail: None,
             description: obj.description.as_ref().map(|d| d.value.to_string()),
             children,
         }
@@ -208,6 +231,8 @@ impl SymbolTable {
             name,
             kind: SymbolType::Interface,
             span: iface.span,
+            selection_span: iface.name.span,
+            detail: None,
             description: iface.description.as_ref().map(|d| d.value.to_string()),
             children,
         }
@@ -224,6 +249,8 @@ impl SymbolTable {
                     name: value_name,
                     kind: SymbolType::EnumValue,
                     span: v.span,
+                    selection_span: v.name.span,
+                    detail: None,
                     description: v.description.as_ref().map(|d| d.value.to_string()),
                     children: Vec::new(),
                 }
@@ -234,6 +261,8 @@ impl SymbolTable {
             name,
             kind: SymbolType::Enum,
             span: e.span,
+            selection_span: e.name.span,
+            detail: None,
             description: e.description.as_ref().map(|d| d.value.to_string()),
             children,
         }
@@ -241,12 +270,16 @@ impl SymbolTable {
 
     fn index_union_type(&mut self, u: &UnionTypeDefinition<'_>, interner: &Interner) -> Symbol {
         let name = interner.get(u.name.value).to_string();
+        let children = union_members(&u.members, interner);
+
         Symbol {
             name,
             kind: SymbolType::Union,
             span: u.span,
+            selection_span: u.name.span,
+            detail: None,
             description: u.description.as_ref().map(|d| d.value.to_string()),
-            children: Vec::new(),
+            children,
         }
     }
 
@@ -265,6 +298,8 @@ impl SymbolTable {
                     name: field_name,
                     kind: SymbolType::Field,
                     span: f.span,
+                    selection_span: f.name.span,
+                    detail: Some(format_type(&f.ty, interner)),
                     description: f.description.as_ref().map(|d| d.value.to_string()),
                     children: Vec::new(),
                 }
@@ -275,6 +310,8 @@ impl SymbolTable {
             name,
             kind: SymbolType::Input,
             span: inp.span,
+            selection_span: inp.name.span,
+            detail: None,
             description: inp.description.as_ref().map(|d| d.value.to_string()),
             children,
         }
@@ -286,6 +323,8 @@ impl SymbolTable {
             name,
             kind: SymbolType::Scalar,
             span: s.span,
+            selection_span: s.name.span,
+            detail: None,
             description: s.description.as_ref().map(|d| d.value.to_string()),
             children: Vec::new(),
         }
@@ -297,6 +336,8 @@ impl SymbolTable {
             name,
             kind: SymbolType::Opaque,
             span: o.span,
+            selection_span: o.name.span,
+            detail: None,
             description: o.description.as_ref().map(|d| d.value.to_string()),
             children: Vec::new(),
         }
@@ -308,6 +349,8 @@ impl SymbolTable {
             name,
             kind: SymbolType::Alias,
             span: a.span,
+            selection_span: a.name.span,
+            detail: None,
             description: a.description.as_ref().map(|d| d.value.to_string()),
             children: Vec::new(),
         }
@@ -319,12 +362,16 @@ impl SymbolTable {
         interner: &Interner,
     ) -> Symbol {
         let name = interner.get(iu.name.value).to_string();
+        let children = union_members(&iu.members, interner);
+
         Symbol {
             name,
             kind: SymbolType::InputUnion,
             span: iu.span,
+            selection_span: iu.name.span,
+            detail: None,
             description: iu.description.as_ref().map(|d| d.value.to_string()),
-            children: Vec::new(),
+            children,
         }
     }
 
@@ -343,6 +390,8 @@ impl SymbolTable {
                     name: variant_name,
                     kind: SymbolType::EnumValue,
                     span: v.span,
+                    selection_span: v.name.span,
+                    detail: None,
                     description: v.description.as_ref().map(|d| d.value.to_string()),
                     children: Vec::new(),
                 }
@@ -353,6 +402,8 @@ impl SymbolTable {
             name,
             kind: SymbolType::InputEnum,
             span: ie.span,
+            selection_span: ie.name.span,
+            detail: None,
             description: ie.description.as_ref().map(|d| d.value.to_string()),
             children,
         }
@@ -369,6 +420,8 @@ impl SymbolTable {
                     name: arg_name,
                     kind: SymbolType::Argument,
                     span: arg.span,
+                    selection_span: arg.name.span,
+                    detail: Some(format_type(&arg.ty, interner)),
                     description: arg.description.as_ref().map(|d| d.value.to_string()),
                     children: Vec::new(),
                 }
@@ -379,6 +432,8 @@ impl SymbolTable {
             name,
             kind: SymbolType::Field,
             span: field.span,
+            selection_span: field.name.span,
+            detail: Some(format_type(&field.ty, interner)),
             description: field.description.as_ref().map(|d| d.value.to_string()),
             children,
         }
@@ -400,6 +455,15 @@ impl SymbolTable {
         None
     }
 
+    /// Collects symbols, including nested ones, whose names fuzzy-match `query`.
+    pub fn search(&self, query: &str, uri: &Url, index: &LineIndex) -> Vec<SymbolInformation> {
+        let mut results = Vec::new();
+        for symbol in &self.root_symbols {
+            collect_matches(symbol, None, query, uri, index, &mut results);
+        }
+        results
+    }
+
     /// Get symbol by name.
     #[allow(dead_code)]
     pub fn get_symbol(&self, name: &str) -> Option<&Symbol> {
@@ -407,15 +471,71 @@ impl SymbolTable {
     }
 }
 
+fn union_members(members: &[Name], interner: &Interner) -> Vec<Symbol> {
+    members
+        .iter()
+        .map(|member| Symbol {
+            name: interner.get(member.value).to_string(),
+            kind: SymbolType::UnionMember,
+            span: member.span,
+            selection_span: member.span,
+            detail: None,
+            description: None,
+            children: Vec::new(),
+        })
+        .collect()
+}
+
+fn collect_matches(
+    symbol: &Symbol,
+    container: Option<&str>,
+    query: &str,
+    uri: &Url,
+    index: &LineIndex,
+    results: &mut Vec<SymbolInformation>,
+) {
+    // Union members refer to types indexed elsewhere
+    if symbol.kind != SymbolType::UnionMember && fuzzy_match(query, &symbol.name) {
+        #[allow(deprecated)]
+        results.push(SymbolInformation {
+            name: symbol.name.clone(),
+            kind: symbol.kind.to_lsp_kind(),
+            tags: None,
+            deprecated: None,
+            location: Location {
+                uri: uri.clone(),
+                range: span_to_range(symbol.span, index),
+            },
+            container_name: container.map(str::to_string),
+        });
+    }
+    for child in &symbol.children {
+        collect_matches(child, Some(&symbol.name), query, uri, index, results);
+    }
+}
+
+/// Returns true if the characters of `query` appear in `name` in order, ignoring case.
+pub fn fuzzy_match(query: &str, name: &str) -> bool {
+    let mut name_chars = name.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|q| name_chars.any(|c| c == q))
+}
+
 /// Convert a Symbol to LSP DocumentSymbol.
-pub fn symbol_to_document_symbol(symbol: &Symbol, content: &str) -> DocumentSymbol {
-    let range = span_to_range(symbol.span, content);
-    let selection_range = range; // Could be more specific to just the name
+pub fn symbol_to_document_symbol(
+    symbol: &Symbol,
+    content: &str,
+    index: &LineIndex,
+) -> DocumentSymbol {
+    let range = span_to_range(trim_trailing_whitespace(symbol.span, content), index);
+    let selection_range = span_to_range(symbol.selection_span, index);
 
     #[allow(deprecated)]
     DocumentSymbol {
         name: symbol.name.clone(),
-        detail: symbol.description.clone(),
+        detail: symbol.detail.clone().or_else(|| symbol.description.clone()),
         kind: symbol.kind.to_lsp_kind(),
         tags: None,
         deprecated: None,
@@ -428,41 +548,36 @@ pub fn symbol_to_document_symbol(symbol: &Symbol, content: &str) -> DocumentSymb
                 symbol
                     .children
                     .iter()
-                    .map(|c| symbol_to_document_symbol(c, content))
+                    .map(|c| symbol_to_document_symbol(c, content, index))
                     .collect(),
             )
         },
     }
 }
 
+/// Shrinks `span` to exclude the whitespace that some definition spans end with.
+fn trim_trailing_whitespace(span: Span, content: &str) -> Span {
+    let text = content
+        .get(span.start as usize..span.end as usize)
+        .unwrap_or_default();
+    Span::new(span.start, span.start + text.trim_end().len() as u32)
+}
+
 /// Convert a Span to LSP Range.
-pub fn span_to_range(span: Span, content: &str) -> Range {
+pub fn span_to_range(span: Span, index: &LineIndex) -> Range {
     Range {
-        start: offset_to_position(content, span.start as usize),
-        end: offset_to_position(content, span.end as usize),
+        start: offset_to_position(index, span.start as usize),
+        end: offset_to_position(index, span.end as usize),
     }
 }
 
 /// Convert a byte offset to LSP Position.
-pub fn offset_to_position(content: &str, offset: usize) -> Position {
-    let offset = offset.min(content.len());
-    let before = &content[..offset];
-    let line = before.matches('\n').count() as u32;
-    let col = before
-        .rfind('\n')
-        .map(|pos| offset - pos - 1)
-        .unwrap_or(offset) as u32;
-    Position::new(line, col)
+pub fn offset_to_position(index: &LineIndex, offset: usize) -> Position {
+    let (line, character) = index.offset_to_line_col(offset as u32, ColumnUnit::Utf16);
+    Position::new(line, character)
 }
 
 /// Convert LSP Position to byte offset.
-pub fn position_to_offset(content: &str, position: Position) -> usize {
-    let mut offset = 0;
-    for (i, line) in content.lines().enumerate() {
-        if i == position.line as usize {
-            return offset + (position.character as usize).min(line.len());
-        }
-        offset += line.len() + 1; // +1 for newline
-    }
-    content.len()
+pub fn position_to_offset(index: &LineIndex, position: Position) -> usize {
+    index.line_col_to_offset(position.line, position.character, ColumnUnit::Utf16) as usize
 }
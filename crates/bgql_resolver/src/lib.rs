@@ -5,8 +5,11 @@
 
 pub mod module_system;
 
-use bgql_core::{diagnostics::codes, DiagnosticBag, Span};
+use bgql_core::{diagnostics::codes, DiagnosticBag, Interner, Span};
+use bgql_semantic::hir::{HirDefinition, HirField, HirTypeDef, HirTypeKind};
+use bgql_semantic::types::ScalarType;
 use bgql_semantic::{DefId, HirDatabase, TypeRegistry};
+use bgql_syntax::{Definition, Document, InputValueDefinition, Name, Type, TypeDefinition};
 use rustc_hash::FxHashMap;
 
 pub use module_system::{
@@ -120,6 +123,24 @@ impl<'a> Resolver<'a> {
         self.ctx.lookup(name)
     }
 
+    /// Registers a type definition in the current scope with a fresh `DefId`.
+    pub fn define_type(&mut self, name: String, kind: HirTypeKind, span: Span) -> DefId {
+        let id = self.ctx.hir.alloc_def_id();
+        self.ctx.define(name.clone(), id);
+        self.ctx.types.register_type_id(name.clone(), id);
+        self.ctx.hir.add_definition(
+            id,
+            HirDefinition::Type(HirTypeDef {
+                name,
+                kind,
+                fields: Vec::new(),
+                implements: Vec::new(),
+                span,
+            }),
+        );
+        id
+    }
+
     pub fn report_undefined_type(&mut self, name: &str, span: Span) {
         self.ctx.diagnostics.error(
             codes::UNDEFINED_TYPE,
@@ -144,8 +165,22 @@ impl ResolverResult {
 }
 
 /// Resolves names in a document.
-pub fn resolve(_document: &bgql_syntax::Document<'_>) -> ResolverResult {
-    let ctx = ResolverContext::new();
+///
+/// Type definitions are registered first, so references may precede the
+/// definitions they name. Inline modules get their own scope.
+pub fn resolve(document: &Document<'_>, interner: &Interner) -> ResolverResult {
+    let mut ctx = ResolverContext::new();
+
+    let mut resolver = DocumentResolver {
+        resolver: Resolver::new(&mut ctx),
+        interner,
+    };
+    for name in ["Int", "Float", "String", "Boolean", "ID"] {
+        resolver
+            .resolver
+            .define_type(name.to_string(), HirTypeKind::Scalar, Span::default());
+    }
+    resolver.resolve_definitions(&document.definitions);
 
     ResolverResult {
         hir: ctx.hir,
@@ -154,6 +189,270 @@ pub fn resolve(_document: &bgql_syntax::Document<'_>) -> ResolverResult {
     }
 }
 
+/// Walks a document, registering definitions and resolving references.
+struct DocumentResolver<'a> {
+    resolver: Resolver<'a>,
+    interner: &'a Interner,
+}
+
+impl DocumentResolver<'_> {
+    fn resolve_definitions(&mut self, definitions: &[Definition<'_>]) {
+        // Register every type of this scope before resolving references
+        let ids: Vec<Option<DefId>> = definitions
+            .iter()
+            .map(|def| match def {
+                Definition::Type(type_def) => Some(self.define(type_def)),
+                _ => None,
+            })
+            .collect();
+
+        for (def, id) in definitions.iter().zip(ids) {
+            match def {
+                Definition::Type(type_def) => {
+                    if let Some(id) = id {
+                        self.resolve_type_definition(type_def, id);
+                    }
+                }
+                Definition::Schema(schema) => {
+                    for op in &schema.operations {
+                        self.lookup(op.type_name, op.span);
+                    }
+                }
+                Definition::Directive(directive) => {
+                    self.resolve_input_values(&directive.arguments);
+                }
+                Definition::Operation(op) => {
+                    for var in &op.variables {
+                        self.resolve_type_ref(&var.ty);
+                    }
+                }
+                Definition::Fragment(fragment) => {
+                    self.lookup_name(&fragment.type_condition);
+                }
+                Definition::Module(module) => {
+                    if let Some(body) = &module.body {
+                        self.resolver.ctx.push_scope();
+                        self.resolve_definitions(body);
+                        self.resolver.ctx.pop_scope();
+                    }
+                }
+                Definition::Use(_) => {}
+                Definition::SchemaExtension(schema) => {
+                    for op in &schema.operations {
+                        self.lookup(op.type_name, op.span);
+                    }
+                }
+                Definition::TypeExtension(type_def) => {
+                    // Extensions of undefined types are reported by the checker
+                    let name = self.interner.get(type_def.name().value);
+                    if let Some(id) = self.resolver.ctx.lookup(&name) {
+                        self.resolve_type_definition(type_def, id);
+                    }
+                }
+            }
+        }
+    }
+
+    fn define(&mut self, type_def: &TypeDefinition<'_>) -> DefId {
+        let (name, kind, span) = match type_def {
+            TypeDefinition::Object(obj) => (obj.name, HirTypeKind::Object, obj.span),
+            TypeDefinition::Interface(iface) => (iface.name, HirTypeKind::Interface, iface.span),
+            TypeDefinition::Union(u) => (u.name, HirTypeKind::Union, u.span),
+            TypeDefinition::Enum(e) => (e.name, HirTypeKind::Enum, e.span),
+            TypeDefinition::Input(input) => (input.name, HirTypeKind::InputObject, input.span),
+            TypeDefinition::Scalar(scalar) => (scalar.name, HirTypeKind::Scalar, scalar.span),
+            TypeDefinition::Opaque(o) => (o.name, HirTypeKind::Opaque, o.span),
+            TypeDefinition::TypeAlias(a) => (a.name, HirTypeKind::TypeAlias, a.span),
+            TypeDefinition::InputUnion(iu) => (iu.name, HirTypeKind::InputUnion, iu.span),
+            TypeDefinition::InputEnum(ie) => (ie.name, HirTypeKind::InputEnum, ie.span),
+        };
+
+        let name = self.interner.get(name.value);
+        if kind == HirTypeKind::Scalar {
+            self.resolver.ctx.types.register_scalar(ScalarType {
+                name: name.clone(),
+                description: None,
+            });
+        }
+        self.resolver.define_type(name, kind, span)
+    }
+
+    fn resolve_type_definition(&mut self, type_def: &TypeDefinition<'_>, id: DefId) {
+        match type_def {
+            TypeDefinition::Object(obj) => {
+                self.resolve_object_like(id, &obj.type_params, &obj.implements, &obj.fields);
+            }
+            TypeDefinition::Interface(iface) => {
+                self.resolve_object_like(id, &iface.type_params, &iface.implements, &iface.fields);
+            }
+            TypeDefinition::Union(u) => {
+                for member in &u.members {
+                    self.lookup_name(member);
+                }
+            }
+            TypeDefinition::InputUnion(iu) => {
+                for member in &iu.members {
+                    self.lookup_name(member);
+                }
+            }
+            TypeDefinition::Enum(e) => {
+                for value in &e.values {
+                    match &value.data {
+                        Some(bgql_syntax::EnumVariantData::Tuple(types, _)) => {
+                            for ty in types {
+                                self.resolve_type_ref(ty);
+                            }
+                        }
+                        Some(bgql_syntax::EnumVariantData::Struct(fields, _)) => {
+                            self.resolve_input_values(fields);
+                        }
+                        None => {}
+                    }
+                }
+            }
+            TypeDefinition::Input(input) => {
+                let fields = self.resolve_input_values(&input.fields);
+                self.set_members(id, fields, Vec::new());
+            }
+            TypeDefinition::InputEnum(ie) => {
+                for variant in &ie.variants {
+                    if let Some(fields) = &variant.fields {
+                        self.resolve_input_values(fields);
+                    }
+                }
+            }
+            TypeDefinition::Opaque(o) => {
+                self.resolve_type_ref(&o.underlying);
+            }
+            TypeDefinition::TypeAlias(a) => {
+                self.resolve_type_ref(&a.aliased);
+            }
+            TypeDefinition::Scalar(_) => {}
+        }
+    }
+
+    fn resolve_object_like(
+        &mut self,
+        id: DefId,
+        type_params: &[bgql_syntax::TypeParameter<'_>],
+        implements: &[Name],
+        fields: &[bgql_syntax::FieldDefinition<'_>],
+    ) {
+        // Type parameters are only visible inside the definition
+        self.resolver.ctx.push_scope();
+        for param in type_params {
+            let param_id = self.resolver.ctx.hir.alloc_def_id();
+            self.resolver
+                .ctx
+                .define(self.interner.get(param.name.value), param_id);
+        }
+        for param in type_params {
+            if let Some(constraint) = &param.constraint {
+                self.resolve_type_ref(constraint);
+            }
+        }
+
+        let implements = implements
+            .iter()
+            .filter_map(|name| self.lookup_name(name))
+            .collect();
+        let fields = fields
+            .iter()
+            .filter_map(|field| {
+                let arguments = self.resolve_input_values(&field.arguments);
+                let type_id = self.resolve_type_ref(&field.ty)?;
+                Some(self.add_field(field.name, type_id, arguments, field.span))
+            })
+            .collect();
+        self.resolver.ctx.pop_scope();
+
+        self.set_members(id, fields, implements);
+    }
+
+    /// Resolves input values, returning the `DefId`s of those whose type resolved.
+    fn resolve_input_values(&mut self, values: &[InputValueDefinition<'_>]) -> Vec<DefId> {
+        values
+            .iter()
+            .filter_map(|value| {
+                let type_id = self.resolve_type_ref(&value.ty)?;
+                Some(self.add_field(value.name, type_id, Vec::new(), value.span))
+            })
+            .collect()
+    }
+
+    fn add_field(
+        &mut self,
+        name: Name,
+        type_id: DefId,
+        arguments: Vec<DefId>,
+        span: Span,
+    ) -> DefId {
+        let id = self.resolver.ctx.hir.alloc_def_id();
+        self.resolver.ctx.hir.add_definition(
+            id,
+            HirDefinition::Field(HirField {
+                name: self.interner.get(name.value),
+                type_id,
+                arguments,
+                span,
+            }),
+        );
+        id
+    }
+
+    /// Adds fields and implemented interfaces to a type, after those of the
+    /// definitions resolved before it.
+    fn set_members(&mut self, id: DefId, fields: Vec<DefId>, implements: Vec<DefId>) {
+        if let Some(HirDefinition::Type(type_def)) = self.resolver.ctx.hir.get(id) {
+            let mut type_def = type_def.clone();
+            type_def.fields.extend(fields);
+            type_def.implements.extend(implements);
+            self.resolver
+                .ctx
+                .hir
+                .add_definition(id, HirDefinition::Type(type_def));
+        }
+    }
+
+    /// Resolves every name in a type reference.
+    ///
+    /// Returns the named type at its core, e.g. `User` for `List<Option<User>>`.
+    /// Tuples have no single core type.
+    fn resolve_type_ref(&mut self, ty: &Type<'_>) -> Option<DefId> {
+        match ty {
+            Type::Named(named) => self.lookup(named.name, named.span),
+            Type::Option(inner, _) | Type::List(inner, _) => self.resolve_type_ref(inner),
+            Type::Generic(generic) => {
+                for arg in &generic.arguments {
+                    self.resolve_type_ref(arg);
+                }
+                self.lookup(generic.name, generic.span)
+            }
+            Type::Tuple(tuple) => {
+                for element in &tuple.elements {
+                    self.resolve_type_ref(&element.ty);
+                }
+                None
+            }
+            Type::_Phantom(_) => None,
+        }
+    }
+
+    fn lookup_name(&mut self, name: &Name) -> Option<DefId> {
+        self.lookup(name.value, name.span)
+    }
+
+    /// Looks up a type name, reporting it if undefined.
+    fn lookup(&mut self, name: bgql_core::Text, span: Span) -> Option<DefId> {
+        let name = self.interner.get(name);
+        let id = self.resolver.resolve_type(&name);
+        if id.is_none() {
+            self.resolver.report_undefined_type(&name, span);
+        }
+        id
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -165,4 +464,92 @@ mod tests {
         scope.define("User".to_string(), id);
         assert_eq!(scope.lookup("User"), Some(id));
     }
+
+    fn resolve_source(source: &str) -> ResolverResult {
+        let interner = Interner::new();
+        let parsed = bgql_syntax::parse(source, &interner);
+        assert!(!parsed.diagnostics.has_errors());
+        resolve(&parsed.document, &interner)
+    }
+
+    #[test]
+    fn test_resolve_reference() {
+        let result = resolve_source(
+            r#"
+            type Query {
+                user(id: ID): Option<User>
+            }
+
+            type User {
+                id: ID
+                friends: List<User>
+            }
+        "#,
+        );
+        assert!(result.is_ok());
+        assert!(result.diagnostics.is_empty());
+
+        let user_id = result.types.lookup("User").unwrap();
+        let query_id = result.types.lookup("Query").unwrap();
+        let Some(HirDefinition::Type(query)) = result.hir.get(query_id) else {
+            panic!("Query is not a type");
+        };
+        assert_eq!(query.kind, HirTypeKind::Object);
+        assert_eq!(query.fields.len(), 1);
+
+        let Some(HirDefinition::Field(field)) = result.hir.get(query.fields[0]) else {
+            panic!("user is not a field");
+        };
+        assert_eq!(field.name, "user");
+        assert_eq!(field.type_id, user_id);
+        assert_eq!(field.arguments.len(), 1);
+    }
+
+    #[test]
+    fn test_resolve_undefined_reference() {
+        let result = resolve_source(
+            r#"
+            type Query {
+                post: Post
+                page: Connection<User>
+            }
+
+            type Connection<T> {
+                items: List<T>
+            }
+
+            type User {
+                id: ID
+            }
+        "#,
+        );
+        assert!(!result.is_ok());
+
+        let errors: Vec<_> = result.diagnostics.errors().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, codes::UNDEFINED_TYPE);
+        assert_eq!(errors[0].labels[0].message, "type `Post` is not defined");
+    }
+
+    #[test]
+    fn test_module_scope() {
+        let result = resolve_source(
+            r#"
+            mod auth {
+                type Token {
+                    user: User
+                }
+            }
+
+            type User {
+                token: Token
+            }
+        "#,
+        );
+
+        // Outer types are visible inside the module, but not the reverse
+        let errors: Vec<_> = result.diagnostics.errors().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].labels[0].message, "type `Token` is not defined");
+    }
 }
@@ -33,10 +33,13 @@
 //! }
 //! ```
 
+use bgql_core::diagnostics::codes;
+use bgql_core::{DiagnosticBag, Span};
 use bgql_semantic::DefId;
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
 /// A module path (e.g., "user::types" or "posts").
@@ -210,6 +213,10 @@ pub struct ModuleImport {
 
     /// Alias for the import.
     pub alias: Option<String>,
+
+    /// Span of the use statement (not serialized).
+    #[serde(skip)]
+    pub span: Span,
 }
 
 impl ModuleImport {
@@ -219,16 +226,13 @@ impl ModuleImport {
             path,
             items,
             alias: None,
+            span: Span::default(),
         }
     }
 
     /// Creates a glob import (use module::*).
     pub fn glob(path: ModulePath) -> Self {
-        Self {
-            path,
-            items: ImportItems::Glob,
-            alias: None,
-        }
+        Self::new(path, ImportItems::Glob)
     }
 
     /// Sets an alias.
@@ -237,6 +241,12 @@ impl ModuleImport {
         self
     }
 
+    /// Sets the span of the use statement.
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+
     /// Parses a use statement string.
     pub fn parse(use_stmt: &str) -> Option<Self> {
         let use_stmt = use_stmt.trim();
@@ -581,6 +591,9 @@ impl SchemaModule {
 pub struct ModuleSystem {
     /// Root module.
     root: SchemaModule,
+
+    /// Named imports that resolved a reference, as (importing module, local name).
+    used_imports: RefCell<HashSet<(ModulePath, String)>>,
 }
 
 impl ModuleSystem {
@@ -588,6 +601,7 @@ impl ModuleSystem {
     pub fn new() -> Self {
         Self {
             root: SchemaModule::new(Module::root()),
+            used_imports: RefCell::new(HashSet::new()),
         }
     }
 
@@ -615,6 +629,7 @@ impl ModuleSystem {
                     ImportItems::Named(items) => {
                         for item in items {
                             if item.local_name() == name {
+                                self.mark_used(from_module, name);
                                 let full_path = format!("{}::{}", import.path, item.name);
                                 return self.root.get_type(&full_path);
                             }
@@ -648,9 +663,69 @@ impl ModuleSystem {
             if let Some(def_id) = module.directives.get(name) {
                 return Some(*def_id);
             }
+
+            // Check named imports
+            for import in &module.module.imports {
+                if let ImportItems::Named(items) = &import.items {
+                    if let Some(item) = items.iter().find(|item| item.local_name() == name) {
+                        self.mark_used(from_module, name);
+                        let full_path = format!("{}::{}", import.path, item.name);
+                        return self.root.get_directive(&full_path);
+                    }
+                }
+            }
         }
         self.root.get_directive(name)
     }
+
+    /// Records that a named import was used to resolve a reference.
+    fn mark_used(&self, from_module: &ModulePath, local_name: &str) {
+        self.used_imports
+            .borrow_mut()
+            .insert((from_module.clone(), local_name.to_string()));
+    }
+
+    /// Warns about named imports that no resolved reference used.
+    ///
+    /// Run this after resolution. Glob and self imports are never reported.
+    pub fn check_unused_imports(&self, diagnostics: &mut DiagnosticBag) {
+        self.check_module_imports(&self.root, &ModulePath::root(), diagnostics);
+    }
+
+    fn check_module_imports(
+        &self,
+        module: &SchemaModule,
+        path: &ModulePath,
+        diagnostics: &mut DiagnosticBag,
+    ) {
+        let used = self.used_imports.borrow();
+        for import in &module.module.imports {
+            let ImportItems::Named(items) = &import.items else {
+                continue;
+            };
+            for item in items {
+                let local_name = item.local_name();
+                if !used.contains(&(path.clone(), local_name.to_string())) {
+                    diagnostics.warning(
+                        codes::UNUSED_IMPORT,
+                        format!("Unused import `{local_name}`"),
+                        import.span,
+                        format!(
+                            "`{}::{}` is imported but never used",
+                            import.path, item.name
+                        ),
+                    );
+                }
+            }
+        }
+        drop(used);
+
+        let mut children: Vec<_> = module.children.iter().collect();
+        children.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, child) in children {
+            self.check_module_imports(child, &path.join(name), diagnostics);
+        }
+    }
 }
 
 impl Default for ModuleSystem {
@@ -823,6 +898,58 @@ mod tests {
         assert_eq!(resolved, Some(id_def_id));
     }
 
+    fn system_with_import(import: &str) -> ModuleSystem {
+        let mut system = ModuleSystem::new();
+
+        let mut user_module = SchemaModule::new(Module::new(ModulePath::parse("user")));
+        user_module.add_type("User", DefId::from_raw(1));
+        user_module.add_type("UserInput", DefId::from_raw(2));
+        system.root_mut().add_child("user", user_module);
+
+        let import = ModuleImport::parse(import)
+            .unwrap()
+            .with_span(Span::new(0, 20));
+        system.root_mut().module.add_import(import);
+        system
+    }
+
+    #[test]
+    fn test_unused_import_warning() {
+        let system = system_with_import("user::{User, UserInput as Input}");
+        assert_eq!(
+            system.resolve_type("User", &ModulePath::root()),
+            Some(DefId::from_raw(1))
+        );
+
+        let mut diagnostics = DiagnosticBag::new();
+        system.check_unused_imports(&mut diagnostics);
+
+        let warnings: Vec<_> = diagnostics.warnings().collect();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, codes::UNUSED_IMPORT);
+        assert_eq!(warnings[0].title, "Unused import `Input`");
+        assert_eq!(warnings[0].primary_span(), Some(Span::new(0, 20)));
+    }
+
+    #[test]
+    fn test_used_imports_not_reported() {
+        let system = system_with_import("user::User as Account");
+        assert_eq!(
+            system.resolve_type("Account", &ModulePath::root()),
+            Some(DefId::from_raw(1))
+        );
+
+        let mut diagnostics = DiagnosticBag::new();
+        system.check_unused_imports(&mut diagnostics);
+        assert!(diagnostics.is_empty());
+
+        // Glob imports are exempt
+        let system = system_with_import("user::*");
+        let mut diagnostics = DiagnosticBag::new();
+        system.check_unused_imports(&mut diagnostics);
+        assert!(diagnostics.is_empty());
+    }
+
     #[test]
     fn test_use_directive() {
         let use_dir = UseDirective::new("user").with_items("User, UserInput");
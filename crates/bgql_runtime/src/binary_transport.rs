@@ -2,9 +2,20 @@
 //!
 //! This module implements the binary streaming protocol for
 //! efficient media and file transfer over GraphQL.
-
+//!
+//! A resolver of a `File`-like scalar answers with a [`BinaryBlob`] through
+//! [`ResolverInfo::respond_binary`](crate::resolver::ResolverInfo::respond_binary);
+//! the field resolves to a reference to it instead of its bytes, and the
+//! executor records the blob with the field's path. Clients that negotiate
+//! binary transport ([`BinaryProtocol::accepts_binary`]) receive each blob
+//! after the response via [`BinaryProtocol::encode_response`]; for others the
+//! references are replaced with base64 by [`inline_binaries`].
+
+use crate::executor::PathSegment;
 use crate::state::{BinaryStreamPhase, BinaryStreamState};
+use base64::Engine;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
@@ -364,6 +375,105 @@ impl BinaryStreamMetadata {
     }
 }
 
+/// Binary data returned by a field of a `File`-like scalar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryBlob {
+    /// Content type (MIME).
+    pub content_type: String,
+
+    /// The bytes.
+    pub data: Vec<u8>,
+}
+
+impl BinaryBlob {
+    /// Creates a blob.
+    pub fn new(content_type: impl Into<String>, data: Vec<u8>) -> Self {
+        Self {
+            content_type: content_type.into(),
+            data,
+        }
+    }
+
+    /// Returns the stream metadata of the blob sent as stream `id`.
+    pub fn metadata(&self, id: impl Into<String>) -> BinaryStreamMetadata {
+        BinaryStreamMetadata::new(id.into(), self.content_type.clone())
+            .with_total_size(self.data.len() as u64)
+    }
+
+    /// Returns the bytes base64-encoded, as inlined in JSON responses.
+    pub fn to_base64(&self) -> String {
+        base64::engine::general_purpose::STANDARD.encode(&self.data)
+    }
+}
+
+/// A blob attached to a response, with the path of the field it answers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttachedBinary {
+    /// Stream id the field's reference carries.
+    pub id: String,
+
+    /// Path of the field in the response.
+    pub path: Vec<PathSegment>,
+
+    /// The blob.
+    pub blob: BinaryBlob,
+}
+
+/// Binary blobs attached to a response, in attachment order.
+///
+/// Clones share the same blobs, so every clone of a
+/// [`Context`](crate::executor::Context) sees the blobs attached through any
+/// other.
+#[derive(Debug, Clone, Default)]
+pub struct BinaryAttachments {
+    blobs: Arc<std::sync::Mutex<Vec<AttachedBinary>>>,
+}
+
+impl BinaryAttachments {
+    /// Creates an empty set of attachments.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attaches `blob` as the answer to the field at `path` and returns the
+    /// reference to it to resolve the field to.
+    pub fn attach(&self, path: Vec<PathSegment>, blob: BinaryBlob) -> Value {
+        let mut blobs = self.blobs.lock().unwrap_or_else(|e| e.into_inner());
+        let id = format!("binary-{}", blobs.len());
+        let reference = serde_json::json!({
+            "id": id,
+            "contentType": blob.content_type,
+            "size": blob.data.len(),
+        });
+        blobs.push(AttachedBinary { id, path, blob });
+        reference
+    }
+
+    /// Removes and returns the attached blobs.
+    pub fn take(&self) -> Vec<AttachedBinary> {
+        std::mem::take(&mut *self.blobs.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// Replaces the reference of each of `binaries` in the response `data` with
+/// the blob's base64-encoded bytes, for clients without binary transport.
+///
+/// Fields nulled since the blob was attached stay null.
+pub fn inline_binaries(data: &mut Value, binaries: &[AttachedBinary]) {
+    for binary in binaries {
+        let field = binary
+            .path
+            .iter()
+            .try_fold(&mut *data, |value, segment| match segment {
+                PathSegment::Field(name) => value.get_mut(name.as_str()),
+                PathSegment::Index(index) => value.get_mut(*index),
+            });
+        if let Some(field) = field.filter(|field| !field.is_null()) {
+            *field = Value::String(binary.blob.to_base64());
+        }
+    }
+}
+
 /// Handle for controlling a binary stream.
 #[derive(Debug, Clone)]
 pub struct BinaryStreamHandle {
@@ -569,6 +679,15 @@ impl BinaryProtocol {
         headers
     }
 
+    /// Returns whether a request's `Accept` header negotiates binary
+    /// transport.
+    pub fn accepts_binary(accept: &str) -> bool {
+        accept.split(',').any(|media_type| {
+            let media_type = media_type.split(';').next().unwrap_or_default();
+            media_type.trim() == CONTENT_TYPE_BINARY_STREAM
+        })
+    }
+
     /// Parses headers to extract stream metadata.
     pub fn parse_headers(headers: &[(String, String)]) -> Option<BinaryStreamMetadata> {
         let mut id = None;
@@ -675,6 +794,91 @@ impl BinaryProtocol {
         Ok(total_bytes)
     }
 
+    /// Encodes an attached blob, in chunks of at most `chunk_size` bytes.
+    ///
+    /// The stream starts with a metadata chunk holding the blob's
+    /// [`BinaryStreamMetadata`], whose id matches the field's reference.
+    pub async fn encode_blob<W>(
+        binary: &AttachedBinary,
+        writer: &mut W,
+        chunk_size: u32,
+    ) -> Result<u64, StreamError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let chunk_size = chunk_size.clamp(1, MAX_CHUNK_SIZE);
+        let metadata = binary
+            .blob
+            .metadata(binary.id.as_str())
+            .with_chunk_size(chunk_size);
+        BinaryChunk::metadata_chunk(0, &metadata)
+            .write_to(writer)
+            .await
+            .map_err(|e| StreamError::Io(e.to_string()))?;
+
+        let (_control_tx, control_rx) = mpsc::channel(1);
+        Self::encode_stream(
+            &mut binary.blob.data.as_slice(),
+            writer,
+            chunk_size,
+            control_rx,
+        )
+        .await
+    }
+
+    /// Encodes a response for a client that negotiated binary transport: a
+    /// final metadata chunk holding the JSON response, followed by each blob
+    /// as encoded by [`BinaryProtocol::encode_blob`].
+    pub async fn encode_response<W>(
+        response: &Value,
+        binaries: &[AttachedBinary],
+        writer: &mut W,
+        chunk_size: u32,
+    ) -> Result<(), StreamError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let payload =
+            serde_json::to_vec(response).map_err(|e| StreamError::InvalidData(e.to_string()))?;
+        let chunk = BinaryChunk {
+            flags: ChunkFlags::METADATA.with_final(),
+            ..BinaryChunk::final_chunk(0, payload)
+        };
+        chunk
+            .write_to(writer)
+            .await
+            .map_err(|e| StreamError::Io(e.to_string()))?;
+
+        for binary in binaries {
+            Self::encode_blob(binary, writer, chunk_size).await?;
+        }
+        Ok(())
+    }
+
+    /// Decodes a blob encoded by [`BinaryProtocol::encode_blob`], returning
+    /// its metadata and the number of bytes written.
+    pub async fn decode_blob<R, W>(
+        reader: &mut R,
+        writer: &mut W,
+    ) -> Result<(BinaryStreamMetadata, u64), StreamError>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let chunk = BinaryChunk::read_from(reader)
+            .await
+            .map_err(|e| StreamError::Io(e.to_string()))?;
+        if !chunk.flags.is_metadata() {
+            return Err(StreamError::InvalidData(
+                "expected a metadata chunk".to_string(),
+            ));
+        }
+        let metadata = serde_json::from_slice(&chunk.payload)
+            .map_err(|e| StreamError::InvalidData(e.to_string()))?;
+        let size = Self::decode_stream(reader, writer).await?;
+        Ok((metadata, size))
+    }
+
     /// Decodes a stream from a reader.
     pub async fn decode_stream<R, W>(reader: &mut R, writer: &mut W) -> Result<u64, StreamError>
     where
@@ -693,7 +897,7 @@ impl BinaryProtocol {
                 return Err(StreamError::InvalidData(msg));
             }
 
-            if !chunk.payload.is_empty() {
+            if !chunk.payload.is_empty() && !chunk.flags.is_metadata() {
                 writer
                     .write_all(&chunk.payload)
                     .await
@@ -871,6 +1075,73 @@ mod tests {
         assert_eq!(tracker.bytes_transferred(), 50);
     }
 
+    #[test]
+    fn test_inline_binaries() {
+        let attachments = BinaryAttachments::new();
+        let files = vec![PathSegment::Field("files".into()), PathSegment::Index(0)];
+        let icon = vec![PathSegment::Field("icon".into())];
+        let mut data = serde_json::json!({
+            "files": [attachments.attach(files, BinaryBlob::new("text/plain", b"Man".to_vec()))],
+            "icon": attachments.attach(icon, BinaryBlob::new("image/png", b"Ma".to_vec())),
+            "missing": null,
+        });
+        assert_eq!(
+            data["icon"],
+            serde_json::json!({ "id": "binary-1", "contentType": "image/png", "size": 2 })
+        );
+
+        let missing = vec![PathSegment::Field("missing".into())];
+        attachments.attach(missing, BinaryBlob::new("text/plain", b"M".to_vec()));
+        let binaries = attachments.take();
+        assert_eq!(binaries.len(), 3);
+        assert!(attachments.take().is_empty());
+
+        inline_binaries(&mut data, &binaries);
+        assert_eq!(
+            data,
+            serde_json::json!({ "files": ["TWFu"], "icon": "TWE=", "missing": null })
+        );
+        assert_eq!(BinaryBlob::new("", b"M".to_vec()).to_base64(), "TQ==");
+
+        assert!(BinaryProtocol::accepts_binary(
+            "application/json, application/vnd.bgql.binary-stream;q=0.9"
+        ));
+        assert!(!BinaryProtocol::accepts_binary("application/json"));
+    }
+
+    #[tokio::test]
+    async fn test_encode_response() {
+        let attachments = BinaryAttachments::new();
+        let data: Vec<u8> = (0..10u8).collect();
+        let reference = attachments.attach(
+            vec![PathSegment::Field("icon".into())],
+            BinaryBlob::new("image/png", data.clone()),
+        );
+        let response = serde_json::json!({ "data": { "icon": reference } });
+
+        let mut wire = Vec::new();
+        BinaryProtocol::encode_response(&response, &attachments.take(), &mut wire, 4)
+            .await
+            .unwrap();
+
+        let mut reader = wire.as_slice();
+        let chunk = BinaryChunk::read_from(&mut reader).await.unwrap();
+        assert!(chunk.flags.is_metadata() && chunk.flags.is_final());
+        let decoded: Value = serde_json::from_slice(&chunk.payload).unwrap();
+        assert_eq!(decoded, response);
+
+        let mut received = Vec::new();
+        let (metadata, size) = BinaryProtocol::decode_blob(&mut reader, &mut received)
+            .await
+            .unwrap();
+        assert_eq!(metadata.id, "binary-0");
+        assert_eq!(metadata.content_type, "image/png");
+        assert_eq!(metadata.total_size, Some(10));
+        assert_eq!(size, 10);
+        assert_eq!(received, data);
+        assert!(reader.is_empty());
+    }
+
     #[tokio::test]
     async fn test_stream_handle() {
         let metadata =
@@ -2,130 +2,208 @@
 //!
 //! Provides batching and caching to prevent N+1 queries.
 
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::any::Any;
 use std::collections::HashMap;
 use std::future::Future;
 use std::hash::Hash;
+use std::pin::Pin;
 use std::sync::Arc;
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{oneshot, Mutex};
+
+/// The future returned by a [`BatchFn`].
+pub type BatchFuture<K, V> = Pin<Box<dyn Future<Output = HashMap<K, V>> + Send>>;
+
+/// Loads the values of a batch of keys. Keys missing from the result load
+/// as `None`.
+pub type BatchFn<K, V> = dyn Fn(Vec<K>) -> BatchFuture<K, V> + Send + Sync;
 
 /// A DataLoader that batches and caches loads.
-pub struct DataLoader<K, V, F>
-where
-    K: Eq + Hash + Clone + Send,
-    V: Clone + Send,
-    F: Fn(Vec<K>) -> std::pin::Pin<Box<dyn Future<Output = HashMap<K, V>> + Send>> + Send + Sync,
-{
-    batch_fn: Arc<F>,
-    cache: Arc<RwLock<FxHashMap<K, V>>>,
-    batch: Arc<Mutex<Vec<K>>>,
+///
+/// Keys loaded during the same tick are collected into one call of the
+/// batch function: the first [`load`](Self::load) of a batch spawns a task
+/// that yields to the scheduler before dispatching, so concurrently running
+/// resolvers can add their keys. Batches run on their own tasks, so they
+/// complete and wake their waiters even if the load that started them is
+/// dropped. Loaded values are cached for the lifetime of the loader.
+pub struct DataLoader<K, V> {
+    batch_fn: Arc<BatchFn<K, V>>,
+    state: Arc<Mutex<LoaderState<K, V>>>,
     batch_size: usize,
 }
 
-impl<K, V, F> DataLoader<K, V, F>
+struct LoaderState<K, V> {
+    cache: FxHashMap<K, V>,
+    /// Keys waiting for the next dispatch
+    queue: Vec<K>,
+    /// Callers waiting for queued or in-flight keys
+    waiters: FxHashMap<K, Vec<oneshot::Sender<Option<V>>>>,
+    /// Bumped on every dispatch
+    generation: u64,
+}
+
+impl<K, V> LoaderState<K, V> {
+    fn take_queue(&mut self) -> Vec<K> {
+        self.generation += 1;
+        std::mem::take(&mut self.queue)
+    }
+}
+
+impl<K, V> DataLoader<K, V>
 where
     K: Eq + Hash + Clone + Send + 'static,
     V: Clone + Send + 'static,
-    F: Fn(Vec<K>) -> std::pin::Pin<Box<dyn Future<Output = HashMap<K, V>> + Send>>
-        + Send
-        + Sync
-        + 'static,
 {
     /// Creates a new DataLoader.
-    pub fn new(batch_fn: F) -> Self {
+    pub fn new(batch_fn: impl Fn(Vec<K>) -> BatchFuture<K, V> + Send + Sync + 'static) -> Self {
         Self {
             batch_fn: Arc::new(batch_fn),
-            cache: Arc::new(RwLock::new(FxHashMap::default())),
-            batch: Arc::new(Mutex::new(Vec::new())),
+            state: Arc::new(Mutex::new(LoaderState {
+                cache: FxHashMap::default(),
+                queue: Vec::new(),
+                waiters: FxHashMap::default(),
+                generation: 0,
+            })),
             batch_size: 100,
         }
     }
 
     /// Sets the maximum batch size.
     pub fn batch_size(mut self, size: usize) -> Self {
-        self.batch_size = size;
+        self.batch_size = size.max(1);
         self
     }
 
     /// Loads a value by key.
     pub async fn load(&self, key: K) -> Option<V> {
-        // Check cache first
-        {
-            let cache = self.cache.read().await;
-            if let Some(value) = cache.get(&key) {
+        let receiver = {
+            let mut state = self.state.lock().await;
+            if let Some(value) = state.cache.get(&key) {
                 return Some(value.clone());
             }
-        }
 
-        // Add to batch and trigger batch load
-        let keys = {
-            let mut batch = self.batch.lock().await;
-            batch.push(key.clone());
-            if batch.len() >= self.batch_size {
-                std::mem::take(&mut *batch)
+            let (sender, receiver) = oneshot::channel();
+            if let Some(waiters) = state.waiters.get_mut(&key) {
+                // Already queued or in flight
+                waiters.push(sender);
             } else {
-                return None; // Will be loaded in batch
+                state.waiters.insert(key.clone(), vec![sender]);
+                state.queue.push(key);
+                if state.queue.len() >= self.batch_size {
+                    let keys = state.take_queue();
+                    self.spawn_dispatch(keys);
+                } else if state.queue.len() == 1 {
+                    self.spawn_batch(state.generation);
+                }
             }
+            receiver
         };
 
-        // Execute batch
-        let results = (self.batch_fn)(keys).await;
+        receiver.await.ok().flatten()
+    }
 
-        // Update cache
+    /// Loads multiple values with a single batch call for the uncached keys.
+    pub async fn load_many(&self, keys: Vec<K>) -> HashMap<K, V> {
+        let mut results = HashMap::new();
+        let mut missing = Vec::new();
         {
-            let mut cache = self.cache.write().await;
-            for (k, v) in results.iter() {
-                cache.insert(k.clone(), v.clone());
+            let state = self.state.lock().await;
+            let mut seen = FxHashSet::default();
+            for key in keys {
+                if let Some(value) = state.cache.get(&key) {
+                    results.insert(key, value.clone());
+                } else if seen.insert(key.clone()) {
+                    missing.push(key);
+                }
             }
         }
 
-        // Return the requested value
-        results.get(&key).cloned()
+        if !missing.is_empty() {
+            results.extend(self.spawn_dispatch(missing).await.unwrap_or_default());
+        }
+        results
     }
 
-    /// Loads multiple values.
-    pub async fn load_many(&self, keys: Vec<K>) -> HashMap<K, V> {
-        let results = (self.batch_fn)(keys).await;
-
-        // Update cache
-        {
-            let mut cache = self.cache.write().await;
-            for (k, v) in results.iter() {
-                cache.insert(k.clone(), v.clone());
+    /// Starts the batch of generation `generation`, which dispatches the
+    /// queue after letting the loads of the current tick join it.
+    fn spawn_batch(&self, generation: u64) {
+        let batch_fn = Arc::clone(&self.batch_fn);
+        let state = Arc::clone(&self.state);
+        tokio::spawn(async move {
+            tokio::task::yield_now().await;
+            let keys = {
+                let mut state = state.lock().await;
+                if state.generation == generation {
+                    state.take_queue()
+                } else {
+                    Vec::new()
+                }
+            };
+            if !keys.is_empty() {
+                dispatch(&*batch_fn, &state, keys).await;
             }
-        }
+        });
+    }
 
-        results
+    /// Dispatches `keys` on a task of its own.
+    fn spawn_dispatch(&self, keys: Vec<K>) -> tokio::task::JoinHandle<HashMap<K, V>> {
+        let batch_fn = Arc::clone(&self.batch_fn);
+        let state = Arc::clone(&self.state);
+        tokio::spawn(async move { dispatch(&*batch_fn, &state, keys).await })
     }
 
     /// Clears the cache.
     pub async fn clear(&self) {
-        let mut cache = self.cache.write().await;
-        cache.clear();
+        self.state.lock().await.cache.clear();
     }
 
     /// Clears a specific key from the cache.
     pub async fn clear_key(&self, key: &K) {
-        let mut cache = self.cache.write().await;
-        cache.remove(key);
+        self.state.lock().await.cache.remove(key);
     }
 
     /// Primes the cache with a value.
     pub async fn prime(&self, key: K, value: V) {
-        let mut cache = self.cache.write().await;
-        cache.insert(key, value);
+        self.state.lock().await.cache.insert(key, value);
+    }
+}
+
+/// Calls the batch function, caches the results and wakes the callers
+/// waiting for `keys`.
+async fn dispatch<K, V>(
+    batch_fn: &BatchFn<K, V>,
+    state: &Mutex<LoaderState<K, V>>,
+    keys: Vec<K>,
+) -> HashMap<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    let results = batch_fn(keys.clone()).await;
+
+    let mut state = state.lock().await;
+    for key in keys {
+        let value = results.get(&key).cloned();
+        for sender in state.waiters.remove(&key).unwrap_or_default() {
+            let _ = sender.send(value.clone());
+        }
+        if let Some(value) = value {
+            state.cache.insert(key, value);
+        }
+    }
+    results
+}
+
+impl<K, V> std::fmt::Debug for DataLoader<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DataLoader")
+            .field("batch_size", &self.batch_size)
+            .finish_non_exhaustive()
     }
 }
 
 /// Creates a simple DataLoader with a batch function.
-#[allow(clippy::type_complexity)]
-pub fn create_loader<K, V, F, Fut>(
-    batch_fn: F,
-) -> DataLoader<
-    K,
-    V,
-    impl Fn(Vec<K>) -> std::pin::Pin<Box<dyn Future<Output = HashMap<K, V>> + Send>> + Send + Sync,
->
+pub fn create_loader<K, V, F, Fut>(batch_fn: F) -> DataLoader<K, V>
 where
     K: Eq + Hash + Clone + Send + 'static,
     V: Clone + Send + 'static,
@@ -135,9 +213,75 @@ where
     DataLoader::new(move |keys| Box::pin(batch_fn(keys)))
 }
 
+/// The DataLoaders of a request, by name.
+///
+/// Clones share the same loaders, so every clone of a
+/// [`Context`](crate::executor::Context) sees the loaders registered
+/// through any other.
+#[derive(Clone, Default)]
+pub struct LoaderRegistry {
+    loaders: Arc<std::sync::Mutex<FxHashMap<String, Arc<dyn Any + Send + Sync>>>>,
+}
+
+impl LoaderRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the loader registered as `name`, registering the one built by
+    /// `create` if there's none.
+    ///
+    /// A loader of other key or value types registered under the same name
+    /// is replaced.
+    pub fn get_or_insert_with<K, V>(
+        &self,
+        name: &str,
+        create: impl FnOnce() -> DataLoader<K, V>,
+    ) -> Arc<DataLoader<K, V>>
+    where
+        K: Send + 'static,
+        V: Send + 'static,
+    {
+        let mut loaders = self.loaders.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(loader) = loaders
+            .get(name)
+            .and_then(|loader| Arc::clone(loader).downcast::<DataLoader<K, V>>().ok())
+        {
+            return loader;
+        }
+
+        let loader = Arc::new(create());
+        loaders.insert(name.to_string(), loader.clone());
+        loader
+    }
+
+    /// Returns the loader registered as `name`, if it has the given key and
+    /// value types.
+    pub fn get<K, V>(&self, name: &str) -> Option<Arc<DataLoader<K, V>>>
+    where
+        K: Send + 'static,
+        V: Send + 'static,
+    {
+        let loaders = self.loaders.lock().unwrap_or_else(|e| e.into_inner());
+        loaders
+            .get(name)
+            .and_then(|loader| Arc::clone(loader).downcast().ok())
+    }
+}
+
+impl std::fmt::Debug for LoaderRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let loaders = self.loaders.lock().unwrap_or_else(|e| e.into_inner());
+        f.debug_set().entries(loaders.keys()).finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::executor::Context;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     #[tokio::test]
     async fn test_dataloader() {
@@ -150,4 +294,52 @@ mod tests {
         assert_eq!(result.get(&2), Some(&4));
         assert_eq!(result.get(&3), Some(&6));
     }
+
+    #[tokio::test]
+    async fn test_dropped_load_still_dispatches() {
+        let loader = create_loader(|keys: Vec<i32>| async move {
+            keys.into_iter().map(|k| (k, k * 2)).collect()
+        });
+
+        // The first load starts the batch and is dropped before it runs
+        let mut first = Box::pin(loader.load(1));
+        assert!(futures::poll!(first.as_mut()).is_pending());
+        drop(first);
+
+        let waiter = tokio::time::timeout(std::time::Duration::from_secs(1), loader.load(1));
+        assert_eq!(waiter.await, Ok(Some(2)));
+    }
+
+    #[tokio::test]
+    async fn test_loads_in_one_tick_are_batched() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let batches = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let ctx = Context::new();
+        let loader = {
+            let calls = Arc::clone(&calls);
+            let batches = Arc::clone(&batches);
+            ctx.create_loader("double", move |keys: Vec<i32>| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                batches.lock().unwrap().push(keys.clone());
+                async move { keys.into_iter().map(|k| (k, k * 2)).collect() }
+            })
+        };
+
+        let (a, b, c, d) = tokio::join!(
+            loader.load(1),
+            loader.load(2),
+            loader.load(3),
+            loader.load(2)
+        );
+        assert_eq!((a, b, c, d), (Some(2), Some(4), Some(6), Some(4)));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(*batches.lock().unwrap(), vec![vec![1, 2, 3]]);
+
+        // Later loads are served from the request's cache
+        let cached = ctx
+            .loader::<i32, i32>("double")
+            .expect("loader is registered");
+        assert_eq!(cached.load(3).await, Some(6));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
 }
@@ -6,11 +6,21 @@
 //! - Binary streaming (@binary)
 //! - Hydration (@hydrate)
 //! - Resumable queries (@resumable)
+//!
+//! It also provides [`DirectiveRegistry`] for user-defined field directives
+//! such as `@uppercase` that run around field resolution, and
+//! [`AuthDirectiveHandler`] for `@hasRole` and `@authScope`.
 
+use crate::executor::Context;
+use crate::resolver::{ResolverError, ResolverFuture, ResolverInfo, ResolverResult};
 use crate::resource::ResourceLevel;
-use crate::schema::{DirectiveDefinition, DirectiveLocation, InputFieldDef, TypeRef};
+use crate::schema::{
+    AppliedDirective, DirectiveDefinition, DirectiveLocation, InputFieldDef, TypeRef,
+};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 // =============================================================================
 // @server directive
@@ -1033,6 +1043,267 @@ fn create_stream_directive() -> DirectiveDefinition {
     }
 }
 
+// =============================================================================
+// Custom directive handlers
+// =============================================================================
+
+/// Handler for a custom field directive.
+///
+/// `next` resolves the field (and any directives applied after this one). A
+/// handler can await it and transform the value, or return without awaiting
+/// it to short-circuit resolution.
+pub trait DirectiveHandler: Send + Sync {
+    /// Runs the directive around the field's resolution.
+    fn handle<'a>(
+        &'a self,
+        directive: &'a AppliedDirective,
+        next: ResolverFuture<'a>,
+        ctx: &'a Context,
+        info: &'a ResolverInfo,
+    ) -> ResolverFuture<'a>;
+}
+
+/// A sync function that transforms a resolved value.
+pub type DirectiveTransformFn =
+    Arc<dyn Fn(serde_json::Value, &AppliedDirective) -> ResolverResult + Send + Sync>;
+
+/// A directive handler that transforms the value produced by `next`.
+pub struct FnDirectiveHandler {
+    func: DirectiveTransformFn,
+}
+
+impl FnDirectiveHandler {
+    /// Creates a new transforming handler.
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(serde_json::Value, &AppliedDirective) -> ResolverResult + Send + Sync + 'static,
+    {
+        Self { func: Arc::new(f) }
+    }
+}
+
+impl DirectiveHandler for FnDirectiveHandler {
+    fn handle<'a>(
+        &'a self,
+        directive: &'a AppliedDirective,
+        next: ResolverFuture<'a>,
+        _ctx: &'a Context,
+        _info: &'a ResolverInfo,
+    ) -> ResolverFuture<'a> {
+        Box::pin(async move { (self.func)(next.await?, directive) })
+    }
+}
+
+/// Registry of custom directive handlers, keyed by directive name.
+#[derive(Default)]
+pub struct DirectiveRegistry {
+    handlers: HashMap<String, Arc<dyn DirectiveHandler>>,
+}
+
+impl DirectiveRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a handler for the directive `name` (without the `@`).
+    pub fn register<H: DirectiveHandler + 'static>(&mut self, name: impl Into<String>, handler: H) {
+        self.handlers.insert(name.into(), Arc::new(handler));
+    }
+
+    /// Registers a sync function that transforms the resolved value.
+    pub fn register_fn<F>(&mut self, name: impl Into<String>, f: F)
+    where
+        F: Fn(serde_json::Value, &AppliedDirective) -> ResolverResult + Send + Sync + 'static,
+    {
+        self.register(name, FnDirectiveHandler::new(f));
+    }
+
+    /// Gets the handler for a directive.
+    pub fn get(&self, name: &str) -> Option<&dyn DirectiveHandler> {
+        self.handlers.get(name).map(|h| h.as_ref())
+    }
+
+    /// Returns true if no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// Wraps `next` with the handlers for `directives`.
+    ///
+    /// The first directive runs outermost, so `@a @b` sees the value after
+    /// `@b` has transformed it. Directives without a handler are skipped.
+    pub fn apply<'a>(
+        &'a self,
+        directives: &'a [AppliedDirective],
+        mut next: ResolverFuture<'a>,
+        ctx: &'a Context,
+        info: &'a ResolverInfo,
+    ) -> ResolverFuture<'a> {
+        for directive in directives.iter().rev() {
+            if let Some(handler) = self.get(&directive.name) {
+                next = handler.handle(directive, next, ctx, info);
+            }
+        }
+        next
+    }
+}
+
+impl std::fmt::Debug for DirectiveRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut names: Vec<_> = self.handlers.keys().collect();
+        names.sort();
+        f.debug_struct("DirectiveRegistry")
+            .field("handlers", &names)
+            .finish()
+    }
+}
+
+// =============================================================================
+// Authorization directives
+// =============================================================================
+
+/// Context data key holding the principal's roles as a list of strings.
+pub const ROLES_CONTEXT_KEY: &str = "roles";
+
+/// Context data key holding the principal's scopes as a list of strings.
+pub const SCOPES_CONTEXT_KEY: &str = "scopes";
+
+/// Roles that imply lesser roles, e.g. `ADMIN` implies `EDITOR` implies
+/// `VIEWER`.
+///
+/// Implication is transitive. Scopes are looked up in the same hierarchy.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RoleHierarchy {
+    implies: HashMap<String, Vec<String>>,
+}
+
+impl RoleHierarchy {
+    /// Creates a hierarchy in which roles only grant themselves.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a linear hierarchy from the most to the least privileged role.
+    pub fn linear<I, S>(roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let roles: Vec<String> = roles.into_iter().map(Into::into).collect();
+        let mut hierarchy = Self::new();
+        for pair in roles.windows(2) {
+            hierarchy = hierarchy.role(pair[0].clone(), [pair[1].clone()]);
+        }
+        hierarchy
+    }
+
+    /// Declares that `role` implies each of `implied`.
+    pub fn role<I, S>(mut self, role: impl Into<String>, implied: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.implies
+            .entry(role.into())
+            .or_default()
+            .extend(implied.into_iter().map(Into::into));
+        self
+    }
+
+    /// Returns true if holding `role` grants `required`.
+    pub fn grants(&self, role: &str, required: &str) -> bool {
+        let mut seen = HashSet::new();
+        let mut pending = vec![role];
+        while let Some(role) = pending.pop() {
+            if role == required {
+                return true;
+            }
+            if seen.insert(role) {
+                if let Some(implied) = self.implies.get(role) {
+                    pending.extend(implied.iter().map(String::as_str));
+                }
+            }
+        }
+        false
+    }
+
+    /// Returns true if any of `roles` grants `required`.
+    pub fn grants_any<S: AsRef<str>>(&self, roles: &[S], required: &str) -> bool {
+        roles
+            .iter()
+            .any(|role| self.grants(role.as_ref(), required))
+    }
+}
+
+/// Checks `@hasRole(role: ...)` and `@authScope(scopes: [...])` against the
+/// principal's roles and scopes in the context.
+///
+/// Roles are read from [`ROLES_CONTEXT_KEY`] and scopes from
+/// [`SCOPES_CONTEXT_KEY`]. Both honor the [`RoleHierarchy`]. `@authScope`
+/// requires every listed scope. A failed check skips the resolver.
+#[derive(Debug, Clone, Default)]
+pub struct AuthDirectiveHandler {
+    hierarchy: Arc<RoleHierarchy>,
+}
+
+impl AuthDirectiveHandler {
+    /// Creates a handler that checks against `hierarchy`.
+    pub fn new(hierarchy: RoleHierarchy) -> Self {
+        Self {
+            hierarchy: Arc::new(hierarchy),
+        }
+    }
+
+    fn check(&self, directive: &AppliedDirective, ctx: &Context) -> Result<(), ResolverError> {
+        let (key, required): (_, Vec<String>) = match directive.name.as_str() {
+            "hasRole" => (
+                ROLES_CONTEXT_KEY,
+                directive
+                    .argument("role")
+                    .and_then(|role| role.as_str())
+                    .map(str::to_string)
+                    .into_iter()
+                    .collect(),
+            ),
+            _ => (
+                SCOPES_CONTEXT_KEY,
+                directive
+                    .argument("scopes")
+                    .and_then(|scopes| serde_json::from_value(scopes.clone()).ok())
+                    .unwrap_or_default(),
+            ),
+        };
+
+        let held: Vec<String> = ctx.get(key).unwrap_or_default();
+        match required
+            .iter()
+            .find(|required| !self.hierarchy.grants_any(&held, required))
+        {
+            Some(missing) => Err(ResolverError::Client(format!(
+                "Forbidden: `{}` is required",
+                missing
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+impl DirectiveHandler for AuthDirectiveHandler {
+    fn handle<'a>(
+        &'a self,
+        directive: &'a AppliedDirective,
+        next: ResolverFuture<'a>,
+        ctx: &'a Context,
+        _info: &'a ResolverInfo,
+    ) -> ResolverFuture<'a> {
+        Box::pin(async move {
+            self.check(directive, ctx)?;
+            next.await
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1104,4 +1375,85 @@ mod tests {
         assert!(names.contains(&"defer"));
         assert!(names.contains(&"stream"));
     }
+
+    #[tokio::test]
+    async fn test_directive_registry_short_circuit() {
+        use crate::resolver::ResolverError;
+
+        let mut registry = DirectiveRegistry::new();
+        registry.register_fn("uppercase", |value, _| {
+            Ok(match value {
+                serde_json::Value::String(s) => serde_json::Value::String(s.to_uppercase()),
+                other => other,
+            })
+        });
+
+        struct Mask;
+        impl DirectiveHandler for Mask {
+            fn handle<'a>(
+                &'a self,
+                directive: &'a AppliedDirective,
+                _next: ResolverFuture<'a>,
+                _ctx: &'a Context,
+                _info: &'a ResolverInfo,
+            ) -> ResolverFuture<'a> {
+                let with = directive.argument("with").cloned();
+                Box::pin(async move { Ok(with.unwrap_or(serde_json::Value::Null)) })
+            }
+        }
+        registry.register("mask", Mask);
+
+        let ctx = Context::new();
+        let info = ResolverInfo::new("name", "User");
+        let directives = vec![
+            AppliedDirective::new("uppercase"),
+            AppliedDirective::new("mask").with_argument("with", serde_json::json!("***x")),
+            AppliedDirective::new("unknown"),
+        ];
+        let next: ResolverFuture<'_> =
+            Box::pin(async { Err(ResolverError::Custom("field resolved".into())) });
+
+        let value = registry.apply(&directives, next, &ctx, &info).await;
+        assert_eq!(value.unwrap(), serde_json::json!("***X"));
+    }
+
+    #[tokio::test]
+    async fn test_has_role_honors_hierarchy() {
+        let mut registry = DirectiveRegistry::new();
+        let auth = AuthDirectiveHandler::new(RoleHierarchy::linear(["ADMIN", "EDITOR", "VIEWER"]));
+        registry.register("hasRole", auth.clone());
+        registry.register("authScope", auth);
+
+        let directives = vec![
+            AppliedDirective::new("hasRole").with_argument("role", serde_json::json!("EDITOR"))
+        ];
+        let info = ResolverInfo::new("publish", "Mutation");
+        let principal = |role: &str| {
+            let mut ctx = Context::new();
+            ctx.set(ROLES_CONTEXT_KEY, [role]);
+            ctx
+        };
+
+        let admin = principal("ADMIN");
+        let next: ResolverFuture<'_> = Box::pin(async { Ok(serde_json::json!(true)) });
+        let value = registry.apply(&directives, next, &admin, &info).await;
+        assert_eq!(value.unwrap(), serde_json::json!(true));
+
+        let viewer = principal("VIEWER");
+        let next: ResolverFuture<'_> = Box::pin(async { Ok(serde_json::json!(true)) });
+        let err = registry
+            .apply(&directives, next, &viewer, &info)
+            .await
+            .unwrap_err();
+        assert!(err.is_client_facing());
+        assert!(err.to_string().contains("`EDITOR` is required"));
+
+        // Scopes are looked up in the same hierarchy
+        let scoped = vec![AppliedDirective::new("authScope")
+            .with_argument("scopes", serde_json::json!(["VIEWER", "EDITOR"]))];
+        let next: ResolverFuture<'_> = Box::pin(async { Ok(serde_json::json!(true)) });
+        let mut ctx = Context::new();
+        ctx.set(SCOPES_CONTEXT_KEY, ["EDITOR"]);
+        assert!(registry.apply(&scoped, next, &ctx, &info).await.is_ok());
+    }
 }
@@ -1,13 +1,29 @@
 //! Query execution for Better GraphQL.
 
+use crate::binary_transport::{AttachedBinary, BinaryAttachments};
+use crate::dataloader::{self, DataLoader, LoaderRegistry};
+use crate::directives::DirectiveRegistry;
+use crate::introspection;
+use crate::pagination::PageBounds;
+use crate::plan_cache::PlanCache;
 use crate::query::{FieldInfo, PlanNode, QueryPlan};
-use crate::resolver::{ResolverArgs, ResolverInfo, ResolverMap};
-use crate::schema::Schema;
+use crate::resolver::{
+    DefaultResolver, FieldAnswer, Resolver, ResolverArgs, ResolverError, ResolverFuture,
+    ResolverInfo, ResolverMap,
+};
+use crate::scheduler::{QueryScheduler, TaskPriority};
+use crate::schema::{FieldDef, Schema, TypeDef, TypeRef};
+use crate::state::{CheckpointStore, ExecutionPhase, ExecutionState, ResumeToken, StreamCursor};
+use crate::streaming::{self, DeferPayload, StreamPayload};
+use futures::future::join_all;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, RwLock, Semaphore};
 
 /// Executor configuration.
 #[derive(Debug, Clone)]
@@ -16,10 +32,30 @@ pub struct ExecutorConfig {
     pub max_parallel_depth: usize,
     /// Enable tracing.
     pub tracing: bool,
-    /// Maximum concurrent field resolutions.
+    /// Maximum number of resolvers of a request running at once. Zero
+    /// disables the limit.
     pub max_concurrent_fields: usize,
     /// Timeout for field resolution in milliseconds.
     pub field_timeout_ms: u64,
+    /// Number of resolved subscription events buffered per subscriber.
+    pub subscription_buffer: usize,
+    /// Replace the messages of internal resolver errors with
+    /// [`MASKED_ERROR_MESSAGE`]. Client-facing errors are left untouched.
+    pub mask_errors: bool,
+    /// Maximum number of query plans kept in the executor's [`PlanCache`].
+    /// Zero disables plan caching.
+    pub plan_cache_size: usize,
+    /// Maximum size in bytes of a response. Execution stops resolving
+    /// fields once their results outgrow it, and the response is replaced by
+    /// a `RESPONSE_TOO_LARGE` error; [`Executor::serialize_response`]
+    /// enforces it exactly. Zero disables the limit.
+    pub max_response_size: usize,
+    /// Page bounds of fields taking `first` or `last` without a
+    /// `@paginate` directive of their own.
+    pub pagination: Option<PageBounds>,
+    /// Reject queries of `@internal` fields unless their [`Context`] is
+    /// [internal](Context::internal).
+    pub reject_internal_fields: bool,
 }
 
 impl Default for ExecutorConfig {
@@ -29,14 +65,34 @@ impl Default for ExecutorConfig {
             tracing: false,
             max_concurrent_fields: 100,
             field_timeout_ms: 30000,
+            subscription_buffer: 16,
+            mask_errors: false,
+            plan_cache_size: 1000,
+            max_response_size: 0,
+            pagination: None,
+            reject_internal_fields: false,
         }
     }
 }
 
+/// Message that replaces internal errors when masking is enabled.
+pub const MASKED_ERROR_MESSAGE: &str = "Internal server error";
+
+/// Called with every resolver error before it is added to the response.
+///
+/// Receives the original error, so internal details stay available for
+/// logging even when the response masks them.
+pub type ErrorHook = Arc<dyn Fn(&ResolverError, &[PathSegment]) + Send + Sync>;
+
 /// The query executor.
+#[derive(Clone)]
 pub struct Executor {
     config: ExecutorConfig,
     resolvers: Arc<ResolverMap>,
+    directives: Arc<DirectiveRegistry>,
+    error_hook: Option<ErrorHook>,
+    plan_cache: Arc<PlanCache>,
+    scheduler: Option<Arc<QueryScheduler>>,
 }
 
 impl Default for Executor {
@@ -49,6 +105,7 @@ impl std::fmt::Debug for Executor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Executor")
             .field("config", &self.config)
+            .field("directives", &self.directives)
             .finish()
     }
 }
@@ -56,79 +113,778 @@ impl std::fmt::Debug for Executor {
 impl Executor {
     /// Creates a new executor.
     pub fn new() -> Self {
-        Self {
-            config: ExecutorConfig::default(),
-            resolvers: Arc::new(ResolverMap::new()),
-        }
+        Self::with_config(ExecutorConfig::default())
     }
 
     /// Creates an executor with configuration.
     pub fn with_config(config: ExecutorConfig) -> Self {
-        Self {
-            config,
-            resolvers: Arc::new(ResolverMap::new()),
-        }
+        Self::new_with(config, ResolverMap::new())
     }
 
     /// Creates an executor with resolvers.
     pub fn with_resolvers(resolvers: ResolverMap) -> Self {
-        Self {
-            config: ExecutorConfig::default(),
-            resolvers: Arc::new(resolvers),
-        }
+        Self::new_with(ExecutorConfig::default(), resolvers)
     }
 
     /// Creates an executor with config and resolvers.
     pub fn new_with(config: ExecutorConfig, resolvers: ResolverMap) -> Self {
         Self {
+            plan_cache: Arc::new(PlanCache::new(config.plan_cache_size)),
             config,
             resolvers: Arc::new(resolvers),
+            directives: Arc::new(DirectiveRegistry::new()),
+            error_hook: None,
+            scheduler: None,
         }
     }
 
+    /// Sets the handlers for custom field directives.
+    pub fn with_directive_handlers(mut self, directives: DirectiveRegistry) -> Self {
+        self.directives = Arc::new(directives);
+        self
+    }
+
+    /// Sets a hook that observes resolver errors, e.g. for logging.
+    pub fn with_error_hook(
+        mut self,
+        hook: impl Fn(&ResolverError, &[PathSegment]) + Send + Sync + 'static,
+    ) -> Self {
+        self.error_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Runs resolvers through `scheduler`, so that while its slots are
+    /// taken, the resolvers of operations with a higher
+    /// [`Context::priority`] start first.
+    pub fn with_scheduler(mut self, scheduler: Arc<QueryScheduler>) -> Self {
+        self.scheduler = Some(scheduler);
+        self
+    }
+
     /// Gets a reference to the resolvers.
     pub fn resolvers(&self) -> &ResolverMap {
         &self.resolvers
     }
 
+    /// Gets a reference to the directive handlers.
+    pub fn directive_handlers(&self) -> &DirectiveRegistry {
+        &self.directives
+    }
+
+    /// Gets the cache of query plans, shared by clones of this executor.
+    pub fn plan_cache(&self) -> &PlanCache {
+        &self.plan_cache
+    }
+
+    /// Serializes `response` to JSON.
+    ///
+    /// Serialization stops as soon as the output grows past
+    /// [`ExecutorConfig::max_response_size`]; the response is then replaced
+    /// by one carrying a single `RESPONSE_TOO_LARGE` error.
+    pub fn serialize_response(&self, response: &Response) -> Vec<u8> {
+        match response.to_json_limited(self.config.max_response_size) {
+            Ok(bytes) => bytes,
+            Err(error) => serde_json::to_vec(&Response::error(error)).unwrap_or_default(),
+        }
+    }
+
     /// Executes a query plan.
     pub async fn execute(&self, plan: &QueryPlan, schema: &Schema, ctx: &Context) -> Response {
-        let exec_ctx = ExecutionContext {
+        // Root value is an empty object for Query/Mutation
+        let root_value = Value::Object(serde_json::Map::new());
+        self.execute_with_root(plan, schema, ctx, root_value).await
+    }
+
+    /// Executes a query plan against the given root value.
+    pub async fn execute_with_root(
+        &self,
+        plan: &QueryPlan,
+        schema: &Schema,
+        ctx: &Context,
+        root_value: Value,
+    ) -> Response {
+        let exec_ctx = self.execution_context(schema, ctx);
+
+        // Execute the plan
+        let data = execute_node(&plan.root, root_value, Vec::new(), &exec_ctx).await;
+        complete_response(data, &exec_ctx).await
+    }
+
+    /// Executes a query plan, delivering the items of `@stream` root fields
+    /// past their `initialCount` one at a time through the returned
+    /// [`StreamExecution`].
+    ///
+    /// The stream's progress is checkpointed to `store` after every item.
+    pub async fn execute_stream(
+        &self,
+        plan: &QueryPlan,
+        schema: &Schema,
+        ctx: &Context,
+        store: Arc<dyn CheckpointStore>,
+    ) -> (Response, StreamExecution) {
+        let exec_ctx = self.execution_context(schema, ctx);
+        let mut state = ExecutionState::new(next_execution_id(), plan_hash(plan))
+            .with_variables(ctx.variables.clone());
+        let mut data = serde_json::Map::new();
+        let mut data_nulled = false;
+        let mut streams = VecDeque::new();
+
+        for node in root_nodes(&plan.root) {
+            if let Some(field) = StreamedField::from_node(node) {
+                let items = resolve_stream_items(field.info, &exec_ctx).await;
+                let mut initial = Vec::new();
+                for (index, item) in items.iter().take(field.initial_count).enumerate() {
+                    initial.push(
+                        complete_stream_item(
+                            field.children,
+                            item.clone(),
+                            field.response_name,
+                            index,
+                            &exec_ctx,
+                        )
+                        .await,
+                    );
+                }
+
+                let key = field.key();
+                let mut cursor = StreamCursor::new(key.clone(), vec![field.response_name.into()]);
+                cursor.total_items = Some(items.len());
+                cursor.advance(initial.len(), None);
+                state.update_stream_cursor(&key, cursor);
+
+                data.insert(field.response_name.to_string(), Value::Array(initial));
+                streams.push_back(field.pending(items));
+                continue;
+            }
+
+            let root_value = Value::Object(serde_json::Map::new());
+            match execute_node(node, root_value, Vec::new(), &exec_ctx).await {
+                Ok(Value::Object(map)) => data.extend(map),
+                Ok(_) => {}
+                Err(NonNullViolation) => data_nulled = true,
+            }
+        }
+
+        state.partial_data = Value::Object(data.clone());
+        state.transition_to(ExecutionPhase::Streaming);
+        let token = state.generate_resume_token();
+
+        let mut stream = StreamExecution {
+            ctx: exec_ctx,
+            store,
+            state,
+            token,
+            streams,
+        };
+        stream.checkpoint();
+
+        let data = if data_nulled {
+            Err(NonNullViolation)
+        } else {
+            Ok(Value::Object(data))
+        };
+        let response = complete_response(data, &stream.ctx).await;
+        (response, stream)
+    }
+
+    /// Executes a query plan, leaving `@defer` fragments out of the initial
+    /// response. They are delivered afterwards through the returned
+    /// [`DeferredExecution`], each with the path of the object it belongs to.
+    pub async fn execute_incremental(
+        &self,
+        plan: &QueryPlan,
+        schema: &Schema,
+        ctx: &Context,
+    ) -> (Response, DeferredExecution) {
+        let mut exec_ctx = self.execution_context(schema, ctx);
+        let deferred = Arc::new(RwLock::new(Vec::new()));
+        exec_ctx.deferred = Some(Arc::clone(&deferred));
+
+        let root_value = Value::Object(serde_json::Map::new());
+        let data = execute_node(&plan.root, root_value, Vec::new(), &exec_ctx).await;
+        let response = complete_response(data, &exec_ctx).await;
+        let pending = std::mem::take(&mut *deferred.write().await);
+        let execution = DeferredExecution {
+            ctx: exec_ctx,
+            pending: pending.into(),
+        };
+        (response, execution)
+    }
+
+    /// Continues a stream started by [`Executor::execute_stream`] from the
+    /// last checkpoint saved under `token`, e.g. after a server restart.
+    ///
+    /// Streamed lists are resolved again and the items delivered before the
+    /// checkpoint are skipped, so their resolvers must return a stable order.
+    /// The variables of the original request are used.
+    pub async fn resume_stream(
+        &self,
+        plan: &QueryPlan,
+        schema: &Schema,
+        ctx: &Context,
+        store: Arc<dyn CheckpointStore>,
+        token: &str,
+    ) -> Result<StreamExecution, FieldError> {
+        let mut state = store
+            .load(token)
+            .filter(ExecutionState::can_resume)
+            .ok_or_else(|| FieldError::new("Unknown or expired resume token"))?;
+        if state.query_hash != plan_hash(plan) {
+            return Err(FieldError::new(
+                "Resume token was issued for a different operation",
+            ));
+        }
+
+        let mut ctx = ctx.clone();
+        ctx.variables = state.variables.clone();
+        let exec_ctx = self.execution_context(schema, &ctx);
+
+        let mut streams = VecDeque::new();
+        for node in root_nodes(&plan.root) {
+            let Some(field) = StreamedField::from_node(node) else {
+                continue;
+            };
+            if !state
+                .active_streams
+                .get(&field.key())
+                .is_some_and(StreamCursor::has_more)
+            {
+                continue;
+            }
+            let items = resolve_stream_items(field.info, &exec_ctx).await;
+            streams.push_back(field.pending(items));
+        }
+
+        state.stats.resume_count += 1;
+        state.transition_to(ExecutionPhase::Streaming);
+
+        Ok(StreamExecution {
+            ctx: exec_ctx,
+            store,
+            state,
+            token: token.to_string(),
+            streams,
+        })
+    }
+
+    /// Resolves a subscription's selection set for one source stream event.
+    ///
+    /// The event payload is the root value, so the subscription field is
+    /// resolved from it like any other field: a `Subscription` resolver gets
+    /// the payload as its parent, and without one the payload's property of
+    /// the same name is used.
+    pub async fn execute_subscription_event(
+        &self,
+        plan: &QueryPlan,
+        schema: &Schema,
+        ctx: &Context,
+        event: Value,
+    ) -> Response {
+        self.execute_with_root(plan, schema, ctx, event).await
+    }
+
+    /// Maps a subscription source stream to a stream of responses, resolving
+    /// each event with [`Executor::execute_subscription_event`].
+    ///
+    /// The returned stream ends when the source stream closes.
+    pub fn subscribe(
+        &self,
+        plan: QueryPlan,
+        schema: Schema,
+        ctx: Context,
+        mut events: mpsc::Receiver<Value>,
+    ) -> mpsc::Receiver<Response> {
+        let (tx, rx) = mpsc::channel(self.config.subscription_buffer.max(1));
+        let executor = self.clone();
+
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                let response = executor
+                    .execute_subscription_event(&plan, &schema, &ctx, event)
+                    .await;
+                if tx.send(response).await.is_err() {
+                    // Subscriber went away
+                    break;
+                }
+            }
+        });
+
+        rx
+    }
+
+    fn execution_context(&self, schema: &Schema, ctx: &Context) -> ExecutionContext {
+        ExecutionContext {
             schema: schema.clone(),
             ctx: ctx.clone(),
             resolvers: Arc::clone(&self.resolvers),
+            directives: Arc::clone(&self.directives),
             config: self.config.clone(),
+            error_hook: self.error_hook.clone(),
+            scheduler: self.scheduler.clone(),
             errors: Arc::new(RwLock::new(Vec::new())),
-        };
+            deferred: None,
+            response_size: Arc::new(AtomicUsize::new(0)),
+            field_permits: (self.config.max_concurrent_fields > 0)
+                .then(|| Arc::new(Semaphore::new(self.config.max_concurrent_fields))),
+        }
+    }
 
-        // Get root value (empty object for Query/Mutation)
-        let root_value = Value::Object(serde_json::Map::new());
+    /// Returns the resolver invocations executing `plan` would make, in
+    /// execution order, without calling any resolvers.
+    ///
+    /// Fields below a list field are listed once, with `[]` in their path
+    /// marking that they run once per item.
+    pub fn plan_only(&self, plan: &QueryPlan, schema: &Schema, ctx: &Context) -> Vec<PlannedCall> {
+        let mut calls = Vec::new();
+        collect_planned_calls(&plan.root, "", schema, &self.resolvers, ctx, &mut calls);
+        calls
+    }
+}
 
-        // Execute the plan
-        let data = execute_node(&plan.root, root_value, Vec::new(), &exec_ctx).await;
+/// The items of `@stream` fields still to be delivered.
+///
+/// Created by [`Executor::execute_stream`] and [`Executor::resume_stream`].
+/// Each call to [`StreamExecution::next`] completes one item and saves a
+/// checkpoint, so a stream resumed with [`StreamExecution::resume_token`]
+/// continues after the last item returned.
+pub struct StreamExecution {
+    ctx: ExecutionContext,
+    store: Arc<dyn CheckpointStore>,
+    state: ExecutionState,
+    token: ResumeToken,
+    streams: VecDeque<PendingStream>,
+}
+
+/// A streamed list field and its resolved items.
+struct PendingStream {
+    /// Key of the field's cursor in the execution state.
+    key: String,
+    label: Option<String>,
+    response_name: String,
+    children: PlanNode,
+    items: Vec<Value>,
+}
+
+impl std::fmt::Debug for StreamExecution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamExecution")
+            .field("token", &self.token)
+            .field("phase", &self.state.phase)
+            .field("active_streams", &self.state.active_streams)
+            .finish()
+    }
+}
+
+impl StreamExecution {
+    /// Returns the token to resume this stream from its last checkpoint.
+    pub fn resume_token(&self) -> &str {
+        &self.token
+    }
+
+    /// Returns the execution state, as last checkpointed.
+    pub fn state(&self) -> &ExecutionState {
+        &self.state
+    }
+
+    /// Completes and returns the next streamed item, or `None` once every
+    /// stream has been delivered.
+    pub async fn next(&mut self) -> Option<StreamPayload> {
+        loop {
+            let stream = self.streams.front()?;
+            let cursor = self.state.active_streams.get_mut(&stream.key)?;
+            let index = cursor.items_delivered;
+
+            let Some(item) = stream.items.get(index).cloned() else {
+                cursor.complete();
+                self.streams.pop_front();
+                self.checkpoint();
+                continue;
+            };
 
-        // Collect errors
-        let errors = exec_ctx.errors.read().await;
-        let errors = if errors.is_empty() {
-            None
+            let data = complete_stream_item(
+                &stream.children,
+                item,
+                &stream.response_name,
+                index,
+                &self.ctx,
+            )
+            .await;
+            let errors: Vec<_> = take_errors(&self.ctx)
+                .await
+                .iter()
+                .filter_map(|e| serde_json::to_value(e).ok())
+                .collect();
+            let has_next = index + 1 < stream.items.len() || self.streams.len() > 1;
+            let payload = StreamPayload {
+                path: vec![stream.response_name.as_str().into(), index.into()],
+                items: vec![data],
+                label: stream.label.clone(),
+                has_next,
+                errors: (!errors.is_empty()).then_some(errors),
+            };
+
+            if let Some(cursor) = self.state.active_streams.get_mut(&stream.key) {
+                cursor.advance(1, None);
+                if !has_next {
+                    cursor.complete();
+                }
+            }
+            if !has_next {
+                self.streams.clear();
+            }
+            self.state.stats.stream_items_sent += 1;
+            self.checkpoint();
+            return Some(payload);
+        }
+    }
+
+    /// Saves the current state, or removes it once every stream is delivered.
+    fn checkpoint(&mut self) {
+        if self.streams.is_empty() {
+            self.state.transition_to(ExecutionPhase::Completed);
+            self.store.remove(&self.token);
         } else {
-            Some(errors.clone())
+            self.state.stats.checkpoints_created += 1;
+            self.state.updated_at = std::time::SystemTime::now();
+            self.store.save(&self.token, &self.state);
+        }
+    }
+}
+
+/// The `@defer` fragments of an execution still to be delivered.
+///
+/// Created by [`Executor::execute_incremental`]. Fragments are executed in
+/// the order they were reached; those deferred inside another fragment
+/// follow it.
+pub struct DeferredExecution {
+    ctx: ExecutionContext,
+    pending: VecDeque<DeferredFragment>,
+}
+
+/// A `@defer` fragment and the object it was reached at.
+struct DeferredFragment {
+    node: PlanNode,
+    label: Option<String>,
+    parent: Value,
+    path: Vec<PathSegment>,
+}
+
+impl std::fmt::Debug for DeferredExecution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeferredExecution")
+            .field("pending", &self.pending.len())
+            .finish()
+    }
+}
+
+impl DeferredExecution {
+    /// Returns true if fragments remain to be delivered.
+    pub fn has_next(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Executes and returns the next deferred fragment, or `None` once all
+    /// of them have been delivered.
+    pub async fn next(&mut self) -> Option<DeferPayload> {
+        let fragment = self.pending.pop_front()?;
+        let data = execute_node(
+            &fragment.node,
+            fragment.parent,
+            fragment.path.clone(),
+            &self.ctx,
+        )
+        .await
+        .unwrap_or(Value::Null);
+        if let Some(deferred) = &self.ctx.deferred {
+            self.pending
+                .extend(std::mem::take(&mut *deferred.write().await));
+        }
+
+        let errors: Vec<_> = take_errors(&self.ctx)
+            .await
+            .iter()
+            .filter_map(|e| serde_json::to_value(e).ok())
+            .collect();
+        Some(DeferPayload {
+            path: fragment.path.into_iter().map(Into::into).collect(),
+            data,
+            label: fragment.label,
+            has_next: self.has_next(),
+            errors: (!errors.is_empty()).then_some(errors),
+        })
+    }
+}
+
+/// Returns the top-level nodes of a plan.
+fn root_nodes(root: &PlanNode) -> &[PlanNode] {
+    match root {
+        PlanNode::Sequence(nodes) | PlanNode::Parallel(nodes) => nodes,
+        node => std::slice::from_ref(node),
+    }
+}
+
+/// A `@stream` field node of a plan.
+struct StreamedField<'a> {
+    info: &'a FieldInfo,
+    response_name: &'a str,
+    children: &'a PlanNode,
+    label: Option<&'a str>,
+    initial_count: usize,
+}
+
+impl<'a> StreamedField<'a> {
+    fn from_node(node: &'a PlanNode) -> Option<Self> {
+        let PlanNode::Stream {
+            node,
+            label,
+            initial_count,
+        } = node
+        else {
+            return None;
+        };
+        let PlanNode::Field {
+            info,
+            response_name,
+            children,
+        } = node.as_ref()
+        else {
+            return None;
         };
+        Some(Self {
+            info,
+            response_name,
+            children,
+            label: label.as_deref(),
+            initial_count: *initial_count,
+        })
+    }
 
-        Response {
-            data: Some(data),
-            errors,
+    /// Returns the key of the field's cursor in the execution state.
+    fn key(&self) -> String {
+        self.label.unwrap_or(self.response_name).to_string()
+    }
+
+    fn pending(&self, items: Vec<Value>) -> PendingStream {
+        PendingStream {
+            key: self.key(),
+            label: self.label.map(str::to_string),
+            response_name: self.response_name.to_string(),
+            children: self.children.clone(),
+            items,
+        }
+    }
+}
+
+/// Resolves a streamed root field to its list items.
+async fn resolve_stream_items(info: &FieldInfo, ctx: &ExecutionContext) -> Vec<Value> {
+    let root_value = Value::Object(serde_json::Map::new());
+    match resolve_field(info, &root_value, Vec::new(), ctx)
+        .await
+        .into_value()
+    {
+        Value::Array(items) => items,
+        Value::Null => Vec::new(),
+        other => vec![other],
+    }
+}
+
+/// Executes the selections of one streamed item.
+async fn complete_stream_item(
+    children: &PlanNode,
+    item: Value,
+    response_name: &str,
+    index: usize,
+    ctx: &ExecutionContext,
+) -> Value {
+    let path = vec![
+        PathSegment::Field(response_name.to_string()),
+        PathSegment::Index(index),
+    ];
+    execute_node(children, item, path, ctx)
+        .await
+        .unwrap_or(Value::Null)
+}
+
+/// Removes and returns the errors collected so far.
+async fn take_errors(ctx: &ExecutionContext) -> Vec<FieldError> {
+    std::mem::take(&mut *ctx.errors.write().await)
+}
+
+/// Builds the response from the data of an execution and its errors, unless
+/// the data outgrew [`ExecutorConfig::max_response_size`]. A null in a
+/// non-null root field nulls the data.
+async fn complete_response(data: Completion, ctx: &ExecutionContext) -> Response {
+    let errors = take_errors(ctx).await;
+    if ctx.response_too_large() {
+        return Response::error(response_too_large(ctx.config.max_response_size));
+    }
+    Response {
+        data: Some(data.unwrap_or(Value::Null)),
+        errors: (!errors.is_empty()).then_some(errors),
+    }
+}
+
+/// The error replacing a response over `max_size` bytes.
+fn response_too_large(max_size: usize) -> FieldError {
+    FieldError::new(format!(
+        "Response exceeds the maximum size of {} bytes",
+        max_size
+    ))
+    .with_code("RESPONSE_TOO_LARGE")
+}
+
+/// Returns a process-unique execution id.
+fn next_execution_id() -> String {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    format!(
+        "exec-{:x}-{}",
+        nanos,
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Hashes a plan, to check that a resume token belongs to the same operation.
+fn plan_hash(plan: &QueryPlan) -> String {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    format!("{:?}", plan.root).hash(&mut hasher);
+    format!("{:x}", hasher.finish())
+}
+
+/// A resolver invocation reported by [`Executor::plan_only`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlannedCall {
+    /// Parent type name.
+    pub type_name: String,
+    /// Field name.
+    pub field_name: String,
+    /// Response path, e.g. `users[].posts`.
+    pub path: String,
+    /// Field arguments, with variables substituted.
+    pub args: Vec<(String, Value)>,
+    /// Whether a resolver is registered; otherwise the parent's property is read.
+    pub has_resolver: bool,
+}
+
+/// Walks a plan node, recording the fields it would resolve.
+fn collect_planned_calls(
+    node: &PlanNode,
+    prefix: &str,
+    schema: &Schema,
+    resolvers: &ResolverMap,
+    ctx: &Context,
+    calls: &mut Vec<PlannedCall>,
+) {
+    match node {
+        PlanNode::Sequence(nodes) | PlanNode::Parallel(nodes) => {
+            for node in nodes {
+                collect_planned_calls(node, prefix, schema, resolvers, ctx, calls);
+            }
+        }
+        PlanNode::Field { info, children, .. } if !info.is_introspection => {
+            let call = planned_call(info, prefix, resolvers, ctx);
+            let mut path = call.path.clone();
+            calls.push(call);
+            let is_list = schema
+                .get_field(&info.parent_type, &info.name)
+                .is_some_and(|field| is_list_type(&field.ty));
+            if is_list {
+                path.push_str("[]");
+            }
+            collect_planned_calls(children, &path, schema, resolvers, ctx, calls);
+        }
+        // `__typename` and introspection are answered by the executor itself
+        PlanNode::Leaf { field } if !field.is_introspection => {
+            calls.push(planned_call(field, prefix, resolvers, ctx));
         }
+        PlanNode::Conditional { condition, node }
+            if !condition.holds(|value| substitute_variables(value, ctx)) => {}
+        PlanNode::TypeCondition { node, .. }
+        | PlanNode::Defer { node, .. }
+        | PlanNode::Stream { node, .. }
+        | PlanNode::Conditional { node, .. } => {
+            collect_planned_calls(node, prefix, schema, resolvers, ctx, calls)
+        }
+        PlanNode::Field { .. } | PlanNode::Leaf { .. } | PlanNode::FragmentSpread { .. } => {}
+    }
+}
+
+fn planned_call(
+    info: &FieldInfo,
+    prefix: &str,
+    resolvers: &ResolverMap,
+    ctx: &Context,
+) -> PlannedCall {
+    let path = if prefix.is_empty() {
+        info.response_key().to_string()
+    } else {
+        format!("{}.{}", prefix, info.response_key())
+    };
+    PlannedCall {
+        type_name: info.parent_type.clone(),
+        field_name: info.name.clone(),
+        path,
+        args: info
+            .arguments
+            .iter()
+            .map(|(name, value)| (name.clone(), substitute_variables(value, ctx)))
+            .collect(),
+        has_resolver: resolvers.contains(&info.parent_type, &info.name),
+    }
+}
+
+fn is_list_type(ty: &TypeRef) -> bool {
+    match ty {
+        TypeRef::List(_) => true,
+        TypeRef::Option(inner) => is_list_type(inner),
+        TypeRef::Named(_) => false,
+    }
+}
+
+/// Replaces `{"$var": name}` placeholders from planning with variable values.
+fn substitute_variables(value: &Value, ctx: &Context) -> Value {
+    match value {
+        Value::Object(map) => match map.get("$var") {
+            Some(Value::String(name)) if map.len() == 1 => {
+                ctx.variable(name).cloned().unwrap_or(Value::Null)
+            }
+            _ => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), substitute_variables(v, ctx)))
+                    .collect(),
+            ),
+        },
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| substitute_variables(item, ctx))
+                .collect(),
+        ),
+        other => other.clone(),
     }
 }
 
+/// A null in a non-null position, already reported as an error, which nulls
+/// the nearest nullable field or list item above it.
+#[derive(Debug)]
+struct NonNullViolation;
+
+/// The result of executing a plan node or completing a field value.
+type Completion = Result<Value, NonNullViolation>;
+
 /// Executes a plan node.
 fn execute_node<'a>(
     node: &'a PlanNode,
     parent: Value,
     path: Vec<PathSegment>,
     ctx: &'a ExecutionContext,
-) -> std::pin::Pin<Box<dyn std::future::Future<Output = Value> + Send + 'a>> {
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = Completion> + Send + 'a>> {
     Box::pin(async move {
         match node {
             PlanNode::Sequence(nodes) => execute_sequence(nodes, parent, path, ctx).await,
@@ -151,12 +907,20 @@ fn execute_node<'a>(
             }
             PlanNode::FragmentSpread { name: _ } => {
                 // Fragment spreads should be resolved during planning
-                Value::Null
-            }
-            PlanNode::Defer { node, label: _ } => {
-                // For now, execute deferred nodes synchronously
-                execute_node(node, parent, path, ctx).await
+                Ok(Value::Null)
             }
+            PlanNode::Defer { node, label } => match &ctx.deferred {
+                Some(deferred) => {
+                    deferred.write().await.push(DeferredFragment {
+                        node: node.as_ref().clone(),
+                        label: label.clone(),
+                        parent,
+                        path,
+                    });
+                    Ok(Value::Object(serde_json::Map::new()))
+                }
+                None => execute_node(node, parent, path, ctx).await,
+            },
             PlanNode::Stream {
                 node,
                 label: _,
@@ -166,10 +930,10 @@ fn execute_node<'a>(
                 execute_node(node, parent, path, ctx).await
             }
             PlanNode::Conditional { condition, node } => {
-                if *condition {
+                if condition.holds(|value| substitute_variables(value, &ctx.ctx)) {
                     execute_node(node, parent, path, ctx).await
                 } else {
-                    Value::Null
+                    Ok(Value::Object(serde_json::Map::new()))
                 }
             }
         }
@@ -182,11 +946,11 @@ async fn execute_sequence(
     parent: Value,
     path: Vec<PathSegment>,
     ctx: &ExecutionContext,
-) -> Value {
+) -> Completion {
     let mut result = serde_json::Map::new();
 
     for node in nodes {
-        let value = execute_node(node, parent.clone(), path.clone(), ctx).await;
+        let value = execute_node(node, parent.clone(), path.clone(), ctx).await?;
 
         // Merge result into the object
         if let Value::Object(map) = value {
@@ -196,59 +960,39 @@ async fn execute_sequence(
         }
     }
 
-    Value::Object(result)
+    Ok(Value::Object(result))
 }
 
 /// Executes nodes in parallel.
+///
+/// The nodes run concurrently on the current task rather than as spawned
+/// tasks: every node gets polled up to its first await before any of them
+/// is polled again, so the DataLoader keys that sibling resolvers load in a
+/// resolution frame are all queued when the loader dispatches its batch
+/// after the current tick.
 async fn execute_parallel(
     nodes: &[PlanNode],
     parent: Value,
     path: Vec<PathSegment>,
     ctx: &ExecutionContext,
-) -> Value {
-    let mut handles = Vec::with_capacity(nodes.len());
-
-    for node in nodes {
-        let parent = parent.clone();
-        let path = path.clone();
-        let resolvers = Arc::clone(&ctx.resolvers);
-        let errors = Arc::clone(&ctx.errors);
-        let config = ctx.config.clone();
-        let schema = ctx.schema.clone();
-        let user_ctx = ctx.ctx.clone();
-        let node = node.clone();
-
-        handles.push(tokio::spawn(async move {
-            let local_ctx = ExecutionContext {
-                schema,
-                ctx: user_ctx,
-                resolvers,
-                config,
-                errors,
-            };
-            execute_node(&node, parent, path, &local_ctx).await
-        }));
-    }
+) -> Completion {
+    let values = join_all(
+        nodes
+            .iter()
+            .map(|node| execute_node(node, parent.clone(), path.clone(), ctx)),
+    )
+    .await;
 
     let mut result = serde_json::Map::new();
-
-    for handle in handles {
-        match handle.await {
-            Ok(value) => {
-                if let Value::Object(map) = value {
-                    for (k, v) in map {
-                        result.insert(k, v);
-                    }
-                }
-            }
-            Err(e) => {
-                let mut errors = ctx.errors.write().await;
-                errors.push(FieldError::new(format!("Parallel execution failed: {}", e)));
+    for value in values {
+        if let Value::Object(map) = value? {
+            for (k, v) in map {
+                result.insert(k, v);
             }
         }
     }
 
-    Value::Object(result)
+    Ok(Value::Object(result))
 }
 
 /// Executes a field with nested selections.
@@ -259,33 +1003,29 @@ async fn execute_field(
     parent: Value,
     path: Vec<PathSegment>,
     ctx: &ExecutionContext,
-) -> Value {
+) -> Completion {
+    let ty = field_type(info, ctx);
+    let mut field_path = path.clone();
+    field_path.push(PathSegment::Field(response_name.to_string()));
+
     // Resolve the field value
-    let field_value = resolve_field(info, &parent, path.clone(), ctx).await;
-
-    // If the field resolved to an array, we need to execute children for each item
-    let result = match field_value {
-        Value::Array(items) => {
-            let mut results = Vec::with_capacity(items.len());
-            for (i, item) in items.into_iter().enumerate() {
-                let mut child_path = path.clone();
-                child_path.push(PathSegment::Index(i));
-                let child_result = execute_node(children, item, child_path, ctx).await;
-                results.push(child_result);
-            }
-            Value::Array(results)
+    let result = match resolve_field(info, &parent, path, ctx).await {
+        FieldValue::Resolved(value) => {
+            ctx.grow_response(response_name, &Value::Null);
+            complete_value(info, ty, Some(children), value, field_path, ctx).await?
         }
-        Value::Null => Value::Null,
-        other => {
-            // Execute children with the resolved value as parent
-            execute_node(children, other, path, ctx).await
+        // Pre-serialized JSON is embedded without resolving the selections
+        FieldValue::Raw(value) => {
+            ctx.grow_response(response_name, &value);
+            complete_value(info, ty, None, value, field_path, ctx).await?
         }
+        FieldValue::Failed => null_field(ty)?,
     };
 
     // Create an object with the response name
     let mut obj = serde_json::Map::new();
     obj.insert(response_name.to_string(), result);
-    Value::Object(obj)
+    Ok(Value::Object(obj))
 }
 
 /// Executes a leaf field.
@@ -294,13 +1034,122 @@ async fn execute_leaf(
     parent: Value,
     path: Vec<PathSegment>,
     ctx: &ExecutionContext,
-) -> Value {
+) -> Completion {
+    let ty = field_type(info, ctx);
     let response_key = info.response_key();
-    let value = resolve_field(info, &parent, path, ctx).await;
+    let mut field_path = path.clone();
+    field_path.push(PathSegment::Field(response_key.to_string()));
+
+    let value = match resolve_field(info, &parent, path, ctx).await {
+        FieldValue::Resolved(value) | FieldValue::Raw(value) => {
+            ctx.grow_response(response_key, &value);
+            complete_value(info, ty, None, value, field_path, ctx).await?
+        }
+        FieldValue::Failed => null_field(ty)?,
+    };
 
     let mut obj = serde_json::Map::new();
     obj.insert(response_key.to_string(), value);
-    Value::Object(obj)
+    Ok(Value::Object(obj))
+}
+
+/// Returns the declared type of a field, unless the schema doesn't know it,
+/// as for introspection fields.
+fn field_type<'a>(info: &FieldInfo, ctx: &'a ExecutionContext) -> Option<&'a TypeRef> {
+    if info.is_introspection {
+        return None;
+    }
+    ctx.schema
+        .get_field(&info.parent_type, &info.name)
+        .map(|field| &field.ty)
+}
+
+/// Completes the null of a field whose resolution failed with an error.
+fn null_field(ty: Option<&TypeRef>) -> Completion {
+    match ty {
+        Some(TypeRef::Option(_)) | None => Ok(Value::Null),
+        Some(_) => Err(NonNullViolation),
+    }
+}
+
+/// Completes `value` as a value of type `ty` at `path`: executes `children`
+/// on objects and on the items of lists, and checks that non-null
+/// positions aren't null.
+///
+/// A null in a non-null position is reported as an error and nulls the
+/// nearest nullable position above it; when there is none in the field, the
+/// violation propagates to the field's parent.
+fn complete_value<'a>(
+    info: &'a FieldInfo,
+    ty: Option<&'a TypeRef>,
+    children: Option<&'a PlanNode>,
+    value: Value,
+    path: Vec<PathSegment>,
+    ctx: &'a ExecutionContext,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = Completion> + Send + 'a>> {
+    Box::pin(async move {
+        let (nullable, ty) = match ty {
+            Some(TypeRef::Option(inner)) => (true, Some(inner.as_ref())),
+            Some(ty) => (false, Some(ty)),
+            None => (true, None),
+        };
+
+        let completed = match value {
+            Value::Null => Ok(Value::Null),
+            Value::Array(items) => {
+                let item_ty = match ty {
+                    Some(TypeRef::List(item)) => Some(item.as_ref()),
+                    _ => None,
+                };
+                // Items run concurrently so their DataLoader loads batch together
+                let items = join_all(items.into_iter().enumerate().map(|(i, item)| {
+                    let mut item_path = path.clone();
+                    item_path.push(PathSegment::Index(i));
+                    complete_value(info, item_ty, children, item, item_path, ctx)
+                }))
+                .await;
+                items
+                    .into_iter()
+                    .collect::<Result<_, _>>()
+                    .map(Value::Array)
+            }
+            value => match children {
+                // Execute children with the resolved value as parent
+                Some(children) => execute_node(children, value, path.clone(), ctx).await,
+                None => Ok(value),
+            },
+        };
+
+        match completed {
+            Ok(Value::Null) if !nullable => {
+                let field = format!("{}.{}", info.parent_type, info.name);
+                report_error(ResolverError::NullValue(field), path, ctx).await;
+                Err(NonNullViolation)
+            }
+            Err(NonNullViolation) if nullable => Ok(Value::Null),
+            completed => completed,
+        }
+    })
+}
+
+/// The value a field resolved to.
+enum FieldValue {
+    /// A value whose selections still need executing.
+    Resolved(Value),
+    /// JSON from [`ResolverInfo::respond_raw`], or the reference to a blob
+    /// from [`ResolverInfo::respond_binary`], complete as it is.
+    Raw(Value),
+    /// The resolution failed with an error already reported.
+    Failed,
+}
+
+impl FieldValue {
+    fn into_value(self) -> Value {
+        match self {
+            Self::Resolved(value) | Self::Raw(value) => value,
+            Self::Failed => Value::Null,
+        }
+    }
 }
 
 /// Resolves a single field.
@@ -309,54 +1158,287 @@ async fn resolve_field(
     parent: &Value,
     mut path: Vec<PathSegment>,
     ctx: &ExecutionContext,
-) -> Value {
+) -> FieldValue {
+    // Past the size limit the response is discarded anyway
+    if ctx.response_too_large() {
+        return FieldValue::Failed;
+    }
+
     // Handle __typename specially
     if info.is_introspection && info.name == "__typename" {
-        return Value::String(info.parent_type.clone());
+        return FieldValue::Resolved(Value::String(info.parent_type.clone()));
     }
 
-    // Build resolver args
-    let args = ResolverArgs::from_pairs(info.arguments.clone());
+    // Introspection is answered from the schema
+    if info.is_introspection {
+        let args = info
+            .arguments
+            .iter()
+            .map(|(name, value)| (name.clone(), substitute_variables(value, &ctx.ctx)))
+            .collect();
+        return FieldValue::Resolved(introspection::resolve(
+            &ctx.schema,
+            &info.parent_type,
+            &info.name,
+            parent,
+            &args,
+        ));
+    }
 
     // Add field to path
     path.push(PathSegment::Field(info.response_key().to_string()));
 
+    let field_def = ctx.schema.get_field(&info.parent_type, &info.name);
+
+    // `@internal` fields look nonexistent to external requests
+    if ctx.config.reject_internal_fields
+        && !ctx.ctx.internal
+        && field_def.is_some_and(FieldDef::is_internal)
+    {
+        let message = format!(
+            "Cannot query field `{}` on type `{}`",
+            info.name, info.parent_type
+        );
+        report_error(ResolverError::Client(message), path, ctx).await;
+        return FieldValue::Failed;
+    }
+
+    // Build resolver args
+    let args = match coerce_arguments(info, field_def, ctx) {
+        Ok(args) => args,
+        Err(e) => {
+            report_error(e, path, ctx).await;
+            return FieldValue::Failed;
+        }
+    };
+
     // Build resolver info
-    let resolver_info = ResolverInfo::new(&info.name, &info.parent_type)
+    let source = field_def.and_then(|field| field.source.as_deref());
+    let nullable = field_def.map_or(true, |field| matches!(field.ty, TypeRef::Option(_)));
+    let mut resolver_info = ResolverInfo::new(&info.name, &info.parent_type)
         .with_return_type(&info.return_type)
-        .with_path(path.clone());
+        .with_path(path.clone())
+        .with_nullable(nullable);
+    if let Some(source) = source {
+        resolver_info = resolver_info.with_source(source);
+    }
 
     // Get the resolver
     let resolver = ctx.resolvers.get(&info.parent_type, &info.name);
 
-    match resolver {
-        Some(r) => {
-            let result = r.resolve(parent, &args, &ctx.ctx, &resolver_info).await;
+    // Resolvers wait for a field permit before they start; property access
+    // doesn't
+    let _permit = match (&ctx.field_permits, resolver) {
+        (Some(permits), Some(_)) => permits.acquire().await.ok(),
+        _ => None,
+    };
 
-            match result {
-                Ok(value) => value,
-                Err(e) => {
-                    let mut errors = ctx.errors.write().await;
-                    errors.push(FieldError::new(e.to_string()).with_path(path));
-                    Value::Null
-                }
-            }
+    let resolved: ResolverFuture<'_> = match resolver {
+        Some(r) => r.resolve(parent, &args, &ctx.ctx, &resolver_info),
+        // No resolver found, read the parent's property
+        None => Resolver::resolve(&DefaultResolver, parent, &args, &ctx.ctx, &resolver_info),
+    };
+
+    // Run custom directive handlers around the resolution
+    let resolved = match field_def {
+        Some(field) if !ctx.directives.is_empty() => {
+            ctx.directives
+                .apply(&field.directives, resolved, &ctx.ctx, &resolver_info)
         }
-        None => {
-            // No resolver found, try default property access
-            parent.get(&info.name).cloned().unwrap_or(Value::Null)
+        _ => resolved,
+    };
+
+    // Resolvers wait for a scheduler slot; property access doesn't
+    let resolved = match (&ctx.scheduler, resolver) {
+        (Some(scheduler), Some(_)) => scheduler.run(&ctx.ctx.priority, resolved).await,
+        _ => resolved.await,
+    };
+
+    match resolved {
+        Ok(value) => match resolver_info.take_answer() {
+            Some(FieldAnswer::Raw(raw)) => FieldValue::Raw(raw.to_value()),
+            Some(FieldAnswer::Binary(blob)) => FieldValue::Raw(ctx.ctx.binaries.attach(path, blob)),
+            None => FieldValue::Resolved(value),
+        },
+        Err(e) => {
+            report_error(e, path, ctx).await;
+            FieldValue::Failed
         }
     }
 }
 
-/// Execution context.
+/// Adds a resolver error to the response, masking it if configured.
+async fn report_error(e: ResolverError, path: Vec<PathSegment>, ctx: &ExecutionContext) {
+    if let Some(hook) = &ctx.error_hook {
+        hook(&e, &path);
+    }
+    let error = if ctx.config.mask_errors && !e.is_client_facing() {
+        FieldError::new(MASKED_ERROR_MESSAGE).with_code("INTERNAL_SERVER_ERROR")
+    } else {
+        FieldError::new(e.to_string())
+    };
+    let mut errors = ctx.errors.write().await;
+    errors.push(error.with_path(path));
+}
+
+/// Substitutes variables into a field's arguments and checks them against
+/// the declared argument types.
+fn coerce_arguments(
+    info: &FieldInfo,
+    field_def: Option<&FieldDef>,
+    ctx: &ExecutionContext,
+) -> Result<ResolverArgs, ResolverError> {
+    let mut pairs = Vec::with_capacity(info.arguments.len());
+    for (name, value) in &info.arguments {
+        let value = substitute_variables(value, &ctx.ctx);
+        if let Some(arg) = field_def.and_then(|field| field.arguments.get(name)) {
+            validate_input(&value, &arg.ty, &ctx.schema).map_err(|message| {
+                ResolverError::Client(format!(
+                    "Invalid value for argument '{}': {}",
+                    name, message
+                ))
+            })?;
+        }
+        pairs.push((name.clone(), value));
+    }
+    if let Some(bounds) =
+        field_def.and_then(|field| PageBounds::for_field(field, ctx.config.pagination))
+    {
+        bounds.apply(&mut pairs)?;
+    }
+    Ok(ResolverArgs::from_pairs(pairs))
+}
+
+/// Validates an input value, enforcing `@oneOf` on input objects and the
+/// ranges of `Int` and `Float`.
+fn validate_input(value: &Value, ty: &TypeRef, schema: &Schema) -> Result<(), String> {
+    match (ty, value) {
+        (_, Value::Null) => Ok(()),
+        (TypeRef::Option(inner), _) => validate_input(value, inner, schema),
+        (TypeRef::List(inner), Value::Array(items)) => items
+            .iter()
+            .try_for_each(|item| validate_input(item, inner, schema)),
+        // A single value is coerced to a list of one
+        (TypeRef::List(inner), _) => validate_input(value, inner, schema),
+        (TypeRef::Named(name), _) if name == "Int" => validate_int(value),
+        (TypeRef::Named(name), _) if name == "Float" => validate_float(value),
+        (TypeRef::Named(name), Value::Object(fields)) => {
+            let Some(TypeDef::InputObject(input)) = schema.get_type(name) else {
+                return Ok(());
+            };
+            if input.one_of {
+                if fields.len() != 1 {
+                    return Err(format!(
+                        "OneOf input object `{}` must specify exactly one field, but {} were given",
+                        name,
+                        fields.len()
+                    ));
+                }
+                if let Some((field_name, Value::Null)) = fields.iter().next() {
+                    return Err(format!(
+                        "Field `{}.{}` of a OneOf input object must not be null",
+                        name, field_name
+                    ));
+                }
+            }
+            for (field_name, field_value) in fields {
+                if let Some(field) = input.fields.get(field_name) {
+                    validate_input(field_value, &field.ty, schema)?;
+                }
+            }
+            Ok(())
+        }
+        (TypeRef::Named(_), _) => Ok(()),
+    }
+}
+
+/// Checks that a value fits GraphQL's 32-bit `Int`.
+fn validate_int(value: &Value) -> Result<(), String> {
+    match value.as_f64() {
+        Some(n) if n.fract() != 0.0 => {
+            Err(format!("Int cannot represent non-integer value: {}", value))
+        }
+        Some(n) if n < f64::from(i32::MIN) || n > f64::from(i32::MAX) => Err(format!(
+            "Int cannot represent value outside the 32-bit signed range: {}",
+            value
+        )),
+        Some(_) => Ok(()),
+        None => Err(format!("Int cannot represent non-numeric value: {}", value)),
+    }
+}
+
+/// Checks that a value is a finite `Float`.
+///
+/// JSON numbers are always finite, so only strings such as `"NaN"` or
+/// `"Infinity"` can smuggle in the others.
+fn validate_float(value: &Value) -> Result<(), String> {
+    match value {
+        Value::Number(_) => Ok(()),
+        Value::String(s) if s.parse::<f64>().is_ok_and(|n| !n.is_finite()) => {
+            Err(format!("Float cannot represent non-finite value: {}", s))
+        }
+        _ => Err(format!(
+            "Float cannot represent non-numeric value: {}",
+            value
+        )),
+    }
+}
+
+/// Execution context.
 #[derive(Clone)]
 struct ExecutionContext {
     schema: Schema,
     ctx: Context,
     resolvers: Arc<ResolverMap>,
+    directives: Arc<DirectiveRegistry>,
     config: ExecutorConfig,
+    error_hook: Option<ErrorHook>,
+    scheduler: Option<Arc<QueryScheduler>>,
     errors: Arc<RwLock<Vec<FieldError>>>,
+    /// Collects `@defer` fragments during incremental execution; without
+    /// it they're executed in place.
+    deferred: Option<Arc<RwLock<Vec<DeferredFragment>>>>,
+    /// Approximate size in bytes of the response's data so far.
+    response_size: Arc<AtomicUsize>,
+    /// Slots of the resolvers running at once, per
+    /// [`ExecutorConfig::max_concurrent_fields`].
+    field_permits: Option<Arc<Semaphore>>,
+}
+
+impl ExecutionContext {
+    /// Adds the JSON size of `value` under `key` to the response size.
+    fn grow_response(&self, key: &str, value: &Value) {
+        if self.config.max_response_size == 0 {
+            return;
+        }
+        let mut counter = ByteCounter(0);
+        let _ = serde_json::to_writer(&mut counter, value);
+        // `"key":` and the separating comma
+        let size = key.len() + 4 + counter.0;
+        self.response_size.fetch_add(size, Ordering::Relaxed);
+    }
+
+    /// Whether the response has outgrown
+    /// [`ExecutorConfig::max_response_size`], after which no further fields
+    /// are resolved.
+    fn response_too_large(&self) -> bool {
+        self.config.max_response_size > 0
+            && self.response_size.load(Ordering::Relaxed) > self.config.max_response_size
+    }
+}
+
+/// Counts the bytes written to it.
+struct ByteCounter(usize);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        self.0 += bytes.len();
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 /// Execution context.
@@ -366,6 +1448,16 @@ pub struct Context {
     pub data: HashMap<String, serde_json::Value>,
     /// Variables from the request.
     pub variables: HashMap<String, serde_json::Value>,
+    /// Priority of the operation's resolvers in the executor's
+    /// [`QueryScheduler`].
+    pub priority: TaskPriority,
+    /// Whether the request comes from the server's own code, which may query
+    /// `@internal` fields.
+    pub internal: bool,
+    /// DataLoaders of the request.
+    loaders: LoaderRegistry,
+    /// Binary blobs of the response.
+    binaries: BinaryAttachments,
 }
 
 impl Default for Context {
@@ -380,6 +1472,10 @@ impl Context {
         Self {
             data: HashMap::new(),
             variables: HashMap::new(),
+            priority: TaskPriority::default(),
+            internal: false,
+            loaders: LoaderRegistry::new(),
+            binaries: BinaryAttachments::new(),
         }
     }
 
@@ -388,9 +1484,26 @@ impl Context {
         Self {
             data: HashMap::new(),
             variables,
+            priority: TaskPriority::default(),
+            internal: false,
+            loaders: LoaderRegistry::new(),
+            binaries: BinaryAttachments::new(),
         }
     }
 
+    /// Sets the priority of the operation's resolvers.
+    pub fn with_priority(mut self, priority: TaskPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Marks the request as coming from the server's own code, allowing it
+    /// to query `@internal` fields.
+    pub fn with_internal(mut self, internal: bool) -> Self {
+        self.internal = internal;
+        self
+    }
+
     /// Sets a value in the context.
     pub fn set<T: Serialize>(&mut self, key: impl Into<String>, value: T) {
         if let Ok(v) = serde_json::to_value(value) {
@@ -416,6 +1529,38 @@ impl Context {
             .get(name)
             .and_then(|v| serde_json::from_value(v.clone()).ok())
     }
+
+    /// Returns the request's DataLoader named `name`, creating it from
+    /// `batch_fn` on first use.
+    ///
+    /// The loader is shared by every resolver of the request, so keys they
+    /// load in the same tick are fetched with one `batch_fn` call and values
+    /// are cached until the request completes.
+    pub fn create_loader<K, V, F, Fut>(&self, name: &str, batch_fn: F) -> Arc<DataLoader<K, V>>
+    where
+        K: Eq + Hash + Clone + Send + 'static,
+        V: Clone + Send + 'static,
+        F: Fn(Vec<K>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = HashMap<K, V>> + Send + 'static,
+    {
+        self.loaders
+            .get_or_insert_with(name, || dataloader::create_loader(batch_fn))
+    }
+
+    /// Gets the request's DataLoader named `name`.
+    pub fn loader<K, V>(&self, name: &str) -> Option<Arc<DataLoader<K, V>>>
+    where
+        K: Send + 'static,
+        V: Send + 'static,
+    {
+        self.loaders.get(name)
+    }
+
+    /// Removes and returns the blobs fields answered with through
+    /// [`ResolverInfo::respond_binary`] while executing the request.
+    pub fn take_binaries(&self) -> Vec<AttachedBinary> {
+        self.binaries.take()
+    }
 }
 
 /// A GraphQL response.
@@ -463,6 +1608,43 @@ impl Response {
     pub fn has_data(&self) -> bool {
         self.data.is_some()
     }
+
+    /// Serializes the response to JSON, aborting once the output exceeds
+    /// `max_size` bytes. Zero disables the limit.
+    pub fn to_json_limited(&self, max_size: usize) -> Result<Vec<u8>, FieldError> {
+        let mut writer = LimitedWriter {
+            buf: Vec::new(),
+            limit: max_size,
+        };
+        match serde_json::to_writer(&mut writer, self) {
+            Ok(()) => Ok(writer.buf),
+            Err(error) if error.is_io() => Err(response_too_large(max_size)),
+            Err(error) => Err(
+                FieldError::new(format!("Failed to serialize response: {}", error))
+                    .with_code("INTERNAL_SERVER_ERROR"),
+            ),
+        }
+    }
+}
+
+/// A buffer that refuses writes past `limit` bytes.
+struct LimitedWriter {
+    buf: Vec<u8>,
+    limit: usize,
+}
+
+impl std::io::Write for LimitedWriter {
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        if self.limit > 0 && self.buf.len() + bytes.len() > self.limit {
+            return Err(std::io::Error::other("response size limit exceeded"));
+        }
+        self.buf.extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 /// A field error.
@@ -479,13 +1661,22 @@ pub struct FieldError {
 }
 
 /// A path segment.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum PathSegment {
     Field(String),
     Index(usize),
 }
 
+impl From<PathSegment> for streaming::PathSegment {
+    fn from(segment: PathSegment) -> Self {
+        match segment {
+            PathSegment::Field(name) => Self::Field(name),
+            PathSegment::Index(index) => Self::Index(index),
+        }
+    }
+}
+
 impl FieldError {
     /// Creates a new field error.
     pub fn new(message: impl Into<String>) -> Self {
@@ -519,65 +1710,34 @@ impl FieldError {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::query::{FieldInfo, PlanNode, QueryPlan};
-    use crate::resolver::{FnResolver, ResolverMap};
-    use crate::schema::{FieldDef, ObjectDef, SchemaBuilder, TypeDef, TypeRef};
-    use bgql_semantic::hir::HirOperationKind;
+    use crate::query::{FieldInfo, PlanNode, QueryPlan, QueryPlanner};
+    use crate::resolver::{FnResolver, RawJson, ResolverMap};
+    use crate::schema::{AppliedDirective, FieldDef, TypeDef, TypeRef, INTERNAL_DIRECTIVE};
+    use crate::test_support::{
+        field_def, field_info, field_node, leaf, object_def, schema_with_fields,
+    };
+    use bgql_core::Span;
+    use bgql_semantic::hir::{
+        HirCondition, HirFieldSelection, HirOperation, HirOperationKind, HirSelection, HirValue,
+    };
     use indexmap::IndexMap;
 
     fn create_test_schema() -> Schema {
-        let mut user_fields = IndexMap::new();
-        user_fields.insert(
-            "id".to_string(),
-            FieldDef {
-                name: "id".to_string(),
-                description: None,
-                ty: TypeRef::Named("ID".to_string()),
-                arguments: IndexMap::new(),
-                deprecated: false,
-                deprecation_reason: None,
-            },
-        );
-        user_fields.insert(
-            "name".to_string(),
-            FieldDef {
-                name: "name".to_string(),
-                description: None,
-                ty: TypeRef::Named("String".to_string()),
-                arguments: IndexMap::new(),
-                deprecated: false,
-                deprecation_reason: None,
-            },
-        );
-
-        let mut query_fields = IndexMap::new();
-        query_fields.insert(
-            "user".to_string(),
-            FieldDef {
-                name: "user".to_string(),
-                description: None,
-                ty: TypeRef::Named("User".to_string()),
-                arguments: IndexMap::new(),
-                deprecated: false,
-                deprecation_reason: None,
-            },
-        );
+        schema_with_fields(vec![
+            ("Query", vec![field_def("user", TypeRef::named("User"))]),
+            (
+                "User",
+                vec![
+                    field_def("id", TypeRef::named("ID")),
+                    field_def("name", TypeRef::named("String")),
+                ],
+            ),
+        ])
+    }
 
-        SchemaBuilder::new()
-            .query_type("Query")
-            .add_type(TypeDef::Object(ObjectDef {
-                name: "Query".to_string(),
-                description: None,
-                fields: query_fields,
-                implements: Vec::new(),
-            }))
-            .add_type(TypeDef::Object(ObjectDef {
-                name: "User".to_string(),
-                description: None,
-                fields: user_fields,
-                implements: Vec::new(),
-            }))
-            .build()
+    /// `user { <fields> }` on the test schema.
+    fn user_plan(children: PlanNode) -> QueryPlan {
+        QueryPlan::simple(field_node(field_info("Query", "user", "User"), children))
     }
 
     #[tokio::test]
@@ -596,45 +1756,10 @@ mod tests {
         let schema = create_test_schema();
         let ctx = Context::new();
 
-        let plan = QueryPlan {
-            root: PlanNode::Field {
-                info: FieldInfo {
-                    name: "user".to_string(),
-                    alias: None,
-                    parent_type: "Query".to_string(),
-                    return_type: "User".to_string(),
-                    arguments: Vec::new(),
-                    is_introspection: false,
-                },
-                response_name: "user".to_string(),
-                children: Box::new(PlanNode::Parallel(vec![
-                    PlanNode::Leaf {
-                        field: FieldInfo {
-                            name: "id".to_string(),
-                            alias: None,
-                            parent_type: "User".to_string(),
-                            return_type: "ID".to_string(),
-                            arguments: Vec::new(),
-                            is_introspection: false,
-                        },
-                    },
-                    PlanNode::Leaf {
-                        field: FieldInfo {
-                            name: "name".to_string(),
-                            alias: None,
-                            parent_type: "User".to_string(),
-                            return_type: "String".to_string(),
-                            arguments: Vec::new(),
-                            is_introspection: false,
-                        },
-                    },
-                ])),
-            },
-            operation_name: None,
-            operation_kind: HirOperationKind::Query,
-            complexity: 0,
-            max_depth: 0,
-        };
+        let plan = user_plan(PlanNode::Parallel(vec![
+            leaf("User", "id", "ID"),
+            leaf("User", "name", "String"),
+        ]));
 
         let response = executor.execute(&plan, &schema, &ctx).await;
 
@@ -646,6 +1771,322 @@ mod tests {
         assert_eq!(data["user"]["name"], "Alice");
     }
 
+    #[tokio::test]
+    async fn test_default_resolver_reads_parent() {
+        let mut resolvers = ResolverMap::new();
+        resolvers.register_fn("Query", "user", |_parent, _args, _ctx, _info| {
+            Ok(serde_json::json!({"name": "Alice"}))
+        });
+
+        let mut schema = create_test_schema();
+        if let Some(TypeDef::Object(user)) = schema.types.get_mut("User") {
+            user.fields.insert(
+                "nickname".to_string(),
+                field_def("nickname", TypeRef::option(TypeRef::named("String"))),
+            );
+        }
+
+        let leaf = |name: &str| leaf("User", name, "String");
+        let plan = |leaves: Vec<PlanNode>| user_plan(PlanNode::Parallel(leaves));
+        let executor = Executor::with_resolvers(resolvers);
+
+        // `name` comes from the parent without a resolver and the optional
+        // `nickname` is null
+        let response = executor
+            .execute(
+                &plan(vec![leaf("name"), leaf("nickname")]),
+                &schema,
+                &Context::new(),
+            )
+            .await;
+        assert!(!response.has_errors());
+        let data = response.data.unwrap();
+        assert_eq!(data["user"]["name"], "Alice");
+        assert_eq!(data["user"]["nickname"], Value::Null);
+
+        // The non-null `id` is missing, which nulls the non-null `user` and
+        // with it the data
+        let with_id = plan(vec![leaf("name"), leaf("nickname"), leaf("id")]);
+        let response = executor.execute(&with_id, &schema, &Context::new()).await;
+        assert_eq!(response.data, Some(Value::Null));
+        let errors = response.errors.unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].message,
+            "Null value for non-nullable field: User.id"
+        );
+        assert_eq!(
+            errors[0].path,
+            Some(vec![
+                PathSegment::Field("user".into()),
+                PathSegment::Field("id".into())
+            ])
+        );
+
+        // An optional `user` is the nearest nullable parent
+        if let Some(TypeDef::Object(query)) = schema.types.get_mut("Query") {
+            let user = query.fields.get_mut("user").unwrap();
+            user.ty = TypeRef::option(TypeRef::named("User"));
+        }
+        let response = executor.execute(&with_id, &schema, &Context::new()).await;
+        assert_eq!(response.data, Some(serde_json::json!({ "user": null })));
+        assert_eq!(response.errors.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_execute_introspection() {
+        let schema = &create_test_schema();
+        let field = |name: &str, arguments, selections| {
+            HirSelection::Field(HirFieldSelection {
+                alias: None,
+                name: name.to_string(),
+                arguments,
+                selections,
+                stream: None,
+                condition: HirCondition::default(),
+            })
+        };
+        let execute = |selections| {
+            let operation = HirOperation {
+                kind: HirOperationKind::Query,
+                name: None,
+                variables: Vec::new(),
+                selections,
+                span: Span::empty(0),
+            };
+            let plan = QueryPlanner::new().plan(&operation, schema).unwrap();
+            async move {
+                let response = Executor::new()
+                    .execute(&plan, schema, &Context::new())
+                    .await;
+                assert!(response.errors.is_none(), "{:?}", response.errors);
+                response.data.unwrap()
+            }
+        };
+
+        // { __schema { types { name } } }
+        let data = execute(vec![field(
+            "__schema",
+            vec![],
+            vec![field("types", vec![], vec![field("name", vec![], vec![])])],
+        )])
+        .await;
+        let names: Vec<_> = data["__schema"]["types"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|ty| ty["name"].as_str())
+            .collect();
+        for name in ["Query", "User", "ID", "__Schema", "__Type"] {
+            assert!(names.contains(&name), "{:?}", names);
+        }
+
+        // { __type(name: "User") { fields { name type { kind ofType { name } } } } }
+        let data = execute(vec![field(
+            "__type",
+            vec![("name".to_string(), HirValue::String("User".to_string()))],
+            vec![field(
+                "fields",
+                vec![],
+                vec![
+                    field("name", vec![], vec![]),
+                    field(
+                        "type",
+                        vec![],
+                        vec![
+                            field("kind", vec![], vec![]),
+                            field("ofType", vec![], vec![field("name", vec![], vec![])]),
+                        ],
+                    ),
+                ],
+            )],
+        )])
+        .await;
+        let id = &data["__type"]["fields"][0];
+        assert_eq!(id["name"], "id");
+        assert_eq!(id["type"]["kind"], "NON_NULL");
+        assert_eq!(id["type"]["ofType"]["name"], "ID");
+    }
+
+    #[tokio::test]
+    async fn test_execute_raw_json() {
+        let mut resolvers = ResolverMap::new();
+        resolvers.register_fn("Query", "user", |_parent, _args, _ctx, info| {
+            let cached = RawJson::new(r#"{"id":"1","name":"Alice","tags":["a","b"]}"#)?;
+            Ok(info.respond_raw(cached))
+        });
+        // Never called: the raw JSON subtree is not resolved field by field
+        resolvers.register_fn("User", "name", |_parent, _args, _ctx, _info| {
+            Ok(serde_json::json!("resolved"))
+        });
+
+        let executor = Executor::with_resolvers(resolvers);
+        let schema = create_test_schema();
+        let ctx = Context::new();
+
+        let plan = user_plan(leaf("User", "name", "String"));
+
+        let response = executor.execute(&plan, &schema, &ctx).await;
+
+        assert!(!response.has_errors());
+        assert_eq!(
+            response.data.unwrap()["user"],
+            serde_json::json!({"id": "1", "name": "Alice", "tags": ["a", "b"]})
+        );
+        assert!(RawJson::new("{not json").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_internal_fields() {
+        let mut resolvers = ResolverMap::new();
+        resolvers.register_fn("Query", "auditLog", |_parent, _args, _ctx, _info| {
+            Ok(serde_json::json!("42 entries"))
+        });
+
+        let mut schema = create_test_schema();
+        let Some(TypeDef::Object(query)) = schema.types.get_mut("Query") else {
+            panic!("expected Query object type");
+        };
+        query.fields.insert(
+            "auditLog".to_string(),
+            FieldDef {
+                directives: vec![AppliedDirective::new(INTERNAL_DIRECTIVE)],
+                ..field_def("auditLog", TypeRef::named("String"))
+            },
+        );
+
+        let plan = QueryPlan::simple(leaf("Query", "auditLog", "String"));
+        let executor = Executor::new_with(
+            ExecutorConfig {
+                reject_internal_fields: true,
+                ..Default::default()
+            },
+            resolvers,
+        );
+
+        let external = executor.execute(&plan, &schema, &Context::new()).await;
+        let errors = external.errors.unwrap();
+        assert_eq!(
+            errors[0].message,
+            "Cannot query field `auditLog` on type `Query`"
+        );
+
+        let internal = Context::new().with_internal(true);
+        let response = executor.execute(&plan, &schema, &internal).await;
+        assert!(!response.has_errors());
+        assert_eq!(response.data.unwrap()["auditLog"], "42 entries");
+    }
+
+    #[tokio::test]
+    async fn test_stream_binary_field() {
+        use crate::binary_transport::{BinaryBlob, BinaryProtocol};
+
+        let avatar: Vec<u8> = (0..300_000u32).map(|i| (i % 251) as u8).collect();
+        let mut resolvers = ResolverMap::new();
+        let data = avatar.clone();
+        resolvers.register_fn("Query", "avatar", move |_parent, _args, _ctx, info| {
+            Ok(info.respond_binary(BinaryBlob::new("image/png", data.clone())))
+        });
+
+        let executor = Executor::with_resolvers(resolvers);
+        let schema = create_test_schema();
+        let ctx = Context::new();
+
+        let plan = QueryPlan::simple(leaf("Query", "avatar", "File"));
+
+        let response = executor.execute(&plan, &schema, &ctx).await;
+
+        assert!(!response.has_errors());
+        let reference = response.data.unwrap()["avatar"].clone();
+        assert_eq!(reference["id"], "binary-0");
+        assert_eq!(reference["size"], 300_000);
+
+        // The blob goes over the binary protocol instead of into the JSON
+        let binaries = ctx.take_binaries();
+        assert_eq!(binaries.len(), 1);
+        assert_eq!(binaries[0].path, [PathSegment::Field("avatar".into())]);
+        let mut wire = Vec::new();
+        let sent = BinaryProtocol::encode_blob(&binaries[0], &mut wire, 65536)
+            .await
+            .unwrap();
+        assert_eq!(sent, avatar.len() as u64);
+
+        let mut received = Vec::new();
+        let (metadata, decoded) = BinaryProtocol::decode_blob(&mut wire.as_slice(), &mut received)
+            .await
+            .unwrap();
+        assert_eq!(metadata.id, "binary-0");
+        assert_eq!(decoded, sent);
+        assert_eq!(received, avatar);
+    }
+
+    #[tokio::test]
+    async fn test_resume_stream_after_restart() {
+        use crate::state::MemoryCheckpointStore;
+
+        fn users_executor() -> Executor {
+            let mut resolvers = ResolverMap::new();
+            resolvers.register_fn("Query", "users", |_parent, _args, _ctx, _info| {
+                let users: Vec<_> = (1..=8)
+                    .map(|i| serde_json::json!({"id": i, "name": format!("user{}", i)}))
+                    .collect();
+                Ok(Value::Array(users))
+            });
+            Executor::with_resolvers(resolvers)
+        }
+
+        let plan = QueryPlan::simple(PlanNode::Parallel(vec![PlanNode::Stream {
+            node: Box::new(field_node(
+                field_info("Query", "users", "User"),
+                leaf("User", "name", "String"),
+            )),
+            label: Some("users".to_string()),
+            initial_count: 2,
+        }]));
+        let schema = create_test_schema();
+        let store = Arc::new(MemoryCheckpointStore::new());
+
+        let (initial, mut stream) = users_executor()
+            .execute_stream(&plan, &schema, &Context::new(), store.clone())
+            .await;
+        assert_eq!(
+            initial.data.unwrap(),
+            serde_json::json!({"users": [{"name": "user1"}, {"name": "user2"}]})
+        );
+        for i in 3..=5 {
+            let payload = stream.next().await.unwrap();
+            assert_eq!(
+                payload.items,
+                vec![serde_json::json!({"name": format!("user{}", i)})]
+            );
+        }
+        let token = stream.resume_token().to_string();
+
+        // Restart: the stream and its executor are gone, only the store survives
+        drop(stream);
+        let mut resumed = users_executor()
+            .resume_stream(&plan, &schema, &Context::new(), store.clone(), &token)
+            .await
+            .unwrap();
+
+        let mut names = Vec::new();
+        let mut last_has_next = true;
+        while let Some(payload) = resumed.next().await {
+            names.push(payload.items[0]["name"].clone());
+            last_has_next = payload.has_next;
+        }
+        assert_eq!(names, ["user6", "user7", "user8"]);
+        assert!(!last_has_next);
+        assert_eq!(resumed.state().phase, ExecutionPhase::Completed);
+
+        // A completed stream can't be resumed again
+        assert!(store.is_empty());
+        assert!(users_executor()
+            .resume_stream(&plan, &schema, &Context::new(), store, &token)
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_execute_typename() {
         let resolvers = ResolverMap::new();
@@ -653,22 +2094,12 @@ mod tests {
         let schema = create_test_schema();
         let ctx = Context::new();
 
-        let plan = QueryPlan {
-            root: PlanNode::Leaf {
-                field: FieldInfo {
-                    name: "__typename".to_string(),
-                    alias: None,
-                    parent_type: "Query".to_string(),
-                    return_type: "String".to_string(),
-                    arguments: Vec::new(),
-                    is_introspection: true,
-                },
+        let plan = QueryPlan::simple(PlanNode::Leaf {
+            field: FieldInfo {
+                is_introspection: true,
+                ..field_info("Query", "__typename", "String")
             },
-            operation_name: None,
-            operation_kind: HirOperationKind::Query,
-            complexity: 0,
-            max_depth: 0,
-        };
+        });
 
         let response = executor.execute(&plan, &schema, &ctx).await;
 
@@ -690,22 +2121,12 @@ mod tests {
         let schema = create_test_schema();
         let ctx = Context::new();
 
-        let plan = QueryPlan {
-            root: PlanNode::Leaf {
-                field: FieldInfo {
-                    name: "user".to_string(),
-                    alias: None,
-                    parent_type: "Query".to_string(),
-                    return_type: "User".to_string(),
-                    arguments: vec![("id".to_string(), serde_json::json!("42"))],
-                    is_introspection: false,
-                },
+        let plan = QueryPlan::simple(PlanNode::Leaf {
+            field: FieldInfo {
+                arguments: vec![("id".to_string(), serde_json::json!("42"))],
+                ..field_info("Query", "user", "User")
             },
-            operation_name: None,
-            operation_kind: HirOperationKind::Query,
-            complexity: 0,
-            max_depth: 0,
-        };
+        });
 
         let response = executor.execute(&plan, &schema, &ctx).await;
 
@@ -728,28 +2149,374 @@ mod tests {
         let schema = create_test_schema();
         let ctx = Context::new();
 
-        let plan = QueryPlan {
-            root: PlanNode::Leaf {
+        let plan = QueryPlan::simple(leaf("Query", "user", "User"));
+
+        let response = executor.execute(&plan, &schema, &ctx).await;
+
+        assert!(response.has_errors());
+        let errors = response.errors.unwrap();
+        assert!(errors[0].message.contains("User not found"));
+    }
+
+    #[tokio::test]
+    async fn test_mask_internal_errors() {
+        let mut resolvers = ResolverMap::new();
+        resolvers.register_fn("Query", "user", |_parent, _args, _ctx, _info| {
+            Err(crate::resolver::ResolverError::Internal(
+                "connection to db-primary:5432 refused".to_string(),
+            ))
+        });
+        resolvers.register_fn("User", "name", |_parent, _args, _ctx, _info| {
+            Err(crate::resolver::ResolverError::Client(
+                "Name must not be empty".to_string(),
+            ))
+        });
+
+        let logged = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = Arc::clone(&logged);
+        let config = ExecutorConfig {
+            mask_errors: true,
+            ..Default::default()
+        };
+        let executor = Executor::new_with(config, resolvers).with_error_hook(move |error, path| {
+            log.lock().unwrap().push((error.to_string(), path.to_vec()));
+        });
+        let schema = create_test_schema();
+        let ctx = Context::new();
+
+        let response = executor
+            .execute(
+                &QueryPlan::simple(leaf("Query", "user", "String")),
+                &schema,
+                &ctx,
+            )
+            .await;
+        let errors = response.errors.unwrap();
+        assert_eq!(errors[0].message, MASKED_ERROR_MESSAGE);
+        assert_eq!(
+            errors[0].extensions.as_ref().unwrap()["code"],
+            "INTERNAL_SERVER_ERROR"
+        );
+
+        let response = executor
+            .execute(
+                &QueryPlan::simple(leaf("User", "name", "String")),
+                &schema,
+                &ctx,
+            )
+            .await;
+        let errors = response.errors.unwrap();
+        assert_eq!(errors[0].message, "Name must not be empty");
+
+        // The hook sees the original errors
+        let logged = logged.lock().unwrap();
+        assert_eq!(
+            *logged,
+            [
+                (
+                    "Internal error: connection to db-primary:5432 refused".to_string(),
+                    vec![PathSegment::Field("user".to_string())],
+                ),
+                (
+                    "Name must not be empty".to_string(),
+                    vec![PathSegment::Field("name".to_string())],
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_response_size_limit() {
+        let mut resolvers = ResolverMap::new();
+        resolvers.register_fn("Query", "user", |_parent, args, _ctx, _info| {
+            let count = args.get_as::<u64>("count").unwrap_or(0);
+            Ok((0..count).map(|i| format!("item-{i}")).collect())
+        });
+        let config = ExecutorConfig {
+            max_response_size: 1024,
+            ..Default::default()
+        };
+        let executor = Executor::new_with(config, resolvers);
+        let schema = create_test_schema();
+        let ctx = Context::new();
+
+        let plan = |count: u64| {
+            QueryPlan::simple(PlanNode::Leaf {
+                field: FieldInfo {
+                    arguments: vec![("count".to_string(), serde_json::json!(count))],
+                    ..field_info("Query", "user", "String")
+                },
+            })
+        };
+
+        let response = executor.execute(&plan(10), &schema, &ctx).await;
+        let body: Value = serde_json::from_slice(&executor.serialize_response(&response)).unwrap();
+        assert_eq!(body["data"]["user"][9], "item-9");
+
+        // Execution stops as the results outgrow the limit
+        let response = executor.execute(&plan(10_000), &schema, &ctx).await;
+        assert!(response.data.is_none());
+        let errors = response.errors.as_ref().unwrap();
+        assert_eq!(
+            errors[0].extensions.as_ref().unwrap()["code"],
+            "RESPONSE_TOO_LARGE"
+        );
+
+        let bytes = executor.serialize_response(&response);
+        let body: Value = serde_json::from_slice(&bytes).unwrap();
+        assert!(body.get("data").is_none());
+        assert_eq!(
+            body["errors"][0]["message"],
+            "Response exceeds the maximum size of 1024 bytes"
+        );
+        assert_eq!(
+            body["errors"][0]["extensions"]["code"],
+            "RESPONSE_TOO_LARGE"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_one_of_input_requires_exactly_one_field() {
+        let input_field = |name: &str, ty: &str| {
+            (
+                name.to_string(),
+                crate::schema::InputFieldDef {
+                    name: name.to_string(),
+                    description: None,
+                    ty: TypeRef::Option(Box::new(TypeRef::Named(ty.to_string()))),
+                    default_value: None,
+                },
+            )
+        };
+
+        let user = FieldDef {
+            arguments: IndexMap::from([(
+                "by".to_string(),
+                crate::schema::InputFieldDef {
+                    name: "by".to_string(),
+                    description: None,
+                    ty: TypeRef::Named("UserBy".to_string()),
+                    default_value: None,
+                },
+            )]),
+            ..field_def("user", TypeRef::named("String"))
+        };
+        let mut schema = schema_with_fields(vec![("Query", vec![user])]);
+        schema.types.insert(
+            "UserBy".to_string(),
+            TypeDef::InputObject(crate::schema::InputObjectDef {
+                name: "UserBy".to_string(),
+                description: None,
+                fields: IndexMap::from([input_field("id", "ID"), input_field("email", "String")]),
+                one_of: true,
+            }),
+        );
+
+        let mut resolvers = ResolverMap::new();
+        resolvers.register_fn("Query", "user", |_parent, _args, _ctx, _info| {
+            Ok(serde_json::json!("found"))
+        });
+        let executor = Executor::with_resolvers(resolvers);
+        let ctx = Context::new();
+
+        let plan = |by: Value| {
+            QueryPlan::simple(PlanNode::Leaf {
                 field: FieldInfo {
-                    name: "user".to_string(),
-                    alias: None,
-                    parent_type: "Query".to_string(),
-                    return_type: "User".to_string(),
-                    arguments: Vec::new(),
-                    is_introspection: false,
+                    arguments: vec![("by".to_string(), by)],
+                    ..field_info("Query", "user", "String")
                 },
+            })
+        };
+
+        let response = executor
+            .execute(&plan(serde_json::json!({"id": "1"})), &schema, &ctx)
+            .await;
+        assert!(!response.has_errors());
+        assert_eq!(response.data.unwrap()["user"], "found");
+
+        let response = executor
+            .execute(&plan(serde_json::json!({})), &schema, &ctx)
+            .await;
+        let errors = response.errors.unwrap();
+        assert_eq!(
+            errors[0].message,
+            "Invalid value for argument 'by': OneOf input object `UserBy` must specify exactly one field, but 0 were given"
+        );
+        assert_eq!(response.data.unwrap()["user"], Value::Null);
+
+        let response = executor
+            .execute(
+                &plan(serde_json::json!({"id": "1", "email": "a@example.com"})),
+                &schema,
+                &ctx,
+            )
+            .await;
+        let errors = response.errors.unwrap();
+        assert!(errors[0].message.contains("but 2 were given"));
+    }
+
+    #[tokio::test]
+    async fn test_numeric_arguments_are_range_checked() {
+        let argument = |name: &str, ty: &str| {
+            (
+                name.to_string(),
+                crate::schema::InputFieldDef {
+                    name: name.to_string(),
+                    description: None,
+                    ty: TypeRef::Option(Box::new(TypeRef::Named(ty.to_string()))),
+                    default_value: None,
+                },
+            )
+        };
+
+        let scale = FieldDef {
+            arguments: IndexMap::from([argument("count", "Int"), argument("factor", "Float")]),
+            ..field_def("scale", TypeRef::named("String"))
+        };
+        let schema = schema_with_fields(vec![("Query", vec![scale])]);
+
+        let mut resolvers = ResolverMap::new();
+        resolvers.register_fn("Query", "scale", |_parent, _args, _ctx, _info| {
+            Ok(serde_json::json!("ok"))
+        });
+        let executor = Executor::with_resolvers(resolvers);
+
+        let plan = QueryPlan::simple(PlanNode::Leaf {
+            field: FieldInfo {
+                arguments: vec![
+                    ("count".to_string(), serde_json::json!({"$var": "count"})),
+                    ("factor".to_string(), serde_json::json!({"$var": "factor"})),
+                ],
+                ..field_info("Query", "scale", "String")
             },
-            operation_name: None,
-            operation_kind: HirOperationKind::Query,
-            complexity: 0,
-            max_depth: 0,
+        });
+        let execute = |count: Value, factor: Value| {
+            let ctx = Context::with_variables(HashMap::from([
+                ("count".to_string(), count),
+                ("factor".to_string(), factor),
+            ]));
+            let executor = executor.clone();
+            let plan = plan.clone();
+            let schema = schema.clone();
+            async move { executor.execute(&plan, &schema, &ctx).await }
         };
 
+        let response = execute(serde_json::json!(2147483647), serde_json::json!(1.5)).await;
+        assert!(!response.has_errors());
+        assert_eq!(response.data.unwrap()["scale"], "ok");
+
+        let response = execute(serde_json::json!(2147483648_i64), serde_json::json!(1.5)).await;
+        assert_eq!(
+            response.errors.unwrap()[0].message,
+            "Invalid value for argument 'count': Int cannot represent value outside the 32-bit signed range: 2147483648"
+        );
+
+        for factor in ["NaN", "Infinity"] {
+            let response = execute(serde_json::json!(1), serde_json::json!(factor)).await;
+            assert_eq!(
+                response.errors.unwrap()[0].message,
+                format!(
+                    "Invalid value for argument 'factor': Float cannot represent non-finite value: {}",
+                    factor
+                )
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sibling_loads_dispatch_one_batch() {
+        let batches = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&batches);
+        let mut resolvers = ResolverMap::new();
+        resolvers.register_async("Query", "user", move |_parent, args, ctx, _info| {
+            let batches = Arc::clone(&recorded);
+            async move {
+                let id: String = args.require("id")?;
+                let users = ctx.create_loader("users", move |ids: Vec<String>| {
+                    batches.lock().unwrap().push(ids.clone());
+                    async move {
+                        ids.into_iter()
+                            .map(|id| (id.clone(), serde_json::json!({ "id": id })))
+                            .collect()
+                    }
+                });
+                Ok(users.load(id).await.unwrap_or(Value::Null))
+            }
+        });
+
+        let executor = Executor::with_resolvers(resolvers);
+        let schema = create_test_schema();
+        let ctx = Context::new();
+
+        let plan = QueryPlan::simple(PlanNode::Parallel(
+            (1..=5)
+                .map(|i| PlanNode::Leaf {
+                    field: FieldInfo {
+                        alias: Some(format!("u{}", i)),
+                        arguments: vec![("id".to_string(), serde_json::json!(i.to_string()))],
+                        ..field_info("Query", "user", "User")
+                    },
+                })
+                .collect(),
+        ));
+
         let response = executor.execute(&plan, &schema, &ctx).await;
 
-        assert!(response.has_errors());
-        let errors = response.errors.unwrap();
-        assert!(errors[0].message.contains("User not found"));
+        assert!(!response.has_errors());
+        let data = response.data.unwrap();
+        for i in 1..=5 {
+            assert_eq!(data[format!("u{}", i)]["id"], i.to_string());
+        }
+        assert_eq!(
+            *batches.lock().unwrap(),
+            vec![vec!["1", "2", "3", "4", "5"]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_concurrent_fields() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (counter, highest) = (Arc::clone(&running), Arc::clone(&peak));
+        let mut resolvers = ResolverMap::new();
+        resolvers.register_async("Query", "user", move |_parent, args, _ctx, _info| {
+            let (running, peak) = (Arc::clone(&counter), Arc::clone(&highest));
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                Ok(serde_json::json!({ "id": args.get("id").cloned() }))
+            }
+        });
+
+        let config = ExecutorConfig {
+            max_concurrent_fields: 2,
+            ..ExecutorConfig::default()
+        };
+        let executor = Executor::new_with(config, resolvers);
+        let schema = create_test_schema();
+
+        let plan = QueryPlan::simple(PlanNode::Parallel(
+            (1..=6)
+                .map(|i| PlanNode::Leaf {
+                    field: FieldInfo {
+                        alias: Some(format!("u{}", i)),
+                        arguments: vec![("id".to_string(), serde_json::json!(i.to_string()))],
+                        ..field_info("Query", "user", "User")
+                    },
+                })
+                .collect(),
+        ));
+
+        let response = executor.execute(&plan, &schema, &Context::new()).await;
+
+        assert!(!response.has_errors());
+        let data = response.data.unwrap();
+        for i in 1..=6 {
+            assert_eq!(data[format!("u{}", i)]["id"], i.to_string());
+        }
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
     }
 
     #[tokio::test]
@@ -767,45 +2534,13 @@ mod tests {
         let schema = create_test_schema();
         let ctx = Context::new();
 
-        let plan = QueryPlan {
-            root: PlanNode::Field {
-                info: FieldInfo {
-                    name: "users".to_string(),
-                    alias: None,
-                    parent_type: "Query".to_string(),
-                    return_type: "User".to_string(),
-                    arguments: Vec::new(),
-                    is_introspection: false,
-                },
-                response_name: "users".to_string(),
-                children: Box::new(PlanNode::Parallel(vec![
-                    PlanNode::Leaf {
-                        field: FieldInfo {
-                            name: "id".to_string(),
-                            alias: None,
-                            parent_type: "User".to_string(),
-                            return_type: "ID".to_string(),
-                            arguments: Vec::new(),
-                            is_introspection: false,
-                        },
-                    },
-                    PlanNode::Leaf {
-                        field: FieldInfo {
-                            name: "name".to_string(),
-                            alias: None,
-                            parent_type: "User".to_string(),
-                            return_type: "String".to_string(),
-                            arguments: Vec::new(),
-                            is_introspection: false,
-                        },
-                    },
-                ])),
-            },
-            operation_name: None,
-            operation_kind: HirOperationKind::Query,
-            complexity: 0,
-            max_depth: 0,
-        };
+        let plan = QueryPlan::simple(field_node(
+            field_info("Query", "users", "User"),
+            PlanNode::Parallel(vec![
+                leaf("User", "id", "ID"),
+                leaf("User", "name", "String"),
+            ]),
+        ));
 
         let response = executor.execute(&plan, &schema, &ctx).await;
 
@@ -817,6 +2552,222 @@ mod tests {
         assert_eq!(users[1]["name"], "Bob");
     }
 
+    #[tokio::test]
+    async fn test_execute_source_directive() {
+        let mut schema = create_test_schema();
+        if let Some(TypeDef::Object(user)) = schema.types.get_mut("User") {
+            user.fields.insert(
+                "joinedAt".to_string(),
+                FieldDef {
+                    source: Some("created_at".to_string()),
+                    ..field_def("joinedAt", TypeRef::named("String"))
+                },
+            );
+        }
+
+        let mut resolvers = ResolverMap::new();
+        resolvers.register_fn("Query", "user", |_parent, _args, _ctx, _info| {
+            Ok(serde_json::json!({"id": "1", "created_at": "2024-01-01"}))
+        });
+
+        let executor = Executor::with_resolvers(resolvers);
+        let ctx = Context::new();
+
+        let plan = user_plan(leaf("User", "joinedAt", "String"));
+
+        let response = executor.execute(&plan, &schema, &ctx).await;
+
+        assert!(!response.has_errors());
+        let data = response.data.unwrap();
+        assert_eq!(data["user"]["joinedAt"], "2024-01-01");
+    }
+
+    #[tokio::test]
+    async fn test_execute_custom_directive_handler() {
+        let mut schema = create_test_schema();
+        if let Some(TypeDef::Object(user)) = schema.types.get_mut("User") {
+            if let Some(name) = user.fields.get_mut("name") {
+                name.directives.push(AppliedDirective::new("uppercase"));
+            }
+        }
+
+        let mut resolvers = ResolverMap::new();
+        resolvers.register_fn("Query", "user", |_parent, _args, _ctx, _info| {
+            Ok(serde_json::json!({"id": "1", "name": "Alice"}))
+        });
+
+        let mut directives = DirectiveRegistry::new();
+        directives.register_fn("uppercase", |value, _directive| {
+            Ok(match value {
+                Value::String(s) => Value::String(s.to_uppercase()),
+                other => other,
+            })
+        });
+
+        let executor = Executor::with_resolvers(resolvers).with_directive_handlers(directives);
+        let ctx = Context::new();
+
+        let plan = user_plan(PlanNode::Sequence(vec![
+            leaf("User", "id", "String"),
+            leaf("User", "name", "String"),
+        ]));
+
+        let response = executor.execute(&plan, &schema, &ctx).await;
+
+        assert!(!response.has_errors());
+        let data = response.data.unwrap();
+        assert_eq!(data["user"]["name"], "ALICE");
+        assert_eq!(data["user"]["id"], "1");
+    }
+
+    #[test]
+    fn test_plan_only_lists_resolver_calls() {
+        let mut schema = create_test_schema();
+        let list_field = |name: &str, ty: &str| field_def(name, TypeRef::list(TypeRef::named(ty)));
+        if let Some(TypeDef::Object(query)) = schema.types.get_mut("Query") {
+            query
+                .fields
+                .insert("users".to_string(), list_field("users", "User"));
+        }
+        if let Some(TypeDef::Object(user)) = schema.types.get_mut("User") {
+            user.fields
+                .insert("friends".to_string(), list_field("friends", "User"));
+        }
+
+        let mut resolvers = ResolverMap::new();
+        for field in ["users", "user"] {
+            resolvers.register_fn("Query", field, |_parent, _args, _ctx, _info| {
+                unreachable!("plan_only must not call resolvers")
+            });
+        }
+        let executor = Executor::with_resolvers(resolvers);
+        let ctx =
+            Context::with_variables(HashMap::from([("first".to_string(), serde_json::json!(2))]));
+
+        let field = |parent: &str, name: &str, arguments: Vec<(String, Value)>| FieldInfo {
+            arguments,
+            ..field_info(parent, name, "")
+        };
+        let leaf = |parent: &str, name: &str| leaf(parent, name, "");
+
+        // { users(first: $first) { id __typename friends { name } } user(id: "1") { name } }
+        let typename = FieldInfo {
+            is_introspection: true,
+            ..field_info("User", "__typename", "")
+        };
+        let plan = QueryPlan::simple(PlanNode::Parallel(vec![
+            field_node(
+                field(
+                    "Query",
+                    "users",
+                    vec![("first".to_string(), serde_json::json!({"$var": "first"}))],
+                ),
+                PlanNode::Parallel(vec![
+                    leaf("User", "id"),
+                    PlanNode::Leaf { field: typename },
+                    field_node(field_info("User", "friends", ""), leaf("User", "name")),
+                ]),
+            ),
+            field_node(
+                field(
+                    "Query",
+                    "user",
+                    vec![("id".to_string(), serde_json::json!("1"))],
+                ),
+                leaf("User", "name"),
+            ),
+        ]));
+
+        let calls = executor.plan_only(&plan, &schema, &ctx);
+        let summary: Vec<_> = calls
+            .iter()
+            .map(|call| {
+                (
+                    call.type_name.as_str(),
+                    call.field_name.as_str(),
+                    call.path.as_str(),
+                    call.has_resolver,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Query", "users", "users", true),
+                ("User", "id", "users[].id", false),
+                ("User", "friends", "users[].friends", false),
+                ("User", "name", "users[].friends[].name", false),
+                ("Query", "user", "user", true),
+                ("User", "name", "user.name", false),
+            ]
+        );
+        assert_eq!(
+            calls[0].args,
+            vec![("first".to_string(), serde_json::json!(2))]
+        );
+        assert_eq!(
+            calls[4].args,
+            vec![("id".to_string(), serde_json::json!("1"))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscription_resolves_each_event() {
+        let mut resolvers = ResolverMap::new();
+        // The source stream yields raw payloads; the field resolver picks the user out
+        resolvers.register(
+            "Subscription",
+            "userUpdated",
+            FnResolver::new(|parent, _args, _ctx, _info| Ok(parent["user"].clone())),
+        );
+
+        let mut schema = create_test_schema();
+        schema.subscription_type = Some("Subscription".to_string());
+        schema.types.insert(
+            "Subscription".to_string(),
+            object_def(
+                "Subscription",
+                vec![field_def("userUpdated", TypeRef::named("User"))],
+            ),
+        );
+
+        let plan = QueryPlan {
+            operation_kind: HirOperationKind::Subscription,
+            ..QueryPlan::simple(field_node(
+                field_info("Subscription", "userUpdated", "User"),
+                leaf("User", "name", "String"),
+            ))
+        };
+
+        let executor = Executor::with_resolvers(resolvers);
+        let (tx, events) = mpsc::channel(4);
+        let mut responses = executor.subscribe(plan, schema, Context::new(), events);
+
+        tx.send(serde_json::json!({
+            "user": {"id": "1", "name": "Alice", "email": "alice@example.com"},
+            "changedAt": 1
+        }))
+        .await
+        .unwrap();
+        tx.send(serde_json::json!({"user": {"id": "2", "name": "Bob"}}))
+            .await
+            .unwrap();
+        drop(tx);
+
+        let first = responses.recv().await.unwrap();
+        assert!(!first.has_errors());
+        assert_eq!(
+            first.data.unwrap(),
+            serde_json::json!({"userUpdated": {"name": "Alice"}})
+        );
+        let second = responses.recv().await.unwrap();
+        assert_eq!(
+            second.data.unwrap(),
+            serde_json::json!({"userUpdated": {"name": "Bob"}})
+        );
+        assert!(responses.recv().await.is_none());
+    }
+
     #[test]
     fn test_context() {
         let mut ctx = Context::new();
@@ -836,6 +2787,62 @@ mod tests {
         assert_eq!(ctx.variable_as::<String>("id"), Some("42".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_scheduler_resolves_high_priority_first() {
+        use crate::resolver::AsyncFnResolver;
+        use crate::scheduler::SchedulerConfig;
+        use crate::ResourceManager;
+
+        let order = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut resolvers = ResolverMap::new();
+        resolvers.register(
+            "Query",
+            "user",
+            AsyncFnResolver::new({
+                let order = Arc::clone(&order);
+                move |_parent, _args, ctx: Context, _info| {
+                    let order = Arc::clone(&order);
+                    async move {
+                        order.lock().unwrap().push(ctx.priority.level);
+                        Ok(serde_json::json!({"id": "1"}))
+                    }
+                }
+            }),
+        );
+
+        let scheduler = Arc::new(QueryScheduler::with_config(
+            Arc::new(ResourceManager::new()),
+            SchedulerConfig {
+                worker_count: 1,
+                ..SchedulerConfig::default()
+            },
+        ));
+        let executor = Executor::with_resolvers(resolvers).with_scheduler(Arc::clone(&scheduler));
+        let schema = create_test_schema();
+        let plan = QueryPlan::simple(leaf("Query", "user", "User"));
+        let low = Context::new().with_priority(TaskPriority::low());
+        let high = Context::new().with_priority(TaskPriority::high());
+
+        // Hold the only slot until both operations wait for it
+        let critical = TaskPriority::critical();
+        let (release, held) = tokio::sync::oneshot::channel::<()>();
+        let (_, low_response, high_response, _) = tokio::join!(
+            scheduler.run(&critical, held),
+            executor.execute(&plan, &schema, &low),
+            executor.execute(&plan, &schema, &high),
+            async {
+                while scheduler.waiting_count() < 2 {
+                    tokio::task::yield_now().await;
+                }
+                release.send(()).unwrap();
+            }
+        );
+
+        assert_eq!(low_response.data.unwrap()["user"]["id"], "1");
+        assert_eq!(high_response.data.unwrap()["user"]["id"], "1");
+        assert_eq!(*order.lock().unwrap(), [2, 8]);
+    }
+
     #[test]
     fn test_field_error() {
         let error = FieldError::new("Something went wrong")
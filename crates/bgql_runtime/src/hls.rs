@@ -617,7 +617,7 @@ impl std::fmt::Display for HlsManifest {
 
         // Sort variants by bandwidth (ascending) for adaptive streaming
         let mut variants = self.variants.clone();
-        variants.sort_by(|a, b| a.bandwidth.cmp(&b.bandwidth));
+        variants.sort_by_key(|variant| variant.bandwidth);
 
         for variant in &variants {
             writeln!(f, "{}", variant.stream_inf())?;
@@ -0,0 +1,650 @@
+//! Introspection of the schema through `__schema` and `__type`.
+//!
+//! The planner resolves the root fields `__schema` and `__type(name:)` on the
+//! query type, and the introspection types (`__Schema`, `__Type`, ...) as if
+//! they were part of the schema. The executor answers them from the
+//! [`Schema`] without resolvers. Fields marked `@internal` are left out, as
+//! in [`Schema::public`].
+//!
+//! # Type mapping
+//!
+//! The runtime schema only has the standard kinds, so introspection reports
+//! each [`TypeDef`] as its GraphQL counterpart: `Scalar` as `SCALAR`,
+//! `Object` as `OBJECT`, and so on. Every type that isn't `Option<T>` is
+//! reported as `NON_NULL`, and `List<T>` as `LIST`.
+//!
+//! bgql's extended types are mapped to these kinds when the SDL is loaded
+//! into a [`Schema`]:
+//!
+//! - `opaque` types become `SCALAR`s, hiding their underlying type
+//! - type aliases are replaced by the type they alias and don't appear in
+//!   `types`
+//! - input unions, and input enums with variants carrying fields, become
+//!   `SCALAR`s, as no standard input kind accepts one of several shapes;
+//!   input enums with unit variants only stay `ENUM`s
+//! - generic types are monomorphized: each instance becomes a type of its
+//!   own, named after its arguments, such as `UserConnection` for
+//!   `Connection<User>`, while the generic definitions don't appear
+//! - tuple types become the `Tuple` scalar, as a list of their elements
+//!
+//! Introspection is answered unless
+//! [`EndpointConfig::introspection`](crate::schema::EndpointConfig::introspection)
+//! is off, in which case the planner rejects `__schema` and `__type`.
+
+use crate::schema::{
+    DirectiveDefinition, DirectiveLocation, EnumDef, EnumValueDef, FieldDef, InputFieldDef,
+    InterfaceDef, ObjectDef, ScalarDef, Schema, TypeDef, TypeRef,
+};
+use indexmap::IndexMap;
+use serde_json::{json, Map, Value};
+use std::sync::OnceLock;
+
+/// The introspection types, root fields and built-in directives.
+struct Meta {
+    schema: Schema,
+    root_fields: IndexMap<String, FieldDef>,
+}
+
+fn meta() -> &'static Meta {
+    static META: OnceLock<Meta> = OnceLock::new();
+    META.get_or_init(|| {
+        let include_deprecated = &[("includeDeprecated", "Boolean", Some("false"))];
+        let mut schema = Schema::new();
+        for name in ["String", "Boolean"] {
+            add_type(
+                &mut schema,
+                TypeDef::Scalar(ScalarDef {
+                    name: name.to_string(),
+                    description: Some(format!("Built-in {name} scalar")),
+                }),
+            );
+        }
+        add_object(
+            &mut schema,
+            "__Schema",
+            vec![
+                field("description", "String", &[]),
+                field("types", "[__Type!]!", &[]),
+                field("queryType", "__Type!", &[]),
+                field("mutationType", "__Type", &[]),
+                field("subscriptionType", "__Type", &[]),
+                field("directives", "[__Directive!]!", &[]),
+            ],
+        );
+        add_object(
+            &mut schema,
+            "__Type",
+            vec![
+                field("kind", "__TypeKind!", &[]),
+                field("name", "String", &[]),
+                field("description", "String", &[]),
+                field("specifiedByURL", "String", &[]),
+                field("fields", "[__Field!]", include_deprecated),
+                field("interfaces", "[__Type!]", &[]),
+                field("possibleTypes", "[__Type!]", &[]),
+                field("enumValues", "[__EnumValue!]", include_deprecated),
+                field("inputFields", "[__InputValue!]", &[]),
+                field("ofType", "__Type", &[]),
+                field("isOneOf", "Boolean", &[]),
+            ],
+        );
+        add_object(
+            &mut schema,
+            "__Field",
+            vec![
+                field("name", "String!", &[]),
+                field("description", "String", &[]),
+                field("args", "[__InputValue!]!", &[]),
+                field("type", "__Type!", &[]),
+                field("isDeprecated", "Boolean!", &[]),
+                field("deprecationReason", "String", &[]),
+            ],
+        );
+        add_object(
+            &mut schema,
+            "__InputValue",
+            vec![
+                field("name", "String!", &[]),
+                field("description", "String", &[]),
+                field("type", "__Type!", &[]),
+                field("defaultValue", "String", &[]),
+            ],
+        );
+        add_object(
+            &mut schema,
+            "__EnumValue",
+            vec![
+                field("name", "String!", &[]),
+                field("description", "String", &[]),
+                field("isDeprecated", "Boolean!", &[]),
+                field("deprecationReason", "String", &[]),
+            ],
+        );
+        add_object(
+            &mut schema,
+            "__Directive",
+            vec![
+                field("name", "String!", &[]),
+                field("description", "String", &[]),
+                field("locations", "[__DirectiveLocation!]!", &[]),
+                field("args", "[__InputValue!]!", &[]),
+                field("isRepeatable", "Boolean!", &[]),
+            ],
+        );
+        add_enum(
+            &mut schema,
+            "__TypeKind",
+            &[
+                "SCALAR",
+                "OBJECT",
+                "INTERFACE",
+                "UNION",
+                "ENUM",
+                "INPUT_OBJECT",
+                "LIST",
+                "NON_NULL",
+            ],
+        );
+        let locations: Vec<_> = ALL_LOCATIONS.iter().map(|l| location_name(*l)).collect();
+        add_enum(&mut schema, "__DirectiveLocation", &locations);
+
+        let executable = vec![
+            DirectiveLocation::Field,
+            DirectiveLocation::FragmentSpread,
+            DirectiveLocation::InlineFragment,
+        ];
+        for (name, description) in [
+            ("skip", "Skips the selection when `if` is true."),
+            ("include", "Includes the selection only when `if` is true."),
+        ] {
+            add_directive(
+                &mut schema,
+                name,
+                description,
+                &[("if", "Boolean!", None)],
+                executable.clone(),
+            );
+        }
+        add_directive(
+            &mut schema,
+            "deprecated",
+            "Marks an element of the schema as no longer supported.",
+            &[("reason", "String", Some("\"No longer supported\""))],
+            vec![
+                DirectiveLocation::FieldDefinition,
+                DirectiveLocation::ArgumentDefinition,
+                DirectiveLocation::InputFieldDefinition,
+                DirectiveLocation::EnumValue,
+            ],
+        );
+
+        let root_fields = [
+            field("__schema", "__Schema!", &[]),
+            field("__type", "__Type", &[("name", "String!", None)]),
+        ]
+        .into_iter()
+        .map(|field| (field.name.clone(), field))
+        .collect();
+
+        Meta {
+            schema,
+            root_fields,
+        }
+    })
+}
+
+/// Returns the definition of `__schema` or `__type`.
+pub(crate) fn root_field(name: &str) -> Option<&'static FieldDef> {
+    meta().root_fields.get(name)
+}
+
+/// Returns an introspection type such as `__Type`.
+pub(crate) fn type_def(name: &str) -> Option<&'static TypeDef> {
+    meta().schema.get_type(name)
+}
+
+/// Resolves an introspection field: a root field of the query type, or a
+/// field of an introspection type.
+pub(crate) fn resolve(
+    schema: &Schema,
+    parent_type: &str,
+    field: &str,
+    parent: &Value,
+    args: &Map<String, Value>,
+) -> Value {
+    match (parent_type, field) {
+        (_, "__schema") => schema_value(schema),
+        (_, "__type") => match args.get("name").and_then(Value::as_str) {
+            Some(name) if lookup(schema, name).is_some() => named_ref(schema, name),
+            _ => Value::Null,
+        },
+        ("__Type", _) => type_field(schema, parent, field, args),
+        _ => parent.get(field).cloned().unwrap_or(Value::Null),
+    }
+}
+
+/// Builds the `__Schema` object. Its types are left to [`type_field`] to
+/// describe.
+fn schema_value(schema: &Schema) -> Value {
+    let meta = &meta().schema;
+    let types: Vec<Value> = schema
+        .types
+        .keys()
+        .chain(
+            meta.types
+                .keys()
+                .filter(|name| !schema.types.contains_key(*name)),
+        )
+        .map(|name| named_ref(schema, name))
+        .collect();
+    let root = |name: &Option<String>| {
+        name.as_deref()
+            .map_or(Value::Null, |name| named_ref(schema, name))
+    };
+    let directives: Vec<Value> = meta
+        .directives
+        .values()
+        .filter(|directive| !schema.directives.contains_key(&directive.name))
+        .chain(schema.directives.values())
+        .map(|directive| directive_value(schema, directive))
+        .collect();
+
+    json!({
+        "description": schema.metadata.description,
+        "types": types,
+        "queryType": root(&schema.query_type),
+        "mutationType": root(&schema.mutation_type),
+        "subscriptionType": root(&schema.subscription_type),
+        "directives": directives,
+    })
+}
+
+/// Resolves a field of `__Type`. Types are passed around as their `kind`,
+/// `name` and `ofType`; the rest is looked up in the schema.
+fn type_field(schema: &Schema, parent: &Value, field: &str, args: &Map<String, Value>) -> Value {
+    let own = || parent.get(field).cloned().unwrap_or(Value::Null);
+    let Some(type_def) = parent
+        .get("name")
+        .and_then(Value::as_str)
+        .and_then(|name| lookup(schema, name))
+    else {
+        return own();
+    };
+    let include_deprecated = args
+        .get("includeDeprecated")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+
+    match (field, type_def) {
+        ("description", _) => json!(description(type_def)),
+        ("fields", TypeDef::Object(ObjectDef { fields, .. }))
+        | ("fields", TypeDef::Interface(InterfaceDef { fields, .. })) => fields
+            .values()
+            .filter(|field| !field.is_internal())
+            .filter(|field| include_deprecated || !field.deprecated)
+            .map(|field| field_value(schema, field))
+            .collect(),
+        ("interfaces", TypeDef::Object(ObjectDef { implements, .. }))
+        | ("interfaces", TypeDef::Interface(InterfaceDef { implements, .. })) => implements
+            .iter()
+            .map(|name| named_ref(schema, name))
+            .collect(),
+        ("possibleTypes", TypeDef::Interface(iface)) => schema
+            .types
+            .values()
+            .filter_map(|type_def| match type_def {
+                TypeDef::Object(obj) if obj.implements.contains(&iface.name) => {
+                    Some(named_ref(schema, &obj.name))
+                }
+                _ => None,
+            })
+            .collect(),
+        ("possibleTypes", TypeDef::Union(union_def)) => union_def
+            .members
+            .iter()
+            .map(|name| named_ref(schema, name))
+            .collect(),
+        ("enumValues", TypeDef::Enum(enum_def)) => enum_def
+            .values
+            .iter()
+            .filter(|value| include_deprecated || !value.deprecated)
+            .map(enum_value)
+            .collect(),
+        ("inputFields", TypeDef::InputObject(input)) => input
+            .fields
+            .values()
+            .map(|field| input_value(schema, field))
+            .collect(),
+        ("isOneOf", TypeDef::InputObject(input)) => Value::Bool(input.one_of),
+        _ => own(),
+    }
+}
+
+fn field_value(schema: &Schema, field: &FieldDef) -> Value {
+    let args: Vec<Value> = field
+        .arguments
+        .values()
+        .map(|arg| input_value(schema, arg))
+        .collect();
+    json!({
+        "name": field.name,
+        "description": field.description,
+        "args": args,
+        "type": type_ref(schema, &field.ty),
+        "isDeprecated": field.deprecated,
+        "deprecationReason": field.deprecation_reason,
+    })
+}
+
+fn input_value(schema: &Schema, input: &InputFieldDef) -> Value {
+    json!({
+        "name": input.name,
+        "description": input.description,
+        "type": type_ref(schema, &input.ty),
+        "defaultValue": input.default_value,
+    })
+}
+
+fn enum_value(value: &EnumValueDef) -> Value {
+    json!({
+        "name": value.name,
+        "description": value.description,
+        "isDeprecated": value.deprecated,
+        "deprecationReason": value.deprecation_reason,
+    })
+}
+
+fn directive_value(schema: &Schema, directive: &DirectiveDefinition) -> Value {
+    let args: Vec<Value> = directive
+        .arguments
+        .values()
+        .map(|arg| input_value(schema, arg))
+        .collect();
+    let locations: Vec<&str> = directive
+        .locations
+        .iter()
+        .map(|location| location_name(*location))
+        .collect();
+    json!({
+        "name": directive.name,
+        "description": directive.description,
+        "locations": locations,
+        "args": args,
+        "isRepeatable": directive.repeatable,
+    })
+}
+
+/// Describes a type reference, wrapping it in `NON_NULL` unless it's an
+/// `Option`.
+fn type_ref(schema: &Schema, ty: &TypeRef) -> Value {
+    match ty {
+        TypeRef::Option(inner) => nullable_ref(schema, inner),
+        _ => json!({
+            "kind": "NON_NULL",
+            "name": null,
+            "ofType": nullable_ref(schema, ty),
+        }),
+    }
+}
+
+fn nullable_ref(schema: &Schema, ty: &TypeRef) -> Value {
+    match ty {
+        TypeRef::Named(name) => named_ref(schema, name),
+        TypeRef::List(item) => json!({
+            "kind": "LIST",
+            "name": null,
+            "ofType": type_ref(schema, item),
+        }),
+        TypeRef::Option(inner) => nullable_ref(schema, inner),
+    }
+}
+
+/// Describes a named type. Names missing from the schema are reported as
+/// scalars.
+fn named_ref(schema: &Schema, name: &str) -> Value {
+    let kind = match lookup(schema, name) {
+        Some(TypeDef::Object(_)) => "OBJECT",
+        Some(TypeDef::Interface(_)) => "INTERFACE",
+        Some(TypeDef::Union(_)) => "UNION",
+        Some(TypeDef::Enum(_)) => "ENUM",
+        Some(TypeDef::InputObject(_)) => "INPUT_OBJECT",
+        Some(TypeDef::Scalar(_)) | None => "SCALAR",
+    };
+    json!({ "kind": kind, "name": name, "ofType": null })
+}
+
+fn lookup<'a>(schema: &'a Schema, name: &str) -> Option<&'a TypeDef> {
+    schema
+        .get_type(name)
+        .or_else(|| meta().schema.get_type(name))
+}
+
+fn description(type_def: &TypeDef) -> Option<&str> {
+    match type_def {
+        TypeDef::Scalar(def) => def.description.as_deref(),
+        TypeDef::Object(def) => def.description.as_deref(),
+        TypeDef::Interface(def) => def.description.as_deref(),
+        TypeDef::Union(def) => def.description.as_deref(),
+        TypeDef::Enum(def) => def.description.as_deref(),
+        TypeDef::InputObject(def) => def.description.as_deref(),
+    }
+}
+
+const ALL_LOCATIONS: [DirectiveLocation; 19] = [
+    DirectiveLocation::Query,
+    DirectiveLocation::Mutation,
+    DirectiveLocation::Subscription,
+    DirectiveLocation::Field,
+    DirectiveLocation::FragmentDefinition,
+    DirectiveLocation::FragmentSpread,
+    DirectiveLocation::InlineFragment,
+    DirectiveLocation::VariableDefinition,
+    DirectiveLocation::Schema,
+    DirectiveLocation::Scalar,
+    DirectiveLocation::Object,
+    DirectiveLocation::FieldDefinition,
+    DirectiveLocation::ArgumentDefinition,
+    DirectiveLocation::Interface,
+    DirectiveLocation::Union,
+    DirectiveLocation::Enum,
+    DirectiveLocation::EnumValue,
+    DirectiveLocation::InputObject,
+    DirectiveLocation::InputFieldDefinition,
+];
+
+fn location_name(location: DirectiveLocation) -> &'static str {
+    match location {
+        DirectiveLocation::Query => "QUERY",
+        DirectiveLocation::Mutation => "MUTATION",
+        DirectiveLocation::Subscription => "SUBSCRIPTION",
+        DirectiveLocation::Field => "FIELD",
+        DirectiveLocation::FragmentDefinition => "FRAGMENT_DEFINITION",
+        DirectiveLocation::FragmentSpread => "FRAGMENT_SPREAD",
+        DirectiveLocation::InlineFragment => "INLINE_FRAGMENT",
+        DirectiveLocation::VariableDefinition => "VARIABLE_DEFINITION",
+        DirectiveLocation::Schema => "SCHEMA",
+        DirectiveLocation::Scalar => "SCALAR",
+        DirectiveLocation::Object => "OBJECT",
+        DirectiveLocation::FieldDefinition => "FIELD_DEFINITION",
+        DirectiveLocation::ArgumentDefinition => "ARGUMENT_DEFINITION",
+        DirectiveLocation::Interface => "INTERFACE",
+        DirectiveLocation::Union => "UNION",
+        DirectiveLocation::Enum => "ENUM",
+        DirectiveLocation::EnumValue => "ENUM_VALUE",
+        DirectiveLocation::InputObject => "INPUT_OBJECT",
+        DirectiveLocation::InputFieldDefinition => "INPUT_FIELD_DEFINITION",
+    }
+}
+
+// Definitions of the introspection types, written with GraphQL type syntax
+
+fn add_type(schema: &mut Schema, type_def: TypeDef) {
+    let name = match &type_def {
+        TypeDef::Scalar(def) => def.name.clone(),
+        TypeDef::Object(def) => def.name.clone(),
+        TypeDef::Enum(def) => def.name.clone(),
+        _ => unreachable!("introspection only defines scalars, objects and enums"),
+    };
+    schema.types.insert(name, type_def);
+}
+
+fn add_object(schema: &mut Schema, name: &str, fields: Vec<FieldDef>) {
+    add_type(
+        schema,
+        TypeDef::Object(ObjectDef {
+            name: name.to_string(),
+            description: None,
+            fields: fields
+                .into_iter()
+                .map(|field| (field.name.clone(), field))
+                .collect(),
+            implements: Vec::new(),
+        }),
+    );
+}
+
+fn add_enum(schema: &mut Schema, name: &str, values: &[&str]) {
+    add_type(
+        schema,
+        TypeDef::Enum(EnumDef {
+            name: name.to_string(),
+            description: None,
+            values: values
+                .iter()
+                .map(|value| EnumValueDef {
+                    name: value.to_string(),
+                    description: None,
+                    deprecated: false,
+                    deprecation_reason: None,
+                })
+                .collect(),
+        }),
+    );
+}
+
+fn add_directive(
+    schema: &mut Schema,
+    name: &str,
+    description: &str,
+    args: &[(&str, &str, Option<&str>)],
+    locations: Vec<DirectiveLocation>,
+) {
+    schema.directives.insert(
+        name.to_string(),
+        DirectiveDefinition {
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            arguments: arguments(args),
+            locations,
+            repeatable: false,
+        },
+    );
+}
+
+fn field(name: &str, ty: &str, args: &[(&str, &str, Option<&str>)]) -> FieldDef {
+    FieldDef {
+        name: name.to_string(),
+        description: None,
+        ty: parse_type(ty),
+        arguments: arguments(args),
+        deprecated: false,
+        deprecation_reason: None,
+        source: None,
+        directives: Vec::new(),
+    }
+}
+
+fn arguments(args: &[(&str, &str, Option<&str>)]) -> IndexMap<String, InputFieldDef> {
+    args.iter()
+        .map(|(name, ty, default_value)| {
+            let arg = InputFieldDef {
+                name: name.to_string(),
+                description: None,
+                ty: parse_type(ty),
+                default_value: default_value.map(str::to_string),
+            };
+            (arg.name.clone(), arg)
+        })
+        .collect()
+}
+
+/// Parses a GraphQL type such as `[__Type!]!`.
+fn parse_type(ty: &str) -> TypeRef {
+    let (inner, non_null) = match ty.strip_suffix('!') {
+        Some(inner) => (inner, true),
+        None => (ty, false),
+    };
+    let inner = match inner.strip_prefix('[').and_then(|i| i.strip_suffix(']')) {
+        Some(item) => TypeRef::list(parse_type(item)),
+        None => TypeRef::named(inner),
+    };
+    if non_null {
+        inner
+    } else {
+        TypeRef::option(inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{AppliedDirective, SchemaBuilder, INTERNAL_DIRECTIVE};
+
+    fn user_schema() -> Schema {
+        let mut name = field("name", "String", &[]);
+        name.deprecated = true;
+        let mut secret = field("secret", "String!", &[]);
+        secret
+            .directives
+            .push(AppliedDirective::new(INTERNAL_DIRECTIVE));
+        let user = ObjectDef {
+            name: "User".to_string(),
+            description: Some("A user".to_string()),
+            fields: [field("friends", "[User!]!", &[]), name, secret]
+                .into_iter()
+                .map(|field| (field.name.clone(), field))
+                .collect(),
+            implements: Vec::new(),
+        };
+        SchemaBuilder::new()
+            .query_type("User")
+            .add_type(TypeDef::Object(user))
+            .build()
+    }
+
+    #[test]
+    fn test_type_fields() {
+        let schema = user_schema();
+        let args = Map::from_iter([("name".to_string(), json!("User"))]);
+        let user = resolve(&schema, "User", "__type", &Value::Null, &args);
+        assert_eq!(user["kind"], "OBJECT");
+        assert_eq!(
+            type_field(&schema, &user, "description", &Map::new()),
+            json!("A user")
+        );
+
+        // Deprecated fields are opt-in, `@internal` ones are never listed
+        let fields = type_field(&schema, &user, "fields", &Map::new());
+        let names: Vec<_> = fields
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| &f["name"])
+            .collect();
+        assert_eq!(names, [&json!("friends")]);
+        let args = Map::from_iter([("includeDeprecated".to_string(), json!(true))]);
+        let fields = type_field(&schema, &user, "fields", &args);
+        assert_eq!(fields.as_array().unwrap().len(), 2);
+
+        // `[User!]!`
+        let ty = &fields[0]["type"];
+        assert_eq!(ty["kind"], "NON_NULL");
+        assert_eq!(ty["ofType"]["kind"], "LIST");
+        assert_eq!(ty["ofType"]["ofType"]["kind"], "NON_NULL");
+        assert_eq!(ty["ofType"]["ofType"]["ofType"]["name"], "User");
+        assert_eq!(fields[1]["type"]["kind"], "SCALAR");
+
+        let args = Map::from_iter([("name".to_string(), json!("Missing"))]);
+        assert!(resolve(&schema, "User", "__type", &Value::Null, &args).is_null());
+    }
+}
@@ -4,6 +4,9 @@
 //! - `schema`: Schema definition and building
 //! - `executor`: Query execution
 //! - `query`: Query planning
+//! - `introspection`: `__schema` and `__type` introspection
+//! - `plan_cache`: LRU cache of query plans
+//! - `pagination`: Page size bounds for paginated fields
 //! - `resolver`: Field resolution system
 //! - `dataloader`: DataLoader for N+1 prevention
 //! - `streaming`: @defer/@stream support
@@ -12,13 +15,17 @@
 //! - `scheduler`: Priority-based query scheduling
 //! - `binary_transport`: Binary streaming protocol
 //! - `hls`: HTTP Live Streaming support
-//! - `directives`: Built-in streaming directives
+//! - `directives`: Built-in streaming directives and custom directive handlers
+//! - `variables`: Validation and coercion of operation variables
 
 pub mod binary_transport;
 pub mod dataloader;
 pub mod directives;
 pub mod executor;
 pub mod hls;
+pub mod introspection;
+pub mod pagination;
+pub mod plan_cache;
 pub mod query;
 pub mod resolver;
 pub mod resource;
@@ -26,29 +33,44 @@ pub mod scheduler;
 pub mod schema;
 pub mod state;
 pub mod streaming;
+#[cfg(test)]
+mod test_support;
+pub mod variables;
 
-pub use binary_transport::{BinaryChunk, BinaryProtocol, BinaryStreamHandle};
-pub use dataloader::DataLoader;
+pub use binary_transport::{
+    AttachedBinary, BinaryAttachments, BinaryBlob, BinaryChunk, BinaryProtocol, BinaryStreamHandle,
+};
+pub use dataloader::{BatchFn, BatchFuture, DataLoader, LoaderRegistry};
 pub use directives::{
-    create_streaming_directives, BinaryDirective, BoundaryDirective, CacheStrategy, DeferDirective,
+    create_streaming_directives, AuthDirectiveHandler, BinaryDirective, BoundaryDirective,
+    CacheStrategy, DeferDirective, DirectiveHandler, DirectiveRegistry, FnDirectiveHandler,
     HydrateDirective, HydrationPriority, HydrationStrategy, IslandDirective, PriorityDirective,
-    ResourcesDirective, ResumableDirective, SerializeStrategy, ServerDirective, StreamDirective,
+    ResourcesDirective, ResumableDirective, RoleHierarchy, SerializeStrategy, ServerDirective,
+    StreamDirective, ROLES_CONTEXT_KEY, SCOPES_CONTEXT_KEY,
+};
+pub use executor::{
+    Context, DeferredExecution, ErrorHook, Executor, ExecutorConfig, FieldError, PathSegment,
+    PlannedCall, Response, StreamExecution, MASKED_ERROR_MESSAGE,
 };
-pub use executor::{Context, Executor, ExecutorConfig, FieldError, PathSegment, Response};
 pub use hls::{HlsManifest, HlsPlaylist, HlsSegment, HlsStreamGenerator};
-pub use query::{FieldInfo, PlanError, PlanNode, PlannerConfig, QueryPlan, QueryPlanner};
+pub use pagination::PageBounds;
+pub use plan_cache::PlanCache;
+pub use query::{
+    Condition, FieldInfo, PlanError, PlanNode, PlannerConfig, QueryPlan, QueryPlanner,
+};
 pub use resolver::{
-    AsyncFnResolver, DefaultResolver, FnResolver, Resolver, ResolverArgs, ResolverError,
-    ResolverFuture, ResolverInfo, ResolverMap, ResolverResult,
+    AsyncFnResolver, DefaultResolver, FnResolver, RawJson, Resolver, ResolverArgs, ResolverError,
+    ResolverFuture, ResolverInfo, ResolverMap, ResolverResult, UnknownResolver,
 };
 pub use resource::{ResourceLimits, ResourceManager, ResourceRequirements, ResourceUsage};
 pub use scheduler::{ExecutionHandle, QueryScheduler, SchedulerConfig, TaskPriority, TaskStatus};
 pub use schema::{
-    DirectiveDefinition, DirectiveLocation, EndpointConfig, Schema, SchemaBuilder, SchemaMetadata,
-    SchemaVersion,
+    AppliedDirective, DirectiveDefinition, DirectiveLocation, EndpointConfig, Schema,
+    SchemaBuilder, SchemaMetadata, SchemaVersion,
 };
 pub use state::{
-    BinaryStreamPhase, BinaryStreamState, Checkpoint, ExecutionPhase, ExecutionPosition,
-    ExecutionState, StreamCursor,
+    BinaryStreamPhase, BinaryStreamState, Checkpoint, CheckpointStore, ExecutionPhase,
+    ExecutionPosition, ExecutionState, MemoryCheckpointStore, StreamCursor,
 };
 pub use streaming::{DeferPayload, StreamPayload, StreamingResponse};
+pub use variables::{coerce_variables, VariableDef};
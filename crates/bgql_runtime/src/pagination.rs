@@ -0,0 +1,150 @@
+//! Page size bounds for paginated fields.
+//!
+//! A field opts in with `@paginate(max: Int, default: Option<Int>)`, or takes
+//! [`ExecutorConfig::pagination`](crate::ExecutorConfig::pagination) when it
+//! declares a `first` or `last` argument.
+
+use crate::resolver::ResolverError;
+use crate::schema::{AppliedDirective, FieldDef};
+use serde_json::Value;
+
+/// Name of the directive setting a field's page bounds.
+pub const PAGINATE_DIRECTIVE: &str = "paginate";
+
+/// Bounds on the `first` and `last` arguments of a paginated field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageBounds {
+    /// Largest accepted `first` or `last`.
+    pub max: u64,
+    /// `first` used when neither `first` nor `last` is given.
+    pub default: Option<u64>,
+}
+
+impl PageBounds {
+    /// Creates bounds accepting pages of up to `max` items.
+    pub fn new(max: u64) -> Self {
+        Self { max, default: None }
+    }
+
+    /// Sets the page size used when the client gives none.
+    pub fn with_default(mut self, default: u64) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Reads the bounds of a `@paginate` directive.
+    pub fn from_directive(directive: &AppliedDirective) -> Option<Self> {
+        let max = directive.argument("max")?.as_u64()?;
+        Some(Self {
+            max,
+            default: directive.argument("default").and_then(Value::as_u64),
+        })
+    }
+
+    /// Returns the bounds of `field`: those of its `@paginate` directive, or
+    /// else `fallback` when the field takes `first` or `last`.
+    pub fn for_field(field: &FieldDef, fallback: Option<PageBounds>) -> Option<Self> {
+        match field
+            .directives
+            .iter()
+            .find(|directive| directive.name == PAGINATE_DIRECTIVE)
+        {
+            Some(directive) => Self::from_directive(directive),
+            None if field.arguments.contains_key("first")
+                || field.arguments.contains_key("last") =>
+            {
+                fallback
+            }
+            None => None,
+        }
+    }
+
+    /// Rejects a `first` or `last` that is negative or above the maximum, and
+    /// sets `first` to the default when neither is given.
+    pub fn apply(&self, args: &mut Vec<(String, Value)>) -> Result<(), ResolverError> {
+        let mut given = false;
+        for (name, value) in args.iter() {
+            if (name != "first" && name != "last") || value.is_null() {
+                continue;
+            }
+            given = true;
+            match value.as_i64() {
+                Some(n) if n < 0 => {
+                    return Err(ResolverError::Client(format!(
+                        "`{}` must not be negative, got {}",
+                        name, n
+                    )));
+                }
+                Some(n) if n as u64 > self.max => {
+                    return Err(ResolverError::Client(format!(
+                        "`{}` must be at most {}, got {}",
+                        name, self.max, n
+                    )));
+                }
+                _ => {}
+            }
+        }
+
+        if !given {
+            if let Some(default) = self.default {
+                args.retain(|(name, _)| name != "first");
+                args.push(("first".to_string(), Value::from(default)));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn args(pairs: &[(&str, Value)]) -> Vec<(String, Value)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_rejects_pages_above_max() {
+        let bounds = PageBounds::new(100).with_default(10);
+
+        let mut first = args(&[("first", json!(10000))]);
+        let error = bounds.apply(&mut first).unwrap_err();
+        assert_eq!(error.to_string(), "`first` must be at most 100, got 10000");
+
+        let mut last = args(&[("last", json!(-1))]);
+        assert!(bounds.apply(&mut last).is_err());
+
+        let mut within = args(&[("first", json!(100))]);
+        bounds.apply(&mut within).unwrap();
+        assert_eq!(within, args(&[("first", json!(100))]));
+    }
+
+    #[test]
+    fn test_default_page_size() {
+        let directive = AppliedDirective::new(PAGINATE_DIRECTIVE)
+            .with_argument("max", json!(50))
+            .with_argument("default", json!(10));
+        let bounds = PageBounds::from_directive(&directive).unwrap();
+        assert_eq!(bounds, PageBounds::new(50).with_default(10));
+
+        let mut omitted = args(&[("after", json!("cursor"))]);
+        bounds.apply(&mut omitted).unwrap();
+        assert_eq!(
+            omitted,
+            args(&[("after", json!("cursor")), ("first", json!(10))])
+        );
+
+        // A null `first` counts as omitted; `last` alone takes no default
+        let mut null = args(&[("first", Value::Null)]);
+        bounds.apply(&mut null).unwrap();
+        assert_eq!(null, args(&[("first", json!(10))]));
+
+        let mut last = args(&[("last", json!(5))]);
+        bounds.apply(&mut last).unwrap();
+        assert_eq!(last, args(&[("last", json!(5))]));
+    }
+}
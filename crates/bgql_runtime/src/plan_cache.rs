@@ -0,0 +1,234 @@
+//! Query plan caching.
+//!
+//! Plans depend only on the operation and the schema, so repeated
+//! operations can skip parsing and planning.
+
+use crate::query::QueryPlan;
+use bgql_core::Interner;
+use bgql_syntax::{Lexer, TokenKind};
+use rustc_hash::FxHashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// A least-recently-used cache of query plans.
+///
+/// Entries are keyed by [`PlanCache::key`]. Plans are only valid for the
+/// schema they were planned against, so the cache must be
+/// [cleared](PlanCache::clear) when the schema is rebuilt.
+#[derive(Debug)]
+pub struct PlanCache {
+    capacity: usize,
+    entries: Mutex<CacheEntries>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+struct CacheEntries {
+    /// Plans with the tick of their last use
+    plans: FxHashMap<String, (Arc<QueryPlan>, u64)>,
+    tick: u64,
+}
+
+impl PlanCache {
+    /// Creates a cache holding up to `capacity` plans. A capacity of zero
+    /// disables caching.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(CacheEntries::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Builds the cache key of an operation.
+    ///
+    /// The operation is normalized to its tokens, so whitespace, commas and
+    /// comments don't matter. The names and JSON kinds of the variables are
+    /// part of the key; their values are not.
+    pub fn key(operation: &str, variables: Option<&serde_json::Value>) -> String {
+        let interner = Interner::new();
+        let mut lexer = Lexer::new(operation, &interner);
+        let mut key = String::with_capacity(operation.len());
+        loop {
+            let token = lexer.next_token();
+            match token.kind {
+                TokenKind::Eof => break,
+                TokenKind::Comma => continue,
+                _ => {
+                    if !key.is_empty() {
+                        key.push(' ');
+                    }
+                    key.push_str(lexer.span_text(token.span));
+                }
+            }
+        }
+
+        if let Some(serde_json::Value::Object(variables)) = variables {
+            let mut shape: Vec<_> = variables
+                .iter()
+                .map(|(name, value)| format!("${name}:{}", json_kind(value)))
+                .collect();
+            shape.sort();
+            key.push('\n');
+            key.push_str(&shape.join(" "));
+        }
+        key
+    }
+
+    /// Returns the plan cached under `key`, calling `plan` to build and
+    /// cache it on a miss.
+    pub fn get_or_try_insert_with<E>(
+        &self,
+        key: &str,
+        plan: impl FnOnce() -> Result<QueryPlan, E>,
+    ) -> Result<Arc<QueryPlan>, E> {
+        if let Some(plan) = self.get(key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(plan);
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let plan = Arc::new(plan()?);
+        self.insert(key.to_string(), Arc::clone(&plan));
+        Ok(plan)
+    }
+
+    /// Returns the plan cached under `key`.
+    pub fn get(&self, key: &str) -> Option<Arc<QueryPlan>> {
+        let mut entries = self.lock();
+        entries.tick += 1;
+        let tick = entries.tick;
+        let (plan, last_used) = entries.plans.get_mut(key)?;
+        *last_used = tick;
+        Some(Arc::clone(plan))
+    }
+
+    /// Caches `plan` under `key`, evicting the least recently used plan if
+    /// the cache is full.
+    pub fn insert(&self, key: String, plan: Arc<QueryPlan>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.lock();
+        if entries.plans.len() >= self.capacity && !entries.plans.contains_key(&key) {
+            let oldest = entries
+                .plans
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.plans.remove(&oldest);
+            }
+        }
+        entries.tick += 1;
+        let tick = entries.tick;
+        entries.plans.insert(key, (plan, tick));
+    }
+
+    /// Removes every cached plan.
+    pub fn clear(&self) {
+        self.lock().plans.clear();
+    }
+
+    /// Returns the number of cached plans.
+    pub fn len(&self) -> usize {
+        self.lock().plans.len()
+    }
+
+    /// Returns true if no plan is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the maximum number of cached plans.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns how many lookups found a cached plan.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns how many lookups had to build a plan.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheEntries> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn json_kind(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "bool",
+        serde_json::Value::Number(_) => "number",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "list",
+        serde_json::Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::PlanNode;
+
+    fn plan() -> Result<QueryPlan, ()> {
+        Ok(QueryPlan::simple(PlanNode::Sequence(Vec::new())))
+    }
+
+    #[test]
+    fn test_same_query_plans_once() {
+        let cache = PlanCache::new(10);
+        let mut planned = 0;
+
+        for query in [
+            "{ user { id } }",
+            "{\n  user {\n    id\n  }\n}",
+            "{ users { id } }",
+        ] {
+            cache
+                .get_or_try_insert_with(&PlanCache::key(query, None), || {
+                    planned += 1;
+                    plan()
+                })
+                .unwrap();
+        }
+
+        assert_eq!(planned, 2);
+        assert_eq!(cache.len(), 2);
+        assert_eq!((cache.hits(), cache.misses()), (1, 2));
+    }
+
+    #[test]
+    fn test_key_includes_variable_shape() {
+        let query = "query ($id: ID) { user(id: $id) { id } }";
+        let a = PlanCache::key(query, Some(&serde_json::json!({ "id": "1" })));
+        let b = PlanCache::key(query, Some(&serde_json::json!({ "id": "2" })));
+        let c = PlanCache::key(query, Some(&serde_json::json!({ "id": 2 })));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = PlanCache::new(2);
+        cache.get_or_try_insert_with("a", plan).unwrap();
+        cache.get_or_try_insert_with("b", plan).unwrap();
+        cache.get("a");
+        cache.get_or_try_insert_with("c", plan).unwrap();
+
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("c").is_some());
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
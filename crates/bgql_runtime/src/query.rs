@@ -1,10 +1,13 @@
 //! Query planning for Better GraphQL.
 
+use crate::introspection;
 use crate::schema::{FieldDef, ObjectDef, Schema, TypeDef, TypeRef};
+use crate::variables::VariableDef;
 use bgql_semantic::hir::{
-    HirFieldSelection, HirOperation, HirOperationKind, HirSelection, HirValue,
+    HirCondition, HirFieldSelection, HirFragment, HirOperation, HirOperationKind, HirSelection,
+    HirValue,
 };
-use std::collections::HashSet;
+use std::collections::HashMap;
 
 /// Query planner configuration.
 #[derive(Debug, Clone)]
@@ -57,6 +60,23 @@ impl QueryPlanner {
 
     /// Plans a query.
     pub fn plan(&self, operation: &HirOperation, schema: &Schema) -> Result<QueryPlan, PlanError> {
+        self.plan_with_fragments(operation, &[], schema)
+    }
+
+    /// Plans a query whose selections spread the given fragments.
+    ///
+    /// Fragment spreads are expanded in place, so their fields count
+    /// towards the depth and complexity limits. Fails before anything is
+    /// executed if the operation exceeds
+    /// [`max_depth`](PlannerConfig::max_depth) or
+    /// [`max_complexity`](PlannerConfig::max_complexity), spreads an
+    /// unknown fragment, or spreads fragments in a cycle.
+    pub fn plan_with_fragments(
+        &self,
+        operation: &HirOperation,
+        fragments: &[HirFragment],
+        schema: &Schema,
+    ) -> Result<QueryPlan, PlanError> {
         let root_type_name = match operation.kind {
             HirOperationKind::Query => schema.query_type.as_deref(),
             HirOperationKind::Mutation => schema.mutation_type.as_deref(),
@@ -89,10 +109,12 @@ impl QueryPlanner {
 
         let mut context = PlanningContext {
             schema,
-            config: &self.config,
+            fragments,
             depth: 0,
+            deepest: 0,
             complexity: 0,
-            visited_fragments: HashSet::new(),
+            fragment_path: Vec::new(),
+            expanded_fragments: HashMap::new(),
         };
 
         let root_node = self.plan_selections(
@@ -107,7 +129,8 @@ impl QueryPlanner {
             operation_name: operation.name.clone(),
             operation_kind: operation.kind,
             complexity: context.complexity,
-            max_depth: context.depth,
+            max_depth: context.deepest,
+            variables: Vec::new(),
         })
     }
 
@@ -134,45 +157,48 @@ impl QueryPlanner {
             match selection {
                 HirSelection::Field(field_sel) => {
                     let node = self.plan_field(field_sel, parent_type, parent_type_name, ctx)?;
-                    field_nodes.push(node);
+                    field_nodes.push(conditional(&field_sel.condition, node));
                 }
                 HirSelection::FragmentSpread(name) => {
-                    // Skip already visited fragments to prevent cycles
-                    if ctx.visited_fragments.contains(name) {
-                        continue;
-                    }
-                    ctx.visited_fragments.insert(name.clone());
-                    // Fragment spreading would be resolved during execution
-                    // For now, we create a placeholder
-                    field_nodes.push(PlanNode::FragmentSpread { name: name.clone() });
+                    let node =
+                        self.plan_fragment_spread(name, parent_type, parent_type_name, ctx)?;
+                    field_nodes.push(node);
                 }
                 HirSelection::InlineFragment(inline) => {
                     // Handle inline fragments
-                    if let Some(type_condition) = &inline.type_condition {
-                        if let Some(TypeDef::Object(cond_type)) =
-                            ctx.schema.get_type(type_condition)
-                        {
-                            let inner = self.plan_selections(
-                                &inline.selections,
-                                cond_type,
-                                type_condition,
-                                ctx,
-                            )?;
-                            field_nodes.push(PlanNode::TypeCondition {
-                                type_name: type_condition.clone(),
-                                node: Box::new(inner),
-                            });
+                    let node = if let Some(type_condition) = &inline.type_condition {
+                        let Some(TypeDef::Object(cond_type)) = ctx.schema.get_type(type_condition)
+                        else {
+                            continue;
+                        };
+                        let inner = self.plan_selections(
+                            &inline.selections,
+                            cond_type,
+                            type_condition,
+                            ctx,
+                        )?;
+                        PlanNode::TypeCondition {
+                            type_name: type_condition.clone(),
+                            node: Box::new(inner),
                         }
                     } else {
                         // Inline fragment without type condition
-                        let inner = self.plan_selections(
+                        self.plan_selections(
                             &inline.selections,
                             parent_type,
                             parent_type_name,
                             ctx,
-                        )?;
-                        field_nodes.push(inner);
-                    }
+                        )?
+                    };
+
+                    let node = match &inline.defer {
+                        Some(defer) => PlanNode::Defer {
+                            node: Box::new(node),
+                            label: defer.label.clone(),
+                        },
+                        None => node,
+                    };
+                    field_nodes.push(conditional(&inline.condition, node));
                 }
             }
         }
@@ -187,6 +213,81 @@ impl QueryPlanner {
         }
     }
 
+    /// Plans a fragment spread by expanding the fragment's selections.
+    fn plan_fragment_spread(
+        &self,
+        name: &str,
+        parent_type: &ObjectDef,
+        parent_type_name: &str,
+        ctx: &mut PlanningContext<'_>,
+    ) -> Result<PlanNode, PlanError> {
+        if ctx.fragment_path.iter().any(|spread| spread == name) {
+            return Err(PlanError {
+                message: format!("Fragment '{}' is spread in a cycle", name),
+            });
+        }
+
+        // Reuse earlier expansions so repeated spreads don't replan the
+        // fragment, which is exponential for chains of doubled spreads
+        let key = (name.to_string(), parent_type_name.to_string(), ctx.depth);
+        if let Some(expansion) = ctx.expanded_fragments.get(&key) {
+            let node = expansion.node.clone();
+            ctx.deepest = ctx.deepest.max(ctx.depth + expansion.depth);
+            ctx.complexity += expansion.complexity;
+            self.check_complexity(ctx)?;
+            return Ok(node);
+        }
+        let complexity_before = ctx.complexity;
+        let deepest_before = std::mem::replace(&mut ctx.deepest, ctx.depth);
+
+        let fragment = ctx
+            .fragments
+            .iter()
+            .find(|fragment| fragment.name == name)
+            .ok_or_else(|| PlanError {
+                message: format!("Unknown fragment '{}'", name),
+            })?;
+
+        ctx.fragment_path.push(name.to_string());
+        let type_condition = &fragment.type_condition;
+        let node = match ctx.schema.get_type(type_condition) {
+            Some(TypeDef::Object(cond_type)) if type_condition != parent_type_name => {
+                let inner =
+                    self.plan_selections(&fragment.selections, cond_type, type_condition, ctx)?;
+                PlanNode::TypeCondition {
+                    type_name: type_condition.clone(),
+                    node: Box::new(inner),
+                }
+            }
+            // Fragments on the parent type or on an abstract type it
+            // belongs to select fields of the parent
+            _ => self.plan_selections(&fragment.selections, parent_type, parent_type_name, ctx)?,
+        };
+        ctx.fragment_path.pop();
+
+        let expansion = FragmentExpansion {
+            node: node.clone(),
+            complexity: ctx.complexity - complexity_before,
+            depth: ctx.deepest - ctx.depth,
+        };
+        ctx.deepest = ctx.deepest.max(deepest_before);
+        ctx.expanded_fragments.insert(key, expansion);
+        Ok(node)
+    }
+
+    /// Fails once the complexity so far exceeds the configured maximum.
+    fn check_complexity(&self, ctx: &PlanningContext<'_>) -> Result<(), PlanError> {
+        if ctx.complexity > self.config.max_complexity {
+            return Err(PlanError {
+                message: format!(
+                    "Query complexity {} exceeds maximum allowed complexity {}",
+                    ctx.complexity, self.config.max_complexity
+                ),
+            });
+        }
+        Ok(())
+    }
+
     /// Plans a single field.
     fn plan_field(
         &self,
@@ -198,6 +299,7 @@ impl QueryPlanner {
         // Handle __typename
         if field.name == "__typename" {
             ctx.complexity += 1;
+            self.check_complexity(ctx)?;
             return Ok(PlanNode::Leaf {
                 field: FieldInfo {
                     name: "__typename".to_string(),
@@ -210,27 +312,37 @@ impl QueryPlanner {
             });
         }
 
-        // Find field definition
-        let field_def = parent_type
-            .fields
-            .get(&field.name)
-            .ok_or_else(|| PlanError {
-                message: format!(
-                    "Field '{}' not found on type '{}'",
-                    field.name, parent_type_name
-                ),
-            })?;
-
-        ctx.complexity += self.calculate_field_complexity(field_def, &field.arguments);
-
-        if ctx.complexity > self.config.max_complexity {
+        // `__schema` and `__type` are only defined on the query type
+        let introspection_root = introspection::root_field(&field.name)
+            .filter(|_| ctx.schema.query_type.as_deref() == Some(parent_type_name));
+        if introspection_root.is_some() && !ctx.schema.endpoint.introspection {
             return Err(PlanError {
-                message: format!(
-                    "Query complexity {} exceeds maximum allowed complexity {}",
-                    ctx.complexity, self.config.max_complexity
-                ),
+                message: "Introspection is disabled".to_string(),
             });
         }
+        let is_introspection = introspection_root.is_some() || parent_type_name.starts_with("__");
+
+        // Find field definition
+        let field_def = match introspection_root {
+            Some(field_def) => field_def,
+            None => parent_type
+                .fields
+                .get(&field.name)
+                .ok_or_else(|| PlanError {
+                    message: format!(
+                        "Field '{}' not found on type '{}'",
+                        field.name, parent_type_name
+                    ),
+                })?,
+        };
+
+        // Of introspection, only `__schema` and `__type` count towards the
+        // complexity limit, so that a schema's size doesn't exhaust it
+        if !is_introspection || introspection_root.is_some() {
+            ctx.complexity += self.calculate_field_complexity(field_def, &field.arguments);
+        }
+
+        self.check_complexity(ctx)?;
 
         // Convert arguments
         let arguments: Vec<(String, serde_json::Value)> = field
@@ -245,75 +357,70 @@ impl QueryPlanner {
         // Check if we need to resolve nested selections
         if !field.selections.is_empty() {
             // Get the return type
-            if let Some(return_type) = ctx.schema.get_type(&return_type_name) {
-                if let TypeDef::Object(obj) = return_type {
-                    ctx.depth += 1;
-                    let max_depth = ctx.depth;
-                    let nested =
-                        self.plan_selections(&field.selections, obj, &return_type_name, ctx)?;
-                    if ctx.depth == max_depth {
-                        ctx.depth = max_depth;
-                    }
-
-                    // Check for @defer directive
-                    let is_deferred = has_defer_directive(&field.arguments);
-                    let defer_label = get_defer_label(&field.arguments);
-
-                    if is_deferred {
-                        return Ok(PlanNode::Defer {
-                            node: Box::new(PlanNode::Field {
-                                info: FieldInfo {
-                                    name: field.name.clone(),
-                                    alias: field.alias.clone(),
-                                    parent_type: parent_type_name.to_string(),
-                                    return_type: return_type_name,
-                                    arguments,
-                                    is_introspection: false,
-                                },
-                                response_name,
-                                children: Box::new(nested),
-                            }),
-                            label: defer_label,
-                        });
-                    }
-
-                    // Check for @stream directive
-                    let is_streamed = has_stream_directive(&field.arguments);
-                    let stream_label = get_stream_label(&field.arguments);
-                    let initial_count = get_stream_initial_count(&field.arguments);
-
-                    if is_streamed {
-                        return Ok(PlanNode::Stream {
-                            node: Box::new(PlanNode::Field {
-                                info: FieldInfo {
-                                    name: field.name.clone(),
-                                    alias: field.alias.clone(),
-                                    parent_type: parent_type_name.to_string(),
-                                    return_type: return_type_name,
-                                    arguments,
-                                    is_introspection: false,
-                                },
-                                response_name,
-                                children: Box::new(nested),
-                            }),
-                            label: stream_label,
-                            initial_count,
-                        });
-                    }
-
-                    return Ok(PlanNode::Field {
-                        info: FieldInfo {
-                            name: field.name.clone(),
-                            alias: field.alias.clone(),
-                            parent_type: parent_type_name.to_string(),
-                            return_type: return_type_name,
-                            arguments,
-                            is_introspection: false,
-                        },
-                        response_name,
-                        children: Box::new(nested),
+            let return_type = match ctx.schema.get_type(&return_type_name) {
+                None if is_introspection => introspection::type_def(&return_type_name),
+                return_type => return_type,
+            };
+            if let Some(TypeDef::Object(obj)) = return_type {
+                ctx.depth += 1;
+                ctx.deepest = ctx.deepest.max(ctx.depth);
+                let nested =
+                    self.plan_selections(&field.selections, obj, &return_type_name, ctx)?;
+                ctx.depth -= 1;
+
+                // Check for @defer directive
+                let is_deferred = has_defer_directive(&field.arguments);
+                let defer_label = get_defer_label(&field.arguments);
+
+                if is_deferred {
+                    return Ok(PlanNode::Defer {
+                        node: Box::new(PlanNode::Field {
+                            info: FieldInfo {
+                                name: field.name.clone(),
+                                alias: field.alias.clone(),
+                                parent_type: parent_type_name.to_string(),
+                                return_type: return_type_name,
+                                arguments,
+                                is_introspection,
+                            },
+                            response_name,
+                            children: Box::new(nested),
+                        }),
+                        label: defer_label,
+                    });
+                }
+
+                if let Some(stream) = &field.stream {
+                    return Ok(PlanNode::Stream {
+                        node: Box::new(PlanNode::Field {
+                            info: FieldInfo {
+                                name: field.name.clone(),
+                                alias: field.alias.clone(),
+                                parent_type: parent_type_name.to_string(),
+                                return_type: return_type_name,
+                                arguments,
+                                is_introspection,
+                            },
+                            response_name,
+                            children: Box::new(nested),
+                        }),
+                        label: stream.label.clone(),
+                        initial_count: stream.initial_count,
                     });
                 }
+
+                return Ok(PlanNode::Field {
+                    info: FieldInfo {
+                        name: field.name.clone(),
+                        alias: field.alias.clone(),
+                        parent_type: parent_type_name.to_string(),
+                        return_type: return_type_name,
+                        arguments,
+                        is_introspection,
+                    },
+                    response_name,
+                    children: Box::new(nested),
+                });
             }
         }
 
@@ -325,7 +432,7 @@ impl QueryPlanner {
                 parent_type: parent_type_name.to_string(),
                 return_type: return_type_name,
                 arguments,
-                is_introspection: false,
+                is_introspection,
             },
         })
     }
@@ -359,11 +466,24 @@ impl QueryPlanner {
 /// Context for query planning.
 struct PlanningContext<'a> {
     schema: &'a Schema,
-    #[allow(dead_code)]
-    config: &'a PlannerConfig,
+    fragments: &'a [HirFragment],
+    /// Nesting depth of the selections being planned
     depth: usize,
+    /// Deepest nesting seen so far
+    deepest: usize,
+    complexity: usize,
+    /// Fragments being expanded, outermost first
+    fragment_path: Vec<String>,
+    /// Planned fragment spreads by fragment, parent type and depth
+    expanded_fragments: HashMap<(String, String, usize), FragmentExpansion>,
+}
+
+/// A planned fragment spread and what it added to the limits.
+struct FragmentExpansion {
+    node: PlanNode,
     complexity: usize,
-    visited_fragments: HashSet<String>,
+    /// Depth of the deepest selection below the spread
+    depth: usize,
 }
 
 /// A query plan.
@@ -377,8 +497,12 @@ pub struct QueryPlan {
     pub operation_kind: HirOperationKind,
     /// Total complexity score.
     pub complexity: usize,
-    /// Maximum depth.
+    /// Maximum depth. Root fields are at depth 0.
     pub max_depth: usize,
+    /// Variables declared by the operation. The planner leaves this empty,
+    /// as HIR operations don't carry runtime types; see
+    /// [`QueryPlan::with_variables`].
+    pub variables: Vec<VariableDef>,
 }
 
 impl QueryPlan {
@@ -390,8 +514,15 @@ impl QueryPlan {
             operation_kind: HirOperationKind::Query,
             complexity: 0,
             max_depth: 0,
+            variables: Vec::new(),
         }
     }
+
+    /// Sets the variables declared by the operation.
+    pub fn with_variables(mut self, variables: Vec<VariableDef>) -> Self {
+        self.variables = variables;
+        self
+    }
 }
 
 /// Information about a field to resolve.
@@ -459,13 +590,30 @@ pub enum PlanNode {
         initial_count: usize,
     },
 
-    /// Conditional node (for @skip/@include).
+    /// A node executed only if its `@skip`/`@include` condition holds.
     Conditional {
-        condition: bool,
+        condition: Condition,
         node: Box<PlanNode>,
     },
 }
 
+/// The `if` arguments of `@skip` and `@include`, as JSON with variables
+/// left as `{"$var": name}` placeholders.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Condition {
+    pub skip: Option<serde_json::Value>,
+    pub include: Option<serde_json::Value>,
+}
+
+impl Condition {
+    /// Returns whether the node is executed, with `resolve` substituting
+    /// the variables of an `if` argument.
+    pub fn holds(&self, resolve: impl Fn(&serde_json::Value) -> serde_json::Value) -> bool {
+        let is_true = |value: &serde_json::Value| resolve(value) == serde_json::Value::Bool(true);
+        !self.skip.as_ref().is_some_and(is_true) && self.include.as_ref().map_or(true, is_true)
+    }
+}
+
 impl PlanNode {
     /// Returns true if this is a leaf node.
     pub fn is_leaf(&self) -> bool {
@@ -483,13 +631,8 @@ impl PlanNode {
             PlanNode::FragmentSpread { .. } => 0,
             PlanNode::TypeCondition { node, .. } => node.field_count(),
             PlanNode::Defer { node, .. } | PlanNode::Stream { node, .. } => node.field_count(),
-            PlanNode::Conditional { node, condition } => {
-                if *condition {
-                    node.field_count()
-                } else {
-                    0
-                }
-            }
+            // Conditions depend on variables, so count the fields either way
+            PlanNode::Conditional { node, .. } => node.field_count(),
         }
     }
 }
@@ -516,6 +659,21 @@ fn get_base_type_name(ty: &TypeRef) -> String {
     }
 }
 
+/// Wraps `node` in a [`PlanNode::Conditional`] if the selection has
+/// `@skip` or `@include`.
+fn conditional(condition: &HirCondition, node: PlanNode) -> PlanNode {
+    if condition.is_unconditional() {
+        return node;
+    }
+    PlanNode::Conditional {
+        condition: Condition {
+            skip: condition.skip.as_ref().map(hir_value_to_json),
+            include: condition.include.as_ref().map(hir_value_to_json),
+        },
+        node: Box::new(node),
+    }
+}
+
 /// Converts a HIR value to JSON.
 fn hir_value_to_json(value: &HirValue) -> serde_json::Value {
     match value {
@@ -551,104 +709,35 @@ fn get_defer_label(_arguments: &[(String, HirValue)]) -> Option<String> {
     None
 }
 
-/// Checks if field has @stream directive.
-fn has_stream_directive(_arguments: &[(String, HirValue)]) -> bool {
-    false
-}
-
-/// Gets the label from @stream directive.
-fn get_stream_label(_arguments: &[(String, HirValue)]) -> Option<String> {
-    None
-}
-
-/// Gets the initial count from @stream directive.
-fn get_stream_initial_count(_arguments: &[(String, HirValue)]) -> usize {
-    0
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::schema::{FieldDef, ObjectDef, SchemaBuilder, TypeDef, TypeRef};
+    use crate::schema::TypeRef;
+    use crate::test_support::{field_def, schema_with_fields};
     use bgql_core::Span;
-    use bgql_semantic::hir::{HirFieldSelection, HirOperation, HirOperationKind, HirSelection};
-    use indexmap::IndexMap;
+    use bgql_semantic::hir::{
+        HirCondition, HirFieldSelection, HirFragment, HirOperation, HirOperationKind, HirSelection,
+    };
 
     fn create_test_schema() -> Schema {
-        let mut user_fields = IndexMap::new();
-        user_fields.insert(
-            "id".to_string(),
-            FieldDef {
-                name: "id".to_string(),
-                description: None,
-                ty: TypeRef::Named("ID".to_string()),
-                arguments: IndexMap::new(),
-                deprecated: false,
-                deprecation_reason: None,
-            },
-        );
-        user_fields.insert(
-            "name".to_string(),
-            FieldDef {
-                name: "name".to_string(),
-                description: None,
-                ty: TypeRef::Named("String".to_string()),
-                arguments: IndexMap::new(),
-                deprecated: false,
-                deprecation_reason: None,
-            },
-        );
-        user_fields.insert(
-            "email".to_string(),
-            FieldDef {
-                name: "email".to_string(),
-                description: None,
-                ty: TypeRef::Named("String".to_string()),
-                arguments: IndexMap::new(),
-                deprecated: false,
-                deprecation_reason: None,
-            },
-        );
-
-        let mut query_fields = IndexMap::new();
-        query_fields.insert(
-            "user".to_string(),
-            FieldDef {
-                name: "user".to_string(),
-                description: None,
-                ty: TypeRef::Named("User".to_string()),
-                arguments: IndexMap::new(),
-                deprecated: false,
-                deprecation_reason: None,
-            },
-        );
-        query_fields.insert(
-            "users".to_string(),
-            FieldDef {
-                name: "users".to_string(),
-                description: None,
-                ty: TypeRef::List(Box::new(TypeRef::Named("User".to_string()))),
-                arguments: IndexMap::new(),
-                deprecated: false,
-                deprecation_reason: None,
-            },
-        );
-
-        SchemaBuilder::new()
-            .query_type("Query")
-            .add_type(TypeDef::Object(ObjectDef {
-                name: "Query".to_string(),
-                description: None,
-                fields: query_fields,
-                implements: Vec::new(),
-            }))
-            .add_type(TypeDef::Object(ObjectDef {
-                name: "User".to_string(),
-                description: None,
-                fields: user_fields,
-                implements: Vec::new(),
-            }))
-            .build()
+        schema_with_fields(vec![
+            (
+                "Query",
+                vec![
+                    field_def("user", TypeRef::named("User")),
+                    field_def("users", TypeRef::list(TypeRef::named("User"))),
+                ],
+            ),
+            (
+                "User",
+                vec![
+                    field_def("id", TypeRef::named("ID")),
+                    field_def("name", TypeRef::named("String")),
+                    field_def("friend", TypeRef::named("User")),
+                    field_def("email", TypeRef::named("String")),
+                ],
+            ),
+        ])
     }
 
     fn create_test_operation() -> HirOperation {
@@ -666,14 +755,20 @@ mod tests {
                         name: "id".to_string(),
                         arguments: Vec::new(),
                         selections: Vec::new(),
+                        stream: None,
+                        condition: HirCondition::default(),
                     }),
                     HirSelection::Field(HirFieldSelection {
                         alias: None,
                         name: "name".to_string(),
                         arguments: Vec::new(),
                         selections: Vec::new(),
+                        stream: None,
+                        condition: HirCondition::default(),
                     }),
                 ],
+                stream: None,
+                condition: HirCondition::default(),
             })],
             span: Span::empty(0),
         }
@@ -714,20 +809,28 @@ mod tests {
                         name: "id".to_string(),
                         arguments: Vec::new(),
                         selections: Vec::new(),
+                        stream: None,
+                        condition: HirCondition::default(),
                     }),
                     HirSelection::Field(HirFieldSelection {
                         alias: None,
                         name: "name".to_string(),
                         arguments: Vec::new(),
                         selections: Vec::new(),
+                        stream: None,
+                        condition: HirCondition::default(),
                     }),
                     HirSelection::Field(HirFieldSelection {
                         alias: None,
                         name: "email".to_string(),
                         arguments: Vec::new(),
                         selections: Vec::new(),
+                        stream: None,
+                        condition: HirCondition::default(),
                     }),
                 ],
+                stream: None,
+                condition: HirCondition::default(),
             })],
             span: Span::empty(0),
         };
@@ -742,6 +845,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_condition_holds() {
+        let variables = serde_json::json!({ "on": true, "off": false });
+        let resolve = |value: &serde_json::Value| match value.get("$var") {
+            Some(serde_json::Value::String(name)) => variables[name.as_str()].clone(),
+            _ => value.clone(),
+        };
+        let condition = |skip: Option<serde_json::Value>, include: Option<serde_json::Value>| {
+            Condition { skip, include }.holds(resolve)
+        };
+        let var = |name: &str| Some(serde_json::json!({ "$var": name }));
+
+        assert!(condition(None, None));
+        assert!(!condition(Some(serde_json::json!(true)), None));
+        assert!(condition(var("off"), var("on")));
+        assert!(!condition(var("off"), var("off")));
+        // `@skip` wins over `@include`
+        assert!(!condition(var("on"), var("on")));
+    }
+
     #[test]
     fn test_plan_depth_limit() {
         let schema = create_test_schema();
@@ -758,6 +881,146 @@ mod tests {
         assert!(result.unwrap_err().message.contains("depth"));
     }
 
+    fn field(name: &str, selections: Vec<HirSelection>) -> HirSelection {
+        HirSelection::Field(HirFieldSelection {
+            alias: None,
+            name: name.to_string(),
+            arguments: Vec::new(),
+            selections,
+            stream: None,
+            condition: HirCondition::default(),
+        })
+    }
+
+    fn query(selections: Vec<HirSelection>) -> HirOperation {
+        HirOperation {
+            kind: HirOperationKind::Query,
+            name: None,
+            variables: Vec::new(),
+            selections,
+            span: Span::empty(0),
+        }
+    }
+
+    fn fragment(name: &str, selections: Vec<HirSelection>) -> HirFragment {
+        HirFragment {
+            name: name.to_string(),
+            type_condition: "User".to_string(),
+            selections,
+        }
+    }
+
+    #[test]
+    fn test_plan_depth_counts_fragment_spreads() {
+        let schema = create_test_schema();
+        let planner = QueryPlanner::with_config(PlannerConfig {
+            max_depth: 2,
+            ..Default::default()
+        });
+        // user { friend { ...FriendOfFriend } }
+        let operation = query(vec![field(
+            "user",
+            vec![field(
+                "friend",
+                vec![HirSelection::FragmentSpread("FriendOfFriend".to_string())],
+            )],
+        )]);
+
+        let shallow = [fragment("FriendOfFriend", vec![field("id", vec![])])];
+        let plan = planner
+            .plan_with_fragments(&operation, &shallow, &schema)
+            .unwrap();
+        assert_eq!(plan.max_depth, 2);
+        assert_eq!(plan.root.field_count(), 3);
+
+        let deep = [fragment(
+            "FriendOfFriend",
+            vec![field("friend", vec![field("id", vec![])])],
+        )];
+        let err = planner
+            .plan_with_fragments(&operation, &deep, &schema)
+            .unwrap_err();
+        assert!(err.message.contains("depth 3"), "{}", err.message);
+    }
+
+    #[test]
+    fn test_plan_complexity_limit() {
+        let schema = create_test_schema();
+        let operation = query(vec![field("users", vec![field("id", vec![])])]);
+
+        let plan = QueryPlanner::new().plan(&operation, &schema).unwrap();
+        assert_eq!(plan.complexity, 11);
+
+        let planner = QueryPlanner::with_config(PlannerConfig {
+            max_complexity: 10,
+            ..Default::default()
+        });
+        let err = planner.plan(&operation, &schema).unwrap_err();
+        assert!(err.message.contains("complexity"), "{}", err.message);
+    }
+
+    #[test]
+    fn test_plan_complexity_counts_typename() {
+        let schema = create_test_schema();
+        let planner = QueryPlanner::with_config(PlannerConfig {
+            max_complexity: 2,
+            ..Default::default()
+        });
+        let typenames = (0..3).map(|_| field("__typename", vec![])).collect();
+        let operation = query(vec![field("user", typenames)]);
+
+        let err = planner.plan(&operation, &schema).unwrap_err();
+        assert!(err.message.contains("complexity 3"), "{}", err.message);
+    }
+
+    #[test]
+    fn test_plan_doubled_fragment_chain() {
+        let schema = create_test_schema();
+        let spread = |name: &str| HirSelection::FragmentSpread(name.to_string());
+        // F0 spreads F1 twice, F1 spreads F2 twice, ... which expands to
+        // 2^40 `id` fields
+        let mut fragments: Vec<HirFragment> = (0..40)
+            .map(|i| {
+                let next = format!("F{}", i + 1);
+                fragment(&format!("F{}", i), vec![spread(&next), spread(&next)])
+            })
+            .collect();
+        fragments.push(fragment("F40", vec![field("id", vec![])]));
+        let operation = query(vec![field("user", vec![spread("F0")])]);
+
+        let err = QueryPlanner::new()
+            .plan_with_fragments(&operation, &fragments, &schema)
+            .unwrap_err();
+        assert!(err.message.contains("complexity"), "{}", err.message);
+    }
+
+    #[test]
+    fn test_plan_fragment_cycle() {
+        let schema = create_test_schema();
+        let operation = query(vec![field(
+            "user",
+            vec![HirSelection::FragmentSpread("A".to_string())],
+        )]);
+        let fragments = [
+            fragment("A", vec![HirSelection::FragmentSpread("B".to_string())]),
+            fragment(
+                "B",
+                vec![
+                    field("id", vec![]),
+                    HirSelection::FragmentSpread("A".to_string()),
+                ],
+            ),
+        ];
+
+        let err = QueryPlanner::new()
+            .plan_with_fragments(&operation, &fragments, &schema)
+            .unwrap_err();
+        assert!(err.message.contains("cycle"), "{}", err.message);
+
+        let err = QueryPlanner::new().plan(&operation, &schema).unwrap_err();
+        assert!(err.message.contains("Unknown fragment"), "{}", err.message);
+    }
+
     #[test]
     fn test_plan_typename() {
         let schema = create_test_schema();
@@ -776,7 +1039,11 @@ mod tests {
                     name: "__typename".to_string(),
                     arguments: Vec::new(),
                     selections: Vec::new(),
+                    stream: None,
+                    condition: HirCondition::default(),
                 })],
+                stream: None,
+                condition: HirCondition::default(),
             })],
             span: Span::empty(0),
         };
@@ -785,6 +1052,56 @@ mod tests {
         assert!(plan.root.field_count() > 0);
     }
 
+    #[test]
+    fn test_plan_introspection() {
+        let mut schema = create_test_schema();
+        let planner = |max_depth, max_complexity| {
+            QueryPlanner::with_config(PlannerConfig {
+                max_depth,
+                max_complexity,
+                ..Default::default()
+            })
+        };
+        // __schema { types { fields { type { ofType { name } } } } }
+        let operation = query(vec![field(
+            "__schema",
+            vec![field(
+                "types",
+                vec![field(
+                    "fields",
+                    vec![field(
+                        "type",
+                        vec![field("ofType", vec![field("name", vec![])])],
+                    )],
+                )],
+            )],
+        )]);
+
+        // Introspection is as deep as it nests, but only `__schema` adds
+        // to the complexity
+        let plan = planner(5, 1).plan(&operation, &schema).unwrap();
+        assert_eq!(plan.max_depth, 5);
+        assert_eq!(plan.complexity, 1);
+        assert_eq!(plan.root.field_count(), 6);
+
+        let err = planner(4, 1).plan(&operation, &schema).unwrap_err();
+        assert_eq!(err.message, "Query depth 5 exceeds maximum allowed depth 4");
+        let err = planner(5, 0).plan(&operation, &schema).unwrap_err();
+        assert_eq!(
+            err.message,
+            "Query complexity 1 exceeds maximum allowed complexity 0"
+        );
+
+        schema.endpoint.introspection = false;
+        let err = planner(5, 1).plan(&operation, &schema).unwrap_err();
+        assert_eq!(err.message, "Introspection is disabled");
+
+        // `__schema` is only defined on the query type
+        let operation = query(vec![field("user", vec![field("__schema", vec![])])]);
+        let err = QueryPlanner::new().plan(&operation, &schema).unwrap_err();
+        assert_eq!(err.message, "Field '__schema' not found on type 'User'");
+    }
+
     #[test]
     fn test_field_info_response_key() {
         let info = FieldInfo {
@@ -2,13 +2,16 @@
 //!
 //! This module provides the resolver trait and infrastructure for field resolution.
 
+use crate::binary_transport::BinaryBlob;
 use crate::executor::{Context, FieldError, PathSegment};
+use crate::schema::Schema;
+use serde_json::value::RawValue;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 /// Arguments passed to a resolver.
 #[derive(Debug, Clone, Default)]
@@ -85,6 +88,16 @@ pub struct ResolverInfo {
 
     /// Selected sub-fields (for object types).
     pub selected_fields: Vec<String>,
+
+    /// Parent property to read instead of the field name (from `@source`).
+    pub source: Option<String>,
+
+    /// Whether the field may be null, i.e. its type is an `Option`.
+    pub nullable: bool,
+
+    /// Answer to the field set instead of a value, shared by the clones of
+    /// this info.
+    answer: Arc<Mutex<Option<FieldAnswer>>>,
 }
 
 impl ResolverInfo {
@@ -96,6 +109,9 @@ impl ResolverInfo {
             parent_type: parent_type.into(),
             path: Vec::new(),
             selected_fields: Vec::new(),
+            source: None,
+            nullable: true,
+            answer: Arc::default(),
         }
     }
 
@@ -116,6 +132,55 @@ impl ResolverInfo {
         self.selected_fields = fields;
         self
     }
+
+    /// Sets the parent property the default resolver reads.
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Sets whether the field may be null.
+    pub fn with_nullable(mut self, nullable: bool) -> Self {
+        self.nullable = nullable;
+        self
+    }
+
+    /// Answers the field with pre-serialized JSON, which the executor
+    /// embeds without resolving the field's selections. Returns the value
+    /// the resolver should resolve to.
+    pub fn respond_raw(&self, json: RawJson) -> Value {
+        self.answer(FieldAnswer::Raw(json))
+    }
+
+    /// Answers a field of a `File`-like scalar with binary data. The field
+    /// resolves to a reference to the blob, which is streamed after the
+    /// response to clients that negotiate binary transport and inlined as
+    /// base64 for the others; see [`binary_transport`](crate::binary_transport).
+    /// Returns the value the resolver should resolve to.
+    pub fn respond_binary(&self, blob: BinaryBlob) -> Value {
+        self.answer(FieldAnswer::Binary(blob))
+    }
+
+    fn answer(&self, answer: FieldAnswer) -> Value {
+        *self.answer.lock().unwrap_or_else(|e| e.into_inner()) = Some(answer);
+        Value::Null
+    }
+
+    /// Removes the answer set by [`ResolverInfo::respond_raw`] or
+    /// [`ResolverInfo::respond_binary`].
+    pub(crate) fn take_answer(&self) -> Option<FieldAnswer> {
+        self.answer.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+}
+
+/// An answer to a field set through [`ResolverInfo`] instead of resolving to
+/// a value.
+#[derive(Debug)]
+pub(crate) enum FieldAnswer {
+    /// Pre-serialized JSON, from [`ResolverInfo::respond_raw`].
+    Raw(RawJson),
+    /// Binary data, from [`ResolverInfo::respond_binary`].
+    Binary(BinaryBlob),
 }
 
 /// Result type for resolvers.
@@ -142,10 +207,25 @@ pub enum ResolverError {
     /// Custom error.
     Custom(String),
 
+    /// Error meant for the client, e.g. a failed validation.
+    ///
+    /// Its message is returned as-is even when error masking is enabled.
+    Client(String),
+
     /// Internal error.
     Internal(String),
 }
 
+impl ResolverError {
+    /// Returns true if the message is safe to show to clients.
+    pub fn is_client_facing(&self) -> bool {
+        matches!(
+            self,
+            Self::Client(_) | Self::MissingArgument(_) | Self::ArgumentParseError(_, _)
+        )
+    }
+}
+
 impl std::fmt::Display for ResolverError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -155,7 +235,7 @@ impl std::fmt::Display for ResolverError {
                 write!(f, "Failed to parse argument '{}': {}", arg, err)
             }
             Self::NullValue(field) => write!(f, "Null value for non-nullable field: {}", field),
-            Self::Custom(msg) => write!(f, "{}", msg),
+            Self::Custom(msg) | Self::Client(msg) => write!(f, "{}", msg),
             Self::Internal(msg) => write!(f, "Internal error: {}", msg),
         }
     }
@@ -163,6 +243,40 @@ impl std::fmt::Display for ResolverError {
 
 impl std::error::Error for ResolverError {}
 
+/// JSON a resolver already holds in serialized form, e.g. from a cache.
+///
+/// Resolvers answer a field with it through [`ResolverInfo::respond_raw`].
+/// The executor then embeds it in the response as-is instead of resolving
+/// the field's selections against it.
+#[derive(Debug, Clone)]
+pub struct RawJson(Box<RawValue>);
+
+impl RawJson {
+    /// Wraps serialized JSON, checking that it is well-formed.
+    pub fn new(json: impl Into<String>) -> Result<Self, ResolverError> {
+        RawValue::from_string(json.into())
+            .map(Self)
+            .map_err(|e| ResolverError::Internal(format!("Invalid raw JSON: {}", e)))
+    }
+
+    /// Returns the serialized JSON.
+    pub fn as_str(&self) -> &str {
+        self.0.get()
+    }
+
+    /// Converts the JSON to a [`Value`] for the response tree.
+    pub(crate) fn to_value(&self) -> Value {
+        // Well-formed since construction
+        serde_json::from_str(self.0.get()).unwrap_or(Value::Null)
+    }
+}
+
+impl PartialEq for RawJson {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
 impl From<ResolverError> for FieldError {
     fn from(error: ResolverError) -> Self {
         FieldError::new(error.to_string())
@@ -260,6 +374,10 @@ impl Resolver for AsyncFnResolver {
 }
 
 /// Default resolver that accesses properties from the parent object.
+///
+/// Reads `info.source` when set, otherwise the field name or its snake_case
+/// form. A missing property resolves to `null`, which the executor reports
+/// for non-null fields.
 pub struct DefaultResolver;
 
 impl Resolver for DefaultResolver {
@@ -273,17 +391,13 @@ impl Resolver for DefaultResolver {
         let field_name = &info.field_name;
         let result = match parent {
             Value::Object(map) => {
-                if let Some(value) = map.get(field_name) {
-                    Ok(value.clone())
-                } else {
-                    // Try snake_case version
-                    let snake_case = to_snake_case(field_name);
-                    if let Some(value) = map.get(&snake_case) {
-                        Ok(value.clone())
-                    } else {
-                        Ok(Value::Null)
-                    }
-                }
+                let value = match &info.source {
+                    Some(source) => map.get(source),
+                    None => map
+                        .get(field_name)
+                        .or_else(|| map.get(&to_snake_case(field_name))),
+                };
+                Ok(value.cloned().unwrap_or(Value::Null))
             }
             Value::Null => Ok(Value::Null),
             _ => Err(ResolverError::FieldNotFound(field_name.clone())),
@@ -308,6 +422,58 @@ fn to_snake_case(s: &str) -> String {
     result
 }
 
+/// A resolver registered for a type or field the schema doesn't define.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnknownResolver {
+    /// The type isn't defined.
+    Type {
+        type_name: String,
+        field_name: String,
+    },
+    /// The type is defined but has no such field.
+    Field {
+        type_name: String,
+        field_name: String,
+    },
+}
+
+impl UnknownResolver {
+    /// Checks a resolver's type and field against `schema`.
+    pub fn check(schema: &Schema, type_name: &str, field_name: &str) -> Option<Self> {
+        if schema.get_type(type_name).is_none() {
+            Some(Self::Type {
+                type_name: type_name.to_string(),
+                field_name: field_name.to_string(),
+            })
+        } else if schema.get_field(type_name, field_name).is_none() {
+            Some(Self::Field {
+                type_name: type_name.to_string(),
+                field_name: field_name.to_string(),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Display for UnknownResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Type {
+                type_name,
+                field_name,
+            } => write!(f, "`{type_name}.{field_name}`: unknown type `{type_name}`"),
+            Self::Field {
+                type_name,
+                field_name,
+            } => write!(
+                f,
+                "`{type_name}.{field_name}`: `{type_name}` has no field `{field_name}`"
+            ),
+        }
+    }
+}
+
 /// Storage for resolvers organized by type and field.
 #[derive(Default)]
 pub struct ResolverMap {
@@ -375,6 +541,31 @@ impl ResolverMap {
             .or(self.default_resolver.as_ref().map(|r| r.as_ref()))
     }
 
+    /// Returns whether a resolver is registered for a type and field,
+    /// ignoring the default resolver.
+    pub fn contains(&self, type_name: &str, field_name: &str) -> bool {
+        self.resolvers
+            .contains_key(&format!("{}.{}", type_name, field_name))
+    }
+
+    /// Returns the `(type, field)` pairs that have a registered resolver.
+    pub fn registered(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.resolvers.keys().filter_map(|key| key.split_once('.'))
+    }
+
+    /// Returns the registered resolvers whose type or field `schema` doesn't
+    /// define, sorted by type and field.
+    pub fn unknown_resolvers(&self, schema: &Schema) -> Vec<UnknownResolver> {
+        let mut registered: Vec<_> = self.registered().collect();
+        registered.sort_unstable();
+        registered
+            .into_iter()
+            .filter_map(|(type_name, field_name)| {
+                UnknownResolver::check(schema, type_name, field_name)
+            })
+            .collect()
+    }
+
     /// Sets the default resolver.
     pub fn set_default<R: Resolver + 'static>(&mut self, resolver: R) {
         self.default_resolver = Some(Box::new(resolver));
@@ -482,4 +673,16 @@ mod tests {
         let result = resolver.resolve(&parent, &args, &ctx, &info).await;
         assert_eq!(result.unwrap(), serde_json::json!("Bob"));
     }
+
+    #[tokio::test]
+    async fn test_default_resolver_with_source() {
+        let resolver = DefaultResolver;
+        let parent = serde_json::json!({"created_at": "2024-01-01", "joinedAt": "ignored"});
+        let args = ResolverArgs::new();
+        let ctx = Context::new();
+        let info = ResolverInfo::new("joinedAt", "User").with_source("created_at");
+
+        let result = resolver.resolve(&parent, &args, &ctx, &info).await;
+        assert_eq!(result.unwrap(), serde_json::json!("2024-01-01"));
+    }
 }
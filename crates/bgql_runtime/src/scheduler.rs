@@ -8,10 +8,11 @@ use crate::state::{ExecutionId, ExecutionState};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap};
+use std::future::Future;
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering as AtomicOrdering};
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime};
-use tokio::sync::{broadcast, mpsc, Mutex, RwLock};
+use tokio::sync::{broadcast, mpsc, oneshot, Mutex, RwLock};
 
 /// Task priority level (1 = highest, 10 = lowest).
 pub type PriorityLevel = u8;
@@ -313,6 +314,90 @@ impl PartialOrd for QueueEntry {
     }
 }
 
+/// Slots for futures run by [`QueryScheduler::run`].
+#[derive(Debug)]
+struct SlotQueue {
+    /// Slots not held by a running future.
+    free: usize,
+    /// Futures waiting for a slot.
+    waiting: BinaryHeap<SlotWaiter>,
+    /// Submission counter, the tiebreaker between equal priorities.
+    next_seq: u64,
+}
+
+impl SlotQueue {
+    /// Hands a released slot to the most urgent waiter still waiting, or
+    /// frees it.
+    fn release(slots: &std::sync::Mutex<SlotQueue>) {
+        let mut slots = slots.lock().unwrap_or_else(|e| e.into_inner());
+        while let Some(waiter) = slots.waiting.pop() {
+            if waiter.tx.send(()).is_ok() {
+                return;
+            }
+        }
+        slots.free += 1;
+    }
+}
+
+/// A future waiting in a [`SlotQueue`].
+#[derive(Debug)]
+struct SlotWaiter {
+    effective_priority: u8,
+    seq: u64,
+    tx: oneshot::Sender<()>,
+}
+
+impl Eq for SlotWaiter {}
+
+impl PartialEq for SlotWaiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.seq == other.seq
+    }
+}
+
+impl Ord for SlotWaiter {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Lower priority number = higher priority, then first come first served
+        other
+            .effective_priority
+            .cmp(&self.effective_priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+impl PartialOrd for SlotWaiter {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A slot held by a running future, released on drop.
+struct SlotPermit {
+    slots: Arc<std::sync::Mutex<SlotQueue>>,
+}
+
+impl Drop for SlotPermit {
+    fn drop(&mut self) {
+        SlotQueue::release(&self.slots);
+    }
+}
+
+/// A slot being waited for. If the wait is cancelled after the slot was
+/// handed over, the slot is released again.
+struct PendingSlot {
+    rx: oneshot::Receiver<()>,
+    slots: Arc<std::sync::Mutex<SlotQueue>>,
+}
+
+impl Drop for PendingSlot {
+    fn drop(&mut self) {
+        self.rx.close();
+        if self.rx.try_recv().is_ok() {
+            SlotQueue::release(&self.slots);
+        }
+    }
+}
+
 /// Execution handle for tracking and controlling a submitted task.
 pub struct ExecutionHandle {
     /// Task ID.
@@ -418,6 +503,9 @@ pub struct QueryScheduler {
 
     /// Running state.
     is_running: Arc<std::sync::atomic::AtomicBool>,
+
+    /// Slots for futures run with [`QueryScheduler::run`].
+    slots: Arc<std::sync::Mutex<SlotQueue>>,
 }
 
 /// Information about a running task.
@@ -466,7 +554,6 @@ impl QueryScheduler {
         let (status_tx, _) = broadcast::channel(1024);
 
         Self {
-            config,
             resource_manager,
             queue: Arc::new(Mutex::new(BinaryHeap::new())),
             running: Arc::new(RwLock::new(HashMap::new())),
@@ -475,6 +562,12 @@ impl QueryScheduler {
             shutdown_tx: None,
             stats: Arc::new(SchedulerStats::default()),
             is_running: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            slots: Arc::new(std::sync::Mutex::new(SlotQueue {
+                free: config.worker_count.max(1),
+                waiting: BinaryHeap::new(),
+                next_seq: 0,
+            })),
+            config,
         }
     }
 
@@ -726,6 +819,62 @@ impl QueryScheduler {
         self.submit(task).await
     }
 
+    /// Runs `future` once one of the scheduler's `worker_count` slots is
+    /// free.
+    ///
+    /// While every slot is taken, waiting futures are admitted by effective
+    /// priority and then in submission order, so under contention a
+    /// high-priority future starts before low-priority ones submitted
+    /// earlier. Unlike [`submit`](Self::submit), this doesn't need the
+    /// workers to be [started](Self::start); the future runs on the calling
+    /// task.
+    pub async fn run<F: Future>(&self, priority: &TaskPriority, future: F) -> F::Output {
+        let _permit = self.acquire_slot(priority).await;
+        future.await
+    }
+
+    /// Waits for a free slot.
+    async fn acquire_slot(&self, priority: &TaskPriority) -> SlotPermit {
+        let rx = {
+            let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+            if slots.free > 0 && slots.waiting.is_empty() {
+                slots.free -= 1;
+                None
+            } else {
+                let (tx, rx) = oneshot::channel();
+                let seq = slots.next_seq;
+                slots.next_seq += 1;
+                slots.waiting.push(SlotWaiter {
+                    effective_priority: priority.effective_priority(),
+                    seq,
+                    tx,
+                });
+                Some(rx)
+            }
+        };
+
+        if let Some(rx) = rx {
+            let mut pending = PendingSlot {
+                rx,
+                slots: Arc::clone(&self.slots),
+            };
+            // The sender is only dropped by handing over the slot
+            let _ = (&mut pending.rx).await;
+        }
+        SlotPermit {
+            slots: Arc::clone(&self.slots),
+        }
+    }
+
+    /// Gets the number of futures waiting for a slot in [`run`](Self::run).
+    pub fn waiting_count(&self) -> usize {
+        self.slots
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .waiting
+            .len()
+    }
+
     /// Cancels a task.
     pub async fn cancel(&self, task_id: &str) -> bool {
         // Try to remove from queue
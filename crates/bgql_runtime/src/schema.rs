@@ -244,10 +244,33 @@ impl Schema {
         self.types.get(name)
     }
 
+    /// Gets a field of an object or interface type.
+    pub fn get_field(&self, type_name: &str, field_name: &str) -> Option<&FieldDef> {
+        match self.types.get(type_name)? {
+            TypeDef::Object(obj) => obj.fields.get(field_name),
+            TypeDef::Interface(iface) => iface.fields.get(field_name),
+            _ => None,
+        }
+    }
+
     /// Returns all types.
     pub fn types(&self) -> impl Iterator<Item = (&String, &TypeDef)> {
         self.types.iter()
     }
+
+    /// Returns the schema as exposed by introspection, without its
+    /// `@internal` fields.
+    pub fn public(&self) -> Schema {
+        let mut schema = self.clone();
+        for type_def in schema.types.values_mut() {
+            if let TypeDef::Object(ObjectDef { fields, .. })
+            | TypeDef::Interface(InterfaceDef { fields, .. }) = type_def
+            {
+                fields.retain(|_, field| !field.is_internal());
+            }
+        }
+        schema
+    }
 }
 
 /// A type definition.
@@ -317,6 +340,9 @@ pub struct InputObjectDef {
     pub name: String,
     pub description: Option<String>,
     pub fields: IndexMap<String, InputFieldDef>,
+    /// Exactly one field must be provided (`@oneOf`).
+    #[serde(default)]
+    pub one_of: bool,
 }
 
 /// Field definition.
@@ -328,6 +354,52 @@ pub struct FieldDef {
     pub arguments: IndexMap<String, InputFieldDef>,
     pub deprecated: bool,
     pub deprecation_reason: Option<String>,
+    /// Parent property the default resolver reads instead of `name` (`@source(name:)`).
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Directives applied to the field, in source order.
+    #[serde(default)]
+    pub directives: Vec<AppliedDirective>,
+}
+
+/// Name of the directive hiding a field from introspection.
+pub const INTERNAL_DIRECTIVE: &str = "internal";
+
+impl FieldDef {
+    /// Returns whether the field is marked `@internal`.
+    pub fn is_internal(&self) -> bool {
+        self.directives
+            .iter()
+            .any(|directive| directive.name == INTERNAL_DIRECTIVE)
+    }
+}
+
+/// A directive applied to a schema element, e.g. `@currency(code: "USD")`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppliedDirective {
+    pub name: String,
+    pub arguments: IndexMap<String, serde_json::Value>,
+}
+
+impl AppliedDirective {
+    /// Creates a directive application without arguments.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            arguments: IndexMap::new(),
+        }
+    }
+
+    /// Adds an argument.
+    pub fn with_argument(mut self, name: impl Into<String>, value: serde_json::Value) -> Self {
+        self.arguments.insert(name.into(), value);
+        self
+    }
+
+    /// Gets an argument by name.
+    pub fn argument(&self, name: &str) -> Option<&serde_json::Value> {
+        self.arguments.get(name)
+    }
 }
 
 /// Input field definition.
@@ -361,6 +433,16 @@ impl TypeRef {
     }
 }
 
+impl std::fmt::Display for TypeRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Named(name) => write!(f, "{}", name),
+            Self::Option(inner) => write!(f, "Option<{}>", inner),
+            Self::List(inner) => write!(f, "List<{}>", inner),
+        }
+    }
+}
+
 /// Directive definition.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DirectiveDefinition {
@@ -530,6 +612,47 @@ mod tests {
         assert!(!v1.is_compatible_with(&v3));
     }
 
+    #[test]
+    fn test_public_schema_hides_internal_fields() {
+        let field = |name: &str, directives: Vec<AppliedDirective>| FieldDef {
+            name: name.to_string(),
+            description: None,
+            ty: TypeRef::named("String"),
+            arguments: IndexMap::new(),
+            deprecated: false,
+            deprecation_reason: None,
+            source: None,
+            directives,
+        };
+        let schema = SchemaBuilder::new()
+            .add_type(TypeDef::Object(ObjectDef {
+                name: "User".to_string(),
+                description: None,
+                fields: [
+                    ("name".to_string(), field("name", Vec::new())),
+                    (
+                        "passwordHash".to_string(),
+                        field(
+                            "passwordHash",
+                            vec![AppliedDirective::new(INTERNAL_DIRECTIVE)],
+                        ),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+                implements: Vec::new(),
+            }))
+            .build();
+
+        let public = schema.public();
+        assert!(public.get_field("User", "name").is_some());
+        assert!(public.get_field("User", "passwordHash").is_none());
+        assert!(schema
+            .get_field("User", "passwordHash")
+            .unwrap()
+            .is_internal());
+    }
+
     #[test]
     fn test_schema_builder() {
         let schema = SchemaBuilder::new()
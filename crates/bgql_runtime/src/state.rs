@@ -6,6 +6,7 @@
 use crate::streaming::PathSegment;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::{Duration, SystemTime};
 
 /// Unique identifier for an execution.
@@ -197,6 +198,72 @@ impl ExecutionState {
     }
 }
 
+/// Storage for resumable execution states, keyed by resume token.
+///
+/// States are saved in serialized form, so an implementation backed by a
+/// database or cache lets executions resume after a server restart.
+pub trait CheckpointStore: Send + Sync {
+    /// Saves `state` under `token`, replacing any previous state.
+    fn save(&self, token: &str, state: &ExecutionState);
+
+    /// Loads the state saved under `token`.
+    fn load(&self, token: &str) -> Option<ExecutionState>;
+
+    /// Removes the state saved under `token`.
+    fn remove(&self, token: &str);
+}
+
+/// A [`CheckpointStore`] that keeps serialized states in memory.
+///
+/// Clones share the same storage.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryCheckpointStore {
+    states: Arc<Mutex<HashMap<ResumeToken, String>>>,
+}
+
+impl MemoryCheckpointStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of saved states.
+    pub fn len(&self) -> usize {
+        self.states
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Returns true if no states are saved.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl CheckpointStore for MemoryCheckpointStore {
+    fn save(&self, token: &str, state: &ExecutionState) {
+        if let Ok(json) = serde_json::to_string(state) {
+            self.states
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(token.to_string(), json);
+        }
+    }
+
+    fn load(&self, token: &str) -> Option<ExecutionState> {
+        let states = self.states.lock().unwrap_or_else(PoisonError::into_inner);
+        serde_json::from_str(states.get(token)?).ok()
+    }
+
+    fn remove(&self, token: &str) {
+        self.states
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(token);
+    }
+}
+
 /// Phase of query execution.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -90,6 +90,8 @@ impl From<usize> for PathSegment {
 /// Incremental delivery event.
 #[derive(Debug, Clone)]
 pub enum IncrementalEvent {
+    /// The initial response, sent before any incremental payload.
+    Initial(StreamingResponse),
     /// A deferred payload.
     Defer(DeferPayload),
     /// A streamed payload.
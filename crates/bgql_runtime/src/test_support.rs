@@ -0,0 +1,73 @@
+//! Schema and plan fixtures shared by the runtime tests.
+
+use crate::query::{FieldInfo, PlanNode};
+use crate::schema::{FieldDef, ObjectDef, Schema, SchemaBuilder, TypeDef, TypeRef};
+use indexmap::IndexMap;
+
+/// A field of type `ty` without arguments or directives.
+pub fn field_def(name: &str, ty: TypeRef) -> FieldDef {
+    FieldDef {
+        name: name.to_string(),
+        description: None,
+        ty,
+        arguments: IndexMap::new(),
+        deprecated: false,
+        deprecation_reason: None,
+        source: None,
+        directives: Vec::new(),
+    }
+}
+
+/// An object type with `fields`.
+pub fn object_def(name: &str, fields: Vec<FieldDef>) -> TypeDef {
+    TypeDef::Object(ObjectDef {
+        name: name.to_string(),
+        description: None,
+        fields: fields
+            .into_iter()
+            .map(|field| (field.name.clone(), field))
+            .collect(),
+        implements: Vec::new(),
+    })
+}
+
+/// A schema with the object types `objects`, the first of which is the
+/// query type.
+pub fn schema_with_fields(objects: Vec<(&str, Vec<FieldDef>)>) -> Schema {
+    let mut builder = SchemaBuilder::new();
+    if let Some((query, _)) = objects.first() {
+        builder = builder.query_type(*query);
+    }
+    for (name, fields) in objects {
+        builder = builder.add_type(object_def(name, fields));
+    }
+    builder.build()
+}
+
+/// The selection of `parent_type.name` returning `return_type`.
+pub fn field_info(parent_type: &str, name: &str, return_type: &str) -> FieldInfo {
+    FieldInfo {
+        name: name.to_string(),
+        alias: None,
+        parent_type: parent_type.to_string(),
+        return_type: return_type.to_string(),
+        arguments: Vec::new(),
+        is_introspection: false,
+    }
+}
+
+/// A leaf selection of `parent_type.name` returning `return_type`.
+pub fn leaf(parent_type: &str, name: &str, return_type: &str) -> PlanNode {
+    PlanNode::Leaf {
+        field: field_info(parent_type, name, return_type),
+    }
+}
+
+/// The selection of `info` with `children` selected on its value.
+pub fn field_node(info: FieldInfo, children: PlanNode) -> PlanNode {
+    PlanNode::Field {
+        response_name: info.alias.clone().unwrap_or_else(|| info.name.clone()),
+        info,
+        children: Box::new(children),
+    }
+}
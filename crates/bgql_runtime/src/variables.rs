@@ -0,0 +1,320 @@
+//! Validation and coercion of operation variables.
+
+use crate::schema::{Schema, TypeDef, TypeRef};
+use bgql_core::Interner;
+use bgql_syntax::Value as SyntaxValue;
+use serde_json::{Map, Value};
+
+/// A variable declared by an operation.
+#[derive(Debug, Clone)]
+pub struct VariableDef {
+    /// Name without the `$`.
+    pub name: String,
+    /// Declared type.
+    pub ty: TypeRef,
+    /// Value used when the variable isn't provided.
+    pub default_value: Option<Value>,
+}
+
+/// Validates `provided` against the operation's variable definitions and
+/// coerces the values to their declared types.
+///
+/// Follows GraphQL's input coercion: an `Int` is accepted where a `Float`
+/// is expected, an `ID` may be given as a string or an integer, a single
+/// value is wrapped into a list, and enum values are given by name. Missing
+/// variables take their default value; nullable ones are left out.
+///
+/// Returns one message per mismatch: undeclared, missing required and
+/// ill-typed variables.
+pub fn coerce_variables(
+    definitions: &[VariableDef],
+    provided: Option<&Value>,
+    schema: &Schema,
+) -> Result<Map<String, Value>, Vec<String>> {
+    let empty = Map::new();
+    let provided = match provided {
+        None | Some(Value::Null) => &empty,
+        Some(Value::Object(provided)) => provided,
+        Some(other) => return Err(vec![format!("Variables must be an object, got {}", other)]),
+    };
+
+    let mut errors = Vec::new();
+    for name in provided.keys() {
+        if !definitions.iter().any(|def| &def.name == name) {
+            errors.push(format!(
+                "Variable `${}` is not defined by the operation",
+                name
+            ));
+        }
+    }
+
+    let mut coerced = Map::new();
+    for def in definitions {
+        let value = match (provided.get(&def.name), &def.default_value) {
+            (Some(value), _) => value,
+            (None, Some(default)) => default,
+            (None, None) if matches!(def.ty, TypeRef::Option(_)) => continue,
+            (None, None) => {
+                errors.push(format!(
+                    "Variable `${}` of required type `{}` was not provided",
+                    def.name, def.ty
+                ));
+                continue;
+            }
+        };
+        match coerce_value(value, &def.ty, schema) {
+            Ok(value) => {
+                coerced.insert(def.name.clone(), value);
+            }
+            Err(message) => errors.push(format!(
+                "Variable `${}` got invalid value {}: {}",
+                def.name, value, message
+            )),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(coerced)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Coerces an input value to `ty`.
+fn coerce_value(value: &Value, ty: &TypeRef, schema: &Schema) -> Result<Value, String> {
+    match (ty, value) {
+        (TypeRef::Option(_), Value::Null) => Ok(Value::Null),
+        (_, Value::Null) => Err(format!("expected non-null type `{}`", ty)),
+        (TypeRef::Option(inner), _) => coerce_value(value, inner, schema),
+        (TypeRef::List(inner), Value::Array(items)) => items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                coerce_value(item, inner, schema).map_err(|message| format!("at [{i}], {message}"))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        // A single value is coerced to a list of one
+        (TypeRef::List(inner), _) => Ok(Value::Array(vec![coerce_value(value, inner, schema)?])),
+        (TypeRef::Named(name), _) => coerce_named(value, name, schema),
+    }
+}
+
+/// Coerces an input value to the named type `name`.
+fn coerce_named(value: &Value, name: &str, schema: &Schema) -> Result<Value, String> {
+    let mismatch = || format!("expected type `{}`", name);
+    match name {
+        "Int" => match value.as_i64() {
+            Some(n) if i32::try_from(n).is_ok() => Ok(value.clone()),
+            Some(_) => Err("Int must fit in 32 bits".to_string()),
+            None => Err(mismatch()),
+        },
+        "Float" => match value.as_f64() {
+            Some(n) => Ok(Value::from(n)),
+            None => Err(mismatch()),
+        },
+        "String" => match value {
+            Value::String(_) => Ok(value.clone()),
+            _ => Err(mismatch()),
+        },
+        "Boolean" => match value {
+            Value::Bool(_) => Ok(value.clone()),
+            _ => Err(mismatch()),
+        },
+        "ID" => match value {
+            Value::String(_) => Ok(value.clone()),
+            Value::Number(n) if n.is_i64() || n.is_u64() => Ok(Value::String(n.to_string())),
+            _ => Err(mismatch()),
+        },
+        _ => match schema.get_type(name) {
+            Some(TypeDef::Enum(enum_def)) => match value {
+                Value::String(s) if enum_def.values.iter().any(|v| &v.name == s) => {
+                    Ok(value.clone())
+                }
+                _ => Err(format!("expected a value of enum `{}`", name)),
+            },
+            Some(TypeDef::InputObject(input)) => {
+                let Value::Object(fields) = value else {
+                    return Err(mismatch());
+                };
+                if let Some(unknown) = fields.keys().find(|f| !input.fields.contains_key(*f)) {
+                    return Err(format!("`{}` has no field `{}`", name, unknown));
+                }
+                let mut coerced = Map::new();
+                for (field_name, field) in &input.fields {
+                    match fields.get(field_name) {
+                        Some(field_value) => {
+                            let field_value = coerce_value(field_value, &field.ty, schema)
+                                .map_err(|message| {
+                                    format!("at `{}.{}`, {}", name, field_name, message)
+                                })?;
+                            coerced.insert(field_name.clone(), field_value);
+                        }
+                        None => match &field.default_value {
+                            Some(default) => {
+                                let default = literal_to_json(default).ok_or_else(|| {
+                                    format!(
+                                        "invalid default value {} of `{}.{}`",
+                                        default, name, field_name
+                                    )
+                                })?;
+                                coerced.insert(
+                                    field_name.clone(),
+                                    coerce_value(&default, &field.ty, schema)?,
+                                );
+                            }
+                            None if matches!(field.ty, TypeRef::Option(_)) => {}
+                            None => {
+                                return Err(format!(
+                                    "missing required field `{}.{}`",
+                                    name, field_name
+                                ))
+                            }
+                        },
+                    }
+                }
+                Ok(Value::Object(coerced))
+            }
+            // Custom scalars accept any value
+            _ => Ok(value.clone()),
+        },
+    }
+}
+
+/// Converts a GraphQL literal, such as an input field's default value, to
+/// JSON. Enum values become strings.
+fn literal_to_json(literal: &str) -> Option<Value> {
+    let interner = Interner::new();
+    let value = bgql_syntax::parse_value(literal, &interner)?;
+    syntax_value_to_json(&value, &interner)
+}
+
+fn syntax_value_to_json(value: &SyntaxValue<'_>, interner: &Interner) -> Option<Value> {
+    Some(match value {
+        SyntaxValue::Int(n, _) => Value::from(*n),
+        SyntaxValue::Float(n, _) => Value::from(*n),
+        SyntaxValue::String(s, _) => Value::from(s.as_str()),
+        SyntaxValue::Boolean(b, _) => Value::from(*b),
+        SyntaxValue::Null(_) => Value::Null,
+        SyntaxValue::Enum(name) => Value::from(interner.get(name.value)),
+        SyntaxValue::List(items, _) => Value::Array(
+            items
+                .iter()
+                .map(|item| syntax_value_to_json(item, interner))
+                .collect::<Option<_>>()?,
+        ),
+        SyntaxValue::Object(fields, _) => Value::Object(
+            fields
+                .iter()
+                .map(|(name, value)| {
+                    Some((
+                        interner.get(name.value),
+                        syntax_value_to_json(value, interner)?,
+                    ))
+                })
+                .collect::<Option<_>>()?,
+        ),
+        // Defaults can't refer to variables
+        SyntaxValue::Variable(_) | SyntaxValue::_Phantom(_) => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{EnumDef, EnumValueDef, InputFieldDef, InputObjectDef, SchemaBuilder};
+    use serde_json::json;
+
+    fn schema() -> Schema {
+        let value = |name: &str| EnumValueDef {
+            name: name.to_string(),
+            description: None,
+            deprecated: false,
+            deprecation_reason: None,
+        };
+        let field = |name: &str, ty: TypeRef, default_value: Option<&str>| InputFieldDef {
+            name: name.to_string(),
+            description: None,
+            ty,
+            default_value: default_value.map(str::to_string),
+        };
+        SchemaBuilder::new()
+            .add_type(TypeDef::Enum(EnumDef {
+                name: "Role".to_string(),
+                description: None,
+                values: vec![value("ADMIN"), value("USER")],
+            }))
+            .add_type(TypeDef::InputObject(InputObjectDef {
+                name: "UserFilter".to_string(),
+                description: None,
+                fields: [
+                    field("limit", TypeRef::named("Int"), Some("10")),
+                    field(
+                        "roles",
+                        TypeRef::list(TypeRef::named("Role")),
+                        Some("[USER]"),
+                    ),
+                    field("name", TypeRef::option(TypeRef::named("String")), None),
+                ]
+                .into_iter()
+                .map(|field| (field.name.clone(), field))
+                .collect(),
+                one_of: false,
+            }))
+            .build()
+    }
+
+    fn var(name: &str, ty: TypeRef) -> VariableDef {
+        VariableDef {
+            name: name.to_string(),
+            ty,
+            default_value: None,
+        }
+    }
+
+    #[test]
+    fn test_coerces_valid_variables() {
+        let definitions = [
+            var("id", TypeRef::named("ID")),
+            var("ratio", TypeRef::named("Float")),
+            var("roles", TypeRef::list(TypeRef::named("Role"))),
+            var("limit", TypeRef::option(TypeRef::named("Int"))),
+        ];
+        let provided = json!({ "id": 42, "ratio": 1, "roles": "ADMIN" });
+
+        let coerced = coerce_variables(&definitions, Some(&provided), &schema()).unwrap();
+        assert_eq!(coerced["id"], json!("42"));
+        assert_eq!(coerced["ratio"], json!(1.0));
+        assert_eq!(coerced["roles"], json!(["ADMIN"]));
+        assert!(!coerced.contains_key("limit"));
+    }
+
+    #[test]
+    fn test_reports_each_mismatch() {
+        let definitions = [
+            var("id", TypeRef::named("ID")),
+            var("count", TypeRef::named("Int")),
+            var("role", TypeRef::named("Role")),
+        ];
+        let provided = json!({ "count": "3", "role": "ROOT", "extra": true });
+
+        let errors = coerce_variables(&definitions, Some(&provided), &schema()).unwrap_err();
+        assert_eq!(errors.len(), 4, "{errors:?}");
+        assert!(errors[0].contains("`$extra` is not defined"));
+        assert!(errors[1].contains("`$id` of required type `ID` was not provided"));
+        assert!(errors[2].contains("`$count`") && errors[2].contains("expected type `Int`"));
+        assert!(errors[3].contains("`$role`") && errors[3].contains("enum `Role`"));
+    }
+
+    #[test]
+    fn test_applies_input_field_defaults() {
+        let definitions = [var("filter", TypeRef::named("UserFilter"))];
+        let provided = json!({ "filter": { "name": "ada" } });
+
+        let coerced = coerce_variables(&definitions, Some(&provided), &schema()).unwrap();
+        assert_eq!(
+            coerced["filter"],
+            json!({ "name": "ada", "limit": 10, "roles": ["USER"] })
+        );
+    }
+}
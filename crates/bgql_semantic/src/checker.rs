@@ -3,20 +3,140 @@
 use crate::hir::HirDatabase;
 use crate::types::TypeRegistry;
 use bgql_core::diagnostics::codes;
-use bgql_core::{DiagnosticBag, Interner, Text};
+use bgql_core::{Diagnostic, DiagnosticBag, Interner, Label, Span, Text};
 use bgql_syntax::{
-    Definition, Document, EnumTypeDefinition, EnumVariantData, FieldDefinition,
-    InputEnumTypeDefinition, InputObjectTypeDefinition, InputUnionTypeDefinition,
-    InputValueDefinition, InterfaceTypeDefinition, ObjectTypeDefinition, OpaqueTypeDefinition,
-    Type, TypeDefinition, UnionTypeDefinition,
+    merge_extensions, Argument, Definition, Directive, DirectiveDefinitionNode, DirectiveLocation,
+    Document, EnumTypeDefinition, EnumVariantData, ExtensionError, FieldDefinition, FieldSelection,
+    FragmentDefinition, InputEnumTypeDefinition, InputObjectTypeDefinition,
+    InputUnionTypeDefinition, InputValueDefinition, InterfaceTypeDefinition, ObjectTypeDefinition,
+    OpaqueTypeDefinition, OperationType, Selection, SelectionSet, Type, TypeDefinition,
+    TypeParameter, UnionTypeDefinition, Value,
 };
 use rustc_hash::{FxHashMap, FxHashSet};
+use std::sync::OnceLock;
+
+/// Directives every document can apply without defining them.
+const BUILTIN_DIRECTIVES: &str = r#"
+directive @deprecated(reason: Option<String>) on FIELD_DEFINITION | ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION | ENUM_VALUE
+directive @specifiedBy(url: String) on SCALAR
+directive @skip(if: Boolean) on FIELD | FRAGMENT_SPREAD | INLINE_FRAGMENT
+directive @include(if: Boolean) on FIELD | FRAGMENT_SPREAD | INLINE_FRAGMENT
+directive @defer(label: Option<String>, if: Boolean = true) on FRAGMENT_SPREAD | INLINE_FRAGMENT | FIELD_DEFINITION
+directive @stream(label: Option<String>, if: Boolean = true, initialCount: Int = 0) on FIELD | FIELD_DEFINITION
+
+directive @minLength(value: Int) on SCALAR | ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION
+directive @maxLength(value: Int) on SCALAR | ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION
+directive @minItems(value: Int) on ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION
+directive @maxItems(value: Int) on ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION
+directive @min(value: Float) on SCALAR | ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION
+directive @max(value: Float) on SCALAR | ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION
+directive @range(min: Option<Float>, max: Option<Float>) on SCALAR | ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION
+directive @positive on SCALAR | ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION
+directive @pattern(regex: String) on SCALAR | ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION
+directive @email on SCALAR | ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION
+directive @url on SCALAR | ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION
+directive @uuid on SCALAR | ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION
+directive @trim on SCALAR | ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION
+directive @lowercase on SCALAR | ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION
+directive @sanitize(allowTags: Option<List<String>>) on SCALAR | ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION
+directive @validate on INPUT_OBJECT
+
+directive @requireAuth(roles: Option<List<String>>) on OBJECT | FIELD_DEFINITION
+directive @hasRole(role: Role) on OBJECT | FIELD_DEFINITION
+directive @authScope(scopes: List<String>) on FIELD_DEFINITION
+directive @hasPermission(permission: String) on OBJECT | FIELD_DEFINITION
+directive @cacheControl(maxAge: Option<Int>, scope: Option<CacheControlScope>) on OBJECT | INTERFACE | UNION | FIELD_DEFINITION
+directive @rateLimit(requests: Int, window: String, key: Option<String>) on SCHEMA | FIELD_DEFINITION
+directive @cache(maxAge: Option<Int>, scope: Option<CacheControlScope>) on OBJECT | FIELD_DEFINITION
+directive @cookie(name: String, options: Option<CookieOptions>) on FIELD_DEFINITION
+directive @cors(origins: List<String>, credentials: Boolean = false, allowHeaders: Option<List<String>>, exposeHeaders: Option<List<String>>, maxAge: Option<Int>) on SCHEMA
+directive @complexity(value: Int) on FIELD_DEFINITION
+directive @paginate(max: Int, default: Option<Int>) on FIELD_DEFINITION
+directive @internal on OBJECT | FIELD_DEFINITION | INPUT_FIELD_DEFINITION
+directive @external on FIELD_DEFINITION
+directive @key(fields: String) repeatable on OBJECT | INTERFACE
+directive @source(name: String) on FIELD_DEFINITION
+
+directive @server(isolate: Boolean = true, cache: CacheStrategy = NONE, prerender: Boolean = false) on FRAGMENT_DEFINITION
+directive @boundary(server: Boolean = false, client: Boolean = false, serialize: SerializeStrategy = JSON) on OBJECT | FIELD_DEFINITION
+directive @island(name: String, hydrate: HydrationStrategy = VISIBLE, clientBundle: Option<String>) on FRAGMENT_DEFINITION
+directive @hydrate(strategy: HydrationStrategy = IDLE, priority: HydrationPriority = NORMAL) on FRAGMENT_SPREAD | INLINE_FRAGMENT
+directive @priority(level: Int = 5, deadline: Option<DateTime>, preemptible: Boolean = true) on QUERY | MUTATION | FIELD
+directive @resources(cpu: Option<Float>, memory: Option<Int>, io: ResourceLevel = LOW, network: ResourceLevel = LOW) on FIELD_DEFINITION
+directive @binary(progressive: Boolean = false, chunkSize: Option<Int>, hls: Boolean = false, segmentDuration: Option<Float>) on FIELD_DEFINITION
+directive @resumable(ttl: Int = 3600, checkpointInterval: Int = 50) on QUERY
+"#;
 
 /// Interface field info for implementation checking.
 #[derive(Clone)]
 struct InterfaceFieldInfo {
     name: String,
     type_repr: String,
+    /// Arguments declared by the interface field
+    arguments: Vec<InterfaceArgInfo>,
+    /// Span of the interface field declaration
+    span: bgql_core::Span,
+}
+
+/// Where a type reference appears, for checking input-only types.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TypePosition {
+    /// The type of a field
+    Output,
+    /// The type of an argument or input field
+    Input,
+    /// Aliases and enum data, which can be used in either position
+    Any,
+}
+
+/// Owned shape of an input type, for checking literal values against it.
+#[derive(Clone)]
+enum InputShape {
+    Named(String),
+    Option(Box<InputShape>),
+    List(Box<InputShape>),
+    /// Generic and tuple types, whose values aren't checked
+    Other,
+}
+
+impl std::fmt::Display for InputShape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Named(name) => write!(f, "{name}"),
+            Self::Option(inner) => write!(f, "Option<{inner}>"),
+            Self::List(inner) => write!(f, "List<{inner}>"),
+            Self::Other => write!(f, "_"),
+        }
+    }
+}
+
+/// Input object field info for checking object literals.
+#[derive(Clone)]
+struct InputFieldShape {
+    name: String,
+    shape: InputShape,
+    has_default: bool,
+}
+
+/// Directive definition info for checking applied directives.
+#[derive(Clone)]
+struct DirectiveInfo {
+    locations: Vec<DirectiveLocation>,
+    arguments: Vec<InputFieldShape>,
+}
+
+/// Interface field argument info for implementation checking.
+#[derive(Clone)]
+struct InterfaceArgInfo {
+    name: String,
+    type_repr: String,
+}
+
+/// A field selected in a selection set, for field merging checks.
+struct SelectedField<'d, 'a> {
+    field: &'d FieldSelection<'a>,
+    /// Type condition of the enclosing fragment, if any
+    parent_type: Option<String>,
 }
 
 /// Generic type parameter info.
@@ -35,6 +155,55 @@ struct GenericTypeInfo {
     params: Vec<GenericTypeParam>,
 }
 
+/// Returns the [built-in directives](BUILTIN_DIRECTIVES), parsed once.
+fn builtin_directives() -> &'static FxHashMap<String, DirectiveInfo> {
+    static BUILTINS: OnceLock<FxHashMap<String, DirectiveInfo>> = OnceLock::new();
+    BUILTINS.get_or_init(|| {
+        let interner = Interner::new();
+        let builtins = bgql_syntax::parse(BUILTIN_DIRECTIVES, &interner);
+        builtins
+            .document
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Directive(directive) => Some(directive_info(directive, &interner)),
+                _ => None,
+            })
+            .collect()
+    })
+}
+
+/// Returns the name and info of a directive definition.
+fn directive_info(
+    directive: &DirectiveDefinitionNode<'_>,
+    interner: &Interner,
+) -> (String, DirectiveInfo) {
+    let arguments = directive
+        .arguments
+        .iter()
+        .map(|arg| InputFieldShape {
+            name: interner.get(arg.name.value),
+            shape: input_shape(&arg.ty, interner),
+            has_default: arg.default_value.is_some(),
+        })
+        .collect();
+    let info = DirectiveInfo {
+        locations: directive.locations.clone(),
+        arguments,
+    };
+    (interner.get(directive.name.value), info)
+}
+
+/// Returns the owned shape of an input type.
+fn input_shape(ty: &Type<'_>, interner: &Interner) -> InputShape {
+    match ty {
+        Type::Named(named) => InputShape::Named(interner.get(named.name)),
+        Type::Option(inner, _) => InputShape::Option(Box::new(input_shape(inner, interner))),
+        Type::List(inner, _) => InputShape::List(Box::new(input_shape(inner, interner))),
+        Type::Generic(_) | Type::Tuple(_) | Type::_Phantom(_) => InputShape::Other,
+    }
+}
+
 /// Type checker for Better GraphQL.
 pub struct TypeChecker<'a> {
     #[allow(dead_code)]
@@ -45,10 +214,21 @@ pub struct TypeChecker<'a> {
     diagnostics: DiagnosticBag,
     /// Set of all defined type names
     defined_types: FxHashSet<String>,
+    /// Set of object type names
+    object_types: FxHashSet<String>,
     /// Set of interface names
     interfaces: FxHashSet<String>,
     /// Set of input type names (for checking input unions)
     input_types: FxHashSet<String>,
+    /// Input unions and input enums with their keyword, which can't be
+    /// field types
+    input_only_types: FxHashMap<String, &'static str>,
+    /// Fields of input object types, for checking default values
+    input_object_fields: FxHashMap<String, Vec<InputFieldShape>>,
+    /// Values of each enum type, for checking default values
+    enum_values: FxHashMap<String, FxHashSet<String>>,
+    /// Directive definitions, built-in and from the document
+    directives: FxHashMap<String, DirectiveInfo>,
     /// Interface fields for implementation checking
     interface_fields: FxHashMap<String, Vec<InterfaceFieldInfo>>,
     /// Generic type definitions with their type parameters
@@ -57,19 +237,58 @@ pub struct TypeChecker<'a> {
     type_implements: FxHashMap<String, FxHashSet<String>>,
     /// Type parameters currently in scope (for checking generic type bodies)
     type_params_in_scope: FxHashSet<String>,
-    /// Type dependency graph for cycle detection
-    type_dependencies: FxHashMap<String, FxHashSet<String>>,
-    /// Set of deprecated types for warning when used
-    deprecated_types: FxHashSet<String>,
+    /// Type parameters in scope that have been referenced
+    used_type_params: FxHashSet<String>,
+    /// Required field edges (field name, field type) for cycle detection
+    type_dependencies: FxHashMap<String, Vec<(String, String)>>,
+    /// Types each alias or opaque type expands to, for alias cycle detection
+    alias_dependencies: FxHashMap<String, Vec<String>>,
+    /// Types each alias or opaque type requires, followed by cycle detection
+    alias_required: FxHashMap<String, Vec<String>>,
+    /// Aliases already reported as part of a cycle
+    reported_alias_cycles: FxHashSet<String>,
     /// Type locations for better error messages
     type_locations: FxHashMap<String, bgql_core::Span>,
     /// Enable strict mode (treat some warnings as errors)
     strict_mode: bool,
+    /// Require names to be valid GraphQL names (ASCII only)
+    graphql_compat: bool,
+    /// Diagnostics of each type definition, checked or reused
+    type_diagnostics: FxHashMap<String, TypeDiagnostics>,
+    /// Types whose diagnostics an incremental check reuses
+    reused_types: FxHashSet<String>,
+    /// Types checked, in order
+    checked_types: Vec<String>,
+}
+
+/// A type definition, extension or directive definition and the types and
+/// directives it references. Directives are named `@name`.
+struct TypeReferences {
+    name: String,
+    span: Span,
+    extension: bool,
+    references: Vec<String>,
+}
+
+/// Diagnostics a type definition produced, kept for incremental checks.
+#[derive(Debug, Clone)]
+struct TypeDiagnostics {
+    /// Span of the definition.
+    span: Span,
+    /// Diagnostics of the definition itself.
+    definition: Vec<Diagnostic>,
+    /// Diagnostics of the directives applied to it.
+    directives: Vec<Diagnostic>,
 }
 
 /// Result of type checking.
 pub struct CheckResult {
     pub diagnostics: DiagnosticBag,
+    /// Names of the type definitions checked, in order. An incremental check
+    /// leaves out the ones whose diagnostics it reused.
+    pub checked_types: Vec<String>,
+    /// Diagnostics of each type definition, for incremental checks.
+    type_diagnostics: FxHashMap<String, TypeDiagnostics>,
 }
 
 impl CheckResult {
@@ -88,16 +307,28 @@ impl<'a> TypeChecker<'a> {
             interner,
             diagnostics: DiagnosticBag::new(),
             defined_types: FxHashSet::default(),
+            object_types: FxHashSet::default(),
             interfaces: FxHashSet::default(),
             input_types: FxHashSet::default(),
+            input_only_types: FxHashMap::default(),
+            input_object_fields: FxHashMap::default(),
+            enum_values: FxHashMap::default(),
+            directives: FxHashMap::default(),
             interface_fields: FxHashMap::default(),
             generic_types: FxHashMap::default(),
             type_implements: FxHashMap::default(),
             type_params_in_scope: FxHashSet::default(),
+            used_type_params: FxHashSet::default(),
             type_dependencies: FxHashMap::default(),
-            deprecated_types: FxHashSet::default(),
+            alias_dependencies: FxHashMap::default(),
+            alias_required: FxHashMap::default(),
+            reported_alias_cycles: FxHashSet::default(),
             type_locations: FxHashMap::default(),
             strict_mode: false,
+            graphql_compat: false,
+            type_diagnostics: FxHashMap::default(),
+            reused_types: FxHashSet::default(),
+            checked_types: Vec::new(),
         }
     }
 
@@ -112,15 +343,57 @@ impl<'a> TypeChecker<'a> {
         checker
     }
 
+    /// Creates a new type checker that rejects names GraphQL tooling can't represent.
+    pub fn new_graphql_compat(
+        types: &'a TypeRegistry,
+        hir: &'a HirDatabase,
+        interner: &'a Interner,
+    ) -> Self {
+        let mut checker = Self::new(types, hir, interner);
+        checker.graphql_compat = true;
+        checker
+    }
+
     /// Resolves a Text to a String.
     fn resolve(&self, text: Text) -> String {
         self.interner.get(text)
     }
 
+    /// Returns the defined type, or interface, closest to `name`.
+    fn similar_type(&self, name: &str, interface: bool) -> Option<&str> {
+        let candidates = if interface {
+            &self.interfaces
+        } else {
+            &self.defined_types
+        };
+        suggest_name(name, candidates)
+    }
+
+    /// Reports an undefined type or interface `name` referenced at the
+    /// start of `span`, suggesting the closest defined one.
+    fn undefined_type(
+        &mut self,
+        name: &str,
+        title: String,
+        span: Span,
+        label: String,
+        interface: bool,
+    ) {
+        let mut diagnostic = Diagnostic::error(codes::UNDEFINED_TYPE, title).with_span(span, label);
+        if let Some(suggestion) = self.similar_type(name, interface) {
+            // Type spans run up to the next token, so the name is cut out
+            let reference = Span::new(span.start, span.start + name.len() as u32);
+            diagnostic = diagnostic
+                .with_note(format!("did you mean `{suggestion}`?"))
+                .with_suggestion(reference, suggestion);
+        }
+        self.diagnostics.add(diagnostic);
+    }
+
     /// Converts a Type to a string representation for comparison.
     fn type_to_string(&self, ty: &Type<'_>) -> String {
         match ty {
-            Type::Named(named) => self.interner.get(named.name),
+            Type::Named(named) => self.interner.resolve(named.name).to_string(),
             Type::Option(inner, _) => format!("Option<{}>", self.type_to_string(inner)),
             Type::List(inner, _) => format!("List<{}>", self.type_to_string(inner)),
             Type::Generic(generic) => {
@@ -129,7 +402,11 @@ impl<'a> TypeChecker<'a> {
                     .iter()
                     .map(|arg| self.type_to_string(arg))
                     .collect();
-                format!("{}<{}>", self.interner.get(generic.name), args.join(", "))
+                format!(
+                    "{}<{}>",
+                    self.interner.resolve(generic.name),
+                    args.join(", ")
+                )
             }
             Type::Tuple(tuple) => {
                 let elements: Vec<String> = tuple
@@ -182,70 +459,385 @@ impl<'a> TypeChecker<'a> {
 
     /// Checks a document.
     pub fn check(&mut self, document: &Document<'_>) -> CheckResult {
-        // Phase 1: Collect all type definitions
+        // Phase 0: Merge `extend` definitions into the definitions they extend
+        let document = &Document {
+            definitions: self.merge_extensions(&document.definitions),
+            span: document.span,
+        };
+
+        // Phase 1: Collect all type and directive definitions
+        self.collect_builtin_directives();
         self.collect_type_definitions(document);
 
         // Phase 2: Build type dependency graph
         self.build_dependency_graph(document);
 
-        // Phase 3: Check for cyclic type references
+        // Phase 3: Check for cycles of required fields
         self.check_cycles();
 
         // Phase 4: Check all type references and semantic rules
         self.check_definitions(document);
+        self.check_schema_definition(document);
+        self.check_operations(document);
+        self.check_directive_applications(document);
 
-        // Phase 5: Naming convention warnings (if not strict mode)
+        // Phase 5: Naming convention and unused type warnings
         self.check_naming_conventions(document);
+        self.check_unused_types(document);
+
+        // Phase 6: GraphQL name validity
+        self.check_graphql_names(document);
 
         CheckResult {
             diagnostics: std::mem::take(&mut self.diagnostics),
+            checked_types: std::mem::take(&mut self.checked_types),
+            type_diagnostics: std::mem::take(&mut self.type_diagnostics),
         }
     }
 
-    /// Builds the type dependency graph.
-    fn build_dependency_graph(&mut self, document: &Document<'_>) {
-        for definition in &document.definitions {
-            if let Definition::Type(type_def) = definition {
-                let type_name = match type_def {
-                    TypeDefinition::Object(obj) => self.resolve(obj.name.value),
-                    TypeDefinition::Interface(iface) => self.resolve(iface.name.value),
-                    TypeDefinition::Input(input) => self.resolve(input.name.value),
-                    TypeDefinition::InputEnum(ie) => self.resolve(ie.name.value),
-                    // Other types (enum, union, scalar, etc.) don't create problematic cycles
-                    _ => continue,
-                };
+    /// Checks a new version of the document `previous` was the result for.
+    ///
+    /// Only the type definitions named in `changed` (including removed and
+    /// added ones), and the types that reference them directly or
+    /// indirectly, are checked again; the others reuse their diagnostics from
+    /// `previous`, moved to where the definitions are now. Everything else in
+    /// the document is checked as usual.
+    ///
+    /// Directive definitions are named `@name` in `changed`. A type applying
+    /// a directive references it, and a directive references the types of
+    /// its arguments, so changing an enum rechecks the types passing its
+    /// values to directives.
+    pub fn check_incremental(
+        &mut self,
+        document: &Document<'_>,
+        previous: &CheckResult,
+        changed: &[&str],
+    ) -> CheckResult {
+        let mut all = Vec::new();
+        self.collect_type_references(&document.definitions, &mut all);
+        let definitions: Vec<_> = all.iter().filter(|def| !def.extension).collect();
 
-                let mut deps = FxHashSet::default();
+        let mut affected: FxHashSet<String> = changed.iter().map(|name| name.to_string()).collect();
+        // Definitions sharing a name can't be told apart in `previous`
+        let mut seen = FxHashSet::default();
+        for def in &definitions {
+            if !seen.insert(&def.name) {
+                affected.insert(def.name.clone());
+            }
+        }
+        // Types referencing an affected type are affected as well
+        loop {
+            let count = affected.len();
+            for def in &all {
+                if !affected.contains(&def.name)
+                    && def.references.iter().any(|r| affected.contains(r))
+                {
+                    affected.insert(def.name.clone());
+                }
+            }
+            if affected.len() == count {
+                break;
+            }
+        }
 
-                match type_def {
-                    TypeDefinition::Object(obj) => {
-                        for field in &obj.fields {
-                            self.collect_type_deps(&field.ty, &mut deps);
-                        }
+        // How far each definition moved, for relocating reused diagnostics
+        let moves: Vec<(Span, i64)> = definitions
+            .iter()
+            .filter_map(|def| {
+                let old = previous.type_diagnostics.get(&def.name)?.span;
+                Some((old, i64::from(def.span.start) - i64::from(old.start)))
+            })
+            .collect();
+        for def in &definitions {
+            if affected.contains(&def.name) {
+                continue;
+            }
+            let Some(cached) = previous.type_diagnostics.get(&def.name) else {
+                continue;
+            };
+            let mut cached = cached.clone();
+            cached.span = def.span;
+            for diagnostic in cached.definition.iter_mut().chain(&mut cached.directives) {
+                for label in &mut diagnostic.labels {
+                    label.span = relocate(label.span, &moves);
+                }
+            }
+            self.type_diagnostics.insert(def.name.clone(), cached);
+            self.reused_types.insert(def.name.clone());
+        }
+
+        self.check(document)
+    }
+
+    /// Collects the types and directives referenced by every type
+    /// definition, extension and directive definition, including those in
+    /// module bodies.
+    fn collect_type_references(
+        &self,
+        definitions: &[Definition<'_>],
+        out: &mut Vec<TypeReferences>,
+    ) {
+        for definition in definitions {
+            let (type_def, extension) = match definition {
+                Definition::Type(type_def) => (type_def, false),
+                Definition::TypeExtension(type_def) => (type_def, true),
+                Definition::Directive(directive) => {
+                    let mut references = Vec::new();
+                    for argument in &directive.arguments {
+                        self.collect_alias_deps(&argument.ty, &mut references);
+                        self.collect_applied_directives(&argument.directives, &mut references);
                     }
-                    TypeDefinition::Interface(iface) => {
-                        for field in &iface.fields {
-                            self.collect_type_deps(&field.ty, &mut deps);
+                    out.push(TypeReferences {
+                        name: format!("@{}", self.resolve(directive.name.value)),
+                        span: directive.span,
+                        extension: false,
+                        references,
+                    });
+                    continue;
+                }
+                Definition::Module(module) => {
+                    if let Some(body) = &module.body {
+                        self.collect_type_references(body, out);
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+
+            let mut references = Vec::new();
+            let fields = |fields: &[FieldDefinition<'_>], references: &mut Vec<String>| {
+                for field in fields {
+                    self.collect_alias_deps(&field.ty, references);
+                    self.collect_applied_directives(&field.directives, references);
+                    for arg in &field.arguments {
+                        self.collect_alias_deps(&arg.ty, references);
+                        self.collect_applied_directives(&arg.directives, references);
+                    }
+                }
+            };
+            let input_fields = |fields: &[InputValueDefinition<'_>],
+                                references: &mut Vec<String>| {
+                for field in fields {
+                    self.collect_alias_deps(&field.ty, references);
+                    self.collect_applied_directives(&field.directives, references);
+                }
+            };
+            let params = |params: &[TypeParameter<'_>], references: &mut Vec<String>| {
+                for constraint in params.iter().filter_map(|p| p.constraint.as_ref()) {
+                    self.collect_alias_deps(constraint, references);
+                }
+            };
+            match type_def {
+                TypeDefinition::Object(obj) => {
+                    references.extend(obj.implements.iter().map(|name| self.resolve(name.value)));
+                    fields(&obj.fields, &mut references);
+                    params(&obj.type_params, &mut references);
+                }
+                TypeDefinition::Interface(iface) => {
+                    references.extend(iface.implements.iter().map(|name| self.resolve(name.value)));
+                    fields(&iface.fields, &mut references);
+                    params(&iface.type_params, &mut references);
+                }
+                TypeDefinition::Union(union_def) => {
+                    references.extend(
+                        union_def
+                            .members
+                            .iter()
+                            .map(|name| self.resolve(name.value)),
+                    );
+                }
+                TypeDefinition::InputUnion(input_union) => {
+                    references.extend(
+                        input_union
+                            .members
+                            .iter()
+                            .map(|name| self.resolve(name.value)),
+                    );
+                }
+                TypeDefinition::Input(input) => {
+                    input_fields(&input.fields, &mut references);
+                }
+                TypeDefinition::InputEnum(input_enum) => {
+                    for variant in &input_enum.variants {
+                        self.collect_applied_directives(&variant.directives, &mut references);
+                        if let Some(fields) = &variant.fields {
+                            input_fields(fields, &mut references);
                         }
                     }
-                    TypeDefinition::Input(input) => {
-                        for field in &input.fields {
-                            self.collect_type_deps(&field.ty, &mut deps);
+                }
+                TypeDefinition::Enum(enum_def) => {
+                    for value in &enum_def.values {
+                        self.collect_applied_directives(&value.directives, &mut references);
+                        if let Some(EnumVariantData::Struct(fields, _)) = &value.data {
+                            input_fields(fields, &mut references);
                         }
                     }
-                    TypeDefinition::InputEnum(ie) => {
-                        for variant in &ie.variants {
-                            if let Some(fields) = &variant.fields {
-                                for field in fields {
-                                    self.collect_type_deps(&field.ty, &mut deps);
-                                }
-                            }
+                }
+                TypeDefinition::Opaque(opaque) => {
+                    self.collect_alias_deps(&opaque.underlying, &mut references);
+                }
+                TypeDefinition::TypeAlias(alias) => {
+                    self.collect_alias_deps(&alias.aliased, &mut references);
+                }
+                TypeDefinition::Scalar(_) => {}
+            }
+            self.collect_applied_directives(type_def.directives(), &mut references);
+            out.push(TypeReferences {
+                name: self.resolve(type_def.name().value),
+                span: type_def.span(),
+                extension,
+                references,
+            });
+        }
+    }
+
+    /// Adds the directives in `directives` to `out` as `@name`.
+    fn collect_applied_directives(&self, directives: &[Directive<'_>], out: &mut Vec<String>) {
+        out.extend(
+            directives
+                .iter()
+                .map(|directive| format!("@{}", self.resolve(directive.name.value))),
+        );
+    }
+
+    /// Runs `check` for a type definition and records the diagnostics it
+    /// adds in `part` of the definition's diagnostics, or adds the recorded
+    /// ones again if an incremental check reuses them.
+    fn check_cached(
+        &mut self,
+        type_def: &TypeDefinition<'_>,
+        part: fn(&mut TypeDiagnostics) -> &mut Vec<Diagnostic>,
+        check: impl FnOnce(&mut Self),
+    ) {
+        let name = self.resolve(type_def.name().value);
+        if self.reused_types.contains(&name) {
+            if let Some(cached) = self.type_diagnostics.get_mut(&name) {
+                for diagnostic in part(cached).clone() {
+                    self.diagnostics.add(diagnostic);
+                }
+            }
+            return;
+        }
+
+        let before = self.diagnostics.len();
+        check(self);
+        let added: Vec<_> = self.diagnostics.iter().skip(before).cloned().collect();
+        let cached = self
+            .type_diagnostics
+            .entry(name)
+            .or_insert_with_key(|name| {
+                self.checked_types.push(name.clone());
+                TypeDiagnostics {
+                    span: type_def.span(),
+                    definition: Vec::new(),
+                    directives: Vec::new(),
+                }
+            });
+        part(cached).extend(added);
+    }
+
+    /// Merges `extend` definitions into the definitions they extend,
+    /// reporting the extensions without a target.
+    fn merge_extensions<'d>(&mut self, definitions: &[Definition<'d>]) -> Vec<Definition<'d>> {
+        let (merged, errors) = merge_extensions(definitions);
+        for error in errors {
+            match error {
+                ExtensionError::UndefinedSchema { span } => self.diagnostics.error(
+                    codes::INVALID_EXTENSION_TARGET,
+                    "Cannot extend an undefined schema",
+                    span,
+                    "there is no `schema` definition to extend",
+                ),
+                ExtensionError::UndefinedType { name } => {
+                    let type_name = self.resolve(name.value);
+                    self.diagnostics.error(
+                        codes::INVALID_EXTENSION_TARGET,
+                        format!("Cannot extend undefined type `{type_name}`"),
+                        name.span,
+                        format!("no type named `{type_name}` is defined"),
+                    );
+                }
+                ExtensionError::KindMismatch {
+                    name,
+                    extension,
+                    definition,
+                } => {
+                    let type_name = self.resolve(name.value);
+                    self.diagnostics.error(
+                        codes::INVALID_EXTENSION_TARGET,
+                        format!("`extend {extension}` cannot extend `{type_name}`"),
+                        name.span,
+                        format!("`{type_name}` is defined with `{definition}`"),
+                    );
+                }
+            }
+        }
+        merged
+    }
+
+    /// Builds the type dependency graph.
+    fn build_dependency_graph(&mut self, document: &Document<'_>) {
+        for definition in &document.definitions {
+            if let Definition::Type(type_def) = definition {
+                match type_def {
+                    TypeDefinition::TypeAlias(alias) => {
+                        // Aliases are expanded in place, so every referenced type counts
+                        let mut deps = Vec::new();
+                        self.collect_alias_deps(&alias.aliased, &mut deps);
+                        let name = self.resolve(alias.name.value);
+                        self.alias_dependencies.insert(name.clone(), deps);
+                        let mut required = FxHashSet::default();
+                        self.collect_type_deps(&alias.aliased, &mut required);
+                        self.alias_required
+                            .insert(name, required.into_iter().collect());
+                    }
+                    TypeDefinition::Opaque(opaque) => {
+                        // An opaque type only expands to the type it directly wraps
+                        let name = self.resolve(opaque.name.value);
+                        if let Type::Named(named) = &opaque.underlying {
+                            self.alias_dependencies
+                                .insert(name.clone(), vec![self.resolve(named.name)]);
                         }
+                        let mut required = FxHashSet::default();
+                        self.collect_type_deps(&opaque.underlying, &mut required);
+                        self.alias_required
+                            .insert(name, required.into_iter().collect());
                     }
                     _ => {}
                 }
 
-                self.type_dependencies.insert(type_name, deps);
+                // Other types (enum, union, scalar, etc.) don't create problematic cycles.
+                // Input enum variants are alternatives, so no single variant is required.
+                let (type_name, fields) = match type_def {
+                    TypeDefinition::Object(obj) => (
+                        self.resolve(obj.name.value),
+                        obj.fields
+                            .iter()
+                            .map(|f| (f.name, &f.ty))
+                            .collect::<Vec<_>>(),
+                    ),
+                    TypeDefinition::Interface(iface) => (
+                        self.resolve(iface.name.value),
+                        iface.fields.iter().map(|f| (f.name, &f.ty)).collect(),
+                    ),
+                    TypeDefinition::Input(input) => (
+                        self.resolve(input.name.value),
+                        input.fields.iter().map(|f| (f.name, &f.ty)).collect(),
+                    ),
+                    _ => continue,
+                };
+
+                let mut edges = Vec::new();
+                for (field_name, ty) in fields {
+                    let mut deps = FxHashSet::default();
+                    self.collect_type_deps(ty, &mut deps);
+                    let mut deps: Vec<_> = deps.into_iter().collect();
+                    deps.sort();
+                    let field_name = self.resolve(field_name.value);
+                    edges.extend(deps.into_iter().map(|dep| (field_name.clone(), dep)));
+                }
+
+                self.type_dependencies.insert(type_name, edges);
             }
         }
     }
@@ -288,61 +880,220 @@ impl<'a> TypeChecker<'a> {
         }
     }
 
-    /// Checks for cyclic type references (non-nullable cycles are errors).
+    /// Collects every type name referenced by an alias's aliased type.
+    fn collect_alias_deps(&self, ty: &Type<'_>, deps: &mut Vec<String>) {
+        match ty {
+            Type::Named(named) => deps.push(self.resolve(named.name)),
+            Type::Option(inner, _) | Type::List(inner, _) => self.collect_alias_deps(inner, deps),
+            Type::Generic(generic) => {
+                deps.push(self.resolve(generic.name));
+                for arg in &generic.arguments {
+                    self.collect_alias_deps(arg, deps);
+                }
+            }
+            Type::Tuple(tuple) => {
+                for elem in &tuple.elements {
+                    self.collect_alias_deps(&elem.ty, deps);
+                }
+            }
+            Type::_Phantom(_) => {}
+        }
+    }
+
+    /// Reports a cycle of aliases and opaque types that leads back to `name`.
+    fn check_alias_cycle(&mut self, name: bgql_syntax::Name) {
+        let type_name = self.resolve(name.value);
+        if self.reported_alias_cycles.contains(&type_name) {
+            return;
+        }
+
+        let mut visited = FxHashSet::default();
+        let mut path = vec![type_name.clone()];
+        if !self.find_alias_cycle(&type_name, &type_name, &mut visited, &mut path) {
+            return;
+        }
+
+        let cycle = path.join(" -> ");
+        self.reported_alias_cycles.extend(path);
+        self.diagnostics.error(
+            codes::CYCLIC_TYPE_ALIAS,
+            format!("Cyclic type alias detected: {cycle}"),
+            name.span,
+            "Type aliases and opaque types cannot expand to themselves",
+        );
+    }
+
+    /// Searches the alias graph for a path from `current` back to `start`.
+    ///
+    /// On success `path` holds the cycle, ending with `start`.
+    fn find_alias_cycle(
+        &self,
+        start: &str,
+        current: &str,
+        visited: &mut FxHashSet<String>,
+        path: &mut Vec<String>,
+    ) -> bool {
+        let Some(deps) = self.alias_dependencies.get(current) else {
+            return false;
+        };
+
+        for dep in deps {
+            path.push(dep.clone());
+            if dep == start {
+                return true;
+            }
+            if visited.insert(dep.clone()) && self.find_alias_cycle(start, dep, visited, path) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+
+    /// Reports cycles of required fields, which no finite value can satisfy:
+    /// as errors in input objects and as warnings in output types.
     fn check_cycles(&mut self) {
-        // Simple DFS-based cycle detection
-        let types: Vec<_> = self.type_dependencies.keys().cloned().collect();
+        let mut types: Vec<_> = self.type_dependencies.keys().cloned().collect();
+        types.sort_by_key(|name| self.type_locations.get(name).map(|span| span.start));
 
+        let mut reported = FxHashSet::default();
         for start_type in &types {
+            if reported.contains(start_type) {
+                continue;
+            }
+
             let mut visited = FxHashSet::default();
             let mut path = Vec::new();
+            if !self.find_required_cycle(start_type, start_type, &mut visited, &mut path) {
+                continue;
+            }
 
-            if self.has_non_nullable_cycle(start_type, &mut visited, &mut path) {
-                // Only report if we haven't already
-                let cycle_str = path.join(" -> ");
-                if let Some(span) = self.type_locations.get(start_type).copied() {
-                    self.diagnostics.error(
-                        codes::CYCLIC_TYPE_REFERENCE,
-                        format!("Cyclic type reference detected: {}", cycle_str),
-                        span,
-                        "Types cannot form non-nullable cycles (use Option<T> to break the cycle)",
-                    );
-                }
+            let mut cycle: Vec<_> = path
+                .iter()
+                .map(|(type_name, field)| format!("{type_name}.{field}"))
+                .collect();
+            cycle.push(start_type.clone());
+            reported.extend(path.into_iter().map(|(type_name, _)| type_name));
+
+            let Some(span) = self.type_locations.get(start_type).copied() else {
+                continue;
+            };
+            let title = format!("Required field cycle detected: {}", cycle.join(" -> "));
+            let label =
+                "No finite value can satisfy these fields; make one of them `Option<T>` or a `List<T>`";
+            // A client can never write such an input, while an output type
+            // can still be returned lazily
+            if self.input_types.contains(start_type) {
+                self.diagnostics
+                    .error(codes::CYCLIC_TYPE_REFERENCE, title, span, label);
+            } else {
+                self.diagnostics
+                    .warning(codes::CYCLIC_TYPE_REFERENCE, title, span, label);
             }
         }
     }
 
-    /// Checks if there's a non-nullable cycle starting from a type.
-    fn has_non_nullable_cycle(
+    /// Searches the required field graph for a path from `current` back to `start`.
+    ///
+    /// On success `path` holds the `(type, field)` edges of the cycle.
+    fn find_required_cycle(
         &self,
+        start: &str,
         current: &str,
         visited: &mut FxHashSet<String>,
-        path: &mut Vec<String>,
+        path: &mut Vec<(String, String)>,
     ) -> bool {
-        if path.contains(&current.to_string()) {
-            path.push(current.to_string());
-            return true;
-        }
-
-        if visited.contains(current) {
+        let Some(edges) = self.type_dependencies.get(current) else {
             return false;
-        }
-
-        visited.insert(current.to_string());
-        path.push(current.to_string());
+        };
 
-        if let Some(deps) = self.type_dependencies.get(current) {
-            for dep in deps {
-                if self.has_non_nullable_cycle(dep, visited, path) {
+        for (field, dep) in edges {
+            path.push((current.to_string(), field.clone()));
+            for dep in self.expand_required_aliases(dep) {
+                if dep == start {
+                    return true;
+                }
+                if visited.insert(dep.clone())
+                    && self.find_required_cycle(start, &dep, visited, path)
+                {
                     return true;
                 }
             }
+            path.pop();
         }
-
-        path.pop();
         false
     }
 
+    /// Returns the types a required reference to `name` requires: `name`
+    /// itself, or what it expands to when it's an alias or opaque type.
+    fn expand_required_aliases(&self, name: &str) -> Vec<String> {
+        let mut expanded = Vec::new();
+        let mut pending = vec![name.to_string()];
+        let mut seen = FxHashSet::default();
+        while let Some(name) = pending.pop() {
+            if !seen.insert(name.clone()) {
+                continue;
+            }
+            match self.alias_required.get(&name) {
+                Some(required) => pending.extend(required.iter().cloned()),
+                None => expanded.push(name),
+            }
+        }
+        expanded.sort();
+        expanded
+    }
+
+    /// Warns about type definitions nothing refers to.
+    ///
+    /// A type is used when it's a root operation type, is referenced by
+    /// another type or a directive definition, or implements an interface,
+    /// as it can then be returned in place of that interface.
+    fn check_unused_types(&mut self, document: &Document<'_>) {
+        let mut definitions = Vec::new();
+        self.collect_type_references(&document.definitions, &mut definitions);
+
+        let mut used: FxHashSet<String> = FxHashSet::default();
+        let roots: Vec<String> = document
+            .definitions
+            .iter()
+            .filter_map(|def| match def {
+                Definition::Schema(schema) => Some(schema),
+                _ => None,
+            })
+            .flat_map(|schema| &schema.operations)
+            .map(|op| self.resolve(op.type_name))
+            .collect();
+        if roots.is_empty() {
+            used.extend(["Query", "Mutation", "Subscription"].map(String::from));
+        }
+        used.extend(roots);
+        used.extend(self.type_implements.keys().cloned());
+        for def in &definitions {
+            used.extend(def.references.iter().filter(|r| **r != def.name).cloned());
+        }
+
+        let mut reported = FxHashSet::default();
+        let types = definitions
+            .iter()
+            .filter(|def| !def.extension && !def.name.starts_with('@'));
+        for def in types {
+            if used.contains(&def.name) || !reported.insert(def.name.clone()) {
+                continue;
+            }
+            let span = self
+                .type_locations
+                .get(&def.name)
+                .copied()
+                .unwrap_or(def.span);
+            self.diagnostics.warning(
+                codes::UNUSED_TYPE,
+                format!("Type `{}` is never used", def.name),
+                span,
+                "No field, argument, union or directive refers to this type",
+            );
+        }
+    }
+
     /// Checks naming conventions and emits warnings.
     fn check_naming_conventions(&mut self, document: &Document<'_>) {
         for definition in &document.definitions {
@@ -442,25 +1193,103 @@ impl<'a> TypeChecker<'a> {
         }
     }
 
-    /// Collects all type definitions (first pass).
-    fn collect_type_definitions(&mut self, document: &Document<'_>) {
-        // Register built-in scalars
-        for name in ["Int", "Float", "String", "Boolean", "ID"] {
-            self.defined_types.insert(name.to_string());
-        }
-
-        // Register built-in generic types
-        for name in ["Option", "List"] {
-            self.defined_types.insert(name.to_string());
-        }
-
+    /// Checks that type, field, and argument names are valid GraphQL names.
+    fn check_graphql_names(&mut self, document: &Document<'_>) {
         for definition in &document.definitions {
-            match definition {
-                Definition::Type(type_def) => {
-                    let (name, is_interface, is_input) = match type_def {
-                        TypeDefinition::Object(obj) => (self.resolve(obj.name.value), false, false),
-                        TypeDefinition::Interface(iface) => {
-                            (self.resolve(iface.name.value), true, false)
+            if let Definition::Type(type_def) = definition {
+                let name = match type_def {
+                    TypeDefinition::Object(obj) => obj.name,
+                    TypeDefinition::Interface(iface) => iface.name,
+                    TypeDefinition::Enum(e) => e.name,
+                    TypeDefinition::Union(u) => u.name,
+                    TypeDefinition::Input(i) => i.name,
+                    TypeDefinition::Scalar(s) => s.name,
+                    TypeDefinition::Opaque(o) => o.name,
+                    TypeDefinition::InputEnum(ie) => ie.name,
+                    TypeDefinition::InputUnion(iu) => iu.name,
+                    TypeDefinition::TypeAlias(ta) => ta.name,
+                };
+                self.check_graphql_name(name, "Type");
+
+                let fields = match type_def {
+                    TypeDefinition::Object(obj) => &obj.fields[..],
+                    TypeDefinition::Interface(iface) => &iface.fields[..],
+                    TypeDefinition::Input(input) => {
+                        for field in &input.fields {
+                            self.check_graphql_name(field.name, "Field");
+                        }
+                        continue;
+                    }
+                    _ => continue,
+                };
+                for field in fields {
+                    self.check_graphql_name(field.name, "Field");
+                    for arg in &field.arguments {
+                        self.check_graphql_name(arg.name, "Argument");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reports a name that doesn't match `/[_A-Za-z][_0-9A-Za-z]*/`.
+    ///
+    /// A leading digit is always an error; other non-GraphQL characters (e.g.
+    /// Unicode letters) are only rejected in GraphQL-compat mode.
+    fn check_graphql_name(&mut self, name: bgql_syntax::Name, kind: &str) {
+        let text = self.resolve(name.value);
+        if text.starts_with(|c: char| c.is_ascii_digit()) {
+            self.diagnostics.error(
+                codes::INVALID_NAME,
+                format!("{} name `{}` starts with a digit", kind, text),
+                name.span,
+                "GraphQL names must start with a letter or underscore",
+            );
+        } else if self.graphql_compat && !is_graphql_name(&text) {
+            self.diagnostics.error(
+                codes::INVALID_NAME,
+                format!("{} name `{}` is not a valid GraphQL name", kind, text),
+                name.span,
+                "GraphQL names may only contain ASCII letters, digits, and underscores",
+            );
+        }
+    }
+
+    /// Registers the [built-in directives](BUILTIN_DIRECTIVES). Documents may
+    /// redefine them.
+    fn collect_builtin_directives(&mut self) {
+        self.directives.extend(
+            builtin_directives()
+                .iter()
+                .map(|(name, info)| (name.clone(), info.clone())),
+        );
+    }
+
+    /// Registers a directive definition for checking its applications.
+    fn collect_directive_definition(&mut self, directive: &DirectiveDefinitionNode<'_>) {
+        let (name, info) = directive_info(directive, self.interner);
+        self.directives.insert(name, info);
+    }
+
+    /// Collects all type definitions (first pass).
+    fn collect_type_definitions(&mut self, document: &Document<'_>) {
+        // Register built-in scalars
+        for name in ["Int", "Float", "String", "Boolean", "ID"] {
+            self.defined_types.insert(name.to_string());
+        }
+
+        // Register built-in generic types
+        for name in ["Option", "List"] {
+            self.defined_types.insert(name.to_string());
+        }
+
+        for definition in &document.definitions {
+            match definition {
+                Definition::Type(type_def) => {
+                    let (name, is_interface, is_input) = match type_def {
+                        TypeDefinition::Object(obj) => (self.resolve(obj.name.value), false, false),
+                        TypeDefinition::Interface(iface) => {
+                            (self.resolve(iface.name.value), true, false)
                         }
                         TypeDefinition::Union(union_def) => {
                             (self.resolve(union_def.name.value), false, false)
@@ -524,6 +1353,15 @@ impl<'a> TypeChecker<'a> {
                                     .map(|f| InterfaceFieldInfo {
                                         name: self.resolve(f.name.value),
                                         type_repr: self.type_to_string(&f.ty),
+                                        arguments: f
+                                            .arguments
+                                            .iter()
+                                            .map(|a| InterfaceArgInfo {
+                                                name: self.resolve(a.name.value),
+                                                type_repr: self.type_to_string(&a.ty),
+                                            })
+                                            .collect(),
+                                        span: f.span,
                                     })
                                     .collect();
                                 self.interface_fields.insert(name.clone(), fields);
@@ -539,9 +1377,44 @@ impl<'a> TypeChecker<'a> {
                         if is_input {
                             self.input_types.insert(name.clone());
                         }
+                        match type_def {
+                            TypeDefinition::InputUnion(_) => {
+                                self.input_only_types.insert(name.clone(), "input union");
+                            }
+                            TypeDefinition::InputEnum(_) => {
+                                self.input_only_types.insert(name.clone(), "input enum");
+                            }
+                            _ => {}
+                        }
+
+                        // Collect what literal default values are checked against
+                        match type_def {
+                            TypeDefinition::Enum(enum_def) => {
+                                let values = enum_def
+                                    .values
+                                    .iter()
+                                    .map(|value| self.resolve(value.name.value))
+                                    .collect();
+                                self.enum_values.insert(name.clone(), values);
+                            }
+                            TypeDefinition::Input(input) => {
+                                let fields = input
+                                    .fields
+                                    .iter()
+                                    .map(|field| InputFieldShape {
+                                        name: self.resolve(field.name.value),
+                                        shape: self.input_shape(&field.ty),
+                                        has_default: field.default_value.is_some(),
+                                    })
+                                    .collect();
+                                self.input_object_fields.insert(name.clone(), fields);
+                            }
+                            _ => {}
+                        }
 
                         // Collect generic type parameters for object types
                         if let TypeDefinition::Object(obj) = type_def {
+                            self.object_types.insert(name.clone());
                             if !obj.type_params.is_empty() {
                                 let params = self.collect_type_params(&obj.type_params);
                                 self.generic_types
@@ -559,6 +1432,7 @@ impl<'a> TypeChecker<'a> {
                         }
                     }
                 }
+                Definition::Directive(directive) => self.collect_directive_definition(directive),
                 Definition::Module(module) => {
                     // Recursively collect from inline modules
                     if let Some(body) = &module.body {
@@ -578,7 +1452,16 @@ impl<'a> TypeChecker<'a> {
     fn check_definitions(&mut self, document: &Document<'_>) {
         for definition in &document.definitions {
             match definition {
-                Definition::Type(type_def) => self.check_type_definition(type_def),
+                Definition::Type(type_def) => self.check_cached(
+                    type_def,
+                    |cached| &mut cached.definition,
+                    |checker| checker.check_type_definition(type_def),
+                ),
+                Definition::Directive(directive) => {
+                    for arg in &directive.arguments {
+                        self.check_input_value_definition(arg);
+                    }
+                }
                 Definition::Module(module) => {
                     if let Some(body) = &module.body {
                         let inner_doc = Document {
@@ -593,6 +1476,493 @@ impl<'a> TypeChecker<'a> {
         }
     }
 
+    /// Checks that the fields selected by operations and fragments can be merged.
+    fn check_operations<'s>(&mut self, document: &Document<'s>) {
+        let fragments: FxHashMap<String, &FragmentDefinition<'s>> = document
+            .definitions
+            .iter()
+            .filter_map(|def| match def {
+                Definition::Fragment(fragment) => {
+                    Some((self.resolve(fragment.name.value), fragment))
+                }
+                _ => None,
+            })
+            .collect();
+
+        // Fragments are checked on their own and where they are spread
+        let mut reported = FxHashSet::default();
+        for definition in &document.definitions {
+            let selection_set = match definition {
+                Definition::Operation(op) => &op.selection_set,
+                Definition::Fragment(fragment) => &fragment.selection_set,
+                _ => continue,
+            };
+            self.check_field_merging(
+                &[selection_set],
+                &fragments,
+                &FxHashSet::default(),
+                &mut reported,
+            );
+        }
+    }
+
+    /// Reports fields with the same response name that select different
+    /// fields or pass different arguments, then checks their merged
+    /// sub-selections.
+    ///
+    /// `expanded` holds the fragments already spread by enclosing selections,
+    /// so fragment cycles don't recurse forever.
+    fn check_field_merging<'d, 's>(
+        &mut self,
+        selection_sets: &[&'d SelectionSet<'s>],
+        fragments: &FxHashMap<String, &'d FragmentDefinition<'s>>,
+        expanded: &FxHashSet<String>,
+        reported: &mut FxHashSet<(u32, u32)>,
+    ) {
+        let mut expanded = expanded.clone();
+        let mut fields = Vec::new();
+        for selection_set in selection_sets {
+            self.collect_selected_fields(
+                selection_set,
+                None,
+                fragments,
+                &mut expanded,
+                &mut fields,
+            );
+        }
+
+        let mut groups: Vec<(String, Vec<SelectedField<'_, '_>>)> = Vec::new();
+        for selected in fields {
+            let field = selected.field;
+            let response_name = self.resolve(field.alias.unwrap_or(field.name).value);
+            match groups.iter_mut().find(|(name, _)| *name == response_name) {
+                Some((_, group)) => group.push(selected),
+                None => groups.push((response_name, vec![selected])),
+            }
+        }
+
+        for (response_name, group) in &groups {
+            let first = &group[0];
+            for other in &group[1..] {
+                // Fields of different object types are never merged
+                if let (Some(a), Some(b)) = (&first.parent_type, &other.parent_type) {
+                    if a != b {
+                        continue;
+                    }
+                }
+                if !reported.insert((first.field.span.start, other.field.span.start)) {
+                    continue;
+                }
+
+                let first_name = self.resolve(first.field.name.value);
+                let other_name = self.resolve(other.field.name.value);
+                let (reason, label) = if first_name != other_name {
+                    (
+                        format!("`{first_name}` and `{other_name}` are different fields"),
+                        format!("selects `{other_name}`"),
+                    )
+                } else if !same_arguments(&first.field.arguments, &other.field.arguments) {
+                    (
+                        "they have different arguments".to_string(),
+                        "different arguments here".to_string(),
+                    )
+                } else {
+                    continue;
+                };
+
+                self.diagnostics.error_with_labels(
+                    codes::CONFLICTING_SELECTION,
+                    format!("Fields `{response_name}` conflict because {reason}"),
+                    [
+                        Label::new(other.field.span, label),
+                        Label::new(first.field.span, "first selected here"),
+                    ],
+                    ["use different aliases to select both".to_string()],
+                );
+            }
+
+            let sub_selections: Vec<_> = group
+                .iter()
+                .filter_map(|selected| selected.field.selection_set.as_ref())
+                .collect();
+            if !sub_selections.is_empty() {
+                self.check_field_merging(&sub_selections, fragments, &expanded, reported);
+            }
+        }
+    }
+
+    /// Collects the fields of a selection set, looking through inline
+    /// fragments and fragment spreads.
+    fn collect_selected_fields<'d, 's>(
+        &self,
+        selection_set: &'d SelectionSet<'s>,
+        parent_type: Option<&str>,
+        fragments: &FxHashMap<String, &'d FragmentDefinition<'s>>,
+        expanded: &mut FxHashSet<String>,
+        fields: &mut Vec<SelectedField<'d, 's>>,
+    ) {
+        for selection in &selection_set.selections {
+            match selection {
+                Selection::Field(field) => fields.push(SelectedField {
+                    field,
+                    parent_type: parent_type.map(str::to_string),
+                }),
+                Selection::InlineFragment(inline) => {
+                    let condition = inline.type_condition.map(|name| self.resolve(name.value));
+                    self.collect_selected_fields(
+                        &inline.selection_set,
+                        condition.as_deref().or(parent_type),
+                        fragments,
+                        expanded,
+                        fields,
+                    );
+                }
+                Selection::FragmentSpread(spread) => {
+                    let name = self.resolve(spread.name.value);
+                    let Some(fragment) = fragments.get(&name).copied() else {
+                        continue;
+                    };
+                    if expanded.insert(name) {
+                        let condition = self.resolve(fragment.type_condition.value);
+                        self.collect_selected_fields(
+                            &fragment.selection_set,
+                            Some(&condition),
+                            fragments,
+                            expanded,
+                            fields,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks that the root operation types resolve to object types.
+    fn check_schema_definition(&mut self, document: &Document<'_>) {
+        let mut schema_defined = false;
+        for definition in &document.definitions {
+            let Definition::Schema(schema) = definition else {
+                continue;
+            };
+            if schema_defined {
+                self.diagnostics.error(
+                    codes::DUPLICATE_SCHEMA,
+                    "Duplicate schema definition",
+                    schema.span,
+                    "A document can only have one schema definition",
+                );
+            }
+            schema_defined = true;
+
+            let mut seen_operations = Vec::new();
+            for op in &schema.operations {
+                let kind = match op.operation {
+                    OperationType::Query => "query",
+                    OperationType::Mutation => "mutation",
+                    OperationType::Subscription => "subscription",
+                };
+                if seen_operations.contains(&op.operation) {
+                    self.diagnostics.error(
+                        codes::DUPLICATE_SCHEMA,
+                        format!("Duplicate `{kind}` root operation type"),
+                        op.span,
+                        format!("The `{kind}` root type is already declared"),
+                    );
+                    continue;
+                }
+                seen_operations.push(op.operation);
+
+                let name = self.resolve(op.type_name);
+                if !self.defined_types.contains(&name) {
+                    // The span starts at the operation keyword, so only a note
+                    let mut diagnostic = Diagnostic::error(
+                        codes::UNDEFINED_TYPE,
+                        format!("Undefined type `{name}`"),
+                    )
+                    .with_span(op.span, format!("Root {kind} type `{name}` is not defined"));
+                    if let Some(suggestion) = self.similar_type(&name, false) {
+                        diagnostic = diagnostic.with_note(format!("did you mean `{suggestion}`?"));
+                    }
+                    self.diagnostics.add(diagnostic);
+                } else if !self.object_types.contains(&name) {
+                    self.diagnostics.error(
+                        codes::INVALID_ROOT_TYPE,
+                        format!("Root {kind} type `{name}` must be an object type"),
+                        op.span,
+                        "Root operation types must be declared with `type`",
+                    );
+                }
+            }
+        }
+
+        if schema_defined || self.object_types.contains("Query") {
+            return;
+        }
+        let operation = document.definitions.iter().find_map(|def| match def {
+            Definition::Operation(op) => Some(op),
+            _ => None,
+        });
+        if let Some(op) = operation {
+            self.diagnostics.warning(
+                codes::MISSING_ROOT_TYPE,
+                "Missing root query type",
+                op.span,
+                "Define `type Query` or a `schema` block to execute operations",
+            );
+        }
+    }
+
+    /// Checks that every applied directive is defined and allowed where it
+    /// appears.
+    fn check_directive_applications(&mut self, document: &Document<'_>) {
+        for definition in &document.definitions {
+            match definition {
+                Definition::Schema(schema) => {
+                    self.check_directives(&schema.directives, DirectiveLocation::Schema);
+                }
+                Definition::Type(type_def) => self.check_cached(
+                    type_def,
+                    |cached| &mut cached.directives,
+                    |checker| checker.check_type_directives(type_def),
+                ),
+                Definition::Directive(directive) => {
+                    self.check_argument_directives(&directive.arguments);
+                }
+                Definition::Operation(op) => {
+                    let location = match op.operation {
+                        OperationType::Query => DirectiveLocation::Query,
+                        OperationType::Mutation => DirectiveLocation::Mutation,
+                        OperationType::Subscription => DirectiveLocation::Subscription,
+                    };
+                    self.check_directives(&op.directives, location);
+                    for variable in &op.variables {
+                        self.check_directives(
+                            &variable.directives,
+                            DirectiveLocation::VariableDefinition,
+                        );
+                    }
+                    self.check_selection_directives(&op.selection_set);
+                }
+                Definition::Fragment(fragment) => {
+                    self.check_directives(
+                        &fragment.directives,
+                        DirectiveLocation::FragmentDefinition,
+                    );
+                    self.check_selection_directives(&fragment.selection_set);
+                }
+                Definition::Module(module) => {
+                    if let Some(body) = &module.body {
+                        let inner_doc = Document {
+                            definitions: body.clone(),
+                            span: module.span,
+                        };
+                        self.check_directive_applications(&inner_doc);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Checks the directives of a type definition and its members.
+    ///
+    /// Opaque types are scalars, input unions and input enums are input
+    /// objects, and the variants of an input enum are enum values.
+    fn check_type_directives(&mut self, type_def: &TypeDefinition<'_>) {
+        match type_def {
+            TypeDefinition::Object(obj) => {
+                self.check_directives(&obj.directives, DirectiveLocation::Object);
+                self.check_field_directives(&obj.fields);
+            }
+            TypeDefinition::Interface(iface) => {
+                self.check_directives(&iface.directives, DirectiveLocation::Interface);
+                self.check_field_directives(&iface.fields);
+            }
+            TypeDefinition::Union(union_def) => {
+                self.check_directives(&union_def.directives, DirectiveLocation::Union);
+            }
+            TypeDefinition::Enum(enum_def) => {
+                self.check_directives(&enum_def.directives, DirectiveLocation::Enum);
+                for value in &enum_def.values {
+                    self.check_directives(&value.directives, DirectiveLocation::EnumValue);
+                    if let Some(EnumVariantData::Struct(fields, _)) = &value.data {
+                        self.check_input_field_directives(fields);
+                    }
+                }
+            }
+            TypeDefinition::Input(input) => {
+                self.check_directives(&input.directives, DirectiveLocation::InputObject);
+                self.check_input_field_directives(&input.fields);
+            }
+            TypeDefinition::Scalar(scalar) => {
+                self.check_directives(&scalar.directives, DirectiveLocation::Scalar);
+            }
+            TypeDefinition::Opaque(opaque) => {
+                self.check_directives(&opaque.directives, DirectiveLocation::Scalar);
+            }
+            TypeDefinition::TypeAlias(_) => {}
+            TypeDefinition::InputUnion(input_union) => {
+                self.check_directives(&input_union.directives, DirectiveLocation::InputObject);
+            }
+            TypeDefinition::InputEnum(input_enum) => {
+                self.check_directives(&input_enum.directives, DirectiveLocation::InputObject);
+                for variant in &input_enum.variants {
+                    self.check_directives(&variant.directives, DirectiveLocation::EnumValue);
+                    if let Some(fields) = &variant.fields {
+                        self.check_input_field_directives(fields);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks the directives of field definitions and their arguments.
+    fn check_field_directives(&mut self, fields: &[FieldDefinition<'_>]) {
+        for field in fields {
+            self.check_directives(&field.directives, DirectiveLocation::FieldDefinition);
+            self.check_argument_directives(&field.arguments);
+        }
+    }
+
+    /// Checks the directives of argument definitions.
+    fn check_argument_directives(&mut self, arguments: &[InputValueDefinition<'_>]) {
+        for argument in arguments {
+            self.check_directives(&argument.directives, DirectiveLocation::ArgumentDefinition);
+        }
+    }
+
+    /// Checks the directives of input field definitions.
+    fn check_input_field_directives(&mut self, fields: &[InputValueDefinition<'_>]) {
+        for field in fields {
+            self.check_directives(&field.directives, DirectiveLocation::InputFieldDefinition);
+        }
+    }
+
+    /// Checks the directives applied within a selection set.
+    fn check_selection_directives(&mut self, selection_set: &SelectionSet<'_>) {
+        for selection in &selection_set.selections {
+            match selection {
+                Selection::Field(field) => {
+                    self.check_directives(&field.directives, DirectiveLocation::Field);
+                    if let Some(selection_set) = &field.selection_set {
+                        self.check_selection_directives(selection_set);
+                    }
+                }
+                Selection::FragmentSpread(spread) => {
+                    self.check_directives(&spread.directives, DirectiveLocation::FragmentSpread);
+                }
+                Selection::InlineFragment(fragment) => {
+                    self.check_directives(&fragment.directives, DirectiveLocation::InlineFragment);
+                    self.check_selection_directives(&fragment.selection_set);
+                }
+            }
+        }
+    }
+
+    /// Checks that each of `directives` is defined, allowed at `location` and
+    /// given valid arguments.
+    fn check_directives(&mut self, directives: &[Directive<'_>], location: DirectiveLocation) {
+        for directive in directives {
+            let name = self.resolve(directive.name.value);
+            let Some(info) = self.directives.get(&name).cloned() else {
+                self.diagnostics.error(
+                    codes::UNKNOWN_DIRECTIVE,
+                    format!("Unknown directive `@{name}`"),
+                    directive.name.span,
+                    format!("Directive `@{name}` is not defined"),
+                );
+                continue;
+            };
+            if !info.locations.contains(&location) {
+                let allowed = info
+                    .locations
+                    .iter()
+                    .map(|location| location.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                self.diagnostics.error(
+                    codes::DIRECTIVE_NOT_ALLOWED,
+                    format!(
+                        "Directive `@{name}` cannot be used on {}",
+                        location.as_str()
+                    ),
+                    directive.span,
+                    format!("`@{name}` is allowed on {allowed}"),
+                );
+            }
+            self.check_directive_arguments(&name, directive, &info.arguments);
+            if name == "deprecated" && directive.arguments.is_empty() {
+                self.diagnostics.warning(
+                    codes::DEPRECATED_WITHOUT_REASON,
+                    "Deprecation without a reason",
+                    directive.span,
+                    "Add `reason:` to tell clients what to use instead",
+                );
+            }
+        }
+    }
+
+    /// Checks the arguments of an applied directive against its definition.
+    fn check_directive_arguments(
+        &mut self,
+        name: &str,
+        directive: &Directive<'_>,
+        expected: &[InputFieldShape],
+    ) {
+        // Arguments given without a name, as in `@minLength(3)`, are bound
+        // to the declared arguments in order
+        let arg_names: Vec<String> = directive
+            .arguments
+            .iter()
+            .enumerate()
+            .map(|(index, arg)| match self.resolve(arg.name.value) {
+                arg_name if arg_name.is_empty() => expected
+                    .get(index)
+                    .map(|expected| expected.name.clone())
+                    .unwrap_or_default(),
+                arg_name => arg_name,
+            })
+            .collect();
+
+        for (arg, arg_name) in directive.arguments.iter().zip(&arg_names) {
+            if arg_name.is_empty() {
+                self.diagnostics.error(
+                    codes::UNKNOWN_ARGUMENT,
+                    format!("Too many arguments for directive `@{name}`"),
+                    arg.span,
+                    format!("`@{name}` takes {} arguments", expected.len()),
+                );
+                continue;
+            }
+            match expected.iter().find(|expected| &expected.name == arg_name) {
+                Some(expected) => self.check_value_type(&arg.value, &expected.shape),
+                None => self.diagnostics.error(
+                    codes::UNKNOWN_ARGUMENT,
+                    format!("Unknown argument `{arg_name}` on directive `@{name}`"),
+                    arg.name.span,
+                    format!("`@{name}` has no argument `{arg_name}`"),
+                ),
+            }
+        }
+
+        for expected in expected {
+            let provided = arg_names.iter().any(|arg_name| arg_name == &expected.name);
+            let required =
+                !matches!(expected.shape, InputShape::Option(_)) && !expected.has_default;
+            if required && !provided {
+                self.diagnostics.error(
+                    codes::MISSING_REQUIRED_ARGUMENT,
+                    format!(
+                        "Missing required argument `{}` of directive `@{name}`",
+                        expected.name
+                    ),
+                    directive.span,
+                    format!("`{}` is required", expected.name),
+                );
+            }
+        }
+    }
+
     /// Checks a single type definition.
     fn check_type_definition(&mut self, type_def: &TypeDefinition<'_>) {
         match type_def {
@@ -602,9 +1972,13 @@ impl<'a> TypeChecker<'a> {
             TypeDefinition::Enum(enum_def) => self.check_enum_type(enum_def),
             TypeDefinition::Input(input) => self.check_input_type(input),
             TypeDefinition::Scalar(_) => {} // Scalars have no type references
-            TypeDefinition::Opaque(opaque) => self.check_opaque_type(opaque),
+            TypeDefinition::Opaque(opaque) => {
+                self.check_alias_cycle(opaque.name);
+                self.check_opaque_type(opaque);
+            }
             TypeDefinition::TypeAlias(alias) => {
-                self.check_type(&alias.aliased);
+                self.check_alias_cycle(alias.name);
+                self.check_type(&alias.aliased, TypePosition::Any);
             }
             TypeDefinition::InputUnion(input_union) => self.check_input_union_type(input_union),
             TypeDefinition::InputEnum(input_enum) => self.check_input_enum_type(input_enum),
@@ -617,27 +1991,34 @@ impl<'a> TypeChecker<'a> {
 
         // Register type parameters in scope for checking field types
         let prev_type_params = std::mem::take(&mut self.type_params_in_scope);
+        let prev_used_type_params = std::mem::take(&mut self.used_type_params);
         for param in &obj.type_params {
             self.type_params_in_scope
                 .insert(self.resolve(param.name.value));
         }
 
         // Build a map of object fields for interface checking
-        let obj_fields: FxHashMap<String, String> = obj
+        let obj_fields: FxHashMap<String, &FieldDefinition<'_>> = obj
             .fields
             .iter()
-            .map(|f| (self.resolve(f.name.value), self.type_to_string(&f.ty)))
+            .map(|f| (self.resolve(f.name.value), f))
             .collect();
 
         // Check implements clause
+        let mut seen_interfaces = FxHashSet::default();
         for iface_name in &obj.implements {
             let name = self.resolve(iface_name.value);
+            if !seen_interfaces.insert(name.clone()) {
+                self.report_duplicate_implementation(&name, iface_name.span);
+                continue;
+            }
             if !self.interfaces.contains(&name) {
-                self.diagnostics.error(
-                    codes::UNDEFINED_TYPE,
+                self.undefined_type(
+                    &name,
                     format!("Undefined interface `{name}`"),
                     iface_name.span,
                     format!("`{name}` is not a defined interface"),
+                    true,
                 );
             } else {
                 // Check that all interface fields are implemented
@@ -658,22 +2039,36 @@ impl<'a> TypeChecker<'a> {
                                     ),
                                 );
                             }
-                            Some(obj_type) => {
+                            Some(obj_field) => {
                                 // Check type compatibility
-                                if obj_type != &iface_field.type_repr {
-                                    self.diagnostics.error(
+                                let obj_type = self.type_to_string(&obj_field.ty);
+                                if obj_type != iface_field.type_repr {
+                                    self.diagnostics.error_with_labels(
                                         codes::INTERFACE_FIELD_TYPE_MISMATCH,
                                         format!(
                                             "Field `{}` has incompatible type",
                                             iface_field.name
                                         ),
-                                        obj.name.span,
-                                        format!(
-                                            "Expected `{}` but found `{}`",
-                                            iface_field.type_repr, obj_type
-                                        ),
+                                        [
+                                            Label::new(
+                                                obj_field.span,
+                                                format!(
+                                                    "Expected `{}` but found `{}`",
+                                                    iface_field.type_repr, obj_type
+                                                ),
+                                            ),
+                                            Label::new(
+                                                iface_field.span,
+                                                "expected type declared here",
+                                            ),
+                                        ],
+                                        [format!("`{type_name}` implements interface `{name}`")],
                                     );
                                 }
+
+                                // Interface arguments must be kept, added ones must be optional
+                                self.check_interface_arguments(obj_field, iface_field, &name);
+                                self.check_extra_interface_arguments(obj_field, iface_field, &name);
                             }
                         }
                     }
@@ -681,7 +2076,8 @@ impl<'a> TypeChecker<'a> {
             }
         }
 
-        // Check field duplicates
+        // Check for missing and duplicate fields
+        self.check_empty_type(obj.name, &type_name, &obj.fields);
         self.check_field_duplicates(&obj.fields, &type_name);
 
         // Check field types
@@ -689,8 +2085,110 @@ impl<'a> TypeChecker<'a> {
             self.check_field_definition(field);
         }
 
+        self.check_unused_type_params(&obj.type_params);
+
         // Restore previous type parameters scope
         self.type_params_in_scope = prev_type_params;
+        self.used_type_params = prev_used_type_params;
+    }
+
+    /// Warns about type parameters that no field or argument refers to.
+    fn check_unused_type_params(&mut self, params: &[TypeParameter<'_>]) {
+        for param in params {
+            let name = self.resolve(param.name.value);
+            if !self.used_type_params.contains(&name) {
+                self.diagnostics.warning(
+                    codes::UNUSED_TYPE_PARAMETER,
+                    format!("Type parameter `{name}` is never used"),
+                    param.name.span,
+                    format!("Remove `{name}` or use it in a field type"),
+                );
+            }
+        }
+    }
+
+    /// Checks that an implementing field declares every interface argument
+    /// with the same type.
+    fn check_interface_arguments(
+        &mut self,
+        field: &FieldDefinition<'_>,
+        iface_field: &InterfaceFieldInfo,
+        iface_name: &str,
+    ) {
+        for iface_arg in &iface_field.arguments {
+            let arg = field
+                .arguments
+                .iter()
+                .find(|arg| self.resolve(arg.name.value) == iface_arg.name);
+            match arg {
+                None => self.diagnostics.error_with_labels(
+                    codes::INTERFACE_FIELD_ARGUMENT_MISMATCH,
+                    format!(
+                        "Field `{}` is missing argument `{}`",
+                        iface_field.name, iface_arg.name
+                    ),
+                    [
+                        Label::new(
+                            field.name.span,
+                            format!("missing `{}: {}`", iface_arg.name, iface_arg.type_repr),
+                        ),
+                        Label::new(iface_field.span, "argument declared here"),
+                    ],
+                    [format!("required by interface `{iface_name}`")],
+                ),
+                Some(arg) => {
+                    let arg_type = self.type_to_string(&arg.ty);
+                    if arg_type != iface_arg.type_repr {
+                        self.diagnostics.error_with_labels(
+                            codes::INTERFACE_FIELD_ARGUMENT_MISMATCH,
+                            format!(
+                                "Argument `{}` of field `{}` has incompatible type",
+                                iface_arg.name, iface_field.name
+                            ),
+                            [
+                                Label::new(
+                                    arg.span,
+                                    format!(
+                                        "Expected `{}` but found `{}`",
+                                        iface_arg.type_repr, arg_type
+                                    ),
+                                ),
+                                Label::new(iface_field.span, "expected type declared here"),
+                            ],
+                            [format!("required by interface `{iface_name}`")],
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks that arguments an implementing field adds beyond the interface's are optional.
+    fn check_extra_interface_arguments(
+        &mut self,
+        field: &FieldDefinition<'_>,
+        iface_field: &InterfaceFieldInfo,
+        iface_name: &str,
+    ) {
+        for arg in &field.arguments {
+            let arg_name = self.resolve(arg.name.value);
+            let is_required = !matches!(arg.ty, Type::Option(..)) && arg.default_value.is_none();
+            let declared = iface_field.arguments.iter().any(|a| a.name == arg_name);
+            if is_required && !declared {
+                self.diagnostics.error(
+                    codes::INTERFACE_FIELD_EXTRA_REQUIRED_ARG,
+                    format!(
+                        "Field `{}` adds required argument `{}`",
+                        iface_field.name, arg_name
+                    ),
+                    arg.name.span,
+                    format!(
+                        "Arguments not declared by interface `{}` must be optional or have a default value",
+                        iface_name
+                    ),
+                );
+            }
+        }
     }
 
     /// Checks an interface type definition.
@@ -699,25 +2197,33 @@ impl<'a> TypeChecker<'a> {
 
         // Register type parameters in scope for checking field types
         let prev_type_params = std::mem::take(&mut self.type_params_in_scope);
+        let prev_used_type_params = std::mem::take(&mut self.used_type_params);
         for param in &iface.type_params {
             self.type_params_in_scope
                 .insert(self.resolve(param.name.value));
         }
 
         // Check extends clause
+        let mut seen_interfaces = FxHashSet::default();
         for extends_name in &iface.implements {
             let name = self.resolve(extends_name.value);
-            if !self.interfaces.contains(&name) {
-                self.diagnostics.error(
-                    codes::UNDEFINED_TYPE,
+            if !seen_interfaces.insert(name.clone()) {
+                self.report_duplicate_implementation(&name, extends_name.span);
+                continue;
+            }
+            if !self.interfaces.contains(&name) {
+                self.undefined_type(
+                    &name,
                     format!("Undefined interface `{name}`"),
                     extends_name.span,
                     format!("`{name}` is not a defined interface"),
+                    true,
                 );
             }
         }
 
-        // Check field duplicates
+        // Check for missing and duplicate fields
+        self.check_empty_type(iface.name, &type_name, &iface.fields);
         self.check_field_duplicates(&iface.fields, &type_name);
 
         // Check field types
@@ -725,8 +2231,11 @@ impl<'a> TypeChecker<'a> {
             self.check_field_definition(field);
         }
 
+        self.check_unused_type_params(&iface.type_params);
+
         // Restore previous type parameters scope
         self.type_params_in_scope = prev_type_params;
+        self.used_type_params = prev_used_type_params;
     }
 
     /// Checks a union type definition.
@@ -741,14 +2250,25 @@ impl<'a> TypeChecker<'a> {
             return;
         }
 
+        let mut seen_members = FxHashSet::default();
         for member in &union_def.members {
             let name = self.resolve(member.value);
+            if !seen_members.insert(name.clone()) {
+                self.diagnostics.warning(
+                    codes::DUPLICATE_UNION_MEMBER,
+                    format!("Duplicate union member `{name}`"),
+                    member.span,
+                    format!("`{name}` is already a member of this union"),
+                );
+                continue;
+            }
             if !self.defined_types.contains(&name) {
-                self.diagnostics.error(
-                    codes::UNDEFINED_TYPE,
+                self.undefined_type(
+                    &name,
                     format!("Undefined type `{name}`"),
                     member.span,
                     format!("Union member `{name}` is not defined"),
+                    false,
                 );
             }
         }
@@ -786,7 +2306,7 @@ impl<'a> TypeChecker<'a> {
                 match data {
                     EnumVariantData::Tuple(types, _) => {
                         for ty in types {
-                            self.check_type(ty);
+                            self.check_type(ty, TypePosition::Any);
                         }
                     }
                     EnumVariantData::Struct(fields, _) => {
@@ -821,7 +2341,7 @@ impl<'a> TypeChecker<'a> {
 
     /// Checks an opaque type definition.
     fn check_opaque_type(&mut self, opaque: &OpaqueTypeDefinition<'_>) {
-        self.check_type(&opaque.underlying);
+        self.check_type(&opaque.underlying, TypePosition::Any);
     }
 
     /// Checks an input union type definition.
@@ -839,11 +2359,12 @@ impl<'a> TypeChecker<'a> {
         for member in &input_union.members {
             let name = self.resolve(member.value);
             if !self.defined_types.contains(&name) {
-                self.diagnostics.error(
-                    codes::UNDEFINED_TYPE,
+                self.undefined_type(
+                    &name,
                     format!("Undefined type `{name}`"),
                     member.span,
                     format!("Input union member `{name}` is not defined"),
+                    false,
                 );
             } else if !self.input_types.contains(&name) {
                 self.diagnostics.error(
@@ -904,6 +2425,33 @@ impl<'a> TypeChecker<'a> {
         }
     }
 
+    /// Reports an interface listed twice in an implements clause.
+    fn report_duplicate_implementation(&mut self, name: &str, span: bgql_core::Span) {
+        self.diagnostics.error(
+            codes::DUPLICATE_INTERFACE_IMPLEMENTATION,
+            format!("Interface `{name}` is implemented more than once"),
+            span,
+            format!("remove the repeated `{name}`"),
+        );
+    }
+
+    /// Warns about object and interface types without fields.
+    fn check_empty_type(
+        &mut self,
+        name: bgql_syntax::Name,
+        type_name: &str,
+        fields: &[FieldDefinition<'_>],
+    ) {
+        if fields.is_empty() {
+            self.diagnostics.warning(
+                codes::EMPTY_TYPE,
+                format!("Type `{type_name}` has no fields"),
+                name.span,
+                "Object and interface types must define at least one field",
+            );
+        }
+    }
+
     /// Checks field duplicates.
     fn check_field_duplicates(&mut self, fields: &[FieldDefinition<'_>], type_name: &str) {
         let mut seen_fields = FxHashSet::default();
@@ -927,7 +2475,7 @@ impl<'a> TypeChecker<'a> {
         let field_name = self.resolve(field.name.value);
 
         // Check field type
-        self.check_type(&field.ty);
+        self.check_type(&field.ty, TypePosition::Output);
 
         // Check argument duplicates
         let mut seen_args = FxHashSet::default();
@@ -949,42 +2497,167 @@ impl<'a> TypeChecker<'a> {
 
     /// Checks an input value definition (argument or input field).
     fn check_input_value_definition(&mut self, input: &InputValueDefinition<'_>) {
-        self.check_type(&input.ty);
+        self.check_type(&input.ty, TypePosition::Input);
+        if let Some(default_value) = &input.default_value {
+            let shape = self.input_shape(&input.ty);
+            self.check_value_type(default_value, &shape);
+        }
+    }
+
+    /// Converts a type reference to an [`InputShape`].
+    fn input_shape(&self, ty: &Type<'_>) -> InputShape {
+        input_shape(ty, self.interner)
+    }
+
+    /// Checks that a literal value is assignable to a type.
+    fn check_value_type(&mut self, value: &Value<'_>, shape: &InputShape) {
+        let accepted = match (shape, value) {
+            (InputShape::Other, _) | (_, Value::Variable(_)) | (_, Value::_Phantom(_)) => true,
+            (InputShape::Option(_), Value::Null(_)) => true,
+            (InputShape::Option(inner), _) => return self.check_value_type(value, inner),
+            (_, Value::Null(_)) => false,
+            (InputShape::List(inner), Value::List(items, _)) => {
+                for item in items {
+                    self.check_value_type(item, inner);
+                }
+                return;
+            }
+            // A single value is coerced to a list of one
+            (InputShape::List(inner), _) => return self.check_value_type(value, inner),
+            (InputShape::Named(name), _) => match (name.as_str(), value) {
+                ("Int", Value::Int(..)) => true,
+                ("Float", Value::Int(..) | Value::Float(..)) => true,
+                ("String", Value::String(..)) => true,
+                ("ID", Value::String(..) | Value::Int(..)) => true,
+                ("Boolean", Value::Boolean(..)) => true,
+                ("Int" | "Float" | "String" | "ID" | "Boolean", _) => false,
+                (_, Value::Object(fields, span)) if self.input_object_fields.contains_key(name) => {
+                    return self.check_object_value(name, fields, *span);
+                }
+                _ if self.input_object_fields.contains_key(name) => false,
+                (_, Value::Enum(member)) if self.enum_values.contains_key(name) => {
+                    self.enum_values[name].contains(&self.resolve(member.value))
+                }
+                _ if self.enum_values.contains_key(name) => false,
+                // Custom scalars and other named types accept any literal
+                _ => true,
+            },
+        };
+
+        if !accepted {
+            let found = self.describe_value(value);
+            self.diagnostics.error(
+                codes::TYPE_MISMATCH,
+                format!("Expected `{shape}`, found {found}"),
+                value.span(),
+                format!("This value is not assignable to `{shape}`"),
+            );
+        }
     }
 
-    /// Checks a type reference.
-    fn check_type(&mut self, ty: &Type<'_>) {
+    /// Checks an object literal against the fields of an input object type.
+    fn check_object_value(
+        &mut self,
+        type_name: &str,
+        fields: &[(bgql_syntax::Name, Value<'_>)],
+        span: bgql_core::Span,
+    ) {
+        let expected = self.input_object_fields[type_name].clone();
+
+        for (field_name, field_value) in fields {
+            let name = self.resolve(field_name.value);
+            match expected.iter().find(|field| field.name == name) {
+                Some(field) => self.check_value_type(field_value, &field.shape),
+                None => self.diagnostics.error(
+                    codes::TYPE_MISMATCH,
+                    format!("Input type `{type_name}` has no field `{name}`"),
+                    field_name.span,
+                    format!("`{name}` is not a field of `{type_name}`"),
+                ),
+            }
+        }
+
+        for field in &expected {
+            let provided = fields
+                .iter()
+                .any(|(name, _)| self.resolve(name.value) == field.name);
+            let required = !matches!(field.shape, InputShape::Option(_)) && !field.has_default;
+            if required && !provided {
+                self.diagnostics.error(
+                    codes::TYPE_MISMATCH,
+                    format!("Missing field `{}` of input type `{type_name}`", field.name),
+                    span,
+                    format!("`{}` is required", field.name),
+                );
+            }
+        }
+    }
+
+    /// Describes a literal value for error messages.
+    fn describe_value(&self, value: &Value<'_>) -> String {
+        match value {
+            Value::Int(..) => "an integer".to_string(),
+            Value::Float(..) => "a float".to_string(),
+            Value::String(..) => "a string".to_string(),
+            Value::Boolean(..) => "a boolean".to_string(),
+            Value::Null(_) => "`null`".to_string(),
+            Value::Enum(name) => format!("enum value `{}`", self.resolve(name.value)),
+            Value::List(..) => "a list".to_string(),
+            Value::Object(..) => "an object".to_string(),
+            Value::Variable(_) | Value::_Phantom(_) => "a variable".to_string(),
+        }
+    }
+
+    /// Checks a type reference appearing in `position`.
+    fn check_type(&mut self, ty: &Type<'_>, position: TypePosition) {
         match ty {
             Type::Named(named) => {
-                let name = self.interner.get(named.name);
+                let name = self.interner.resolve(named.name);
                 // Allow type parameters that are in scope
-                if !self.defined_types.contains(&name) && !self.type_params_in_scope.contains(&name)
-                {
-                    self.diagnostics.error(
-                        codes::UNDEFINED_TYPE,
+                if self.type_params_in_scope.contains(name) {
+                    self.used_type_params.insert(name.to_string());
+                } else if !self.defined_types.contains(name) {
+                    self.undefined_type(
+                        name,
                         format!("Undefined type `{name}`"),
                         named.span,
                         format!("Type `{name}` is not defined"),
+                        false,
                     );
+                } else if position == TypePosition::Output {
+                    if let Some((target, keyword)) = self.input_only_target(name) {
+                        let label = if target == name {
+                            format!("`{name}` is an `{keyword}`, which is input-only")
+                        } else {
+                            format!("`{name}` stands for the `{keyword}` `{target}`, which is input-only")
+                        };
+                        self.diagnostics.error(
+                            codes::INPUT_TYPE_IN_OUTPUT_POSITION,
+                            format!("Input type `{target}` cannot be used as a field type"),
+                            named.span,
+                            label,
+                        );
+                    }
                 }
             }
-            Type::Option(inner, _) => self.check_type(inner),
-            Type::List(inner, _) => self.check_type(inner),
+            Type::Option(inner, _) => self.check_type(inner, position),
+            Type::List(inner, _) => self.check_type(inner, position),
             Type::Generic(generic) => {
                 // Check the generic type name
-                let name = self.interner.get(generic.name);
-                if !self.defined_types.contains(&name) {
-                    self.diagnostics.error(
-                        codes::UNDEFINED_TYPE,
+                let name = self.interner.resolve(generic.name);
+                if !self.defined_types.contains(name) {
+                    self.undefined_type(
+                        name,
                         format!("Undefined type `{name}`"),
                         generic.span,
                         format!("Generic type `{name}` is not defined"),
+                        false,
                     );
                 }
 
                 // Check type arguments
                 for arg in &generic.arguments {
-                    self.check_type(arg);
+                    self.check_type(arg, position);
                 }
 
                 // Check generic constraints
@@ -992,13 +2665,32 @@ impl<'a> TypeChecker<'a> {
             }
             Type::Tuple(tuple) => {
                 for element in &tuple.elements {
-                    self.check_type(&element.ty);
+                    self.check_type(&element.ty, position);
                 }
             }
             Type::_Phantom(_) => {}
         }
     }
 
+    /// Returns the input-only type `name` is or, through aliases and opaque
+    /// types, stands for, with its keyword.
+    fn input_only_target(&self, name: &str) -> Option<(String, &'static str)> {
+        let mut pending = vec![name.to_string()];
+        let mut seen = FxHashSet::default();
+        while let Some(name) = pending.pop() {
+            if !seen.insert(name.clone()) {
+                continue;
+            }
+            if let Some(keyword) = self.input_only_types.get(&name) {
+                return Some((name, keyword));
+            }
+            if let Some(deps) = self.alias_dependencies.get(&name) {
+                pending.extend(deps.iter().cloned());
+            }
+        }
+        None
+    }
+
     /// Checks generic constraints when a generic type is instantiated.
     fn check_generic_constraints(&mut self, generic: &bgql_syntax::GenericType<'_>) {
         let type_name = self.interner.get(generic.name);
@@ -1088,6 +2780,95 @@ impl<'a> TypeChecker<'a> {
     }
 }
 
+/// Returns true if `name` matches the GraphQL name grammar `/[_A-Za-z][_0-9A-Za-z]*/`.
+fn is_graphql_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Returns true if two argument lists pass the same values, in any order.
+fn same_arguments(a: &[Argument<'_>], b: &[Argument<'_>]) -> bool {
+    a.len() == b.len()
+        && a.iter().all(|arg| {
+            b.iter().any(|other| {
+                other.name.value == arg.name.value && same_value(&arg.value, &other.value)
+            })
+        })
+}
+
+/// Returns true if two literal values are equal, ignoring spans and the
+/// order of object fields.
+fn same_value(a: &Value<'_>, b: &Value<'_>) -> bool {
+    match (a, b) {
+        (Value::Variable(a), Value::Variable(b)) | (Value::Enum(a), Value::Enum(b)) => {
+            a.value == b.value
+        }
+        (Value::Int(a, _), Value::Int(b, _)) => a == b,
+        (Value::Float(a, _), Value::Float(b, _)) => a == b,
+        (Value::String(a, _), Value::String(b, _)) => a == b,
+        (Value::Boolean(a, _), Value::Boolean(b, _)) => a == b,
+        (Value::Null(_), Value::Null(_)) => true,
+        (Value::List(a, _), Value::List(b, _)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same_value(a, b))
+        }
+        (Value::Object(a, _), Value::Object(b, _)) => {
+            a.len() == b.len()
+                && a.iter().all(|(name, value)| {
+                    b.iter()
+                        .any(|(other, v)| other.value == name.value && same_value(value, v))
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Moves a span of a previous version of a document by as much as the
+/// type definition containing it moved.
+fn relocate(span: Span, moves: &[(Span, i64)]) -> Span {
+    match moves
+        .iter()
+        .find(|(old, _)| old.start <= span.start && span.end <= old.end)
+    {
+        Some(&(_, delta)) => Span::new(
+            (i64::from(span.start) + delta) as u32,
+            (i64::from(span.end) + delta) as u32,
+        ),
+        None => span,
+    }
+}
+
+/// Returns the candidate closest to `name` by edit distance, if any is
+/// within two edits and closer than the length of `name`.
+fn suggest_name<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a String>,
+) -> Option<&'a str> {
+    let max = 2.min(name.chars().count().saturating_sub(1));
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate.as_str()))
+        .filter(|&(distance, _)| distance <= max)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// Returns the Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
 /// Type checks a document.
 pub fn check(
     document: &Document<'_>,
@@ -1116,171 +2897,1116 @@ mod tests {
     }
 
     #[test]
-    fn test_valid_object_type() {
+    fn test_valid_object_type() {
+        let result = check_source(
+            r#"
+            type User {
+                id: ID
+                name: String
+            }
+        "#,
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_undefined_type() {
+        let result = check_source(
+            r#"
+            type User {
+                id: ID
+                profile: Profile
+            }
+        "#,
+        );
+        assert!(!result.is_ok());
+        assert!(result
+            .diagnostics
+            .iter()
+            .any(|d| d.code == codes::UNDEFINED_TYPE));
+    }
+
+    #[test]
+    fn test_duplicate_field() {
+        let result = check_source(
+            r#"
+            type User {
+                id: ID
+                name: String
+                name: Int
+            }
+        "#,
+        );
+        assert!(!result.is_ok());
+        assert!(result
+            .diagnostics
+            .iter()
+            .any(|d| d.code == codes::DUPLICATE_FIELD));
+    }
+
+    #[test]
+    fn test_duplicate_type() {
+        let result = check_source(
+            r#"
+            type User {
+                id: ID
+            }
+            type User {
+                name: String
+            }
+        "#,
+        );
+        assert!(!result.is_ok());
+        assert!(result
+            .diagnostics
+            .iter()
+            .any(|d| d.code == codes::DUPLICATE_TYPE));
+    }
+
+    #[test]
+    fn test_undefined_interface() {
+        let result = check_source(
+            r#"
+            type User implements Node {
+                id: ID
+            }
+        "#,
+        );
+        assert!(!result.is_ok());
+        assert!(result
+            .diagnostics
+            .iter()
+            .any(|d| d.code == codes::UNDEFINED_TYPE));
+    }
+
+    #[test]
+    fn test_valid_interface_implementation() {
+        let result = check_source(
+            r#"
+            interface Node {
+                id: ID
+            }
+            type User implements Node {
+                id: ID
+                name: String
+            }
+        "#,
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_valid_union() {
+        let result = check_source(
+            r#"
+            type User {
+                id: ID
+            }
+            type Post {
+                id: ID
+            }
+            union SearchResult = User | Post
+        "#,
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_undefined_union_member() {
+        let result = check_source(
+            r#"
+            type User {
+                id: ID
+            }
+            union SearchResult = User | Post
+        "#,
+        );
+        assert!(!result.is_ok());
+        assert!(result
+            .diagnostics
+            .iter()
+            .any(|d| d.code == codes::UNDEFINED_TYPE));
+    }
+
+    #[test]
+    fn test_valid_input_type() {
+        let result = check_source(
+            r#"
+            input CreateUserInput {
+                name: String
+                email: String
+            }
+        "#,
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_option_and_list_types() {
+        let result = check_source(
+            r#"
+            type User {
+                id: ID
+                nickname: Option<String>
+                friends: List<User>
+            }
+        "#,
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_enum_with_data() {
+        let result = check_source(
+            r#"
+            enum Result {
+                Ok(String)
+                Err { message: String, code: Int }
+            }
+        "#,
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_input_enum() {
+        let result = check_source(
+            r#"
+            input enum LoginMethod {
+                Email { email: String, password: String }
+                OAuth { provider: String }
+                Anonymous
+            }
+        "#,
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_input_union_as_field_type() {
+        let result = check_source(
+            r#"
+            input EmailLogin { email: String }
+            input TokenLogin { token: String }
+            input union LoginInput = EmailLogin | TokenLogin
+            type Query {
+                lastLogin: Option<LoginInput>
+            }
+        "#,
+        );
+        assert!(!result.is_ok());
+        assert!(result.diagnostics.iter().any(|d| {
+            d.code == codes::INPUT_TYPE_IN_OUTPUT_POSITION && d.title.contains("LoginInput")
+        }));
+    }
+
+    #[test]
+    fn test_input_only_types_through_aliases() {
+        let result = check_source(
+            r#"
+            input EmailLogin { email: String }
+            input TokenLogin { token: String }
+            input union LoginInput = EmailLogin | TokenLogin
+            input enum Method { Password { hash: String } Anonymous }
+            alias Login = LoginInput
+            alias Logins = List<Login>
+            type Query {
+                lastLogin: Option<Login>
+                history: Logins
+                method: Method
+                login(input: Login): String
+            }
+        "#,
+        );
+        let errors: Vec<_> = result
+            .diagnostics
+            .errors()
+            .map(|d| (d.code.as_str(), d.title.as_str()))
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                (
+                    codes::INPUT_TYPE_IN_OUTPUT_POSITION,
+                    "Input type `LoginInput` cannot be used as a field type"
+                ),
+                (
+                    codes::INPUT_TYPE_IN_OUTPUT_POSITION,
+                    "Input type `LoginInput` cannot be used as a field type"
+                ),
+                (
+                    codes::INPUT_TYPE_IN_OUTPUT_POSITION,
+                    "Input type `Method` cannot be used as a field type"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_input_union_in_input_positions() {
+        let result = check_source(
+            r#"
+            input EmailLogin { email: String }
+            input TokenLogin { token: String }
+            input union LoginInput = EmailLogin | TokenLogin
+            input enum Method { Password { hash: String } Anonymous }
+            input LoginRequest {
+                login: LoginInput
+                methods: List<Method>
+            }
+            type Query {
+                login(input: LoginInput, method: Option<Method>): String
+            }
+        "#,
+        );
+        assert!(result.is_ok(), "{:?}", result.diagnostics);
+    }
+
+    #[test]
+    fn test_misplaced_directive() {
+        let result = check_source(
+            r#"
+            directive @audit on FIELD_DEFINITION
+            scalar Email @deprecated(reason: "Use String")
+            type Query {
+                user(id: ID @audit): String @unknown
+                email: Email
+            }
+        "#,
+        );
+        let errors: Vec<_> = result
+            .diagnostics
+            .iter()
+            .map(|d| (d.code.as_str(), d.title.as_str()))
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                (
+                    codes::DIRECTIVE_NOT_ALLOWED,
+                    "Directive `@deprecated` cannot be used on SCALAR"
+                ),
+                (codes::UNKNOWN_DIRECTIVE, "Unknown directive `@unknown`"),
+                (
+                    codes::DIRECTIVE_NOT_ALLOWED,
+                    "Directive `@audit` cannot be used on ARGUMENT_DEFINITION"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_placed_directives() {
+        let result = check_source(
+            r#"
+            directive @audit on FIELD_DEFINITION | ARGUMENT_DEFINITION
+            scalar Email @specifiedBy(url: "https://html.spec.whatwg.org")
+            enum Status { ACTIVE INACTIVE @deprecated }
+            type Query {
+                user(id: ID @audit): String @audit @deprecated(reason: "Use users")
+            }
+            query GetUser @priority(level: 1) {
+                user(id: "1") @skip(if: false)
+                ... @defer { other: user(id: "2") }
+            }
+        "#,
+        );
+        assert!(result.is_ok(), "{:?}", result.diagnostics);
+    }
+
+    #[test]
+    fn test_documented_directives() {
+        let result = check_source(
+            r#"
+            opaque EmailAddress = String @email
+            opaque Slug = String @pattern(regex: "^[a-z0-9-]+$")
+            opaque Percentage = Float @range(min: 0, max: 100)
+            opaque PositiveInt = Int @positive
+            input UserInput @validate {
+                name: String @minLength(value: 1)
+                tags: List<String> @maxItems(value: 10)
+            }
+            type User {
+                analytics: Option<String> @defer(label: "analytics")
+                comments: List<String> @stream(initialCount: 5)
+            }
+            type Query {
+                me: Option<User> @cache(maxAge: 60, scope: PUBLIC)
+            }
+        "#,
+        );
+        assert!(result.is_ok(), "{:?}", result.diagnostics);
+    }
+
+    #[test]
+    fn test_positional_directive_arguments() {
+        let result = check_source(
+            r#"
+            opaque Username = String @minLength(3) @maxLength(20) @pattern("^[a-zA-Z0-9_]+$")
+            input PageInput {
+                first: Option<Int> @min(1) @max(100)
+            }
+            type Query {
+                users(page: PageInput): List<String>
+            }
+        "#,
+        );
+        assert!(result.is_ok(), "{:?}", result.diagnostics);
+
+        let result = check_source(
+            r#"
+            opaque Username = String @minLength("three") @email(1)
+            type Query { name: Username }
+        "#,
+        );
+        let errors: Vec<_> = result
+            .diagnostics
+            .iter()
+            .map(|d| (d.code.as_str(), d.title.as_str()))
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                (codes::TYPE_MISMATCH, "Expected `Int`, found a string"),
+                (
+                    codes::UNKNOWN_ARGUMENT,
+                    "Too many arguments for directive `@email`"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_directive_arguments() {
+        let result = check_source(
+            r#"
+            enum Level { INFO WARN }
+            directive @log(level: Level, tag: Option<String>) on FIELD_DEFINITION
+            type Query {
+                a: String @deprecated(resaon: "Use b")
+                b: String @log
+                c: String @log(level: DEBUG, tag: 1)
+                d: String @log(level: WARN) @deprecated(reason: "Use b")
+            }
+        "#,
+        );
+        let errors: Vec<_> = result
+            .diagnostics
+            .iter()
+            .map(|d| (d.code.as_str(), d.title.as_str()))
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                (
+                    codes::UNKNOWN_ARGUMENT,
+                    "Unknown argument `resaon` on directive `@deprecated`"
+                ),
+                (
+                    codes::MISSING_REQUIRED_ARGUMENT,
+                    "Missing required argument `level` of directive `@log`"
+                ),
+                (
+                    codes::TYPE_MISMATCH,
+                    "Expected `Level`, found enum value `DEBUG`"
+                ),
+                (codes::TYPE_MISMATCH, "Expected `String`, found an integer"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_directive_argument_types() {
+        let valid = check_source(
+            r#"
+            type Query {
+                search(term: String @minLength(value: 3)): String
+            }
+        "#,
+        );
+        assert!(valid.is_ok(), "{:?}", valid.diagnostics);
+
+        let result = check_source(
+            r#"
+            directive @retry(times: Int = "3", backoff: Missing) on FIELD_DEFINITION
+            type Query {
+                search(term: String @minLength(value: "3")): String
+            }
+        "#,
+        );
+        let errors: Vec<_> = result
+            .diagnostics
+            .iter()
+            .map(|d| (d.code.as_str(), d.title.as_str()))
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                (codes::TYPE_MISMATCH, "Expected `Int`, found a string"),
+                (codes::UNDEFINED_TYPE, "Undefined type `Missing`"),
+                (codes::TYPE_MISMATCH, "Expected `Int`, found a string"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_undefined_type_suggestion() {
+        let source = r#"
+            type Profile { bio: String }
+            type User { profile: Profiel }
+            type Post { author: Writer }
+        "#;
+        let result = check_source(source);
+        let notes: Vec<_> = result
+            .diagnostics
+            .iter()
+            .filter(|d| d.code == codes::UNDEFINED_TYPE)
+            .map(|d| (d.title.as_str(), d.notes.clone()))
+            .collect();
+        assert_eq!(
+            notes,
+            [
+                (
+                    "Undefined type `Profiel`",
+                    vec!["did you mean `Profile`?".to_string()]
+                ),
+                ("Undefined type `Writer`", vec![]),
+            ]
+        );
+
+        let suggestion = &result.diagnostics.iter().next().unwrap().suggestions[0];
+        assert_eq!(suggestion.replacement, "Profile");
+        let span = suggestion.span;
+        assert_eq!(&source[span.start as usize..span.end as usize], "Profiel");
+    }
+
+    #[test]
+    fn test_opaque_type() {
+        let result = check_source(
+            r#"
+            opaque Email = String
+            type User {
+                email: Email
+            }
+        "#,
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_cyclic_type_alias() {
+        let result = check_source(
+            r#"
+            alias UserRef = Option<Account>
+            alias Account = UserRef
+            type Query {
+                me: Account
+            }
+        "#,
+        );
+        let cycles: Vec<_> = result
+            .diagnostics
+            .iter()
+            .filter(|d| d.code == codes::CYCLIC_TYPE_ALIAS)
+            .collect();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(
+            cycles[0].title,
+            "Cyclic type alias detected: UserRef -> Account -> UserRef"
+        );
+    }
+
+    #[test]
+    fn test_self_referential_opaque_type() {
+        let result = check_source("opaque Email = Email");
+        assert!(result
+            .diagnostics
+            .iter()
+            .any(|d| d.code == codes::CYCLIC_TYPE_ALIAS
+                && d.title == "Cyclic type alias detected: Email -> Email"));
+
+        // Wrapping itself in a container is not an expansion cycle
+        let result = check_source("opaque Tree = List<Tree>");
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_schema_root_type_must_exist() {
+        let result = check_source(
+            r#"
+            schema {
+                query: Query
+                mutation: Mutation
+            }
+            type Query {
+                hello: String
+            }
+        "#,
+        );
+        let errors: Vec<_> = result
+            .diagnostics
+            .iter()
+            .map(|d| d.title.as_str())
+            .collect();
+        assert_eq!(errors, ["Undefined type `Mutation`"]);
+    }
+
+    #[test]
+    fn test_schema_duplicate_root_operation() {
+        let result = check_source(
+            r#"
+            schema {
+                query: Query
+                query: Other
+            }
+            type Query {
+                hello: String
+            }
+            type Other {
+                hello: String
+            }
+        "#,
+        );
+        assert!(result
+            .diagnostics
+            .iter()
+            .any(|d| d.code == codes::DUPLICATE_SCHEMA
+                && d.title == "Duplicate `query` root operation type"));
+    }
+
+    #[test]
+    fn test_operation_without_root_type() {
+        let result = check_source("query { hello }");
+        assert!(result.is_ok());
+        assert!(result
+            .diagnostics
+            .iter()
+            .any(|d| d.code == codes::MISSING_ROOT_TYPE));
+
+        let result = check_source("type Query { hello: String }\nquery { hello }");
+        assert!(!result
+            .diagnostics
+            .iter()
+            .any(|d| d.code == codes::MISSING_ROOT_TYPE));
+    }
+
+    #[test]
+    fn test_required_field_cycle_warns() {
+        let result = check_source(
+            r#"
+            type A {
+                id: ID
+                b: B
+            }
+            type B {
+                a: A
+            }
+        "#,
+        );
+        assert!(result.is_ok());
+        let cycles: Vec<_> = result
+            .diagnostics
+            .iter()
+            .filter(|d| d.code == codes::CYCLIC_TYPE_REFERENCE)
+            .collect();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(
+            cycles[0].title,
+            "Required field cycle detected: A.b -> B.a -> A"
+        );
+    }
+
+    #[test]
+    fn test_required_input_field_cycle_errors() {
+        let result = check_source(
+            r#"
+            input A {
+                b: B
+            }
+            input B {
+                a: A
+            }
+        "#,
+        );
+        assert!(!result.is_ok());
+        let cycle = result
+            .diagnostics
+            .errors()
+            .find(|d| d.code == codes::CYCLIC_TYPE_REFERENCE)
+            .unwrap();
+        assert_eq!(
+            cycle.title,
+            "Required field cycle detected: A.b -> B.a -> A"
+        );
+    }
+
+    #[test]
+    fn test_optional_field_breaks_cycle() {
+        let result = check_source(
+            r#"
+            type A {
+                b: B
+            }
+            type B {
+                a: Option<A>
+                all: List<A>
+            }
+        "#,
+        );
+        assert!(!result
+            .diagnostics
+            .iter()
+            .any(|d| d.code == codes::CYCLIC_TYPE_REFERENCE));
+    }
+
+    #[test]
+    fn test_recursive_types() {
+        // Recursion through `Option` or `List` always terminates
+        let result = check_source(
+            r#"
+            type Tree {
+                value: Int
+                children: List<Tree>
+                parent: Option<Tree>
+                siblings: Option<List<Tree>>
+            }
+            type Query {
+                tree: Tree
+            }
+        "#,
+        );
+        assert!(result.is_ok());
+        assert!(result.diagnostics.is_empty(), "{:?}", result.diagnostics);
+
+        // A required self-reference, directly or through an alias, does not
+        let result = check_source(
+            r#"
+            type Node {
+                child: Node
+            }
+            type Chain {
+                next: Link
+            }
+            alias Link = Chain
+            alias MaybeChain = Option<Chain>
+            type Terminated {
+                next: MaybeChain
+            }
+        "#,
+        );
+        assert!(result.is_ok(), "{:?}", result.diagnostics);
+        let cycles: Vec<_> = result
+            .diagnostics
+            .iter()
+            .filter(|d| d.code == codes::CYCLIC_TYPE_REFERENCE)
+            .map(|d| d.title.as_str())
+            .collect();
+        assert_eq!(
+            cycles,
+            [
+                "Required field cycle detected: Node.child -> Node",
+                "Required field cycle detected: Chain.next -> Chain",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_object_type_warns() {
+        let result = check_source(
+            r#"
+            type Foo {}
+            type Query {
+                foo: Foo
+            }
+        "#,
+        );
+        assert!(result.is_ok());
+        let warnings: Vec<_> = result
+            .diagnostics
+            .iter()
+            .filter(|d| d.code == codes::EMPTY_TYPE)
+            .collect();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].title, "Type `Foo` has no fields");
+    }
+
+    fn conflicting_selections(operation: &str) -> Vec<String> {
+        let source = format!(
+            r#"
+            type User {{
+                id: ID
+                name: String
+            }}
+            type Query {{
+                user(id: ID): Option<User>
+                me: Option<User>
+            }}
+            {operation}
+        "#
+        );
+        check_source(&source)
+            .diagnostics
+            .iter()
+            .filter(|d| d.code == codes::CONFLICTING_SELECTION)
+            .map(|d| d.title.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_conflicting_alias_arguments() {
+        assert_eq!(
+            conflicting_selections(r#"query { a: user(id: 1) { id } a: user(id: 2) { id } }"#),
+            ["Fields `a` conflict because they have different arguments"]
+        );
+        assert_eq!(
+            conflicting_selections(r#"query { a: user(id: 1) { id } a: me { id } }"#),
+            ["Fields `a` conflict because `user` and `me` are different fields"]
+        );
+        // Conflicts inside merged sub-selections and fragments are found too
+        assert_eq!(
+            conflicting_selections(
+                r#"
+                query { me { ...Names } me { n: id } }
+                fragment Names on User { n: name }
+                "#
+            ),
+            ["Fields `n` conflict because `name` and `id` are different fields"]
+        );
+    }
+
+    #[test]
+    fn test_mergeable_selections() {
+        assert!(conflicting_selections(
+            r#"query { a: user(id: 1) { id } a: user(id: 1) { name } }"#
+        )
+        .is_empty());
+        assert!(
+            conflicting_selections(r#"query { a: user(id: 1) { id } b: user(id: 2) { id } }"#)
+                .is_empty()
+        );
+        // Recursive fragments don't hang the check
+        assert!(conflicting_selections(
+            r#"
+            query { me { ...Recursive } }
+            fragment Recursive on User { id ...Recursive }
+            "#
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_union_member_warns() {
+        let source = r#"
+            type A {
+                id: ID
+            }
+            union U = A | A
+        "#;
+        let result = check_source(source);
+        assert!(result.is_ok());
+        let warning = result
+            .diagnostics
+            .iter()
+            .find(|d| d.code == codes::DUPLICATE_UNION_MEMBER)
+            .unwrap();
+        let span = warning.primary_span().unwrap();
+        assert_eq!(span.start as usize, source.rfind('A').unwrap());
+    }
+
+    #[test]
+    fn test_unused_types_warn() {
+        let result = check_source(
+            r#"
+            directive @log(level: Level) on FIELD_DEFINITION
+            enum Level { INFO WARN }
+            interface Node { id: ID }
+            type User implements Node { id: ID }
+            type Orphan { id: ID }
+            scalar Unused
+            type Query {
+                node(id: ID): Option<Node>
+            }
+        "#,
+        );
+        assert!(result.is_ok());
+        let unused: Vec<_> = result
+            .diagnostics
+            .iter()
+            .filter(|d| d.code == codes::UNUSED_TYPE)
+            .map(|d| d.title.as_str())
+            .collect();
+        assert_eq!(
+            unused,
+            ["Type `Orphan` is never used", "Type `Unused` is never used"]
+        );
+    }
+
+    #[test]
+    fn test_deprecated_without_reason_warns() {
+        let result = check_source(
+            r#"
+            enum Status { ACTIVE INACTIVE @deprecated }
+            type Query {
+                status: Status @deprecated(reason: "Use state")
+            }
+        "#,
+        );
+        assert!(result.is_ok());
+        let warnings: Vec<_> = result
+            .diagnostics
+            .iter()
+            .filter(|d| d.code == codes::DEPRECATED_WITHOUT_REASON)
+            .collect();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].title, "Deprecation without a reason");
+    }
+
+    #[test]
+    fn test_extend_enum_adds_value() {
+        let result = check_source(
+            r#"
+            enum Status {
+                Active
+            }
+            extend enum Status {
+                Archived
+            }
+            type Query {
+                items(status: Status = Archived): List<String>
+            }
+        "#,
+        );
+        assert!(result.is_ok(), "{:?}", result.diagnostics);
+    }
+
+    #[test]
+    fn test_extend_undefined_enum() {
+        let source = r#"
+            extend enum Status {
+                Archived
+            }
+        "#;
+        let result = check_source(source);
+        let errors: Vec<_> = result
+            .diagnostics
+            .iter()
+            .filter(|d| d.code == codes::INVALID_EXTENSION_TARGET)
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].title, "Cannot extend undefined type `Status`");
+        assert_eq!(
+            errors[0].primary_span().unwrap().start as usize,
+            source.find("Status").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_extend_schema_and_interface() {
         let result = check_source(
             r#"
-            type User {
+            schema {
+                query: Query
+            }
+            extend schema {
+                subscription: Subscription
+            }
+            interface Node {
                 id: ID
-                name: String
+            }
+            extend interface Node {
+                createdAt: String
+            }
+            type Query {
+                node: Option<Node>
+            }
+            type Subscription {
+                updated: String
+            }
+            type Item implements Node {
+                id: ID
+            }
+            extend type Item {
+                createdAt: String
             }
         "#,
         );
-        assert!(result.is_ok());
-    }
+        assert!(result.is_ok(), "{:?}", result.diagnostics);
 
-    #[test]
-    fn test_undefined_type() {
         let result = check_source(
             r#"
-            type User {
+            type Status {
                 id: ID
-                profile: Profile
+            }
+            extend enum Status {
+                Archived
             }
         "#,
         );
-        assert!(!result.is_ok());
         assert!(result
             .diagnostics
             .iter()
-            .any(|d| d.code == codes::UNDEFINED_TYPE));
+            .any(|d| d.code == codes::INVALID_EXTENSION_TARGET
+                && d.title == "`extend enum` cannot extend `Status`"));
     }
 
     #[test]
-    fn test_duplicate_field() {
-        let result = check_source(
-            r#"
-            type User {
-                id: ID
-                name: String
-                name: Int
+    fn test_default_value_type_mismatch() {
+        let source = r#"
+            type Query {
+                users(count: Int = "hi", flag: Boolean = 3): List<String>
             }
-        "#,
-        );
-        assert!(!result.is_ok());
-        assert!(result
+        "#;
+        let result = check_source(source);
+        let mismatches: Vec<_> = result
             .diagnostics
             .iter()
-            .any(|d| d.code == codes::DUPLICATE_FIELD));
+            .filter(|d| d.code == codes::TYPE_MISMATCH)
+            .collect();
+        assert_eq!(mismatches.len(), 2);
+        assert_eq!(mismatches[0].title, "Expected `Int`, found a string");
+        assert_eq!(
+            mismatches[0].primary_span().unwrap().start as usize,
+            source.find("\"hi\"").unwrap()
+        );
+        assert_eq!(mismatches[1].title, "Expected `Boolean`, found an integer");
     }
 
     #[test]
-    fn test_duplicate_type() {
+    fn test_valid_default_values() {
         let result = check_source(
             r#"
-            type User {
-                id: ID
+            enum Role {
+                ADMIN
+                MEMBER
             }
-            type User {
-                name: String
+            input UserFilter {
+                role: Role
+                name: Option<String>
+            }
+            type Query {
+                users(
+                    role: Role = MEMBER
+                    roles: List<Role> = [ADMIN, MEMBER]
+                    after: Option<String> = null
+                    ratio: Float = 1
+                    filter: UserFilter = { role: ADMIN }
+                ): List<String>
             }
         "#,
         );
-        assert!(!result.is_ok());
-        assert!(result
-            .diagnostics
-            .iter()
-            .any(|d| d.code == codes::DUPLICATE_TYPE));
-    }
+        assert!(result.is_ok(), "{:?}", result.diagnostics);
 
-    #[test]
-    fn test_undefined_interface() {
         let result = check_source(
             r#"
-            type User implements Node {
-                id: ID
+            enum Role {
+                ADMIN
+            }
+            type Query {
+                users(role: Role = OWNER): List<String>
             }
         "#,
         );
-        assert!(!result.is_ok());
         assert!(result
             .diagnostics
             .iter()
-            .any(|d| d.code == codes::UNDEFINED_TYPE));
+            .any(|d| d.title == "Expected `Role`, found enum value `OWNER`"));
     }
 
     #[test]
-    fn test_valid_interface_implementation() {
+    fn test_duplicate_interface_implementation() {
         let result = check_source(
             r#"
             interface Node {
                 id: ID
             }
-            type User implements Node {
+            type User implements Node & Node {
                 id: ID
-                name: String
             }
         "#,
         );
-        assert!(result.is_ok());
-    }
+        let duplicates: Vec<_> = result
+            .diagnostics
+            .iter()
+            .filter(|d| d.code == codes::DUPLICATE_INTERFACE_IMPLEMENTATION)
+            .collect();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(
+            duplicates[0].title,
+            "Interface `Node` is implemented more than once"
+        );
 
-    #[test]
-    fn test_valid_union() {
         let result = check_source(
             r#"
-            type User {
+            interface Node {
                 id: ID
             }
-            type Post {
+            interface Timestamped {
+                createdAt: String
+            }
+            type User implements Node & Timestamped {
                 id: ID
+                createdAt: String
             }
-            union SearchResult = User | Post
         "#,
         );
         assert!(result.is_ok());
     }
 
     #[test]
-    fn test_undefined_union_member() {
+    fn test_missing_interface_field() {
         let result = check_source(
             r#"
-            type User {
+            interface Node {
                 id: ID
             }
-            union SearchResult = User | Post
+            type User implements Node {
+                name: String
+            }
         "#,
         );
         assert!(!result.is_ok());
         assert!(result
             .diagnostics
             .iter()
-            .any(|d| d.code == codes::UNDEFINED_TYPE));
+            .any(|d| d.code == codes::MISSING_INTERFACE_FIELD));
     }
 
     #[test]
-    fn test_valid_input_type() {
+    fn test_interface_field_type_mismatch() {
         let result = check_source(
             r#"
-            input CreateUserInput {
+            interface Node {
+                id: ID
+            }
+            type User implements Node {
+                id: String
                 name: String
-                email: String
             }
         "#,
         );
-        assert!(result.is_ok());
+        assert!(!result.is_ok());
+        let diag = result
+            .diagnostics
+            .iter()
+            .find(|d| d.code == codes::INTERFACE_FIELD_TYPE_MISMATCH)
+            .unwrap();
+        assert_eq!(diag.labels.len(), 2);
+        assert_eq!(diag.labels[0].message, "Expected `ID` but found `String`");
+        assert_eq!(diag.labels[1].message, "expected type declared here");
+        assert!(diag.labels[1].span.start < diag.labels[0].span.start);
     }
 
     #[test]
-    fn test_option_and_list_types() {
+    fn test_interface_field_extra_optional_arg() {
         let result = check_source(
             r#"
-            type User {
+            interface Node {
                 id: ID
-                nickname: Option<String>
-                friends: List<User>
+                posts(first: Int): List<String>
             }
-        "#,
-        );
-        assert!(result.is_ok());
-    }
-
-    #[test]
-    fn test_enum_with_data() {
-        let result = check_source(
-            r#"
-            enum Result {
-                Ok(String)
-                Err { message: String, code: Int }
+            type User implements Node {
+                id: ID
+                posts(first: Int, after: Option<String>, order: String = "asc"): List<String>
             }
         "#,
         );
@@ -1288,69 +4014,71 @@ mod tests {
     }
 
     #[test]
-    fn test_input_enum() {
+    fn test_interface_field_extra_required_arg() {
         let result = check_source(
             r#"
-            input enum LoginMethod {
-                Email { email: String, password: String }
-                OAuth { provider: String }
-                Anonymous
+            interface Node {
+                id: ID
+                posts(first: Int): List<String>
             }
-        "#,
-        );
-        assert!(result.is_ok());
-    }
-
-    #[test]
-    fn test_opaque_type() {
-        let result = check_source(
-            r#"
-            opaque Email = String
-            type User {
-                email: Email
+            type User implements Node {
+                id: ID
+                posts(first: Int, after: String): List<String>
             }
         "#,
         );
-        assert!(result.is_ok());
+        assert!(!result.is_ok());
+        assert!(result
+            .diagnostics
+            .iter()
+            .any(|d| d.code == codes::INTERFACE_FIELD_EXTRA_REQUIRED_ARG));
     }
 
     #[test]
-    fn test_missing_interface_field() {
+    fn test_interface_field_missing_arg() {
         let result = check_source(
             r#"
             interface Node {
                 id: ID
+                posts(first: Int, after: Option<String>): List<String>
             }
             type User implements Node {
-                name: String
+                id: ID
+                posts(after: Option<String>): List<String>
             }
         "#,
         );
-        assert!(!result.is_ok());
-        assert!(result
+        let mismatches: Vec<_> = result
             .diagnostics
             .iter()
-            .any(|d| d.code == codes::MISSING_INTERFACE_FIELD));
+            .filter(|d| d.code == codes::INTERFACE_FIELD_ARGUMENT_MISMATCH)
+            .collect();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(
+            mismatches[0].title,
+            "Field `posts` is missing argument `first`"
+        );
     }
 
     #[test]
-    fn test_interface_field_type_mismatch() {
+    fn test_interface_field_arg_type_mismatch() {
         let result = check_source(
             r#"
             interface Node {
                 id: ID
+                posts(first: Int): List<String>
             }
             type User implements Node {
-                id: String
-                name: String
+                id: ID
+                posts(first: String): List<String>
             }
         "#,
         );
-        assert!(!result.is_ok());
         assert!(result
             .diagnostics
             .iter()
-            .any(|d| d.code == codes::INTERFACE_FIELD_TYPE_MISMATCH));
+            .any(|d| d.code == codes::INTERFACE_FIELD_ARGUMENT_MISMATCH
+                && d.title == "Argument `first` of field `posts` has incompatible type"));
     }
 
     #[test]
@@ -1625,4 +4353,195 @@ mod tests {
             .iter()
             .any(|d| d.code == codes::GENERIC_CONSTRAINT_VIOLATION));
     }
+
+    #[test]
+    fn test_name_with_leading_digit() {
+        let interner = Interner::new();
+        let mut parser = Parser::new("type Foo { id: ID }", &interner);
+        let mut doc = parser.parse_document();
+        // The lexer never produces such a name, but other frontends can.
+        if let Definition::Type(TypeDefinition::Object(obj)) = &mut doc.definitions[0] {
+            obj.name.value = interner.intern("1Foo");
+        }
+        let types = TypeRegistry::new();
+        let hir = HirDatabase::new();
+        let result = check(&doc, &types, &hir, &interner);
+        assert!(!result.is_ok());
+        assert!(result
+            .diagnostics
+            .iter()
+            .any(|d| d.code == codes::INVALID_NAME));
+    }
+
+    #[test]
+    fn test_unicode_name_allowed_by_default() {
+        let result = check_source(
+            r#"
+            type Café {
+                prénom: String
+            }
+        "#,
+        );
+        assert!(result.is_ok());
+        assert!(!result
+            .diagnostics
+            .iter()
+            .any(|d| d.code == codes::INVALID_NAME));
+    }
+
+    #[test]
+    fn test_unicode_name_in_graphql_compat_mode() {
+        let interner = Interner::new();
+        let mut parser = Parser::new(
+            r#"
+            type Café {
+                prénom: String
+            }
+        "#,
+            &interner,
+        );
+        let doc = parser.parse_document();
+        let types = TypeRegistry::new();
+        let hir = HirDatabase::new();
+        let result = TypeChecker::new_graphql_compat(&types, &hir, &interner).check(&doc);
+        assert!(!result.is_ok());
+        assert_eq!(
+            result
+                .diagnostics
+                .iter()
+                .filter(|d| d.code == codes::INVALID_NAME)
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_unused_type_parameter() {
+        let result = check_source(
+            r#"
+            type Box<T> {
+                value: String
+            }
+        "#,
+        );
+        assert!(result.is_ok());
+        let warnings: Vec<_> = result
+            .diagnostics
+            .warnings()
+            .filter(|d| d.code == codes::UNUSED_TYPE_PARAMETER)
+            .collect();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].title.contains("`T`"));
+    }
+
+    #[test]
+    fn test_used_type_parameter() {
+        let result = check_source(
+            r#"
+            type Box<T> {
+                value: Option<T>
+            }
+
+            interface Lookup<K, V> {
+                get(key: K): List<V>
+            }
+        "#,
+        );
+        assert!(result.is_ok());
+        assert!(!result
+            .diagnostics
+            .iter()
+            .any(|d| d.code == codes::UNUSED_TYPE_PARAMETER));
+    }
+
+    #[test]
+    fn test_incremental_check() {
+        let before = r#"
+            type User { id: ID posts: List<Post> }
+            type Post { title: String }
+            type Tag { name: String @minLength(value: "1") }
+            type Comment { body: Strng }
+        "#;
+        let after = r#"
+            type User { id: ID posts: List<Post> }
+            type Post { title: String score: Int }
+            type Tag { name: String @minLength(value: "1") }
+            type Comment { body: Strng }
+        "#;
+        let types = TypeRegistry::new();
+        let hir = HirDatabase::new();
+        let interner = Interner::new();
+        let parse = |source| Parser::new(source, &interner).parse_document();
+
+        let previous = TypeChecker::new(&types, &hir, &interner).check(&parse(before));
+        assert_eq!(previous.checked_types, ["User", "Post", "Tag", "Comment"]);
+
+        let document = parse(after);
+        let result = TypeChecker::new(&types, &hir, &interner).check_incremental(
+            &document,
+            &previous,
+            &["Post"],
+        );
+        // `User` references `Post`; `Tag` and `Comment` are unrelated
+        assert_eq!(result.checked_types, ["User", "Post"]);
+
+        // Reused diagnostics moved with their definitions
+        let full = TypeChecker::new(&types, &hir, &interner).check(&document);
+        let spans = |result: &CheckResult| -> Vec<_> {
+            result
+                .diagnostics
+                .iter()
+                .map(|d| (d.code.clone(), d.primary_span()))
+                .collect()
+        };
+        assert_eq!(spans(&result), spans(&full));
+        let typo = result
+            .diagnostics
+            .iter()
+            .find(|d| d.code == codes::UNDEFINED_TYPE)
+            .and_then(|d| d.primary_span())
+            .unwrap();
+        assert!(after[typo.start as usize..].starts_with("Strng"));
+    }
+
+    #[test]
+    fn test_incremental_check_follows_directives() {
+        let before = r#"
+            directive @log(level: Level) on FIELD_DEFINITION
+            enum Level { INFO WARN }
+            type Query { users: String @log(level: WARN) }
+            type Post { title: String }
+        "#;
+        let after = before.replace("INFO WARN", "INFO");
+        let types = TypeRegistry::new();
+        let hir = HirDatabase::new();
+        let interner = Interner::new();
+        let parse = |source| Parser::new(source, &interner).parse_document();
+
+        let previous = TypeChecker::new(&types, &hir, &interner).check(&parse(before));
+        assert!(previous.is_ok());
+
+        // `Query` passes a `Level` value to `@log`, which takes a `Level`
+        let document = parse(&after);
+        let result = TypeChecker::new(&types, &hir, &interner).check_incremental(
+            &document,
+            &previous,
+            &["Level"],
+        );
+        assert_eq!(result.checked_types, ["Level", "Query"]);
+        assert!(!result.is_ok());
+        let full = TypeChecker::new(&types, &hir, &interner).check(&document);
+        let codes = |result: &CheckResult| -> Vec<_> {
+            result.diagnostics.iter().map(|d| d.code.clone()).collect()
+        };
+        assert_eq!(codes(&result), codes(&full));
+
+        // Changing the directive definition rechecks the types applying it
+        let result = TypeChecker::new(&types, &hir, &interner).check_incremental(
+            &parse(before),
+            &previous,
+            &["@log"],
+        );
+        assert_eq!(result.checked_types, ["Query"]);
+    }
 }
@@ -0,0 +1,169 @@
+//! Deprecation info from `@deprecated` directives.
+
+use bgql_core::{Interner, Text};
+use bgql_syntax::{Definition, Directive, Document, FieldDefinition, TypeDefinition, Value};
+use rustc_hash::FxHashMap;
+
+/// Deprecation status of a field or enum value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Deprecation {
+    pub is_deprecated: bool,
+    /// The `reason` argument of `@deprecated`, if given.
+    pub deprecation_reason: Option<String>,
+}
+
+impl Deprecation {
+    /// Reads the `@deprecated` directive among `directives`.
+    pub fn from_directives(directives: &[Directive<'_>], interner: &Interner) -> Self {
+        let Some(directive) = directives
+            .iter()
+            .find(|directive| interner.get(directive.name.value) == "deprecated")
+        else {
+            return Self::default();
+        };
+
+        let deprecation_reason = directive.arguments.iter().find_map(|arg| {
+            match (interner.get(arg.name.value).as_str(), &arg.value) {
+                ("reason", Value::String(reason, _)) => Some(reason.clone()),
+                _ => None,
+            }
+        });
+        Self {
+            is_deprecated: true,
+            deprecation_reason,
+        }
+    }
+}
+
+/// Deprecation status of the fields and enum values of a document.
+///
+/// Covers the fields of object, interface and input object types and the
+/// values of enums. Members that aren't deprecated, or don't exist, report
+/// [`Deprecation::default`].
+#[derive(Debug, Default)]
+pub struct DeprecationIndex {
+    /// Deprecated members, keyed by type and member name
+    deprecated: FxHashMap<(String, String), Deprecation>,
+}
+
+impl DeprecationIndex {
+    /// Collects the deprecated members of `document`.
+    pub fn new(document: &Document<'_>, interner: &Interner) -> Self {
+        let mut index = Self::default();
+        for definition in &document.definitions {
+            let Definition::Type(type_def) = definition else {
+                continue;
+            };
+            match type_def {
+                TypeDefinition::Object(obj) => {
+                    index.add_fields(interner.get(obj.name.value), &obj.fields, interner);
+                }
+                TypeDefinition::Interface(iface) => {
+                    index.add_fields(interner.get(iface.name.value), &iface.fields, interner);
+                }
+                TypeDefinition::Input(input) => {
+                    let type_name = interner.get(input.name.value);
+                    for field in &input.fields {
+                        index.add(&type_name, field.name.value, &field.directives, interner);
+                    }
+                }
+                TypeDefinition::Enum(e) => {
+                    let type_name = interner.get(e.name.value);
+                    for value in &e.values {
+                        index.add(&type_name, value.name.value, &value.directives, interner);
+                    }
+                }
+                _ => {}
+            }
+        }
+        index
+    }
+
+    /// Returns the deprecation status of `type_name.field_name`.
+    pub fn field(&self, type_name: &str, field_name: &str) -> Deprecation {
+        self.get(type_name, field_name)
+    }
+
+    /// Returns the deprecation status of the enum value `enum_name.value`.
+    pub fn enum_value(&self, enum_name: &str, value: &str) -> Deprecation {
+        self.get(enum_name, value)
+    }
+
+    fn get(&self, type_name: &str, member: &str) -> Deprecation {
+        self.deprecated
+            .get(&(type_name.to_string(), member.to_string()))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn add_fields(
+        &mut self,
+        type_name: String,
+        fields: &[FieldDefinition<'_>],
+        interner: &Interner,
+    ) {
+        for field in fields {
+            self.add(&type_name, field.name.value, &field.directives, interner);
+        }
+    }
+
+    fn add(
+        &mut self,
+        type_name: &str,
+        member: Text,
+        directives: &[Directive<'_>],
+        interner: &Interner,
+    ) {
+        let deprecation = Deprecation::from_directives(directives, interner);
+        if deprecation.is_deprecated {
+            self.deprecated
+                .insert((type_name.to_string(), interner.get(member)), deprecation);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bgql_syntax::parse;
+
+    #[test]
+    fn test_deprecated_field() {
+        let interner = Interner::new();
+        let result = parse(
+            r#"
+            type User {
+                id: ID
+                username: String @deprecated(reason: "Use `name`")
+                name: String
+            }
+
+            enum Role {
+                ADMIN
+                ROOT @deprecated
+            }
+            "#,
+            &interner,
+        );
+        assert!(!result.diagnostics.has_errors());
+        let index = DeprecationIndex::new(&result.document, &interner);
+
+        let username = index.field("User", "username");
+        assert!(username.is_deprecated);
+        assert_eq!(username.deprecation_reason.as_deref(), Some("Use `name`"));
+        assert!(index.enum_value("Role", "ROOT").is_deprecated);
+        assert_eq!(index.enum_value("Role", "ROOT").deprecation_reason, None);
+    }
+
+    #[test]
+    fn test_field_without_deprecation() {
+        let interner = Interner::new();
+        let result = parse("type User {\n  name: String\n}", &interner);
+        let index = DeprecationIndex::new(&result.document, &interner);
+
+        let name = index.field("User", "name");
+        assert!(!name.is_deprecated);
+        assert_eq!(name.deprecation_reason, None);
+        assert!(!index.enum_value("Role", "ADMIN").is_deprecated);
+    }
+}
@@ -75,8 +75,11 @@ pub enum HirTypeKind {
     Union,
     Enum,
     InputObject,
+    InputUnion,
+    InputEnum,
     Scalar,
     Opaque,
+    TypeAlias,
 }
 
 /// A field in HIR.
@@ -137,6 +140,10 @@ pub struct HirFieldSelection {
     pub name: String,
     pub arguments: Vec<(String, HirValue)>,
     pub selections: Vec<HirSelection>,
+    /// Set when the field is marked `@stream`.
+    pub stream: Option<HirStream>,
+    /// The field's `@skip` and `@include` directives.
+    pub condition: HirCondition,
 }
 
 /// An inline fragment in HIR.
@@ -144,6 +151,47 @@ pub struct HirFieldSelection {
 pub struct HirInlineFragment {
     pub type_condition: Option<String>,
     pub selections: Vec<HirSelection>,
+    /// Set when the fragment is marked `@defer`.
+    pub defer: Option<HirDefer>,
+    /// The fragment's `@skip` and `@include` directives.
+    pub condition: HirCondition,
+}
+
+/// A named fragment definition in HIR.
+#[derive(Debug, Clone)]
+pub struct HirFragment {
+    pub name: String,
+    pub type_condition: String,
+    pub selections: Vec<HirSelection>,
+}
+
+/// A `@defer` applied to an inline fragment.
+#[derive(Debug, Clone, Default)]
+pub struct HirDefer {
+    pub label: Option<String>,
+}
+
+/// The `if` arguments of the `@skip` and `@include` directives of a
+/// selection. Without either, the selection is always executed.
+#[derive(Debug, Clone, Default)]
+pub struct HirCondition {
+    pub skip: Option<HirValue>,
+    pub include: Option<HirValue>,
+}
+
+impl HirCondition {
+    /// Returns true if neither `@skip` nor `@include` is present.
+    pub fn is_unconditional(&self) -> bool {
+        self.skip.is_none() && self.include.is_none()
+    }
+}
+
+/// The arguments of a `@stream` directive.
+#[derive(Debug, Clone, Default)]
+pub struct HirStream {
+    pub label: Option<String>,
+    /// Number of items delivered with the initial response.
+    pub initial_count: usize,
 }
 
 /// A value in HIR.
@@ -4,10 +4,13 @@
 //! - `hir`: High-level intermediate representation
 //! - `types`: Type system
 //! - `checker`: Type checking
+//! - `deprecation`: Deprecation status of fields and enum values
 
 pub mod checker;
+pub mod deprecation;
 pub mod hir;
 pub mod types;
 
+pub use deprecation::{Deprecation, DeprecationIndex};
 pub use hir::{DefId, HirDatabase};
 pub use types::{Type, TypeRegistry};
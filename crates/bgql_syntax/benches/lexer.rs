@@ -0,0 +1,29 @@
+//! Lexer and parser throughput over the example schema.
+//!
+//! Run with `cargo bench -p bgql_syntax`.
+
+use bgql_core::Interner;
+use bgql_syntax::lexer::tokenize;
+use bgql_syntax::parse;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+/// The example schema repeated into a ~270 KB document.
+fn large_schema() -> String {
+    include_str!("../../../examples/schema.bgql").repeat(20)
+}
+
+fn bench_lexer(c: &mut Criterion) {
+    let source = large_schema();
+    let interner = Interner::new();
+
+    let mut group = c.benchmark_group("syntax");
+    group.throughput(Throughput::Bytes(source.len() as u64));
+    group.bench_function("tokenize", |b| {
+        b.iter(|| tokenize(black_box(&source), &interner))
+    });
+    group.bench_function("parse", |b| b.iter(|| parse(black_box(&source), &interner)));
+    group.finish();
+}
+
+criterion_group!(benches, bench_lexer);
+criterion_main!(benches);
@@ -2,6 +2,7 @@
 
 use crate::token::DirectiveLocation;
 use bgql_core::{Span, Text};
+use std::borrow::Cow;
 
 /// A complete document.
 #[derive(Debug, Clone)]
@@ -20,6 +21,26 @@ pub enum Definition<'a> {
     Fragment(FragmentDefinition<'a>),
     Module(ModuleDeclaration<'a>),
     Use(UseStatement<'a>),
+    /// `extend schema { ... }`, adding root operations to the schema.
+    SchemaExtension(SchemaDefinition<'a>),
+    /// `extend type`, `extend interface`, `extend enum`, ..., adding members
+    /// to the type of the same name.
+    TypeExtension(TypeDefinition<'a>),
+}
+
+impl Definition<'_> {
+    /// Returns the span of the definition.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Schema(s) | Self::SchemaExtension(s) => s.span,
+            Self::Type(t) | Self::TypeExtension(t) => t.span(),
+            Self::Directive(d) => d.span,
+            Self::Operation(o) => o.span,
+            Self::Fragment(f) => f.span,
+            Self::Module(m) => m.span,
+            Self::Use(u) => u.span,
+        }
+    }
 }
 
 /// Schema definition.
@@ -62,6 +83,72 @@ pub enum TypeDefinition<'a> {
     InputEnum(InputEnumTypeDefinition<'a>),
 }
 
+impl TypeDefinition<'_> {
+    /// Returns the name of the defined type.
+    pub fn name(&self) -> Name {
+        match self {
+            Self::Object(obj) => obj.name,
+            Self::Interface(iface) => iface.name,
+            Self::Union(u) => u.name,
+            Self::Enum(e) => e.name,
+            Self::Input(i) => i.name,
+            Self::Scalar(s) => s.name,
+            Self::Opaque(o) => o.name,
+            Self::TypeAlias(ta) => ta.name,
+            Self::InputUnion(iu) => iu.name,
+            Self::InputEnum(ie) => ie.name,
+        }
+    }
+
+    /// Returns the keyword that introduces the definition.
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            Self::Object(_) => "type",
+            Self::Interface(_) => "interface",
+            Self::Union(_) => "union",
+            Self::Enum(_) => "enum",
+            Self::Input(_) => "input",
+            Self::Scalar(_) => "scalar",
+            Self::Opaque(_) => "opaque",
+            Self::TypeAlias(_) => "alias",
+            Self::InputUnion(_) => "input union",
+            Self::InputEnum(_) => "input enum",
+        }
+    }
+
+    /// Returns the span of the definition.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Object(obj) => obj.span,
+            Self::Interface(iface) => iface.span,
+            Self::Union(u) => u.span,
+            Self::Enum(e) => e.span,
+            Self::Input(i) => i.span,
+            Self::Scalar(s) => s.span,
+            Self::Opaque(o) => o.span,
+            Self::TypeAlias(ta) => ta.span,
+            Self::InputUnion(iu) => iu.span,
+            Self::InputEnum(ie) => ie.span,
+        }
+    }
+
+    /// Returns the directives applied to the type itself.
+    pub fn directives(&self) -> &[Directive<'_>] {
+        match self {
+            Self::Object(obj) => &obj.directives,
+            Self::Interface(iface) => &iface.directives,
+            Self::Union(u) => &u.directives,
+            Self::Enum(e) => &e.directives,
+            Self::Input(i) => &i.directives,
+            Self::Scalar(s) => &s.directives,
+            Self::Opaque(o) => &o.directives,
+            Self::TypeAlias(_) => &[],
+            Self::InputUnion(iu) => &iu.directives,
+            Self::InputEnum(ie) => &ie.directives,
+        }
+    }
+}
+
 /// Object type definition.
 #[derive(Debug, Clone)]
 pub struct ObjectTypeDefinition<'a> {
@@ -314,6 +401,9 @@ pub struct Directive<'a> {
 }
 
 /// Argument.
+///
+/// A directive argument given without a name, as in `@minLength(3)`, has an
+/// empty name.
 #[derive(Debug, Clone)]
 pub struct Argument<'a> {
     pub name: Name,
@@ -399,7 +489,11 @@ pub struct InlineFragment<'a> {
 #[derive(Debug, Clone)]
 pub enum Value<'a> {
     Variable(Name),
+    /// Integer literal in the 32-bit range of `Int`, stored as `i64`.
+    /// Literals outside that range are reported by the parser.
     Int(i64, Span),
+    /// Float literal. Literals that overflow to infinity are reported by the
+    /// parser.
     Float(f64, Span),
     String(String, Span),
     Boolean(bool, Span),
@@ -411,6 +505,23 @@ pub enum Value<'a> {
     _Phantom(std::marker::PhantomData<&'a ()>),
 }
 
+impl Value<'_> {
+    /// Returns the span of the value.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Variable(name) | Self::Enum(name) => name.span,
+            Self::Int(_, span)
+            | Self::Float(_, span)
+            | Self::String(_, span)
+            | Self::Boolean(_, span)
+            | Self::Null(span)
+            | Self::List(_, span)
+            | Self::Object(_, span) => *span,
+            Self::_Phantom(_) => Span::default(),
+        }
+    }
+}
+
 /// Name with span.
 #[derive(Debug, Clone, Copy)]
 pub struct Name {
@@ -425,15 +536,21 @@ impl Name {
 }
 
 /// Description (documentation string).
+///
+/// The value has its escape sequences decoded, so it only borrows from the
+/// source when the string contains none.
 #[derive(Debug, Clone)]
 pub struct Description<'a> {
-    pub value: &'a str,
+    pub value: Cow<'a, str>,
     pub span: Span,
 }
 
 impl<'a> Description<'a> {
-    pub fn new(value: &'a str, span: Span) -> Self {
-        Self { value, span }
+    pub fn new(value: impl Into<Cow<'a, str>>, span: Span) -> Self {
+        Self {
+            value: value.into(),
+            span,
+        }
     }
 }
 
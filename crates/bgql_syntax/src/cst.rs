@@ -0,0 +1,285 @@
+//! Lossless concrete syntax tree.
+//!
+//! The CST keeps every token of the source, including whitespace, newlines
+//! and comments, so printing it reproduces the source byte for byte. Tokens
+//! are grouped into a node per top-level definition and a node per
+//! bracketed group. The AST is parsed from the same source, and each
+//! definition node records the index of its AST definition.
+
+use crate::ast::Document;
+use crate::lexer::Lexer;
+use crate::parser::parse;
+use crate::token::{Token, TokenKind};
+use bgql_core::{DiagnosticBag, Interner, Span};
+use std::fmt;
+
+/// The kind of a CST node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    /// The whole source.
+    Document,
+    /// A top-level definition.
+    Definition,
+    /// `{ ... }`
+    Braces,
+    /// `( ... )`
+    Parens,
+    /// `[ ... ]`
+    Brackets,
+}
+
+impl NodeKind {
+    fn closer(self) -> Option<TokenKind> {
+        match self {
+            Self::Braces => Some(TokenKind::RBrace),
+            Self::Parens => Some(TokenKind::RParen),
+            Self::Brackets => Some(TokenKind::RBracket),
+            Self::Document | Self::Definition => None,
+        }
+    }
+
+    fn opened_by(kind: TokenKind) -> Option<Self> {
+        match kind {
+            TokenKind::LBrace => Some(Self::Braces),
+            TokenKind::LParen => Some(Self::Parens),
+            TokenKind::LBracket => Some(Self::Brackets),
+            _ => None,
+        }
+    }
+}
+
+/// A child of a CST node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(Token),
+}
+
+impl SyntaxElement {
+    /// Returns the span of the element.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Node(node) => node.span,
+            Self::Token(token) => token.span,
+        }
+    }
+}
+
+/// A CST node. Its children cover its span without gaps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxNode {
+    pub kind: NodeKind,
+    pub span: Span,
+    /// Index into [`Document::definitions`] for definition nodes.
+    pub definition: Option<usize>,
+    pub children: Vec<SyntaxElement>,
+}
+
+impl SyntaxNode {
+    fn new(kind: NodeKind, children: Vec<SyntaxElement>) -> Self {
+        let span = match (children.first(), children.last()) {
+            (Some(first), Some(last)) => Span::new(first.span().start, last.span().end),
+            _ => Span::new(0, 0),
+        };
+        Self {
+            kind,
+            span,
+            definition: None,
+            children,
+        }
+    }
+
+    /// Returns the child nodes.
+    pub fn nodes(&self) -> impl Iterator<Item = &SyntaxNode> {
+        self.children.iter().filter_map(|child| match child {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+
+    /// Returns every token under the node in source order.
+    pub fn tokens(&self) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        self.collect_tokens(&mut tokens);
+        tokens
+    }
+
+    fn collect_tokens(&self, tokens: &mut Vec<Token>) {
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => node.collect_tokens(tokens),
+                SyntaxElement::Token(token) => tokens.push(*token),
+            }
+        }
+    }
+}
+
+/// A concrete syntax tree over its source.
+#[derive(Debug, Clone)]
+pub struct SyntaxTree<'a> {
+    source: &'a str,
+    root: SyntaxNode,
+}
+
+impl<'a> SyntaxTree<'a> {
+    /// Returns the source the tree was built from.
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// Returns the document node.
+    pub fn root(&self) -> &SyntaxNode {
+        &self.root
+    }
+
+    /// Returns the source text of a node.
+    pub fn text(&self, node: &SyntaxNode) -> &'a str {
+        &self.source[node.span.start as usize..node.span.end as usize]
+    }
+
+    /// Returns the source text of a token.
+    pub fn token_text(&self, token: &Token) -> &'a str {
+        &self.source[token.span.start as usize..token.span.end as usize]
+    }
+}
+
+impl fmt::Display for SyntaxTree<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for token in self.root.tokens() {
+            f.write_str(self.token_text(&token))?;
+        }
+        Ok(())
+    }
+}
+
+/// Result of parsing in CST mode.
+#[derive(Debug)]
+pub struct CstParseResult<'a> {
+    pub tree: SyntaxTree<'a>,
+    pub document: Document<'a>,
+    pub diagnostics: DiagnosticBag,
+}
+
+/// Parses a source string into a lossless syntax tree and its AST.
+pub fn parse_cst<'a>(source: &'a str, interner: &'a Interner) -> CstParseResult<'a> {
+    let result = parse(source, interner);
+
+    let mut lexer = Lexer::with_trivia(source, interner);
+    let mut tokens = Vec::new();
+    loop {
+        let token = lexer.next_token();
+        if token.is_eof() {
+            break;
+        }
+        tokens.push(token);
+    }
+
+    let mut children = Vec::new();
+    let mut rest = tokens.as_slice();
+    for (index, definition) in result.document.definitions.iter().enumerate() {
+        let span = definition.span();
+        let before = rest.partition_point(|token| token.span.start < span.start);
+        children.extend(group(&rest[..before]));
+        rest = &rest[before..];
+
+        let len = rest.partition_point(|token| token.span.start < span.end);
+        if len > 0 {
+            let mut node = SyntaxNode::new(NodeKind::Definition, group(&rest[..len]));
+            node.definition = Some(index);
+            children.push(SyntaxElement::Node(node));
+            rest = &rest[len..];
+        }
+    }
+    children.extend(group(rest));
+
+    let mut root = SyntaxNode::new(NodeKind::Document, children);
+    root.span = Span::new(0, source.len() as u32);
+
+    CstParseResult {
+        tree: SyntaxTree { source, root },
+        document: result.document,
+        diagnostics: result.diagnostics,
+    }
+}
+
+/// Nests `tokens` into bracketed groups. Unmatched closers stay plain tokens
+/// and unclosed groups end with the tokens.
+fn group(tokens: &[Token]) -> Vec<SyntaxElement> {
+    let mut stack: Vec<(NodeKind, Vec<SyntaxElement>)> = Vec::new();
+    let mut current = Vec::new();
+
+    for &token in tokens {
+        if let Some(kind) = NodeKind::opened_by(token.kind) {
+            stack.push((kind, std::mem::take(&mut current)));
+            current.push(SyntaxElement::Token(token));
+        } else if stack
+            .last()
+            .is_some_and(|(kind, _)| kind.closer() == Some(token.kind))
+        {
+            current.push(SyntaxElement::Token(token));
+            let (kind, parent) = stack.pop().expect("checked above");
+            let node = SyntaxNode::new(kind, std::mem::replace(&mut current, parent));
+            current.push(SyntaxElement::Node(node));
+        } else {
+            current.push(SyntaxElement::Token(token));
+        }
+    }
+
+    while let Some((kind, parent)) = stack.pop() {
+        let node = SyntaxNode::new(kind, std::mem::replace(&mut current, parent));
+        current.push(SyntaxElement::Node(node));
+    }
+    current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(source: &str) {
+        let interner = Interner::new();
+        let result = parse_cst(source, &interner);
+        assert_eq!(result.tree.to_string(), source);
+    }
+
+    #[test]
+    fn test_cst_round_trip() {
+        round_trip("");
+        round_trip(
+            "# Users\n\"\"\"\nA user.\n\"\"\"\ntype User implements Node {\n  id: ID  # primary key\n\tposts(first: Int = 10, tags: [String]): List<Post> @deprecated\n}\r\n\r\nunion Result =\n  | User\n  | Error\n\nquery GetUser($id: ID) {\n  user(id: $id) { ...UserFields }\n}\n",
+        );
+        round_trip("\u{feff}type Café {\n  naïve: String\n}   \n\n\n# trailing comment");
+        // Invalid source is kept as-is too
+        round_trip("type User {\n  id: ID\n  ) → }\n}}\ninput {");
+    }
+
+    #[test]
+    fn test_cst_structure() {
+        let source = "# leading\ntype User {\n  posts(first: Int): List<Post>\n}\n\nenum Role {\n  ADMIN\n}\n";
+        let interner = Interner::new();
+        let result = parse_cst(source, &interner);
+        let root = result.tree.root();
+
+        assert_eq!(root.kind, NodeKind::Document);
+        assert_eq!(root.span, Span::new(0, source.len() as u32));
+        assert_eq!(
+            root.children.first().map(|c| match c {
+                SyntaxElement::Token(token) => token.kind,
+                SyntaxElement::Node(_) => TokenKind::Eof,
+            }),
+            Some(TokenKind::Comment)
+        );
+
+        let definitions: Vec<_> = root.nodes().collect();
+        assert_eq!(definitions.len(), 2);
+        assert_eq!(definitions[0].definition, Some(0));
+        assert_eq!(definitions[1].definition, Some(1));
+        assert!(result.tree.text(definitions[1]).starts_with("enum Role {"));
+
+        let body = definitions[0].nodes().next().unwrap();
+        assert_eq!(body.kind, NodeKind::Braces);
+        let arguments = body.nodes().next().unwrap();
+        assert_eq!(arguments.kind, NodeKind::Parens);
+        assert_eq!(result.tree.text(arguments), "(first: Int)");
+    }
+}
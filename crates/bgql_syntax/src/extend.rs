@@ -0,0 +1,200 @@
+//! Merging of `extend` definitions into the definitions they extend.
+
+use crate::ast::*;
+use bgql_core::Span;
+
+/// An `extend` definition that has nothing to extend.
+#[derive(Debug, Clone)]
+pub enum ExtensionError {
+    /// `extend schema` without a `schema` definition.
+    UndefinedSchema { span: Span },
+    /// `extend` of a type that isn't defined.
+    UndefinedType { name: Name },
+    /// `extend` with another keyword than the type's definition, such as
+    /// `extend enum` of an object type.
+    KindMismatch {
+        name: Name,
+        extension: &'static str,
+        definition: &'static str,
+    },
+}
+
+/// Merges the `extend schema` and `extend type` definitions into the
+/// definitions they extend, returning the definitions without extensions.
+///
+/// Extensions apply to the definitions of the same module, after every
+/// definition has been seen, so a type can be extended before it's defined.
+/// Extensions without a target are left out and reported.
+pub fn merge_extensions<'a>(
+    definitions: &[Definition<'a>],
+) -> (Vec<Definition<'a>>, Vec<ExtensionError>) {
+    let mut errors = Vec::new();
+    let merged = merge_definitions(definitions, &mut errors);
+    (merged, errors)
+}
+
+fn merge_definitions<'a>(
+    definitions: &[Definition<'a>],
+    errors: &mut Vec<ExtensionError>,
+) -> Vec<Definition<'a>> {
+    let mut merged = Vec::with_capacity(definitions.len());
+    let mut extensions = Vec::new();
+    for definition in definitions {
+        match definition {
+            Definition::SchemaExtension(_) | Definition::TypeExtension(_) => {
+                extensions.push(definition);
+            }
+            Definition::Module(module) => {
+                let mut module = module.clone();
+                if let Some(body) = &module.body {
+                    module.body = Some(merge_definitions(body, errors));
+                }
+                merged.push(Definition::Module(module));
+            }
+            _ => merged.push(definition.clone()),
+        }
+    }
+
+    for extension in extensions {
+        let result = match extension {
+            Definition::SchemaExtension(schema) => merge_schema_extension(&mut merged, schema),
+            Definition::TypeExtension(type_def) => merge_type_extension(&mut merged, type_def),
+            _ => Ok(()),
+        };
+        if let Err(error) = result {
+            errors.push(error);
+        }
+    }
+    merged
+}
+
+/// Adds the root operations and directives of `extension` to the schema
+/// definition.
+fn merge_schema_extension<'a>(
+    definitions: &mut [Definition<'a>],
+    extension: &SchemaDefinition<'a>,
+) -> Result<(), ExtensionError> {
+    let schema = definitions
+        .iter_mut()
+        .find_map(|def| match def {
+            Definition::Schema(schema) => Some(schema),
+            _ => None,
+        })
+        .ok_or(ExtensionError::UndefinedSchema {
+            span: extension.span,
+        })?;
+    schema
+        .directives
+        .extend(extension.directives.iter().cloned());
+    schema
+        .operations
+        .extend(extension.operations.iter().cloned());
+    Ok(())
+}
+
+/// Adds the members and directives of `extension` to the type it extends.
+fn merge_type_extension<'a>(
+    definitions: &mut [Definition<'a>],
+    extension: &TypeDefinition<'a>,
+) -> Result<(), ExtensionError> {
+    let name = extension.name();
+    let target = definitions
+        .iter_mut()
+        .find_map(|def| match def {
+            Definition::Type(type_def) if type_def.name().value == name.value => Some(type_def),
+            _ => None,
+        })
+        .ok_or(ExtensionError::UndefinedType { name })?;
+
+    match (target, extension) {
+        (TypeDefinition::Object(target), TypeDefinition::Object(ext)) => {
+            target.implements.extend(ext.implements.iter().cloned());
+            target.directives.extend(ext.directives.iter().cloned());
+            target.fields.extend(ext.fields.iter().cloned());
+        }
+        (TypeDefinition::Interface(target), TypeDefinition::Interface(ext)) => {
+            target.implements.extend(ext.implements.iter().cloned());
+            target.directives.extend(ext.directives.iter().cloned());
+            target.fields.extend(ext.fields.iter().cloned());
+        }
+        (TypeDefinition::Union(target), TypeDefinition::Union(ext)) => {
+            target.directives.extend(ext.directives.iter().cloned());
+            target.members.extend(ext.members.iter().cloned());
+        }
+        (TypeDefinition::Enum(target), TypeDefinition::Enum(ext)) => {
+            target.directives.extend(ext.directives.iter().cloned());
+            target.values.extend(ext.values.iter().cloned());
+        }
+        (TypeDefinition::Input(target), TypeDefinition::Input(ext)) => {
+            target.directives.extend(ext.directives.iter().cloned());
+            target.fields.extend(ext.fields.iter().cloned());
+        }
+        (TypeDefinition::Scalar(target), TypeDefinition::Scalar(ext)) => {
+            target.directives.extend(ext.directives.iter().cloned());
+        }
+        (target, _) => {
+            return Err(ExtensionError::KindMismatch {
+                name,
+                extension: extension.keyword(),
+                definition: target.keyword(),
+            })
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+    use bgql_core::Interner;
+
+    #[test]
+    fn test_merge_extensions() {
+        let interner = Interner::new();
+        let result = parse(
+            r#"
+            extend type Query { posts: List<String> }
+            type Query { users: List<String> }
+            extend enum Role { ADMIN }
+            extend input Missing { id: ID }
+            enum Role { USER }
+            extend union Role = User
+            "#,
+            &interner,
+        );
+        assert!(!result.diagnostics.has_errors());
+
+        let (definitions, errors) = merge_extensions(&result.document.definitions);
+        assert_eq!(definitions.len(), 2);
+        match &definitions[0] {
+            Definition::Type(TypeDefinition::Object(query)) => {
+                let fields: Vec<_> = query
+                    .fields
+                    .iter()
+                    .map(|f| interner.get(f.name.value))
+                    .collect();
+                assert_eq!(fields, ["users", "posts"]);
+            }
+            other => panic!("expected the query type, got {other:?}"),
+        }
+        match &definitions[1] {
+            Definition::Type(TypeDefinition::Enum(role)) => assert_eq!(role.values.len(), 2),
+            other => panic!("expected the enum, got {other:?}"),
+        }
+
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            &errors[0],
+            ExtensionError::UndefinedType { name } if interner.get(name.value) == "Missing"
+        ));
+        assert!(matches!(
+            errors[1],
+            ExtensionError::KindMismatch {
+                extension: "union",
+                definition: "enum",
+                ..
+            }
+        ));
+    }
+}
@@ -1,17 +1,34 @@
 //! Code formatting for Better GraphQL.
 
 use crate::ast::*;
-use bgql_core::Interner;
+use bgql_core::{Interner, Text};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Formatting options.
+///
+/// With the `serde` feature, options (de)serialize with camelCase names and
+/// missing ones take their default.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(default, rename_all = "camelCase")
+)]
 pub struct FormatOptions {
     /// Number of spaces for indentation.
     pub indent_size: usize,
     /// Use tabs instead of spaces.
     pub use_tabs: bool,
-    /// Maximum line width.
+    /// Maximum line width. Longer `implements` clauses, union members and
+    /// field arguments wrap onto continuation lines.
     pub max_width: usize,
+    /// Keep type bodies with a single field on one line, e.g. `type X { id: ID }`,
+    /// as long as the line fits in `max_width`. When false, bodies always expand.
+    pub collapse_single_field: bool,
+    /// Sort fields of object, interface and input types by name.
+    pub sort_fields: bool,
 }
 
 impl Default for FormatOptions {
@@ -20,6 +37,8 @@ impl Default for FormatOptions {
             indent_size: 2,
             use_tabs: false,
             max_width: 80,
+            collapse_single_field: false,
+            sort_fields: false,
         }
     }
 }
@@ -66,6 +85,14 @@ impl<'a> Formatter<'a> {
             Definition::Fragment(f) => self.format_fragment(f),
             Definition::Module(m) => self.format_module(m),
             Definition::Use(u) => self.format_use(u),
+            Definition::SchemaExtension(s) => {
+                self.output.push_str("extend ");
+                self.format_schema(s);
+            }
+            Definition::TypeExtension(t) => {
+                self.output.push_str("extend ");
+                self.format_type_definition(t);
+            }
         }
     }
 
@@ -181,54 +208,44 @@ impl<'a> Formatter<'a> {
         if let Some(desc) = &obj.description {
             self.format_description(desc);
         }
+        self.format_visibility(&obj.visibility);
         self.output.push_str("type ");
         self.output.push_str(&self.interner.get(obj.name.value));
         self.format_type_params(&obj.type_params);
         self.format_implements(&obj.implements);
         self.format_directives(&obj.directives);
-        self.output.push_str(" {\n");
-        self.indent += 1;
-        self.format_fields(&obj.fields);
-        self.indent -= 1;
-        self.output.push('}');
+        self.format_fields_body(&obj.fields);
     }
 
     fn format_interface_type(&mut self, iface: &InterfaceTypeDefinition<'_>) {
         if let Some(desc) = &iface.description {
             self.format_description(desc);
         }
+        self.format_visibility(&iface.visibility);
         self.output.push_str("interface ");
         self.output.push_str(&self.interner.get(iface.name.value));
         self.format_type_params(&iface.type_params);
         self.format_implements(&iface.implements);
         self.format_directives(&iface.directives);
-        self.output.push_str(" {\n");
-        self.indent += 1;
-        self.format_fields(&iface.fields);
-        self.indent -= 1;
-        self.output.push('}');
+        self.format_fields_body(&iface.fields);
     }
 
     fn format_union_type(&mut self, u: &UnionTypeDefinition<'_>) {
         if let Some(desc) = &u.description {
             self.format_description(desc);
         }
+        self.format_visibility(&u.visibility);
         self.output.push_str("union ");
         self.output.push_str(&self.interner.get(u.name.value));
         self.format_directives(&u.directives);
-        self.output.push_str(" = ");
-        for (i, member) in u.members.iter().enumerate() {
-            if i > 0 {
-                self.output.push_str(" | ");
-            }
-            self.output.push_str(&self.interner.get(member.value));
-        }
+        self.format_wrapped_list(" =", '|', &u.members);
     }
 
     fn format_enum_type(&mut self, e: &EnumTypeDefinition<'_>) {
         if let Some(desc) = &e.description {
             self.format_description(desc);
         }
+        self.format_visibility(&e.visibility);
         self.output.push_str("enum ");
         self.output.push_str(&self.interner.get(e.name.value));
         self.format_directives(&e.directives);
@@ -284,27 +301,41 @@ impl<'a> Formatter<'a> {
         if let Some(desc) = &inp.description {
             self.format_description(desc);
         }
+        self.format_visibility(&inp.visibility);
         self.output.push_str("input ");
         self.output.push_str(&self.interner.get(inp.name.value));
         self.format_directives(&inp.directives);
+
+        if let [field] = inp.fields.as_slice() {
+            if field.description.is_none() && self.try_single_line(|f| f.format_input_field(field))
+            {
+                return;
+            }
+        }
+
         self.output.push_str(" {\n");
         self.indent += 1;
-        for field in &inp.fields {
+        for field in self.ordered(&inp.fields, |field| field.name.value) {
             self.push_indent();
-            self.output.push_str(&self.interner.get(field.name.value));
-            self.output.push_str(": ");
-            self.format_type(&field.ty);
-            self.format_directives(&field.directives);
+            self.format_input_field(field);
             self.output.push('\n');
         }
         self.indent -= 1;
         self.output.push('}');
     }
 
+    fn format_input_field(&mut self, field: &InputValueDefinition<'_>) {
+        self.output.push_str(&self.interner.get(field.name.value));
+        self.output.push_str(": ");
+        self.format_type(&field.ty);
+        self.format_directives(&field.directives);
+    }
+
     fn format_scalar_type(&mut self, s: &ScalarTypeDefinition<'_>) {
         if let Some(desc) = &s.description {
             self.format_description(desc);
         }
+        self.format_visibility(&s.visibility);
         self.output.push_str("scalar ");
         self.output.push_str(&self.interner.get(s.name.value));
         self.format_directives(&s.directives);
@@ -314,6 +345,7 @@ impl<'a> Formatter<'a> {
         if let Some(desc) = &o.description {
             self.format_description(desc);
         }
+        self.format_visibility(&o.visibility);
         self.output.push_str("opaque ");
         self.output.push_str(&self.interner.get(o.name.value));
         self.output.push_str(" = ");
@@ -335,22 +367,18 @@ impl<'a> Formatter<'a> {
         if let Some(desc) = &iu.description {
             self.format_description(desc);
         }
+        self.format_visibility(&iu.visibility);
         self.output.push_str("input union ");
         self.output.push_str(&self.interner.get(iu.name.value));
         self.format_directives(&iu.directives);
-        self.output.push_str(" = ");
-        for (i, member) in iu.members.iter().enumerate() {
-            if i > 0 {
-                self.output.push_str(" | ");
-            }
-            self.output.push_str(&self.interner.get(member.value));
-        }
+        self.format_wrapped_list(" =", '|', &iu.members);
     }
 
     fn format_input_enum_type(&mut self, ie: &InputEnumTypeDefinition<'_>) {
         if let Some(desc) = &ie.description {
             self.format_description(desc);
         }
+        self.format_visibility(&ie.visibility);
         self.output.push_str("input enum ");
         self.output.push_str(&self.interner.get(ie.name.value));
         self.format_directives(&ie.directives);
@@ -397,31 +425,115 @@ impl<'a> Formatter<'a> {
         // TODO: Implement
     }
 
+    /// Formats a `{ ... }` field body, on one line if allowed by the options.
+    fn format_fields_body(&mut self, fields: &[FieldDefinition<'_>]) {
+        if let [field] = fields {
+            if field.description.is_none() && self.try_single_line(|f| f.format_field(field)) {
+                return;
+            }
+        }
+
+        self.output.push_str(" {\n");
+        self.indent += 1;
+        self.format_fields(fields);
+        self.indent -= 1;
+        self.output.push('}');
+    }
+
+    /// Writes ` { <item> }` if single-field collapsing is enabled and the
+    /// resulting line fits. Returns false, leaving the output untouched, otherwise.
+    fn try_single_line(&mut self, write_item: impl FnOnce(&mut Self)) -> bool {
+        if !self.options.collapse_single_field {
+            return false;
+        }
+
+        let start = self.output.len();
+        self.output.push_str(" { ");
+        write_item(self);
+        self.output.push_str(" }");
+
+        let line_start = self.output.rfind('\n').map_or(0, |i| i + 1);
+        let line = &self.output[line_start..];
+        if line_start <= start && line.chars().count() <= self.options.max_width {
+            true
+        } else {
+            self.output.truncate(start);
+            false
+        }
+    }
+
+    /// Returns `items` in source order, or sorted by name if `sort_fields` is set.
+    fn ordered<'b, T>(&self, items: &'b [T], name: impl Fn(&T) -> Text) -> Vec<&'b T> {
+        let mut items: Vec<_> = items.iter().collect();
+        if self.options.sort_fields {
+            items.sort_by_cached_key(|item| self.interner.get(name(item)));
+        }
+        items
+    }
+
     fn format_fields(&mut self, fields: &[FieldDefinition<'_>]) {
-        for field in fields {
+        for field in self.ordered(fields, |field| field.name.value) {
             if let Some(desc) = &field.description {
                 self.push_indent();
                 self.format_description(desc);
             }
             self.push_indent();
-            self.output.push_str(&self.interner.get(field.name.value));
-            if !field.arguments.is_empty() {
-                self.output.push('(');
-                for (i, arg) in field.arguments.iter().enumerate() {
-                    if i > 0 {
-                        self.output.push_str(", ");
-                    }
-                    self.output.push_str(&self.interner.get(arg.name.value));
-                    self.output.push_str(": ");
-                    self.format_type(&arg.ty);
-                }
-                self.output.push(')');
+            self.format_field(field);
+            self.output.push('\n');
+        }
+    }
+
+    fn format_field(&mut self, field: &FieldDefinition<'_>) {
+        self.output.push_str(&self.interner.get(field.name.value));
+        let start = self.output.len();
+        self.format_field_signature(field, false);
+        if !field.arguments.is_empty() && self.line_width() > self.options.max_width {
+            self.output.truncate(start);
+            self.format_field_signature(field, true);
+        }
+    }
+
+    /// Writes the arguments, type and directives of a field, with one argument
+    /// per line if `wrap` is set.
+    fn format_field_signature(&mut self, field: &FieldDefinition<'_>, wrap: bool) {
+        if !field.arguments.is_empty() {
+            self.format_arguments(&field.arguments, wrap);
+        }
+        self.output.push_str(": ");
+        self.format_type(&field.ty);
+        self.format_directives(&field.directives);
+    }
+
+    /// Writes `(a: T, b: U)`, or one argument per line with trailing commas
+    /// if `wrap` is set.
+    fn format_arguments(&mut self, arguments: &[InputValueDefinition<'_>], wrap: bool) {
+        self.output.push('(');
+        self.indent += 1;
+        for (i, arg) in arguments.iter().enumerate() {
+            if wrap {
+                self.output.push('\n');
+                self.push_indent();
+            } else if i > 0 {
+                self.output.push_str(", ");
             }
+            self.output.push_str(&self.interner.get(arg.name.value));
             self.output.push_str(": ");
-            self.format_type(&field.ty);
-            self.format_directives(&field.directives);
+            self.format_type(&arg.ty);
+            if let Some(default) = &arg.default_value {
+                self.output.push_str(" = ");
+                self.format_value(default);
+            }
+            self.format_directives(&arg.directives);
+            if wrap {
+                self.output.push(',');
+            }
+        }
+        self.indent -= 1;
+        if wrap {
             self.output.push('\n');
+            self.push_indent();
         }
+        self.output.push(')');
     }
 
     fn format_type(&mut self, ty: &Type<'_>) {
@@ -490,13 +602,44 @@ impl<'a> Formatter<'a> {
         if implements.is_empty() {
             return;
         }
-        self.output.push_str(" implements ");
-        for (i, name) in implements.iter().enumerate() {
+        self.format_wrapped_list(" implements", '&', implements);
+    }
+
+    /// Writes `prefix` and `names` separated by `separator`, on one line if it
+    /// fits in `max_width`. Otherwise each name goes on a continuation line,
+    /// indented one level deeper and led by `separator`.
+    fn format_wrapped_list(&mut self, prefix: &str, separator: char, names: &[Name]) {
+        let start = self.output.len();
+        self.output.push_str(prefix);
+        for (i, name) in names.iter().enumerate() {
             if i > 0 {
-                self.output.push_str(" & ");
+                self.output.push(' ');
+                self.output.push(separator);
             }
+            self.output.push(' ');
             self.output.push_str(&self.interner.get(name.value));
         }
+        if self.line_width() <= self.options.max_width {
+            return;
+        }
+
+        self.output.truncate(start);
+        self.output.push_str(prefix);
+        self.indent += 1;
+        for name in names {
+            self.output.push('\n');
+            self.push_indent();
+            self.output.push(separator);
+            self.output.push(' ');
+            self.output.push_str(&self.interner.get(name.value));
+        }
+        self.indent -= 1;
+    }
+
+    /// Returns the width of the last line of the output.
+    fn line_width(&self) -> usize {
+        let line_start = self.output.rfind('\n').map_or(0, |i| i + 1);
+        self.output[line_start..].chars().count()
     }
 
     fn format_directives(&mut self, directives: &[Directive<'_>]) {
@@ -510,8 +653,11 @@ impl<'a> Formatter<'a> {
                     if i > 0 {
                         self.output.push_str(", ");
                     }
-                    self.output.push_str(&self.interner.get(arg.name.value));
-                    self.output.push_str(": ");
+                    let name = self.interner.get(arg.name.value);
+                    if !name.is_empty() {
+                        self.output.push_str(&name);
+                        self.output.push_str(": ");
+                    }
                     self.format_value(&arg.value);
                 }
                 self.output.push(')');
@@ -529,12 +675,15 @@ impl<'a> Formatter<'a> {
                 self.output.push_str(&n.to_string());
             }
             Value::Float(n, _) => {
-                self.output.push_str(&n.to_string());
+                // Keep a fraction so the literal stays a Float
+                let text = n.to_string();
+                self.output.push_str(&text);
+                if !text.contains(['.', 'e', 'E']) {
+                    self.output.push_str(".0");
+                }
             }
             Value::String(s, _) => {
-                self.output.push('"');
-                self.output.push_str(s);
-                self.output.push('"');
+                self.push_string(s);
             }
             Value::Boolean(b, _) => {
                 self.output.push_str(if *b { "true" } else { "false" });
@@ -571,23 +720,47 @@ impl<'a> Formatter<'a> {
         }
     }
 
+    /// Writes a description as a block string when it spans several lines
+    /// and as a quoted string otherwise. Block string lines are re-indented
+    /// to the current level.
     fn format_description(&mut self, desc: &Description<'_>) {
-        if desc.value.contains('\n') {
+        let lines = block_string_lines(&desc.value);
+        if lines.len() > 1 {
             self.output.push_str("\"\"\"\n");
-            for line in desc.value.lines() {
-                self.push_indent();
-                self.output.push_str(line);
+            for line in lines {
+                if !line.is_empty() {
+                    self.push_indent();
+                    self.output.push_str(&line.replace("\"\"\"", "\\\"\"\""));
+                }
                 self.output.push('\n');
             }
             self.push_indent();
             self.output.push_str("\"\"\"\n");
         } else {
-            self.output.push('"');
-            self.output.push_str(desc.value);
-            self.output.push_str("\"\n");
+            self.push_string(lines.first().copied().unwrap_or_default());
+            self.output.push('\n');
         }
     }
 
+    /// Writes a quoted string, escaping characters that can't appear raw.
+    fn push_string(&mut self, value: &str) {
+        self.output.push('"');
+        for c in value.chars() {
+            match c {
+                '"' => self.output.push_str("\\\""),
+                '\\' => self.output.push_str("\\\\"),
+                '\n' => self.output.push_str("\\n"),
+                '\r' => self.output.push_str("\\r"),
+                '\t' => self.output.push_str("\\t"),
+                c if c.is_control() => {
+                    self.output.push_str(&format!("\\u{:04X}", c as u32));
+                }
+                c => self.output.push(c),
+            }
+        }
+        self.output.push('"');
+    }
+
     fn push_indent(&mut self) {
         if self.options.use_tabs {
             for _ in 0..self.indent {
@@ -601,6 +774,46 @@ impl<'a> Formatter<'a> {
     }
 }
 
+/// Splits a description into lines the way GraphQL reads a block string:
+/// the indentation common to every line after the first is removed, along
+/// with leading and trailing blank lines. A value without line breaks is
+/// kept as-is.
+fn block_string_lines(value: &str) -> Vec<&str> {
+    if !value.contains('\n') {
+        return vec![value];
+    }
+
+    let is_blank = |line: &str| line.trim_matches([' ', '\t']).is_empty();
+    let indent_of = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+    let common_indent = value
+        .lines()
+        .skip(1)
+        .filter(|line| !is_blank(line))
+        .map(indent_of)
+        .min()
+        .unwrap_or(0);
+
+    let mut lines: Vec<_> = value
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if is_blank(line) {
+                ""
+            } else if i == 0 {
+                line
+            } else {
+                &line[common_indent..]
+            }
+        })
+        .collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    let leading = lines.iter().take_while(|line| line.is_empty()).count();
+    lines.drain(..leading);
+    lines
+}
+
 /// Formats a document with default options.
 pub fn format(document: &Document<'_>, interner: &Interner) -> String {
     let mut formatter = Formatter::new(interner, FormatOptions::default());
@@ -616,3 +829,258 @@ pub fn format_with_options(
     let mut formatter = Formatter::new(interner, options);
     formatter.format(document)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    fn format_source(source: &str, options: &FormatOptions) -> String {
+        let interner = Interner::new();
+        let result = parse(source, &interner);
+        assert!(!result.diagnostics.has_errors());
+        format_with_options(&result.document, &interner, options.clone())
+    }
+
+    fn assert_idempotent(formatted: &str, options: &FormatOptions) {
+        assert_eq!(format_source(formatted, options), formatted);
+    }
+
+    #[test]
+    fn test_single_field_type_expanded_by_default() {
+        let options = FormatOptions::default();
+        let formatted = format_source("type X { id: ID }\ninput Filter { name: String }", &options);
+        assert_eq!(
+            formatted,
+            "type X {\n  id: ID\n}\n\ninput Filter {\n  name: String\n}"
+        );
+        assert_idempotent(&formatted, &options);
+    }
+
+    #[test]
+    fn test_single_field_type_collapsed() {
+        let options = FormatOptions {
+            collapse_single_field: true,
+            ..FormatOptions::default()
+        };
+        let formatted = format_source(
+            "type X {\n  id: ID\n}\n\ninterface Node {\n  id: ID\n}\n\ninput Filter {\n  name: String\n}\n\ntype Y {\n  a: Int\n  b: Int\n}",
+            &options,
+        );
+        assert_eq!(
+            formatted,
+            "type X { id: ID }\n\ninterface Node { id: ID }\n\ninput Filter { name: String }\n\ntype Y {\n  a: Int\n  b: Int\n}"
+        );
+        assert_idempotent(&formatted, &options);
+    }
+
+    #[test]
+    fn test_single_field_type_expands_past_max_width() {
+        let options = FormatOptions {
+            collapse_single_field: true,
+            max_width: 20,
+            ..FormatOptions::default()
+        };
+        let formatted = format_source("type X { identifier: String }", &options);
+        assert_eq!(formatted, "type X {\n  identifier: String\n}");
+        assert_idempotent(&formatted, &options);
+    }
+
+    #[test]
+    fn test_wide_union_wraps() {
+        let options = FormatOptions::default();
+        let formatted = format_source(
+            "union SearchResult = User | Post | Comment | Organization | Repository | Discussion",
+            &options,
+        );
+        assert_eq!(
+            formatted,
+            "union SearchResult =\n  | User\n  | Post\n  | Comment\n  | Organization\n  | Repository\n  | Discussion"
+        );
+        assert_idempotent(&formatted, &options);
+
+        let formatted = format_source("union Actor = User | Bot", &options);
+        assert_eq!(formatted, "union Actor = User | Bot");
+        assert_idempotent(&formatted, &options);
+    }
+
+    #[test]
+    fn test_many_interfaces_wrap() {
+        let options = FormatOptions::default();
+        let formatted = format_source(
+            "type Repository implements Node & Starrable & Subscribable & Timestamped & Lockable { id: ID }",
+            &options,
+        );
+        assert_eq!(
+            formatted,
+            "type Repository implements\n  & Node\n  & Starrable\n  & Subscribable\n  & Timestamped\n  & Lockable {\n  id: ID\n}"
+        );
+        assert_idempotent(&formatted, &options);
+    }
+
+    #[test]
+    fn test_long_arguments_wrap() {
+        let options = FormatOptions {
+            max_width: 40,
+            ..FormatOptions::default()
+        };
+        let formatted = format_source(
+            "type Query { search(term: String, first: Int, after: Option<String>): List<Result> user(id: ID): User }",
+            &options,
+        );
+        assert_eq!(
+            formatted,
+            "type Query {\n  search(\n    term: String,\n    first: Int,\n    after: Option<String>,\n  ): List<Result>\n  user(id: ID): User\n}"
+        );
+        assert_idempotent(&formatted, &options);
+    }
+
+    #[test]
+    fn test_arguments_normalized() {
+        let messy = r#"type Query {
+  users(first:Int=10 ,after : String @deprecated( reason:"x" ) ,): List<User> @cache( maxAge :60 , scope:{ kind:PUBLIC,ratio:1.0 , tags:[ "a" ,"b", ] } ,)
+}"#;
+        let wide = FormatOptions {
+            max_width: 200,
+            ..FormatOptions::default()
+        };
+        let formatted = format_source(messy, &wide);
+        assert_eq!(
+            formatted,
+            r#"type Query {
+  users(first: Int = 10, after: String @deprecated(reason: "x")): List<User> @cache(maxAge: 60, scope: {kind: PUBLIC, ratio: 1.0, tags: ["a", "b"]})
+}"#
+        );
+        assert_idempotent(&formatted, &wide);
+
+        // Past the width the arguments wrap with trailing commas
+        let options = FormatOptions::default();
+        let wrapped = format_source(messy, &options);
+        assert_eq!(
+            wrapped,
+            r#"type Query {
+  users(
+    first: Int = 10,
+    after: String @deprecated(reason: "x"),
+  ): List<User> @cache(maxAge: 60, scope: {kind: PUBLIC, ratio: 1.0, tags: ["a", "b"]})
+}"#
+        );
+        assert_idempotent(&wrapped, &options);
+    }
+
+    #[test]
+    fn test_sort_fields() {
+        let options = FormatOptions {
+            sort_fields: true,
+            ..FormatOptions::default()
+        };
+        let formatted = format_source(
+            "type User {\n  name: String\n  id: ID\n}\n\ninput Filter {\n  limit: Int\n  after: String\n}",
+            &options,
+        );
+        assert_eq!(
+            formatted,
+            "type User {\n  id: ID\n  name: String\n}\n\ninput Filter {\n  after: String\n  limit: Int\n}"
+        );
+        assert_idempotent(&formatted, &options);
+    }
+
+    #[test]
+    fn test_public_visibility_round_trips() {
+        let options = FormatOptions::default();
+        let formatted = format_source(
+            "pub type User { id: ID }\npub enum Role { ADMIN }\npub opaque Email = String",
+            &options,
+        );
+        assert_eq!(
+            formatted,
+            "pub type User {\n  id: ID\n}\n\npub enum Role {\n  ADMIN\n}\n\npub opaque Email = String"
+        );
+        assert_idempotent(&formatted, &options);
+    }
+
+    #[test]
+    fn test_private_type_stays_private() {
+        let options = FormatOptions::default();
+        let formatted = format_source("type User { id: ID }", &options);
+        assert_eq!(formatted, "type User {\n  id: ID\n}");
+        assert_idempotent(&formatted, &options);
+    }
+
+    #[test]
+    fn test_extensions_round_trip() {
+        let options = FormatOptions::default();
+        let formatted = format_source(
+            "extend schema { subscription: Subscription }\nextend enum Status { Archived }",
+            &options,
+        );
+        assert_eq!(
+            formatted,
+            "extend schema {\n  subscription: Subscription\n}\n\nextend enum Status {\n  Archived\n}"
+        );
+        assert_idempotent(&formatted, &options);
+    }
+
+    #[test]
+    fn test_single_line_description_round_trips() {
+        let options = FormatOptions::default();
+        let formatted = format_source(
+            "\"\"\"\n    A user.\n\"\"\"\ntype User {\n  \"\"\"  The \"id\".  \"\"\"\n  id: ID\n}",
+            &options,
+        );
+        assert_eq!(
+            formatted,
+            "\"A user.\"\ntype User {\n  \"  The \\\"id\\\".  \"\n  id: ID\n}"
+        );
+        assert_idempotent(&formatted, &options);
+    }
+
+    #[test]
+    fn test_multi_line_description_round_trips() {
+        let options = FormatOptions::default();
+        let source = r#"type User {
+        """
+        The user's name.
+
+          Quotes \""" and \ stay escaped.
+        """
+  name: String
+}
+
+enum Role {
+  "Full access.\nUse sparingly."
+  ADMIN
+}"#;
+        let formatted = format_source(source, &options);
+        assert_eq!(
+            formatted,
+            r#"type User {
+  """
+  The user's name.
+
+    Quotes \""" and \ stay escaped.
+  """
+  name: String
+}
+
+enum Role {
+  """
+  Full access.
+  Use sparingly.
+  """
+  ADMIN
+}"#
+        );
+        assert_idempotent(&formatted, &options);
+
+        let interner = Interner::new();
+        let result = parse(&formatted, &interner);
+        match &result.document.definitions[0] {
+            Definition::Type(TypeDefinition::Object(user)) => {
+                let description = &user.fields[0].description.as_ref().unwrap().value;
+                assert!(description.contains("Quotes \"\"\" and \\ stay"));
+            }
+            other => panic!("expected object type, got {other:?}"),
+        }
+    }
+}
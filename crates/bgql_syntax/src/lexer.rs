@@ -9,6 +9,8 @@ pub struct Lexer<'a> {
     bytes: &'a [u8],
     pos: u32,
     interner: &'a Interner,
+    /// Whether whitespace, newlines and comments are returned as tokens.
+    trivia: bool,
 }
 
 impl<'a> Lexer<'a> {
@@ -19,6 +21,16 @@ impl<'a> Lexer<'a> {
             bytes: source.as_bytes(),
             pos: 0,
             interner,
+            trivia: false,
+        }
+    }
+
+    /// Creates a lexer that also returns whitespace, newline and comment
+    /// tokens, so the tokens cover every byte of the source.
+    pub fn with_trivia(source: &'a str, interner: &'a Interner) -> Self {
+        Self {
+            trivia: true,
+            ..Self::new(source, interner)
         }
     }
 
@@ -46,6 +58,12 @@ impl<'a> Lexer<'a> {
         self.bytes.get((self.pos + offset) as usize).copied()
     }
 
+    /// Peeks at the current character (which may span several bytes).
+    #[inline]
+    fn peek_char(&self) -> Option<char> {
+        self.source[self.pos as usize..].chars().next()
+    }
+
     /// Advances by one byte.
     #[inline]
     fn advance(&mut self) {
@@ -73,7 +91,14 @@ impl<'a> Lexer<'a> {
 
     /// Scans the next token.
     pub fn next_token(&mut self) -> Token {
-        self.skip_trivia();
+        let start = self.pos;
+        if self.trivia {
+            if let Some(kind) = self.scan_trivia() {
+                return Token::new(kind, Span::new(start, self.pos));
+            }
+        } else {
+            self.skip_trivia();
+        }
 
         let start = self.pos;
 
@@ -183,8 +208,13 @@ impl<'a> Lexer<'a> {
             // Identifiers and keywords
             b'a'..=b'z' | b'A'..=b'Z' | b'_' => self.scan_identifier(),
 
+            // Unicode identifiers
+            0x80.. if self.peek_char().is_some_and(char::is_alphabetic) => self.scan_identifier(),
+
             _ => {
-                self.advance();
+                // Skip the whole character so spans stay on char boundaries
+                let len = self.peek_char().map_or(1, char::len_utf8);
+                self.advance_by(len as u32);
                 TokenKind::Error
             }
         };
@@ -202,7 +232,7 @@ impl<'a> Lexer<'a> {
                 Some(b'#') => {
                     // Comment - skip to end of line
                     while let Some(c) = self.peek() {
-                        if c == b'\n' {
+                        if c == b'\n' || c == b'\r' {
                             break;
                         }
                         self.advance();
@@ -217,15 +247,63 @@ impl<'a> Lexer<'a> {
         }
     }
 
+    /// Scans one whitespace run, newline or comment, if any.
+    fn scan_trivia(&mut self) -> Option<TokenKind> {
+        match self.peek()? {
+            b'\n' => {
+                self.advance();
+                Some(TokenKind::Newline)
+            }
+            b'\r' => {
+                self.advance();
+                if self.peek() == Some(b'\n') {
+                    self.advance();
+                }
+                Some(TokenKind::Newline)
+            }
+            b'#' => {
+                while !matches!(self.peek(), None | Some(b'\n' | b'\r')) {
+                    self.advance();
+                }
+                Some(TokenKind::Comment)
+            }
+            _ => {
+                let start = self.pos;
+                loop {
+                    match self.peek() {
+                        Some(b' ' | b'\t') => self.advance(),
+                        Some(0xEF)
+                            if self.peek_at(1) == Some(0xBB) && self.peek_at(2) == Some(0xBF) =>
+                        {
+                            self.advance_by(3);
+                        }
+                        _ => break,
+                    }
+                }
+                (self.pos > start).then_some(TokenKind::Whitespace)
+            }
+        }
+    }
+
     /// Scans an identifier or keyword.
     fn scan_identifier(&mut self) -> TokenKind {
         let start = self.pos;
 
-        while let Some(c) = self.peek() {
-            if c.is_ascii_alphanumeric() || c == b'_' {
-                self.advance();
-            } else {
-                break;
+        loop {
+            // ASCII runs are scanned over the bytes directly
+            let rest = &self.bytes[self.pos as usize..];
+            let run = rest
+                .iter()
+                .position(|&c| !is_ident_byte(c))
+                .unwrap_or(rest.len());
+            self.advance_by(run as u32);
+
+            match self.peek() {
+                Some(0x80..) => match self.peek_char() {
+                    Some(ch) if ch.is_alphanumeric() => self.advance_by(ch.len_utf8() as u32),
+                    _ => break,
+                },
+                _ => break,
             }
         }
 
@@ -305,8 +383,8 @@ impl<'a> Lexer<'a> {
 
         loop {
             match self.peek() {
-                None | Some(b'\n') => {
-                    return TokenKind::Error;
+                None | Some(b'\n' | b'\r') => {
+                    return TokenKind::UnterminatedString;
                 }
                 Some(b'"') => {
                     self.advance();
@@ -314,7 +392,9 @@ impl<'a> Lexer<'a> {
                 }
                 Some(b'\\') => {
                     self.advance();
-                    self.advance(); // Escaped char
+                    if self.peek().is_some() {
+                        self.advance(); // Escaped char
+                    }
                 }
                 _ => {
                     self.advance();
@@ -328,7 +408,7 @@ impl<'a> Lexer<'a> {
         loop {
             match self.peek() {
                 None => {
-                    return TokenKind::Error;
+                    return TokenKind::UnterminatedString;
                 }
                 Some(b'"') if self.peek_at(1) == Some(b'"') && self.peek_at(2) == Some(b'"') => {
                     self.advance_by(3);
@@ -360,6 +440,23 @@ impl<'a> Lexer<'a> {
     }
 }
 
+/// Bytes that continue an ASCII identifier.
+const IDENT_BYTES: [bool; 256] = {
+    let mut table = [false; 256];
+    let mut c = 0;
+    while c < 256 {
+        let b = c as u8;
+        table[c] = b.is_ascii_alphanumeric() || b == b'_';
+        c += 1;
+    }
+    table
+};
+
+#[inline]
+fn is_ident_byte(c: u8) -> bool {
+    IDENT_BYTES[c as usize]
+}
+
 /// Tokenizes the entire source.
 pub fn tokenize(source: &str, interner: &Interner) -> Vec<Token> {
     let mut lexer = Lexer::new(source, interner);
@@ -475,4 +572,114 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_unterminated_strings() {
+        let interner = Interner::new();
+
+        let tokens = tokenize("\"abc\nx", &interner);
+        assert_eq!(tokens[0].kind, TokenKind::UnterminatedString);
+        assert_eq!(tokens[0].span, Span::new(0, 4));
+        assert_eq!(tokens[1].kind, TokenKind::Ident);
+
+        let tokens = tokenize("\"\"\"abc\n\"", &interner);
+        assert_eq!(tokens[0].kind, TokenKind::UnterminatedString);
+        assert_eq!(tokens[0].span, Span::new(0, 8));
+        assert_eq!(tokens[1].kind, TokenKind::Eof);
+
+        // A trailing escape doesn't run past the end of the input
+        let tokens = tokenize("\"abc\\", &interner);
+        assert_eq!(tokens[0].kind, TokenKind::UnterminatedString);
+        assert_eq!(tokens[0].span, Span::new(0, 5));
+    }
+
+    #[test]
+    fn test_unicode_identifiers() {
+        let interner = Interner::new();
+        let tokens = tokenize("Café naïve_1 → x", &interner);
+
+        let kinds: Vec<_> = tokens.iter().map(|t| t.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Ident,
+                TokenKind::Ident,
+                TokenKind::Error,
+                TokenKind::Ident,
+                TokenKind::Eof,
+            ]
+        );
+        assert_eq!(tokens[0].span, Span::new(0, 5));
+        assert_eq!(tokens[2].span, Span::new(15, 18));
+    }
+
+    #[test]
+    fn test_trivia_tokens() {
+        let interner = Interner::new();
+        let mut lexer = Lexer::with_trivia("a \t# note\r\nb\n", &interner);
+        let tokens: Vec<_> = std::iter::from_fn(|| Some(lexer.next_token()))
+            .take_while(|token| !token.is_eof())
+            .collect();
+
+        let kinds: Vec<_> = tokens.iter().map(|t| t.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Ident,
+                TokenKind::Whitespace,
+                TokenKind::Comment,
+                TokenKind::Newline,
+                TokenKind::Ident,
+                TokenKind::Newline,
+            ]
+        );
+        assert_eq!(tokens[2].span, Span::new(3, 9));
+        assert_eq!(tokens[3].span, Span::new(9, 11));
+    }
+
+    /// End of the identifier at `start`, scanned a char at a time.
+    fn char_identifier_end(source: &str, start: usize) -> usize {
+        source[start..]
+            .char_indices()
+            .find(|&(_, c)| !(c.is_alphanumeric() || c == '_'))
+            .map_or(source.len(), |(i, _)| start + i)
+    }
+
+    #[test]
+    fn test_identifier_fast_path_matches_char_scan() {
+        let fixture = format!(
+            "{}
+type Café_2 {{ naïve_ß: Straße→x 名前: ID }}
+",
+            include_str!("../../../examples/schema.bgql")
+        );
+        let interner = Interner::new();
+        let tokens = tokenize(&fixture, &interner);
+        assert!(tokens.len() > 1000);
+
+        for token in &tokens {
+            if token.kind == TokenKind::Ident || token.kind.is_keyword() {
+                let start = token.span.start as usize;
+                assert_eq!(
+                    token.span.end as usize,
+                    char_identifier_end(&fixture, start),
+                    "identifier at {start}"
+                );
+                let text = &fixture[start..token.span.end as usize];
+                assert_eq!(
+                    TokenKind::from_keyword(text).unwrap_or(TokenKind::Ident),
+                    token.kind
+                );
+            }
+        }
+
+        let idents: Vec<_> = tokens
+            .iter()
+            .rev()
+            .filter(|t| t.kind == TokenKind::Ident)
+            .take(6)
+            .map(|t| &fixture[t.span.start as usize..t.span.end as usize])
+            .collect();
+        assert_eq!(idents, ["ID", "名前", "x", "Straße", "naïve_ß", "Café_2"]);
+    }
 }
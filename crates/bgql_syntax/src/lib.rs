@@ -4,17 +4,23 @@
 //! - `token`: Token kinds and token structures
 //! - `lexer`: Tokenization
 //! - `ast`: Abstract syntax tree types
+//! - `cst`: Lossless concrete syntax tree
+//! - `extend`: Merging of `extend` definitions
 //! - `parser`: Recursive descent parser
 //! - `formatter`: Code formatting
 
 pub mod ast;
+pub mod cst;
+pub mod extend;
 pub mod formatter;
 pub mod lexer;
 pub mod parser;
 pub mod token;
 
 pub use ast::*;
+pub use cst::{parse_cst, CstParseResult, SyntaxTree};
+pub use extend::{merge_extensions, ExtensionError};
 pub use formatter::{format, format_with_options, FormatOptions, Formatter};
 pub use lexer::Lexer;
-pub use parser::{parse, ParseResult};
+pub use parser::{parse, parse_value, ParseResult};
 pub use token::{DirectiveLocation, Token, TokenKind};
@@ -4,6 +4,7 @@ use crate::ast::*;
 use crate::lexer::Lexer;
 use crate::token::{DirectiveLocation, Token, TokenKind};
 use bgql_core::{diagnostics::codes, DiagnosticBag, Interner, Span, Text};
+use std::borrow::Cow;
 
 /// Parser for Better GraphQL.
 pub struct Parser<'a> {
@@ -13,6 +14,8 @@ pub struct Parser<'a> {
     #[allow(dead_code)]
     interner: &'a Interner,
     current: Token,
+    /// End of the token before `current`
+    prev_end: u32,
     diagnostics: DiagnosticBag,
 }
 
@@ -32,18 +35,30 @@ pub fn parse<'a>(source: &'a str, interner: &'a Interner) -> ParseResult<'a> {
     }
 }
 
+/// Parses a single value, such as a default value kept as source text.
+///
+/// Returns `None` unless `source` is exactly one valid value.
+pub fn parse_value<'a>(source: &'a str, interner: &'a Interner) -> Option<Value<'a>> {
+    let mut parser = Parser::new(source, interner);
+    let value = parser.parse_value();
+    (parser.at_kind(TokenKind::Eof) && !parser.diagnostics.has_errors()).then_some(value)
+}
+
 impl<'a> Parser<'a> {
     /// Creates a new parser.
     pub fn new(source: &'a str, interner: &'a Interner) -> Self {
         let mut lexer = Lexer::new(source, interner);
         let current = lexer.next_token();
-        Self {
+        let mut parser = Self {
             lexer,
             source,
             interner,
             current,
+            prev_end: 0,
             diagnostics: DiagnosticBag::new(),
-        }
+        };
+        parser.skip_unterminated_strings();
+        parser
     }
 
     /// Returns the current token kind.
@@ -60,7 +75,27 @@ impl<'a> Parser<'a> {
 
     /// Advances to the next token.
     fn advance(&mut self) {
+        self.prev_end = self.current.span.end;
         self.current = self.lexer.next_token();
+        self.skip_unterminated_strings();
+    }
+
+    /// Reports and skips unterminated strings, which run to the end of the
+    /// line (or the input, for block strings) and have no usable value.
+    fn skip_unterminated_strings(&mut self) {
+        while self.at_kind(TokenKind::UnterminatedString) {
+            let (title, label) = if self.current_text().starts_with("\"\"\"") {
+                (
+                    "unterminated block string",
+                    "block string is never closed with `\"\"\"`",
+                )
+            } else {
+                ("unterminated string", "string is never closed with `\"`")
+            };
+            self.diagnostics
+                .error(codes::UNTERMINATED_STRING, title, self.current.span, label);
+            self.current = self.lexer.next_token();
+        }
     }
 
     /// Expects a specific token kind.
@@ -186,6 +221,7 @@ impl<'a> Parser<'a> {
                 self.parse_module_declaration(visibility),
             )),
             TokenKind::Use => Some(Definition::Use(self.parse_use_statement(visibility))),
+            TokenKind::Extend => self.parse_extension(description),
             _ => {
                 self.error("expected definition");
                 None
@@ -193,6 +229,61 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Parses an extension of the schema or of a type.
+    ///
+    /// ```graphql
+    /// extend schema { subscription: Subscription }
+    /// extend type User { email: String }
+    /// extend enum Status { ARCHIVED }
+    /// ```
+    fn parse_extension(&mut self, description: Option<Description<'a>>) -> Option<Definition<'a>> {
+        if let Some(description) = description {
+            self.diagnostics.error(
+                codes::INVALID_SYNTAX,
+                "unexpected description",
+                description.span,
+                "extensions can't have a description",
+            );
+        }
+        self.advance(); // extend
+
+        let visibility = Visibility::Private;
+        let type_def = match self.at() {
+            TokenKind::Schema => {
+                return Some(Definition::SchemaExtension(
+                    self.parse_schema_definition(None),
+                ))
+            }
+            TokenKind::Type => {
+                TypeDefinition::Object(self.parse_object_type_with_visibility(None, visibility))
+            }
+            TokenKind::Interface => TypeDefinition::Interface(
+                self.parse_interface_type_with_visibility(None, visibility),
+            ),
+            TokenKind::Union => {
+                TypeDefinition::Union(self.parse_union_type_with_visibility(None, visibility))
+            }
+            TokenKind::Enum => {
+                TypeDefinition::Enum(self.parse_enum_type_with_visibility(None, visibility))
+            }
+            TokenKind::Input if !matches!(self.peek_next(), TokenKind::Union | TokenKind::Enum) => {
+                TypeDefinition::Input(
+                    self.parse_input_object_type_with_visibility(None, visibility),
+                )
+            }
+            TokenKind::Scalar => {
+                TypeDefinition::Scalar(self.parse_scalar_type_with_visibility(None, visibility))
+            }
+            _ => {
+                self.error(
+                    "expected `schema`, `type`, `interface`, `union`, `enum`, `input` or `scalar` after `extend`",
+                );
+                return None;
+            }
+        };
+        Some(Definition::TypeExtension(type_def))
+    }
+
     /// Parses visibility modifier.
     fn parse_visibility(&mut self) -> Visibility {
         if self.at_kind(TokenKind::Pub) {
@@ -374,35 +465,127 @@ impl<'a> Parser<'a> {
     }
 
     /// Tries to parse a description.
+    ///
+    /// Of several adjacent strings, the one closest to the definition is the
+    /// description; the earlier ones are reported as errors.
     fn try_parse_description(&mut self) -> Option<Description<'a>> {
-        if matches!(
+        let mut description = None;
+        while matches!(
             self.at(),
             TokenKind::StringLiteral | TokenKind::BlockStringLiteral
         ) {
-            let span = self.current.span;
-            let text = self.current_text();
-            // Strip quotes
-            let value = if text.starts_with("\"\"\"") {
-                &text[3..text.len() - 3]
-            } else {
-                &text[1..text.len() - 1]
-            };
+            if let Some(stray) = description.replace(self.parse_description_string()) {
+                self.error_stray_description(stray);
+            }
+        }
+        description
+    }
+
+    fn parse_description_string(&mut self) -> Description<'a> {
+        let span = self.current.span;
+        let value = self.unescape_string(span);
+        self.advance();
+        Description::new(value, span)
+    }
+
+    /// Strips the quotes from a string token and decodes its escape sequences.
+    ///
+    /// Block strings only recognize `\"""`; any other backslash in them is
+    /// kept as written, and their `\r\n` and `\r` line endings become `\n`.
+    /// Invalid escapes are reported and left out of the value.
+    fn unescape_string(&mut self, span: Span) -> Cow<'a, str> {
+        let text = self.lexer.span_text(span);
+        if let Some(body) = text
+            .strip_prefix("\"\"\"")
+            .and_then(|text| text.strip_suffix("\"\"\""))
+        {
+            let mut value = Cow::Borrowed(body);
+            if value.contains('\r') {
+                value = Cow::Owned(value.replace("\r\n", "\n").replace('\r', "\n"));
+            }
+            if value.contains("\\\"\"\"") {
+                value = Cow::Owned(value.replace("\\\"\"\"", "\"\"\""));
+            }
+            return value;
+        }
+
+        let body = &text[1..text.len() - 1];
+        if !body.contains('\\') {
+            return Cow::Borrowed(body);
+        }
+
+        let mut value = String::with_capacity(body.len());
+        let mut rest = body;
+        while let Some(index) = rest.find('\\') {
+            value.push_str(&rest[..index]);
+            let escape = &rest[index..];
+            let (decoded, len) = decode_escape(escape);
+            match decoded {
+                Some(c) => value.push(c),
+                None => {
+                    let start = span.start + 1 + (body.len() - escape.len()) as u32;
+                    self.diagnostics.error(
+                        codes::INVALID_ESCAPE_SEQUENCE,
+                        "invalid escape sequence",
+                        Span::new(start, start + len as u32),
+                        format!("`{}` is not a valid escape sequence", &escape[..len]),
+                    );
+                }
+            }
+            rest = &escape[len..];
+        }
+        value.push_str(rest);
+        Cow::Owned(value)
+    }
+
+    fn error_stray_description(&mut self, stray: Description<'a>) {
+        self.diagnostics.error(
+            codes::UNEXPECTED_TOKEN,
+            "unexpected string",
+            stray.span,
+            "only one description may precede a definition",
+        );
+    }
+
+    fn error_number_out_of_range(&mut self, title: &str) {
+        self.diagnostics.error(
+            codes::NUMBER_OUT_OF_RANGE,
+            title,
+            self.current.span,
+            "values must fit in a 64-bit integer or a finite double",
+        );
+    }
+
+    /// Expects the `=` of an opaque type or alias definition. A `:` in its
+    /// place, as in `opaque Email: String`, is reported and skipped.
+    fn expect_definition_eq(&mut self, definition: &str) {
+        if self.at_kind(TokenKind::Colon) {
+            self.diagnostics.error(
+                codes::UNEXPECTED_TOKEN,
+                format!("expected `=` in {definition} definition, found `:`"),
+                self.current.span,
+                "use `=` here",
+            );
             self.advance();
-            Some(Description::new(value, span))
         } else {
-            None
+            self.expect(TokenKind::Eq);
         }
     }
 
     /// Parses a name.
+    ///
+    /// A missing name is empty, so the token that is left in place isn't
+    /// interned.
     fn parse_name(&mut self) -> Name {
         let span = self.current.span;
-        let value = self.intern_current();
-        if self.at_kind(TokenKind::Ident) || self.at().is_keyword() {
+        let value = if self.at_kind(TokenKind::Ident) || self.at().is_keyword() {
+            let value = self.intern_current();
             self.advance();
+            value
         } else {
             self.error("expected name");
-        }
+            self.interner.intern("")
+        };
         Name::new(value, span)
     }
 
@@ -701,7 +884,7 @@ impl<'a> Parser<'a> {
         self.advance(); // opaque
 
         let name = self.parse_name();
-        self.expect(TokenKind::Eq);
+        self.expect_definition_eq("opaque type");
         let underlying = self.parse_type();
         let directives = self.parse_directives();
 
@@ -725,7 +908,7 @@ impl<'a> Parser<'a> {
         self.advance(); // alias
 
         let name = self.parse_name();
-        self.expect(TokenKind::Eq);
+        self.expect_definition_eq("type alias");
         let aliased = self.parse_type();
 
         let end = self.current.span.start;
@@ -823,7 +1006,7 @@ impl<'a> Parser<'a> {
                 // Create a single unnamed field for the tuple variant
                 Some(vec![InputValueDefinition {
                     description: None,
-                    name: Name::new(self.lexer.intern_span(type_name.span), type_name.span),
+                    name: Name::new(type_name.value, type_name.span),
                     ty: Type::Named(NamedType {
                         name: type_name.value,
                         span: type_name.span,
@@ -1182,7 +1365,7 @@ impl<'a> Parser<'a> {
         let name = self.parse_name();
         let arguments = if self.at_kind(TokenKind::LParen) {
             self.advance();
-            let args = self.parse_arguments();
+            let args = self.parse_directive_arguments();
             self.expect(TokenKind::RParen);
             args
         } else {
@@ -1201,11 +1384,49 @@ impl<'a> Parser<'a> {
     fn parse_arguments(&mut self) -> Vec<Argument<'a>> {
         let mut args = Vec::new();
         while !self.at_kind(TokenKind::RParen) && !self.at_kind(TokenKind::Eof) {
+            // Skip optional commas between arguments
+            while self.at_kind(TokenKind::Comma) {
+                self.advance();
+            }
+            if self.at_kind(TokenKind::RParen) {
+                break;
+            }
             args.push(self.parse_argument());
         }
         args
     }
 
+    /// Parses the arguments of a directive, which may leave out the names,
+    /// as in `@minLength(3)`.
+    fn parse_directive_arguments(&mut self) -> Vec<Argument<'a>> {
+        let mut args = Vec::new();
+        while !self.at_kind(TokenKind::RParen) && !self.at_kind(TokenKind::Eof) {
+            while self.at_kind(TokenKind::Comma) {
+                self.advance();
+            }
+            if self.at_kind(TokenKind::RParen) {
+                break;
+            }
+            let named = (self.at_kind(TokenKind::Ident) || self.at().is_keyword())
+                && self.peek_next() == TokenKind::Colon;
+            if named {
+                args.push(self.parse_argument());
+                continue;
+            }
+
+            let start = self.current.span.start;
+            let name = Name::new(self.interner.intern(""), Span::new(start, start));
+            let value = self.parse_value();
+            let end = self.current.span.start;
+            args.push(Argument {
+                name,
+                value,
+                span: Span::new(start, end),
+            });
+        }
+        args
+    }
+
     /// Parses an argument.
     fn parse_argument(&mut self) -> Argument<'a> {
         let start = self.current.span.start;
@@ -1231,47 +1452,55 @@ impl<'a> Parser<'a> {
                 Value::Variable(name)
             }
             TokenKind::IntLiteral => {
-                let text = self.current_text();
-                let value = text.parse().unwrap_or(0);
+                // `Int` is a signed 32-bit integer, as in GraphQL
+                let value = self.current_text().parse::<i32>().unwrap_or_else(|_| {
+                    self.error_number_out_of_range("integer literal out of range for Int");
+                    0
+                });
                 self.advance();
-                Value::Int(value, Span::new(start, self.current.span.start))
+                Value::Int(value.into(), Span::new(start, self.prev_end))
             }
             TokenKind::FloatLiteral => {
-                let text = self.current_text();
-                let value = text.parse().unwrap_or(0.0);
+                let value = self
+                    .current_text()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|value| value.is_finite())
+                    .unwrap_or_else(|| {
+                        self.error_number_out_of_range("float literal out of range for Float");
+                        0.0
+                    });
                 self.advance();
-                Value::Float(value, Span::new(start, self.current.span.start))
+                Value::Float(value, Span::new(start, self.prev_end))
             }
             TokenKind::StringLiteral | TokenKind::BlockStringLiteral => {
-                let text = self.current_text();
-                let value = if text.starts_with("\"\"\"") {
-                    text[3..text.len() - 3].to_string()
-                } else {
-                    text[1..text.len() - 1].to_string()
-                };
+                let value = self.unescape_string(self.current.span).into_owned();
                 self.advance();
-                Value::String(value, Span::new(start, self.current.span.start))
+                Value::String(value, Span::new(start, self.prev_end))
             }
             TokenKind::True => {
                 self.advance();
-                Value::Boolean(true, Span::new(start, self.current.span.start))
+                Value::Boolean(true, Span::new(start, self.prev_end))
             }
             TokenKind::False => {
                 self.advance();
-                Value::Boolean(false, Span::new(start, self.current.span.start))
+                Value::Boolean(false, Span::new(start, self.prev_end))
             }
             TokenKind::Null => {
                 self.advance();
-                Value::Null(Span::new(start, self.current.span.start))
+                Value::Null(Span::new(start, self.prev_end))
             }
             TokenKind::LBracket => {
                 self.advance();
                 let mut values = Vec::new();
                 while !self.at_kind(TokenKind::RBracket) && !self.at_kind(TokenKind::Eof) {
                     values.push(self.parse_value());
+                    while self.at_kind(TokenKind::Comma) {
+                        self.advance();
+                    }
                 }
                 self.expect(TokenKind::RBracket);
-                Value::List(values, Span::new(start, self.current.span.start))
+                Value::List(values, Span::new(start, self.prev_end))
             }
             TokenKind::LBrace => {
                 self.advance();
@@ -1281,9 +1510,12 @@ impl<'a> Parser<'a> {
                     self.expect(TokenKind::Colon);
                     let value = self.parse_value();
                     fields.push((name, value));
+                    while self.at_kind(TokenKind::Comma) {
+                        self.advance();
+                    }
                 }
                 self.expect(TokenKind::RBrace);
-                Value::Object(fields, Span::new(start, self.current.span.start))
+                Value::Object(fields, Span::new(start, self.prev_end))
             }
             TokenKind::Ident => {
                 let name = self.parse_name();
@@ -1292,7 +1524,7 @@ impl<'a> Parser<'a> {
             _ => {
                 self.error("expected value");
                 self.advance();
-                Value::Null(Span::new(start, self.current.span.start))
+                Value::Null(Span::new(start, self.prev_end))
             }
         }
     }
@@ -1504,10 +1736,78 @@ impl<'a> Parser<'a> {
     }
 }
 
+/// Decodes the escape sequence at the start of `escape`, which begins with a
+/// backslash. Returns the character, if valid, and the escape's length in bytes.
+fn decode_escape(escape: &str) -> (Option<char>, usize) {
+    let Some(c) = escape[1..].chars().next() else {
+        return (None, 1);
+    };
+    let decoded = match c {
+        '"' => '"',
+        '\\' => '\\',
+        '/' => '/',
+        'b' => '\u{8}',
+        'f' => '\u{c}',
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        'u' => return decode_unicode_escape(escape),
+        _ => return (None, 1 + c.len_utf8()),
+    };
+    (Some(decoded), 2)
+}
+
+/// Decodes `\uXXXX`, combining a UTF-16 surrogate pair spelled as two escapes.
+fn decode_unicode_escape(escape: &str) -> (Option<char>, usize) {
+    let code_unit = |at: usize| {
+        escape
+            .get(at..at + 4)
+            .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+    };
+    let Some(high) = code_unit(2) else {
+        let digits = escape[2..]
+            .bytes()
+            .take(4)
+            .take_while(u8::is_ascii_hexdigit)
+            .count();
+        return (None, 2 + digits);
+    };
+    if (0xD800..0xDC00).contains(&high) && escape[6..].starts_with("\\u") {
+        if let Some(low @ 0xDC00..=0xDFFF) = code_unit(8) {
+            let c = char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00));
+            return (c, 12);
+        }
+    }
+    (char::from_u32(high), 6)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_value_spans_end_at_their_last_token() {
+        let interner = Interner::new();
+        let source =
+            "type Query {\n  users(first: Int = 10\n    tags: List<String> = [\"a\"] ): String\n}";
+        let result = parse(source, &interner);
+        assert!(!result.diagnostics.has_errors());
+        let Definition::Type(TypeDefinition::Object(query)) = &result.document.definitions[0]
+        else {
+            panic!("expected an object type");
+        };
+        let defaults: Vec<_> = query.fields[0]
+            .arguments
+            .iter()
+            .map(|arg| {
+                let span = arg.default_value.as_ref().unwrap().span();
+                &source[span.start as usize..span.end as usize]
+            })
+            .collect();
+        assert_eq!(defaults, ["10", "[\"a\"]"]);
+    }
+
     #[test]
     fn test_parse_simple_type() {
         let interner = Interner::new();
@@ -1598,6 +1898,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_extensions() {
+        let interner = Interner::new();
+        let result = parse(
+            r#"
+            extend schema { subscription: Subscription }
+            extend type User { email: String }
+            extend interface Node { createdAt: String }
+            extend enum Status { Archived }
+        "#,
+            &interner,
+        );
+        assert!(!result.diagnostics.has_errors());
+        let definitions = &result.document.definitions;
+        assert_eq!(definitions.len(), 4);
+        assert!(
+            matches!(&definitions[0], Definition::SchemaExtension(s) if s.operations.len() == 1)
+        );
+        assert!(matches!(
+            &definitions[1],
+            Definition::TypeExtension(TypeDefinition::Object(obj)) if obj.fields.len() == 1
+        ));
+        assert!(matches!(
+            &definitions[2],
+            Definition::TypeExtension(TypeDefinition::Interface(_))
+        ));
+        match &definitions[3] {
+            Definition::TypeExtension(TypeDefinition::Enum(e)) => {
+                assert_eq!(interner.get(e.name.value), "Status");
+                assert_eq!(interner.get(e.values[0].name.value), "Archived");
+            }
+            _ => panic!("expected enum extension"),
+        }
+    }
+
+    #[test]
+    fn test_parse_extension_of_alias() {
+        let interner = Interner::new();
+        let result = parse("extend alias Id = ID", &interner);
+        assert!(result.diagnostics.has_errors());
+    }
+
     #[test]
     fn test_parse_use_statement() {
         let interner = Interner::new();
@@ -1648,4 +1990,261 @@ mod tests {
             _ => panic!("expected use statement"),
         }
     }
+
+    #[test]
+    fn test_parse_description_before_field() {
+        let interner = Interner::new();
+        let source = r#"
+            "A filter"
+            input Filter {
+                "Name to match"
+                name: String = "any"
+                "Maximum results"
+                limit: Int
+            }
+        "#;
+        let result = parse(source, &interner);
+        assert!(!result.diagnostics.has_errors());
+        match &result.document.definitions[0] {
+            Definition::Type(TypeDefinition::Input(input)) => {
+                assert_eq!(input.description.as_ref().unwrap().value, "A filter");
+                let name = &input.fields[0];
+                assert_eq!(name.description.as_ref().unwrap().value, "Name to match");
+                assert!(matches!(&name.default_value, Some(Value::String(s, _)) if s == "any"));
+                // The default value string doesn't take the next description
+                let limit = &input.fields[1];
+                assert_eq!(limit.description.as_ref().unwrap().value, "Maximum results");
+                assert!(limit.default_value.is_none());
+            }
+            _ => panic!("expected input object type definition"),
+        }
+    }
+
+    #[test]
+    fn test_parse_unterminated_string() {
+        let interner = Interner::new();
+        let result = parse("type User @deprecated(reason: \"abc", &interner);
+
+        let errors: Vec<_> = result
+            .diagnostics
+            .errors()
+            .filter(|d| d.code == codes::UNTERMINATED_STRING)
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].title, "unterminated string");
+        assert_eq!(errors[0].primary_span(), Some(Span::new(30, 34)));
+    }
+
+    #[test]
+    fn test_parse_unterminated_block_string() {
+        let interner = Interner::new();
+        let result = parse("type User {\n  id: ID\n}\n\"\"\"abc", &interner);
+
+        let errors: Vec<_> = result.diagnostics.errors().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].title, "unterminated block string");
+        assert_eq!(errors[0].primary_span(), Some(Span::new(23, 29)));
+        assert_eq!(result.document.definitions.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_crlf_spans() {
+        let interner = Interner::new();
+        let source = "# Users\r\ntype User {\r\n  id: ID\r\n}\r\n\r\ntype Post {\r\n  title: String\r\n}\r\n";
+        let result = parse(source, &interner);
+
+        assert!(!result.diagnostics.has_errors(), "{:?}", result.diagnostics);
+        let text = |span: Span| &source[span.start as usize..span.end as usize];
+        match &result.document.definitions[..] {
+            [Definition::Type(TypeDefinition::Object(user)), Definition::Type(TypeDefinition::Object(post))] =>
+            {
+                assert_eq!(text(user.name.span), "User");
+                assert_eq!(user.span.start, 9);
+                assert_eq!(text(user.fields[0].name.span), "id");
+                assert_eq!(text(post.name.span), "Post");
+                assert_eq!(text(post.fields[0].name.span), "title");
+            }
+            other => panic!("expected two object types, got {other:?}"),
+        }
+
+        // A string literal ends at a `\r` like at a `\n`
+        let result = parse("type User @deprecated(reason: \"abc\r\n)", &interner);
+        let error = result.diagnostics.errors().next().unwrap();
+        assert_eq!(error.code, codes::UNTERMINATED_STRING);
+        assert_eq!(error.primary_span(), Some(Span::new(30, 34)));
+    }
+
+    #[test]
+    fn test_parse_crlf_block_string_description() {
+        let interner = Interner::new();
+        let source =
+            "\"\"\"\r\nA user.\r\n\r\nOld line\rend.\r\n\"\"\"\r\ntype User {\r\n  id: ID\r\n}\r\n";
+        let result = parse(source, &interner);
+
+        assert!(!result.diagnostics.has_errors(), "{:?}", result.diagnostics);
+        match &result.document.definitions[0] {
+            Definition::Type(TypeDefinition::Object(user)) => {
+                let description = user.description.as_ref().unwrap();
+                assert_eq!(description.value, "\nA user.\n\nOld line\nend.\n");
+                assert_eq!(description.span, Span::new(0, 34));
+            }
+            other => panic!("expected object type, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_comma_separated_arguments() {
+        let interner = Interner::new();
+        let result = parse(
+            "type Query { a: String @log(level: INFO, tag: \"a\",) }\nquery { user(id: 1, name: 2) { id } }",
+            &interner,
+        );
+
+        assert!(!result.diagnostics.has_errors(), "{:?}", result.diagnostics);
+        match &result.document.definitions[1] {
+            Definition::Operation(op) => match &op.selection_set.selections[0] {
+                Selection::Field(field) => assert_eq!(field.arguments.len(), 2),
+                other => panic!("expected field, got {other:?}"),
+            },
+            other => panic!("expected operation, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_positional_directive_arguments() {
+        let interner = Interner::new();
+        let result = parse(
+            "opaque Username = String @minLength(3) @pattern(regex: \"^a\")",
+            &interner,
+        );
+
+        assert!(!result.diagnostics.has_errors(), "{:?}", result.diagnostics);
+        match &result.document.definitions[0] {
+            Definition::Type(TypeDefinition::Opaque(opaque)) => {
+                let names: Vec<_> = opaque
+                    .directives
+                    .iter()
+                    .flat_map(|directive| &directive.arguments)
+                    .map(|arg| interner.get(arg.name.value))
+                    .collect();
+                assert_eq!(names, ["", "regex"]);
+            }
+            other => panic!("expected opaque type, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_colon_in_opaque_and_alias() {
+        let interner = Interner::new();
+        let result = parse("opaque Email: String\nalias UserId: ID", &interner);
+
+        let errors: Vec<_> = result.diagnostics.errors().collect();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0].title,
+            "expected `=` in opaque type definition, found `:`"
+        );
+        assert_eq!(errors[0].primary_span(), Some(Span::new(12, 13)));
+        assert_eq!(
+            errors[1].title,
+            "expected `=` in type alias definition, found `:`"
+        );
+        assert_eq!(errors[1].primary_span(), Some(Span::new(33, 34)));
+        assert_eq!(result.document.definitions.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_adjacent_descriptions() {
+        let interner = Interner::new();
+        let result = parse("\"first\" \"second\"\ntype User {\n  id: ID\n}", &interner);
+
+        // The closest string is the description; the stray one is an error
+        let errors: Vec<_> = result.diagnostics.errors().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].title, "unexpected string");
+        assert_eq!(errors[0].primary_span(), Some(Span::new(0, 7)));
+        match &result.document.definitions[0] {
+            Definition::Type(TypeDefinition::Object(obj)) => {
+                assert_eq!(obj.description.as_ref().unwrap().value, "second");
+                assert_eq!(obj.fields.len(), 1);
+            }
+            _ => panic!("expected object type definition"),
+        }
+    }
+
+    #[test]
+    fn test_parse_newline_escape() {
+        let interner = Interner::new();
+        let result = parse(
+            "input Filter {\n  name: String = \"line\\nbreak\"\n}",
+            &interner,
+        );
+        assert!(!result.diagnostics.has_errors());
+        match &result.document.definitions[0] {
+            Definition::Type(TypeDefinition::Input(input)) => {
+                let default = &input.fields[0].default_value;
+                assert!(matches!(default, Some(Value::String(s, _)) if s == "line\nbreak"));
+            }
+            _ => panic!("expected input object type definition"),
+        }
+    }
+
+    #[test]
+    fn test_parse_unicode_escape() {
+        let interner = Interner::new();
+        let result = parse("\"\\u0041 user\"\ntype User {\n  id: ID\n}", &interner);
+        assert!(!result.diagnostics.has_errors());
+        match &result.document.definitions[0] {
+            Definition::Type(TypeDefinition::Object(obj)) => {
+                assert_eq!(obj.description.as_ref().unwrap().value, "A user");
+            }
+            _ => panic!("expected object type definition"),
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_escape() {
+        let interner = Interner::new();
+        let result = parse("input Filter {\n  name: String = \"a\\qb\"\n}", &interner);
+
+        let errors: Vec<_> = result.diagnostics.errors().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, codes::INVALID_ESCAPE_SEQUENCE);
+        assert_eq!(errors[0].primary_span(), Some(Span::new(34, 36)));
+    }
+
+    #[test]
+    fn test_parse_int_default() {
+        let interner = Interner::new();
+        let result = parse(
+            "input Page {\n  first: Int = 2147483647\n  offset: Int = -2147483648\n}",
+            &interner,
+        );
+        assert!(!result.diagnostics.has_errors());
+        match &result.document.definitions[0] {
+            Definition::Type(TypeDefinition::Input(input)) => {
+                let default = &input.fields[0].default_value;
+                assert!(matches!(default, Some(Value::Int(2147483647, _))));
+                let default = &input.fields[1].default_value;
+                assert!(matches!(default, Some(Value::Int(-2147483648, _))));
+            }
+            _ => panic!("expected input object type definition"),
+        }
+    }
+
+    #[test]
+    fn test_parse_int_out_of_range() {
+        let interner = Interner::new();
+        let result = parse(
+            "input Page {\n  first: Int = 2147483648\n  last: Int = 99999999999999999999\n}",
+            &interner,
+        );
+
+        let errors: Vec<_> = result.diagnostics.errors().collect();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].code, codes::NUMBER_OUT_OF_RANGE);
+        assert_eq!(errors[0].title, "integer literal out of range for Int");
+        assert_eq!(errors[0].primary_span(), Some(Span::new(28, 38)));
+        assert_eq!(errors[1].title, "integer literal out of range for Int");
+    }
 }
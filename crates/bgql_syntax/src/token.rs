@@ -13,6 +13,8 @@ pub enum TokenKind {
     // Special tokens
     Eof,
     Error,
+    /// A string or block string with no closing quote.
+    UnterminatedString,
     Whitespace,
     Newline,
     Comment,
@@ -137,6 +139,7 @@ impl TokenKind {
         match self {
             Self::Eof => "<eof>",
             Self::Error => "<error>",
+            Self::UnterminatedString => "<unterminated-string>",
             Self::Whitespace => "<whitespace>",
             Self::Newline => "<newline>",
             Self::Comment => "<comment>",
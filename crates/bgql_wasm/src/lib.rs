@@ -21,9 +21,12 @@
 //! }
 //! ```
 
-use bgql_core::Interner;
-use bgql_syntax::{format, parse, Definition, Type, TypeDefinition};
+use bgql_core::{ColumnUnit, Interner, LineIndex, Span};
+use bgql_semantic::checker;
+use bgql_semantic::{HirDatabase, TypeRegistry};
+use bgql_syntax::{format_with_options, parse, Definition, FormatOptions, Type, TypeDefinition};
 use serde::Serialize;
+use std::cell::{Ref, RefCell};
 use wasm_bindgen::prelude::*;
 
 #[derive(Serialize, Clone)]
@@ -75,15 +78,31 @@ pub struct ValidateResult {
     pub diagnostics: Vec<Diagnostic>,
 }
 
+/// The signature and description of the symbol under the cursor.
+#[derive(Serialize, Clone)]
+pub struct HoverInfo {
+    pub signature: String,
+    pub description: Option<String>,
+    /// Byte offsets of the hovered name.
+    pub start: u32,
+    pub end: u32,
+}
+
 /// The main Better GraphQL WebAssembly API.
 #[wasm_bindgen]
-pub struct BetterGraphQL {}
+pub struct BetterGraphQL {
+    /// Analysis of the last source queried by offset, reused while the
+    /// source doesn't change.
+    analysis: RefCell<Option<Analysis>>,
+}
 
 #[wasm_bindgen]
 impl BetterGraphQL {
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
-        Self {}
+        Self {
+            analysis: RefCell::new(None),
+        }
     }
 
     #[wasm_bindgen]
@@ -91,45 +110,13 @@ impl BetterGraphQL {
         let interner = Interner::new();
         let result = parse(source, &interner);
 
+        let index = LineIndex::new(source);
         let mut diagnostics = Vec::new();
         let mut types = Vec::new();
 
         // Collect diagnostics
         for diag in result.diagnostics.iter() {
-            let (start_line, start_col, end_line, end_col) = if let Some(span) = diag.primary_span()
-            {
-                let before = &source[..span.start as usize];
-                let start_line = before.lines().count() as u32;
-                let start_col = before
-                    .lines()
-                    .last()
-                    .map(|l| l.len() as u32 + 1)
-                    .unwrap_or(1);
-                let end_before = &source[..span.end as usize];
-                let end_line = end_before.lines().count() as u32;
-                let end_col = end_before
-                    .lines()
-                    .last()
-                    .map(|l| l.len() as u32 + 1)
-                    .unwrap_or(1);
-                (start_line, start_col, end_line, end_col)
-            } else {
-                (1, 1, 1, 1)
-            };
-
-            diagnostics.push(Diagnostic {
-                severity: if diag.severity == bgql_core::DiagnosticSeverity::Error {
-                    "error".to_string()
-                } else {
-                    "warning".to_string()
-                },
-                message: diag.title.clone(),
-                code: diag.code.clone(),
-                start_line,
-                start_column: start_col,
-                end_line,
-                end_column: end_col,
-            });
+            diagnostics.push(to_diagnostic(diag, &index));
         }
 
         // Extract types
@@ -150,19 +137,21 @@ impl BetterGraphQL {
 
     #[wasm_bindgen]
     pub fn format(&self, source: &str) -> Result<String, JsValue> {
-        let interner = Interner::new();
-        let result = parse(source, &interner);
-
-        if result.diagnostics.has_errors() {
-            let errors: Vec<String> = result
-                .diagnostics
-                .errors()
-                .map(|e| e.title.clone())
-                .collect();
-            return Err(JsValue::from_str(&errors.join("; ")));
-        }
+        format_source(source, FormatOptions::default()).map_err(|e| JsValue::from_str(&e))
+    }
 
-        Ok(format(&result.document, &interner))
+    /// Formats `source` with options given as a JS object, e.g.
+    /// `{ indentSize: 4, useTabs: false }`. Missing options take their
+    /// default.
+    #[wasm_bindgen]
+    pub fn format_with_options(&self, source: &str, options: JsValue) -> Result<String, JsValue> {
+        let options = if options.is_undefined() || options.is_null() {
+            FormatOptions::default()
+        } else {
+            serde_wasm_bindgen::from_value(options)
+                .map_err(|e| JsValue::from_str(&format!("Invalid format options: {}", e)))?
+        };
+        format_source(source, options).map_err(|e| JsValue::from_str(&e))
     }
 
     #[wasm_bindgen]
@@ -170,62 +159,341 @@ impl BetterGraphQL {
         env!("CARGO_PKG_VERSION").to_string()
     }
 
+    /// Parses `source` and, when it parses without errors, type checks it.
     #[wasm_bindgen]
     pub fn validate(&self, source: &str) -> JsValue {
+        let result = self.analysis(source).validate_result();
+        serde_wasm_bindgen::to_value(&result).unwrap_or(JsValue::NULL)
+    }
+
+    /// Runs the parser and the semantic type checker over `source`.
+    #[wasm_bindgen]
+    pub fn check(&self, source: &str) -> JsValue {
+        self.validate(source)
+    }
+
+    /// Returns the diagnostics whose span contains the byte `offset`.
+    #[wasm_bindgen]
+    pub fn diagnostics_at(&self, source: &str, offset: u32) -> JsValue {
+        let diagnostics = self.analysis(source).diagnostics_at(offset);
+        serde_wasm_bindgen::to_value(&diagnostics).unwrap_or(JsValue::NULL)
+    }
+
+    /// Returns the signature of the type or field under the byte `offset`,
+    /// or `null`.
+    #[wasm_bindgen]
+    pub fn hover_at(&self, source: &str, offset: u32) -> JsValue {
+        match self.analysis(source).hover_at(offset) {
+            Some(hover) => serde_wasm_bindgen::to_value(&hover).unwrap_or(JsValue::NULL),
+            None => JsValue::NULL,
+        }
+    }
+}
+
+impl BetterGraphQL {
+    /// Returns the analysis of `source`, parsing it only if it differs from
+    /// the previously analyzed source.
+    fn analysis(&self, source: &str) -> Ref<'_, Analysis> {
+        let cached =
+            matches!(&*self.analysis.borrow(), Some(analysis) if analysis.source == source);
+        if !cached {
+            *self.analysis.borrow_mut() = Some(Analysis::new(source));
+        }
+        Ref::map(self.analysis.borrow(), |analysis| {
+            analysis.as_ref().expect("analysis was just stored")
+        })
+    }
+}
+
+impl Default for BetterGraphQL {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Formats `source` with `options`, failing with the parse errors joined by
+/// `; ` when it doesn't parse.
+fn format_source(source: &str, options: FormatOptions) -> Result<String, String> {
+    let interner = Interner::new();
+    let result = parse(source, &interner);
+
+    if result.diagnostics.has_errors() {
+        let errors: Vec<String> = result
+            .diagnostics
+            .errors()
+            .map(|e| e.title.clone())
+            .collect();
+        return Err(errors.join("; "));
+    }
+
+    Ok(format_with_options(&result.document, &interner, options))
+}
+
+/// The result of parsing and type checking a source once, kept for
+/// answering queries at different offsets.
+struct Analysis {
+    source: String,
+    valid: bool,
+    diagnostics: Vec<(Option<Span>, Diagnostic)>,
+    /// Hovers of type definitions, by type name.
+    types: Vec<(String, HoverInfo)>,
+    /// Hovers of field definitions, located at the field names.
+    fields: Vec<HoverInfo>,
+}
+
+impl Analysis {
+    /// Parses `source` and, when it parses without errors, type checks it.
+    ///
+    /// The checker only runs on documents that parse without errors, since
+    /// it would otherwise report the gaps the parser left behind.
+    fn new(source: &str) -> Self {
         let interner = Interner::new();
         let result = parse(source, &interner);
+        let index = LineIndex::new(source);
 
-        let mut diagnostics = Vec::new();
+        let mut diagnostics: Vec<_> = result
+            .diagnostics
+            .iter()
+            .map(|diag| (diag.primary_span(), to_diagnostic(diag, &index)))
+            .collect();
+        let mut valid = !result.diagnostics.has_errors();
 
-        for diag in result.diagnostics.iter() {
-            let (start_line, start_col, end_line, end_col) = if let Some(span) = diag.primary_span()
-            {
-                let before = &source[..span.start as usize];
-                let start_line = before.lines().count() as u32;
-                let start_col = before
-                    .lines()
-                    .last()
-                    .map(|l| l.len() as u32 + 1)
-                    .unwrap_or(1);
-                let end_before = &source[..span.end as usize];
-                let end_line = end_before.lines().count() as u32;
-                let end_col = end_before
-                    .lines()
-                    .last()
-                    .map(|l| l.len() as u32 + 1)
-                    .unwrap_or(1);
-                (start_line, start_col, end_line, end_col)
-            } else {
-                (1, 1, 1, 1)
-            };
+        if valid {
+            let types = TypeRegistry::new();
+            let hir = HirDatabase::new();
+            let check_result = checker::check(&result.document, &types, &hir, &interner);
+            diagnostics.extend(
+                check_result
+                    .diagnostics
+                    .iter()
+                    .map(|diag| (diag.primary_span(), to_diagnostic(diag, &index))),
+            );
+            valid = check_result.is_ok();
+        }
 
-            diagnostics.push(Diagnostic {
-                severity: if diag.severity == bgql_core::DiagnosticSeverity::Error {
-                    "error".to_string()
-                } else {
-                    "warning".to_string()
-                },
-                message: diag.title.clone(),
-                code: diag.code.clone(),
-                start_line,
-                start_column: start_col,
-                end_line,
-                end_column: end_col,
-            });
+        let mut types = Vec::new();
+        let mut fields = Vec::new();
+        for def in &result.document.definitions {
+            if let Definition::Type(type_def) = def {
+                let (name, hover) = type_hover(type_def, &interner);
+                types.push((name, hover));
+                fields.extend(field_hovers(type_def, &interner));
+            }
         }
 
-        let validate_result = ValidateResult {
-            valid: !result.diagnostics.has_errors(),
+        Self {
+            source: source.to_string(),
+            valid,
             diagnostics,
+            types,
+            fields,
+        }
+    }
+
+    fn validate_result(&self) -> ValidateResult {
+        ValidateResult {
+            valid: self.valid,
+            diagnostics: self
+                .diagnostics
+                .iter()
+                .map(|(_, diag)| diag.clone())
+                .collect(),
+        }
+    }
+
+    fn diagnostics_at(&self, offset: u32) -> Vec<Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|(span, _)| {
+                span.is_some_and(|span| {
+                    span.contains(offset) || (span.is_empty() && span.start == offset)
+                })
+            })
+            .map(|(_, diag)| diag.clone())
+            .collect()
+    }
+
+    /// Finds the field whose name is under `offset`, or else the type named
+    /// by the word under it.
+    fn hover_at(&self, offset: u32) -> Option<HoverInfo> {
+        if let Some(field) = self
+            .fields
+            .iter()
+            .find(|field| field.start <= offset && offset < field.end)
+        {
+            return Some(field.clone());
+        }
+
+        let (start, end) = word_at(&self.source, offset as usize)?;
+        let word = &self.source[start..end];
+        let mut hover = match self.types.iter().find(|(name, _)| name == word) {
+            Some((_, hover)) => hover.clone(),
+            None => builtin_type_hover(word)?,
         };
+        hover.start = start as u32;
+        hover.end = end as u32;
+        Some(hover)
+    }
+}
 
-        serde_wasm_bindgen::to_value(&validate_result).unwrap_or(JsValue::NULL)
+/// Returns the byte range of the identifier around `offset`.
+fn word_at(source: &str, offset: usize) -> Option<(usize, usize)> {
+    let bytes = source.as_bytes();
+    let is_identifier = |c: u8| c.is_ascii_alphanumeric() || c == b'_';
+    let mut start = offset.min(bytes.len());
+    while start > 0 && is_identifier(bytes[start - 1]) {
+        start -= 1;
+    }
+    let mut end = offset.min(bytes.len());
+    while end < bytes.len() && is_identifier(bytes[end]) {
+        end += 1;
     }
+    (start < end).then_some((start, end))
 }
 
-impl Default for BetterGraphQL {
-    fn default() -> Self {
-        Self::new()
+/// Builds the hover of a type definition, as the language server shows it.
+fn type_hover(type_def: &TypeDefinition, interner: &Interner) -> (String, HoverInfo) {
+    let info = extract_type_info(type_def, interner);
+    let name = &info.name;
+    let first_five = |values: &[String]| {
+        let suffix = if values.len() > 5 { ", ..." } else { "" };
+        format!("{}{}", values[..values.len().min(5)].join(", "), suffix)
+    };
+    let signature = match type_def {
+        TypeDefinition::Object(_) if info.implements.is_empty() => {
+            format!("type {} {{ ... }}", name)
+        }
+        TypeDefinition::Object(_) => format!(
+            "type {} implements {} {{ ... }}",
+            name,
+            info.implements.join(" & ")
+        ),
+        TypeDefinition::Interface(_) => format!("interface {} {{ ... }}", name),
+        TypeDefinition::Enum(_) => format!("enum {} {{ {} }}", name, first_five(&info.values)),
+        TypeDefinition::Union(_) => format!("union {} = {}", name, info.members.join(" | ")),
+        TypeDefinition::Input(_) => format!("input {} {{ ... }}", name),
+        TypeDefinition::Scalar(_) => format!("scalar {}", name),
+        TypeDefinition::Opaque(_) => format!("opaque {} = {}", name, info.members[0]),
+        TypeDefinition::TypeAlias(_) => format!("alias {} = {}", name, info.members[0]),
+        TypeDefinition::InputUnion(_) => {
+            format!("input union {} = {}", name, info.members.join(" | "))
+        }
+        TypeDefinition::InputEnum(_) => {
+            format!("input enum {} {{ {} }}", name, first_five(&info.values))
+        }
+    };
+
+    let hover = HoverInfo {
+        signature,
+        description: info.description.clone(),
+        start: 0,
+        end: 0,
+    };
+    (info.name, hover)
+}
+
+/// Builds the hovers of the fields of a type definition.
+fn field_hovers(type_def: &TypeDefinition, interner: &Interner) -> Vec<HoverInfo> {
+    let fields = match type_def {
+        TypeDefinition::Object(obj) => &obj.fields,
+        TypeDefinition::Interface(iface) => &iface.fields,
+        TypeDefinition::Input(inp) => {
+            return inp
+                .fields
+                .iter()
+                .map(|f| HoverInfo {
+                    signature: format!(
+                        "{}: {}",
+                        interner.get(f.name.value),
+                        format_type(&f.ty, interner)
+                    ),
+                    description: f.description.as_ref().map(|d| d.value.to_string()),
+                    start: f.name.span.start,
+                    end: f.name.span.end,
+                })
+                .collect();
+        }
+        _ => return Vec::new(),
+    };
+
+    fields
+        .iter()
+        .map(|f| {
+            let arguments: Vec<String> = f
+                .arguments
+                .iter()
+                .map(|a| {
+                    format!(
+                        "{}: {}",
+                        interner.get(a.name.value),
+                        format_type(&a.ty, interner)
+                    )
+                })
+                .collect();
+            let arguments = if arguments.is_empty() {
+                String::new()
+            } else {
+                format!("({})", arguments.join(", "))
+            };
+            HoverInfo {
+                signature: format!(
+                    "{}{}: {}",
+                    interner.get(f.name.value),
+                    arguments,
+                    format_type(&f.ty, interner)
+                ),
+                description: f.description.as_ref().map(|d| d.value.to_string()),
+                start: f.name.span.start,
+                end: f.name.span.end,
+            }
+        })
+        .collect()
+}
+
+/// Describes the built-in types.
+fn builtin_type_hover(name: &str) -> Option<HoverInfo> {
+    let description = match name {
+        "Int" => "A signed 32-bit integer.",
+        "Float" => "A signed double-precision floating-point value.",
+        "String" => "A UTF-8 character sequence.",
+        "Boolean" => "A `true` or `false` value.",
+        "ID" => "A unique identifier, serialized as a string.",
+        "Option" => "An optional value that may be null.",
+        "List" => "A list of values.",
+        "DateTime" => "An ISO 8601 date-time string.",
+        "JSON" => "Arbitrary JSON data.",
+        _ => return None,
+    };
+    Some(HoverInfo {
+        signature: name.to_string(),
+        description: Some(description.to_string()),
+        start: 0,
+        end: 0,
+    })
+}
+
+/// Converts a diagnostic to its JavaScript shape, with 1-based positions.
+fn to_diagnostic(diag: &bgql_core::Diagnostic, index: &LineIndex) -> Diagnostic {
+    let (start_line, start_col, end_line, end_col) = if let Some(span) = diag.primary_span() {
+        let (start_line, start_col) = index.offset_to_line_col(span.start, ColumnUnit::Byte);
+        let (end_line, end_col) = index.offset_to_line_col(span.end, ColumnUnit::Byte);
+        (start_line + 1, start_col + 1, end_line + 1, end_col + 1)
+    } else {
+        (1, 1, 1, 1)
+    };
+
+    Diagnostic {
+        severity: if diag.severity == bgql_core::DiagnosticSeverity::Error {
+            "error".to_string()
+        } else {
+            "warning".to_string()
+        },
+        message: diag.title.clone(),
+        code: diag.code.clone(),
+        start_line,
+        start_column: start_col,
+        end_line,
+        end_column: end_col,
     }
 }
 
@@ -411,3 +679,73 @@ pub fn parse_schema(source: &str) -> JsValue {
 pub fn version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_reports_semantic_errors() {
+        let result = Analysis::new("type Query { user: User }").validate_result();
+        assert!(!result.valid);
+        let diag = &result.diagnostics[0];
+        assert_eq!(diag.code, bgql_core::diagnostics::codes::UNDEFINED_TYPE);
+        assert_eq!((diag.start_line, diag.start_column), (1, 20));
+
+        let result =
+            Analysis::new("type User { id: ID }\ntype Query { user: User }").validate_result();
+        assert!(result.valid);
+        assert!(result.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_diagnostic_at_start_of_later_line() {
+        let result = Analysis::new("type A { id: ID }\ntype A { id: ID }\n").validate_result();
+        let diag = result
+            .diagnostics
+            .iter()
+            .find(|d| d.code == bgql_core::diagnostics::codes::DUPLICATE_TYPE)
+            .unwrap();
+        assert_eq!((diag.start_line, diag.start_column), (2, 6));
+
+        let result = Analysis::new("type Query { a: String }\n\n}").validate_result();
+        let diag = &result.diagnostics[0];
+        assert_eq!((diag.start_line, diag.start_column), (3, 1));
+    }
+
+    #[test]
+    fn test_format_with_js_options() {
+        let options: FormatOptions =
+            serde_json::from_value(serde_json::json!({ "indentSize": 4, "useTabs": false }))
+                .unwrap();
+        let formatted = format_source("type Query { a: String b: Int }", options).unwrap();
+        assert!(formatted.contains("\n    a: String\n"), "{formatted}");
+
+        assert!(format_source("type Query {", FormatOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_queries_at_offset() {
+        let source = "\"A registered user\"\ntype User { id: ID }\ntype Query { user(id: ID): User post: Post }";
+        let bgql = BetterGraphQL::new();
+
+        let post = source.rfind("Post").unwrap() as u32;
+        let diagnostics = bgql.analysis(source).diagnostics_at(post + 1);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].code,
+            bgql_core::diagnostics::codes::UNDEFINED_TYPE
+        );
+        assert!(bgql.analysis(source).diagnostics_at(0).is_empty());
+
+        let user_ref = source.rfind("User").unwrap() as u32;
+        let hover = bgql.analysis(source).hover_at(user_ref + 2).unwrap();
+        assert_eq!(hover.signature, "type User { ... }");
+        assert_eq!(hover.description.as_deref(), Some("A registered user"));
+        assert_eq!((hover.start, hover.end), (user_ref, user_ref + 4));
+
+        let field = source.find("user(").unwrap() as u32;
+        let hover = bgql.analysis(source).hover_at(field).unwrap();
+        assert_eq!(hover.signature, "user(id: ID): User");
+    }
+}
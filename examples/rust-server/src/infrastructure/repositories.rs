@@ -93,7 +93,7 @@ impl UserRepository for InMemoryUserRepository {
     async fn find_all(&self, pagination: Pagination) -> Connection<User> {
         let users = self.users.read().await;
         let mut all: Vec<_> = users.values().cloned().collect();
-        all.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        all.sort_by_key(|user| user.created_at);
 
         let total = all.len();
         let first = pagination.first.unwrap_or(10);
@@ -222,7 +222,7 @@ impl PostRepository for InMemoryPostRepository {
             })
             .cloned()
             .collect();
-        all.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        all.sort_by_key(|post| std::cmp::Reverse(post.created_at));
 
         let total = all.len();
         let first = pagination.first.unwrap_or(10);
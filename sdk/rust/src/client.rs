@@ -32,12 +32,17 @@
 //! ```
 
 use crate::error::{ErrorCode, SdkError, SdkResult};
+use crate::http::{send_request, ConnectionPool};
 use crate::typed::{GraphQLError as TypedGraphQLError, TypedOperation, TypedResponse};
+use bgql_core::Interner;
+use bgql_syntax::{parse, Definition, OperationType};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::time::timeout;
 
 // Re-export legacy types for backwards compatibility
 pub use crate::result::{BgqlError, BgqlResult};
@@ -55,6 +60,10 @@ pub struct ClientConfig {
     pub headers: HashMap<String, String>,
     /// Retry delay base (in milliseconds) - exponential backoff will be applied.
     pub retry_delay_ms: u64,
+    /// Maximum idle connections kept per host. 0 opens a new connection per request.
+    pub pool_size: usize,
+    /// How long an idle connection is kept for reuse.
+    pub keep_alive: Duration,
 }
 
 impl Default for ClientConfig {
@@ -65,6 +74,8 @@ impl Default for ClientConfig {
             max_retries: 3,
             headers: HashMap::new(),
             retry_delay_ms: 100,
+            pool_size: 8,
+            keep_alive: Duration::from_secs(90),
         }
     }
 }
@@ -101,6 +112,18 @@ impl ClientConfig {
         self.retry_delay_ms = delay;
         self
     }
+
+    /// Sets the maximum idle connections kept per host.
+    pub fn pool_size(mut self, size: usize) -> Self {
+        self.pool_size = size;
+        self
+    }
+
+    /// Sets how long idle connections are kept for reuse.
+    pub fn keep_alive(mut self, keep_alive: Duration) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
 }
 
 /// Middleware function type.
@@ -136,6 +159,31 @@ pub struct Request {
     pub headers: HashMap<String, String>,
 }
 
+impl Request {
+    /// Returns true if the operation to execute is a mutation.
+    ///
+    /// The operation is the one named by `operation_name`, or the first.
+    pub fn is_mutation(&self) -> bool {
+        let interner = Interner::new();
+        let document = parse(&self.query, &interner).document;
+        document
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Operation(operation) => Some(operation),
+                _ => None,
+            })
+            .find(|operation| match &self.operation_name {
+                Some(name) => operation
+                    .name
+                    .as_ref()
+                    .is_some_and(|op_name| interner.get(op_name.value) == *name),
+                None => true,
+            })
+            .is_some_and(|operation| operation.operation == OperationType::Mutation)
+    }
+}
+
 /// A GraphQL response.
 #[derive(Debug, Clone, Deserialize)]
 pub struct Response {
@@ -155,39 +203,33 @@ pub struct GraphQLError {
 
 /// HTTP client implementation using simple TCP sockets.
 /// This avoids the need for external HTTP client dependencies.
+///
+/// Connections are kept alive and reused through a [`ConnectionPool`].
 struct HttpClient {
     timeout: Duration,
+    pool: ConnectionPool,
 }
 
 impl HttpClient {
-    fn new(timeout: Duration) -> Self {
-        Self { timeout }
+    fn new(config: &ClientConfig) -> Self {
+        Self {
+            timeout: config.timeout,
+            pool: ConnectionPool::new(config.pool_size, config.keep_alive),
+        }
     }
 
+    /// Posts `body` to `url`. `idempotent` is false for mutations, which are
+    /// never sent twice.
     async fn post(
         &self,
         url: &str,
         body: &str,
         headers: &HashMap<String, String>,
+        idempotent: bool,
     ) -> SdkResult<String> {
-        use tokio::io::{AsyncReadExt, AsyncWriteExt};
-        use tokio::net::TcpStream;
-        use tokio::time::timeout;
-
         // Parse URL
         let (host, port, path) = parse_url(url)?;
-
-        // Connect with timeout
-        let connect_future = TcpStream::connect(format!("{}:{}", host, port));
-        let mut stream = timeout(self.timeout, connect_future)
-            .await
-            .map_err(|_| SdkError::timeout())?
-            .map_err(|e| {
-                SdkError::new(
-                    ErrorCode::ConnectionRefused,
-                    format!("Connection failed: {}", e),
-                )
-            })?;
+        let addr = format!("{}:{}", host, port);
 
         // Build HTTP request
         let mut request = format!(
@@ -195,10 +237,15 @@ impl HttpClient {
              Host: {}\r\n\
              Content-Type: application/json\r\n\
              Content-Length: {}\r\n\
-             Connection: close\r\n",
+             Connection: {}\r\n",
             path,
             host,
-            body.len()
+            body.len(),
+            if self.pool.is_enabled() {
+                "keep-alive"
+            } else {
+                "close"
+            }
         );
 
         for (key, value) in headers {
@@ -207,25 +254,54 @@ impl HttpClient {
         request.push_str("\r\n");
         request.push_str(body);
 
-        // Send request
-        let write_future = stream.write_all(request.as_bytes());
-        timeout(self.timeout, write_future)
+        // Prefer an idle pooled connection. The server may have closed it
+        // since, in which case an idempotent request is retried once on a
+        // fresh one; a mutation may already have been received, so it isn't.
+        let response_bytes = match self.pool.checkout(&addr) {
+            Some(stream) => match self.send(&addr, stream, request.as_bytes()).await {
+                Ok(response) => response,
+                Err(_) if idempotent => {
+                    let stream = self.connect(&addr).await?;
+                    self.send(&addr, stream, request.as_bytes()).await?
+                }
+                Err(e) => return Err(e),
+            },
+            None => {
+                let stream = self.connect(&addr).await?;
+                self.send(&addr, stream, request.as_bytes()).await?
+            }
+        };
+
+        let response_str = String::from_utf8_lossy(&response_bytes);
+
+        // Parse HTTP response
+        parse_http_response(&response_str)
+    }
+
+    async fn connect(&self, addr: &str) -> SdkResult<TcpStream> {
+        // Connect with timeout
+        timeout(self.timeout, self.pool.connect(addr))
             .await
             .map_err(|_| SdkError::timeout())?
-            .map_err(|e| SdkError::network(format!("Write failed: {}", e)))?;
+            .map_err(|e| {
+                SdkError::new(
+                    ErrorCode::ConnectionRefused,
+                    format!("Connection failed: {}", e),
+                )
+            })
+    }
 
-        // Read response
-        let mut response_bytes = Vec::new();
-        let read_future = stream.read_to_end(&mut response_bytes);
-        timeout(self.timeout, read_future)
+    /// Sends a request and returns the connection to the pool if it can be reused.
+    async fn send(&self, addr: &str, mut stream: TcpStream, request: &[u8]) -> SdkResult<Vec<u8>> {
+        let (response, reusable) = timeout(self.timeout, send_request(&mut stream, request))
             .await
             .map_err(|_| SdkError::timeout())?
-            .map_err(|e| SdkError::network(format!("Read failed: {}", e)))?;
+            .map_err(|e| SdkError::network(format!("Request failed: {}", e)))?;
 
-        let response_str = String::from_utf8_lossy(&response_bytes);
-
-        // Parse HTTP response
-        parse_http_response(&response_str)
+        if reusable {
+            self.pool.checkin(addr, stream);
+        }
+        Ok(response)
     }
 }
 
@@ -234,16 +310,13 @@ fn parse_url(url: &str) -> SdkResult<(String, u16, String)> {
     let url = url.trim();
 
     // Remove protocol
-    let without_protocol = if url.starts_with("https://") {
+    if url.starts_with("https://") {
         return Err(SdkError::new(
             ErrorCode::HttpsNotSupported,
             "HTTPS is not supported in the simple HTTP client. Use a proxy or configure your server for HTTP.",
         ));
-    } else if url.starts_with("http://") {
-        &url[7..]
-    } else {
-        url
-    };
+    }
+    let without_protocol = url.strip_prefix("http://").unwrap_or(url);
 
     // Split host:port and path
     let (host_port, path) = if let Some(slash_pos) = without_protocol.find('/') {
@@ -360,20 +433,20 @@ fn parse_chunked_body(body: &str) -> SdkResult<String> {
 pub struct BgqlClient {
     config: ClientConfig,
     middlewares: Vec<Middleware>,
+    /// Shared by clones so they reuse the same connections.
+    http: Arc<HttpClient>,
 }
 
 impl BgqlClient {
     /// Creates a new client with the given URL.
     pub fn new(url: impl Into<String>) -> Self {
-        Self {
-            config: ClientConfig::new(url),
-            middlewares: Vec::new(),
-        }
+        Self::with_config(ClientConfig::new(url))
     }
 
     /// Creates a new client with configuration.
     pub fn with_config(config: ClientConfig) -> Self {
         Self {
+            http: Arc::new(HttpClient::new(&config)),
             config,
             middlewares: Vec::new(),
         }
@@ -414,9 +487,16 @@ impl BgqlClient {
     /// Executes a raw request with retry logic.
     pub async fn execute_raw(&self, request: Request) -> SdkResult<Response> {
         let mut last_error = SdkError::network("No attempts made");
-        let http_client = HttpClient::new(self.config.timeout);
+        // A mutation whose request failed midway may have been applied, so
+        // it's never sent again
+        let idempotent = !request.is_mutation();
+        let max_retries = if idempotent {
+            self.config.max_retries
+        } else {
+            0
+        };
 
-        for attempt in 0..=self.config.max_retries {
+        for attempt in 0..=max_retries {
             if attempt > 0 {
                 // Exponential backoff
                 let delay = self.config.retry_delay_ms * (2_u64.pow(attempt - 1));
@@ -439,8 +519,9 @@ impl BgqlClient {
                 serde_json::to_string(&body).map_err(|e| SdkError::serialize(e.to_string()))?;
 
             // Execute HTTP request
-            match http_client
-                .post(&self.config.url, &body_str, &headers)
+            match self
+                .http
+                .post(&self.config.url, &body_str, &headers, idempotent)
                 .await
             {
                 Ok(response_body) => {
@@ -774,4 +855,145 @@ mod tests {
         let result = parse_chunked_body(chunked).unwrap();
         assert_eq!(result, "helloworld");
     }
+
+    /// Serves keep-alive responses and counts accepted connections.
+    async fn mock_server() -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/bgql", listener.local_addr().unwrap());
+        let accepted = Arc::new(AtomicUsize::new(0));
+
+        let counter = accepted.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    // One read per request is enough for these small bodies
+                    while let Ok(n) = stream.read(&mut buf).await {
+                        if n == 0 {
+                            break;
+                        }
+                        let body = r#"{"data":{"hello":"world"}}"#;
+                        let response = format!(
+                            "HTTP/1.1 200 OK\r\n\
+                             Content-Type: application/json\r\n\
+                             Content-Length: {}\r\n\
+                             \r\n{}",
+                            body.len(),
+                            body
+                        );
+                        if stream.write_all(response.as_bytes()).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        (url, accepted)
+    }
+
+    #[tokio::test]
+    async fn test_sequential_requests_reuse_connection() {
+        use std::sync::atomic::Ordering;
+
+        let (url, accepted) = mock_server().await;
+        let client = BgqlClient::new(url);
+
+        for _ in 0..3 {
+            let data = client
+                .query::<serde_json::Value>("query { hello }")
+                .execute()
+                .await
+                .unwrap();
+            assert_eq!(data["hello"], "world");
+        }
+
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+        assert_eq!(client.http.pool.connections_opened(), 1);
+    }
+
+    /// Answers one request per connection, then closes it without saying so.
+    async fn closing_server() -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/bgql", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 4096];
+                if stream.read(&mut buf).await.unwrap_or(0) == 0 {
+                    continue;
+                }
+                let body = r#"{"data":{"hello":"world"}}"#;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_stale_connection_retries_only_queries() {
+        let url = closing_server().await;
+        let client = BgqlClient::with_config(ClientConfig::new(url).max_retries(0));
+        let hello = || {
+            client
+                .query::<serde_json::Value>("query { hello }")
+                .execute()
+        };
+
+        hello().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        // The pooled connection is closed; the query is sent again
+        hello().await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let result = client
+            .mutate::<serde_json::Value>("mutation { hello }")
+            .execute()
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_request_is_mutation() {
+        let request = |query: &str, operation_name: Option<&str>| Request {
+            query: query.to_string(),
+            variables: None,
+            operation_name: operation_name.map(str::to_string),
+            headers: HashMap::new(),
+        };
+        let document = "query Get { hello } mutation Set { hello }";
+
+        assert!(!request("{ hello }", None).is_mutation());
+        assert!(!request(document, None).is_mutation());
+        assert!(request(document, Some("Set")).is_mutation());
+        assert!(request("mutation { hello }", None).is_mutation());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_pool_opens_connection_per_request() {
+        use std::sync::atomic::Ordering;
+
+        let (url, accepted) = mock_server().await;
+        let client = BgqlClient::with_config(ClientConfig::new(url).pool_size(0));
+
+        for _ in 0..3 {
+            client
+                .query::<serde_json::Value>("query { hello }")
+                .execute()
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(accepted.load(Ordering::SeqCst), 3);
+    }
 }
@@ -11,6 +11,9 @@
 //! - **@resources** - Resource usage hints for scheduling
 //! - **@resumable** - Pause/resume support for long-running queries
 //!
+//! It also re-exports the handler for the authorization directives
+//! **@hasRole** and **@authScope**, which honor the server's [`RoleHierarchy`].
+//!
 //! # Example
 //!
 //! ```ignore
@@ -33,6 +36,8 @@
 pub use bgql_runtime::directives::{
     // Utility function
     create_streaming_directives,
+    // @hasRole and @authScope handler
+    AuthDirectiveHandler,
     // @binary directive
     BinaryDirective,
     // @boundary directive
@@ -54,12 +59,17 @@ pub use bgql_runtime::directives::{
     ResourcesDirective,
     // @resumable directive
     ResumableDirective,
+    // Role implication for authorization directives
+    RoleHierarchy,
     // Serialization strategy enum
     SerializeStrategy,
     // @server directive
     ServerDirective,
     // @stream directive (extended)
     StreamDirective,
+    // Context keys read by authorization directives
+    ROLES_CONTEXT_KEY,
+    SCOPES_CONTEXT_KEY,
 };
 
 // Re-export resource level from resource module
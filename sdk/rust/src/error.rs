@@ -2,6 +2,7 @@
 //!
 //! Provides compile-time guarantees for error handling with typed error codes.
 
+use bgql_runtime::resolver::UnknownResolver;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
@@ -30,6 +31,7 @@ pub enum ErrorCode {
     ExecutionError,
     NoOperation,
     NoData,
+    OperationTooLarge,
 
     // Schema errors
     SchemaError,
@@ -80,6 +82,7 @@ impl ErrorCode {
             Self::ExecutionError => "EXECUTION_ERROR",
             Self::NoOperation => "NO_OPERATION",
             Self::NoData => "NO_DATA",
+            Self::OperationTooLarge => "OPERATION_TOO_LARGE",
             Self::SchemaError => "SCHEMA_ERROR",
             Self::NoSchema => "NO_SCHEMA",
             Self::TypeNotFound => "TYPE_NOT_FOUND",
@@ -121,6 +124,7 @@ impl ErrorCode {
                 | Self::Conflict
                 | Self::InvalidUrl
                 | Self::NoOperation
+                | Self::OperationTooLarge
         )
     }
 
@@ -241,6 +245,19 @@ impl SdkError {
         Self::new(ErrorCode::InternalError, message)
     }
 
+    /// Creates an error listing resolvers registered for types or fields
+    /// the schema doesn't define.
+    pub fn unknown_resolvers(unknown: &[UnknownResolver]) -> Self {
+        let unknown: Vec<String> = unknown.iter().map(ToString::to_string).collect();
+        Self::new(
+            ErrorCode::FieldNotFound,
+            format!(
+                "Resolvers for fields not in the schema: {}",
+                unknown.join("; ")
+            ),
+        )
+    }
+
     /// Returns true if this error is retryable.
     pub fn is_retryable(&self) -> bool {
         self.code.is_retryable()
@@ -309,6 +326,88 @@ impl<T, E: std::error::Error> ResultExt<T> for std::result::Result<T, E> {
     }
 }
 
+/// A domain error returned as a member of an error union, such as
+/// `NotFoundError` in `union UserResult = User | NotFoundError`.
+///
+/// # Example
+///
+/// ```ignore
+/// struct NotFound { id: String }
+///
+/// impl DomainError for NotFound {
+///     fn typename(&self) -> &str { "NotFoundError" }
+///     fn message(&self) -> String { format!("User '{}' not found", self.id) }
+///     fn code(&self) -> &str { "NOT_FOUND" }
+/// }
+///
+/// // `Err(NotFound)` serializes as
+/// // `{ "__typename": "NotFoundError", "message": ..., "code": ... }`
+/// let resolvers = ResolverBuilder::<MySchema>::new()
+///     .union_query::<GetUserArgs, (), User, NotFound, _, _>("user", |args, _| async move {
+///         repo.find(&args.id).ok_or(NotFound { id: args.id })
+///     })
+///     .build();
+/// ```
+pub trait DomainError {
+    /// Name of the union member type.
+    fn typename(&self) -> &str;
+
+    /// Human-readable message.
+    fn message(&self) -> String;
+
+    /// Machine-readable code.
+    fn code(&self) -> &str;
+
+    /// Further fields of the member type, such as `resourceId`.
+    fn fields(&self) -> serde_json::Map<String, serde_json::Value> {
+        serde_json::Map::new()
+    }
+
+    /// Returns the union member with its `__typename`, `message` and `code`.
+    fn to_union_member(&self) -> serde_json::Value {
+        let mut member = self.fields();
+        member.insert("__typename".to_string(), self.typename().into());
+        member.insert("message".to_string(), self.message().into());
+        member.insert("code".to_string(), self.code().into());
+        serde_json::Value::Object(member)
+    }
+}
+
+/// The success member of an error union, such as `User` in
+/// `union UserResult = User | NotFoundError`.
+pub trait UnionMember {
+    /// Name of the union member type.
+    fn typename(&self) -> &str;
+}
+
+/// A resolver result that serializes as a member of an error union.
+///
+/// Both arms carry their `__typename`, so clients can select on the
+/// union with inline fragments.
+#[derive(Debug, Clone)]
+pub struct ErrorUnion<T, E>(pub std::result::Result<T, E>);
+
+impl<T, E> From<std::result::Result<T, E>> for ErrorUnion<T, E> {
+    fn from(result: std::result::Result<T, E>) -> Self {
+        Self(result)
+    }
+}
+
+impl<T: Serialize + UnionMember, E: DomainError> Serialize for ErrorUnion<T, E> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.0 {
+            Ok(value) => {
+                let mut member = serde_json::to_value(value).map_err(serde::ser::Error::custom)?;
+                if let serde_json::Value::Object(fields) = &mut member {
+                    fields.insert("__typename".to_string(), value.typename().into());
+                }
+                member.serialize(serializer)
+            }
+            Err(error) => error.to_union_member().serialize(serializer),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
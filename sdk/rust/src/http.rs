@@ -1,27 +1,55 @@
 //! Built-in HTTP server for BGQL.
 //!
 //! This module provides a complete HTTP server that handles:
-//! - POST /bgql - GraphQL queries and mutations
-//! - GET /bgql - Playground UI
+//! - POST /bgql (or /graphql) - GraphQL queries and mutations, followed by
+//!   the binary data of their fields for clients accepting
+//!   `application/vnd.bgql.binary-stream`
+//! - OPTIONS /bgql (or /graphql) - CORS preflight
+//! - GET /bgql - Playground UI, or a `graphql-transport-ws` WebSocket upgrade
 //! - GET /health - Health check
 //! - GET /.well-known/bgql - Server capabilities
+//! - GET /metrics - Prometheus metrics, if enabled
+//!
+//! It also holds the keep-alive connection pool used by the client.
 
+use bgql_runtime::binary_transport::{
+    BinaryProtocol, CONTENT_TYPE_BINARY_STREAM, DEFAULT_CHUNK_SIZE,
+};
 use bytes::Bytes;
-use http_body_util::{BodyExt, Full};
-use hyper::body::Incoming;
+use futures_util::{SinkExt, StreamExt};
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use hyper::body::{Body, Incoming};
+use hyper::header;
 use hyper::server::conn::http1;
 use hyper::service::service_fn;
+use hyper::upgrade::{OnUpgrade, Upgraded};
 use hyper::{Method, Request, Response, StatusCode};
 use hyper_util::rt::TokioIo;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::convert::Infallible;
+use std::future::Future;
+use std::io;
 use std::net::SocketAddr;
-use std::sync::Arc;
-use tokio::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinSet;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::{CloseFrame, Role};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
 use tracing::{debug, error, info};
 
 use crate::error::SdkResult;
+use crate::metrics::Metrics;
 use crate::server::{BgqlServer, Context, ServerConfig};
+use crate::ws::{self, WsSession};
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct GraphQLRequest {
@@ -56,6 +84,29 @@ fn full<T: Into<Bytes>>(chunk: T) -> BoxBody {
         .boxed()
 }
 
+/// Room in a request body for the variables and the rest of the JSON
+/// envelope around the operation.
+const BODY_ENVELOPE_BYTES: usize = 64 * 1024;
+
+/// Returns the largest request body accepted for operations of at most
+/// `max_operation_bytes`. Escaping the operation as a JSON string can
+/// double its size.
+fn body_limit(max_operation_bytes: usize) -> usize {
+    if max_operation_bytes == 0 {
+        return usize::MAX;
+    }
+    max_operation_bytes
+        .saturating_mul(2)
+        .saturating_add(BODY_ENVELOPE_BYTES)
+}
+
+fn body_too_large(limit: usize) -> Response<BoxBody> {
+    error_response(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        &format!("Request body exceeds the limit of {} bytes", limit),
+    )
+}
+
 fn json_response<T: Serialize>(data: &T) -> Response<BoxBody> {
     Response::builder()
         .status(StatusCode::OK)
@@ -83,6 +134,7 @@ fn error_response(status: StatusCode, message: &str) -> Response<BoxBody> {
 
 pub(crate) async fn handle_graphql_request(
     body_bytes: Bytes,
+    headers: &header::HeaderMap,
     server: &BgqlServer,
 ) -> Response<BoxBody> {
     let gql_request: GraphQLRequest = match serde_json::from_slice(&body_bytes) {
@@ -98,6 +150,14 @@ pub(crate) async fn handle_graphql_request(
     );
 
     let ctx = Context::new();
+    let accepts_binary = headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(BinaryProtocol::accepts_binary);
+    if accepts_binary {
+        return binary_response(server, gql_request, ctx).await;
+    }
+
     let result = server
         .execute(&gql_request.query, gql_request.variables, ctx)
         .await;
@@ -132,6 +192,42 @@ pub(crate) async fn handle_graphql_request(
     }
 }
 
+/// Answers a client that negotiated binary transport with the JSON response
+/// followed by the blobs of the fields it references, as encoded by
+/// [`BinaryProtocol::encode_response`].
+async fn binary_response(
+    server: &BgqlServer,
+    gql_request: GraphQLRequest,
+    ctx: Context,
+) -> Response<BoxBody> {
+    let (response, binaries) = match server
+        .execute_binary(&gql_request.query, gql_request.variables, ctx)
+        .await
+    {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Query execution error: {}", e);
+            (
+                serde_json::json!({ "errors": [{ "message": e.to_string() }] }),
+                Vec::new(),
+            )
+        }
+    };
+
+    let mut body = Vec::new();
+    if let Err(e) =
+        BinaryProtocol::encode_response(&response, &binaries, &mut body, DEFAULT_CHUNK_SIZE).await
+    {
+        return error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string());
+    }
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", CONTENT_TYPE_BINARY_STREAM)
+        .header("Access-Control-Allow-Origin", "*")
+        .body(full(body))
+        .unwrap()
+}
+
 pub(crate) fn playground_html(endpoint: &str) -> String {
     format!(
         r#"<!DOCTYPE html>
@@ -159,6 +255,7 @@ pub(crate) fn playground_html(endpoint: &str) -> String {
         <ul>
             <li><code>POST {endpoint}</code> - GraphQL queries and mutations</li>
             <li><code>GET {endpoint}</code> - This playground</li>
+            <li><code>ws://localhost:4000{endpoint}</code> - Subscriptions over <code>graphql-transport-ws</code></li>
             <li><code>GET /health</code> - Health check</li>
         </ul>
     </div>
@@ -180,11 +277,14 @@ pub(crate) fn well_known_bgql(config: &ServerConfig) -> String {
         "version": "1.0",
         "endpoints": {
             "graphql": "/bgql",
+            "subscriptions": "/bgql",
             "health": "/health"
         },
+        "subscriptionProtocol": ws::PROTOCOL,
         "features": {
             "introspection": config.introspection,
-            "playground": config.playground
+            "playground": config.playground,
+            "metrics": config.metrics
         },
         "limits": {
             "maxDepth": config.max_depth,
@@ -198,16 +298,32 @@ pub(crate) fn health_response() -> &'static str {
     r#"{"status":"healthy"}"#
 }
 
-/// Starts the HTTP server.
-pub(crate) async fn run_server(server: Arc<BgqlServer>) -> SdkResult<()> {
-    let config = server.config();
+/// Binds a listener to the configured host and port.
+pub(crate) async fn bind(config: &ServerConfig) -> SdkResult<TcpListener> {
     let addr: SocketAddr = format!("{}:{}", config.host, config.port)
         .parse()
         .map_err(|e| crate::error::SdkError::server(format!("Invalid address: {}", e)))?;
 
-    let listener = TcpListener::bind(addr)
+    TcpListener::bind(addr)
         .await
+        .map_err(|e| crate::error::SdkError::server(format!("Failed to bind: {}", e)))
+}
+
+/// Serves connections from `listener` until `shutdown` completes.
+///
+/// Every connection is served on its own task. On shutdown each connection
+/// answers the request in flight before closing, and WebSocket sessions
+/// are closed.
+pub(crate) async fn run_server(
+    server: Arc<BgqlServer>,
+    listener: TcpListener,
+    shutdown: impl Future<Output = ()>,
+) -> SdkResult<()> {
+    let config = server.config();
+    let addr = listener
+        .local_addr()
         .map_err(|e| crate::error::SdkError::server(format!("Failed to bind: {}", e)))?;
+    let mut shutdown = std::pin::pin!(shutdown);
 
     println!();
     println!("╔════════════════════════════════════════════════════════╗");
@@ -220,78 +336,372 @@ pub(crate) async fn run_server(server: Arc<BgqlServer>) -> SdkResult<()> {
     }
     println!();
 
-    // Process requests sequentially to avoid Send requirement on BgqlServer
-    // TODO: Make BgqlServer Send+Sync for parallel request processing
+    let (stop, stopping) = watch::channel(false);
+    let mut sessions = JoinSet::new();
     loop {
-        let (stream, _addr) = listener
-            .accept()
-            .await
+        let accepted = tokio::select! {
+            accepted = listener.accept() => accepted,
+            () = &mut shutdown => break,
+        };
+        let (stream, _addr) = accepted
             .map_err(|e| crate::error::SdkError::server(format!("Failed to accept: {}", e)))?;
+        sessions.spawn(serve_connection(
+            Arc::clone(&server),
+            stream,
+            stopping.clone(),
+        ));
+        // Reap finished sessions
+        while sessions.try_join_next().is_some() {}
+    }
 
-        let io = TokioIo::new(stream);
-        let server_ref = &server;
+    let _ = stop.send(true);
+    while sessions.join_next().await.is_some() {}
+    info!("Server stopped");
+    Ok(())
+}
 
-        let service = service_fn(|req: Request<Incoming>| {
-            let config = server_ref.config();
-            async move {
-                let (parts, body) = req.into_parts();
+/// Serves the requests of one connection, and the WebSocket session it
+/// may be upgraded to, until the client disconnects or `stopping` turns
+/// true.
+async fn serve_connection(
+    server: Arc<BgqlServer>,
+    stream: TcpStream,
+    mut stopping: watch::Receiver<bool>,
+) {
+    let io = TokioIo::new(stream);
+    let server_ref = &server;
+    let pending_upgrade = Mutex::new(None);
+
+    let service = service_fn(|mut req: Request<Incoming>| {
+        let config = server_ref.config();
+        let upgrade = websocket_upgrade(&mut req, &pending_upgrade);
+        async move {
+            if let Some(response) = upgrade {
+                return Ok::<_, Infallible>(response);
+            }
 
-                let response: Response<BoxBody> = match (parts.method.clone(), parts.uri.path()) {
-                    (Method::GET, "/health") => Response::builder()
-                        .status(StatusCode::OK)
-                        .header("Content-Type", "application/json")
-                        .body(full(health_response()))
-                        .unwrap(),
+            let (parts, body) = req.into_parts();
+
+            let response: Response<BoxBody> = match (parts.method.clone(), parts.uri.path()) {
+                (Method::GET, "/health") => Response::builder()
+                    .status(StatusCode::OK)
+                    .header("Content-Type", "application/json")
+                    .body(full(health_response()))
+                    .unwrap(),
+
+                (Method::GET, "/metrics") if config.metrics => Response::builder()
+                    .status(StatusCode::OK)
+                    .header("Content-Type", "text/plain; version=0.0.4")
+                    .body(full(
+                        server_ref
+                            .metrics()
+                            .map(Metrics::render)
+                            .unwrap_or_default(),
+                    ))
+                    .unwrap(),
+
+                (Method::GET, "/.well-known/bgql") => Response::builder()
+                    .status(StatusCode::OK)
+                    .header("Content-Type", "application/json")
+                    .body(full(well_known_bgql(config)))
+                    .unwrap(),
+
+                (Method::POST, "/bgql" | "/graphql") => {
+                    let limit = body_limit(config.max_operation_bytes);
+                    // A declared length over the limit is refused without
+                    // reading the body
+                    if body.size_hint().lower() > limit as u64 {
+                        body_too_large(limit)
+                    } else {
+                        match Limited::new(body, limit).collect().await {
+                            Ok(collected) => {
+                                let body_bytes = collected.to_bytes();
+                                handle_graphql_request(body_bytes, &parts.headers, server_ref).await
+                            }
+                            Err(e) if e.is::<LengthLimitError>() => body_too_large(limit),
+                            Err(e) => error_response(
+                                StatusCode::BAD_REQUEST,
+                                &format!("Invalid request body: {}", e),
+                            ),
+                        }
+                    }
+                }
 
-                    (Method::GET, "/.well-known/bgql") => Response::builder()
+                (Method::GET, "/bgql") | (Method::GET, "/") if config.playground => {
+                    Response::builder()
                         .status(StatusCode::OK)
-                        .header("Content-Type", "application/json")
-                        .body(full(well_known_bgql(config)))
-                        .unwrap(),
-
-                    (Method::POST, "/bgql") => {
-                        let body_bytes = body
-                            .collect()
-                            .await
-                            .map(|c| c.to_bytes())
-                            .unwrap_or_default();
-                        handle_graphql_request(body_bytes, server_ref).await
-                    }
+                        .header("Content-Type", "text/html; charset=utf-8")
+                        .body(full(playground_html("/bgql")))
+                        .unwrap()
+                }
+
+                (Method::OPTIONS, "/bgql" | "/graphql") => Response::builder()
+                    .status(StatusCode::OK)
+                    .header("Access-Control-Allow-Origin", "*")
+                    .header("Access-Control-Allow-Methods", "GET, POST, OPTIONS")
+                    .header(
+                        "Access-Control-Allow-Headers",
+                        "Content-Type, Authorization",
+                    )
+                    .body(full(""))
+                    .unwrap(),
+
+                _ => Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .header("Content-Type", "application/json")
+                    .body(full(r#"{"error":"Not Found"}"#))
+                    .unwrap(),
+            };
+
+            Ok::<_, Infallible>(response)
+        }
+    });
+
+    let mut connection = std::pin::pin!(http1::Builder::new()
+        .serve_connection(io, service)
+        .with_upgrades());
+    let result = tokio::select! {
+        result = connection.as_mut() => result,
+        () = stopped(&mut stopping) => {
+            connection.as_mut().graceful_shutdown();
+            connection.as_mut().await
+        }
+    };
+    if let Err(err) = result {
+        if !err.to_string().contains("connection closed") {
+            error!("Connection error: {:?}", err);
+        }
+    }
 
-                    (Method::GET, "/bgql") | (Method::GET, "/") if config.playground => {
-                        Response::builder()
-                            .status(StatusCode::OK)
-                            .header("Content-Type", "text/html; charset=utf-8")
-                            .body(full(playground_html("/bgql")))
-                            .unwrap()
+    let upgrade = pending_upgrade
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take();
+    if let Some(on_upgrade) = upgrade {
+        tokio::select! {
+            upgraded = on_upgrade => match upgraded {
+                Ok(upgraded) => serve_websocket(upgraded, &server).await,
+                Err(e) => error!("WebSocket upgrade failed: {}", e),
+            },
+            () = stopped(&mut stopping) => {}
+        }
+    }
+}
+
+/// Completes once `stopping` turns true or the server is gone.
+async fn stopped(stopping: &mut watch::Receiver<bool>) {
+    let _ = stopping.wait_for(|stop| *stop).await;
+}
+
+/// Answers a WebSocket upgrade request to `/bgql`.
+///
+/// Returns `None` if `req` is not one. On success the upgrade is stored in
+/// `pending`, to be served once the handshake response has been sent.
+fn websocket_upgrade(
+    req: &mut Request<Incoming>,
+    pending: &Mutex<Option<OnUpgrade>>,
+) -> Option<Response<BoxBody>> {
+    let headers = req.headers();
+    let is_upgrade = headers
+        .get(header::UPGRADE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("websocket"));
+    if req.method() != Method::GET || req.uri().path() != "/bgql" || !is_upgrade {
+        return None;
+    }
+
+    let supports_protocol = headers
+        .get(header::SEC_WEBSOCKET_PROTOCOL)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|p| p.trim() == ws::PROTOCOL));
+    if !supports_protocol {
+        return Some(error_response(
+            StatusCode::BAD_REQUEST,
+            &format!(
+                "Unsupported WebSocket subprotocol, expected {}",
+                ws::PROTOCOL
+            ),
+        ));
+    }
+    let Some(key) = headers.get(header::SEC_WEBSOCKET_KEY) else {
+        return Some(error_response(
+            StatusCode::BAD_REQUEST,
+            "Missing Sec-WebSocket-Key header",
+        ));
+    };
+    let accept = derive_accept_key(key.as_bytes());
+
+    *pending.lock().unwrap_or_else(PoisonError::into_inner) = Some(hyper::upgrade::on(req));
+    Some(
+        Response::builder()
+            .status(StatusCode::SWITCHING_PROTOCOLS)
+            .header(header::CONNECTION, "Upgrade")
+            .header(header::UPGRADE, "websocket")
+            .header(header::SEC_WEBSOCKET_ACCEPT, accept)
+            .header(header::SEC_WEBSOCKET_PROTOCOL, ws::PROTOCOL)
+            .body(full(""))
+            .unwrap(),
+    )
+}
+
+/// Runs a `graphql-transport-ws` session until the client disconnects.
+async fn serve_websocket(upgraded: Upgraded, server: &BgqlServer) {
+    let socket = WebSocketStream::from_raw_socket(TokioIo::new(upgraded), Role::Server, None).await;
+    let (mut sink, mut stream) = socket.split();
+    let (outgoing, mut outgoing_rx) = mpsc::unbounded_channel();
+    let mut session = WsSession::new(server, outgoing);
+
+    loop {
+        tokio::select! {
+            Some(text) = outgoing_rx.recv() => {
+                if sink.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            incoming = stream.next() => match incoming {
+                Some(Ok(Message::Text(text))) => {
+                    if let Err(close) = session.handle(&text).await {
+                        let frame = CloseFrame {
+                            code: CloseCode::from(close.code),
+                            reason: Cow::Owned(close.reason),
+                        };
+                        let _ = sink.send(Message::Close(Some(frame))).await;
+                        break;
                     }
+                }
+                Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
 
-                    (Method::OPTIONS, "/bgql") => Response::builder()
-                        .status(StatusCode::OK)
-                        .header("Access-Control-Allow-Origin", "*")
-                        .header("Access-Control-Allow-Methods", "GET, POST, OPTIONS")
-                        .header(
-                            "Access-Control-Allow-Headers",
-                            "Content-Type, Authorization",
-                        )
-                        .body(full(""))
-                        .unwrap(),
-
-                    _ => Response::builder()
-                        .status(StatusCode::NOT_FOUND)
-                        .header("Content-Type", "application/json")
-                        .body(full(r#"{"error":"Not Found"}"#))
-                        .unwrap(),
-                };
-
-                Ok::<_, Infallible>(response)
+// =============================================================================
+// Client Connection Pool
+// =============================================================================
+
+/// Idle keep-alive connections reused by the client, keyed by `host:port`.
+pub(crate) struct ConnectionPool {
+    max_idle_per_host: usize,
+    keep_alive: Duration,
+    idle: Mutex<HashMap<String, Vec<(TcpStream, Instant)>>>,
+    opened: AtomicUsize,
+}
+
+impl ConnectionPool {
+    /// Creates a pool. A `max_idle_per_host` of 0 disables reuse.
+    pub(crate) fn new(max_idle_per_host: usize, keep_alive: Duration) -> Self {
+        Self {
+            max_idle_per_host,
+            keep_alive,
+            idle: Mutex::new(HashMap::new()),
+            opened: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns true if connections are kept alive for reuse.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.max_idle_per_host > 0
+    }
+
+    /// Takes an idle connection to `addr`, dropping any that have expired.
+    pub(crate) fn checkout(&self, addr: &str) -> Option<TcpStream> {
+        let mut idle = self.idle.lock().unwrap_or_else(PoisonError::into_inner);
+        let connections = idle.get_mut(addr)?;
+        while let Some((stream, since)) = connections.pop() {
+            if since.elapsed() < self.keep_alive {
+                return Some(stream);
             }
-        });
+        }
+        None
+    }
 
-        if let Err(err) = http1::Builder::new().serve_connection(io, service).await {
-            if !err.to_string().contains("connection closed") {
-                error!("Connection error: {:?}", err);
+    /// Returns a connection to the pool if there is room for it.
+    pub(crate) fn checkin(&self, addr: &str, stream: TcpStream) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut idle = self.idle.lock().unwrap_or_else(PoisonError::into_inner);
+        let connections = idle.entry(addr.to_string()).or_default();
+        connections.retain(|(_, since)| since.elapsed() < self.keep_alive);
+        if connections.len() < self.max_idle_per_host {
+            connections.push((stream, Instant::now()));
+        }
+    }
+
+    /// Opens a new connection to `addr`.
+    pub(crate) async fn connect(&self, addr: &str) -> io::Result<TcpStream> {
+        let stream = TcpStream::connect(addr).await?;
+        self.opened.fetch_add(1, Ordering::Relaxed);
+        Ok(stream)
+    }
+
+    /// Returns how many connections the pool has opened.
+    #[cfg(test)]
+    pub(crate) fn connections_opened(&self) -> usize {
+        self.opened.load(Ordering::Relaxed)
+    }
+}
+
+/// Writes a request and reads one HTTP/1.1 response from `stream`.
+///
+/// Returns the raw response and whether the connection can be reused.
+pub(crate) async fn send_request(
+    stream: &mut TcpStream,
+    request: &[u8],
+) -> io::Result<(Vec<u8>, bool)> {
+    stream.write_all(request).await?;
+
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 8192];
+
+    // Read until the end of the headers
+    let header_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "connection closed before response",
+            ));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_ascii_lowercase();
+    let keep_alive = !head.contains("connection: close");
+    let content_length = head
+        .lines()
+        .find_map(|line| line.strip_prefix("content-length:"))
+        .and_then(|value| value.trim().parse::<usize>().ok());
+
+    if let Some(len) = content_length {
+        while buf.len() < header_end + len {
+            let n = stream.read(&mut chunk).await?;
+            if n == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "connection closed mid-response",
+                ));
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        }
+        buf.truncate(header_end + len);
+        Ok((buf, keep_alive))
+    } else if head.contains("transfer-encoding: chunked") {
+        while !buf[header_end..].ends_with(b"0\r\n\r\n") {
+            let n = stream.read(&mut chunk).await?;
+            if n == 0 {
+                return Ok((buf, false));
             }
+            buf.extend_from_slice(&chunk[..n]);
         }
+        Ok((buf, keep_alive))
+    } else {
+        // No framing: the body runs until the server closes the connection
+        stream.read_to_end(&mut buf).await?;
+        Ok((buf, false))
     }
 }
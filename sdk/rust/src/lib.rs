@@ -56,12 +56,14 @@ pub mod context;
 pub mod directives;
 pub mod error;
 pub(crate) mod http;
+pub mod metrics;
 pub mod pubsub;
 pub mod result;
 pub mod server;
 pub mod streaming;
 pub mod typed;
 pub mod validation;
+pub mod ws;
 
 // Re-export macros
 pub use bgql_macros::{args, gql, graphql, resolver, resolvers, ContextKey, TypedOperation};
@@ -69,7 +71,7 @@ pub use bgql_macros::{args, gql, graphql, resolver, resolvers, ContextKey, Typed
 // Re-exports for convenience
 pub use client::{BgqlClient, ClientConfig, GraphQLOperation, Request, Response};
 pub use context::{ContextExt, SharedContext, TypedContext};
-pub use error::{ErrorCode, ResultExt, SdkError, SdkResult};
+pub use error::{DomainError, ErrorCode, ErrorUnion, ResultExt, SdkError, SdkResult, UnionMember};
 pub use typed::{
     FromTypedContext, GraphQLArgs, GraphQLOutput, GraphQLParent, NoArgs, NoVariables,
     OperationKind, ResolverBuilder, Root, TypedOperation, TypedResolver, TypedResponse,
@@ -79,7 +81,9 @@ pub use typed::{
 pub use result::{BgqlError, BgqlResult, Err, Ok, Result};
 
 // Server re-exports
-pub use server::{create_loader, BgqlServer, Context, DataLoader, Resolver, ServerConfig};
+pub use server::{
+    create_loader, BgqlServer, Context, DataLoader, Resolver, ServerConfig, ServerMode,
+};
 
 // Re-export runtime types that are commonly needed
 pub use bgql_runtime::executor::{ExecutorConfig, FieldError};
@@ -109,4 +113,5 @@ pub use validation::{
 };
 
 // PubSub re-exports
+pub use metrics::Metrics;
 pub use pubsub::{PubSub, TypedPubSub, TypedReceiver};
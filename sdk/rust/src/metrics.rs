@@ -0,0 +1,196 @@
+//! Server metrics in the Prometheus text format.
+//!
+//! Enabled with [`ServerConfig::metrics`](crate::ServerConfig::metrics) and
+//! served on `GET /metrics`:
+//!
+//! - `bgql_requests_total` - operations executed
+//! - `bgql_errors_total` - operations that failed or returned errors
+//! - `bgql_request_duration_seconds` - operation latency
+//! - `bgql_query_complexity` - complexity of planned operations
+//! - `bgql_field_resolve_duration_seconds` - resolver latency per field
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+const LATENCY_BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+const COMPLEXITY_BUCKETS: &[f64] = &[1.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0];
+
+/// A cumulative histogram with fixed bucket bounds.
+#[derive(Debug, Clone)]
+struct Histogram {
+    bounds: &'static [f64],
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            counts: vec![0; bounds.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        for (bound, count) in self.bounds.iter().zip(&mut self.counts) {
+            if value <= *bound {
+                *count += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    /// Writes the samples of the histogram. `labels` is empty or ends with a
+    /// comma.
+    fn write(&self, out: &mut String, name: &str, labels: &str) {
+        for (bound, count) in self.bounds.iter().zip(&self.counts) {
+            let _ = writeln!(out, "{name}_bucket{{{labels}le=\"{bound}\"}} {count}");
+        }
+        let _ = writeln!(out, "{name}_bucket{{{labels}le=\"+Inf\"}} {}", self.count);
+        let labels = labels.trim_end_matches(',');
+        let labels = if labels.is_empty() {
+            String::new()
+        } else {
+            format!("{{{labels}}}")
+        };
+        let _ = writeln!(out, "{name}_sum{labels} {}", self.sum);
+        let _ = writeln!(out, "{name}_count{labels} {}", self.count);
+    }
+}
+
+/// Metrics collected by a server.
+#[derive(Debug)]
+pub struct Metrics {
+    requests: AtomicU64,
+    errors: AtomicU64,
+    latency: Mutex<Histogram>,
+    complexity: Mutex<Histogram>,
+    fields: Mutex<BTreeMap<(String, String), Histogram>>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    /// Creates empty metrics.
+    pub fn new() -> Self {
+        Self {
+            requests: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+            latency: Mutex::new(Histogram::new(LATENCY_BUCKETS)),
+            complexity: Mutex::new(Histogram::new(COMPLEXITY_BUCKETS)),
+            fields: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Records an executed operation.
+    pub fn record_request(&self, duration: Duration, failed: bool) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        if failed {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        self.latency.lock().unwrap().observe(duration.as_secs_f64());
+    }
+
+    /// Records the complexity of a planned operation.
+    pub fn record_complexity(&self, complexity: usize) {
+        self.complexity.lock().unwrap().observe(complexity as f64);
+    }
+
+    /// Records a resolver call for `type_name.field`.
+    pub fn record_field(&self, type_name: &str, field: &str, duration: Duration) {
+        self.fields
+            .lock()
+            .unwrap()
+            .entry((type_name.to_string(), field.to_string()))
+            .or_insert_with(|| Histogram::new(LATENCY_BUCKETS))
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Returns the number of operations executed.
+    pub fn requests(&self) -> u64 {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of operations that failed or returned errors.
+    pub fn errors(&self) -> u64 {
+        self.errors.load(Ordering::Relaxed)
+    }
+
+    /// Renders the metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP bgql_requests_total Operations executed.\n");
+        out.push_str("# TYPE bgql_requests_total counter\n");
+        let _ = writeln!(out, "bgql_requests_total {}", self.requests());
+
+        out.push_str("# HELP bgql_errors_total Operations that failed or returned errors.\n");
+        out.push_str("# TYPE bgql_errors_total counter\n");
+        let _ = writeln!(out, "bgql_errors_total {}", self.errors());
+
+        out.push_str("# HELP bgql_request_duration_seconds Operation latency.\n");
+        out.push_str("# TYPE bgql_request_duration_seconds histogram\n");
+        self.latency
+            .lock()
+            .unwrap()
+            .write(&mut out, "bgql_request_duration_seconds", "");
+
+        out.push_str("# HELP bgql_query_complexity Complexity of planned operations.\n");
+        out.push_str("# TYPE bgql_query_complexity histogram\n");
+        self.complexity
+            .lock()
+            .unwrap()
+            .write(&mut out, "bgql_query_complexity", "");
+
+        out.push_str("# HELP bgql_field_resolve_duration_seconds Resolver latency per field.\n");
+        out.push_str("# TYPE bgql_field_resolve_duration_seconds histogram\n");
+        for ((type_name, field), histogram) in self.fields.lock().unwrap().iter() {
+            let labels = format!("type=\"{type_name}\",field=\"{field}\",");
+            histogram.write(&mut out, "bgql_field_resolve_duration_seconds", &labels);
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_histograms() {
+        let metrics = Metrics::new();
+        metrics.record_request(Duration::from_millis(3), false);
+        metrics.record_request(Duration::from_millis(30), true);
+        metrics.record_complexity(4);
+        metrics.record_field("Query", "user", Duration::from_millis(2));
+
+        let text = metrics.render();
+        assert!(text.contains("bgql_requests_total 2\n"));
+        assert!(text.contains("bgql_errors_total 1\n"));
+        assert!(text.contains("bgql_request_duration_seconds_bucket{le=\"0.005\"} 1\n"));
+        assert!(text.contains("bgql_request_duration_seconds_bucket{le=\"+Inf\"} 2\n"));
+        assert!(text.contains("bgql_request_duration_seconds_count 2\n"));
+        assert!(text.contains("bgql_query_complexity_bucket{le=\"5\"} 1\n"));
+        assert!(text.contains(
+            "bgql_field_resolve_duration_seconds_bucket{type=\"Query\",field=\"user\",le=\"0.005\"} 1\n"
+        ));
+        assert!(text.contains(
+            "bgql_field_resolve_duration_seconds_count{type=\"Query\",field=\"user\"} 1\n"
+        ));
+    }
+}
@@ -1,7 +1,9 @@
 //! Publish/Subscribe system for BGQL subscriptions.
 
+use futures_util::{stream, Stream, StreamExt};
 use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::{broadcast, RwLock};
 
 const DEFAULT_CAPACITY: usize = 256;
@@ -39,10 +41,7 @@ impl PubSub {
         let channels = self.channels.read().await;
 
         if let Some(sender) = channels.get(topic) {
-            match sender.send(event) {
-                Ok(count) => count,
-                Err(_) => 0,
-            }
+            sender.send(event).unwrap_or_default()
         } else {
             0
         }
@@ -129,4 +128,33 @@ impl<T: serde::de::DeserializeOwned> TypedReceiver<T> {
             Err(_) => None,
         }
     }
+
+    /// Converts the receiver into a stream of events, e.g. to return from a
+    /// subscription handler.
+    ///
+    /// Events that don't deserialize as `T`, and events missed because the
+    /// receiver lagged behind, are skipped. The stream ends when the topic
+    /// closes.
+    pub fn into_stream(self) -> impl Stream<Item = T> + Send + 'static
+    where
+        T: Send + 'static,
+    {
+        receiver_stream(self.inner)
+            .filter_map(|value| std::future::ready(serde_json::from_value(value).ok()))
+    }
+}
+
+/// Streams the events of a topic, skipping those missed by lagging.
+pub(crate) fn receiver_stream(
+    receiver: broadcast::Receiver<serde_json::Value>,
+) -> impl Stream<Item = serde_json::Value> + Send + 'static {
+    stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => return Some((event, receiver)),
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
 }
@@ -9,27 +9,47 @@
 
 use crate::context::TypedContext;
 use crate::error::{ErrorCode, SdkError, SdkResult};
+use crate::metrics::Metrics;
+use crate::pubsub::{receiver_stream, PubSub};
+use crate::streaming::{IncrementalEvent, StreamingResponse};
 
 // Legacy re-exports for backwards compatibility
 pub use crate::result::{BgqlError, BgqlResult};
 use bgql_core::Interner;
-use bgql_runtime::executor::{Context as RuntimeContext, Executor, ExecutorConfig};
-use bgql_runtime::query::{PlannerConfig, QueryPlanner};
-use bgql_runtime::resolver::ResolverMap;
+use bgql_runtime::binary_transport::{inline_binaries, AttachedBinary, BinaryBlob};
+use bgql_runtime::directives::{
+    AuthDirectiveHandler, DirectiveHandler, DirectiveRegistry, RoleHierarchy,
+};
+use bgql_runtime::executor::{
+    Context as RuntimeContext, Executor, ExecutorConfig, Response as RuntimeResponse,
+};
+use bgql_runtime::plan_cache::PlanCache;
+use bgql_runtime::query::{PlanNode, PlannerConfig, QueryPlan, QueryPlanner};
+use bgql_runtime::resolver::{ResolverMap, UnknownResolver};
 use bgql_runtime::schema::{
-    EnumDef, EnumValueDef, FieldDef, InputFieldDef, InputObjectDef, InterfaceDef, ObjectDef,
-    ScalarDef, Schema, SchemaBuilder, TypeDef, TypeRef, UnionDef,
+    AppliedDirective, EnumDef, EnumValueDef, FieldDef, InputFieldDef, InputObjectDef, InterfaceDef,
+    ObjectDef, ScalarDef, Schema, SchemaBuilder, TypeDef, TypeRef, UnionDef,
 };
+use bgql_runtime::state::MemoryCheckpointStore;
+use bgql_runtime::variables::{coerce_variables, VariableDef};
 use bgql_semantic::hir::{
-    HirFieldSelection, HirOperation, HirOperationKind, HirSelection, HirValue,
+    HirCondition, HirDefer, HirFieldSelection, HirFragment, HirInlineFragment, HirOperation,
+    HirOperationKind, HirSelection, HirStream, HirValue,
 };
-use bgql_syntax::{parse, Definition, OperationType, TypeDefinition};
+use bgql_semantic::Deprecation;
+use bgql_syntax::{
+    merge_extensions, parse, Definition, InterfaceTypeDefinition, Lexer, ObjectTypeDefinition,
+    OperationType, TokenKind, TypeDefinition,
+};
+use futures_util::{future, stream, Stream, StreamExt};
 use indexmap::IndexMap;
 use serde::{de::DeserializeOwned, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::mpsc;
 
 /// Server configuration.
 #[derive(Debug, Clone)]
@@ -46,6 +66,39 @@ pub struct ServerConfig {
     pub max_depth: usize,
     /// Maximum query complexity.
     pub max_complexity: usize,
+    /// Hide the messages of internal errors from clients.
+    pub mask_errors: bool,
+    /// Maximum size of an operation document in bytes. Zero disables the
+    /// limit.
+    pub max_operation_bytes: usize,
+    /// Maximum number of tokens in an operation document, not counting
+    /// commas. Zero disables the limit.
+    pub max_operation_tokens: usize,
+    /// Maximum size of a response's JSON in bytes. Larger responses are
+    /// replaced by a `RESPONSE_TOO_LARGE` error. Zero disables the limit.
+    pub max_response_bytes: usize,
+    /// How root fields without a resolver are handled.
+    pub mode: ServerMode,
+    /// Roles implied by other roles in `@hasRole` and `@authScope` checks.
+    pub role_hierarchy: RoleHierarchy,
+    /// Collect metrics and serve them on `GET /metrics`.
+    pub metrics: bool,
+}
+
+/// How a server handles query and mutation fields without a resolver.
+///
+/// Root fields have no parent value to read from, so without a resolver
+/// they always resolve to null.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ServerMode {
+    /// Unresolved root fields resolve to null.
+    #[default]
+    Standard,
+    /// Unresolved root fields fail with a "not implemented" error, so the
+    /// schema can be queried before every resolver is written.
+    Dev,
+    /// Building the server fails if a root field has no resolver.
+    Production,
 }
 
 impl Default for ServerConfig {
@@ -64,6 +117,13 @@ impl ServerConfig {
             playground: true,
             max_depth: 10,
             max_complexity: 1000,
+            mask_errors: false,
+            max_operation_bytes: 1024 * 1024,
+            max_operation_tokens: 10_000,
+            max_response_bytes: 0,
+            mode: ServerMode::Standard,
+            role_hierarchy: RoleHierarchy::new(),
+            metrics: false,
         }
     }
 
@@ -90,6 +150,60 @@ impl ServerConfig {
         self.playground = false;
         self
     }
+
+    /// Replaces internal error messages with a generic one in responses.
+    ///
+    /// Errors with a client error code still reach the client unchanged.
+    /// The original errors are logged.
+    pub fn mask_errors(mut self) -> Self {
+        self.mask_errors = true;
+        self
+    }
+
+    /// Answers root fields without a resolver with a "not implemented"
+    /// error. Meant for development only.
+    pub fn dev(mut self) -> Self {
+        self.mode = ServerMode::Dev;
+        self
+    }
+
+    /// Requires every query and mutation field to have a resolver when the
+    /// server is built.
+    pub fn production(mut self) -> Self {
+        self.mode = ServerMode::Production;
+        self
+    }
+
+    /// Sets the maximum size of an operation document in bytes.
+    pub fn max_operation_bytes(mut self, bytes: usize) -> Self {
+        self.max_operation_bytes = bytes;
+        self
+    }
+
+    /// Sets the maximum number of tokens in an operation document.
+    pub fn max_operation_tokens(mut self, tokens: usize) -> Self {
+        self.max_operation_tokens = tokens;
+        self
+    }
+
+    /// Sets the maximum size of a response's JSON in bytes.
+    pub fn max_response_bytes(mut self, bytes: usize) -> Self {
+        self.max_response_bytes = bytes;
+        self
+    }
+
+    /// Collects metrics and serves them in the Prometheus text format on
+    /// `GET /metrics`.
+    pub fn metrics(mut self) -> Self {
+        self.metrics = true;
+        self
+    }
+
+    /// Sets the role hierarchy used by `@hasRole` and `@authScope`.
+    pub fn role_hierarchy(mut self, hierarchy: RoleHierarchy) -> Self {
+        self.role_hierarchy = hierarchy;
+        self
+    }
 }
 
 /// Request context (legacy API, prefer TypedContext for new code).
@@ -197,11 +311,28 @@ pub type ResolverFn = Arc<
         + Sync,
 >;
 
+/// Binary resolver function type.
+pub type BinaryResolverFn = Arc<
+    dyn Fn(
+            serde_json::Value,
+            Context,
+        ) -> Pin<Box<dyn Future<Output = SdkResult<BinaryBlob>> + Send>>
+        + Send
+        + Sync,
+>;
+
+/// The function of a [`Resolver`].
+#[derive(Clone)]
+enum ResolverFunc {
+    Value(ResolverFn),
+    Binary(BinaryResolverFn),
+}
+
 /// A resolver.
 pub struct Resolver {
     type_name: String,
     field_name: String,
-    func: ResolverFn,
+    func: ResolverFunc,
 }
 
 impl Resolver {
@@ -214,11 +345,42 @@ impl Resolver {
         Self {
             type_name: type_name.into(),
             field_name: field_name.into(),
-            func: Arc::new(move |args, ctx| Box::pin(func(args, ctx))),
+            func: ResolverFunc::Value(Arc::new(move |args, ctx| Box::pin(func(args, ctx)))),
+        }
+    }
+
+    /// Creates a resolver of a `File`-like field, answering with binary
+    /// data.
+    pub fn binary<F, Fut>(
+        type_name: impl Into<String>,
+        field_name: impl Into<String>,
+        func: F,
+    ) -> Self
+    where
+        F: Fn(serde_json::Value, Context) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = SdkResult<BinaryBlob>> + Send + 'static,
+    {
+        Self {
+            type_name: type_name.into(),
+            field_name: field_name.into(),
+            func: ResolverFunc::Binary(Arc::new(move |args, ctx| Box::pin(func(args, ctx)))),
         }
     }
 }
 
+/// The events of a subscription, as returned by a subscription handler.
+pub type SubscriptionStream = Pin<Box<dyn Stream<Item = serde_json::Value> + Send>>;
+
+/// Subscription handler function type.
+pub type SubscriptionFn = Arc<
+    dyn Fn(
+            serde_json::Value,
+            Context,
+        ) -> Pin<Box<dyn Future<Output = SdkResult<SubscriptionStream>> + Send>>
+        + Send
+        + Sync,
+>;
+
 /// Server builder.
 #[derive(Default)]
 pub struct ServerBuilder {
@@ -227,6 +389,9 @@ pub struct ServerBuilder {
     schema: Option<Schema>,
     sdl: Option<String>,
     resolvers: Vec<Resolver>,
+    subscriptions: HashMap<(String, String), SubscriptionFn>,
+    directives: DirectiveRegistry,
+    pubsub: PubSub,
     interner: Interner,
 }
 
@@ -281,32 +446,119 @@ impl ServerBuilder {
         self
     }
 
+    /// Adds a resolver of a `File`-like field, answering with binary data.
+    ///
+    /// The blob is streamed after the response to clients that accept
+    /// binary transport, and inlined as base64 for the others.
+    pub fn binary_resolver<F, Fut>(
+        mut self,
+        type_name: impl Into<String>,
+        field_name: impl Into<String>,
+        func: F,
+    ) -> Self
+    where
+        F: Fn(serde_json::Value, Context) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = SdkResult<BinaryBlob>> + Send + 'static,
+    {
+        self.resolvers
+            .push(Resolver::binary(type_name, field_name, func));
+        self
+    }
+
+    /// Adds a subscription handler.
+    ///
+    /// The handler is called with the field's arguments when a client
+    /// subscribes, and each item of the returned stream becomes the value of
+    /// the field in one response. Handlers usually stream the events of a
+    /// [`TypedPubSub`](crate::pubsub::TypedPubSub) topic.
+    pub fn subscription<F, Fut, S, T>(
+        mut self,
+        type_name: impl Into<String>,
+        field_name: impl Into<String>,
+        handler: F,
+    ) -> Self
+    where
+        F: Fn(serde_json::Value, Context) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = SdkResult<S>> + Send + 'static,
+        S: Stream<Item = T> + Send + 'static,
+        T: Serialize,
+    {
+        let handler: SubscriptionFn = Arc::new(move |args, ctx| {
+            let events = handler(args, ctx);
+            Box::pin(async move {
+                let events = events
+                    .await?
+                    .filter_map(|event| future::ready(serde_json::to_value(event).ok()));
+                Ok(Box::pin(events) as SubscriptionStream)
+            })
+        });
+        self.subscriptions
+            .insert((type_name.into(), field_name.into()), handler);
+        self
+    }
+
+    /// Adds a handler for a custom field directive such as `@uppercase`.
+    pub fn directive_handler<H: DirectiveHandler + 'static>(
+        mut self,
+        name: impl Into<String>,
+        handler: H,
+    ) -> Self {
+        self.directives.register(name, handler);
+        self
+    }
+
+    /// Sets the hub subscriptions receive their events from.
+    pub fn pubsub(mut self, pubsub: PubSub) -> Self {
+        self.pubsub = pubsub;
+        self
+    }
+
     /// Builds the server.
     pub fn build(mut self) -> SdkResult<BgqlServer> {
         // Parse schema from SDL if provided
-        let schema = if let Some(sdl) = &self.sdl {
+        let mut schema = if let Some(sdl) = &self.sdl {
             parse_sdl_to_schema(sdl, &self.interner)?
         } else {
             return Err(SdkError::new(ErrorCode::NoSchema, "Schema is required"));
         };
+        schema.endpoint.introspection = self.config.introspection;
+
+        let metrics = self.config.metrics.then(|| Arc::new(Metrics::new()));
 
         // Build resolver map from provided resolvers
         let mut resolver_map = ResolverMap::new();
         for resolver in std::mem::take(&mut self.resolvers) {
             let func = resolver.func.clone();
+            let metrics = metrics.clone();
+            let (type_name, field_name) = (resolver.type_name.clone(), resolver.field_name.clone());
             resolver_map.register_async(
                 resolver.type_name.clone(),
                 resolver.field_name.clone(),
-                move |parent, args, _ctx, _info| {
+                move |parent, args, _ctx, info| {
                     let func = func.clone();
+                    let metrics = metrics.clone();
+                    let (type_name, field_name) = (type_name.clone(), field_name.clone());
                     let args_json =
                         serde_json::to_value(args.all()).unwrap_or(serde_json::Value::Null);
                     let _parent = parent.clone();
                     async move {
                         // Create SDK context from args
                         let sdk_ctx = Context::new();
-                        match func(args_json, sdk_ctx).await {
+                        let start = Instant::now();
+                        let result = match &func {
+                            ResolverFunc::Value(func) => func(args_json, sdk_ctx).await,
+                            ResolverFunc::Binary(func) => func(args_json, sdk_ctx)
+                                .await
+                                .map(|blob| info.respond_binary(blob)),
+                        };
+                        if let Some(metrics) = &metrics {
+                            metrics.record_field(&type_name, &field_name, start.elapsed());
+                        }
+                        match result {
                             Ok(value) => Ok(value),
+                            Err(e) if e.is_client_error() => {
+                                Err(bgql_runtime::resolver::ResolverError::Client(e.message))
+                            }
                             Err(e) => Err(bgql_runtime::resolver::ResolverError::Custom(e.message)),
                         }
                     }
@@ -314,14 +566,45 @@ impl ServerBuilder {
             );
         }
 
+        let mut unknown = resolver_map.unknown_resolvers(&schema);
+        let mut subscriptions: Vec<_> = self.subscriptions.keys().collect();
+        subscriptions.sort_unstable();
+        unknown.extend(
+            subscriptions
+                .into_iter()
+                .filter_map(|(type_name, field)| UnknownResolver::check(&schema, type_name, field)),
+        );
+        if !unknown.is_empty() {
+            return Err(SdkError::unknown_resolvers(&unknown));
+        }
+
+        self.handle_unresolved_root_fields(&schema, &mut resolver_map)?;
+
         let executor_config = ExecutorConfig {
             max_parallel_depth: self.config.max_depth,
             tracing: false,
             max_concurrent_fields: 100,
             field_timeout_ms: 30000,
+            mask_errors: self.config.mask_errors,
+            max_response_size: self.config.max_response_bytes,
+            ..ExecutorConfig::default()
         };
 
-        let executor = Executor::new_with(executor_config, resolver_map);
+        // Built-in authorization directives, unless replaced by a custom handler
+        let auth = AuthDirectiveHandler::new(self.config.role_hierarchy.clone());
+        for name in ["hasRole", "authScope"] {
+            if self.directives.get(name).is_none() {
+                self.directives.register(name, auth.clone());
+            }
+        }
+
+        let executor = Executor::new_with(executor_config, resolver_map)
+            .with_directive_handlers(std::mem::take(&mut self.directives))
+            .with_error_hook(|error, path| {
+                if !error.is_client_facing() {
+                    tracing::error!(?path, "resolver error: {}", error);
+                }
+            });
 
         let planner_config = PlannerConfig {
             max_depth: self.config.max_depth,
@@ -337,18 +620,76 @@ impl ServerBuilder {
             schema,
             executor,
             planner,
-            interner: self.interner,
+            subscriptions: self.subscriptions,
+            pubsub: self.pubsub,
+            metrics,
         })
     }
 }
 
+impl ServerBuilder {
+    /// Applies the [`ServerMode`] to the query and mutation fields that have
+    /// no resolver.
+    fn handle_unresolved_root_fields(
+        &self,
+        schema: &Schema,
+        resolver_map: &mut ResolverMap,
+    ) -> SdkResult<()> {
+        let mut unresolved = Vec::new();
+        for type_name in [&schema.query_type, &schema.mutation_type]
+            .into_iter()
+            .flatten()
+        {
+            let Some(TypeDef::Object(obj)) = schema.get_type(type_name) else {
+                continue;
+            };
+            for field in obj.fields.keys() {
+                if !resolver_map.contains(type_name, field) {
+                    unresolved.push((type_name.clone(), field.clone()));
+                }
+            }
+        }
+
+        match self.config.mode {
+            ServerMode::Standard => {
+                for (type_name, field) in unresolved {
+                    tracing::warn!("no resolver for `{type_name}.{field}`");
+                }
+            }
+            ServerMode::Dev => {
+                for (type_name, field) in unresolved {
+                    let message = format!("Resolver for `{type_name}.{field}` is not implemented");
+                    resolver_map.register_async(type_name, field, move |_, _, _, _| {
+                        let message = message.clone();
+                        async move { Err(bgql_runtime::resolver::ResolverError::Client(message)) }
+                    });
+                }
+            }
+            ServerMode::Production if !unresolved.is_empty() => {
+                let fields: Vec<String> = unresolved
+                    .iter()
+                    .map(|(type_name, field)| format!("`{type_name}.{field}`"))
+                    .collect();
+                return Err(SdkError::new(
+                    ErrorCode::ResolverNotFound,
+                    format!("No resolver for {}", fields.join(", ")),
+                ));
+            }
+            ServerMode::Production => {}
+        }
+        Ok(())
+    }
+}
+
 /// The Better GraphQL server.
 pub struct BgqlServer {
     config: ServerConfig,
     schema: Schema,
     executor: Executor,
     planner: QueryPlanner,
-    interner: Interner,
+    subscriptions: HashMap<(String, String), SubscriptionFn>,
+    pubsub: PubSub,
+    metrics: Option<Arc<Metrics>>,
 }
 
 impl BgqlServer {
@@ -367,26 +708,340 @@ impl BgqlServer {
         &self.config
     }
 
-    /// Starts the server and blocks until shutdown.
+    /// Returns the hub subscriptions receive their events from.
+    pub fn pubsub(&self) -> &PubSub {
+        &self.pubsub
+    }
+
+    /// Returns the collected metrics, if enabled by [`ServerConfig::metrics`].
+    pub fn metrics(&self) -> Option<&Metrics> {
+        self.metrics.as_deref()
+    }
+
+    /// Starts the server on the configured host and port, and blocks until
+    /// Ctrl-C.
     ///
     /// Handles:
-    /// - POST /bgql - GraphQL queries and mutations
-    /// - GET /bgql - Playground UI (if enabled)
+    /// - POST /bgql or /graphql - GraphQL queries and mutations
+    /// - OPTIONS /bgql or /graphql - CORS preflight
+    /// - GET / and /bgql - Playground UI (if enabled)
     /// - GET /health - Health check endpoint
     /// - GET /.well-known/bgql - Server capabilities
+    /// - GET /metrics - Prometheus metrics (if enabled)
     pub async fn listen(self) -> SdkResult<()> {
-        crate::http::run_server(Arc::new(self)).await
+        let listener = crate::http::bind(&self.config).await?;
+        self.serve(listener, async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
     }
 
-    /// Executes a query.
+    /// Serves requests like [`BgqlServer::listen`], on an already bound
+    /// listener, until `shutdown` completes.
+    pub async fn serve(
+        self,
+        listener: tokio::net::TcpListener,
+        shutdown: impl Future<Output = ()>,
+    ) -> SdkResult<()> {
+        crate::http::run_server(Arc::new(self), listener, shutdown).await
+    }
+
+    /// Executes a query or mutation.
+    ///
+    /// Subscriptions are rejected; start them with [`BgqlServer::subscribe`].
+    /// Fields answered with binary data hold it base64-encoded.
     pub async fn execute(
         &self,
         query: &str,
         variables: Option<serde_json::Value>,
         ctx: Context,
     ) -> SdkResult<serde_json::Value> {
-        // Parse the query
-        let parse_result = parse(query, &self.interner);
+        let (mut response, binaries) = self.execute_binary(query, variables, ctx).await?;
+        if let Some(data) = response.get_mut("data") {
+            inline_binaries(data, &binaries);
+        }
+        Ok(response)
+    }
+
+    /// Executes a query or mutation for a client with binary transport.
+    ///
+    /// Fields answered with binary data hold a reference to their blob,
+    /// returned beside the response to be sent after it with
+    /// [`BinaryProtocol::encode_response`](bgql_runtime::binary_transport::BinaryProtocol::encode_response).
+    pub async fn execute_binary(
+        &self,
+        query: &str,
+        variables: Option<serde_json::Value>,
+        ctx: Context,
+    ) -> SdkResult<(serde_json::Value, Vec<AttachedBinary>)> {
+        let start = Instant::now();
+        let result = self.execute_operation(query, variables, ctx).await;
+        if let Some(metrics) = &self.metrics {
+            let failed = result
+                .as_ref()
+                .map_or(true, |(response, _)| response.get("errors").is_some());
+            metrics.record_request(start.elapsed(), failed);
+        }
+        result
+    }
+
+    async fn execute_operation(
+        &self,
+        query: &str,
+        variables: Option<serde_json::Value>,
+        ctx: Context,
+    ) -> SdkResult<(serde_json::Value, Vec<AttachedBinary>)> {
+        let plan = self.cached_plan(query, variables.as_ref())?;
+        reject_subscription(&plan)?;
+        if let Some(metrics) = &self.metrics {
+            metrics.record_complexity(plan.complexity);
+        }
+        let variables = match self.validate_variables(&plan, variables.as_ref()) {
+            Ok(variables) => variables,
+            Err(response) => return Ok((response, Vec::new())),
+        };
+
+        // Execute the plan
+        let runtime_ctx = ctx.to_runtime_context(Some(variables));
+        let response = self
+            .executor
+            .execute(&plan, &self.schema, &runtime_ctx)
+            .await;
+
+        Ok((response_to_json(response), runtime_ctx.take_binaries()))
+    }
+
+    /// Executes a query, delivering its `@defer` fragments and `@stream`
+    /// list items incrementally.
+    ///
+    /// The stream starts with [`IncrementalEvent::Initial`], which holds the
+    /// response without the deferred fragments, shaped like that of
+    /// [`BgqlServer::execute`]. Each fragment then follows as an
+    /// [`IncrementalEvent::Defer`] carrying the path of the object it
+    /// belongs to, ready to be written as Server-Sent Events or with a
+    /// [`MultipartWriter`](crate::streaming::MultipartWriter).
+    ///
+    /// When root fields are marked `@stream`, the items past their
+    /// `initialCount` follow one at a time as [`IncrementalEvent::Stream`]
+    /// instead, and `@defer` fragments are resolved with the initial
+    /// response.
+    pub async fn execute_incremental(
+        &self,
+        query: &str,
+        variables: Option<serde_json::Value>,
+        ctx: Context,
+    ) -> SdkResult<impl Stream<Item = IncrementalEvent> + Send + 'static> {
+        let plan = self.cached_plan(query, variables.as_ref())?;
+        reject_subscription(&plan)?;
+        let runtime_ctx = match self.validate_variables(&plan, variables.as_ref()) {
+            Ok(variables) => ctx.to_runtime_context(Some(variables)),
+            Err(response) => {
+                let initial = IncrementalEvent::Initial(StreamingResponse::new(response));
+                return Ok(stream::iter([initial]).chain(stream::empty().boxed()));
+            }
+        };
+        let (response, has_next, patches) = if has_streamed_root_fields(&plan) {
+            let store = Arc::new(MemoryCheckpointStore::new());
+            let (response, mut execution) = self
+                .executor
+                .execute_stream(&plan, &self.schema, &runtime_ctx, store)
+                .await;
+            // Peek at the first item so the initial payload knows whether
+            // more follow; empty streams deliver nothing
+            let first = execution.next().await;
+            let has_next = first.is_some();
+            let rest = stream::unfold(execution, |mut execution| async move {
+                let payload = execution.next().await?;
+                Some((IncrementalEvent::Stream(payload), execution))
+            });
+            let patches = stream::iter(first.map(IncrementalEvent::Stream))
+                .chain(rest)
+                .boxed();
+            (response, has_next, patches)
+        } else {
+            let (response, execution) = self
+                .executor
+                .execute_incremental(&plan, &self.schema, &runtime_ctx)
+                .await;
+            let has_next = execution.has_next();
+            let patches = stream::unfold(execution, |mut execution| async move {
+                let payload = execution.next().await?;
+                Some((IncrementalEvent::Defer(payload), execution))
+            })
+            .boxed();
+            (response, has_next, patches)
+        };
+
+        let mut initial = StreamingResponse::new(response_to_json(response));
+        if has_next {
+            initial = initial.with_pending();
+        }
+        Ok(stream::iter([IncrementalEvent::Initial(initial)]).chain(patches))
+    }
+
+    /// Starts a subscription and returns its stream of responses, shaped
+    /// like those of [`BgqlServer::execute`].
+    ///
+    /// Each event of the root field's source becomes the field's value in
+    /// one response. The source is the stream of the field's
+    /// [subscription handler](ServerBuilder::subscription), or else the
+    /// [`PubSub`] topic named after the field. Queries and mutations yield a
+    /// single response.
+    pub async fn subscribe(
+        &self,
+        query: &str,
+        variables: Option<serde_json::Value>,
+        ctx: Context,
+    ) -> SdkResult<mpsc::Receiver<serde_json::Value>> {
+        self.check_operation_size(query)?;
+        let (hir_operation, plan) = self.plan(query)?;
+        let runtime_ctx = match self.validate_variables(&plan, variables.as_ref()) {
+            Ok(variables) => ctx.to_runtime_context(Some(variables)),
+            Err(response) => {
+                let (tx, rx) = mpsc::channel(1);
+                let _ = tx.send(response).await;
+                return Ok(rx);
+            }
+        };
+
+        if hir_operation.kind != HirOperationKind::Subscription {
+            let response = self
+                .executor
+                .execute(&plan, &self.schema, &runtime_ctx)
+                .await;
+            let (tx, rx) = mpsc::channel(1);
+            let _ = tx.send(response_to_json(response)).await;
+            return Ok(rx);
+        }
+
+        let field = hir_operation
+            .selections
+            .iter()
+            .find_map(|selection| match selection {
+                HirSelection::Field(field) => Some(field),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                SdkError::new(ErrorCode::NoOperation, "Subscription selects no field")
+            })?;
+
+        let subscription_type = self.schema.subscription_type.clone().unwrap_or_default();
+        let mut source = match self
+            .subscriptions
+            .get(&(subscription_type, field.name.clone()))
+        {
+            Some(handler) => {
+                let args = arguments_to_json(&field.arguments, &runtime_ctx.variables);
+                handler(args, ctx).await?
+            }
+            None => Box::pin(receiver_stream(
+                self.pubsub.subscribe(field.name.as_str()).await,
+            )),
+        };
+
+        // Wrap each event as the root value for the field
+        let field = field.name.clone();
+        let (events_tx, events_rx) = mpsc::channel(16);
+        tokio::spawn(async move {
+            while let Some(event) = source.next().await {
+                let root = serde_json::json!({ field.as_str(): event });
+                if events_tx.send(root).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut responses =
+            self.executor
+                .subscribe(plan, self.schema.clone(), runtime_ctx, events_rx);
+        let (tx, rx) = mpsc::channel(16);
+        tokio::spawn(async move {
+            while let Some(response) = responses.recv().await {
+                if tx.send(response_to_json(response)).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(rx)
+    }
+
+    /// Plans the first operation in `query`, reusing the executor's cached
+    /// plan if the operation was planned before.
+    fn cached_plan(
+        &self,
+        query: &str,
+        variables: Option<&serde_json::Value>,
+    ) -> SdkResult<Arc<QueryPlan>> {
+        self.check_operation_size(query)?;
+        let key = PlanCache::key(query, variables);
+        self.executor
+            .plan_cache()
+            .get_or_try_insert_with(&key, || self.plan(query).map(|(_, plan)| plan))
+    }
+
+    /// Validates and coerces `variables` to the types declared by the
+    /// operation of `plan`. Fails with the response reporting every
+    /// mismatch.
+    fn validate_variables(
+        &self,
+        plan: &QueryPlan,
+        variables: Option<&serde_json::Value>,
+    ) -> Result<serde_json::Value, serde_json::Value> {
+        coerce_variables(&plan.variables, variables, &self.schema)
+            .map(serde_json::Value::Object)
+            .map_err(|errors| {
+                let errors: Vec<_> = errors
+                    .into_iter()
+                    .map(|message| serde_json::json!({ "message": message }))
+                    .collect();
+                serde_json::json!({ "errors": errors })
+            })
+    }
+
+    /// Rejects operations over the configured byte or token limit without
+    /// parsing them.
+    fn check_operation_size(&self, query: &str) -> SdkResult<()> {
+        let max_bytes = self.config.max_operation_bytes;
+        if max_bytes > 0 && query.len() > max_bytes {
+            return Err(SdkError::new(
+                ErrorCode::OperationTooLarge,
+                format!(
+                    "Operation is {} bytes, exceeding the limit of {} bytes",
+                    query.len(),
+                    max_bytes
+                ),
+            ));
+        }
+
+        let max_tokens = self.config.max_operation_tokens;
+        if max_tokens > 0 {
+            let interner = Interner::new();
+            let mut lexer = Lexer::new(query, &interner);
+            let mut tokens = 0;
+            loop {
+                match lexer.next_token().kind {
+                    TokenKind::Eof => break,
+                    TokenKind::Comma => continue,
+                    _ => tokens += 1,
+                }
+                if tokens > max_tokens {
+                    return Err(SdkError::new(
+                        ErrorCode::OperationTooLarge,
+                        format!("Operation exceeds the limit of {} tokens", max_tokens),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses and plans the first operation in `query`.
+    fn plan(&self, query: &str) -> SdkResult<(HirOperation, QueryPlan)> {
+        // Parse the query. Each query gets its own interner, so names from
+        // client queries don't accumulate in the server
+        let interner = Interner::new();
+        let parse_result = parse(query, &interner);
 
         if parse_result.diagnostics.has_errors() {
             return Err(SdkError::parse(format!(
@@ -410,47 +1065,112 @@ impl BgqlServer {
             .ok_or_else(|| SdkError::new(ErrorCode::NoOperation, "No operation found in query"))?;
 
         // Convert AST operation to HIR operation
-        let hir_operation = ast_operation_to_hir(operation_def, &self.interner);
+        let hir_operation = ast_operation_to_hir(operation_def, &interner);
+        let fragments: Vec<HirFragment> = parse_result
+            .document
+            .definitions
+            .iter()
+            .filter_map(|def| match def {
+                Definition::Fragment(fragment) => Some(ast_fragment_to_hir(fragment, &interner)),
+                _ => None,
+            })
+            .collect();
 
-        // Plan the query
+        // Plan the query, rejecting it if it exceeds the depth and
+        // complexity limits
         let plan = self
             .planner
-            .plan(&hir_operation, &self.schema)
+            .plan_with_fragments(&hir_operation, &fragments, &self.schema)
             .map_err(|e| SdkError::new(ErrorCode::PlanError, e.message))?;
+        let variables = operation_def
+            .variables
+            .iter()
+            .map(|var| VariableDef {
+                name: interner.get(var.name.value).to_string(),
+                ty: convert_type(&var.ty, &interner, &mut Generics::default()),
+                default_value: var
+                    .default_value
+                    .as_ref()
+                    .map(|value| convert_const_value(value, &interner)),
+            })
+            .collect();
 
-        // Execute the plan
-        let runtime_ctx = ctx.to_runtime_context(variables);
-        let response = self
-            .executor
-            .execute(&plan, &self.schema, &runtime_ctx)
-            .await;
+        Ok((hir_operation, plan.with_variables(variables)))
+    }
+}
 
-        // Convert response to JSON
-        if response.has_errors() {
-            let errors: Vec<String> = response
-                .errors
-                .unwrap_or_default()
-                .iter()
-                .map(|e| e.message.clone())
-                .collect();
+/// Returns whether any root field of `plan` is marked `@stream`.
+fn has_streamed_root_fields(plan: &QueryPlan) -> bool {
+    let roots = match &plan.root {
+        PlanNode::Sequence(nodes) | PlanNode::Parallel(nodes) => nodes.as_slice(),
+        node => std::slice::from_ref(node),
+    };
+    roots
+        .iter()
+        .any(|node| matches!(node, PlanNode::Stream { .. }))
+}
 
-            let mut result = serde_json::Map::new();
-            if let Some(data) = response.data {
-                result.insert("data".to_string(), data);
-            }
-            result.insert("errors".to_string(), serde_json::json!(errors));
-            Ok(serde_json::Value::Object(result))
-        } else {
-            let mut result = serde_json::Map::new();
-            if let Some(data) = response.data {
-                result.insert("data".to_string(), data);
-            }
-            Ok(serde_json::Value::Object(result))
+/// Fails if `plan` is a subscription, which needs [`BgqlServer::subscribe`].
+fn reject_subscription(plan: &QueryPlan) -> SdkResult<()> {
+    if plan.operation_kind == HirOperationKind::Subscription {
+        return Err(SdkError::new(
+            ErrorCode::ValidationError,
+            "Subscriptions must be started with `subscribe`, not executed",
+        ));
+    }
+    Ok(())
+}
+
+/// Converts the arguments of a field to a JSON object, substituting
+/// variables.
+fn arguments_to_json(
+    arguments: &[(String, HirValue)],
+    variables: &HashMap<String, serde_json::Value>,
+) -> serde_json::Value {
+    fn to_json(
+        value: &HirValue,
+        variables: &HashMap<String, serde_json::Value>,
+    ) -> serde_json::Value {
+        match value {
+            HirValue::Variable(name) => variables
+                .get(name)
+                .cloned()
+                .unwrap_or(serde_json::Value::Null),
+            HirValue::Int(n) => serde_json::json!(n),
+            HirValue::Float(n) => serde_json::json!(n),
+            HirValue::String(s) | HirValue::Enum(s) => serde_json::json!(s),
+            HirValue::Boolean(b) => serde_json::json!(b),
+            HirValue::Null => serde_json::Value::Null,
+            HirValue::List(items) => items.iter().map(|v| to_json(v, variables)).collect(),
+            HirValue::Object(fields) => fields
+                .iter()
+                .map(|(name, v)| (name.clone(), to_json(v, variables)))
+                .collect(),
         }
     }
+
+    arguments
+        .iter()
+        .map(|(name, value)| (name.clone(), to_json(value, variables)))
+        .collect()
+}
+
+/// Converts an execution response to JSON.
+fn response_to_json(response: RuntimeResponse) -> serde_json::Value {
+    let mut result = serde_json::Map::new();
+    if let Some(data) = response.data {
+        result.insert("data".to_string(), data);
+    }
+    if let Some(errors) = response.errors.filter(|errors| !errors.is_empty()) {
+        result.insert("errors".to_string(), serde_json::json!(errors));
+    }
+    serde_json::Value::Object(result)
 }
 
 /// Parses SDL string to Schema.
+///
+/// bgql's extended types are mapped to the closest standard kinds, as
+/// described in [`bgql_runtime::introspection`].
 fn parse_sdl_to_schema(sdl: &str, interner: &Interner) -> SdkResult<Schema> {
     let parse_result = parse(sdl, interner);
 
@@ -461,14 +1181,26 @@ fn parse_sdl_to_schema(sdl: &str, interner: &Interner) -> SdkResult<Schema> {
         ));
     }
 
+    // `extend` definitions add to the definitions they extend
+    let (definitions, extension_errors) = merge_extensions(&parse_result.document.definitions);
+    if !extension_errors.is_empty() {
+        return Err(SdkError::new(
+            ErrorCode::SchemaError,
+            format!("Schema extension errors: {:?}", extension_errors),
+        ));
+    }
+
     let mut builder = SchemaBuilder::new();
     let mut query_type = None;
     let mut mutation_type = None;
     let mut subscription_type = None;
+    let mut aliases = HashMap::new();
+    let mut templates = HashMap::new();
+    let mut generics = Generics::default();
 
-    for definition in &parse_result.document.definitions {
+    for definition in &definitions {
         match definition {
-            Definition::Schema(schema_def) => {
+            Definition::Schema(schema_def) | Definition::SchemaExtension(schema_def) => {
                 for op in &schema_def.operations {
                     let type_name = interner.get(op.type_name).to_string();
                     match op.operation {
@@ -478,28 +1210,46 @@ fn parse_sdl_to_schema(sdl: &str, interner: &Interner) -> SdkResult<Schema> {
                     }
                 }
             }
+            Definition::Type(TypeDefinition::TypeAlias(alias)) => {
+                aliases.insert(
+                    interner.get(alias.name.value).to_string(),
+                    convert_type(&alias.aliased, interner, &mut generics),
+                );
+            }
+            // Generic types are added once per instance, below
+            Definition::Type(
+                type_def @ (TypeDefinition::Object(ObjectTypeDefinition { type_params, .. })
+                | TypeDefinition::Interface(InterfaceTypeDefinition {
+                    type_params, ..
+                })),
+            ) if !type_params.is_empty() => {
+                templates.insert(interner.get(type_def.name().value), (type_def, type_params));
+            }
             Definition::Type(type_def) => {
-                let type_def = convert_type_definition(type_def, interner);
+                let type_def = convert_type_definition(type_def, sdl, interner, &mut generics);
                 builder = builder.add_type(type_def);
             }
             _ => {}
         }
     }
 
-    // Set root types (default to Query if not specified)
-    if let Some(qt) = query_type {
-        builder = builder.query_type(qt);
-    } else {
-        // Check if there's a type named "Query"
-        for def in &parse_result.document.definitions {
-            if let Definition::Type(TypeDefinition::Object(obj)) = def {
-                let name = interner.get(obj.name.value);
-                if name == "Query" {
-                    builder = builder.query_type("Query");
-                    break;
-                }
+    // Root types default to objects named Query, Mutation and Subscription
+    let default_root = |name: &str| {
+        definitions.iter().find_map(|def| match def {
+            Definition::Type(TypeDefinition::Object(obj))
+                if interner.get(obj.name.value) == name =>
+            {
+                Some(name.to_string())
             }
-        }
+            _ => None,
+        })
+    };
+    let query_type = query_type.or_else(|| default_root("Query"));
+    let mutation_type = mutation_type.or_else(|| default_root("Mutation"));
+    let subscription_type = subscription_type.or_else(|| default_root("Subscription"));
+
+    if let Some(qt) = query_type {
+        builder = builder.query_type(qt);
     }
 
     if let Some(mt) = mutation_type {
@@ -510,11 +1260,132 @@ fn parse_sdl_to_schema(sdl: &str, interner: &Interner) -> SdkResult<Schema> {
         builder = builder.subscription_type(st);
     }
 
-    Ok(builder.build())
+    // Monomorphize the generic types, binding their type parameters to the
+    // arguments of each instance. Instances can refer to further instances.
+    let mut instantiated = HashSet::new();
+    while let Some((name, arguments)) = generics.instances.pop() {
+        let instance = instance_name(&name, &arguments);
+        let Some((template, type_params)) = templates.get(&name) else {
+            continue;
+        };
+        if !instantiated.insert(instance.clone()) {
+            continue;
+        }
+        generics.params = type_params
+            .iter()
+            .map(|param| interner.get(param.name.value))
+            .zip(arguments)
+            .collect();
+        let mut type_def = convert_type_definition(template, sdl, interner, &mut generics);
+        generics.params.clear();
+        match &mut type_def {
+            TypeDef::Object(ObjectDef { name, .. })
+            | TypeDef::Interface(InterfaceDef { name, .. }) => *name = instance,
+            _ => {}
+        }
+        builder = builder.add_type(type_def);
+    }
+    if generics.has_tuples {
+        builder = builder.add_type(TypeDef::Scalar(ScalarDef {
+            name: TUPLE_SCALAR.to_string(),
+            description: Some("A tuple, as a list of its elements.".to_string()),
+        }));
+    }
+
+    let mut schema = builder.build();
+    expand_aliases(&mut schema, &aliases);
+    Ok(schema)
+}
+
+/// Name of the scalar that tuple types are reported as.
+const TUPLE_SCALAR: &str = "Tuple";
+
+/// The type parameters bound while converting an instance of a generic
+/// type, and the instances the converted types refer to.
+#[derive(Default)]
+struct Generics {
+    params: HashMap<String, TypeRef>,
+    instances: Vec<(String, Vec<TypeRef>)>,
+    has_tuples: bool,
+}
+
+/// Names the monomorphized instance of a generic type, such as
+/// `UserConnection` for `Connection<User>`.
+fn instance_name(name: &str, arguments: &[TypeRef]) -> String {
+    fn prefix(ty: &TypeRef) -> String {
+        match ty {
+            TypeRef::Named(name) => name.clone(),
+            TypeRef::Option(inner) => format!("{}Option", prefix(inner)),
+            TypeRef::List(inner) => format!("{}List", prefix(inner)),
+        }
+    }
+    let mut instance: String = arguments.iter().map(prefix).collect();
+    instance.push_str(name);
+    instance
 }
 
-/// Converts AST type definition to runtime TypeDef.
-fn convert_type_definition(type_def: &TypeDefinition, interner: &Interner) -> TypeDef {
+/// Replaces the references to type aliases with the types they alias.
+fn expand_aliases(schema: &mut Schema, aliases: &HashMap<String, TypeRef>) {
+    if aliases.is_empty() {
+        return;
+    }
+    let mut types = Vec::new();
+    for type_def in schema.types.values_mut() {
+        match type_def {
+            TypeDef::Object(ObjectDef { fields, .. })
+            | TypeDef::Interface(InterfaceDef { fields, .. }) => {
+                for field in fields.values_mut() {
+                    types.push(&mut field.ty);
+                    types.extend(field.arguments.values_mut().map(|arg| &mut arg.ty));
+                }
+            }
+            TypeDef::InputObject(input) => {
+                types.extend(input.fields.values_mut().map(|field| &mut field.ty))
+            }
+            _ => {}
+        }
+    }
+    types.extend(
+        schema
+            .directives
+            .values_mut()
+            .flat_map(|directive| directive.arguments.values_mut())
+            .map(|arg| &mut arg.ty),
+    );
+    for ty in types {
+        expand_alias(ty, aliases, &mut Vec::new());
+    }
+}
+
+/// Expands the aliases in `ty`, leaving aliases that refer to themselves as
+/// they are.
+fn expand_alias(ty: &mut TypeRef, aliases: &HashMap<String, TypeRef>, seen: &mut Vec<String>) {
+    match ty {
+        TypeRef::Named(name) => {
+            let Some(aliased) = aliases.get(name.as_str()) else {
+                return;
+            };
+            if seen.contains(name) {
+                return;
+            }
+            seen.push(name.clone());
+            *ty = aliased.clone();
+            expand_alias(ty, aliases, seen);
+            seen.pop();
+        }
+        TypeRef::Option(inner) | TypeRef::List(inner) => expand_alias(inner, aliases, seen),
+    }
+}
+
+/// Converts AST type definition to runtime TypeDef. Default values are
+/// copied from `sdl` as source text, and the generic instances the
+/// definition refers to are collected into `generics`.
+fn convert_type_definition(
+    type_def: &TypeDefinition,
+    sdl: &str,
+    interner: &Interner,
+    generics: &mut Generics,
+) -> TypeDef {
     match type_def {
         TypeDefinition::Scalar(scalar) => TypeDef::Scalar(ScalarDef {
             name: interner.get(scalar.name.value).to_string(),
@@ -532,20 +1403,23 @@ fn convert_type_definition(type_def: &TypeDefinition, interner: &Interner) -> Ty
                         InputFieldDef {
                             name: arg_name,
                             description: arg.description.as_ref().map(|d| d.value.to_string()),
-                            ty: convert_type(&arg.ty, interner),
+                            ty: convert_type(&arg.ty, interner, generics),
                             default_value: None,
                         },
                     );
                 }
+                let deprecation = Deprecation::from_directives(&field.directives, interner);
                 fields.insert(
                     field_name.clone(),
                     FieldDef {
                         name: field_name,
                         description: field.description.as_ref().map(|d| d.value.to_string()),
-                        ty: convert_type(&field.ty, interner),
+                        ty: convert_type(&field.ty, interner, generics),
                         arguments,
-                        deprecated: false,
-                        deprecation_reason: None,
+                        deprecated: deprecation.is_deprecated,
+                        deprecation_reason: deprecation.deprecation_reason,
+                        source: source_directive(&field.directives, interner),
+                        directives: convert_directives(&field.directives, interner),
                     },
                 );
             }
@@ -572,20 +1446,23 @@ fn convert_type_definition(type_def: &TypeDefinition, interner: &Interner) -> Ty
                         InputFieldDef {
                             name: arg_name,
                             description: arg.description.as_ref().map(|d| d.value.to_string()),
-                            ty: convert_type(&arg.ty, interner),
+                            ty: convert_type(&arg.ty, interner, generics),
                             default_value: None,
                         },
                     );
                 }
+                let deprecation = Deprecation::from_directives(&field.directives, interner);
                 fields.insert(
                     field_name.clone(),
                     FieldDef {
                         name: field_name,
                         description: field.description.as_ref().map(|d| d.value.to_string()),
-                        ty: convert_type(&field.ty, interner),
+                        ty: convert_type(&field.ty, interner, generics),
                         arguments,
-                        deprecated: false,
-                        deprecation_reason: None,
+                        deprecated: deprecation.is_deprecated,
+                        deprecation_reason: deprecation.deprecation_reason,
+                        source: source_directive(&field.directives, interner),
+                        directives: convert_directives(&field.directives, interner),
                     },
                 );
             }
@@ -613,11 +1490,14 @@ fn convert_type_definition(type_def: &TypeDefinition, interner: &Interner) -> Ty
             let values = enum_def
                 .values
                 .iter()
-                .map(|v| EnumValueDef {
-                    name: interner.get(v.name.value).to_string(),
-                    description: v.description.as_ref().map(|d| d.value.to_string()),
-                    deprecated: false,
-                    deprecation_reason: None,
+                .map(|v| {
+                    let deprecation = Deprecation::from_directives(&v.directives, interner);
+                    EnumValueDef {
+                        name: interner.get(v.name.value).to_string(),
+                        description: v.description.as_ref().map(|d| d.value.to_string()),
+                        deprecated: deprecation.is_deprecated,
+                        deprecation_reason: deprecation.deprecation_reason,
+                    }
                 })
                 .collect();
             TypeDef::Enum(EnumDef {
@@ -635,8 +1515,14 @@ fn convert_type_definition(type_def: &TypeDefinition, interner: &Interner) -> Ty
                     InputFieldDef {
                         name: field_name,
                         description: field.description.as_ref().map(|d| d.value.to_string()),
-                        ty: convert_type(&field.ty, interner),
-                        default_value: None,
+                        ty: convert_type(&field.ty, interner, generics),
+                        // Kept as the literal's source text
+                        default_value: field.default_value.as_ref().map(|value| {
+                            let span = value.span();
+                            sdl[span.start as usize..span.end as usize]
+                                .trim()
+                                .to_string()
+                        }),
                     },
                 );
             }
@@ -644,38 +1530,50 @@ fn convert_type_definition(type_def: &TypeDefinition, interner: &Interner) -> Ty
                 name: interner.get(input.name.value).to_string(),
                 description: input.description.as_ref().map(|d| d.value.to_string()),
                 fields,
+                one_of: input
+                    .directives
+                    .iter()
+                    .any(|d| interner.get(d.name.value) == "oneOf"),
             })
         }
-        // Handle other type definitions as scalars for now
+        // Opaque types hide their underlying type
         TypeDefinition::Opaque(opaque) => TypeDef::Scalar(ScalarDef {
             name: interner.get(opaque.name.value).to_string(),
             description: opaque.description.as_ref().map(|d| d.value.to_string()),
         }),
+        // Aliases are expanded by `parse_sdl_to_schema`
         TypeDefinition::TypeAlias(alias) => TypeDef::Scalar(ScalarDef {
             name: interner.get(alias.name.value).to_string(),
             description: alias.description.as_ref().map(|d| d.value.to_string()),
         }),
-        TypeDefinition::InputUnion(input_union) => TypeDef::Union(UnionDef {
+        // No standard input kind accepts one of several shapes
+        TypeDefinition::InputUnion(input_union) => TypeDef::Scalar(ScalarDef {
             name: interner.get(input_union.name.value).to_string(),
             description: input_union
                 .description
                 .as_ref()
                 .map(|d| d.value.to_string()),
-            members: input_union
-                .members
-                .iter()
-                .map(|n| interner.get(n.value).to_string())
-                .collect(),
         }),
+        TypeDefinition::InputEnum(input_enum)
+            if input_enum.variants.iter().any(|v| v.fields.is_some()) =>
+        {
+            TypeDef::Scalar(ScalarDef {
+                name: interner.get(input_enum.name.value).to_string(),
+                description: input_enum.description.as_ref().map(|d| d.value.to_string()),
+            })
+        }
         TypeDefinition::InputEnum(input_enum) => {
             let values = input_enum
                 .variants
                 .iter()
-                .map(|v| EnumValueDef {
-                    name: interner.get(v.name.value).to_string(),
-                    description: v.description.as_ref().map(|d| d.value.to_string()),
-                    deprecated: false,
-                    deprecation_reason: None,
+                .map(|v| {
+                    let deprecation = Deprecation::from_directives(&v.directives, interner);
+                    EnumValueDef {
+                        name: interner.get(v.name.value).to_string(),
+                        description: v.description.as_ref().map(|d| d.value.to_string()),
+                        deprecated: deprecation.is_deprecated,
+                        deprecation_reason: deprecation.deprecation_reason,
+                    }
                 })
                 .collect();
             TypeDef::Enum(EnumDef {
@@ -687,21 +1585,105 @@ fn convert_type_definition(type_def: &TypeDefinition, interner: &Interner) -> Ty
     }
 }
 
+/// Reads the property name from a `@source(name: "...")` directive.
+fn source_directive(directives: &[bgql_syntax::Directive], interner: &Interner) -> Option<String> {
+    let directive = directives
+        .iter()
+        .find(|d| interner.get(d.name.value) == "source")?;
+    directive.arguments.iter().find_map(|arg| match &arg.value {
+        bgql_syntax::Value::String(name, _) if interner.get(arg.name.value) == "name" => {
+            Some(name.clone())
+        }
+        _ => None,
+    })
+}
+
+/// Converts AST directives to runtime directive applications.
+fn convert_directives(
+    directives: &[bgql_syntax::Directive],
+    interner: &Interner,
+) -> Vec<AppliedDirective> {
+    directives
+        .iter()
+        .map(|directive| AppliedDirective {
+            name: interner.get(directive.name.value).to_string(),
+            arguments: directive
+                .arguments
+                .iter()
+                .map(|arg| {
+                    (
+                        interner.get(arg.name.value).to_string(),
+                        convert_const_value(&arg.value, interner),
+                    )
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Converts a constant AST value to JSON. Variables have no value in SDL and become null.
+fn convert_const_value(value: &bgql_syntax::Value, interner: &Interner) -> serde_json::Value {
+    match value {
+        bgql_syntax::Value::Int(n, _) => serde_json::json!(n),
+        bgql_syntax::Value::Float(n, _) => serde_json::json!(n),
+        bgql_syntax::Value::String(s, _) => serde_json::json!(s),
+        bgql_syntax::Value::Boolean(b, _) => serde_json::json!(b),
+        bgql_syntax::Value::Enum(name) => serde_json::json!(interner.get(name.value)),
+        bgql_syntax::Value::List(items, _) => serde_json::Value::Array(
+            items
+                .iter()
+                .map(|v| convert_const_value(v, interner))
+                .collect(),
+        ),
+        bgql_syntax::Value::Object(fields, _) => serde_json::Value::Object(
+            fields
+                .iter()
+                .map(|(name, value)| {
+                    (
+                        interner.get(name.value).to_string(),
+                        convert_const_value(value, interner),
+                    )
+                })
+                .collect(),
+        ),
+        bgql_syntax::Value::Variable(_)
+        | bgql_syntax::Value::Null(_)
+        | bgql_syntax::Value::_Phantom(_) => serde_json::Value::Null,
+    }
+}
+
 /// Converts AST type to runtime TypeRef.
-fn convert_type(ty: &bgql_syntax::Type, interner: &Interner) -> TypeRef {
+fn convert_type(ty: &bgql_syntax::Type, interner: &Interner, generics: &mut Generics) -> TypeRef {
     match ty {
-        bgql_syntax::Type::Named(named) => TypeRef::Named(interner.get(named.name).to_string()),
+        bgql_syntax::Type::Named(named) => {
+            let name = interner.get(named.name);
+            match generics.params.get(&name) {
+                Some(argument) => argument.clone(),
+                None => TypeRef::Named(name),
+            }
+        }
         bgql_syntax::Type::Option(inner, _) => {
-            TypeRef::Option(Box::new(convert_type(inner, interner)))
+            TypeRef::Option(Box::new(convert_type(inner, interner, generics)))
+        }
+        bgql_syntax::Type::List(inner, _) => {
+            TypeRef::List(Box::new(convert_type(inner, interner, generics)))
         }
-        bgql_syntax::Type::List(inner, _) => TypeRef::List(Box::new(convert_type(inner, interner))),
+        // Generic types refer to their monomorphized instances
         bgql_syntax::Type::Generic(generic) => {
-            // Treat generic types as named types for now
-            TypeRef::Named(interner.get(generic.name).to_string())
+            let name = interner.get(generic.name);
+            let arguments: Vec<_> = generic
+                .arguments
+                .iter()
+                .map(|argument| convert_type(argument, interner, generics))
+                .collect();
+            let instance = instance_name(&name, &arguments);
+            generics.instances.push((name, arguments));
+            TypeRef::Named(instance)
         }
+        // No standard kind has a fixed number of elements
         bgql_syntax::Type::Tuple(_) => {
-            // Treat tuple types as named types for now
-            TypeRef::Named("Tuple".to_string())
+            generics.has_tuples = true;
+            TypeRef::Named(TUPLE_SCALAR.to_string())
         }
         bgql_syntax::Type::_Phantom(_) => {
             // Phantom variant, should not occur in practice
@@ -739,6 +1721,23 @@ fn ast_operation_to_hir(
     }
 }
 
+/// Converts an AST fragment definition to a HIR fragment.
+fn ast_fragment_to_hir(
+    fragment: &bgql_syntax::FragmentDefinition,
+    interner: &Interner,
+) -> HirFragment {
+    HirFragment {
+        name: interner.get(fragment.name.value).to_string(),
+        type_condition: interner.get(fragment.type_condition.value).to_string(),
+        selections: fragment
+            .selection_set
+            .selections
+            .iter()
+            .map(|sel| convert_selection(sel, interner))
+            .collect(),
+    }
+}
+
 /// Converts AST selection to HIR selection.
 fn convert_selection(sel: &bgql_syntax::Selection, interner: &Interner) -> HirSelection {
     match sel {
@@ -773,10 +1772,24 @@ fn convert_selection(sel: &bgql_syntax::Selection, interner: &Interner) -> HirSe
                 name,
                 arguments,
                 selections,
+                stream: convert_stream(&field.directives, interner),
+                condition: convert_condition(&field.directives, interner),
             })
         }
         bgql_syntax::Selection::FragmentSpread(spread) => {
-            HirSelection::FragmentSpread(interner.get(spread.name.value).to_string())
+            let selection =
+                HirSelection::FragmentSpread(interner.get(spread.name.value).to_string());
+            let condition = convert_condition(&spread.directives, interner);
+            if condition.is_unconditional() {
+                return selection;
+            }
+            // Conditions are kept on inline fragments, so wrap the spread
+            HirSelection::InlineFragment(HirInlineFragment {
+                type_condition: None,
+                selections: vec![selection],
+                defer: None,
+                condition,
+            })
         }
         bgql_syntax::Selection::InlineFragment(inline) => {
             let type_condition = inline
@@ -790,14 +1803,74 @@ fn convert_selection(sel: &bgql_syntax::Selection, interner: &Interner) -> HirSe
                 .map(|s| convert_selection(s, interner))
                 .collect();
 
-            HirSelection::InlineFragment(bgql_semantic::hir::HirInlineFragment {
+            HirSelection::InlineFragment(HirInlineFragment {
                 type_condition,
                 selections,
+                defer: convert_defer(&inline.directives, interner),
+                condition: convert_condition(&inline.directives, interner),
             })
         }
     }
 }
 
+/// Reads the `@stream` directive of a field, unless its `if` argument is
+/// `false`.
+fn convert_stream(directives: &[bgql_syntax::Directive], interner: &Interner) -> Option<HirStream> {
+    let directive = directives
+        .iter()
+        .find(|directive| interner.get(directive.name.value) == "stream")?;
+
+    let mut stream = HirStream::default();
+    for arg in &directive.arguments {
+        match (interner.get(arg.name.value).as_str(), &arg.value) {
+            ("if", bgql_syntax::Value::Boolean(false, _)) => return None,
+            ("label", bgql_syntax::Value::String(label, _)) => stream.label = Some(label.clone()),
+            ("initialCount", bgql_syntax::Value::Int(count, _)) => {
+                stream.initial_count = usize::try_from(*count).unwrap_or(0)
+            }
+            _ => {}
+        }
+    }
+    Some(stream)
+}
+
+/// Reads the `@defer` directive of an inline fragment, unless its `if`
+/// argument is `false`.
+fn convert_defer(directives: &[bgql_syntax::Directive], interner: &Interner) -> Option<HirDefer> {
+    let directive = directives
+        .iter()
+        .find(|directive| interner.get(directive.name.value) == "defer")?;
+
+    let mut defer = HirDefer::default();
+    for arg in &directive.arguments {
+        match (interner.get(arg.name.value).as_str(), &arg.value) {
+            ("if", bgql_syntax::Value::Boolean(false, _)) => return None,
+            ("label", bgql_syntax::Value::String(label, _)) => defer.label = Some(label.clone()),
+            _ => {}
+        }
+    }
+    Some(defer)
+}
+
+/// Reads the `if` arguments of the `@skip` and `@include` directives of a
+/// selection.
+fn convert_condition(directives: &[bgql_syntax::Directive], interner: &Interner) -> HirCondition {
+    let argument = |name: &str| {
+        let directive = directives
+            .iter()
+            .find(|directive| interner.get(directive.name.value) == name)?;
+        let arg = directive
+            .arguments
+            .iter()
+            .find(|arg| interner.get(arg.name.value) == "if")?;
+        Some(convert_value(&arg.value, interner))
+    };
+    HirCondition {
+        skip: argument("skip"),
+        include: argument("include"),
+    }
+}
+
 /// Converts AST value to HIR value.
 fn convert_value(value: &bgql_syntax::Value, interner: &Interner) -> HirValue {
     match value {
@@ -832,23 +1905,19 @@ fn convert_value(value: &bgql_syntax::Value, interner: &Interner) -> HirValue {
 }
 
 /// DataLoader for batching and caching.
-pub struct DataLoader<K, V, F>
-where
-    K: Eq + std::hash::Hash + Clone + Send,
-    V: Clone + Send,
-    F: Fn(Vec<K>) -> Pin<Box<dyn Future<Output = HashMap<K, V>> + Send>> + Send + Sync,
-{
-    inner: bgql_runtime::DataLoader<K, V, F>,
+pub struct DataLoader<K, V> {
+    inner: bgql_runtime::DataLoader<K, V>,
 }
 
-impl<K, V, F> DataLoader<K, V, F>
+impl<K, V> DataLoader<K, V>
 where
     K: Eq + std::hash::Hash + Clone + Send + 'static,
     V: Clone + Send + 'static,
-    F: Fn(Vec<K>) -> Pin<Box<dyn Future<Output = HashMap<K, V>> + Send>> + Send + Sync + 'static,
 {
     /// Creates a new DataLoader.
-    pub fn new(batch_fn: F) -> Self {
+    pub fn new(
+        batch_fn: impl Fn(Vec<K>) -> bgql_runtime::BatchFuture<K, V> + Send + Sync + 'static,
+    ) -> Self {
         Self {
             inner: bgql_runtime::DataLoader::new(batch_fn),
         }
@@ -871,13 +1940,7 @@ where
 }
 
 /// Creates a DataLoader with the given batch function.
-pub fn create_loader<K, V, F, Fut>(
-    batch_fn: F,
-) -> DataLoader<
-    K,
-    V,
-    impl Fn(Vec<K>) -> Pin<Box<dyn Future<Output = HashMap<K, V>> + Send>> + Send + Sync,
->
+pub fn create_loader<K, V, F, Fut>(batch_fn: F) -> DataLoader<K, V>
 where
     K: Eq + std::hash::Hash + Clone + Send + 'static,
     V: Clone + Send + 'static,
@@ -892,6 +1955,8 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pubsub::TypedPubSub;
+    use crate::streaming::PathSegment;
 
     #[test]
     fn test_server_config() {
@@ -912,6 +1977,205 @@ mod tests {
         assert_eq!(ctx.get::<String>("user_id"), Some("123".to_string()));
     }
 
+    #[test]
+    fn test_extended_types_map_to_standard_kinds() {
+        let sdl = r#"
+            opaque UserId = ID
+            alias Emails = List<String>
+            input union Credentials = EmailLogin | TokenLogin
+            input enum Sort { NEWEST OLDEST }
+            input enum Filter { ALL BY_TAG { tag: String } }
+            input EmailLogin { email: String }
+            input TokenLogin { token: String }
+            type Query {
+                emails(id: UserId, sort: Sort, filter: Filter, login: Credentials): Option<Emails>
+            }
+        "#;
+        let schema = parse_sdl_to_schema(sdl, &Interner::new()).unwrap();
+
+        assert!(matches!(
+            schema.get_type("UserId"),
+            Some(TypeDef::Scalar(_))
+        ));
+        assert!(matches!(
+            schema.get_type("Credentials"),
+            Some(TypeDef::Scalar(_))
+        ));
+        assert!(matches!(schema.get_type("Sort"), Some(TypeDef::Enum(_))));
+        assert!(matches!(
+            schema.get_type("Filter"),
+            Some(TypeDef::Scalar(_))
+        ));
+        assert!(schema.get_type("Emails").is_none());
+        let emails = schema.get_field("Query", "emails").unwrap();
+        assert_eq!(emails.ty.to_string(), "Option<List<String>>");
+    }
+
+    #[tokio::test]
+    async fn test_generic_instances_are_introspectable() {
+        let server = BgqlServer::builder()
+            .schema_sdl(
+                r#"
+                type User { id: ID }
+                type PageInfo { hasNextPage: Boolean }
+                type Edge<T> { node: T cursor: String }
+                type Connection<T> { edges: List<Edge<T>> pageInfo: PageInfo }
+                type Query {
+                    users: Connection<User>
+                    range: (start: Int, end: Int)
+                }
+            "#,
+            )
+            .resolver("Query", "users", |_args, _ctx| async {
+                Ok(serde_json::json!({
+                    "edges": [{ "node": { "id": "1" }, "cursor": "a" }],
+                    "pageInfo": { "hasNextPage": false }
+                }))
+            })
+            .resolver("Query", "range", |_args, _ctx| async {
+                Ok(serde_json::json!([1, 5]))
+            })
+            .build()
+            .unwrap();
+
+        let result = server
+            .execute(
+                "{ users { edges { node { id } } } range }",
+                None,
+                Context::new(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result["data"]["users"]["edges"][0]["node"]["id"], "1");
+        assert_eq!(result["data"]["range"], serde_json::json!([1, 5]));
+
+        let result = server
+            .execute(
+                "{ __schema { types { name fields { type { name ofType { name ofType { name ofType { name } } } } } } } }",
+                None,
+                Context::new(),
+            )
+            .await
+            .unwrap();
+        let types = result["data"]["__schema"]["types"].as_array().unwrap();
+        let names: Vec<_> = types.iter().filter_map(|ty| ty["name"].as_str()).collect();
+        for instance in ["UserConnection", "UserEdge", "Tuple"] {
+            assert!(names.contains(&instance), "{names:?}");
+        }
+        assert!(!names.contains(&"Connection") && !names.contains(&"Edge"));
+
+        // Every type a field refers to is one of the schema's types
+        for field_type in types
+            .iter()
+            .filter_map(|ty| ty["fields"].as_array())
+            .flatten()
+        {
+            let mut ty = &field_type["type"];
+            while ty["name"].is_null() && !ty["ofType"].is_null() {
+                ty = &ty["ofType"];
+            }
+            assert!(names.contains(&ty["name"].as_str().unwrap()), "{ty}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deprecated_members_are_introspectable() {
+        let server = BgqlServer::builder()
+            .schema_sdl(
+                r#"
+                enum Role { ADMIN ROOT @deprecated(reason: "Use ADMIN") }
+                type User {
+                    name: String
+                    username: String @deprecated(reason: "Use `name`")
+                    role: Role
+                }
+                type Query { user: User }
+            "#,
+            )
+            .build()
+            .unwrap();
+        let introspect = |query: &'static str| {
+            let server = &server;
+            async move {
+                let result = server.execute(query, None, Context::new()).await.unwrap();
+                assert!(result.get("errors").is_none(), "{result}");
+                result["data"]["__type"].clone()
+            }
+        };
+
+        let user =
+            introspect(r#"{ __type(name: "User") { fields(includeDeprecated: false) { name } } }"#)
+                .await;
+        assert_eq!(
+            user["fields"],
+            serde_json::json!([{ "name": "name" }, { "name": "role" }])
+        );
+
+        let user = introspect(
+            r#"{ __type(name: "User") { fields(includeDeprecated: true) { name isDeprecated deprecationReason } } }"#,
+        )
+        .await;
+        assert_eq!(
+            user["fields"][1],
+            serde_json::json!({
+                "name": "username",
+                "isDeprecated": true,
+                "deprecationReason": "Use `name`"
+            })
+        );
+
+        let role = introspect(
+            r#"{ __type(name: "Role") { enumValues(includeDeprecated: false) { name } } }"#,
+        )
+        .await;
+        assert_eq!(role["enumValues"], serde_json::json!([{ "name": "ADMIN" }]));
+    }
+
+    #[tokio::test]
+    async fn test_skip_and_include() {
+        let server = BgqlServer::builder()
+            .schema_sdl(
+                r#"
+                type User { name: String email: String }
+                type Query { user: User }
+            "#,
+            )
+            .resolver("Query", "user", |_args, _ctx| async {
+                Ok(serde_json::json!({ "name": "Ada", "email": "ada@example.com" }))
+            })
+            .build()
+            .unwrap();
+        let query = r#"
+            query ($withEmail: Boolean) {
+                user {
+                    name @skip(if: true)
+                    email @include(if: $withEmail)
+                    ... on User @skip(if: $withEmail) { fallback: name }
+                    ...Name @include(if: $withEmail)
+                }
+            }
+            fragment Name on User { spread: name }
+        "#;
+        let user = |with_email: bool| {
+            let server = &server;
+            async move {
+                let variables = serde_json::json!({ "withEmail": with_email });
+                let result = server
+                    .execute(query, Some(variables), Context::new())
+                    .await
+                    .unwrap();
+                assert!(result.get("errors").is_none(), "{result}");
+                result["data"]["user"].clone()
+            }
+        };
+
+        assert_eq!(
+            user(true).await,
+            serde_json::json!({ "email": "ada@example.com", "spread": "Ada" })
+        );
+        assert_eq!(user(false).await, serde_json::json!({ "fallback": "Ada" }));
+    }
+
     #[tokio::test]
     async fn test_server_builder_with_sdl() {
         let server = BgqlServer::builder()
@@ -953,6 +2217,596 @@ mod tests {
         assert_eq!(data["data"]["hello"], "Hello, World!");
     }
 
+    #[tokio::test]
+    async fn test_operation_size_limit() {
+        let server = BgqlServer::builder()
+            .config(
+                ServerConfig::new()
+                    .max_operation_bytes(64)
+                    .max_operation_tokens(8),
+            )
+            .schema_sdl("type Query { hello: String }")
+            .resolver("Query", "hello", |_args, _ctx| async {
+                Ok(serde_json::json!("Hello"))
+            })
+            .build()
+            .unwrap();
+
+        let result = server
+            .execute("query { hello }", None, Context::new())
+            .await
+            .unwrap();
+        assert_eq!(result["data"]["hello"], "Hello");
+
+        let padded = format!("query {{ hello }}{}", " ".repeat(64));
+        let err = server
+            .execute(&padded, None, Context::new())
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::OperationTooLarge);
+        assert!(err.message.contains("64 bytes"), "{}", err.message);
+
+        let err = server
+            .execute("query { a: hello b: hello c: hello }", None, Context::new())
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::OperationTooLarge);
+        assert!(err.message.contains("8 tokens"), "{}", err.message);
+    }
+
+    #[tokio::test]
+    async fn test_response_size_limit() {
+        let server = BgqlServer::builder()
+            .config(ServerConfig::new().max_response_bytes(256))
+            .schema_sdl("type Query { items(count: Int): List<String> }")
+            .resolver("Query", "items", |args, _ctx| async move {
+                let count = args["count"].as_u64().unwrap_or(0);
+                Ok(serde_json::json!((0..count)
+                    .map(|i| format!("item-{i}"))
+                    .collect::<Vec<_>>()))
+            })
+            .build()
+            .unwrap();
+
+        let result = server
+            .execute("{ items(count: 3) }", None, Context::new())
+            .await
+            .unwrap();
+        assert_eq!(result["data"]["items"][2], "item-2");
+
+        let result = server
+            .execute("{ items(count: 1000) }", None, Context::new())
+            .await
+            .unwrap();
+        assert!(result.get("data").is_none());
+        assert_eq!(
+            result["errors"][0]["extensions"]["code"],
+            "RESPONSE_TOO_LARGE"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_depth_limit() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let server = BgqlServer::builder()
+            .config(ServerConfig {
+                max_depth: 2,
+                ..ServerConfig::new()
+            })
+            .schema_sdl(
+                r#"
+                type User {
+                    id: ID
+                    friend: User
+                }
+
+                type Query {
+                    user: User
+                }
+            "#,
+            )
+            .resolver("Query", "user", {
+                let calls = Arc::clone(&calls);
+                move |_args, _ctx| {
+                    calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    async { Ok(serde_json::json!({ "id": "1", "friend": { "id": "2" } })) }
+                }
+            })
+            .build()
+            .unwrap();
+
+        let result = server
+            .execute("{ user { friend { id } } }", None, Context::new())
+            .await
+            .unwrap();
+        assert_eq!(result["data"]["user"]["friend"]["id"], "2");
+
+        let err = server
+            .execute(
+                "{ user { ...Friends } }\nfragment Friends on User { friend { friend { id } } }",
+                None,
+                Context::new(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::PlanError);
+        assert!(err.message.contains("depth"), "{}", err.message);
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_subscription_handler() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Message {
+            text: String,
+        }
+
+        let pubsub = PubSub::new();
+        let server = BgqlServer::builder()
+            .schema_sdl(
+                r#"
+                type Message {
+                    text: String
+                }
+
+                type Query {
+                    hello: String
+                }
+
+                type Subscription {
+                    messageAdded(room: String): Message
+                }
+            "#,
+            )
+            .pubsub(pubsub.clone())
+            .subscription("Subscription", "messageAdded", move |args, _ctx| {
+                let pubsub = pubsub.clone();
+                async move {
+                    let room = args["room"].as_str().unwrap_or_default().to_string();
+                    let messages = TypedPubSub::<Message>::new(pubsub, room);
+                    Ok(messages.subscribe().await.into_stream())
+                }
+            })
+            .build()
+            .unwrap();
+
+        let query = "subscription ($room: String) { messageAdded(room: $room) { text } }";
+        let err = server
+            .execute(query, None, Context::new())
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("subscribe"), "{}", err.message);
+
+        let mut responses = server
+            .subscribe(
+                query,
+                Some(serde_json::json!({ "room": "general" })),
+                Context::new(),
+            )
+            .await
+            .unwrap();
+        let messages = TypedPubSub::<Message>::new(server.pubsub().clone(), "general");
+        for text in ["hi", "there"] {
+            messages
+                .publish(Message {
+                    text: text.to_string(),
+                })
+                .await;
+        }
+
+        for text in ["hi", "there"] {
+            let response = responses.recv().await.unwrap();
+            assert_eq!(response["data"]["messageAdded"]["text"], text, "{response}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unresolved_root_fields() {
+        const SDL: &str = r#"
+            type Query {
+                hello: String
+                draft: Option<String>
+            }
+        "#;
+        let builder = |config: ServerConfig| {
+            BgqlServer::builder()
+                .config(config)
+                .schema_sdl(SDL)
+                .resolver("Query", "hello", |_args, _ctx| async {
+                    Ok(serde_json::json!("Hello"))
+                })
+        };
+
+        let server = builder(ServerConfig::new().dev()).build().unwrap();
+        let result = server
+            .execute("query { hello draft }", None, Context::new())
+            .await
+            .unwrap();
+        assert_eq!(result["data"]["hello"], "Hello");
+        assert_eq!(result["data"]["draft"], serde_json::Value::Null);
+        let errors = result["errors"].to_string();
+        assert!(
+            errors.contains("`Query.draft` is not implemented"),
+            "{errors}"
+        );
+
+        let err = builder(ServerConfig::new().production())
+            .build()
+            .err()
+            .expect("build fails without a resolver for `Query.draft`");
+        assert_eq!(err.code, ErrorCode::ResolverNotFound);
+        assert!(err.message.contains("`Query.draft`"), "{}", err.message);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_resolvers_fail_build() {
+        let err = BgqlServer::builder()
+            .schema_sdl("type Query { user: String }")
+            .resolver("Query", "user", |_args, _ctx| async {
+                Ok(serde_json::json!("a"))
+            })
+            .resolver("Querry", "user", |_args, _ctx| async {
+                Ok(serde_json::json!("b"))
+            })
+            .resolver("Query", "usr", |_args, _ctx| async {
+                Ok(serde_json::json!("c"))
+            })
+            .build()
+            .err()
+            .expect("build fails with resolvers for unknown fields");
+
+        assert_eq!(err.code, ErrorCode::FieldNotFound);
+        assert_eq!(
+            err.message,
+            "Resolvers for fields not in the schema: `Querry.user`: unknown type `Querry`; \
+             `Query.usr`: `Query` has no field `usr`"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_type_extensions() {
+        let server = BgqlServer::builder()
+            .schema_sdl(
+                r#"
+                type Query { user: String }
+                extend type Query { posts: List<String> }
+                enum Role { USER }
+                extend enum Role { ADMIN }
+            "#,
+            )
+            .resolver("Query", "user", |_args, _ctx| async {
+                Ok(serde_json::json!("a"))
+            })
+            .resolver("Query", "posts", |_args, _ctx| async {
+                Ok(serde_json::json!(["b"]))
+            })
+            .build()
+            .unwrap();
+
+        let result = server
+            .execute("{ user posts }", None, Context::new())
+            .await
+            .unwrap();
+        assert_eq!(result["data"]["posts"], serde_json::json!(["b"]));
+        match server.schema.get_type("Role") {
+            Some(TypeDef::Enum(role)) => assert_eq!(role.values.len(), 2),
+            other => panic!("expected an enum, got {other:?}"),
+        }
+
+        let err = BgqlServer::builder()
+            .schema_sdl("type Query { user: String } extend type Missing { id: ID }")
+            .build()
+            .err()
+            .expect("build fails with an extension of an undefined type");
+        assert_eq!(err.code, ErrorCode::SchemaError);
+    }
+
+    #[tokio::test]
+    async fn test_variables_are_validated() {
+        let server = BgqlServer::builder()
+            .schema_sdl(
+                r#"
+                type User {
+                    id: ID
+                }
+
+                type Query {
+                    users(limit: Option<Int>): List<User>
+                    user(id: ID): Option<User>
+                }
+            "#,
+            )
+            .resolver("Query", "users", |args, _ctx| async move {
+                let limit = args["limit"].as_i64().unwrap_or(0);
+                Ok(serde_json::json!((0..limit)
+                    .map(|i| serde_json::json!({ "id": i.to_string() }))
+                    .collect::<Vec<_>>()))
+            })
+            .resolver("Query", "user", |args, _ctx| async move {
+                Ok(serde_json::json!({ "id": args["id"] }))
+            })
+            .build()
+            .unwrap();
+
+        let result = server
+            .execute(
+                "query ($id: ID) { user(id: $id) { id } }",
+                Some(serde_json::json!({ "id": 42 })),
+                Context::new(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result["data"]["user"]["id"], "42");
+
+        let result = server
+            .execute(
+                "query ($id: ID) { user(id: $id) { id } }",
+                None,
+                Context::new(),
+            )
+            .await
+            .unwrap();
+        assert!(result.get("data").is_none());
+        assert_eq!(
+            result["errors"][0],
+            serde_json::json!({
+                "message": "Variable `$id` of required type `ID` was not provided"
+            })
+        );
+
+        let result = server
+            .execute(
+                "query ($limit: Option<Int>) { users(limit: $limit) { id } }",
+                Some(serde_json::json!({ "limit": "two", "offset": 1 })),
+                Context::new(),
+            )
+            .await
+            .unwrap();
+        let errors = result["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        let message = |e: &serde_json::Value| e["message"].as_str().unwrap().to_string();
+        assert!(errors.iter().any(|e| message(e).contains("`$offset`")));
+        assert!(errors
+            .iter()
+            .any(|e| message(e).contains("expected type `Int`")));
+
+        // Incremental delivery and subscriptions report them the same way
+        let query = "query ($id: ID) { user(id: $id) { id } }";
+        let events: Vec<_> = server
+            .execute_incremental(query, None, Context::new())
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            IncrementalEvent::Initial(initial) => {
+                assert!(!initial.has_next);
+                assert_eq!(
+                    initial.initial["errors"][0]["message"],
+                    "Variable `$id` of required type `ID` was not provided"
+                );
+            }
+            _ => panic!("expected the initial response"),
+        }
+
+        let mut responses = server.subscribe(query, None, Context::new()).await.unwrap();
+        let response = responses.recv().await.unwrap();
+        assert_eq!(
+            response["errors"][0]["message"],
+            "Variable `$id` of required type `ID` was not provided"
+        );
+        assert!(responses.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_repeated_query_is_planned_once() {
+        let server = BgqlServer::builder()
+            .schema_sdl(
+                r#"
+                type Query {
+                    hello: String
+                    world: String
+                }
+            "#,
+            )
+            .resolver("Query", "hello", |_args, _ctx| async {
+                Ok(serde_json::json!("Hello"))
+            })
+            .resolver("Query", "world", |_args, _ctx| async {
+                Ok(serde_json::json!("World"))
+            })
+            .build()
+            .unwrap();
+
+        for query in ["query { hello }", "query {\n  hello\n}", "query { world }"] {
+            let result = server.execute(query, None, Context::new()).await.unwrap();
+            assert!(result.get("errors").is_none(), "{result}");
+        }
+
+        let cache = server.executor.plan_cache();
+        assert_eq!(cache.misses(), 2);
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_source_directive() {
+        let server = BgqlServer::builder()
+            .schema_sdl(
+                r#"
+                type User {
+                    id: ID
+                    joinedAt: String @source(name: "created_at")
+                }
+
+                type Query {
+                    me: User
+                }
+            "#,
+            )
+            .resolver("Query", "me", |_args, _ctx| async {
+                Ok(serde_json::json!({"id": "1", "created_at": "2024-01-01"}))
+            })
+            .build()
+            .unwrap();
+
+        let field = server.schema().get_field("User", "joinedAt").unwrap();
+        assert_eq!(field.source.as_deref(), Some("created_at"));
+
+        let result = server
+            .execute("query { me { joinedAt } }", None, Context::new())
+            .await
+            .unwrap();
+        assert_eq!(result["data"]["me"]["joinedAt"], "2024-01-01");
+    }
+
+    #[tokio::test]
+    async fn test_custom_directive_handler() {
+        let server = BgqlServer::builder()
+            .schema_sdl(
+                r#"
+                type User {
+                    id: ID
+                    name: String @uppercase
+                }
+
+                type Query {
+                    me: User
+                }
+            "#,
+            )
+            .resolver("Query", "me", |_args, _ctx| async {
+                Ok(serde_json::json!({"id": "1", "name": "alice"}))
+            })
+            .directive_handler(
+                "uppercase",
+                bgql_runtime::directives::FnDirectiveHandler::new(|value, _| {
+                    Ok(match value {
+                        serde_json::Value::String(s) => serde_json::Value::String(s.to_uppercase()),
+                        other => other,
+                    })
+                }),
+            )
+            .build()
+            .unwrap();
+
+        let result = server
+            .execute("query { me { name } }", None, Context::new())
+            .await
+            .unwrap();
+        assert_eq!(result["data"]["me"]["name"], "ALICE");
+    }
+
+    #[tokio::test]
+    async fn test_execute_incremental_defers_fragment() {
+        let server = BgqlServer::builder()
+            .schema_sdl(
+                r#"
+                type User {
+                    id: ID
+                    bio: String
+                }
+
+                type Query {
+                    me: User
+                }
+            "#,
+            )
+            .resolver("Query", "me", |_args, _ctx| async {
+                Ok(serde_json::json!({"id": "1", "bio": "Hello"}))
+            })
+            .build()
+            .unwrap();
+
+        let events: Vec<_> = server
+            .execute_incremental(
+                r#"query { me { id ... @defer(label: "bio") { bio } } }"#,
+                None,
+                Context::new(),
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        assert_eq!(events.len(), 2);
+        match &events[0] {
+            IncrementalEvent::Initial(initial) => {
+                assert!(initial.has_next);
+                assert_eq!(
+                    initial.initial["data"]["me"],
+                    serde_json::json!({"id": "1"})
+                );
+            }
+            other => panic!("expected the initial payload, got {:?}", other),
+        }
+        match &events[1] {
+            IncrementalEvent::Defer(payload) => {
+                assert!(matches!(&payload.path[..], [PathSegment::Field(f)] if f == "me"));
+                assert_eq!(payload.data, serde_json::json!({"bio": "Hello"}));
+                assert_eq!(payload.label.as_deref(), Some("bio"));
+                assert!(!payload.has_next);
+            }
+            other => panic!("expected a deferred payload, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_incremental_streams_list() {
+        let server = BgqlServer::builder()
+            .schema_sdl(
+                r#"
+                type User {
+                    id: ID
+                }
+
+                type Query {
+                    users: List<User>
+                }
+            "#,
+            )
+            .resolver("Query", "users", |_args, _ctx| async {
+                Ok(serde_json::json!([{"id": "1"}, {"id": "2"}, {"id": "3"}]))
+            })
+            .build()
+            .unwrap();
+
+        let events: Vec<_> = server
+            .execute_incremental(
+                r#"query { users @stream(initialCount: 1, label: "users") { id } }"#,
+                None,
+                Context::new(),
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        assert_eq!(events.len(), 3);
+        match &events[0] {
+            IncrementalEvent::Initial(initial) => {
+                assert!(initial.has_next);
+                assert_eq!(
+                    initial.initial["data"]["users"],
+                    serde_json::json!([{"id": "1"}])
+                );
+            }
+            other => panic!("expected the initial payload, got {:?}", other),
+        }
+        match &events[2] {
+            IncrementalEvent::Stream(payload) => {
+                assert!(
+                    matches!(&payload.path[..], [PathSegment::Field(f), PathSegment::Index(2)] if f == "users")
+                );
+                assert_eq!(payload.items, vec![serde_json::json!({"id": "3"})]);
+                assert_eq!(payload.label.as_deref(), Some("users"));
+                assert!(!payload.has_next);
+            }
+            other => panic!("expected a streamed item, got {:?}", other),
+        }
+    }
+
     #[tokio::test]
     async fn test_dataloader() {
         let loader = create_loader(|keys: Vec<i32>| async move {
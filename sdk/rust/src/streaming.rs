@@ -353,6 +353,17 @@ impl MultipartWriter {
     /// Formats an incremental part.
     pub fn format_incremental(&self, event: &IncrementalEvent) -> String {
         match event {
+            IncrementalEvent::Initial(response) => {
+                let mut body = response.initial.clone();
+                if let Some(body) = body.as_object_mut() {
+                    body.insert("hasNext".to_string(), response.has_next.into());
+                }
+                format!(
+                    "--{}\r\nContent-Type: application/json\r\n\r\n{}\r\n",
+                    self.boundary,
+                    serde_json::to_string(&body).unwrap_or_default()
+                )
+            }
             IncrementalEvent::Defer(payload) => {
                 let body = serde_json::json!({
                     "incremental": [{
@@ -3,7 +3,8 @@
 //! Provides compile-time type safety for GraphQL operations and resolvers.
 
 use crate::context::TypedContext;
-use crate::error::{ErrorCode, SdkError, SdkResult};
+use crate::error::{DomainError, ErrorCode, ErrorUnion, SdkError, SdkResult, UnionMember};
+use bgql_runtime::resolver::UnknownResolver;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::future::Future;
 use std::marker::PhantomData;
@@ -354,6 +355,79 @@ impl<Schema> ResolverBuilder<Schema> {
         )
     }
 
+    /// Registers a typed resolver whose domain errors are returned as
+    /// members of an error union.
+    ///
+    /// `Ok` resolves to the success member and `Err` to the error member,
+    /// both with their `__typename`.
+    pub fn union_resolver<Parent, Args, Ctx, T, E, F, Fut>(
+        self,
+        type_name: impl Into<String>,
+        field_name: impl Into<String>,
+        resolver: F,
+    ) -> Self
+    where
+        Parent: DeserializeOwned + Send + 'static,
+        Args: DeserializeOwned + Send + 'static,
+        Ctx: FromTypedContext + Send + 'static,
+        T: Serialize + UnionMember + Send + 'static,
+        E: DomainError + Send + 'static,
+        F: Fn(Parent, Args, Ctx) -> Fut + Send + Sync + Clone + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        self.resolver::<Parent, Args, Ctx, ErrorUnion<T, E>, _, _>(
+            type_name,
+            field_name,
+            move |parent: Parent, args, ctx| {
+                let result = resolver.clone()(parent, args, ctx);
+                async move { Ok(ErrorUnion(result.await)) }
+            },
+        )
+    }
+
+    /// Registers a root query resolver (no parent) returning an error union.
+    pub fn union_query<Args, Ctx, T, E, F, Fut>(
+        self,
+        field_name: impl Into<String>,
+        resolver: F,
+    ) -> Self
+    where
+        Args: DeserializeOwned + Send + 'static,
+        Ctx: FromTypedContext + Send + 'static,
+        T: Serialize + UnionMember + Send + 'static,
+        E: DomainError + Send + 'static,
+        F: Fn(Args, Ctx) -> Fut + Send + Sync + Clone + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        self.union_resolver::<(), Args, Ctx, T, E, _, _>(
+            "Query",
+            field_name,
+            move |_parent: (), args, ctx| resolver.clone()(args, ctx),
+        )
+    }
+
+    /// Registers a root mutation resolver (no parent) returning an error
+    /// union.
+    pub fn union_mutation<Args, Ctx, T, E, F, Fut>(
+        self,
+        field_name: impl Into<String>,
+        resolver: F,
+    ) -> Self
+    where
+        Args: DeserializeOwned + Send + 'static,
+        Ctx: FromTypedContext + Send + 'static,
+        T: Serialize + UnionMember + Send + 'static,
+        E: DomainError + Send + 'static,
+        F: Fn(Args, Ctx) -> Fut + Send + Sync + Clone + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        self.union_resolver::<(), Args, Ctx, T, E, _, _>(
+            "Mutation",
+            field_name,
+            move |_parent: (), args, ctx| resolver.clone()(args, ctx),
+        )
+    }
+
     /// Returns all registered resolvers.
     pub fn build(self) -> Vec<(String, String, BoxedResolver)> {
         self.resolvers
@@ -361,6 +435,23 @@ impl<Schema> ResolverBuilder<Schema> {
             .map(|e| (e.type_name, e.field_name, e.resolver))
             .collect()
     }
+
+    /// Returns all registered resolvers after checking that `schema`
+    /// defines each of their types and fields.
+    pub fn build_checked(
+        self,
+        schema: &bgql_runtime::schema::Schema,
+    ) -> SdkResult<Vec<(String, String, BoxedResolver)>> {
+        let unknown: Vec<_> = self
+            .resolvers
+            .iter()
+            .filter_map(|e| UnknownResolver::check(schema, &e.type_name, &e.field_name))
+            .collect();
+        if !unknown.is_empty() {
+            return Err(SdkError::unknown_resolvers(&unknown));
+        }
+        Ok(self.build())
+    }
 }
 
 // ============================================================================
@@ -549,6 +640,75 @@ mod tests {
         assert_eq!(resolvers[0].1, "user");
     }
 
+    struct NotFound {
+        id: String,
+    }
+
+    impl UnionMember for User {
+        fn typename(&self) -> &str {
+            "User"
+        }
+    }
+
+    impl DomainError for NotFound {
+        fn typename(&self) -> &str {
+            "NotFoundError"
+        }
+
+        fn message(&self) -> String {
+            format!("User '{}' not found", self.id)
+        }
+
+        fn code(&self) -> &str {
+            "NOT_FOUND"
+        }
+
+        fn fields(&self) -> serde_json::Map<String, serde_json::Value> {
+            let mut fields = serde_json::Map::new();
+            fields.insert("resourceId".to_string(), self.id.clone().into());
+            fields
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_union_member() {
+        let resolvers = ResolverBuilder::<()>::new()
+            .union_query::<GetUserArgs, (), User, NotFound, _, _>("user", |args, _ctx| async move {
+                if args.id == "1" {
+                    Ok(User {
+                        id: args.id,
+                        name: "Alice".to_string(),
+                    })
+                } else {
+                    Err(NotFound { id: args.id })
+                }
+            })
+            .build();
+
+        let ctx = TypedContext::new();
+        let resolve = |id: &str| {
+            (resolvers[0].2)(
+                serde_json::Value::Null,
+                serde_json::json!({ "id": id }),
+                &ctx,
+            )
+        };
+
+        assert_eq!(
+            resolve("2").await.unwrap(),
+            serde_json::json!({
+                "__typename": "NotFoundError",
+                "message": "User '2' not found",
+                "code": "NOT_FOUND",
+                "resourceId": "2",
+            })
+        );
+        assert_eq!(
+            resolve("1").await.unwrap(),
+            serde_json::json!({ "__typename": "User", "id": "1", "name": "Alice" })
+        );
+    }
+
     #[test]
     fn test_typed_args() {
         let json = serde_json::json!({ "id": "123" });
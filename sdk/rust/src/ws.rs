@@ -0,0 +1,363 @@
+//! GraphQL over WebSocket, using the `graphql-transport-ws` protocol.
+//!
+//! A [`WsSession`] multiplexes any number of subscriptions over one
+//! connection. Each subscription is identified by the id the client chose
+//! when subscribing, and every `next`/`complete` message carries that id.
+
+use crate::server::{BgqlServer, Context};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// WebSocket subprotocol name.
+pub const PROTOCOL: &str = "graphql-transport-ws";
+
+/// A message sent by the client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    ConnectionInit {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        payload: Option<Value>,
+    },
+    Ping {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        payload: Option<Value>,
+    },
+    Pong {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        payload: Option<Value>,
+    },
+    Subscribe {
+        id: String,
+        payload: SubscribePayload,
+    },
+    Complete {
+        id: String,
+    },
+    /// `subscriptions-transport-ws` equivalent of `complete`.
+    Stop {
+        id: String,
+    },
+}
+
+/// The operation of a `subscribe` message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscribePayload {
+    pub query: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variables: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation_name: Option<String>,
+}
+
+/// A message sent by the server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    ConnectionAck,
+    Pong {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        payload: Option<Value>,
+    },
+    Next {
+        id: String,
+        payload: Value,
+    },
+    Error {
+        id: String,
+        payload: Vec<Value>,
+    },
+    Complete {
+        id: String,
+    },
+}
+
+/// Why the server closes the connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloseReason {
+    /// WebSocket close code.
+    pub code: u16,
+    /// Human-readable reason.
+    pub reason: String,
+}
+
+impl CloseReason {
+    fn new(code: u16, reason: impl Into<String>) -> Self {
+        Self {
+            code,
+            reason: reason.into(),
+        }
+    }
+}
+
+/// The protocol state of one WebSocket connection.
+///
+/// Feed it the text frames received from the client; the messages to send
+/// back are written to the `outgoing` channel. Dropping the session stops
+/// its subscriptions.
+pub struct WsSession<'a> {
+    server: &'a BgqlServer,
+    outgoing: mpsc::UnboundedSender<String>,
+    acknowledged: bool,
+    subscriptions: HashMap<String, JoinHandle<()>>,
+}
+
+impl<'a> WsSession<'a> {
+    /// Creates a session for a new connection.
+    pub fn new(server: &'a BgqlServer, outgoing: mpsc::UnboundedSender<String>) -> Self {
+        Self {
+            server,
+            outgoing,
+            acknowledged: false,
+            subscriptions: HashMap::new(),
+        }
+    }
+
+    /// Returns the number of subscriptions that are still running.
+    pub fn active_subscriptions(&self) -> usize {
+        self.subscriptions
+            .values()
+            .filter(|task| !task.is_finished())
+            .count()
+    }
+
+    /// Handles one text frame from the client.
+    ///
+    /// Returns an error if the connection must be closed.
+    pub async fn handle(&mut self, text: &str) -> Result<(), CloseReason> {
+        let message: ClientMessage = serde_json::from_str(text)
+            .map_err(|_| CloseReason::new(4400, "Invalid message received"))?;
+
+        match message {
+            ClientMessage::ConnectionInit { .. } => {
+                if self.acknowledged {
+                    return Err(CloseReason::new(4429, "Too many initialisation requests"));
+                }
+                self.acknowledged = true;
+                send(&self.outgoing, &ServerMessage::ConnectionAck);
+            }
+            ClientMessage::Ping { payload } => {
+                send(&self.outgoing, &ServerMessage::Pong { payload });
+            }
+            ClientMessage::Pong { .. } => {}
+            ClientMessage::Subscribe { id, payload } => {
+                if !self.acknowledged {
+                    return Err(CloseReason::new(4401, "Unauthorized"));
+                }
+                if self
+                    .subscriptions
+                    .get(&id)
+                    .is_some_and(|task| !task.is_finished())
+                {
+                    return Err(CloseReason::new(
+                        4409,
+                        format!("Subscriber for {} already exists", id),
+                    ));
+                }
+                self.subscribe(id, payload).await;
+            }
+            ClientMessage::Complete { id } | ClientMessage::Stop { id } => {
+                if let Some(task) = self.subscriptions.remove(&id) {
+                    task.abort();
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Starts a subscription and forwards its responses tagged with `id`.
+    async fn subscribe(&mut self, id: String, payload: SubscribePayload) {
+        let mut responses = match self
+            .server
+            .subscribe(&payload.query, payload.variables, Context::new())
+            .await
+        {
+            Ok(responses) => responses,
+            Err(e) => {
+                let payload = vec![serde_json::json!({ "message": e.to_string() })];
+                send(&self.outgoing, &ServerMessage::Error { id, payload });
+                return;
+            }
+        };
+
+        let outgoing = self.outgoing.clone();
+        let task_id = id.clone();
+        let task = tokio::spawn(async move {
+            while let Some(payload) = responses.recv().await {
+                let next = ServerMessage::Next {
+                    id: task_id.clone(),
+                    payload,
+                };
+                if !send(&outgoing, &next) {
+                    return;
+                }
+            }
+            send(&outgoing, &ServerMessage::Complete { id: task_id });
+        });
+        self.subscriptions.insert(id, task);
+    }
+}
+
+impl Drop for WsSession<'_> {
+    fn drop(&mut self) {
+        for task in self.subscriptions.values() {
+            task.abort();
+        }
+    }
+}
+
+/// Serializes and queues a message. Returns false if the connection is gone.
+fn send(outgoing: &mpsc::UnboundedSender<String>, message: &ServerMessage) -> bool {
+    match serde_json::to_string(message) {
+        Ok(text) => outgoing.send(text).is_ok(),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pubsub::PubSub;
+    use serde_json::json;
+    use std::time::Duration;
+
+    const SCHEMA: &str = r#"
+        type User {
+            id: ID
+            name: String
+        }
+
+        type Post {
+            id: ID
+            title: String
+        }
+
+        type Query {
+            hello: String
+        }
+
+        type Subscription {
+            userCreated: User
+            postCreated: Post
+        }
+    "#;
+
+    fn subscribe(id: &str, query: &str) -> String {
+        json!({ "type": "subscribe", "id": id, "payload": { "query": query } }).to_string()
+    }
+
+    async fn next_message(rx: &mut mpsc::UnboundedReceiver<String>) -> Value {
+        let text = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+            .await
+            .expect("timed out waiting for a message")
+            .expect("connection closed");
+        serde_json::from_str(&text).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_multiplexed_subscriptions() {
+        let pubsub = PubSub::new();
+        let server = BgqlServer::builder()
+            .schema_sdl(SCHEMA)
+            .pubsub(pubsub.clone())
+            .build()
+            .unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut session = WsSession::new(&server, tx);
+
+        session
+            .handle(r#"{"type":"connection_init"}"#)
+            .await
+            .unwrap();
+        assert_eq!(
+            next_message(&mut rx).await,
+            json!({"type": "connection_ack"})
+        );
+
+        session
+            .handle(&subscribe("users", "subscription { userCreated { name } }"))
+            .await
+            .unwrap();
+        session
+            .handle(&subscribe(
+                "posts",
+                "subscription { postCreated { title } }",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(session.active_subscriptions(), 2);
+
+        pubsub
+            .publish("postCreated", json!({"id": "p1", "title": "Hello"}))
+            .await;
+        assert_eq!(
+            next_message(&mut rx).await,
+            json!({
+                "type": "next",
+                "id": "posts",
+                "payload": {"data": {"postCreated": {"title": "Hello"}}}
+            })
+        );
+
+        pubsub
+            .publish("userCreated", json!({"id": "u1", "name": "Alice"}))
+            .await;
+        assert_eq!(
+            next_message(&mut rx).await,
+            json!({
+                "type": "next",
+                "id": "users",
+                "payload": {"data": {"userCreated": {"name": "Alice"}}}
+            })
+        );
+
+        // Completing one subscription leaves the other running
+        session
+            .handle(r#"{"type":"complete","id":"users"}"#)
+            .await
+            .unwrap();
+        assert_eq!(session.active_subscriptions(), 1);
+
+        pubsub
+            .publish("userCreated", json!({"id": "u2", "name": "Bob"}))
+            .await;
+        pubsub
+            .publish("postCreated", json!({"id": "p2", "title": "Again"}))
+            .await;
+        assert_eq!(
+            next_message(&mut rx).await,
+            json!({
+                "type": "next",
+                "id": "posts",
+                "payload": {"data": {"postCreated": {"title": "Again"}}}
+            })
+        );
+
+        // Ids must be unique among running subscriptions
+        let close = session
+            .handle(&subscribe("posts", "subscription { postCreated { id } }"))
+            .await
+            .unwrap_err();
+        assert_eq!(close.code, 4409);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_requires_connection_init() {
+        let server = BgqlServer::builder().schema_sdl(SCHEMA).build().unwrap();
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut session = WsSession::new(&server, tx);
+
+        let close = session
+            .handle(&subscribe("1", "subscription { userCreated { id } }"))
+            .await
+            .unwrap_err();
+        assert_eq!(close.code, 4401);
+        assert_eq!(session.handle("not json").await.unwrap_err().code, 4400);
+    }
+}
@@ -4,6 +4,7 @@ use bgql_sdk::client::ClientConfig;
 use bgql_sdk::error::{ErrorCode, SdkError};
 use bgql_sdk::server::{create_loader, BgqlServer, Context, ServerConfig};
 use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 /// Test schema parsing and query execution
 #[tokio::test]
@@ -204,7 +205,7 @@ async fn test_error_handling() {
 
     assert!(result.is_ok());
     let data = result.unwrap();
-    // The default resolver returns null for missing fields
+    // The default resolver reports the missing non-null field, leaving it null
     assert!(data.get("data").is_some());
 }
 
@@ -391,3 +392,336 @@ fn test_sdk_error() {
 
     assert!(ErrorCode::ExecutionError.is_server_error());
 }
+
+/// Test serving a query over HTTP
+#[tokio::test]
+async fn test_serve_over_http() {
+    let server = BgqlServer::builder()
+        .schema_sdl("type Query { hello: String }")
+        .resolver("Query", "hello", |_args, _ctx| async {
+            Ok(serde_json::json!("Hello, World!"))
+        })
+        .build()
+        .unwrap();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+
+    let client = async move {
+        let body = r#"{"query": "{ hello }", "variables": null, "operationName": null}"#;
+        let request = format!(
+            "POST /graphql HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            addr,
+            body.len(),
+            body
+        );
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let _ = stop.send(());
+        response
+    };
+    let serve = server.serve(listener, async {
+        let _ = stopped.await;
+    });
+
+    let (served, response) = tokio::join!(serve, client);
+    assert!(served.is_ok(), "Server failed: {:?}", served.err());
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    assert!(response.ends_with(r#"{"data":{"hello":"Hello, World!"}}"#));
+}
+
+/// Test rejecting request bodies over the limit without reading them
+#[tokio::test]
+async fn test_http_body_limit() {
+    let server = BgqlServer::builder()
+        .config(ServerConfig::new().max_operation_bytes(16))
+        .schema_sdl("type Query { hello: String }")
+        .build()
+        .unwrap();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+
+    let client = async move {
+        let send = |request: Vec<u8>| async move {
+            let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+            stream.write_all(&request).await.unwrap();
+            let mut response = String::new();
+            let read =
+                tokio::time::timeout(Duration::from_secs(5), stream.read_to_string(&mut response))
+                    .await;
+            assert!(read.is_ok(), "server waited for the rest of the body");
+            response
+        };
+        let headers = |framing: String| {
+            format!(
+                "POST /graphql HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n{}\r\nConnection: close\r\n\r\n",
+                addr, framing
+            )
+            .into_bytes()
+        };
+
+        // Only the headers are sent; the declared length alone is too large
+        let declared = send(headers(format!("Content-Length: {}", 1024 * 1024))).await;
+
+        // A chunked body is cut off once it passes the limit of twice the
+        // operation limit plus 64 KiB
+        let size = 2 * 16 + 64 * 1024 + 1;
+        let mut request = headers("Transfer-Encoding: chunked".to_string());
+        request.extend(format!("{:x}\r\n", size).into_bytes());
+        request.extend(vec![b' '; size]);
+        let streamed = send(request).await;
+
+        let _ = stop.send(());
+        [declared, streamed]
+    };
+    let serve = server.serve(listener, async {
+        let _ = stopped.await;
+    });
+
+    let (served, responses) = tokio::join!(serve, client);
+    assert!(served.is_ok(), "Server failed: {:?}", served.err());
+    for response in responses {
+        assert!(
+            response.starts_with("HTTP/1.1 413 Payload Too Large"),
+            "{}",
+            response
+        );
+    }
+}
+
+/// Test answering other clients while a keep-alive connection stays open
+#[tokio::test]
+async fn test_http_alongside_idle_connection() {
+    let server = BgqlServer::builder()
+        .schema_sdl("type Query { hello: String }")
+        .resolver("Query", "hello", |_args, _ctx| async {
+            Ok(serde_json::json!("Hello, World!"))
+        })
+        .build()
+        .unwrap();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+
+    let client = async move {
+        // Connected but never sending a request, like an idle pooled connection
+        let idle = tokio::net::TcpStream::connect(addr).await.unwrap();
+
+        let body = r#"{"query": "{ hello }"}"#;
+        let request = format!(
+            "POST /graphql HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            addr,
+            body.len(),
+            body
+        );
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        let read =
+            tokio::time::timeout(Duration::from_secs(5), stream.read_to_string(&mut response))
+                .await;
+        let _ = stop.send(());
+        (idle, read.is_ok(), response)
+    };
+    let serve = server.serve(listener, async {
+        let _ = stopped.await;
+    });
+
+    // The server stops with the idle connection still open
+    let (served, (_idle, answered, response)) =
+        tokio::time::timeout(Duration::from_secs(5), async {
+            tokio::join!(serve, client)
+        })
+        .await
+        .expect("server didn't stop with an idle connection open");
+    assert!(served.is_ok(), "Server failed: {:?}", served.err());
+    assert!(answered, "request blocked behind the idle connection");
+    assert!(response.ends_with(r#"{"data":{"hello":"Hello, World!"}}"#));
+}
+
+/// Test answering binary fields inline and over binary transport
+#[tokio::test]
+async fn test_binary_fields_over_http() {
+    use bgql_runtime::binary_transport::{BinaryBlob, BinaryChunk, BinaryProtocol};
+
+    let server = BgqlServer::builder()
+        .schema_sdl("scalar File\ntype Query { avatar: File }")
+        .binary_resolver("Query", "avatar", |_args, _ctx| async {
+            Ok(BinaryBlob::new("image/png", b"Man".to_vec()))
+        })
+        .build()
+        .unwrap();
+
+    // Without binary transport the bytes are inlined as base64
+    let inline = server
+        .execute("{ avatar }", None, Context::new())
+        .await
+        .unwrap();
+    assert_eq!(inline, serde_json::json!({ "data": { "avatar": "TWFu" } }));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+
+    let client = async move {
+        let body = r#"{"query": "{ avatar }"}"#;
+        let request = format!(
+            "POST /graphql HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nAccept: application/vnd.bgql.binary-stream\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            addr,
+            body.len(),
+            body
+        );
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        let _ = stop.send(());
+        response
+    };
+    let serve = server.serve(listener, async {
+        let _ = stopped.await;
+    });
+
+    let (served, response) = tokio::join!(serve, client);
+    assert!(served.is_ok(), "Server failed: {:?}", served.err());
+    let split = response.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+    let head = String::from_utf8_lossy(&response[..split]);
+    assert!(head.starts_with("HTTP/1.1 200 OK"), "{}", head);
+    assert!(
+        head.contains("application/vnd.bgql.binary-stream"),
+        "{}",
+        head
+    );
+
+    let mut body = &response[split + 4..];
+    let chunk = BinaryChunk::read_from(&mut body).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&chunk.payload).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "data": { "avatar": { "id": "binary-0", "contentType": "image/png", "size": 3 } }
+        })
+    );
+
+    let mut received = Vec::new();
+    let (metadata, _) = BinaryProtocol::decode_blob(&mut body, &mut received)
+        .await
+        .unwrap();
+    assert_eq!(metadata.id, "binary-0");
+    assert_eq!(received, b"Man");
+}
+
+/// Test scraping Prometheus metrics after serving queries
+#[tokio::test]
+async fn test_metrics_endpoint() {
+    let server = BgqlServer::builder()
+        .schema_sdl("type Query { hello: String }")
+        .config(ServerConfig::new().metrics())
+        .resolver("Query", "hello", |_args, _ctx| async {
+            Ok(serde_json::json!("Hello, World!"))
+        })
+        .build()
+        .unwrap();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+
+    let send = move |request: String| async move {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    };
+    let client = async move {
+        for query in ["{ hello }", "{ hello }", "{ missing }"] {
+            let body = serde_json::json!({ "query": query }).to_string();
+            send(format!(
+                "POST /graphql HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                addr,
+                body.len(),
+                body
+            ))
+            .await;
+        }
+        let response = send(format!(
+            "GET /metrics HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+            addr
+        ))
+        .await;
+        let _ = stop.send(());
+        response
+    };
+    let serve = server.serve(listener, async {
+        let _ = stopped.await;
+    });
+
+    let (served, response) = tokio::join!(serve, client);
+    assert!(served.is_ok(), "Server failed: {:?}", served.err());
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    assert!(response.contains("bgql_requests_total 3\n"), "{}", response);
+    assert!(response.contains("bgql_errors_total 1\n"), "{}", response);
+    assert!(response.contains("bgql_request_duration_seconds_count 3\n"));
+    assert!(response.contains("bgql_query_complexity_count 2\n"));
+    assert!(response
+        .contains("bgql_field_resolve_duration_seconds_count{type=\"Query\",field=\"hello\"} 2\n"));
+}
+
+/// Test answering HTTP requests while a WebSocket session stays open
+#[tokio::test]
+async fn test_http_alongside_websocket() {
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+    let server = BgqlServer::builder()
+        .schema_sdl("type Query { hello: String }")
+        .resolver("Query", "hello", |_args, _ctx| async {
+            Ok(serde_json::json!("Hello, World!"))
+        })
+        .build()
+        .unwrap();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+
+    let client = async move {
+        let mut request = format!("ws://{}/bgql", addr).into_client_request().unwrap();
+        request.headers_mut().insert(
+            "Sec-WebSocket-Protocol",
+            "graphql-transport-ws".parse().unwrap(),
+        );
+        let (socket, _) = tokio_tungstenite::connect_async(request).await.unwrap();
+
+        let body = r#"{"query": "{ hello }"}"#;
+        let request = format!(
+            "POST /graphql HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            addr,
+            body.len(),
+            body
+        );
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        let read =
+            tokio::time::timeout(Duration::from_secs(5), stream.read_to_string(&mut response))
+                .await;
+        drop(socket);
+        let _ = stop.send(());
+        (read.is_ok(), response)
+    };
+    let serve = server.serve(listener, async {
+        let _ = stopped.await;
+    });
+
+    let (served, (answered, response)) = tokio::join!(serve, client);
+    assert!(served.is_ok(), "Server failed: {:?}", served.err());
+    assert!(answered, "request blocked behind the WebSocket session");
+    assert!(response.ends_with(r#"{"data":{"hello":"Hello, World!"}}"#));
+}